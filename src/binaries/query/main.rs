@@ -33,11 +33,16 @@ use common_metrics::init_default_metrics_recorder;
 use common_tracing::set_panic_hook;
 use databend_query::api::HttpService;
 use databend_query::api::RpcService;
+use databend_query::auto_compaction::AutoCompactionService;
+use databend_query::auto_purge::AutoPurgeService;
 use databend_query::clusters::ClusterDiscovery;
 use databend_query::metrics::MetricService;
+use databend_query::servers::ClickHouseHandler;
+use databend_query::servers::FlightSqlHandler;
 use databend_query::servers::HttpHandler;
 use databend_query::servers::HttpHandlerKind;
 use databend_query::servers::MySQLHandler;
+use databend_query::servers::PostgresHandler;
 use databend_query::servers::Server;
 use databend_query::servers::ShutdownHandle;
 use databend_query::GlobalServices;
@@ -141,6 +146,54 @@ async fn main_entrypoint() -> Result<()> {
         );
     }
 
+    // PostgreSQL handler.
+    {
+        let hostname = conf.query.postgres_handler_host.clone();
+        let listening = format!("{}:{}", hostname, conf.query.postgres_handler_port);
+        let mut handler = PostgresHandler::create()?;
+        let listening = handler.start(listening.parse()?).await?;
+        shutdown_handle.add_service(handler);
+
+        info!(
+            "Listening for PostgreSQL compatibility protocol: {}, Usage: psql -h{} -p{} -Uroot",
+            listening,
+            listening.ip(),
+            listening.port(),
+        );
+    }
+
+    // Arrow Flight SQL handler.
+    {
+        let hostname = conf.query.flight_sql_handler_host.clone();
+        let listening = format!("{}:{}", hostname, conf.query.flight_sql_handler_port);
+        let mut handler = FlightSqlHandler::create()?;
+        let listening = handler.start(listening.parse()?).await?;
+        shutdown_handle.add_service(handler);
+
+        info!(
+            "Listening for Arrow Flight SQL API: {}, Usage: grpc://{}:{}",
+            listening,
+            listening.ip(),
+            listening.port(),
+        );
+    }
+
+    // ClickHouse handler.
+    {
+        let hostname = conf.query.clickhouse_handler_host.clone();
+        let listening = format!("{}:{}", hostname, conf.query.clickhouse_handler_port);
+        let mut handler = ClickHouseHandler::create()?;
+        let listening = handler.start(listening.parse()?).await?;
+        shutdown_handle.add_service(handler);
+
+        info!(
+            "Listening for ClickHouse native protocol: {}, Usage: clickhouse-client --host {} --port {}",
+            listening,
+            listening.ip(),
+            listening.port(),
+        );
+    }
+
     // ClickHouse HTTP handler.
     {
         let hostname = conf.query.clickhouse_http_handler_host.clone();
@@ -211,6 +264,24 @@ async fn main_entrypoint() -> Result<()> {
         );
     }
 
+    // Background compaction of the tables.
+    if conf.query.auto_compaction_enabled {
+        AutoCompactionService::instance().start();
+        info!(
+            "Auto compaction started, every {} seconds",
+            conf.query.auto_compaction_interval_secs
+        );
+    }
+
+    // Background purge of the tables beyond their retention period.
+    if conf.query.auto_purge_enabled {
+        AutoPurgeService::instance().start();
+        info!(
+            "Auto purge started, every {} seconds",
+            conf.query.auto_purge_interval_secs
+        );
+    }
+
     // Print information to users.
     println!("Databend Query");
     println!();
@@ -274,6 +345,33 @@ async fn main_entrypoint() -> Result<()> {
         "    connect via: mysql -uroot -h{} -P{}",
         conf.query.mysql_handler_host, conf.query.mysql_handler_port
     );
+    println!("PostgreSQL");
+    println!(
+        "    listened at {}:{}",
+        conf.query.postgres_handler_host, conf.query.postgres_handler_port
+    );
+    println!(
+        "    connect via: psql -h{} -p{} -Uroot",
+        conf.query.postgres_handler_host, conf.query.postgres_handler_port
+    );
+    println!("Arrow Flight SQL");
+    println!(
+        "    listened at {}:{}",
+        conf.query.flight_sql_handler_host, conf.query.flight_sql_handler_port
+    );
+    println!(
+        "    connect via: grpc://{}:{}",
+        conf.query.flight_sql_handler_host, conf.query.flight_sql_handler_port
+    );
+    println!("Clickhouse(native)");
+    println!(
+        "    listened at {}:{}",
+        conf.query.clickhouse_handler_host, conf.query.clickhouse_handler_port
+    );
+    println!(
+        "    connect via: clickhouse-client --host {} --port {}",
+        conf.query.clickhouse_handler_host, conf.query.clickhouse_handler_port
+    );
     println!("Clickhouse(http)");
     println!(
         "    listened at {}:{}",
@@ -351,6 +351,89 @@ pub fn object_keys(value: &[u8]) -> Option<Vec<u8>> {
     }
 }
 
+/// Get the values of a `JSONB` array.
+pub fn array_values(value: &[u8]) -> Option<Vec<Vec<u8>>> {
+    if !is_jsonb(value) {
+        let json_value = decode_value(value).unwrap();
+        return json_value
+            .as_array()
+            .map(|values| values.iter().map(Value::to_vec).collect());
+    }
+
+    let header = read_u32(value, 0).unwrap();
+    match header & CONTAINER_HEADER_TYPE_MASK {
+        ARRAY_CONTAINER_TAG => {
+            let length = (header & CONTAINER_HEADER_LEN_MASK) as usize;
+            let mut jentry_offset = 4;
+            let mut val_offset = 4 * length + 4;
+            let mut values = Vec::with_capacity(length);
+            for _ in 0..length {
+                let encoded = read_u32(value, jentry_offset).unwrap();
+                let jentry = JEntry::decode_jentry(encoded);
+                let prev_val_offset = val_offset;
+                val_offset += jentry.length as usize;
+                values.push(extract_value(
+                    value,
+                    encoded,
+                    &jentry,
+                    prev_val_offset,
+                    val_offset,
+                ));
+                jentry_offset += 4;
+            }
+            Some(values)
+        }
+        _ => None,
+    }
+}
+
+/// Get the key-value pairs of a `JSONB` object, in the order of keys.
+pub fn object_each(value: &[u8]) -> Option<Vec<(String, Vec<u8>)>> {
+    if !is_jsonb(value) {
+        let json_value = decode_value(value).unwrap();
+        return json_value.as_object().map(|object| {
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_vec()))
+                .collect()
+        });
+    }
+
+    let header = read_u32(value, 0).unwrap();
+    match header & CONTAINER_HEADER_TYPE_MASK {
+        OBJECT_CONTAINER_TAG => {
+            let length = (header & CONTAINER_HEADER_LEN_MASK) as usize;
+            let mut jentry_offset = 4;
+            let mut key_offset = 8 * length + 4;
+            let mut keys = Vec::with_capacity(length);
+            for _ in 0..length {
+                let encoded = read_u32(value, jentry_offset).unwrap();
+                let key_jentry = JEntry::decode_jentry(encoded);
+                let prev_key_offset = key_offset;
+                key_offset += key_jentry.length as usize;
+                let key =
+                    unsafe { std::str::from_utf8_unchecked(&value[prev_key_offset..key_offset]) };
+                keys.push(key.to_string());
+                jentry_offset += 4;
+            }
+
+            let mut val_offset = key_offset;
+            let mut items = Vec::with_capacity(length);
+            for key in keys {
+                let encoded = read_u32(value, jentry_offset).unwrap();
+                let jentry = JEntry::decode_jentry(encoded);
+                let prev_val_offset = val_offset;
+                val_offset += jentry.length as usize;
+                let val = extract_value(value, encoded, &jentry, prev_val_offset, val_offset);
+                items.push((key, val));
+                jentry_offset += 4;
+            }
+            Some(items)
+        }
+        _ => None,
+    }
+}
+
 /// `JSONB` values supports partial decode for comparison,
 /// if the values are found to be unequal, the result will be returned immediately.
 /// In first level header, values compare as the following order:
@@ -1089,6 +1172,21 @@ fn read_char(buf: &[u8], idx: &mut usize) -> Result<u8, Error> {
     }
 }
 
+// Extract an inner value of a container as a standalone `JSONB` value.
+fn extract_value(value: &[u8], encoded: u32, jentry: &JEntry, start: usize, end: usize) -> Vec<u8> {
+    match jentry.type_code {
+        CONTAINER_TAG => value[start..end].to_vec(),
+        _ => {
+            let mut buf = Vec::with_capacity(8 + end - start);
+            let scalar_header = SCALAR_CONTAINER_TAG;
+            buf.extend_from_slice(&scalar_header.to_be_bytes());
+            buf.extend_from_slice(&encoded.to_be_bytes());
+            buf.extend_from_slice(&value[start..end]);
+            buf
+        }
+    }
+}
+
 fn read_u32(buf: &[u8], idx: usize) -> Result<u32, Error> {
     let bytes: [u8; 4] = buf
         .get(idx..idx + 4)
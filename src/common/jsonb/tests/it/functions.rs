@@ -16,6 +16,7 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 
 use common_jsonb::array_length;
+use common_jsonb::array_values;
 use common_jsonb::as_bool;
 use common_jsonb::as_null;
 use common_jsonb::as_number;
@@ -28,6 +29,7 @@ use common_jsonb::get_by_name_ignore_case;
 use common_jsonb::get_by_path;
 use common_jsonb::is_array;
 use common_jsonb::is_object;
+use common_jsonb::object_each;
 use common_jsonb::object_keys;
 use common_jsonb::parse_json_path;
 use common_jsonb::parse_value;
@@ -271,6 +273,80 @@ fn test_object_keys() {
     }
 }
 
+#[test]
+fn test_array_values() {
+    let sources = vec![
+        (r#"{"a":1}"#, None),
+        (r#"[]"#, Some(vec![])),
+        (
+            r#"[1,"a",[2,3],{"k":null}]"#,
+            Some(vec![r#"1"#, r#""a""#, r#"[2,3]"#, r#"{"k":null}"#]),
+        ),
+    ];
+
+    let mut buf: Vec<u8> = Vec::new();
+    for (s, expect) in sources {
+        let expect = expect.map(|values| {
+            values
+                .into_iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+        });
+        let res = array_values(s.as_bytes())
+            .map(|values| values.iter().map(|v| to_string(v)).collect::<Vec<_>>());
+        assert_eq!(res, expect);
+        let value = parse_value(s.as_bytes()).unwrap();
+        value.write_to_vec(&mut buf);
+        let res = array_values(&buf)
+            .map(|values| values.iter().map(|v| to_string(v)).collect::<Vec<_>>());
+        assert_eq!(res, expect);
+        buf.clear();
+    }
+}
+
+#[test]
+fn test_object_each() {
+    let sources = vec![
+        (r#"[1,2,3]"#, None),
+        (r#"{}"#, Some(vec![])),
+        (
+            r#"{"b":[1,2],"a":"v1","c":{"k":true}}"#,
+            Some(vec![
+                ("a", r#""v1""#),
+                ("b", r#"[1,2]"#),
+                ("c", r#"{"k":true}"#),
+            ]),
+        ),
+    ];
+
+    let mut buf: Vec<u8> = Vec::new();
+    for (s, expect) in sources {
+        let expect = expect.map(|items| {
+            items
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        });
+        let res = object_each(s.as_bytes()).map(|items| {
+            items
+                .iter()
+                .map(|(k, v)| (k.clone(), to_string(v)))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(res, expect);
+        let value = parse_value(s.as_bytes()).unwrap();
+        value.write_to_vec(&mut buf);
+        let res = object_each(&buf).map(|items| {
+            items
+                .iter()
+                .map(|(k, v)| (k.clone(), to_string(v)))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(res, expect);
+        buf.clear();
+    }
+}
+
 #[test]
 fn test_compare() {
     let sources = vec![
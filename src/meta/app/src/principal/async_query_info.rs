@@ -0,0 +1,57 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::principal::UserIdentity;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsyncQueryState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A column of the result of an async query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AsyncQueryField {
+    pub name: String,
+    pub data_type: String,
+}
+
+/// The state of a query submitted to run in the background, it can be read by the
+/// query id on any query node.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AsyncQueryInfo {
+    pub query_id: String,
+    // The user who submits the query, only this user can read it.
+    pub user: UserIdentity,
+    pub sql: String,
+    // The id of the query node running the query.
+    pub node_id: String,
+    pub state: AsyncQueryState,
+    pub error_code: Option<u16>,
+    pub error_message: Option<String>,
+    pub schema: Vec<AsyncQueryField>,
+    pub num_rows: u64,
+    // The location of the result in the storage, None if the result has no rows.
+    pub result_location: Option<String>,
+    // The number of rows in each page of the result.
+    pub max_rows_per_page: u64,
+    pub submit_time: DateTime<Utc>,
+    pub finish_time: Option<DateTime<Utc>>,
+}
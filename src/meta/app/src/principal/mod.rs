@@ -14,8 +14,10 @@
 
 //! Principal is a user or role that accesses an entity.
 
+mod async_query_info;
 mod principal_identity;
 mod role_info;
+mod session_state;
 mod user_auth;
 mod user_defined_file_format;
 mod user_defined_function;
@@ -27,11 +29,16 @@ mod user_quota;
 mod user_setting;
 mod user_stage;
 
+pub use async_query_info::AsyncQueryField;
+pub use async_query_info::AsyncQueryInfo;
+pub use async_query_info::AsyncQueryState;
 pub use principal_identity::PrincipalIdentity;
 pub use role_info::RoleInfo;
 pub use role_info::RoleInfoSerdeError;
+pub use session_state::SessionState;
 pub use user_auth::AuthInfo;
 pub use user_auth::AuthType;
+pub use user_auth::ClientCert;
 pub use user_auth::PasswordHashMethod;
 pub use user_defined_file_format::UserDefinedFileFormat;
 pub use user_defined_function::UserDefinedFunction;
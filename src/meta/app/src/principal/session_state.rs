@@ -0,0 +1,33 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::principal::UserIdentity;
+
+/// The state of a session that can be resumed on another query node.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionState {
+    // The user who owns the session, only this user can resume it.
+    pub user: UserIdentity,
+    // The current database of the session.
+    pub database: String,
+    // The current role of the session, if it was set.
+    pub role: Option<String>,
+    // The settings changed in the session.
+    pub settings: BTreeMap<String, String>,
+}
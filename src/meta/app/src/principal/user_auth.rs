@@ -22,6 +22,7 @@ const NO_PASSWORD_STR: &str = "no_password";
 const SHA256_PASSWORD_STR: &str = "sha256_password";
 const DOUBLE_SHA1_PASSWORD_STR: &str = "double_sha1_password";
 const JWT_AUTH_STR: &str = "jwt";
+const X509_AUTH_STR: &str = "x509";
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
 pub enum AuthType {
@@ -29,6 +30,7 @@ pub enum AuthType {
     Sha256Password,
     DoubleSha1Password,
     JWT,
+    X509,
 }
 
 impl std::str::FromStr for AuthType {
@@ -39,6 +41,7 @@ impl std::str::FromStr for AuthType {
             DOUBLE_SHA1_PASSWORD_STR => Ok(AuthType::DoubleSha1Password),
             NO_PASSWORD_STR => Ok(AuthType::NoPassword),
             JWT_AUTH_STR => Ok(AuthType::JWT),
+            X509_AUTH_STR => Ok(AuthType::X509),
             _ => Err(ErrorCode::InvalidAuthInfo(AuthType::bad_auth_types(s))),
         }
     }
@@ -51,6 +54,7 @@ impl AuthType {
             AuthType::Sha256Password => SHA256_PASSWORD_STR,
             AuthType::DoubleSha1Password => DOUBLE_SHA1_PASSWORD_STR,
             AuthType::JWT => JWT_AUTH_STR,
+            AuthType::X509 => X509_AUTH_STR,
         }
     }
 
@@ -60,6 +64,7 @@ impl AuthType {
             SHA256_PASSWORD_STR,
             DOUBLE_SHA1_PASSWORD_STR,
             JWT_AUTH_STR,
+            X509_AUTH_STR,
         ];
         let all = all
             .iter()
@@ -86,6 +91,11 @@ pub enum AuthInfo {
         hash_method: PasswordHashMethod,
     },
     JWT,
+    /// Authenticated by the client certificate of the TLS connection, the subject of the
+    /// certificate must be `subject`, or the common name must be the user name if it is None.
+    X509 {
+        subject: Option<String>,
+    },
 }
 
 fn calc_sha1(v: &[u8]) -> [u8; 20] {
@@ -103,6 +113,9 @@ impl AuthInfo {
         match auth_type {
             AuthType::NoPassword => Ok(AuthInfo::None),
             AuthType::JWT => Ok(AuthInfo::JWT),
+            AuthType::X509 => Ok(AuthInfo::X509 {
+                subject: auth_string.clone(),
+            }),
             AuthType::Sha256Password | AuthType::DoubleSha1Password => match auth_string {
                 Some(p) => {
                     let method = auth_type.get_password_type().unwrap();
@@ -151,7 +164,12 @@ impl AuthInfo {
         auth_type: &Option<AuthType>,
         auth_string: &Option<String>,
     ) -> Result<AuthInfo> {
-        let old_auth_type = self.get_type();
+        // the subject of the certificate is not a password, changing the password of a
+        // x509 user means to authenticate it by the password from now on.
+        let old_auth_type = match self.get_type() {
+            AuthType::X509 => AuthType::DoubleSha1Password,
+            t => t,
+        };
         let new_auth_type = auth_type.clone().unwrap_or(old_auth_type);
 
         AuthInfo::new(new_auth_type, auth_string)
@@ -161,6 +179,7 @@ impl AuthInfo {
         match self {
             AuthInfo::None => AuthType::NoPassword,
             AuthInfo::JWT => AuthType::JWT,
+            AuthInfo::X509 { .. } => AuthType::X509,
             AuthInfo::Password {
                 hash_value: _,
                 hash_method: t,
@@ -177,6 +196,7 @@ impl AuthInfo {
                 hash_value: p,
                 hash_method: t,
             } => t.to_string(p),
+            AuthInfo::X509 { subject } => subject.clone().unwrap_or_default(),
             AuthInfo::None | AuthInfo::JWT => "".to_string(),
         }
     }
@@ -239,6 +259,35 @@ impl AuthInfo {
             ))),
         }
     }
+
+    /// Check the client certificate of the connection for the x509 user.
+    pub fn auth_x509(&self, user_name: &str, cert: Option<&ClientCert>) -> Result<bool> {
+        match self {
+            AuthInfo::X509 { subject } => {
+                let cert = cert.ok_or_else(|| {
+                    ErrorCode::AuthenticateFailure(
+                        "user require a client certificate, connect with TLS",
+                    )
+                })?;
+                match subject {
+                    Some(subject) => Ok(&cert.subject == subject),
+                    None => Ok(cert.common_name.as_deref() == Some(user_name)),
+                }
+            }
+            _ => Err(ErrorCode::AuthenticateFailure(format!(
+                "user require auth type {}",
+                self.get_type().to_str()
+            ))),
+        }
+    }
+}
+
+/// The client certificate verified by the TLS handshake.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClientCert {
+    /// The distinguished name, like `C=US, ST=CA, CN=Databend Client`.
+    pub subject: String,
+    pub common_name: Option<String>,
 }
 
 impl Default for AuthInfo {
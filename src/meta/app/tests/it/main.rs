@@ -12,6 +12,7 @@
 //  See the License for the specific language governing permissions and
 //  limitations under the License.
 
+mod user_auth;
 mod user_defined_function;
 mod user_grant;
 mod user_info;
@@ -0,0 +1,51 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use common_exception::exception::Result;
+use common_meta_app::principal::AuthInfo;
+use common_meta_app::principal::AuthType;
+use common_meta_app::principal::ClientCert;
+
+#[test]
+fn test_auth_x509() -> Result<()> {
+    let cert = ClientCert {
+        subject: "C=US, ST=CA, CN=u1".to_string(),
+        common_name: Some("u1".to_string()),
+    };
+
+    // the common name is the user name
+    let auth_info = AuthInfo::create2(&Some(AuthType::X509), &None)?;
+    assert_eq!(auth_info, AuthInfo::X509 { subject: None });
+    assert!(auth_info.auth_x509("u1", Some(&cert))?);
+    assert!(!auth_info.auth_x509("u2", Some(&cert))?);
+    assert!(auth_info.auth_x509("u1", None).is_err());
+
+    // the subject is required
+    let auth_info = AuthInfo::create2(
+        &Some(AuthType::X509),
+        &Some("C=US, ST=CA, CN=u1".to_string()),
+    )?;
+    assert!(auth_info.auth_x509("u2", Some(&cert))?);
+    let auth_info = AuthInfo::create2(&Some(AuthType::X509), &Some("CN=u1".to_string()))?;
+    assert!(!auth_info.auth_x509("u1", Some(&cert))?);
+
+    // the password can not be used by the x509 user, and vice versa
+    assert!(auth_info.auth_mysql(b"", b"").is_err());
+    let auth_info = AuthInfo::create2(&None, &Some("pwd".to_string()))?;
+    assert!(auth_info.auth_x509("u1", Some(&cert)).is_err());
+
+    // set a password to the x509 user
+    let auth_info = AuthInfo::X509 { subject: None }.alter2(&None, &Some("pwd".to_string()))?;
+    assert_eq!(auth_info.get_type(), AuthType::DoubleSha1Password);
+    Ok(())
+}
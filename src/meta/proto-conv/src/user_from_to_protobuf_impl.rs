@@ -48,6 +48,9 @@ impl FromToProto for mt::principal::AuthInfo {
             Some(pb::auth_info::Info::Jwt(pb::auth_info::Jwt {})) => {
                 Ok(mt::principal::AuthInfo::JWT)
             }
+            Some(pb::auth_info::Info::X509(pb::auth_info::X509 { subject })) => {
+                Ok(mt::principal::AuthInfo::X509 { subject })
+            }
             Some(pb::auth_info::Info::Password(pb::auth_info::Password {
                 hash_value,
                 hash_method,
@@ -69,6 +72,11 @@ impl FromToProto for mt::principal::AuthInfo {
                 Some(pb::auth_info::Info::None(pb::auth_info::None {}))
             }
             mt::principal::AuthInfo::JWT => Some(pb::auth_info::Info::Jwt(pb::auth_info::Jwt {})),
+            mt::principal::AuthInfo::X509 { subject } => {
+                Some(pb::auth_info::Info::X509(pb::auth_info::X509 {
+                    subject: subject.clone(),
+                }))
+            }
             mt::principal::AuthInfo::Password {
                 hash_value,
                 hash_method,
@@ -0,0 +1,46 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_meta_app::principal::AuthInfo;
+
+use crate::common;
+
+// These bytes are built when a new version in introduced,
+// and are kept for backward compatibility test.
+//
+// *************************************************************
+// * These messages should never be updated,                   *
+// * only be added when a new version is added,                *
+// * or be removed when an old version is no longer supported. *
+// *************************************************************
+//
+// The message bytes are built from the output of `test_pb_from_to()`
+#[test]
+fn test_decode_v29_user_auth_info() -> anyhow::Result<()> {
+    let auth_info_v29 = vec![
+        34, 20, 10, 18, 67, 78, 61, 68, 97, 116, 97, 98, 101, 110, 100, 32, 67, 108, 105, 101, 110,
+        116, 160, 6, 29, 168, 6, 24,
+    ];
+    let want = || AuthInfo::X509 {
+        subject: Some("CN=Databend Client".to_string()),
+    };
+    common::test_load_old(func_name!(), auth_info_v29.as_slice(), 29, want())?;
+    common::test_pb_from_to(func_name!(), want())?;
+
+    let auth_info_v29 = vec![34, 0, 160, 6, 29, 168, 6, 24];
+    let want = || AuthInfo::X509 { subject: None };
+    common::test_load_old(func_name!(), auth_info_v29.as_slice(), 29, want())?;
+    common::test_pb_from_to(func_name!(), want())?;
+    Ok(())
+}
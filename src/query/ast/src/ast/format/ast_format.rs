@@ -411,6 +411,13 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
         self.children.push(node);
     }
 
+    fn visit_placeholder(&mut self, _span: Span) {
+        let name = "Placeholder".to_string();
+        let format_ctx = AstFormatContext::new(name);
+        let node = FormatTreeNode::new(format_ctx);
+        self.children.push(node);
+    }
+
     fn visit_tuple(&mut self, _span: Span, elements: &'ast [Expr]) {
         let mut children = Vec::with_capacity(elements.len());
         for element in elements.iter() {
@@ -430,12 +437,34 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
         name: &'ast Identifier,
         args: &'ast [Expr],
         _params: &'ast [Literal],
+        window: &'ast Option<Window>,
     ) {
         let mut children = Vec::with_capacity(args.len());
         for arg in args.iter() {
             self.visit_expr(arg);
             children.push(self.children.pop().unwrap());
         }
+        if let Some(window) = window {
+            let mut window_children = Vec::new();
+            for expr in window.partition_by.iter() {
+                self.visit_expr(expr);
+                window_children.push(self.children.pop().unwrap());
+            }
+            for order_by in window.order_by.iter() {
+                self.visit_order_by(order_by);
+                window_children.push(self.children.pop().unwrap());
+            }
+            let window_name = match &window.window_frame {
+                Some(frame) => format!("Window {frame}"),
+                None => "Window".to_string(),
+            };
+            let window_format_ctx =
+                AstFormatContext::with_children(window_name, window_children.len());
+            children.push(FormatTreeNode::with_children(
+                window_format_ctx,
+                window_children,
+            ));
+        }
         let node_name = if distinct {
             format!("Function {name}Distinct")
         } else {
@@ -1000,6 +1029,67 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
         self.children.push(node);
     }
 
+    fn visit_merge_into(&mut self, merge_into: &'ast MergeIntoStmt) {
+        let mut children = Vec::new();
+        self.visit_table_reference(&merge_into.table);
+        children.push(self.children.pop().unwrap());
+        self.visit_table_reference(&merge_into.source);
+        children.push(self.children.pop().unwrap());
+        self.visit_expr(&merge_into.join_expr);
+        children.push(self.children.pop().unwrap());
+
+        for clause in merge_into.merge_clauses.iter() {
+            let mut clause_children = Vec::new();
+            let (name, selection) = match clause {
+                MergeClause::Matched {
+                    selection,
+                    operation,
+                } => {
+                    let name = match operation {
+                        MatchOperation::Update { update_list } => {
+                            for update_expr in update_list.iter() {
+                                self.visit_identifier(&update_expr.name);
+                                clause_children.push(self.children.pop().unwrap());
+                                self.visit_expr(&update_expr.expr);
+                                clause_children.push(self.children.pop().unwrap());
+                            }
+                            "MatchedUpdate"
+                        }
+                        MatchOperation::Delete => "MatchedDelete",
+                    };
+                    (name, selection)
+                }
+                MergeClause::NotMatched {
+                    selection,
+                    columns,
+                    values,
+                } => {
+                    for column in columns.iter() {
+                        self.visit_identifier(column);
+                        clause_children.push(self.children.pop().unwrap());
+                    }
+                    for value in values.iter() {
+                        self.visit_expr(value);
+                        clause_children.push(self.children.pop().unwrap());
+                    }
+                    ("NotMatchedInsert", selection)
+                }
+            };
+            if let Some(selection) = selection {
+                self.visit_expr(selection);
+                clause_children.push(self.children.pop().unwrap());
+            }
+            let format_ctx =
+                AstFormatContext::with_children(name.to_string(), clause_children.len());
+            children.push(FormatTreeNode::with_children(format_ctx, clause_children));
+        }
+
+        let name = "MergeInto".to_string();
+        let format_ctx = AstFormatContext::with_children(name, children.len());
+        let node = FormatTreeNode::with_children(format_ctx, children);
+        self.children.push(node);
+    }
+
     fn visit_show_databases(&mut self, stmt: &'ast ShowDatabasesStmt) {
         let mut children = Vec::new();
         if let Some(limit) = &stmt.limit {
@@ -1229,6 +1319,24 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
                 let node = FormatTreeNode::with_children(format_ctx, vec![child]);
                 self.children.push(node);
             }
+            CreateTableSource::Clone {
+                catalog,
+                database,
+                table,
+                travel_point,
+            } => {
+                let mut children = Vec::with_capacity(2);
+                self.visit_table_ref(catalog, database, table);
+                children.push(self.children.pop().unwrap());
+                if let Some(travel_point) = travel_point {
+                    self.visit_time_travel_point(travel_point);
+                    children.push(self.children.pop().unwrap());
+                }
+                let name = "CloneTable".to_string();
+                let format_ctx = AstFormatContext::with_children(name, children.len());
+                let node = FormatTreeNode::with_children(format_ctx, children);
+                self.children.push(node);
+            }
         }
     }
 
@@ -1416,6 +1524,33 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
         self.children.push(node);
     }
 
+    fn visit_create_stream(&mut self, stmt: &'ast CreateStreamStmt) {
+        let mut children = Vec::with_capacity(3);
+        self.visit_table_ref(&stmt.catalog, &stmt.database, &stmt.stream);
+        children.push(self.children.pop().unwrap());
+        self.visit_table_ref(&None, &stmt.table_database, &stmt.table);
+        children.push(self.children.pop().unwrap());
+        if let Some(travel_point) = &stmt.travel_point {
+            self.visit_time_travel_point(travel_point);
+            children.push(self.children.pop().unwrap());
+        }
+
+        let name = "CreateStream".to_string();
+        let format_ctx = AstFormatContext::with_children(name, children.len());
+        let node = FormatTreeNode::with_children(format_ctx, children);
+        self.children.push(node);
+    }
+
+    fn visit_drop_stream(&mut self, stmt: &'ast DropStreamStmt) {
+        self.visit_table_ref(&stmt.catalog, &stmt.database, &stmt.stream);
+        let child = self.children.pop().unwrap();
+
+        let name = "DropStream".to_string();
+        let format_ctx = AstFormatContext::with_children(name, 1);
+        let node = FormatTreeNode::with_children(format_ctx, vec![child]);
+        self.children.push(node);
+    }
+
     fn visit_show_users(&mut self) {
         let name = "ShowUsers".to_string();
         let format_ctx = AstFormatContext::new(name);
@@ -2059,18 +2194,69 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
                 FormatTreeNode::with_children(selection_format_ctx, vec![selection_child]);
             children.push(selection_node);
         }
-        if !stmt.group_by.is_empty() {
-            let mut group_by_list_children = Vec::with_capacity(stmt.group_by.len());
-            for group_by in stmt.group_by.iter() {
-                self.visit_expr(group_by);
-                group_by_list_children.push(self.children.pop().unwrap());
+        if let Some(group_by) = &stmt.group_by {
+            match group_by {
+                GroupBy::Normal(exprs) => {
+                    let mut group_by_list_children = Vec::with_capacity(exprs.len());
+                    for group_by in exprs.iter() {
+                        self.visit_expr(group_by);
+                        group_by_list_children.push(self.children.pop().unwrap());
+                    }
+                    let group_by_list_name = "GroupByList".to_string();
+                    let group_by_list_format_ctx = AstFormatContext::with_children(
+                        group_by_list_name,
+                        group_by_list_children.len(),
+                    );
+                    let group_by_list_node = FormatTreeNode::with_children(
+                        group_by_list_format_ctx,
+                        group_by_list_children,
+                    );
+                    children.push(group_by_list_node);
+                }
+                GroupBy::GroupingSets(sets) => {
+                    let mut grouping_sets = Vec::with_capacity(sets.len());
+                    for set in sets.iter() {
+                        let mut grouping_set = Vec::with_capacity(set.len());
+                        for expr in set.iter() {
+                            self.visit_expr(expr);
+                            grouping_set.push(self.children.pop().unwrap());
+                        }
+                        let name = "GroupingSet".to_string();
+                        let grouping_set_format_ctx =
+                            AstFormatContext::with_children(name, grouping_set.len());
+                        let grouping_set_node =
+                            FormatTreeNode::with_children(grouping_set_format_ctx, grouping_set);
+                        grouping_sets.push(grouping_set_node);
+                    }
+                    let group_by_list_name = "GroupByList".to_string();
+                    let group_by_list_format_ctx =
+                        AstFormatContext::with_children(group_by_list_name, grouping_sets.len());
+                    let group_by_list_node =
+                        FormatTreeNode::with_children(group_by_list_format_ctx, grouping_sets);
+                    children.push(group_by_list_node);
+                }
+                GroupBy::Cube(exprs) | GroupBy::Rollup(exprs) => {
+                    let mut group_by_list_children = Vec::with_capacity(exprs.len());
+                    for group_by in exprs.iter() {
+                        self.visit_expr(group_by);
+                        group_by_list_children.push(self.children.pop().unwrap());
+                    }
+                    let group_by_list_name = if matches!(group_by, GroupBy::Cube(_)) {
+                        "GroupByCube".to_string()
+                    } else {
+                        "GroupByRollup".to_string()
+                    };
+                    let group_by_list_format_ctx = AstFormatContext::with_children(
+                        group_by_list_name,
+                        group_by_list_children.len(),
+                    );
+                    let group_by_list_node = FormatTreeNode::with_children(
+                        group_by_list_format_ctx,
+                        group_by_list_children,
+                    );
+                    children.push(group_by_list_node);
+                }
             }
-            let group_by_list_name = "GroupByList".to_string();
-            let group_by_list_format_ctx =
-                AstFormatContext::with_children(group_by_list_name, group_by_list_children.len());
-            let group_by_list_node =
-                FormatTreeNode::with_children(group_by_list_format_ctx, group_by_list_children);
-            children.push(group_by_list_node);
         }
         if let Some(having) = &stmt.having {
             self.visit_expr(having);
@@ -2151,12 +2337,17 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
             }
             TableReference::Subquery {
                 span: _,
+                lateral,
                 subquery,
                 alias,
             } => {
                 self.visit_query(subquery);
                 let child = self.children.pop().unwrap();
-                let name = "Subquery".to_string();
+                let name = if *lateral {
+                    "LateralSubquery".to_string()
+                } else {
+                    "Subquery".to_string()
+                };
                 let format_ctx = if let Some(alias) = alias {
                     AstFormatContext::with_children_alias(name, 1, Some(format!("{}", alias)))
                 } else {
@@ -2167,6 +2358,7 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
             }
             TableReference::TableFunction {
                 span: _,
+                lateral,
                 name,
                 params,
                 named_params,
@@ -2186,7 +2378,11 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
                     );
                     children.push(node);
                 }
-                let func_name = format!("TableFunction {}", name);
+                let func_name = if *lateral {
+                    format!("LateralTableFunction {}", name)
+                } else {
+                    format!("TableFunction {}", name)
+                };
                 let format_ctx = if let Some(alias) = alias {
                     AstFormatContext::with_children_alias(
                         func_name,
@@ -2207,6 +2403,61 @@ impl<'ast> Visitor<'ast> for AstFormatVisitor {
                 let node = FormatTreeNode::with_children(format_ctx, vec![child]);
                 self.children.push(node);
             }
+            TableReference::Pivot {
+                span: _,
+                table,
+                pivot,
+                alias,
+            } => {
+                let mut children = Vec::with_capacity(pivot.values.len() + 2);
+                self.visit_table_reference(table);
+                children.push(self.children.pop().unwrap());
+                self.visit_expr(&pivot.aggregate);
+                children.push(self.children.pop().unwrap());
+                for value in pivot.values.iter() {
+                    self.visit_expr(value);
+                    children.push(self.children.pop().unwrap());
+                }
+                let name = format!("Pivot {}", pivot.value_column);
+                let format_ctx = if let Some(alias) = alias {
+                    AstFormatContext::with_children_alias(
+                        name,
+                        children.len(),
+                        Some(format!("{}", alias)),
+                    )
+                } else {
+                    AstFormatContext::with_children(name, children.len())
+                };
+                let node = FormatTreeNode::with_children(format_ctx, children);
+                self.children.push(node);
+            }
+            TableReference::Unpivot {
+                span: _,
+                table,
+                unpivot,
+                alias,
+            } => {
+                self.visit_table_reference(table);
+                let child = self.children.pop().unwrap();
+                let name = format!(
+                    "Unpivot {} FOR {} IN ({})",
+                    unpivot.value_column,
+                    unpivot.name_column,
+                    unpivot
+                        .columns
+                        .iter()
+                        .map(|column| column.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                let format_ctx = if let Some(alias) = alias {
+                    AstFormatContext::with_children_alias(name, 1, Some(format!("{}", alias)))
+                } else {
+                    AstFormatContext::with_children(name, 1)
+                };
+                let node = FormatTreeNode::with_children(format_ctx, vec![child]);
+                self.children.push(node);
+            }
             TableReference::Stage {
                 span: _,
                 location,
@@ -221,6 +221,7 @@ pub(crate) fn pretty_expr(expr: Expr) -> RcDoc<'static> {
             .append(RcDoc::text(")")),
         Expr::Literal { lit, .. } => RcDoc::text(lit.to_string()),
         Expr::CountAll { .. } => RcDoc::text("COUNT(*)"),
+        Expr::Placeholder { .. } => RcDoc::text("?"),
         Expr::Tuple { exprs, .. } => RcDoc::text("(")
             .append(inline_comma(exprs.into_iter().map(pretty_expr)))
             .append(RcDoc::text(")")),
@@ -229,6 +230,7 @@ pub(crate) fn pretty_expr(expr: Expr) -> RcDoc<'static> {
             name,
             args,
             params,
+            window,
             ..
         } => RcDoc::text(name.to_string())
             .append(if !params.is_empty() {
@@ -249,7 +251,15 @@ pub(crate) fn pretty_expr(expr: Expr) -> RcDoc<'static> {
                 RcDoc::nil()
             })
             .append(inline_comma(args.into_iter().map(pretty_expr)))
-            .append(RcDoc::text(")")),
+            .append(RcDoc::text(")"))
+            .append(if let Some(window) = window {
+                RcDoc::space()
+                    .append(RcDoc::text("OVER ("))
+                    .append(RcDoc::text(window.to_string()))
+                    .append(RcDoc::text(")"))
+            } else {
+                RcDoc::nil()
+            }),
         Expr::Case {
             operand,
             conditions,
@@ -21,6 +21,7 @@ use crate::ast::format::syntax::interweave_comma;
 use crate::ast::format::syntax::parenthenized;
 use crate::ast::format::syntax::NEST_FACTOR;
 use crate::ast::Expr;
+use crate::ast::GroupBy;
 use crate::ast::JoinCondition;
 use crate::ast::JoinOperator;
 use crate::ast::OrderByExpr;
@@ -194,24 +195,49 @@ fn pretty_selection(selection: Option<Expr>) -> RcDoc<'static> {
     }
 }
 
-fn pretty_group_by(group_by: Vec<Expr>) -> RcDoc<'static> {
-    if !group_by.is_empty() {
-        RcDoc::line()
-            .append(
-                RcDoc::text("GROUP BY").append(
-                    if group_by.len() > 1 {
-                        RcDoc::line()
-                    } else {
-                        RcDoc::space()
-                    }
-                    .nest(NEST_FACTOR),
+fn pretty_group_by(group_by: Option<GroupBy>) -> RcDoc<'static> {
+    if let Some(group_by) = group_by {
+        match group_by {
+            GroupBy::Normal(exprs) => RcDoc::line()
+                .append(
+                    RcDoc::text("GROUP BY").append(
+                        if exprs.len() > 1 {
+                            RcDoc::line()
+                        } else {
+                            RcDoc::space()
+                        }
+                        .nest(NEST_FACTOR),
+                    ),
+                )
+                .append(
+                    interweave_comma(exprs.into_iter().map(pretty_expr))
+                        .nest(NEST_FACTOR)
+                        .group(),
                 ),
-            )
-            .append(
-                interweave_comma(group_by.into_iter().map(pretty_expr))
+            GroupBy::GroupingSets(sets) => RcDoc::line()
+                .append(RcDoc::text("GROUP BY GROUPING SETS").append(RcDoc::space()))
+                .append(parenthenized(
+                    interweave_comma(sets.into_iter().map(|set| {
+                        parenthenized(interweave_comma(set.into_iter().map(pretty_expr)))
+                    }))
                     .nest(NEST_FACTOR)
                     .group(),
-            )
+                )),
+            GroupBy::Cube(exprs) => RcDoc::line()
+                .append(RcDoc::text("GROUP BY CUBE").append(RcDoc::space()))
+                .append(parenthenized(
+                    interweave_comma(exprs.into_iter().map(pretty_expr))
+                        .nest(NEST_FACTOR)
+                        .group(),
+                )),
+            GroupBy::Rollup(exprs) => RcDoc::line()
+                .append(RcDoc::text("GROUP BY ROLLUP").append(RcDoc::space()))
+                .append(parenthenized(
+                    interweave_comma(exprs.into_iter().map(pretty_expr))
+                        .nest(NEST_FACTOR)
+                        .group(),
+                )),
+        }
     } else {
         RcDoc::nil()
     }
@@ -261,15 +287,23 @@ pub(crate) fn pretty_table(table: TableReference) -> RcDoc<'static> {
         }),
         TableReference::Subquery {
             span: _,
+            lateral,
             subquery,
             alias,
-        } => parenthenized(pretty_query(*subquery)).append(if let Some(alias) = alias {
+        } => if lateral {
+            RcDoc::text("LATERAL").append(RcDoc::space())
+        } else {
+            RcDoc::nil()
+        }
+        .append(parenthenized(pretty_query(*subquery)))
+        .append(if let Some(alias) = alias {
             RcDoc::text(format!(" AS {alias}"))
         } else {
             RcDoc::nil()
         }),
         TableReference::TableFunction {
             span: _,
+            lateral,
             name,
             params,
             named_params,
@@ -280,7 +314,13 @@ pub(crate) fn pretty_table(table: TableReference) -> RcDoc<'static> {
             } else {
                 RcDoc::nil()
             };
-            RcDoc::text(name.to_string())
+            let lateral = if lateral {
+                RcDoc::text("LATERAL").append(RcDoc::space())
+            } else {
+                RcDoc::nil()
+            };
+            lateral
+                .append(RcDoc::text(name.to_string()))
                 .append(RcDoc::text("("))
                 .append(inline_comma(params.into_iter().map(pretty_expr)))
                 .append(separator)
@@ -296,6 +336,30 @@ pub(crate) fn pretty_table(table: TableReference) -> RcDoc<'static> {
                     RcDoc::nil()
                 })
         }
+        TableReference::Pivot {
+            span: _,
+            table,
+            pivot,
+            alias,
+        } => pretty_table(*table)
+            .append(RcDoc::text(format!(" {pivot}")))
+            .append(if let Some(alias) = alias {
+                RcDoc::text(format!(" AS {alias}"))
+            } else {
+                RcDoc::nil()
+            }),
+        TableReference::Unpivot {
+            span: _,
+            table,
+            unpivot,
+            alias,
+        } => pretty_table(*table)
+            .append(RcDoc::text(format!(" {unpivot}")))
+            .append(if let Some(alias) = alias {
+                RcDoc::text(format!(" AS {alias}"))
+            } else {
+                RcDoc::nil()
+            }),
         TableReference::Join { span: _, join } => pretty_table(*join.left)
             .append(RcDoc::line())
             .append(if join.condition == JoinCondition::Natural {
@@ -82,11 +82,26 @@ pub struct SelectStmt {
     // `WHERE` clause
     pub selection: Option<Expr>,
     // `GROUP BY` clause
-    pub group_by: Vec<Expr>,
+    pub group_by: Option<GroupBy>,
     // `HAVING` clause
     pub having: Option<Expr>,
 }
 
+/// Group by Clause.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupBy {
+    /// GROUP BY expr [, expr]*
+    Normal(Vec<Expr>),
+    /// GROUP BY GROUPING SETS ( GroupSet [, GroupSet]* )
+    ///
+    /// GroupSet := (expr [, expr]*) | expr
+    GroupingSets(Vec<Vec<Expr>>),
+    /// GROUP BY CUBE ( expr [, expr]* )
+    Cube(Vec<Expr>),
+    /// GROUP BY ROLLUP ( expr [, expr]* )
+    Rollup(Vec<Expr>),
+}
+
 /// A relational set expression, like `SELECT ... FROM ... {UNION|EXCEPT|INTERSECT} SELECT ... FROM ...`
 #[derive(Debug, Clone, PartialEq)]
 pub enum SetExpr {
@@ -161,9 +176,10 @@ pub enum TableReference {
         alias: Option<TableAlias>,
         travel_point: Option<TimeTravelPoint>,
     },
-    // `TABLE(expr)[ AS alias ]`
+    // `[LATERAL] TABLE(expr)[ AS alias ]`
     TableFunction {
         span: Span,
+        lateral: bool,
         name: Identifier,
         params: Vec<Expr>,
         named_params: Vec<(String, Expr)>,
@@ -172,6 +188,7 @@ pub enum TableReference {
     // Derived table, which can be a subquery or joined tables or combination of them
     Subquery {
         span: Span,
+        lateral: bool,
         subquery: Box<Query>,
         alias: Option<TableAlias>,
     },
@@ -185,6 +202,38 @@ pub enum TableReference {
         options: SelectStageOptions,
         alias: Option<TableAlias>,
     },
+    // `table_ref PIVOT (aggregate FOR column IN (values))[ AS alias ]`
+    Pivot {
+        span: Span,
+        table: Box<TableReference>,
+        pivot: Box<Pivot>,
+        alias: Option<TableAlias>,
+    },
+    // `table_ref UNPIVOT (value_column FOR name_column IN (columns))[ AS alias ]`
+    Unpivot {
+        span: Span,
+        table: Box<TableReference>,
+        unpivot: Box<Unpivot>,
+        alias: Option<TableAlias>,
+    },
+}
+
+/// Rotate the rows of a table into columns, one column for each of the `values`
+/// of `value_column`, computed by `aggregate` over the rows with that value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pivot {
+    pub aggregate: Expr,
+    pub value_column: Identifier,
+    pub values: Vec<Expr>,
+}
+
+/// Rotate the `columns` of a table into rows, the name of the column is put in
+/// `name_column` and its value in `value_column`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unpivot {
+    pub value_column: Identifier,
+    pub name_column: Identifier,
+    pub columns: Vec<Identifier>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -287,11 +336,15 @@ impl Display for TableReference {
             }
             TableReference::TableFunction {
                 span: _,
+                lateral,
                 name,
                 params,
                 named_params,
                 alias,
             } => {
+                if *lateral {
+                    write!(f, "LATERAL ")?;
+                }
                 write!(f, "{name}(")?;
                 write_comma_separated_list(f, params)?;
                 if !params.is_empty() && !named_params.is_empty() {
@@ -310,9 +363,13 @@ impl Display for TableReference {
             }
             TableReference::Subquery {
                 span: _,
+                lateral,
                 subquery,
                 alias,
             } => {
+                if *lateral {
+                    write!(f, "LATERAL ")?;
+                }
                 write!(f, "({subquery})")?;
                 if let Some(alias) = alias {
                     write!(f, " AS {alias}")?;
@@ -383,11 +440,53 @@ impl Display for TableReference {
                     write!(f, " AS {alias}")?;
                 }
             }
+            TableReference::Pivot {
+                span: _,
+                table,
+                pivot,
+                alias,
+            } => {
+                write!(f, "{table} {pivot}")?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+            }
+            TableReference::Unpivot {
+                span: _,
+                table,
+                unpivot,
+                alias,
+            } => {
+                write!(f, "{table} {unpivot}")?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+            }
         }
         Ok(())
     }
 }
 
+impl Display for Pivot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PIVOT({} FOR {} IN (", self.aggregate, self.value_column)?;
+        write_comma_separated_list(f, &self.values)?;
+        write!(f, "))")
+    }
+}
+
+impl Display for Unpivot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "UNPIVOT({} FOR {} IN (",
+            self.value_column, self.name_column
+        )?;
+        write_comma_separated_list(f, &self.columns)?;
+        write!(f, "))")
+    }
+}
+
 impl Display for Indirection {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -448,9 +547,8 @@ impl Display for SelectStmt {
         }
 
         // GROUP BY clause
-        if !self.group_by.is_empty() {
-            write!(f, " GROUP BY ")?;
-            write_comma_separated_list(f, &self.group_by)?;
+        if let Some(group_by) = &self.group_by {
+            write!(f, " GROUP BY {group_by}")?;
         }
 
         // HAVING clause
@@ -462,6 +560,39 @@ impl Display for SelectStmt {
     }
 }
 
+impl Display for GroupBy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupBy::Normal(exprs) => {
+                write_comma_separated_list(f, exprs)?;
+            }
+            GroupBy::GroupingSets(sets) => {
+                write!(f, "GROUPING SETS (")?;
+                for (i, set) in sets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "(")?;
+                    write_comma_separated_list(f, set)?;
+                    write!(f, ")")?;
+                }
+                write!(f, ")")?;
+            }
+            GroupBy::Cube(exprs) => {
+                write!(f, "CUBE (")?;
+                write_comma_separated_list(f, exprs)?;
+                write!(f, ")")?;
+            }
+            GroupBy::Rollup(exprs) => {
+                write!(f, "ROLLUP (")?;
+                write_comma_separated_list(f, exprs)?;
+                write!(f, ")")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Display for SetExpr {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -0,0 +1,114 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use crate::ast::write_comma_separated_list;
+use crate::ast::Expr;
+use crate::ast::Identifier;
+use crate::ast::TableReference;
+use crate::ast::UpdateExpr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeIntoStmt {
+    pub table: TableReference,
+    pub source: TableReference,
+    pub join_expr: Expr,
+    /// `WHEN [NOT] MATCHED` clauses, in the order they are written.
+    pub merge_clauses: Vec<MergeClause>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeClause {
+    /// `WHEN MATCHED [AND <condition>] THEN UPDATE SET ... | DELETE`
+    Matched {
+        selection: Option<Expr>,
+        operation: MatchOperation,
+    },
+    /// `WHEN NOT MATCHED [AND <condition>] THEN INSERT [(<column>, ...)] VALUES (...)`
+    NotMatched {
+        selection: Option<Expr>,
+        columns: Vec<Identifier>,
+        values: Vec<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchOperation {
+    Update { update_list: Vec<UpdateExpr> },
+    Delete,
+}
+
+impl Display for MergeIntoStmt {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "MERGE INTO {} USING {} ON {}",
+            self.table, self.source, self.join_expr
+        )?;
+        for clause in &self.merge_clauses {
+            write!(f, " {clause}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for MergeClause {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            MergeClause::Matched {
+                selection,
+                operation,
+            } => {
+                write!(f, "WHEN MATCHED")?;
+                if let Some(selection) = selection {
+                    write!(f, " AND {selection}")?;
+                }
+                write!(f, " THEN {operation}")
+            }
+            MergeClause::NotMatched {
+                selection,
+                columns,
+                values,
+            } => {
+                write!(f, "WHEN NOT MATCHED")?;
+                if let Some(selection) = selection {
+                    write!(f, " AND {selection}")?;
+                }
+                write!(f, " THEN INSERT")?;
+                if !columns.is_empty() {
+                    write!(f, " (")?;
+                    write_comma_separated_list(f, columns)?;
+                    write!(f, ")")?;
+                }
+                write!(f, " VALUES (")?;
+                write_comma_separated_list(f, values)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl Display for MatchOperation {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            MatchOperation::Update { update_list } => {
+                write!(f, "UPDATE SET ")?;
+                write_comma_separated_list(f, update_list)
+            }
+            MatchOperation::Delete => write!(f, "DELETE"),
+        }
+    }
+}
@@ -19,11 +19,13 @@ mod database;
 mod explain;
 mod insert;
 mod kill;
+mod merge_into;
 mod presign;
 mod share;
 mod show;
 mod stage;
 mod statement;
+mod stream;
 mod table;
 mod unset;
 mod update;
@@ -37,11 +39,13 @@ pub use database::*;
 pub use explain::*;
 pub use insert::*;
 pub use kill::*;
+pub use merge_into::*;
 pub use presign::*;
 pub use share::*;
 pub use show::*;
 pub use stage::*;
 pub use statement::*;
+pub use stream::*;
 pub use table::*;
 pub use unset::*;
 pub use update::*;
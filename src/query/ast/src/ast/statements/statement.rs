@@ -79,6 +79,8 @@ pub enum Statement {
 
     Update(UpdateStmt),
 
+    MergeInto(MergeIntoStmt),
+
     // Catalogs
     ShowCatalogs(ShowCatalogsStmt),
     ShowCreateCatalog(ShowCreateCatalogStmt),
@@ -116,6 +118,10 @@ pub enum Statement {
     AlterView(AlterViewStmt),
     DropView(DropViewStmt),
 
+    // Streams
+    CreateStream(CreateStreamStmt),
+    DropStream(DropStreamStmt),
+
     // User
     ShowUsers,
     CreateUser(CreateUserStmt),
@@ -242,6 +248,7 @@ impl Display for Statement {
                 }
             }
             Statement::Update(update) => write!(f, "{update}")?,
+            Statement::MergeInto(merge_into) => write!(f, "{merge_into}")?,
             Statement::Copy(stmt) => write!(f, "{stmt}")?,
             Statement::ShowSettings { like } => {
                 write!(f, "SHOW SETTINGS")?;
@@ -319,6 +326,8 @@ impl Display for Statement {
             Statement::CreateView(stmt) => write!(f, "{stmt}")?,
             Statement::AlterView(stmt) => write!(f, "{stmt}")?,
             Statement::DropView(stmt) => write!(f, "{stmt}")?,
+            Statement::CreateStream(stmt) => write!(f, "{stmt}")?,
+            Statement::DropStream(stmt) => write!(f, "{stmt}")?,
             Statement::ShowUsers => write!(f, "SHOW USERS")?,
             Statement::ShowRoles => write!(f, "SHOW ROLES")?,
             Statement::CreateUser(stmt) => write!(f, "{stmt}")?,
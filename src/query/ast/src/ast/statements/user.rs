@@ -38,8 +38,7 @@ impl Display for CreateUserStmt {
         if self.if_not_exists {
             write!(f, " IF NOT EXISTS")?;
         }
-        write!(f, " {} IDENTIFIED", self.user)?;
-        write!(f, " {}", self.auth_option)?;
+        write!(f, " {} {}", self.user, self.auth_option)?;
         if !self.user_options.is_empty() {
             write!(f, " WITH")?;
             for user_option in &self.user_options {
@@ -59,11 +58,19 @@ pub struct AuthOption {
 
 impl Display for AuthOption {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.auth_type == Some(AuthType::X509) {
+            return match &self.password {
+                Some(subject) => write!(f, "REQUIRE SUBJECT '{subject}'"),
+                None => write!(f, "REQUIRE X509"),
+            };
+        }
+
+        write!(f, "IDENTIFIED")?;
         if let Some(auth_type) = &self.auth_type {
-            write!(f, "WITH {} ", auth_type.to_str())?;
+            write!(f, " WITH {}", auth_type.to_str())?;
         }
         if let Some(password) = &self.password {
-            write!(f, "BY '{password}'")?;
+            write!(f, " BY '{password}'")?;
         }
 
         Ok(())
@@ -88,7 +95,7 @@ impl Display for AlterUserStmt {
             write!(f, " USER()")?;
         }
         if let Some(auth_option) = &self.auth_option {
-            write!(f, " IDENTIFIED {}", auth_option)?;
+            write!(f, " {}", auth_option)?;
         }
         if !self.user_options.is_empty() {
             write!(f, " WITH")?;
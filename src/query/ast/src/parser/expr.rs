@@ -139,6 +139,14 @@ pub fn subexpr(min_precedence: u32) -> impl FnMut(Input) -> IResult<Expr> {
                         },
                     };
                 }
+
+                // and replace `:<name>` map access to a named placeholder.
+                if let ExprElement::MapAccess {
+                    accessor: MapAccessor::Colon { .. },
+                } = &expr_elements[curr as usize].elem
+                {
+                    expr_elements[curr as usize].elem = ExprElement::Placeholder;
+                }
             }
         }
         let iter = &mut expr_elements.into_iter();
@@ -250,6 +258,7 @@ pub enum ExprElement {
         name: Identifier,
         args: Vec<Expr>,
         params: Vec<Literal>,
+        window: Option<Window>,
     },
     /// `CASE ... WHEN ... ELSE ...` expression
     Case {
@@ -304,6 +313,8 @@ pub enum ExprElement {
         unit: IntervalKind,
         date: Expr,
     },
+    /// `?`, `$<n>` or `:<name>` placeholder of a prepared statement
+    Placeholder,
 }
 
 struct ExprParser;
@@ -426,6 +437,9 @@ impl<'a, I: Iterator<Item = WithSpan<'a, ExprElement>>> PrattParser<I> for ExprP
             ExprElement::CountAll => Expr::CountAll {
                 span: transform_span(elem.span.0),
             },
+            ExprElement::Placeholder => Expr::Placeholder {
+                span: transform_span(elem.span.0),
+            },
             ExprElement::Tuple { exprs } => Expr::Tuple {
                 span: transform_span(elem.span.0),
                 exprs,
@@ -435,12 +449,14 @@ impl<'a, I: Iterator<Item = WithSpan<'a, ExprElement>>> PrattParser<I> for ExprP
                 name,
                 args,
                 params,
+                window,
             } => Expr::FunctionCall {
                 span: transform_span(elem.span.0),
                 distinct,
                 name,
                 args,
                 params,
+                window,
             },
             ExprElement::Case {
                 operand,
@@ -767,9 +783,26 @@ pub fn expr_element(i: Input) -> IResult<WithSpan<ExprElement>> {
             trim_where: Some((trim_where, Box::new(trim_str))),
         },
     );
-    let count_all = value(ExprElement::CountAll, rule! {
-        COUNT ~ "(" ~ "*" ~ ^")"
-    });
+    let count_all = map(
+        rule! {
+            COUNT ~ "(" ~ "*" ~ ^")" ~ #window_spec?
+        },
+        |(count, _, _, _, window)| match window {
+            // `COUNT(*) OVER (...)` is treated as a window call of `COUNT()`
+            Some(window) => ExprElement::FunctionCall {
+                distinct: false,
+                name: Identifier {
+                    name: count.text().to_string(),
+                    quote: None,
+                    span: transform_span(&[count.clone()]),
+                },
+                args: vec![],
+                params: vec![],
+                window: Some(window),
+            },
+            None => ExprElement::CountAll,
+        },
+    );
     let tuple = map(
         rule! {
             "(" ~ #comma_separated_list0_ignore_trailing(subexpr(0)) ~ ","? ~ ^")"
@@ -789,12 +822,14 @@ pub fn expr_element(i: Input) -> IResult<WithSpan<ExprElement>> {
             ~ DISTINCT?
             ~ #comma_separated_list0(subexpr(0))?
             ~ ")"
+            ~ #window_spec?
         },
-        |(name, _, opt_distinct, opt_args, _)| ExprElement::FunctionCall {
+        |(name, _, opt_distinct, opt_args, _, window)| ExprElement::FunctionCall {
             distinct: opt_distinct.is_some(),
             name,
             args: opt_args.unwrap_or_default(),
             params: vec![],
+            window,
         },
     );
     let function_call_with_param = map(
@@ -802,12 +837,14 @@ pub fn expr_element(i: Input) -> IResult<WithSpan<ExprElement>> {
             #function_name
             ~ "(" ~ #comma_separated_list1(literal) ~ ")"
             ~ "(" ~ DISTINCT? ~ #comma_separated_list0(subexpr(0))? ~ ")"
+            ~ #window_spec?
         },
-        |(name, _, params, _, _, opt_distinct, opt_args, _)| ExprElement::FunctionCall {
+        |(name, _, params, _, _, opt_distinct, opt_args, _, window)| ExprElement::FunctionCall {
             distinct: opt_distinct.is_some(),
             name,
             args: opt_args.unwrap_or_default(),
             params,
+            window,
         },
     );
     let case = map(
@@ -930,6 +967,7 @@ pub fn expr_element(i: Input) -> IResult<WithSpan<ExprElement>> {
         },
         |(_, not, _, _)| ExprElement::IsDistinctFrom { not: not.is_some() },
     );
+    let placeholder = map(rule! { Placeholder }, |_| ExprElement::Placeholder);
     let (rest, (span, elem)) = consumed(alt((
         // Note: each `alt` call supports maximum of 21 parsers
         rule!(
@@ -965,12 +1003,87 @@ pub fn expr_element(i: Input) -> IResult<WithSpan<ExprElement>> {
             | #map_access : "[<key>] | .<key> | :<key>"
             | #literal : "<literal>"
             | #array : "`[...]`"
+            | #placeholder : "`?`"
         ),
     )))(i)?;
 
     Ok((rest, WithSpan { span, elem }))
 }
 
+/// Parse the `OVER (...)` clause of a window function call.
+pub fn window_spec(i: Input) -> IResult<Window> {
+    map(
+        rule! {
+            OVER ~ "("
+            ~ ( PARTITION ~ ^BY ~ ^#comma_separated_list1(subexpr(0)) )?
+            ~ ( ORDER ~ ^BY ~ ^#comma_separated_list1(order_by_expr) )?
+            ~ #window_frame?
+            ~ ^")"
+        },
+        |(_, _, opt_partition_by, opt_order_by, window_frame, _)| Window {
+            partition_by: opt_partition_by
+                .map(|(_, _, exprs)| exprs)
+                .unwrap_or_default(),
+            order_by: opt_order_by.map(|(_, _, exprs)| exprs).unwrap_or_default(),
+            window_frame,
+        },
+    )(i)
+}
+
+pub fn window_frame(i: Input) -> IResult<WindowFrame> {
+    let between = map(
+        rule! {
+            #window_frame_units ~ BETWEEN ~ ^#window_frame_bound ~ ^AND ~ ^#window_frame_bound
+        },
+        |(units, _, start_bound, _, end_bound)| WindowFrame {
+            units,
+            start_bound,
+            end_bound,
+        },
+    );
+    // `ROWS <bound>` is a shorthand of `ROWS BETWEEN <bound> AND CURRENT ROW`
+    let start_only = map(
+        rule! {
+            #window_frame_units ~ #window_frame_bound
+        },
+        |(units, start_bound)| WindowFrame {
+            units,
+            start_bound,
+            end_bound: WindowFrameBound::CurrentRow,
+        },
+    );
+
+    rule!(
+        #between
+        | #start_only
+    )(i)
+}
+
+pub fn window_frame_units(i: Input) -> IResult<WindowFrameUnits> {
+    alt((
+        value(WindowFrameUnits::Rows, rule! { ROWS }),
+        value(WindowFrameUnits::Range, rule! { RANGE }),
+    ))(i)
+}
+
+pub fn window_frame_bound(i: Input) -> IResult<WindowFrameBound> {
+    alt((
+        value(WindowFrameBound::CurrentRow, rule! { CURRENT ~ ROW }),
+        value(WindowFrameBound::Preceding(None), rule! {
+            UNBOUNDED ~ PRECEDING
+        }),
+        value(WindowFrameBound::Following(None), rule! {
+            UNBOUNDED ~ FOLLOWING
+        }),
+        map(rule! { #subexpr(0) ~ PRECEDING }, |(expr, _)| {
+            WindowFrameBound::Preceding(Some(Box::new(expr)))
+        }),
+        map(rule! { #subexpr(0) ~ FOLLOWING }, |(expr, _)| {
+            WindowFrameBound::Following(Some(Box::new(expr)))
+        }),
+    ))(i)
+}
+
 pub fn unary_op(i: Input) -> IResult<UnaryOperator> {
     // Plus and Minus are parsed as binary op at first.
     value(UnaryOperator::Not, rule! { NOT })(i)
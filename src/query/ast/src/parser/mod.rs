@@ -24,6 +24,7 @@ pub mod unescape;
 pub use parser::parse_comma_separated_exprs;
 pub use parser::parse_expr;
 pub use parser::parse_sql;
+pub use parser::parse_type_name;
 pub use parser::parser_values_with_placeholder;
 pub use parser::tokenize_sql;
 pub use token::all_reserved_keywords;
@@ -17,6 +17,7 @@ use common_exception::Result;
 
 use crate::ast::Expr;
 use crate::ast::Statement;
+use crate::ast::TypeName;
 use crate::error::display_parser_error;
 use crate::input::Dialect;
 use crate::input::Input;
@@ -79,6 +80,28 @@ pub fn parse_expr<'a>(
     }
 }
 
+/// Parse the name of a data type, e.g. `DECIMAL(10, 2)` or `NULLABLE(DATE)`.
+pub fn parse_type_name<'a>(
+    sql_tokens: &'a [Token<'a>],
+    dialect: Dialect,
+    backtrace: &'a Backtrace,
+) -> Result<TypeName> {
+    match expr::type_name(Input(sql_tokens, dialect, backtrace)) {
+        Ok((rest, type_name)) if rest[0].kind == TokenKind::EOI => Ok(type_name),
+        Ok((rest, _)) => Err(ErrorCode::SyntaxException(
+            "unable to parse rest of the type name".to_string(),
+        )
+        .set_span(transform_span(&rest[..1]))),
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            let source = sql_tokens[0].source;
+            Err(ErrorCode::SyntaxException(display_parser_error(
+                err, source,
+            )))
+        }
+        Err(nom::Err::Incomplete(_)) => unreachable!(),
+    }
+}
+
 pub fn parse_comma_separated_exprs<'a>(
     sql_tokens: &'a [Token<'a>],
     dialect: Dialect,
@@ -262,14 +262,16 @@ pub enum TableReferenceElement {
         alias: Option<TableAlias>,
         travel_point: Option<TimeTravelPoint>,
     },
-    // `TABLE(expr)[ AS alias ]`
+    // `[LATERAL] TABLE(expr)[ AS alias ]`
     TableFunction {
+        lateral: bool,
         name: Identifier,
         params: Vec<TableFunctionParam>,
         alias: Option<TableAlias>,
     },
     // Derived table, which can be a subquery or joined tables or combination of them
     Subquery {
+        lateral: bool,
         subquery: Box<Query>,
         alias: Option<TableAlias>,
     },
@@ -280,6 +282,16 @@ pub enum TableReferenceElement {
     },
     // ON expr | USING (ident, ...)
     JoinCondition(JoinCondition),
+    // PIVOT (aggregate FOR column IN (values))[ AS alias ]
+    Pivot {
+        pivot: Box<Pivot>,
+        alias: Option<TableAlias>,
+    },
+    // UNPIVOT (value_column FOR name_column IN (columns))[ AS alias ]
+    Unpivot {
+        unpivot: Box<Unpivot>,
+        alias: Option<TableAlias>,
+    },
     Group(TableReference),
     Stage {
         location: FileLocation,
@@ -303,9 +315,10 @@ pub fn table_reference_element(i: Input) -> IResult<WithSpan<TableReferenceEleme
     );
     let table_function = map(
         rule! {
-            #ident ~ "(" ~ #comma_separated_list0(table_function_param) ~ ")" ~ #table_alias?
+            LATERAL? ~ #ident ~ "(" ~ #comma_separated_list0(table_function_param) ~ ")" ~ #table_alias?
         },
-        |(name, _, params, _, alias)| TableReferenceElement::TableFunction {
+        |(lateral, name, _, params, _, alias)| TableReferenceElement::TableFunction {
+            lateral: lateral.is_some(),
             name,
             params,
             alias,
@@ -313,9 +326,10 @@ pub fn table_reference_element(i: Input) -> IResult<WithSpan<TableReferenceEleme
     );
     let subquery = map(
         rule! {
-            ( #parenthesized_query | #query ) ~ #table_alias?
+            LATERAL? ~ ( #parenthesized_query | #query ) ~ #table_alias?
         },
-        |(subquery, alias)| TableReferenceElement::Subquery {
+        |(lateral, subquery, alias)| TableReferenceElement::Subquery {
+            lateral: lateral.is_some(),
             subquery: Box::new(subquery),
             alias,
         },
@@ -341,6 +355,40 @@ pub fn table_reference_element(i: Input) -> IResult<WithSpan<TableReferenceEleme
         },
         |(_, _, idents, _)| TableReferenceElement::JoinCondition(JoinCondition::Using(idents)),
     );
+    let pivot = map(
+        rule! {
+            PIVOT ~ ^"(" ~ ^#expr ~ ^FOR ~ ^#ident
+            ~ ^IN ~ ^"(" ~ ^#comma_separated_list1(expr) ~ ^")" ~ ^")"
+            ~ #table_alias?
+        },
+        |(_, _, aggregate, _, value_column, _, _, values, _, _, alias)| {
+            TableReferenceElement::Pivot {
+                pivot: Box::new(Pivot {
+                    aggregate,
+                    value_column,
+                    values,
+                }),
+                alias,
+            }
+        },
+    );
+    let unpivot = map(
+        rule! {
+            UNPIVOT ~ ^"(" ~ ^#ident ~ ^FOR ~ ^#ident
+            ~ ^IN ~ ^"(" ~ ^#comma_separated_list1(ident) ~ ^")" ~ ^")"
+            ~ #table_alias?
+        },
+        |(_, _, value_column, _, name_column, _, _, columns, _, _, alias)| {
+            TableReferenceElement::Unpivot {
+                unpivot: Box::new(Unpivot {
+                    value_column,
+                    name_column,
+                    columns,
+                }),
+                alias,
+            }
+        },
+    );
     let group = map(
         rule! {
            "(" ~ #table_reference ~ ^")"
@@ -392,6 +440,8 @@ pub fn table_reference_element(i: Input) -> IResult<WithSpan<TableReferenceEleme
         | #join
         | #join_condition_on
         | #join_condition_using
+        | #pivot
+        | #unpivot
     })(i)?;
     Ok((rest, WithSpan { span, elem }))
 }
@@ -409,6 +459,9 @@ impl<'a, I: Iterator<Item = WithSpan<'a, TableReferenceElement>>> PrattParser<I>
         let affix = match &input.elem {
             TableReferenceElement::Join { .. } => Affix::Infix(Precedence(10), Associativity::Left),
             TableReferenceElement::JoinCondition(..) => Affix::Postfix(Precedence(5)),
+            TableReferenceElement::Pivot { .. } | TableReferenceElement::Unpivot { .. } => {
+                Affix::Postfix(Precedence(20))
+            }
             _ => Affix::Nilfix,
         };
         Ok(affix)
@@ -432,6 +485,7 @@ impl<'a, I: Iterator<Item = WithSpan<'a, TableReferenceElement>>> PrattParser<I>
                 travel_point,
             },
             TableReferenceElement::TableFunction {
+                lateral,
                 name,
                 params,
                 alias,
@@ -452,14 +506,20 @@ impl<'a, I: Iterator<Item = WithSpan<'a, TableReferenceElement>>> PrattParser<I>
                     .collect();
                 TableReference::TableFunction {
                     span: transform_span(input.span.0),
+                    lateral,
                     name,
                     params: normal_params,
                     named_params,
                     alias,
                 }
             }
-            TableReferenceElement::Subquery { subquery, alias } => TableReference::Subquery {
+            TableReferenceElement::Subquery {
+                lateral,
+                subquery,
+                alias,
+            } => TableReference::Subquery {
                 span: transform_span(input.span.0),
+                lateral,
                 subquery,
                 alias,
             },
@@ -537,11 +597,56 @@ impl<'a, I: Iterator<Item = WithSpan<'a, TableReferenceElement>>> PrattParser<I>
                 },
                 _ => Err("join condition must apply to a join"),
             },
+            TableReferenceElement::Pivot { pivot, alias } => Ok(TableReference::Pivot {
+                span: transform_span(op.span.0),
+                table: Box::new(lhs),
+                pivot,
+                alias,
+            }),
+            TableReferenceElement::Unpivot { unpivot, alias } => Ok(TableReference::Unpivot {
+                span: transform_span(op.span.0),
+                table: Box::new(lhs),
+                unpivot,
+                alias,
+            }),
             _ => unreachable!(),
         }
     }
 }
 
+pub fn group_by_items(i: Input) -> IResult<GroupBy> {
+    let normal = map(comma_separated_list1(expr), GroupBy::Normal);
+    let cube = map(
+        rule! {
+            CUBE ~ "(" ~ ^#comma_separated_list1(expr) ~ ^")"
+        },
+        |(_, _, groups, _)| GroupBy::Cube(groups),
+    );
+    let rollup = map(
+        rule! {
+            ROLLUP ~ "(" ~ ^#comma_separated_list1(expr) ~ ^")"
+        },
+        |(_, _, groups, _)| GroupBy::Rollup(groups),
+    );
+    let group_set = alt((
+        map(
+            rule! {
+                "(" ~ #comma_separated_list0(expr) ~ ")"
+            },
+            |(_, exprs, _)| exprs,
+        ),
+        map(expr, |expr| vec![expr]),
+    ));
+    let grouping_sets = map(
+        rule! {
+            GROUPING ~ SETS ~ ^"(" ~ ^#comma_separated_list1(group_set) ~ ^")"
+        },
+        |(_, _, _, sets, _)| GroupBy::GroupingSets(sets),
+    );
+
+    rule!(#grouping_sets | #cube | #rollup | #normal)(i)
+}
+
 pub fn set_operation(i: Input) -> IResult<SetExpr> {
     let (rest, set_operation_elements) = rule!(#set_operation_element+)(i)?;
     let iter = &mut set_operation_elements.into_iter();
@@ -555,7 +660,7 @@ pub enum SetOperationElement {
         select_list: Box<Vec<SelectTarget>>,
         from: Box<Vec<TableReference>>,
         selection: Box<Option<Expr>>,
-        group_by: Box<Vec<Expr>>,
+        group_by: Box<Option<GroupBy>>,
         having: Box<Option<Expr>>,
     },
     SetOperation {
@@ -588,7 +693,7 @@ pub fn set_operation_element(i: Input) -> IResult<WithSpan<SetOperationElement>>
              SELECT ~ DISTINCT? ~ ^#comma_separated_list1(select_target)
                 ~ ( FROM ~ ^#comma_separated_list1(table_reference) )?
                 ~ ( WHERE ~ ^#expr )?
-                ~ ( GROUP ~ ^BY ~ ^#comma_separated_list1(expr) )?
+                ~ ( GROUP ~ ^BY ~ ^#group_by_items )?
                 ~ ( HAVING ~ ^#expr )?
         },
         |(
@@ -609,11 +714,7 @@ pub fn set_operation_element(i: Input) -> IResult<WithSpan<SetOperationElement>>
                         .unwrap_or_default(),
                 ),
                 selection: Box::new(opt_where_block.map(|(_, selection)| selection)),
-                group_by: Box::new(
-                    opt_group_by_block
-                        .map(|(_, _, group_by)| group_by)
-                        .unwrap_or_default(),
-                ),
+                group_by: Box::new(opt_group_by_block.map(|(_, _, group_by)| group_by)),
                 having: Box::new(opt_having_block.map(|(_, having)| having)),
             }
         },
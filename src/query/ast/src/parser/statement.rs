@@ -138,6 +138,23 @@ pub fn statement(i: Input) -> IResult<StatementMsg> {
         },
     );
 
+    let merge_into = map(
+        rule! {
+            MERGE ~ INTO ~ #merge_target_table
+            ~ ^USING ~ ^#table_reference
+            ~ ^ON ~ ^#expr
+            ~ #merge_clause+
+        },
+        |(_, _, table, _, source, _, join_expr, merge_clauses)| {
+            Statement::MergeInto(MergeIntoStmt {
+                table,
+                source,
+                join_expr,
+                merge_clauses,
+            })
+        },
+    );
+
     let show_settings = map(
         rule! {
             SHOW ~ SETTINGS ~ (LIKE ~ #literal_string)?
@@ -614,22 +631,60 @@ pub fn statement(i: Input) -> IResult<StatementMsg> {
             })
         },
     );
+    let create_stream = map(
+        rule! {
+            CREATE ~ STREAM ~ ( IF ~ NOT ~ EXISTS )?
+            ~ #period_separated_idents_1_to_3
+            ~ ON ~ TABLE ~ #period_separated_idents_1_to_2
+            ~ ( AT ~ ^#travel_point )?
+        },
+        |(
+            _,
+            _,
+            opt_if_not_exists,
+            (catalog, database, stream),
+            _,
+            _,
+            (table_database, table),
+            travel_point,
+        )| {
+            Statement::CreateStream(CreateStreamStmt {
+                if_not_exists: opt_if_not_exists.is_some(),
+                catalog,
+                database,
+                stream,
+                table_database,
+                table,
+                travel_point: travel_point.map(|(_, point)| point),
+            })
+        },
+    );
+    let drop_stream = map(
+        rule! {
+            DROP ~ STREAM ~ ( IF ~ EXISTS )? ~ #period_separated_idents_1_to_3
+        },
+        |(_, _, opt_if_exists, (catalog, database, stream))| {
+            Statement::DropStream(DropStreamStmt {
+                if_exists: opt_if_exists.is_some(),
+                catalog,
+                database,
+                stream,
+            })
+        },
+    );
     let show_users = value(Statement::ShowUsers, rule! { SHOW ~ USERS });
     let create_user = map(
         rule! {
             CREATE ~ USER ~ ( IF ~ NOT ~ EXISTS )?
             ~ #user_identity
-            ~ IDENTIFIED ~ ( WITH ~ ^#auth_type )? ~ ( BY ~ ^#literal_string )?
+            ~ #auth_option
             ~ ( WITH ~ ^#comma_separated_list1(user_option))?
         },
-        |(_, _, opt_if_not_exists, user, _, opt_auth_type, opt_password, opt_user_option)| {
+        |(_, _, opt_if_not_exists, user, auth_option, opt_user_option)| {
             Statement::CreateUser(CreateUserStmt {
                 if_not_exists: opt_if_not_exists.is_some(),
                 user,
-                auth_option: AuthOption {
-                    auth_type: opt_auth_type.map(|(_, auth_type)| auth_type),
-                    password: opt_password.map(|(_, password)| password),
-                },
+                auth_option,
                 user_options: opt_user_option
                     .map(|(_, user_options)| user_options)
                     .unwrap_or_default(),
@@ -639,16 +694,13 @@ pub fn statement(i: Input) -> IResult<StatementMsg> {
     let alter_user = map(
         rule! {
             ALTER ~ USER ~ ( #map(rule! { USER ~ "(" ~ ")" }, |_| None) | #map(user_identity, Some) )
-            ~ ( IDENTIFIED ~ ( WITH ~ ^#auth_type )? ~ ( BY ~ ^#literal_string )? )?
+            ~ ( #auth_option )?
             ~ ( WITH ~ ^#comma_separated_list1(user_option) )?
         },
-        |(_, _, user, opt_auth_option, opt_user_option)| {
+        |(_, _, user, auth_option, opt_user_option)| {
             Statement::AlterUser(AlterUserStmt {
                 user,
-                auth_option: opt_auth_option.map(|(_, opt_auth_type, opt_password)| AuthOption {
-                    auth_type: opt_auth_type.map(|(_, auth_type)| auth_type),
-                    password: opt_password.map(|(_, password)| password),
-                }),
+                auth_option,
                 user_options: opt_user_option
                     .map(|(_, user_options)| user_options)
                     .unwrap_or_default(),
@@ -1051,6 +1103,7 @@ pub fn statement(i: Input) -> IResult<StatementMsg> {
         rule!(
             #set_variable : "`SET <variable> = <value>`"
             | #unset_variable : "`UNSET <variable>`"
+            | #merge_into : "`MERGE INTO <table> USING <source> ON <expr> WHEN [NOT] MATCHED [AND <expr>] THEN ...`"
         ),
         rule!(
             #show_tables : "`SHOW [FULL] TABLES [FROM <database>] [<show_limit>]`"
@@ -1073,6 +1126,10 @@ pub fn statement(i: Input) -> IResult<StatementMsg> {
             | #drop_view : "`DROP VIEW [IF EXISTS] [<database>.]<view>`"
             | #alter_view : "`ALTER VIEW [<database>.]<view> [(<column>, ...)] AS SELECT ...`"
         ),
+        rule!(
+            #create_stream : "`CREATE STREAM [IF NOT EXISTS] [<database>.]<stream> ON TABLE [<database>.]<table> [AT (SNAPSHOT => <snapshot_id> | TIMESTAMP => <timestamp>)]`"
+            | #drop_stream : "`DROP STREAM [IF EXISTS] [<database>.]<stream>`"
+        ),
         rule!(
             #show_users : "`SHOW USERS`"
             | #create_user : "`CREATE USER [IF NOT EXISTS] '<username>'@'hostname' IDENTIFIED [WITH <auth_type>] [BY <password>] [WITH <user_option>, ...]`"
@@ -1467,9 +1524,22 @@ pub fn create_table_source(i: Input) -> IResult<CreateTableSource> {
         },
     );
 
+    let clone = map(
+        rule! {
+            CLONE ~ #period_separated_idents_1_to_3 ~ ( AT ~ ^#travel_point )?
+        },
+        |(_, (catalog, database, table), travel_point)| CreateTableSource::Clone {
+            catalog,
+            database,
+            table,
+            travel_point: travel_point.map(|(_, point)| point),
+        },
+    );
+
     rule!(
         #columns
         | #like
+        | #clone
     )(i)
 }
 
@@ -1754,12 +1824,43 @@ pub fn user_identity(i: Input) -> IResult<UserIdentity> {
     )(i)
 }
 
+pub fn auth_option(i: Input) -> IResult<AuthOption> {
+    let identified = map(
+        rule! {
+            IDENTIFIED ~ ( WITH ~ ^#auth_type )? ~ ( BY ~ ^#literal_string )?
+        },
+        |(_, opt_auth_type, opt_password)| AuthOption {
+            auth_type: opt_auth_type.map(|(_, auth_type)| auth_type),
+            password: opt_password.map(|(_, password)| password),
+        },
+    );
+    // The user is authenticated by the client certificate, the subject is kept as the password.
+    let require_x509 = value(
+        AuthOption {
+            auth_type: Some(AuthType::X509),
+            password: None,
+        },
+        rule! { REQUIRE ~ X509 },
+    );
+    let require_subject = map(
+        rule! {
+            REQUIRE ~ SUBJECT ~ ^#literal_string
+        },
+        |(_, _, subject)| AuthOption {
+            auth_type: Some(AuthType::X509),
+            password: Some(subject),
+        },
+    );
+    alt((identified, require_x509, require_subject))(i)
+}
+
 pub fn auth_type(i: Input) -> IResult<AuthType> {
     alt((
         value(AuthType::NoPassword, rule! { NO_PASSWORD }),
         value(AuthType::Sha256Password, rule! { SHA256_PASSWORD }),
         value(AuthType::DoubleSha1Password, rule! { DOUBLE_SHA1_PASSWORD }),
         value(AuthType::JWT, rule! { JWT }),
+        value(AuthType::X509, rule! { X509 }),
     ))(i)
 }
 
@@ -1851,6 +1952,70 @@ pub fn table_reference_only(i: Input) -> IResult<TableReference> {
     )(i)
 }
 
+pub fn merge_target_table(i: Input) -> IResult<TableReference> {
+    map(
+        consumed(rule! {
+            #period_separated_idents_1_to_3 ~ #table_alias?
+        }),
+        |(span, ((catalog, database, table), alias))| TableReference::Table {
+            span: transform_span(span.0),
+            catalog,
+            database,
+            table,
+            alias,
+            travel_point: None,
+        },
+    )(i)
+}
+
+pub fn merge_clause(i: Input) -> IResult<MergeClause> {
+    let matched = map(
+        rule! {
+            WHEN ~ MATCHED ~ ( AND ~ ^#expr )?
+            ~ ^THEN ~ ^#match_operation
+        },
+        |(_, _, opt_selection, _, operation)| MergeClause::Matched {
+            selection: opt_selection.map(|(_, selection)| selection),
+            operation,
+        },
+    );
+    let not_matched = map(
+        rule! {
+            WHEN ~ NOT ~ ^MATCHED ~ ( AND ~ ^#expr )?
+            ~ ^THEN ~ ^INSERT
+            ~ ( "(" ~ ^#comma_separated_list1(ident) ~ ^")" )?
+            ~ ^VALUES ~ ^"(" ~ ^#comma_separated_list1(expr) ~ ^")"
+        },
+        |(_, _, _, opt_selection, _, _, opt_columns, _, _, values, _)| MergeClause::NotMatched {
+            selection: opt_selection.map(|(_, selection)| selection),
+            columns: opt_columns
+                .map(|(_, columns, _)| columns)
+                .unwrap_or_default(),
+            values,
+        },
+    );
+
+    rule!(
+        #matched
+        | #not_matched
+    )(i)
+}
+
+pub fn match_operation(i: Input) -> IResult<MatchOperation> {
+    let update = map(
+        rule! {
+            UPDATE ~ ^SET ~ ^#comma_separated_list1(update_expr)
+        },
+        |(_, _, update_list)| MatchOperation::Update { update_list },
+    );
+    let delete = value(MatchOperation::Delete, rule! { DELETE });
+
+    rule!(
+        #update
+        | #delete
+    )(i)
+}
+
 pub fn update_expr(i: Input) -> IResult<UpdateExpr> {
     map(rule! { ( #ident ~ "=" ~ ^#expr ) }, |(name, _, expr)| {
         UpdateExpr { name, expr }
@@ -226,8 +226,9 @@ pub enum TokenKind {
     /// A cube root math operator in PostgreSQL
     #[token("||/")]
     PGCubeRoot,
-    /// Placeholder used in prepared stmt
+    /// Placeholder used in prepared stmt, `?` in MySQL and `$1` in PostgreSQL
     #[token("?")]
+    #[regex(r"\$[0-9]+")]
     Placeholder,
 
     // Keywords
@@ -303,6 +304,8 @@ pub enum TokenKind {
     CATALOGS,
     #[token("CENTURY", ignore(ascii_case))]
     CENTURY,
+    #[token("CLONE", ignore(ascii_case))]
+    CLONE,
     #[token("CLUSTER", ignore(ascii_case))]
     CLUSTER,
     #[token("COMMENT", ignore(ascii_case))]
@@ -337,6 +340,10 @@ pub enum TokenKind {
     CROSS,
     #[token("CSV", ignore(ascii_case))]
     CSV,
+    #[token("CUBE", ignore(ascii_case))]
+    CUBE,
+    #[token("CURRENT", ignore(ascii_case))]
+    CURRENT,
     #[token("CURRENT_TIMESTAMP", ignore(ascii_case))]
     CURRENT_TIMESTAMP,
     #[token("DATABASE", ignore(ascii_case))]
@@ -419,6 +426,8 @@ pub enum TokenKind {
     FIELDS,
     #[token("FIELD_DELIMITER", ignore(ascii_case))]
     FIELD_DELIMITER,
+    #[token("FOLLOWING", ignore(ascii_case))]
+    FOLLOWING,
     #[token("NAN_DISPLAY", ignore(ascii_case))]
     NAN_DISPLAY,
     #[token("FILE_FORMAT", ignore(ascii_case))]
@@ -465,6 +474,8 @@ pub enum TokenKind {
     GRAPH,
     #[token("GROUP", ignore(ascii_case))]
     GROUP,
+    #[token("GROUPING", ignore(ascii_case))]
+    GROUPING,
     #[token("GZIP", ignore(ascii_case))]
     GZIP,
     #[token("HAVING", ignore(ascii_case))]
@@ -527,6 +538,8 @@ pub enum TokenKind {
     LOCATION_PREFIX,
     #[token("ROLES", ignore(ascii_case))]
     ROLES,
+    #[token("LATERAL", ignore(ascii_case))]
+    LATERAL,
     #[token("LEADING", ignore(ascii_case))]
     LEADING,
     #[token("LEFT", ignore(ascii_case))]
@@ -545,10 +558,14 @@ pub enum TokenKind {
     MAX_FILE_SIZE,
     #[token("MASTER_KEY", ignore(ascii_case))]
     MASTER_KEY,
+    #[token("MATCHED", ignore(ascii_case))]
+    MATCHED,
     #[token("MEMO", ignore(ascii_case))]
     MEMO,
     #[token("MEMORY", ignore(ascii_case))]
     MEMORY,
+    #[token("MERGE", ignore(ascii_case))]
+    MERGE,
     #[token("METRICS", ignore(ascii_case))]
     METRICS,
     #[token("MICROSECONDS", ignore(ascii_case))]
@@ -595,20 +612,28 @@ pub enum TokenKind {
     ORDER,
     #[token("OUTER", ignore(ascii_case))]
     OUTER,
+    #[token("OVER", ignore(ascii_case))]
+    OVER,
     #[token("ON_ERROR", ignore(ascii_case))]
     ON_ERROR,
     #[token("OVERWRITE", ignore(ascii_case))]
     OVERWRITE,
     #[token("PARQUET", ignore(ascii_case))]
     PARQUET,
+    #[token("PARTITION", ignore(ascii_case))]
+    PARTITION,
     #[token("PATTERN", ignore(ascii_case))]
     PATTERN,
     #[token("PIPELINE", ignore(ascii_case))]
     PIPELINE,
+    #[token("PIVOT", ignore(ascii_case))]
+    PIVOT,
     #[token("PLAINTEXT_PASSWORD", ignore(ascii_case))]
     PLAINTEXT_PASSWORD,
     #[token("POSITION", ignore(ascii_case))]
     POSITION,
+    #[token("PRECEDING", ignore(ascii_case))]
+    PRECEDING,
     #[token("PROCESSLIST", ignore(ascii_case))]
     PROCESSLIST,
     #[token("PURGE", ignore(ascii_case))]
@@ -621,6 +646,8 @@ pub enum TokenKind {
     QUOTE,
     #[token("RAWDEFLATE", ignore(ascii_case))]
     RAWDEFLATE,
+    #[token("RANGE", ignore(ascii_case))]
+    RANGE,
     #[token("RECLUSTER", ignore(ascii_case))]
     RECLUSTER,
     #[token("RECORD_DELIMITER", ignore(ascii_case))]
@@ -631,8 +658,16 @@ pub enum TokenKind {
     REGEXP,
     #[token("RENAME", ignore(ascii_case))]
     RENAME,
+    #[token("REQUIRE", ignore(ascii_case))]
+    REQUIRE,
     #[token("ROW_TAG", ignore(ascii_case))]
     ROW_TAG,
+    #[token("ROW", ignore(ascii_case))]
+    ROW,
+    #[token("ROLLUP", ignore(ascii_case))]
+    ROLLUP,
+    #[token("ROWS", ignore(ascii_case))]
+    ROWS,
     #[token("GRANT", ignore(ascii_case))]
     GRANT,
     #[token("ROLE", ignore(ascii_case))]
@@ -673,6 +708,8 @@ pub enum TokenKind {
     SESSION_TOKEN,
     #[token("SET", ignore(ascii_case))]
     SET,
+    #[token("SETS", ignore(ascii_case))]
+    SETS,
     #[token("UNSET", ignore(ascii_case))]
     UNSET,
     #[token("SETTINGS", ignore(ascii_case))]
@@ -719,8 +756,12 @@ pub enum TokenKind {
     SUPER,
     #[token("STATUS", ignore(ascii_case))]
     STATUS,
+    #[token("STREAM", ignore(ascii_case))]
+    STREAM,
     #[token("STRING", ignore(ascii_case))]
     STRING,
+    #[token("SUBJECT", ignore(ascii_case))]
+    SUBJECT,
     #[token("SUBSTRING", ignore(ascii_case))]
     SUBSTRING,
     #[token("SUBSTR", ignore(ascii_case))]
@@ -785,6 +826,10 @@ pub enum TokenKind {
     UINT8,
     #[token("UNDROP", ignore(ascii_case))]
     UNDROP,
+    #[token("UNBOUNDED", ignore(ascii_case))]
+    UNBOUNDED,
+    #[token("UNPIVOT", ignore(ascii_case))]
+    UNPIVOT,
     #[token("UNSIGNED", ignore(ascii_case))]
     UNSIGNED,
     #[token("URL", ignore(ascii_case))]
@@ -815,6 +860,8 @@ pub enum TokenKind {
     WHERE,
     #[token("WITH", ignore(ascii_case))]
     WITH,
+    #[token("X509", ignore(ascii_case))]
+    X509,
     #[token("XML", ignore(ascii_case))]
     XML,
     #[token("XOR", ignore(ascii_case))]
@@ -1156,6 +1203,9 @@ impl TokenKind {
             // | TokenKind::WINDOW
             | TokenKind::WITH
             | TokenKind::IGNORE_RESULT
+            | TokenKind::PIVOT
+            | TokenKind::UNPIVOT
+            | TokenKind::LATERAL
             if !after_as => true,
             _ => false
         }
@@ -194,6 +194,8 @@ pub trait Visitor<'ast>: Sized {
 
     fn visit_count_all(&mut self, _span: Span) {}
 
+    fn visit_placeholder(&mut self, _span: Span) {}
+
     fn visit_tuple(&mut self, _span: Span, elements: &'ast [Expr]) {
         for element in elements {
             walk_expr(self, element);
@@ -207,10 +209,19 @@ pub trait Visitor<'ast>: Sized {
         _name: &'ast Identifier,
         args: &'ast [Expr],
         _params: &'ast [Literal],
+        window: &'ast Option<Window>,
     ) {
         for arg in args {
             walk_expr(self, arg);
         }
+        if let Some(window) = window {
+            for expr in &window.partition_by {
+                walk_expr(self, expr);
+            }
+            for order_by in &window.order_by {
+                self.visit_order_by(order_by);
+            }
+        }
     }
 
     fn visit_case_when(
@@ -347,6 +358,8 @@ pub trait Visitor<'ast>: Sized {
 
     fn visit_update(&mut self, _update: &'ast UpdateStmt) {}
 
+    fn visit_merge_into(&mut self, _merge_into: &'ast MergeIntoStmt) {}
+
     fn visit_show_catalogs(&mut self, _stmt: &'ast ShowCatalogsStmt) {}
 
     fn visit_show_create_catalog(&mut self, _stmt: &'ast ShowCreateCatalogStmt) {}
@@ -405,6 +418,10 @@ pub trait Visitor<'ast>: Sized {
 
     fn visit_drop_view(&mut self, _stmt: &'ast DropViewStmt) {}
 
+    fn visit_create_stream(&mut self, _stmt: &'ast CreateStreamStmt) {}
+
+    fn visit_drop_stream(&mut self, _stmt: &'ast DropStreamStmt) {}
+
     fn visit_show_users(&mut self) {}
 
     fn visit_create_user(&mut self, _stmt: &'ast CreateUserStmt) {}
@@ -535,8 +552,19 @@ pub trait Visitor<'ast>: Sized {
             walk_expr(self, selection);
         }
 
-        for expr in group_by.iter() {
-            walk_expr(self, expr);
+        if let Some(group_by) = group_by {
+            match group_by {
+                GroupBy::Normal(exprs) | GroupBy::Cube(exprs) | GroupBy::Rollup(exprs) => {
+                    for expr in exprs.iter() {
+                        walk_expr(self, expr);
+                    }
+                }
+                GroupBy::GroupingSets(sets) => {
+                    for expr in sets.iter().flatten() {
+                        walk_expr(self, expr);
+                    }
+                }
+            }
         }
 
         if let Some(having) = having {
@@ -195,6 +195,8 @@ pub trait VisitorMut: Sized {
 
     fn visit_count_all(&mut self, _span: Span) {}
 
+    fn visit_placeholder(&mut self, _span: Span) {}
+
     fn visit_tuple(&mut self, _span: Span, elements: &mut [Expr]) {
         for elem in elements.iter_mut() {
             walk_expr_mut(self, elem);
@@ -208,10 +210,19 @@ pub trait VisitorMut: Sized {
         _name: &mut Identifier,
         args: &mut [Expr],
         _params: &mut [Literal],
+        window: &mut Option<Window>,
     ) {
         for arg in args.iter_mut() {
             walk_expr_mut(self, arg);
         }
+        if let Some(window) = window {
+            for expr in window.partition_by.iter_mut() {
+                walk_expr_mut(self, expr);
+            }
+            for order_by in window.order_by.iter_mut() {
+                self.visit_order_by(order_by);
+            }
+        }
     }
 
     fn visit_case_when(
@@ -353,6 +364,8 @@ pub trait VisitorMut: Sized {
 
     fn visit_update(&mut self, _update: &mut UpdateStmt) {}
 
+    fn visit_merge_into(&mut self, _merge_into: &mut MergeIntoStmt) {}
+
     fn visit_show_catalogs(&mut self, _stmt: &mut ShowCatalogsStmt) {}
 
     fn visit_show_create_catalog(&mut self, _stmt: &mut ShowCreateCatalogStmt) {}
@@ -411,6 +424,10 @@ pub trait VisitorMut: Sized {
 
     fn visit_drop_view(&mut self, _stmt: &mut DropViewStmt) {}
 
+    fn visit_create_stream(&mut self, _stmt: &mut CreateStreamStmt) {}
+
+    fn visit_drop_stream(&mut self, _stmt: &mut DropStreamStmt) {}
+
     fn visit_show_users(&mut self) {}
 
     fn visit_create_user(&mut self, _stmt: &mut CreateUserStmt) {}
@@ -541,8 +558,19 @@ pub trait VisitorMut: Sized {
             walk_expr_mut(self, selection);
         }
 
-        for expr in group_by.iter_mut() {
-            walk_expr_mut(self, expr);
+        if let Some(group_by) = group_by {
+            match group_by {
+                GroupBy::Normal(exprs) | GroupBy::Cube(exprs) | GroupBy::Rollup(exprs) => {
+                    for expr in exprs.iter_mut() {
+                        walk_expr_mut(self, expr);
+                    }
+                }
+                GroupBy::GroupingSets(sets) => {
+                    for expr in sets.iter_mut().flatten() {
+                        walk_expr_mut(self, expr);
+                    }
+                }
+            }
         }
 
         if let Some(having) = having {
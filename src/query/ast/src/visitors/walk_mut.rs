@@ -93,7 +93,8 @@ pub fn walk_expr_mut<V: VisitorMut>(visitor: &mut V, expr: &mut Expr) {
             name,
             args,
             params,
-        } => visitor.visit_function_call(*span, *distinct, name, args, params),
+            window,
+        } => visitor.visit_function_call(*span, *distinct, name, args, params, window),
         Expr::Case {
             span,
             operand,
@@ -137,6 +138,7 @@ pub fn walk_expr_mut<V: VisitorMut>(visitor: &mut V, expr: &mut Expr) {
             unit,
         } => visitor.visit_date_sub(*span, unit, interval, date),
         Expr::DateTrunc { span, unit, date } => visitor.visit_date_trunc(*span, unit, date),
+        Expr::Placeholder { span } => visitor.visit_placeholder(*span),
     }
 }
 
@@ -265,6 +267,38 @@ pub fn walk_table_reference_mut<V: VisitorMut>(visitor: &mut V, table_ref: &mut
         TableReference::Join { join, .. } => {
             visitor.visit_join(join);
         }
+        TableReference::Pivot {
+            table,
+            pivot,
+            alias,
+            ..
+        } => {
+            visitor.visit_table_reference(table);
+            visitor.visit_expr(&mut pivot.aggregate);
+            visitor.visit_identifier(&mut pivot.value_column);
+            for value in pivot.values.iter_mut() {
+                visitor.visit_expr(value);
+            }
+            if let Some(alias) = alias {
+                visitor.visit_identifier(&mut alias.name);
+            }
+        }
+        TableReference::Unpivot {
+            table,
+            unpivot,
+            alias,
+            ..
+        } => {
+            visitor.visit_table_reference(table);
+            visitor.visit_identifier(&mut unpivot.value_column);
+            visitor.visit_identifier(&mut unpivot.name_column);
+            for column in unpivot.columns.iter_mut() {
+                visitor.visit_identifier(column);
+            }
+            if let Some(alias) = alias {
+                visitor.visit_identifier(&mut alias.name);
+            }
+        }
         TableReference::Stage { .. } => {}
     }
 }
@@ -308,6 +342,7 @@ pub fn walk_statement_mut<V: VisitorMut>(visitor: &mut V, statement: &mut Statem
             ..
         } => visitor.visit_delete(table_reference, selection),
         Statement::Update(update) => visitor.visit_update(update),
+        Statement::MergeInto(merge_into) => visitor.visit_merge_into(merge_into),
         Statement::Copy(stmt) => visitor.visit_copy(stmt),
         Statement::ShowSettings { like } => visitor.visit_show_settings(like),
         Statement::ShowProcessList => visitor.visit_show_process_list(),
@@ -355,6 +390,8 @@ pub fn walk_statement_mut<V: VisitorMut>(visitor: &mut V, statement: &mut Statem
         Statement::CreateView(stmt) => visitor.visit_create_view(stmt),
         Statement::AlterView(stmt) => visitor.visit_alter_view(stmt),
         Statement::DropView(stmt) => visitor.visit_drop_view(stmt),
+        Statement::CreateStream(stmt) => visitor.visit_create_stream(stmt),
+        Statement::DropStream(stmt) => visitor.visit_drop_stream(stmt),
         Statement::ShowUsers => visitor.visit_show_users(),
         Statement::ShowRoles => visitor.visit_show_roles(),
         Statement::CreateUser(stmt) => visitor.visit_create_user(stmt),
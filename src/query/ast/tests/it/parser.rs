@@ -81,6 +81,7 @@ fn test_statement() {
         r#"create table if not exists a.b (c integer default 1 not null, b varchar) as select * from t;"#,
         r#"create table if not exists a.b (c tuple(m integer, n string), d tuple(integer, string));"#,
         r#"create table a.b like c.d;"#,
+        r#"create table a.b clone c.d;"#,
         r#"create table t like t2 engine = memory;"#,
         r#"create table if not exists a.b (a int) 's3://testbucket/admin/data/' connection=(aws_key_id='minioadmin' aws_secret_key='minioadmin' endpoint_url='http://127.0.0.1:9900');"#,
         r#"create table if not exists a.b (a int) 's3://testbucket/admin/data/'
@@ -104,6 +105,8 @@ fn test_statement() {
         r#"drop view v;"#,
         r#"create view v1(c1) as select number % 3 as a from numbers(1000);"#,
         r#"alter view v1(c2) as select number % 3 as a from numbers(1000);"#,
+        r#"create stream if not exists s on table d.t;"#,
+        r#"drop stream if exists d.s;"#,
         r#"rename table d.t to e.s;"#,
         r#"truncate table test;"#,
         r#"truncate table test_db.test;"#,
@@ -116,6 +119,8 @@ fn test_statement() {
         r#"ALTER USER u1 WITH DEFAULT_ROLE = 'role1';"#,
         r#"ALTER USER u1 WITH DEFAULT_ROLE = 'role1', TENANTSETTING;"#,
         r#"CREATE USER u1 IDENTIFIED BY '123456' WITH DEFAULT_ROLE='role123', TENANTSETTING"#,
+        r#"CREATE USER u1 REQUIRE X509;"#,
+        r#"ALTER USER u1 REQUIRE SUBJECT 'C=US, CN=u1';"#,
         r#"DROP database if exists db1;"#,
         r#"select distinct a, count(*) from t where a = 1 and b - 1 < a group by a having a = 1;"#,
         r#"select * from t4;"#,
@@ -353,6 +358,7 @@ fn test_statement() {
         r#"SHOW GRANTS ON DATABASE db;"#,
         r#"SHOW GRANTS OF SHARE t;"#,
         r#"UPDATE db1.tb1 set a = a + 1, b = 2 WHERE c > 3;"#,
+        r#"MERGE INTO t USING s ON t.a = s.a WHEN MATCHED THEN UPDATE SET b = s.b WHEN NOT MATCHED THEN INSERT VALUES (s.a, s.b);"#,
         r#"SET max_threads = 10;"#,
         r#"SET max_threads = 10*2;"#,
         r#"UNSET max_threads;"#,
@@ -466,6 +472,13 @@ fn test_query() {
         r#"select * from t1 union select * from t2 intersect select * from t3"#,
         r#"(select * from t1 union select * from t2) union select * from t3"#,
         r#"select * from t1 union (select * from t2 union select * from t3)"#,
+        r#"select * from t group by grouping sets((a, b), (c), ())"#,
+        r#"select * from t group by rollup(a, b)"#,
+        r#"select * from t group by cube(a, b)"#,
+        r#"select * from t pivot(sum(a) for b in ('x', 'y'))"#,
+        r#"select * from t unpivot(v for k in (a, b)) as u"#,
+        r#"select * from t, lateral flatten(input => t.v) as f"#,
+        r#"select * from t, lateral (select t.a) as s"#,
     ];
 
     for case in cases {
@@ -565,6 +578,10 @@ fn test_expr() {
         r#"1 is distinct from 2"#,
         r#"a is distinct from b"#,
         r#"1 is not distinct from null"#,
+        r#"sum(a) over (partition by b order by c desc rows between 1 preceding and current row)"#,
+        r#"count(*) over ()"#,
+        r#"a = ?"#,
+        r#"a = $1"#,
     ];
 
     for case in cases {
@@ -0,0 +1,92 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_expression::DataBlock;
+use common_expression::FieldIndex;
+use common_expression::RemoteExpr;
+
+/// Information needed by a table engine to execute `MERGE INTO`.
+///
+/// The expressions of `join_filter` and of the matched clauses are evaluated on
+/// the joined block, whose columns are the fields of the target table in schema
+/// order, followed by the columns of the source block. The expressions of
+/// `target_keys` only refer to the target fields, so they can also be evaluated
+/// on the target block. The expressions of `source_keys` and of the not matched
+/// clauses are evaluated on the source block.
+#[derive(Clone, Debug)]
+pub struct MergeIntoInfo {
+    /// Target expressions of the equi join keys.
+    pub target_keys: Vec<RemoteExpr>,
+    /// Source expressions of the equi join keys, of the same type as the
+    /// corresponding target expressions.
+    pub source_keys: Vec<RemoteExpr>,
+    /// The non-equi part of the join condition.
+    pub join_filter: Option<RemoteExpr>,
+    pub matched: Vec<MergeMatchedClause>,
+    pub not_matched: Vec<MergeNotMatchedClause>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MergeMatchedClause {
+    pub condition: Option<RemoteExpr>,
+    pub operation: MergeMatchedOperation,
+}
+
+#[derive(Clone, Debug)]
+pub enum MergeMatchedOperation {
+    /// Target fields to update, with the new values cast to the field types.
+    Update(Vec<(FieldIndex, RemoteExpr)>),
+    Delete,
+}
+
+#[derive(Clone, Debug)]
+pub struct MergeNotMatchedClause {
+    pub condition: Option<RemoteExpr>,
+    /// One value per target table field, cast to the field type.
+    pub values: Vec<RemoteExpr>,
+}
+
+/// The rows of the source of `MERGE INTO`.
+///
+/// The blocks are kept in memory up to the setting `max_merge_into_source_bytes`,
+/// the rest are spilled to the storage and read back by `spill_reader` when they
+/// are joined with the target blocks.
+#[derive(Clone)]
+pub struct MergeIntoSource {
+    pub blocks: Vec<MergeIntoSourceBlock>,
+    pub spill_reader: Option<Arc<dyn SpilledBlockReader>>,
+}
+
+#[derive(Clone)]
+pub enum MergeIntoSourceBlock {
+    Memory(DataBlock),
+    Spilled { location: String, num_rows: usize },
+}
+
+impl MergeIntoSourceBlock {
+    pub fn num_rows(&self) -> usize {
+        match self {
+            MergeIntoSourceBlock::Memory(block) => block.num_rows(),
+            MergeIntoSourceBlock::Spilled { num_rows, .. } => *num_rows,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait SpilledBlockReader: Send + Sync {
+    async fn read_spilled(&self, location: &str) -> Result<DataBlock>;
+}
@@ -37,6 +37,8 @@ use common_storage::StorageMetrics;
 
 use crate::plan::DataSourceInfo;
 use crate::plan::DataSourcePlan;
+use crate::plan::MergeIntoInfo;
+use crate::plan::MergeIntoSource;
 use crate::plan::PartStatistics;
 use crate::plan::Partitions;
 use crate::plan::PushDownInfo;
@@ -260,6 +262,22 @@ pub trait Table: Sync + Send {
         )))
     }
 
+    async fn merge_into(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        merge_info: MergeIntoInfo,
+        source: MergeIntoSource,
+        pipeline: &mut Pipeline,
+    ) -> Result<()> {
+        let (_, _, _, _) = (ctx, merge_info, source, pipeline);
+
+        Err(ErrorCode::Unimplemented(format!(
+            "table {},  of engine type {}, does not support MERGE INTO",
+            self.name(),
+            self.get_table_info().engine(),
+        )))
+    }
+
     fn get_block_compact_thresholds(&self) -> BlockThresholds {
         BlockThresholds {
             max_rows_per_block: DEFAULT_BLOCK_MAX_ROWS,
@@ -316,6 +334,21 @@ pub trait Table: Sync + Send {
             self.get_table_info().engine(),
         )))
     }
+
+    /// Make this (empty) table share the data of `source`, which is the table
+    /// to be cloned, possibly navigated to a historical point.
+    async fn clone_from_table(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        source: Arc<dyn Table>,
+    ) -> Result<()> {
+        let (_, _) = (ctx, source);
+        Err(ErrorCode::Unimplemented(format!(
+            "table {},  of engine type {}, does not support clone",
+            self.name(),
+            self.get_table_info().engine(),
+        )))
+    }
 }
 
 #[async_trait::async_trait]
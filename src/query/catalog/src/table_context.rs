@@ -66,6 +66,21 @@ pub struct StageAttachment {
     pub values_str: String,
 }
 
+/// The parameters bound to the placeholders of the query, by position for `?` and `$<n>`,
+/// or by name for `:<name>`.
+#[derive(Debug, Clone)]
+pub enum QueryParams {
+    Positional(Vec<QueryParam>),
+    Named(BTreeMap<String, QueryParam>),
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryParam {
+    pub value: serde_json::Value,
+    /// The SQL type of the value, e.g. `DATE`, it's inferred from the json value if not given.
+    pub data_type: Option<String>,
+}
+
 #[async_trait::async_trait]
 pub trait TableContext: Send + Sync {
     /// Build a table instance the plan wants to operate on.
@@ -109,6 +124,7 @@ pub trait TableContext: Send + Sync {
     fn get_cluster(&self) -> Arc<Cluster>;
     fn get_processes_info(&self) -> Vec<ProcessInfo>;
     fn get_stage_attachment(&self) -> Option<StageAttachment>;
+    fn get_query_params(&self) -> Option<QueryParams>;
     fn set_on_error_map(&self, map: Option<HashMap<String, ErrorCode>>);
 
     fn apply_changed_settings(&self, changed_settings: Arc<Settings>) -> Result<()>;
@@ -1048,6 +1048,28 @@ pub struct QueryConfig {
     #[clap(long, default_value = "120")]
     pub mysql_handler_tcp_keepalive_timeout_secs: u64,
 
+    #[clap(long, default_value_t)]
+    pub mysql_handler_tls_server_cert: String,
+
+    #[clap(long, default_value_t)]
+    pub mysql_handler_tls_server_key: String,
+
+    /// The CA to verify the client certificates, required by the users of `REQUIRE X509`.
+    #[clap(long, default_value_t)]
+    pub mysql_handler_tls_server_root_ca_cert: String,
+
+    #[clap(long, default_value = "127.0.0.1")]
+    pub postgres_handler_host: String,
+
+    #[clap(long, default_value = "5433")]
+    pub postgres_handler_port: u16,
+
+    #[clap(long, default_value = "127.0.0.1")]
+    pub flight_sql_handler_host: String,
+
+    #[clap(long, default_value = "8900")]
+    pub flight_sql_handler_port: u16,
+
     #[clap(long, default_value = "256")]
     pub max_active_sessions: u64,
 
@@ -1058,11 +1080,9 @@ pub struct QueryConfig {
     #[clap(long, parse(try_from_str), default_value = "false")]
     pub max_memory_limit_enabled: bool,
 
-    #[deprecated(note = "clickhouse tcp support is deprecated")]
     #[clap(long, default_value = "127.0.0.1")]
     pub clickhouse_handler_host: String,
 
-    #[deprecated(note = "clickhouse tcp support is deprecated")]
     #[clap(long, default_value = "9000")]
     pub clickhouse_handler_port: u16,
 
@@ -1157,6 +1177,43 @@ pub struct QueryConfig {
     #[clap(long, default_value = "0")]
     pub async_insert_stale_timeout: u64,
 
+    /// Compact and recluster the Fuse tables in the background.
+    #[clap(long)]
+    pub auto_compaction_enabled: bool,
+
+    /// The interval in seconds between two rounds of the background compaction.
+    #[clap(long, default_value = "600")]
+    pub auto_compaction_interval_secs: u64,
+
+    /// The maximum number of tables compacted or reclustered in one round.
+    #[clap(long, default_value = "4")]
+    pub auto_compaction_max_tables_per_round: u64,
+
+    /// The maximum number of threads used by the background compaction.
+    #[clap(long, default_value = "2")]
+    pub auto_compaction_max_threads: u64,
+
+    /// Compact the segments of a table once it has this many more segments than needed.
+    #[clap(long, default_value = "100")]
+    pub auto_compaction_segment_threshold: u64,
+
+    /// Compact the blocks of a table once it has this many undersized blocks.
+    #[clap(long, default_value = "100")]
+    pub auto_compaction_block_threshold: u64,
+
+    /// Recluster a table once the average depth on its cluster key exceeds this value.
+    #[clap(long, default_value = "4")]
+    pub auto_recluster_depth_threshold: u64,
+
+    /// Purge the snapshots beyond the retention period and the dropped tables in the background,
+    /// only the tables with the option `data_retention_time` are purged.
+    #[clap(long)]
+    pub auto_purge_enabled: bool,
+
+    /// The interval in seconds between two rounds of the background purge.
+    #[clap(long, default_value = "3600")]
+    pub auto_purge_interval_secs: u64,
+
     #[clap(long, default_value = "auto")]
     pub default_storage_format: String,
 
@@ -1252,9 +1309,18 @@ impl TryInto<InnerQueryConfig> for QueryConfig {
             mysql_handler_host: self.mysql_handler_host,
             mysql_handler_port: self.mysql_handler_port,
             mysql_handler_tcp_keepalive_timeout_secs: self.mysql_handler_tcp_keepalive_timeout_secs,
+            mysql_handler_tls_server_cert: self.mysql_handler_tls_server_cert,
+            mysql_handler_tls_server_key: self.mysql_handler_tls_server_key,
+            mysql_handler_tls_server_root_ca_cert: self.mysql_handler_tls_server_root_ca_cert,
+            postgres_handler_host: self.postgres_handler_host,
+            postgres_handler_port: self.postgres_handler_port,
+            flight_sql_handler_host: self.flight_sql_handler_host,
+            flight_sql_handler_port: self.flight_sql_handler_port,
             max_active_sessions: self.max_active_sessions,
             max_server_memory_usage: self.max_server_memory_usage,
             max_memory_limit_enabled: self.max_memory_limit_enabled,
+            clickhouse_handler_host: self.clickhouse_handler_host,
+            clickhouse_handler_port: self.clickhouse_handler_port,
             clickhouse_http_handler_host: self.clickhouse_http_handler_host,
             clickhouse_http_handler_port: self.clickhouse_http_handler_port,
             http_handler_host: self.http_handler_host,
@@ -1282,6 +1348,15 @@ impl TryInto<InnerQueryConfig> for QueryConfig {
             async_insert_max_data_size: self.async_insert_max_data_size,
             async_insert_busy_timeout: self.async_insert_busy_timeout,
             async_insert_stale_timeout: self.async_insert_stale_timeout,
+            auto_compaction_enabled: self.auto_compaction_enabled,
+            auto_compaction_interval_secs: self.auto_compaction_interval_secs,
+            auto_compaction_max_tables_per_round: self.auto_compaction_max_tables_per_round,
+            auto_compaction_max_threads: self.auto_compaction_max_threads,
+            auto_compaction_segment_threshold: self.auto_compaction_segment_threshold,
+            auto_compaction_block_threshold: self.auto_compaction_block_threshold,
+            auto_recluster_depth_threshold: self.auto_recluster_depth_threshold,
+            auto_purge_enabled: self.auto_purge_enabled,
+            auto_purge_interval_secs: self.auto_purge_interval_secs,
             default_storage_format: self.default_storage_format,
             default_compression: self.default_compression,
             idm: InnerIDMConfig {
@@ -1296,7 +1371,6 @@ impl TryInto<InnerQueryConfig> for QueryConfig {
     }
 }
 
-#[allow(deprecated)]
 impl From<InnerQueryConfig> for QueryConfig {
     fn from(inner: InnerQueryConfig) -> Self {
         Self {
@@ -1307,14 +1381,18 @@ impl From<InnerQueryConfig> for QueryConfig {
             mysql_handler_port: inner.mysql_handler_port,
             mysql_handler_tcp_keepalive_timeout_secs: inner
                 .mysql_handler_tcp_keepalive_timeout_secs,
+            mysql_handler_tls_server_cert: inner.mysql_handler_tls_server_cert,
+            mysql_handler_tls_server_key: inner.mysql_handler_tls_server_key,
+            mysql_handler_tls_server_root_ca_cert: inner.mysql_handler_tls_server_root_ca_cert,
+            postgres_handler_host: inner.postgres_handler_host,
+            postgres_handler_port: inner.postgres_handler_port,
+            flight_sql_handler_host: inner.flight_sql_handler_host,
+            flight_sql_handler_port: inner.flight_sql_handler_port,
             max_active_sessions: inner.max_active_sessions,
             max_server_memory_usage: inner.max_server_memory_usage,
             max_memory_limit_enabled: inner.max_memory_limit_enabled,
-
-            // clickhouse tcp is deprecated
-            clickhouse_handler_host: "127.0.0.1".to_string(),
-            clickhouse_handler_port: 9000,
-
+            clickhouse_handler_host: inner.clickhouse_handler_host,
+            clickhouse_handler_port: inner.clickhouse_handler_port,
             clickhouse_http_handler_host: inner.clickhouse_http_handler_host,
             clickhouse_http_handler_port: inner.clickhouse_http_handler_port,
             http_handler_host: inner.http_handler_host,
@@ -1342,6 +1420,15 @@ impl From<InnerQueryConfig> for QueryConfig {
             async_insert_max_data_size: inner.async_insert_max_data_size,
             async_insert_busy_timeout: inner.async_insert_busy_timeout,
             async_insert_stale_timeout: inner.async_insert_stale_timeout,
+            auto_compaction_enabled: inner.auto_compaction_enabled,
+            auto_compaction_interval_secs: inner.auto_compaction_interval_secs,
+            auto_compaction_max_tables_per_round: inner.auto_compaction_max_tables_per_round,
+            auto_compaction_max_threads: inner.auto_compaction_max_threads,
+            auto_compaction_segment_threshold: inner.auto_compaction_segment_threshold,
+            auto_compaction_block_threshold: inner.auto_compaction_block_threshold,
+            auto_recluster_depth_threshold: inner.auto_recluster_depth_threshold,
+            auto_purge_enabled: inner.auto_purge_enabled,
+            auto_purge_interval_secs: inner.auto_purge_interval_secs,
             default_storage_format: inner.default_storage_format,
             default_compression: inner.default_compression,
 
@@ -1682,6 +1769,10 @@ impl TryInto<AuthInfo> for UserAuthConfig {
         match auth_type {
             AuthType::NoPassword => check_no_auth_string(self.auth_string, AuthInfo::None),
             AuthType::JWT => check_no_auth_string(self.auth_string, AuthInfo::JWT),
+            // the auth_string is the subject of the client certificate, not hex encoded.
+            AuthType::X509 => Ok(AuthInfo::X509 {
+                subject: self.auth_string.filter(|s| !s.is_empty()),
+            }),
             AuthType::Sha256Password | AuthType::DoubleSha1Password => {
                 let password_type = auth_type.get_password_type().expect("must success");
                 match self.auth_string {
@@ -1704,11 +1795,16 @@ impl TryInto<AuthInfo> for UserAuthConfig {
 impl From<AuthInfo> for UserAuthConfig {
     fn from(inner: AuthInfo) -> Self {
         let auth_type = inner.get_type().to_str().to_owned();
-        let auth_string = inner.get_auth_string();
-        let auth_string = if auth_string.is_empty() {
-            None
-        } else {
-            Some(hex::encode(auth_string))
+        let auth_string = match inner {
+            AuthInfo::X509 { subject } => subject,
+            _ => {
+                let auth_string = inner.get_auth_string();
+                if auth_string.is_empty() {
+                    None
+                } else {
+                    Some(hex::encode(auth_string))
+                }
+            }
         };
         UserAuthConfig {
             auth_type,
@@ -123,9 +123,18 @@ pub struct QueryConfig {
     pub mysql_handler_host: String,
     pub mysql_handler_port: u16,
     pub mysql_handler_tcp_keepalive_timeout_secs: u64,
+    pub mysql_handler_tls_server_cert: String,
+    pub mysql_handler_tls_server_key: String,
+    pub mysql_handler_tls_server_root_ca_cert: String,
+    pub postgres_handler_host: String,
+    pub postgres_handler_port: u16,
+    pub flight_sql_handler_host: String,
+    pub flight_sql_handler_port: u16,
     pub max_active_sessions: u64,
     pub max_server_memory_usage: u64,
     pub max_memory_limit_enabled: bool,
+    pub clickhouse_handler_host: String,
+    pub clickhouse_handler_port: u16,
     pub clickhouse_http_handler_host: String,
     pub clickhouse_http_handler_port: u16,
     pub http_handler_host: String,
@@ -158,6 +167,17 @@ pub struct QueryConfig {
     pub async_insert_max_data_size: u64,
     pub async_insert_busy_timeout: u64,
     pub async_insert_stale_timeout: u64,
+    /// Compact and recluster the Fuse tables in the background, within the limits below.
+    pub auto_compaction_enabled: bool,
+    pub auto_compaction_interval_secs: u64,
+    pub auto_compaction_max_tables_per_round: u64,
+    pub auto_compaction_max_threads: u64,
+    pub auto_compaction_segment_threshold: u64,
+    pub auto_compaction_block_threshold: u64,
+    pub auto_recluster_depth_threshold: u64,
+    /// Purge the tables with the option `data_retention_time` in the background, dropped or not.
+    pub auto_purge_enabled: bool,
+    pub auto_purge_interval_secs: u64,
     pub default_storage_format: String,
     pub default_compression: String,
     pub idm: IDMConfig,
@@ -177,9 +197,18 @@ impl Default for QueryConfig {
             mysql_handler_host: "127.0.0.1".to_string(),
             mysql_handler_port: 3307,
             mysql_handler_tcp_keepalive_timeout_secs: 120,
+            mysql_handler_tls_server_cert: "".to_string(),
+            mysql_handler_tls_server_key: "".to_string(),
+            mysql_handler_tls_server_root_ca_cert: "".to_string(),
+            postgres_handler_host: "127.0.0.1".to_string(),
+            postgres_handler_port: 5433,
+            flight_sql_handler_host: "127.0.0.1".to_string(),
+            flight_sql_handler_port: 8900,
             max_active_sessions: 256,
             max_server_memory_usage: 0,
             max_memory_limit_enabled: false,
+            clickhouse_handler_host: "127.0.0.1".to_string(),
+            clickhouse_handler_port: 9000,
             clickhouse_http_handler_host: "127.0.0.1".to_string(),
             clickhouse_http_handler_port: 8124,
             http_handler_host: "127.0.0.1".to_string(),
@@ -207,6 +236,15 @@ impl Default for QueryConfig {
             async_insert_max_data_size: 10000,
             async_insert_busy_timeout: 200,
             async_insert_stale_timeout: 0,
+            auto_compaction_enabled: false,
+            auto_compaction_interval_secs: 600,
+            auto_compaction_max_tables_per_round: 4,
+            auto_compaction_max_threads: 2,
+            auto_compaction_segment_threshold: 100,
+            auto_compaction_block_threshold: 100,
+            auto_recluster_depth_threshold: 4,
+            auto_purge_enabled: false,
+            auto_purge_interval_secs: 3600,
             default_storage_format: "auto".to_string(),
             default_compression: "auto".to_string(),
             idm: IDMConfig::default(),
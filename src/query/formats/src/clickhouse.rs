@@ -16,6 +16,10 @@ use std::str::FromStr;
 
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_expression::types::decimal::DecimalDataType;
+use common_expression::types::decimal::DecimalSize;
+use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
 use common_meta_app::principal::StageFileFormatType;
 
 const SUFFIX_WITH_NAMES_AND_TYPES: &str = "withnamesandtypes";
@@ -93,3 +97,104 @@ impl ClickhouseFormatType {
         })
     }
 }
+
+/// The name of the ClickHouse type which the data type is sent as in the native protocol.
+///
+/// The types without a counterpart in ClickHouse, like the nested types and variant, are sent
+/// as `String` in the text format.
+pub fn clickhouse_type_name(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Null => "Nullable(Nothing)".to_string(),
+        DataType::Nullable(inner) => format!("Nullable({})", clickhouse_type_name(inner)),
+        DataType::Boolean => "Bool".to_string(),
+        DataType::Number(num_ty) => match num_ty {
+            NumberDataType::UInt8 => "UInt8",
+            NumberDataType::UInt16 => "UInt16",
+            NumberDataType::UInt32 => "UInt32",
+            NumberDataType::UInt64 => "UInt64",
+            NumberDataType::Int8 => "Int8",
+            NumberDataType::Int16 => "Int16",
+            NumberDataType::Int32 => "Int32",
+            NumberDataType::Int64 => "Int64",
+            NumberDataType::Float32 => "Float32",
+            NumberDataType::Float64 => "Float64",
+        }
+        .to_string(),
+        DataType::Decimal(DecimalDataType::Decimal128(size))
+        | DataType::Decimal(DecimalDataType::Decimal256(size)) => {
+            format!("Decimal({}, {})", size.precision, size.scale)
+        }
+        // Both are stored in the same way, the days and the microseconds since the epoch.
+        DataType::Date => "Date32".to_string(),
+        DataType::Timestamp => "DateTime64(6)".to_string(),
+        _ => "String".to_string(),
+    }
+}
+
+/// Parse the name of the ClickHouse type returned by `clickhouse_type_name`.
+pub fn parse_clickhouse_type_name(name: &str) -> Result<DataType> {
+    let name = name.trim();
+    if let Some(inner) = strip_type_args(name, "Nullable") {
+        return match inner.trim() {
+            "Nothing" => Ok(DataType::Null),
+            inner => Ok(parse_clickhouse_type_name(inner)?.wrap_nullable()),
+        };
+    }
+    if let Some(args) = strip_type_args(name, "Decimal") {
+        let args = args
+            .split(',')
+            .map(|arg| arg.trim().parse::<u8>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| ErrorCode::BadDataValueType(format!("Invalid type {}", name)))?;
+        let size = match args[..] {
+            [precision, scale] => DecimalSize { precision, scale },
+            _ => {
+                return Err(ErrorCode::BadDataValueType(format!(
+                    "Invalid type {}",
+                    name
+                )));
+            }
+        };
+        return Ok(DataType::Decimal(if size.precision <= 38 {
+            DecimalDataType::Decimal128(size)
+        } else {
+            DecimalDataType::Decimal256(size)
+        }));
+    }
+    // The timezone is not a part of the data, it is ignored.
+    if let Some(args) = strip_type_args(name, "DateTime64") {
+        if args.split(',').next().map(str::trim) == Some("6") {
+            return Ok(DataType::Timestamp);
+        }
+    }
+
+    let data_type = match name {
+        "Bool" => DataType::Boolean,
+        "UInt8" => DataType::Number(NumberDataType::UInt8),
+        "UInt16" => DataType::Number(NumberDataType::UInt16),
+        "UInt32" => DataType::Number(NumberDataType::UInt32),
+        "UInt64" => DataType::Number(NumberDataType::UInt64),
+        "Int8" => DataType::Number(NumberDataType::Int8),
+        "Int16" => DataType::Number(NumberDataType::Int16),
+        "Int32" => DataType::Number(NumberDataType::Int32),
+        "Int64" => DataType::Number(NumberDataType::Int64),
+        "Float32" => DataType::Number(NumberDataType::Float32),
+        "Float64" => DataType::Number(NumberDataType::Float64),
+        "String" => DataType::String,
+        "Date32" => DataType::Date,
+        _ => {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Unsupported ClickHouse type {}",
+                name
+            )));
+        }
+    };
+    Ok(data_type)
+}
+
+// Returns the arguments of the type like `Name(args)`.
+fn strip_type_args<'a>(name: &'a str, type_name: &str) -> Option<&'a str> {
+    name.strip_prefix(type_name)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
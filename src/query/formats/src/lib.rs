@@ -24,6 +24,8 @@ mod file_format_type;
 mod format_option_checker;
 pub mod output_format;
 
+pub use clickhouse::clickhouse_type_name;
+pub use clickhouse::parse_clickhouse_type_name;
 pub use clickhouse::ClickhouseFormatType;
 pub use delimiter::RecordDelimiter;
 pub use field_decoder::*;
@@ -0,0 +1,67 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_arrow::arrow::chunk::Chunk as ArrowChunk;
+use common_arrow::arrow::io::ipc::write::StreamWriter;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_arrow::ArrayRef;
+use common_exception::Result;
+use common_expression::DataBlock;
+use common_expression::TableSchemaRef;
+use common_io::constants::DEFAULT_BLOCK_BUFFER_SIZE;
+
+use crate::output_format::OutputFormat;
+
+/// Write the blocks in the Arrow IPC streaming format, the output of `finalize` is a
+/// complete stream with the schema message first.
+pub struct ArrowIpcOutputFormat {
+    schema: TableSchemaRef,
+    data_blocks: Vec<DataBlock>,
+}
+
+impl ArrowIpcOutputFormat {
+    pub fn create(schema: TableSchemaRef) -> Self {
+        Self {
+            schema,
+            data_blocks: vec![],
+        }
+    }
+}
+
+impl OutputFormat for ArrowIpcOutputFormat {
+    fn serialize_block(&mut self, block: &DataBlock) -> Result<Vec<u8>> {
+        self.data_blocks.push(block.clone());
+        Ok(vec![])
+    }
+
+    fn buffer_size(&mut self) -> usize {
+        self.data_blocks.iter().map(|b| b.memory_size()).sum()
+    }
+
+    fn finalize(&mut self) -> Result<Vec<u8>> {
+        let blocks = std::mem::take(&mut self.data_blocks);
+        if blocks.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut buf = Vec::with_capacity(DEFAULT_BLOCK_BUFFER_SIZE);
+        let mut writer = StreamWriter::new(&mut buf, WriteOptions { compression: None });
+        writer.start(&self.schema.to_arrow(), None)?;
+        for block in blocks {
+            let chunk = ArrowChunk::<ArrayRef>::try_from(block)?;
+            writer.write(&chunk, None)?;
+        }
+        writer.finish()?;
+        Ok(buf)
+    }
+}
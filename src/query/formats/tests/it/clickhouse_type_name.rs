@@ -0,0 +1,71 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+use common_expression::types::decimal::DecimalDataType;
+use common_expression::types::decimal::DecimalSize;
+use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
+use common_formats::clickhouse_type_name;
+use common_formats::parse_clickhouse_type_name;
+
+#[test]
+fn test_clickhouse_type_name() -> Result<()> {
+    let decimal = |precision, scale| {
+        let size = DecimalSize { precision, scale };
+        if precision <= 38 {
+            DataType::Decimal(DecimalDataType::Decimal128(size))
+        } else {
+            DataType::Decimal(DecimalDataType::Decimal256(size))
+        }
+    };
+    let cases = vec![
+        (DataType::Null, "Nullable(Nothing)"),
+        (DataType::Boolean, "Bool"),
+        (DataType::Number(NumberDataType::UInt8), "UInt8"),
+        (DataType::Number(NumberDataType::Int64), "Int64"),
+        (DataType::Number(NumberDataType::Float64), "Float64"),
+        (decimal(10, 2), "Decimal(10, 2)"),
+        (decimal(50, 10), "Decimal(50, 10)"),
+        (DataType::String, "String"),
+        (DataType::Date, "Date32"),
+        (DataType::Timestamp, "DateTime64(6)"),
+        (
+            DataType::Nullable(Box::new(DataType::Number(NumberDataType::Int32))),
+            "Nullable(Int32)",
+        ),
+    ];
+    for (data_type, name) in cases {
+        assert_eq!(clickhouse_type_name(&data_type), name);
+        assert_eq!(parse_clickhouse_type_name(name)?, data_type, "{}", name);
+    }
+
+    // The types without a counterpart are sent as strings.
+    let array = DataType::Array(Box::new(DataType::Number(NumberDataType::Int32)));
+    assert_eq!(clickhouse_type_name(&array), "String");
+    assert_eq!(clickhouse_type_name(&DataType::Variant), "String");
+    assert_eq!(
+        clickhouse_type_name(&DataType::Nullable(Box::new(DataType::Variant))),
+        "Nullable(String)"
+    );
+
+    // The timezone of DateTime64 is ignored.
+    assert_eq!(
+        parse_clickhouse_type_name("DateTime64(6, 'Asia/Shanghai')")?,
+        DataType::Timestamp
+    );
+    assert!(parse_clickhouse_type_name("DateTime64(3)").is_err());
+    assert!(parse_clickhouse_type_name("LowCardinality(String)").is_err());
+    Ok(())
+}
@@ -19,6 +19,7 @@ use common_formats::ClickhouseFormatType;
 use common_formats::FileFormatOptionsExt;
 use common_settings::Settings;
 
+mod clickhouse_type_name;
 mod field_encoder;
 mod format_option_checker;
 mod output_format_json_each_row;
@@ -0,0 +1,30 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_exception::Result;
+use common_meta_app::principal::AsyncQueryInfo;
+
+#[async_trait::async_trait]
+pub trait AsyncQueryApi: Sync + Send {
+    // Save the query info by query id to /tenant/query_id, it expires after the ttl.
+    async fn upsert_query(&self, info: AsyncQueryInfo, ttl: Duration) -> Result<u64>;
+
+    // Get the query info by query id, None if it does not exist or has expired.
+    async fn get_query(&self, query_id: &str) -> Result<Option<AsyncQueryInfo>>;
+
+    // Drop the query info by query id.
+    async fn drop_query(&self, query_id: &str) -> Result<()>;
+}
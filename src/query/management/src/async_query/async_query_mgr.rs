@@ -0,0 +1,110 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use common_base::base::escape_for_key;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_app::principal::AsyncQueryInfo;
+use common_meta_kvapi::kvapi;
+use common_meta_kvapi::kvapi::UpsertKVReq;
+use common_meta_types::KVMeta;
+use common_meta_types::MatchSeq;
+use common_meta_types::MetaError;
+use common_meta_types::Operation;
+
+use crate::async_query::AsyncQueryApi;
+
+static ASYNC_QUERY_API_KEY_PREFIX: &str = "__fd_async_queries";
+
+pub struct AsyncQueryMgr {
+    kv_api: Arc<dyn kvapi::KVApi<Error = MetaError>>,
+    query_prefix: String,
+}
+
+impl AsyncQueryMgr {
+    pub fn create(kv_api: Arc<dyn kvapi::KVApi<Error = MetaError>>, tenant: &str) -> Result<Self> {
+        if tenant.is_empty() {
+            return Err(ErrorCode::TenantIsEmpty(
+                "Tenant can not empty(while async query mgr create)",
+            ));
+        }
+
+        Ok(AsyncQueryMgr {
+            kv_api,
+            query_prefix: format!("{}/{}", ASYNC_QUERY_API_KEY_PREFIX, escape_for_key(tenant)?),
+        })
+    }
+
+    fn query_key(&self, query_id: &str) -> Result<String> {
+        Ok(format!(
+            "{}/{}",
+            self.query_prefix,
+            escape_for_key(query_id)?
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncQueryApi for AsyncQueryMgr {
+    async fn upsert_query(&self, info: AsyncQueryInfo, ttl: Duration) -> Result<u64> {
+        let key = self.query_key(&info.query_id)?;
+        let val = Operation::Update(serde_json::to_vec(&info)?);
+        let expire_at = std::time::SystemTime::now()
+            .add(ttl)
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards");
+        let meta = KVMeta {
+            expire_at: Some(expire_at.as_secs()),
+        };
+
+        let res = self
+            .kv_api
+            .upsert_kv(UpsertKVReq::new(&key, MatchSeq::GE(0), val, Some(meta)))
+            .await?;
+
+        match res.result {
+            Some(added) => Ok(added.seq),
+            None => Err(ErrorCode::Internal(format!(
+                "Fail to save the info of async query {}",
+                info.query_id
+            ))),
+        }
+    }
+
+    async fn get_query(&self, query_id: &str) -> Result<Option<AsyncQueryInfo>> {
+        let key = self.query_key(query_id)?;
+        match self.kv_api.get_kv(&key).await? {
+            Some(value) => Ok(Some(serde_json::from_slice::<AsyncQueryInfo>(&value.data)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn drop_query(&self, query_id: &str) -> Result<()> {
+        let key = self.query_key(query_id)?;
+        self.kv_api
+            .upsert_kv(UpsertKVReq::new(
+                &key,
+                MatchSeq::GE(0),
+                Operation::Delete,
+                None,
+            ))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_exception::Result;
+
+#[async_trait::async_trait]
+pub trait LeaseApi: Sync + Send {
+    // Acquire the lease of name for the holder, or renew it if the holder has it already.
+    // Returns false if the lease is held by another holder, it expires after the ttl.
+    async fn acquire_lease(&self, name: &str, holder: &str, ttl: Duration) -> Result<bool>;
+
+    // Release the lease of name if it is held by the holder.
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<()>;
+}
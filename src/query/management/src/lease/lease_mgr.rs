@@ -0,0 +1,104 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use common_base::base::escape_for_key;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_kvapi::kvapi;
+use common_meta_kvapi::kvapi::UpsertKVReq;
+use common_meta_types::KVMeta;
+use common_meta_types::MatchSeq;
+use common_meta_types::MetaError;
+use common_meta_types::Operation;
+use common_meta_types::SeqV;
+
+use crate::lease::LeaseApi;
+
+static LEASE_API_KEY_PREFIX: &str = "__fd_leases";
+
+pub struct LeaseMgr {
+    kv_api: Arc<dyn kvapi::KVApi<Error = MetaError>>,
+    lease_prefix: String,
+}
+
+impl LeaseMgr {
+    pub fn create(kv_api: Arc<dyn kvapi::KVApi<Error = MetaError>>, tenant: &str) -> Result<Self> {
+        if tenant.is_empty() {
+            return Err(ErrorCode::TenantIsEmpty(
+                "Tenant can not empty(while lease mgr create)",
+            ));
+        }
+
+        Ok(LeaseMgr {
+            kv_api,
+            lease_prefix: format!("{}/{}", LEASE_API_KEY_PREFIX, escape_for_key(tenant)?),
+        })
+    }
+
+    fn lease_key(&self, name: &str) -> Result<String> {
+        Ok(format!("{}/{}", self.lease_prefix, escape_for_key(name)?))
+    }
+
+    // The seq to match to take the lease, None if it is held by another holder.
+    async fn lease_seq(&self, key: &str, holder: &str) -> Result<Option<MatchSeq>> {
+        match self.kv_api.get_kv(key).await? {
+            None => Ok(Some(MatchSeq::Exact(0))),
+            Some(SeqV { seq, data, .. }) if data == holder.as_bytes() => {
+                Ok(Some(MatchSeq::Exact(seq)))
+            }
+            Some(_) => Ok(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LeaseApi for LeaseMgr {
+    async fn acquire_lease(&self, name: &str, holder: &str, ttl: Duration) -> Result<bool> {
+        let key = self.lease_key(name)?;
+        let seq = match self.lease_seq(&key, holder).await? {
+            Some(seq) => seq,
+            None => return Ok(false),
+        };
+
+        let expire_at = std::time::SystemTime::now()
+            .add(ttl)
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards");
+        let meta = KVMeta {
+            expire_at: Some(expire_at.as_secs()),
+        };
+        let val = Operation::Update(holder.as_bytes().to_vec());
+        let res = self
+            .kv_api
+            .upsert_kv(UpsertKVReq::new(&key, seq, val, Some(meta)))
+            .await?;
+        // another holder takes the lease in the meantime.
+        Ok(res.is_changed())
+    }
+
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<()> {
+        let key = self.lease_key(name)?;
+        if let Some(seq) = self.lease_seq(&key, holder).await? {
+            self.kv_api
+                .upsert_kv(UpsertKVReq::new(&key, seq, Operation::Delete, None))
+                .await?;
+        }
+        Ok(())
+    }
+}
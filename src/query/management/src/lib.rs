@@ -14,26 +14,35 @@
 
 #![allow(clippy::uninlined_format_args)]
 
+mod async_query;
 mod cluster;
 mod file_format;
+mod lease;
 mod quota;
 mod role;
 mod serde;
+mod session;
 mod setting;
 mod stage;
 mod udf;
 mod user;
 
+pub use async_query::AsyncQueryApi;
+pub use async_query::AsyncQueryMgr;
 pub use cluster::ClusterApi;
 pub use cluster::ClusterMgr;
 pub use file_format::FileFormatApi;
 pub use file_format::FileFormatMgr;
+pub use lease::LeaseApi;
+pub use lease::LeaseMgr;
 pub use quota::QuotaApi;
 pub use quota::QuotaMgr;
 pub use role::RoleApi;
 pub use role::RoleMgr;
 pub use serde::deserialize_struct;
 pub use serde::serialize_struct;
+pub use session::SessionApi;
+pub use session::SessionMgr;
 pub use setting::SettingApi;
 pub use setting::SettingMgr;
 pub use stage::StageApi;
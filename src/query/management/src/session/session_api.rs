@@ -0,0 +1,30 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_exception::Result;
+use common_meta_app::principal::SessionState;
+
+#[async_trait::async_trait]
+pub trait SessionApi: Sync + Send {
+    // Save the session state by token to /tenant/token, it expires after the ttl.
+    async fn upsert_session(&self, token: &str, state: SessionState, ttl: Duration) -> Result<u64>;
+
+    // Get the session state by token, None if it does not exist or has expired.
+    async fn get_session(&self, token: &str) -> Result<Option<SessionState>>;
+
+    // Drop the session state by token.
+    async fn drop_session(&self, token: &str) -> Result<()>;
+}
@@ -0,0 +1,110 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use common_base::base::escape_for_key;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_app::principal::SessionState;
+use common_meta_kvapi::kvapi;
+use common_meta_kvapi::kvapi::UpsertKVReq;
+use common_meta_types::KVMeta;
+use common_meta_types::MatchSeq;
+use common_meta_types::MetaError;
+use common_meta_types::Operation;
+
+use crate::session::SessionApi;
+
+static SESSION_API_KEY_PREFIX: &str = "__fd_sessions";
+
+pub struct SessionMgr {
+    kv_api: Arc<dyn kvapi::KVApi<Error = MetaError>>,
+    session_prefix: String,
+}
+
+impl SessionMgr {
+    pub fn create(kv_api: Arc<dyn kvapi::KVApi<Error = MetaError>>, tenant: &str) -> Result<Self> {
+        if tenant.is_empty() {
+            return Err(ErrorCode::TenantIsEmpty(
+                "Tenant can not empty(while session mgr create)",
+            ));
+        }
+
+        Ok(SessionMgr {
+            kv_api,
+            session_prefix: format!("{}/{}", SESSION_API_KEY_PREFIX, escape_for_key(tenant)?),
+        })
+    }
+
+    fn session_key(&self, token: &str) -> Result<String> {
+        Ok(format!(
+            "{}/{}",
+            self.session_prefix,
+            escape_for_key(token)?
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionApi for SessionMgr {
+    async fn upsert_session(&self, token: &str, state: SessionState, ttl: Duration) -> Result<u64> {
+        let key = self.session_key(token)?;
+        let val = Operation::Update(serde_json::to_vec(&state)?);
+        let expire_at = std::time::SystemTime::now()
+            .add(ttl)
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards");
+        let meta = KVMeta {
+            expire_at: Some(expire_at.as_secs()),
+        };
+
+        let res = self
+            .kv_api
+            .upsert_kv(UpsertKVReq::new(&key, MatchSeq::GE(0), val, Some(meta)))
+            .await?;
+
+        match res.result {
+            Some(added) => Ok(added.seq),
+            None => Err(ErrorCode::Internal(format!(
+                "Fail to save session state by token {}",
+                token
+            ))),
+        }
+    }
+
+    async fn get_session(&self, token: &str) -> Result<Option<SessionState>> {
+        let key = self.session_key(token)?;
+        match self.kv_api.get_kv(&key).await? {
+            Some(value) => Ok(Some(serde_json::from_slice::<SessionState>(&value.data)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn drop_session(&self, token: &str) -> Result<()> {
+        let key = self.session_key(token)?;
+        self.kv_api
+            .upsert_kv(UpsertKVReq::new(
+                &key,
+                MatchSeq::GE(0),
+                Operation::Delete,
+                None,
+            ))
+            .await?;
+        Ok(())
+    }
+}
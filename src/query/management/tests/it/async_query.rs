@@ -0,0 +1,101 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use common_base::base::tokio;
+use common_exception::Result;
+use common_management::*;
+use common_meta_app::principal::AsyncQueryField;
+use common_meta_app::principal::AsyncQueryInfo;
+use common_meta_app::principal::AsyncQueryState;
+use common_meta_app::principal::UserIdentity;
+use common_meta_embedded::MetaEmbedded;
+use common_meta_kvapi::kvapi::KVApi;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_async_query_info() -> Result<()> {
+    let (kv_api, mgr) = new_async_query_api().await?;
+
+    let info = AsyncQueryInfo {
+        query_id: "query1".to_string(),
+        user: UserIdentity::new("u1", "%"),
+        sql: "select 1".to_string(),
+        node_id: "node1".to_string(),
+        state: AsyncQueryState::Running,
+        error_code: None,
+        error_message: None,
+        schema: vec![],
+        num_rows: 0,
+        result_location: None,
+        max_rows_per_page: 10000,
+        submit_time: Utc::now(),
+        finish_time: None,
+    };
+
+    // Save and get.
+    {
+        mgr.upsert_query(info.clone(), Duration::from_secs(60))
+            .await?;
+        let value = kv_api
+            .get_kv("__fd_async_queries/databend_query/query1")
+            .await?;
+        let value = value.expect("async query info is saved");
+        assert_eq!(value.data, serde_json::to_vec(&info)?);
+        assert!(value.meta.and_then(|m| m.expire_at).is_some());
+
+        let actual = mgr.get_query("query1").await?;
+        assert_eq!(actual, Some(info.clone()));
+    }
+
+    // Save the finished query.
+    {
+        let mut info = info.clone();
+        info.state = AsyncQueryState::Succeeded;
+        info.schema = vec![AsyncQueryField {
+            name: "1".to_string(),
+            data_type: "UInt8".to_string(),
+        }];
+        info.num_rows = 1;
+        info.result_location = Some("_async_query/query1.parquet".to_string());
+        info.finish_time = Some(Utc::now());
+        mgr.upsert_query(info.clone(), Duration::from_secs(60))
+            .await?;
+        let actual = mgr.get_query("query1").await?;
+        assert_eq!(actual, Some(info));
+    }
+
+    // Get unknown query.
+    {
+        let actual = mgr.get_query("query2").await?;
+        assert_eq!(actual, None);
+    }
+
+    // Drop.
+    {
+        mgr.drop_query("query1").await?;
+        let actual = mgr.get_query("query1").await?;
+        assert_eq!(actual, None);
+    }
+
+    Ok(())
+}
+
+async fn new_async_query_api() -> Result<(Arc<MetaEmbedded>, AsyncQueryMgr)> {
+    let test_api = Arc::new(MetaEmbedded::new_temp().await?);
+    let mgr = AsyncQueryMgr::create(test_api.clone(), "databend_query")?;
+    Ok((test_api, mgr))
+}
@@ -0,0 +1,62 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::base::tokio;
+use common_exception::Result;
+use common_management::*;
+use common_meta_embedded::MetaEmbedded;
+use common_meta_kvapi::kvapi::KVApi;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_lease() -> Result<()> {
+    let (kv_api, mgr) = new_lease_api().await?;
+    let ttl = Duration::from_secs(60);
+
+    // Acquire.
+    {
+        assert!(mgr.acquire_lease("compaction", "node1", ttl).await?);
+        let value = kv_api
+            .get_kv("__fd_leases/databend_query/compaction")
+            .await?;
+        let value = value.expect("lease is saved");
+        assert_eq!(value.data, b"node1".to_vec());
+        assert!(value.meta.and_then(|m| m.expire_at).is_some());
+    }
+
+    // Renew by the holder, the others can not acquire it.
+    {
+        assert!(mgr.acquire_lease("compaction", "node1", ttl).await?);
+        assert!(!mgr.acquire_lease("compaction", "node2", ttl).await?);
+        assert!(mgr.acquire_lease("purge", "node2", ttl).await?);
+    }
+
+    // Release.
+    {
+        mgr.release_lease("compaction", "node2").await?;
+        assert!(!mgr.acquire_lease("compaction", "node2", ttl).await?);
+        mgr.release_lease("compaction", "node1").await?;
+        assert!(mgr.acquire_lease("compaction", "node2", ttl).await?);
+    }
+
+    Ok(())
+}
+
+async fn new_lease_api() -> Result<(Arc<MetaEmbedded>, LeaseMgr)> {
+    let test_api = Arc::new(MetaEmbedded::new_temp().await?);
+    let mgr = LeaseMgr::create(test_api.clone(), "databend_query")?;
+    Ok((test_api, mgr))
+}
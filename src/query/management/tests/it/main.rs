@@ -14,7 +14,10 @@
 
 #![allow(clippy::uninlined_format_args)]
 
+mod async_query;
 mod cluster;
+mod lease;
+mod session;
 mod setting;
 mod stage;
 mod udf;
@@ -0,0 +1,81 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::base::tokio;
+use common_exception::Result;
+use common_management::*;
+use common_meta_app::principal::SessionState;
+use common_meta_app::principal::UserIdentity;
+use common_meta_embedded::MetaEmbedded;
+use common_meta_kvapi::kvapi::KVApi;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_session_state() -> Result<()> {
+    let (kv_api, mgr) = new_session_api().await?;
+
+    let state = SessionState {
+        user: UserIdentity::new("u1", "%"),
+        database: "db1".to_string(),
+        role: Some("r1".to_string()),
+        settings: BTreeMap::from([("max_threads".to_string(), "3".to_string())]),
+    };
+
+    // Save and get.
+    {
+        mgr.upsert_session("token1", state.clone(), Duration::from_secs(60))
+            .await?;
+        let value = kv_api.get_kv("__fd_sessions/databend_query/token1").await?;
+        let value = value.expect("session state is saved");
+        assert_eq!(value.data, serde_json::to_vec(&state)?);
+        assert!(value.meta.and_then(|m| m.expire_at).is_some());
+
+        let actual = mgr.get_session("token1").await?;
+        assert_eq!(actual, Some(state.clone()));
+    }
+
+    // Save again overwrites the state.
+    {
+        let mut state = state.clone();
+        state.database = "db2".to_string();
+        mgr.upsert_session("token1", state.clone(), Duration::from_secs(60))
+            .await?;
+        let actual = mgr.get_session("token1").await?;
+        assert_eq!(actual, Some(state));
+    }
+
+    // Get unknown token.
+    {
+        let actual = mgr.get_session("token2").await?;
+        assert_eq!(actual, None);
+    }
+
+    // Drop.
+    {
+        mgr.drop_session("token1").await?;
+        let actual = mgr.get_session("token1").await?;
+        assert_eq!(actual, None);
+    }
+
+    Ok(())
+}
+
+async fn new_session_api() -> Result<(Arc<MetaEmbedded>, SessionMgr)> {
+    let test_api = Arc::new(MetaEmbedded::new_temp().await?);
+    let mgr = SessionMgr::create(test_api.clone(), "databend_query")?;
+    Ok((test_api, mgr))
+}
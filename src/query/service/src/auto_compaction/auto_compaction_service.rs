@@ -0,0 +1,322 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::base::GlobalInstance;
+use common_catalog::catalog_kind::CATALOG_DEFAULT;
+use common_catalog::table::Table;
+use common_config::InnerConfig;
+use common_config::QueryConfig;
+use common_exception::Result;
+use common_meta_app::principal::UserInfo;
+use common_sql::plans::OptimizeTableAction;
+use common_sql::plans::OptimizeTablePlan;
+use common_sql::plans::ReclusterTablePlan;
+use common_sql::Metadata;
+use common_storages_fuse::operations::MaintenanceStatistics;
+use common_storages_fuse::FuseTable;
+use futures::TryStreamExt;
+use parking_lot::RwLock;
+use tracing::info;
+use tracing::warn;
+
+use crate::background::BackgroundLease;
+use crate::background::BackgroundLoop;
+use crate::background::BackgroundRound;
+use crate::interpreters::Interpreter;
+use crate::interpreters::OptimizeTableInterpreter;
+use crate::interpreters::ReclusterTableInterpreter;
+use crate::sessions::QueryContext;
+use crate::sessions::Session;
+use crate::sessions::SessionManager;
+use crate::sessions::SessionType;
+use crate::sessions::TableContext;
+
+/// The lease held by the node running the background compaction.
+const AUTO_COMPACTION_LEASE: &str = "auto_compaction";
+
+/// The maintenance scheduled on a table by the background compaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaintenanceAction {
+    /// `OPTIMIZE TABLE ... COMPACT`
+    CompactBlocks,
+    /// `OPTIMIZE TABLE ... COMPACT SEGMENT`
+    CompactSegments,
+    /// `ALTER TABLE ... RECLUSTER`
+    Recluster,
+}
+
+/// Decides the maintenance of a table, the compaction goes first as it is cheaper.
+pub fn plan_maintenance(
+    conf: &QueryConfig,
+    stats: &MaintenanceStatistics,
+) -> Option<MaintenanceAction> {
+    let excess_segment_count = stats
+        .segment_count
+        .saturating_sub(stats.expected_segment_count);
+    let too_deep = stats.average_depth.map_or(false, |depth| {
+        depth > conf.auto_recluster_depth_threshold as f64
+    });
+    if stats.undersized_block_count as u64 >= conf.auto_compaction_block_threshold {
+        Some(MaintenanceAction::CompactBlocks)
+    } else if excess_segment_count as u64 >= conf.auto_compaction_segment_threshold {
+        Some(MaintenanceAction::CompactSegments)
+    } else if too_deep {
+        Some(MaintenanceAction::Recluster)
+    } else {
+        None
+    }
+}
+
+/// Compacts and reclusters the Fuse tables of the default catalog in the background.
+///
+/// Each round maintains at most `auto_compaction_max_tables_per_round` tables, one at a time,
+/// with `auto_compaction_max_threads` threads. The maintenance commits like the statements do,
+/// without locking the table, a conflicting writer wins and the table is retried in the next round.
+///
+/// The rounds run on one node of the tenant at a time, which holds the lease `auto_compaction`
+/// in the meta service. The lease is renewed before each table and expires after three
+/// intervals, then another node takes over. A round stops once the lease is lost.
+pub struct AutoCompactionService {
+    conf: QueryConfig,
+    lease: Arc<BackgroundLease>,
+    background_loop: BackgroundLoop,
+}
+
+impl AutoCompactionService {
+    pub fn init(config: &InnerConfig) -> Result<()> {
+        GlobalInstance::set(Arc::new(AutoCompactionService {
+            conf: config.query.clone(),
+            lease: Arc::new(BackgroundLease::create(
+                &config.query.tenant_id,
+                AUTO_COMPACTION_LEASE,
+                Duration::from_secs(config.query.auto_compaction_interval_secs * 3),
+            )),
+            background_loop: BackgroundLoop::try_create("auto compaction")?,
+        }));
+        Ok(())
+    }
+
+    pub fn instance() -> Arc<AutoCompactionService> {
+        GlobalInstance::get()
+    }
+
+    pub fn start(&self) {
+        if !self.conf.auto_compaction_enabled {
+            return;
+        }
+
+        let interval = Duration::from_secs(self.conf.auto_compaction_interval_secs);
+        self.background_loop.start(interval, CompactionRound {
+            conf: self.conf.clone(),
+            lease: self.lease.clone(),
+            idle_snapshots: HashMap::new(),
+        });
+    }
+
+    pub async fn shutdown(&self) {
+        if self.background_loop.shutdown().await {
+            self.lease.release().await;
+        }
+    }
+
+    async fn compaction_round(
+        conf: &QueryConfig,
+        lease: &BackgroundLease,
+        idle_snapshots: &mut HashMap<u64, Option<String>>,
+    ) -> Result<()> {
+        if !lease.acquire().await? {
+            // the tables are maintained by another node.
+            return Ok(());
+        }
+
+        let session = SessionManager::instance()
+            .create_session(SessionType::AutoCompaction)
+            .await?;
+        // the interpreters are run directly, the user is only recorded by the query log.
+        let user = UserInfo::new_no_auth("auto_compaction", "127.0.0.1");
+        session.set_authed_user(user, None).await?;
+        session
+            .get_settings()
+            .set_max_threads(conf.auto_compaction_max_threads)?;
+
+        let ctx = session.create_query_context().await?;
+        let tenant = ctx.get_tenant();
+        let catalog = ctx.get_catalog(CATALOG_DEFAULT)?;
+
+        let mut maintained = 0;
+        for database in catalog.list_databases(&tenant).await? {
+            for table in catalog.list_tables(&tenant, database.name()).await? {
+                if maintained >= conf.auto_compaction_max_tables_per_round {
+                    return Ok(());
+                }
+                // a long round may outlive the lease, stop once another node takes it over.
+                if !lease.acquire().await? {
+                    info!("Auto compaction lease is lost, stop the round");
+                    return Ok(());
+                }
+                // a table failing to be maintained does not stop the others.
+                match Self::maintain_table(
+                    conf,
+                    &session,
+                    &ctx,
+                    database.name(),
+                    table.clone(),
+                    idle_snapshots,
+                )
+                .await
+                {
+                    Ok(true) => maintained += 1,
+                    Ok(false) => {}
+                    Err(cause) => warn!(
+                        "Auto compaction on table {}.{} failure: {:?}",
+                        database.name(),
+                        table.name(),
+                        cause
+                    ),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Maintain the table if it needs, returns false if it is left as it is.
+    async fn maintain_table(
+        conf: &QueryConfig,
+        session: &Arc<Session>,
+        ctx: &Arc<QueryContext>,
+        database: &str,
+        table: Arc<dyn Table>,
+        idle_snapshots: &mut HashMap<u64, Option<String>>,
+    ) -> Result<bool> {
+        let fuse_table = match FuseTable::try_from_table(table.as_ref()) {
+            Ok(fuse_table) => fuse_table,
+            Err(_) => return Ok(false),
+        };
+        let snapshot_loc = fuse_table.snapshot_loc().await?;
+        if idle_snapshots.get(&table.get_id()) == Some(&snapshot_loc) {
+            return Ok(false);
+        }
+        let action = match fuse_table.maintenance_statistics(ctx.clone()).await? {
+            Some(stats) => plan_maintenance(conf, &stats),
+            None => None,
+        };
+        let action = match action {
+            Some(action) => action,
+            None => {
+                // nothing to do, the table is not checked again until it is written.
+                idle_snapshots.insert(table.get_id(), snapshot_loc);
+                return Ok(false);
+            }
+        };
+
+        info!(
+            "Auto compaction {:?} on table {}.{}",
+            action,
+            database,
+            table.name()
+        );
+        // the query context caches the tables, each maintenance runs in a new one.
+        if let Err(cause) = Self::maintain(session, database, table.name(), action).await {
+            warn!(
+                "Auto compaction {:?} on table {}.{} failure: {:?}",
+                action,
+                database,
+                table.name(),
+                cause
+            );
+        }
+
+        let latest = ctx
+            .get_catalog(CATALOG_DEFAULT)?
+            .get_table(&ctx.get_tenant(), database, table.name())
+            .await?;
+        let latest_loc = FuseTable::try_from_table(latest.as_ref())?
+            .snapshot_loc()
+            .await?;
+        if latest_loc == snapshot_loc {
+            idle_snapshots.insert(table.get_id(), snapshot_loc);
+        } else {
+            idle_snapshots.remove(&table.get_id());
+        }
+        Ok(true)
+    }
+
+    async fn maintain(
+        session: &Arc<Session>,
+        database: &str,
+        table: &str,
+        action: MaintenanceAction,
+    ) -> Result<()> {
+        let ctx = session.create_query_context().await?;
+        let interpreter: Arc<dyn Interpreter> = match action {
+            MaintenanceAction::CompactBlocks | MaintenanceAction::CompactSegments => {
+                let action = match action {
+                    MaintenanceAction::CompactBlocks => OptimizeTableAction::CompactBlocks(None),
+                    _ => OptimizeTableAction::CompactSegments(None),
+                };
+                Arc::new(OptimizeTableInterpreter::try_create(
+                    ctx.clone(),
+                    OptimizeTablePlan {
+                        catalog: CATALOG_DEFAULT.to_string(),
+                        database: database.to_string(),
+                        table: table.to_string(),
+                        action,
+                    },
+                )?)
+            }
+            // a single round of recluster, the next one is scheduled by the next round
+            // of the service if the table is still not well clustered.
+            MaintenanceAction::Recluster => Arc::new(ReclusterTableInterpreter::try_create(
+                ctx.clone(),
+                ReclusterTablePlan {
+                    tenant: ctx.get_tenant(),
+                    catalog: CATALOG_DEFAULT.to_string(),
+                    database: database.to_string(),
+                    table: table.to_string(),
+                    is_final: false,
+                    metadata: Arc::new(RwLock::new(Metadata::default())),
+                    push_downs: None,
+                },
+            )?),
+        };
+
+        Self::execute(ctx, interpreter).await
+    }
+
+    async fn execute(ctx: Arc<QueryContext>, interpreter: Arc<dyn Interpreter>) -> Result<()> {
+        let stream = interpreter.execute(ctx).await?;
+        let _ = stream.try_collect::<Vec<_>>().await?;
+        Ok(())
+    }
+}
+
+struct CompactionRound {
+    conf: QueryConfig,
+    lease: Arc<BackgroundLease>,
+    // the snapshots left by the maintenance which did nothing, the tables are not
+    // maintained again until they are written.
+    idle_snapshots: HashMap<u64, Option<String>>,
+}
+
+#[async_trait::async_trait]
+impl BackgroundRound for CompactionRound {
+    async fn round(&mut self) -> Result<()> {
+        AutoCompactionService::compaction_round(&self.conf, &self.lease, &mut self.idle_snapshots)
+            .await
+    }
+}
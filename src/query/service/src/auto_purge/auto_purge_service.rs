@@ -0,0 +1,248 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use common_base::base::GlobalInstance;
+use common_catalog::catalog_kind::CATALOG_DEFAULT;
+use common_catalog::table::Table;
+use common_config::InnerConfig;
+use common_config::QueryConfig;
+use common_exception::Result;
+use common_meta_app::principal::UserInfo;
+use common_meta_app::schema::TableInfo;
+use common_meta_app::schema::UpsertTableOptionReq;
+use common_storages_fuse::operations::PurgeStatistics;
+use common_storages_fuse::FuseTable;
+use common_storages_system::PurgeHistoryLogElement;
+use common_storages_system::PurgeHistoryQueue;
+use storages_common_table_meta::table::OPT_KEY_DATA_PURGED;
+use storages_common_table_meta::table::OPT_KEY_DATA_RETENTION_TIME;
+use tracing::info;
+use tracing::warn;
+
+use crate::background::BackgroundLease;
+use crate::background::BackgroundLoop;
+use crate::background::BackgroundRound;
+use crate::sessions::Session;
+use crate::sessions::SessionManager;
+use crate::sessions::SessionType;
+use crate::sessions::TableContext;
+
+/// The lease held by the node running the background purge.
+const AUTO_PURGE_LEASE: &str = "auto_purge";
+
+/// Purges the Fuse tables of the default catalog in the background.
+///
+/// Only the tables with the option `data_retention_time` are purged, the others are left to
+/// `OPTIMIZE TABLE ... PURGE`. The history within the retention period is kept like
+/// `OPTIMIZE TABLE ... PURGE`. The dropped tables beyond the retention period are truncated
+/// and purged like `DROP TABLE ... ALL`, and marked by the option `data_purged` first, so
+/// that they can not be undropped any more.
+///
+/// The rounds run on one node of the tenant at a time, which holds the lease `auto_purge` in
+/// the meta service, renewed before each table. Each purge which removes files is recorded in
+/// `system.purge_history` of that node only.
+pub struct AutoPurgeService {
+    conf: QueryConfig,
+    lease: BackgroundLease,
+    background_loop: BackgroundLoop,
+}
+
+impl AutoPurgeService {
+    pub fn init(config: &InnerConfig) -> Result<()> {
+        GlobalInstance::set(Arc::new(AutoPurgeService {
+            conf: config.query.clone(),
+            lease: BackgroundLease::create(
+                &config.query.tenant_id,
+                AUTO_PURGE_LEASE,
+                Duration::from_secs(config.query.auto_purge_interval_secs * 3),
+            ),
+            background_loop: BackgroundLoop::try_create("auto purge")?,
+        }));
+        Ok(())
+    }
+
+    pub fn instance() -> Arc<AutoPurgeService> {
+        GlobalInstance::get()
+    }
+
+    pub fn start(&self) {
+        if !self.conf.auto_purge_enabled {
+            return;
+        }
+
+        let interval = Duration::from_secs(self.conf.auto_purge_interval_secs);
+        self.background_loop.start(interval, PurgeRound);
+    }
+
+    pub async fn shutdown(&self) {
+        if self.background_loop.shutdown().await {
+            self.lease.release().await;
+        }
+    }
+
+    pub async fn purge_round(&self) -> Result<()> {
+        if !self.lease.acquire().await? {
+            // the tables are purged by another node.
+            return Ok(());
+        }
+
+        let session = SessionManager::instance()
+            .create_session(SessionType::AutoPurge)
+            .await?;
+        // the tables are purged directly, the user is only recorded by the query log.
+        let user = UserInfo::new_no_auth("auto_purge", "127.0.0.1");
+        session.set_authed_user(user, None).await?;
+
+        let ctx = session.create_query_context().await?;
+        let tenant = ctx.get_tenant();
+        let catalog = ctx.get_catalog(CATALOG_DEFAULT)?;
+
+        for database in catalog.list_databases(&tenant).await? {
+            // the dropped tables are listed until the meta service collects them.
+            for table in catalog
+                .list_tables_history(&tenant, database.name())
+                .await?
+            {
+                // a long round may outlive the lease, stop once another node takes it over.
+                if !self.lease.acquire().await? {
+                    info!("Auto purge lease is lost, stop the round");
+                    return Ok(());
+                }
+                let start = Utc::now();
+                let statistics =
+                    match Self::purge_table(&session, database.name(), table.as_ref()).await {
+                        Ok(Some(statistics)) => statistics,
+                        Ok(None) => continue,
+                        Err(cause) => {
+                            warn!(
+                                "Auto purge on table {}.{} failure: {:?}",
+                                database.name(),
+                                table.name(),
+                                cause
+                            );
+                            continue;
+                        }
+                    };
+                if statistics.file_count() == 0 {
+                    continue;
+                }
+
+                info!(
+                    "Auto purge on table {}.{}, {:?}",
+                    database.name(),
+                    table.name(),
+                    statistics
+                );
+                PurgeHistoryQueue::instance()?.append_data(PurgeHistoryLogElement {
+                    start_time: start.timestamp_micros(),
+                    end_time: Utc::now().timestamp_micros(),
+                    database: database.name().to_string(),
+                    table: table.name().to_string(),
+                    purged_files: statistics.file_count() as u64,
+                    reclaimed_bytes: statistics.reclaimed_bytes,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    // Returns None if the table is not to be purged.
+    async fn purge_table(
+        session: &Arc<Session>,
+        database: &str,
+        table: &dyn Table,
+    ) -> Result<Option<PurgeStatistics>> {
+        let fuse_table = match FuseTable::try_from_table(table) {
+            Ok(fuse_table) => fuse_table,
+            Err(_) => return Ok(None),
+        };
+
+        // the query context caches the tables, each purge runs in a new one.
+        let ctx: Arc<dyn TableContext> = session.create_query_context().await?;
+        let table_info = table.get_table_info();
+        // the retention period of the others is the session setting, which is too short to
+        // be the window of UNDROP.
+        if !table_info
+            .options()
+            .contains_key(OPT_KEY_DATA_RETENTION_TIME)
+        {
+            return Ok(None);
+        }
+        match table_info.meta.drop_on {
+            Some(drop_on) => {
+                if Utc::now() - drop_on < fuse_table.data_retention_interval(ctx.as_ref())? {
+                    return Ok(None);
+                }
+                let marked_table;
+                let fuse_table = if table_info.options().contains_key(OPT_KEY_DATA_PURGED) {
+                    fuse_table
+                } else {
+                    marked_table =
+                        Self::mark_data_purged(ctx.as_ref(), database, table_info).await?;
+                    marked_table.as_ref()
+                };
+                // the table has been truncated by the purge of previous rounds.
+                match fuse_table.read_table_snapshot().await? {
+                    Some(snapshot) if !snapshot.segments.is_empty() => {}
+                    _ => return Ok(None),
+                }
+                let purge = true;
+                let statistics = fuse_table.truncate_with_statistics(ctx, purge).await?;
+                Ok(Some(statistics))
+            }
+            None => {
+                let keep_last_snapshot = true;
+                let statistics = fuse_table.do_purge(&ctx, keep_last_snapshot).await?;
+                Ok(Some(statistics))
+            }
+        }
+    }
+
+    // Marks the dropped table before its data is purged, UNDROP refuses the marked tables.
+    // Returns the table of the marked version, the truncation commits upon it.
+    async fn mark_data_purged(
+        ctx: &dyn TableContext,
+        database: &str,
+        table_info: &TableInfo,
+    ) -> Result<Box<FuseTable>> {
+        let catalog = ctx.get_catalog(CATALOG_DEFAULT)?;
+        let req = UpsertTableOptionReq::new(&table_info.ident, OPT_KEY_DATA_PURGED, "true");
+        catalog
+            .upsert_table_option(&ctx.get_tenant(), database, req)
+            .await?;
+
+        let (ident, meta) = catalog
+            .get_table_meta_by_id(table_info.ident.table_id)
+            .await?;
+        let table_info = TableInfo {
+            ident,
+            meta: meta.as_ref().clone(),
+            ..table_info.clone()
+        };
+        FuseTable::do_create(table_info)
+    }
+}
+
+struct PurgeRound;
+
+#[async_trait::async_trait]
+impl BackgroundRound for PurgeRound {
+    async fn round(&mut self) -> Result<()> {
+        AutoPurgeService::instance().purge_round().await
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_base::base::GlobalUniqName;
+use common_exception::Result;
+use common_users::UserApiProvider;
+use tracing::warn;
+
+/// The lease in the meta service which keeps a background service running on one node
+/// of the tenant at a time.
+///
+/// The lease expires after the ttl if it is not renewed, then another node takes over.
+/// The holder renews it before each unit of work, and stops the round once it is lost.
+pub struct BackgroundLease {
+    tenant: String,
+    name: &'static str,
+    // unique to this node.
+    holder: String,
+    ttl: Duration,
+}
+
+impl BackgroundLease {
+    pub fn create(tenant: &str, name: &'static str, ttl: Duration) -> BackgroundLease {
+        BackgroundLease {
+            tenant: tenant.to_string(),
+            name,
+            holder: GlobalUniqName::unique(),
+            ttl,
+        }
+    }
+
+    /// Acquires the lease, or renews it if this node holds it already.
+    /// Returns false if it is held by another node.
+    pub async fn acquire(&self) -> Result<bool> {
+        UserApiProvider::instance()
+            .get_lease_api_client(&self.tenant)?
+            .acquire_lease(self.name, &self.holder, self.ttl)
+            .await
+    }
+
+    /// Releases the lease to let another node take over at once.
+    pub async fn release(&self) {
+        let res = match UserApiProvider::instance().get_lease_api_client(&self.tenant) {
+            Ok(lease_api) => lease_api.release_lease(self.name, &self.holder).await,
+            Err(cause) => Err(cause),
+        };
+        if let Err(cause) = res {
+            warn!("Cannot release the lease {}, cause {:?}", self.name, cause);
+        }
+    }
+}
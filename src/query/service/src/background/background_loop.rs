@@ -0,0 +1,105 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::base::tokio::sync::Notify;
+use common_base::base::tokio::task::JoinHandle;
+use common_base::base::tokio::time::sleep as tokio_async_sleep;
+use common_base::runtime::Runtime;
+use common_base::runtime::TrySpawn;
+use common_exception::Result;
+use futures::future::select;
+use futures::future::Either;
+use parking_lot::Mutex;
+use tracing::warn;
+
+/// The work done by each round of a [`BackgroundLoop`].
+#[async_trait::async_trait]
+pub trait BackgroundRound: Send + 'static {
+    async fn round(&mut self) -> Result<()>;
+}
+
+/// Runs a [`BackgroundRound`] every interval on a runtime of its own, until shutdown.
+///
+/// A failing round is logged, the next one runs as usual.
+pub struct BackgroundLoop {
+    name: &'static str,
+    runtime: Arc<Runtime>,
+    shutdown: Arc<AtomicBool>,
+    shutdown_notify: Arc<Notify>,
+    join_handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl BackgroundLoop {
+    pub fn try_create(name: &'static str) -> Result<BackgroundLoop> {
+        Ok(BackgroundLoop {
+            name,
+            runtime: Arc::new(Runtime::with_worker_threads(
+                1,
+                Some(name.replace(' ', "-")),
+            )?),
+            shutdown: Arc::new(AtomicBool::new(false)),
+            shutdown_notify: Arc::new(Notify::new()),
+            join_handle: Mutex::new(None),
+        })
+    }
+
+    /// Starts the loop, does nothing if it is running.
+    pub fn start<R: BackgroundRound>(&self, interval: Duration, mut round: R) {
+        let mut join_handle = self.join_handle.lock();
+        if join_handle.is_some() {
+            return;
+        }
+
+        let name = self.name;
+        let shutdown = self.shutdown.clone();
+        let shutdown_notify = self.shutdown_notify.clone();
+        *join_handle = Some(self.runtime.spawn(async move {
+            let mut shutdown_notified = Box::pin(shutdown_notify.notified());
+
+            while !shutdown.load(Ordering::Relaxed) {
+                let sleep = tokio_async_sleep(interval);
+                match select(shutdown_notified, Box::pin(sleep)).await {
+                    Either::Left((_, _)) => break,
+                    Either::Right((_, new_shutdown_notified)) => {
+                        shutdown_notified = new_shutdown_notified;
+                        if let Err(cause) = round.round().await {
+                            warn!("{} round failure: {:?}", name, cause);
+                        }
+                    }
+                }
+            }
+        }));
+    }
+
+    /// Stops the loop and waits for the running round, returns false if it was not started.
+    pub async fn shutdown(&self) -> bool {
+        let join_handle = self.join_handle.lock().take();
+        match join_handle {
+            Some(join_handle) => {
+                self.shutdown.store(true, Ordering::Relaxed);
+                self.shutdown_notify.notify_waiters();
+                if let Err(cause) = join_handle.await {
+                    warn!("Cannot shutdown {}, cause {:?}", self.name, cause);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -26,6 +26,8 @@ use common_users::UserApiProvider;
 use storages_common_cache_manager::CacheManager;
 
 use crate::api::DataExchangeManager;
+use crate::auto_compaction::AutoCompactionService;
+use crate::auto_purge::AutoPurgeService;
 use crate::catalogs::CatalogManagerHelper;
 use crate::clusters::ClusterDiscovery;
 use crate::servers::http::v1::HttpQueryManager;
@@ -72,6 +74,8 @@ impl GlobalServices {
         )
         .await?;
         RoleCacheManager::init()?;
+        AutoCompactionService::init(&config)?;
+        AutoPurgeService::init(&config)?;
 
         Ok(())
     }
@@ -20,6 +20,7 @@ use common_meta_app::principal::UserPrivilegeType;
 
 use crate::interpreters::access::AccessChecker;
 use crate::sessions::QueryContext;
+use crate::sql::plans::MatchedOperation;
 use crate::sql::plans::Plan;
 
 pub struct PrivilegeAccess {
@@ -241,6 +242,30 @@ impl AccessChecker for PrivilegeAccess {
                     )
                     .await?;
             }
+            Plan::MergeInto(plan) => {
+                self.check(&plan.source).await?;
+                let object = GrantObject::Table(
+                    plan.catalog.clone(),
+                    plan.database.clone(),
+                    plan.table.clone(),
+                );
+                let mut privileges = vec![];
+                for clause in plan.matched_clauses.iter() {
+                    let privilege = match clause.operation {
+                        MatchedOperation::Update(_) => UserPrivilegeType::Update,
+                        MatchedOperation::Delete => UserPrivilegeType::Delete,
+                    };
+                    if !privileges.contains(&privilege) {
+                        privileges.push(privilege);
+                    }
+                }
+                if !plan.not_matched_clauses.is_empty() {
+                    privileges.push(UserPrivilegeType::Insert);
+                }
+                for privilege in privileges {
+                    session.validate_privilege(&object, privilege).await?;
+                }
+            }
             Plan::CreateView(plan) => {
                 session
                     .validate_privilege(
@@ -265,6 +290,22 @@ impl AccessChecker for PrivilegeAccess {
                     )
                     .await?;
             }
+            Plan::CreateStream(plan) => {
+                session
+                    .validate_privilege(
+                        &GrantObject::Database(plan.catalog.clone(), plan.database.clone()),
+                        UserPrivilegeType::Create,
+                    )
+                    .await?;
+            }
+            Plan::DropStream(plan) => {
+                session
+                    .validate_privilege(
+                        &GrantObject::Database(plan.catalog.clone(), plan.database.clone()),
+                        UserPrivilegeType::Drop,
+                    )
+                    .await?;
+            }
             Plan::AlterUser(_) => {}
             Plan::CreateUser(_) => {}
             Plan::DropUser(_) => {}
@@ -16,4 +16,7 @@ mod grant;
 mod table;
 
 pub use grant::validate_grant_object_exists;
+pub use table::advance_stream_offsets;
 pub use table::append2table;
+pub use table::check_stream_offsets;
+pub use table::commit_insertion;
@@ -19,8 +19,12 @@ use common_catalog::table::AppendMode;
 use common_catalog::table::Table;
 use common_catalog::table_context::TableContext;
 use common_exception::Result;
+use common_expression::DataBlock;
 use common_expression::DataSchemaRef;
 use common_pipeline_core::Pipeline;
+use common_storages_fuse::stream::StreamTable;
+use common_storages_fuse::stream::STREAM_ENGINE;
+use tracing::error;
 
 use crate::pipelines::processors::TransformResortAddOn;
 use crate::pipelines::PipelineBuildResult;
@@ -78,7 +82,7 @@ pub fn append2table(
                 let append_entries = ctx.consume_precommit_blocks();
                 // We must put the commit operation to global runtime, which will avoid the "dispatch dropped without returning error" in tower
                 return GlobalIORuntime::instance().block_on(async move {
-                    table.commit_insertion(ctx, append_entries, overwrite).await
+                    commit_insertion(ctx, table, append_entries, overwrite).await
                 });
             }
 
@@ -88,3 +92,58 @@ pub fn append2table(
 
     Ok(())
 }
+
+/// Commit the insertion, and move the offsets of the streams read by the query past the
+/// changes it consumed.
+///
+/// The streams are checked not to be consumed by another statement before the insertion is
+/// committed. Once committed, the statement does not fail any more: if moving an offset
+/// fails, it is logged and the changes will be delivered again.
+pub async fn commit_insertion(
+    ctx: Arc<QueryContext>,
+    table: Arc<dyn Table>,
+    append_entries: Vec<DataBlock>,
+    overwrite: bool,
+) -> Result<()> {
+    let streams = check_stream_offsets(&ctx).await?;
+
+    table
+        .commit_insertion(ctx.clone(), append_entries, overwrite)
+        .await?;
+
+    advance_stream_offsets(&ctx, &streams).await;
+    Ok(())
+}
+
+/// Returns the streams read by the query, checked not to be consumed by another statement.
+///
+/// The DML statements call it before they commit, and [`advance_stream_offsets`] after.
+pub async fn check_stream_offsets(ctx: &Arc<QueryContext>) -> Result<Vec<Arc<dyn Table>>> {
+    let streams = ctx
+        .get_tables_refs()
+        .into_iter()
+        .filter(|table| table.engine() == STREAM_ENGINE)
+        .collect::<Vec<_>>();
+    for stream in &streams {
+        StreamTable::try_from_table(stream.as_ref())?
+            .check_offset(ctx.as_ref())
+            .await?;
+    }
+    Ok(streams)
+}
+
+/// Moves the offsets of the streams past the changes consumed by the committed statement,
+/// the failures are logged only.
+pub async fn advance_stream_offsets(ctx: &Arc<QueryContext>, streams: &[Arc<dyn Table>]) {
+    for stream in streams {
+        if let Ok(stream) = StreamTable::try_from_table(stream.as_ref()) {
+            if let Err(e) = stream.advance_offset(ctx.as_ref()).await {
+                error!(
+                    "failed to advance the offset of stream {}, the changes consumed will be delivered again: {}",
+                    stream.get_table_info().desc,
+                    e
+                );
+            }
+        }
+    }
+}
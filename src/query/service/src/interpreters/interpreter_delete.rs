@@ -21,6 +21,8 @@ use common_functions::scalars::BUILTIN_FUNCTIONS;
 use common_pipeline_core::Pipeline;
 use common_sql::executor::cast_expr_to_non_null_boolean;
 
+use crate::interpreters::common::advance_stream_offsets;
+use crate::interpreters::common::check_stream_offsets;
 use crate::interpreters::Interpreter;
 use crate::pipelines::executor::ExecutorSettings;
 use crate::pipelines::executor::PipelineCompleteExecutor;
@@ -83,6 +85,9 @@ impl Interpreter for DeleteInterpreter {
             (None, vec![])
         };
 
+        // the streams read by the statement are consumed once it commits.
+        let streams = check_stream_offsets(&self.ctx).await?;
+
         let mut pipeline = Pipeline::create();
         tbl.delete(self.ctx.clone(), filter, col_indices, &mut pipeline)
             .await?;
@@ -97,6 +102,7 @@ impl Interpreter for DeleteInterpreter {
             executor.execute()?;
             drop(executor);
         }
+        advance_stream_offsets(&self.ctx, &streams).await;
 
         Ok(PipelineBuildResult::create())
     }
@@ -84,6 +84,7 @@ use tracing::error;
 use tracing::info;
 
 use crate::interpreters::common::append2table;
+use crate::interpreters::common::commit_insertion;
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterPtr;
 use crate::pipelines::processors::transforms::TransformAddConstColumns;
@@ -320,9 +321,7 @@ impl InsertInterpreterV2 {
                             append_entries.len(),
                             start.elapsed().as_secs()
                         );
-                        table
-                            .commit_insertion(ctx.clone(), append_entries, overwrite)
-                            .await?;
+                        commit_insertion(ctx.clone(), table, append_entries, overwrite).await?;
 
                         if stage_info.copy_options.purge {
                             info!(
@@ -474,7 +473,7 @@ impl Interpreter for InsertInterpreterV2 {
                             let append_entries = ctx.consume_precommit_blocks();
                             // We must put the commit operation to global runtime, which will avoid the "dispatch dropped without returning error" in tower
                             return GlobalIORuntime::instance().block_on(async move {
-                                table.commit_insertion(ctx, append_entries, overwrite).await
+                                commit_insertion(ctx, table, append_entries, overwrite).await
                             });
                         }
 
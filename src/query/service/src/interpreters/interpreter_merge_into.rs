@@ -0,0 +1,324 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_catalog::plan::MergeIntoInfo;
+use common_catalog::plan::MergeIntoSource;
+use common_catalog::plan::MergeIntoSourceBlock;
+use common_catalog::plan::MergeMatchedClause;
+use common_catalog::plan::MergeMatchedOperation;
+use common_catalog::plan::MergeNotMatchedClause;
+use common_catalog::plan::SpilledBlockReader;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::type_check;
+use common_expression::types::DataType;
+use common_expression::DataSchemaRef;
+use common_expression::Expr;
+use common_expression::TableSchemaRef;
+use common_functions::scalars::BUILTIN_FUNCTIONS;
+use common_pipeline_core::Pipeline;
+use common_sql::executor::cast_expr_to_non_null_boolean;
+use common_sql::field_default_value;
+use common_sql::IndexType;
+use common_sql::ScalarExpr;
+
+use crate::interpreters::common::advance_stream_offsets;
+use crate::interpreters::common::check_stream_offsets;
+use crate::interpreters::Interpreter;
+use crate::interpreters::SelectInterpreterV2;
+use crate::pipelines::executor::ExecutorSettings;
+use crate::pipelines::executor::PipelineCompleteExecutor;
+use crate::pipelines::executor::PipelinePullingExecutor;
+use crate::pipelines::processors::transforms::BlockSpiller;
+use crate::pipelines::PipelineBuildResult;
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+use crate::sql::plans::MatchedOperation;
+use crate::sql::plans::MergeIntoPlan;
+use crate::sql::plans::Plan;
+
+/// interprets MergeIntoPlan
+pub struct MergeIntoInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: MergeIntoPlan,
+}
+
+impl MergeIntoInterpreter {
+    /// Create the MergeIntoInterpreter from MergeIntoPlan
+    pub fn try_create(ctx: Arc<QueryContext>, plan: MergeIntoPlan) -> Result<Self> {
+        Ok(MergeIntoInterpreter { ctx, plan })
+    }
+
+    /// Execute the source query and collect its result blocks.
+    ///
+    /// The source is joined with the target table after it is collected. The blocks
+    /// are kept in memory up to the setting `max_merge_into_source_bytes`, the rest
+    /// are spilled to the storage.
+    async fn read_source(&self) -> Result<Option<MergeIntoSource>> {
+        let (s_expr, metadata, bind_context) = match self.plan.source.as_ref() {
+            Plan::Query {
+                s_expr,
+                metadata,
+                bind_context,
+                ..
+            } => (s_expr, metadata, bind_context),
+            v => unreachable!("Input plan must be Query, but it's {}", v),
+        };
+
+        let select_interpreter = SelectInterpreterV2::try_create(
+            self.ctx.clone(),
+            *(bind_context.clone()),
+            *s_expr.clone(),
+            metadata.clone(),
+            None,
+            false,
+        )?;
+        let mut build_res = select_interpreter.execute2().await?;
+
+        let settings = self.ctx.get_settings();
+        let query_id = self.ctx.get_id();
+        let max_memory_bytes = settings.get_max_merge_into_source_bytes()?;
+        build_res.set_max_threads(settings.get_max_threads()? as usize);
+        let settings = ExecutorSettings::try_create(&settings, query_id)?;
+
+        let mut pulling_executor = PipelinePullingExecutor::from_pipelines(build_res, settings)?;
+        pulling_executor.start();
+        let mut blocks = vec![];
+        let mut source_bytes = 0;
+        let mut spiller: Option<Arc<BlockSpiller>> = None;
+        while let Some(block) = pulling_executor.pull_data()? {
+            if block.is_empty() {
+                continue;
+            }
+            source_bytes += block.memory_size();
+            if source_bytes <= max_memory_bytes {
+                blocks.push(MergeIntoSourceBlock::Memory(block));
+                continue;
+            }
+
+            if spiller.is_none() {
+                spiller = Some(Arc::new(BlockSpiller::try_create(self.ctx.clone())?));
+            }
+            let location = spiller.as_ref().unwrap().spill(&block).await?;
+            blocks.push(MergeIntoSourceBlock::Spilled {
+                location,
+                num_rows: block.num_rows(),
+            });
+        }
+
+        if blocks.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(MergeIntoSource {
+            blocks,
+            spill_reader: spiller.map(|spiller| spiller as Arc<dyn SpilledBlockReader>),
+        }))
+    }
+
+    fn build_merge_info(&self, table_schema: TableSchemaRef) -> Result<MergeIntoInfo> {
+        let source_columns = match self.plan.source.as_ref() {
+            Plan::Query { bind_context, .. } => bind_context
+                .columns
+                .iter()
+                .map(|column| column.index)
+                .collect::<Vec<_>>(),
+            v => unreachable!("Input plan must be Query, but it's {}", v),
+        };
+
+        // The offsets of the columns in the source block.
+        let source_offsets: HashMap<IndexType, usize> = source_columns
+            .iter()
+            .enumerate()
+            .map(|(offset, index)| (*index, offset))
+            .collect();
+        // The offsets of the columns in the joined block, which are the target
+        // columns followed by the source columns.
+        let num_target_fields = self.plan.target_columns.len();
+        let joined_offsets: HashMap<IndexType, usize> = self
+            .plan
+            .target_columns
+            .iter()
+            .enumerate()
+            .map(|(offset, index)| (*index, offset))
+            .chain(
+                source_columns
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, index)| (*index, num_target_fields + offset)),
+            )
+            .collect();
+
+        let field_types = table_schema
+            .fields()
+            .iter()
+            .map(|field| DataType::from(field.data_type()))
+            .collect::<Vec<_>>();
+
+        let as_expr = |scalar: &ScalarExpr, offsets: &HashMap<IndexType, usize>| {
+            Ok::<_, ErrorCode>(
+                scalar
+                    .as_expr_with_col_index()?
+                    .project_column_ref(|index| offsets[index]),
+            )
+        };
+        let as_predicate = |scalar: &ScalarExpr, offsets: &HashMap<IndexType, usize>| {
+            cast_expr_to_non_null_boolean(as_expr(scalar, offsets)?)
+        };
+        let as_field_value = |expr: Expr, index: usize| {
+            type_check::check_cast(None, false, expr, &field_types[index], &BUILTIN_FUNCTIONS)
+        };
+
+        let target_keys = self
+            .plan
+            .target_keys
+            .iter()
+            .map(|key| Ok(as_expr(key, &joined_offsets)?.as_remote_expr()))
+            .collect::<Result<Vec<_>>>()?;
+        let source_keys = self
+            .plan
+            .source_keys
+            .iter()
+            .map(|key| Ok(as_expr(key, &source_offsets)?.as_remote_expr()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut join_filter: Option<Expr> = None;
+        for filter in self.plan.join_filters.iter() {
+            let filter = as_predicate(filter, &joined_offsets)?;
+            join_filter = Some(match join_filter {
+                Some(acc) => type_check::check_function(
+                    None,
+                    "and",
+                    &[],
+                    &[acc, filter],
+                    &BUILTIN_FUNCTIONS,
+                )?,
+                None => filter,
+            });
+        }
+
+        let mut matched = Vec::with_capacity(self.plan.matched_clauses.len());
+        for clause in self.plan.matched_clauses.iter() {
+            let condition = match &clause.condition {
+                Some(condition) => Some(as_predicate(condition, &joined_offsets)?.as_remote_expr()),
+                None => None,
+            };
+            let operation = match &clause.operation {
+                MatchedOperation::Update(update_list) => {
+                    let mut values = Vec::with_capacity(update_list.len());
+                    for (index, scalar) in update_list {
+                        let expr = as_field_value(as_expr(scalar, &joined_offsets)?, *index)?;
+                        values.push((*index, expr.as_remote_expr()));
+                    }
+                    MergeMatchedOperation::Update(values)
+                }
+                MatchedOperation::Delete => MergeMatchedOperation::Delete,
+            };
+            matched.push(MergeMatchedClause {
+                condition,
+                operation,
+            });
+        }
+
+        let mut not_matched = Vec::with_capacity(self.plan.not_matched_clauses.len());
+        for clause in self.plan.not_matched_clauses.iter() {
+            let condition = match &clause.condition {
+                Some(condition) => Some(as_predicate(condition, &source_offsets)?.as_remote_expr()),
+                None => None,
+            };
+            let mut values = Vec::with_capacity(clause.values.len());
+            for (index, value) in clause.values.iter().enumerate() {
+                let expr = match value {
+                    Some(scalar) => as_field_value(as_expr(scalar, &source_offsets)?, index)?,
+                    None => Expr::Constant {
+                        span: None,
+                        scalar: field_default_value(
+                            self.ctx.clone(),
+                            &table_schema.fields()[index],
+                        )?,
+                        data_type: field_types[index].clone(),
+                    },
+                };
+                values.push(expr.as_remote_expr());
+            }
+            not_matched.push(MergeNotMatchedClause { condition, values });
+        }
+
+        Ok(MergeIntoInfo {
+            target_keys,
+            source_keys,
+            join_filter: join_filter.map(|filter| filter.as_remote_expr()),
+            matched,
+            not_matched,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for MergeIntoInterpreter {
+    /// Get the name of current interpreter
+    fn name(&self) -> &str {
+        "MergeIntoInterpreter"
+    }
+
+    /// Get the schema of MergeIntoPlan
+    fn schema(&self) -> DataSchemaRef {
+        self.plan.schema()
+    }
+
+    #[tracing::instrument(level = "debug", name = "merge_into_interpreter_execute", skip(self), fields(ctx.id = self.ctx.get_id().as_str()))]
+    async fn execute2(&self) -> Result<PipelineBuildResult> {
+        let catalog_name = self.plan.catalog.as_str();
+        let db_name = self.plan.database.as_str();
+        let tbl_name = self.plan.table.as_str();
+        let tbl = self.ctx.get_table(catalog_name, db_name, tbl_name).await?;
+
+        let source = match self.read_source().await? {
+            Some(source) => source,
+            // No source rows, nothing to merge.
+            None => return Ok(PipelineBuildResult::create()),
+        };
+        let merge_info = self.build_merge_info(tbl.schema())?;
+
+        // the streams read by the statement are consumed once it commits.
+        let streams = check_stream_offsets(&self.ctx).await?;
+
+        let mut pipeline = Pipeline::create();
+        tbl.merge_into(self.ctx.clone(), merge_info, source, &mut pipeline)
+            .await?;
+        if !pipeline.is_empty() {
+            let settings = self.ctx.get_settings();
+            pipeline.set_max_threads(settings.get_max_threads()? as usize);
+            let query_id = self.ctx.get_id();
+            let executor_settings = ExecutorSettings::try_create(&settings, query_id)?;
+            let executor = PipelineCompleteExecutor::try_create(pipeline, executor_settings)?;
+
+            self.ctx.set_executor(Arc::downgrade(&executor.get_inner()));
+            executor.execute()?;
+            drop(executor);
+        }
+
+        // If the target table is empty, the source rows are simply appended.
+        let append_entries = self.ctx.consume_precommit_blocks();
+        if !append_entries.is_empty() {
+            tbl.commit_insertion(self.ctx.clone(), append_entries, false)
+                .await?;
+        }
+        advance_stream_offsets(&self.ctx, &streams).await;
+
+        Ok(PipelineBuildResult::create())
+    }
+}
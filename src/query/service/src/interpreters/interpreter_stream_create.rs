@@ -0,0 +1,119 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_app::schema::CreateTableReq;
+use common_meta_app::schema::TableMeta;
+use common_meta_app::schema::TableNameIdent;
+use common_sql::plans::CreateStreamPlan;
+use common_storages_fuse::stream::StreamTable;
+use common_storages_fuse::stream::OPT_KEY_TABLE_DATABASE;
+use common_storages_fuse::stream::OPT_KEY_TABLE_ID;
+use common_storages_fuse::stream::OPT_KEY_TABLE_NAME;
+use common_storages_fuse::stream::STREAM_ENGINE;
+use common_storages_fuse::FuseTable;
+use storages_common_table_meta::table::OPT_KEY_SNAPSHOT_LOCATION;
+
+use crate::interpreters::Interpreter;
+use crate::pipelines::PipelineBuildResult;
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+
+pub struct CreateStreamInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: CreateStreamPlan,
+}
+
+impl CreateStreamInterpreter {
+    pub fn try_create(ctx: Arc<QueryContext>, plan: CreateStreamPlan) -> Result<Self> {
+        Ok(CreateStreamInterpreter { ctx, plan })
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for CreateStreamInterpreter {
+    fn name(&self) -> &str {
+        "CreateStreamInterpreter"
+    }
+
+    async fn execute2(&self) -> Result<PipelineBuildResult> {
+        let catalog = self.ctx.get_catalog(&self.plan.catalog)?;
+        let mut table = catalog
+            .get_table(
+                &self.plan.tenant,
+                &self.plan.table_database,
+                &self.plan.table_name,
+            )
+            .await?;
+        if table.engine() != "FUSE" {
+            return Err(ErrorCode::TableEngineNotSupported(format!(
+                "stream can only be created on table of engine FUSE, but {}.{} is of engine {}",
+                self.plan.table_database,
+                self.plan.table_name,
+                table.engine()
+            )));
+        }
+        let schema = StreamTable::stream_schema(&table.schema())?;
+        let table_id = table.get_id();
+        if let Some(point) = &self.plan.navigation {
+            table = table.navigate_to(point).await?;
+        }
+
+        let mut options = BTreeMap::new();
+        options.insert(
+            OPT_KEY_TABLE_DATABASE.to_string(),
+            self.plan.table_database.clone(),
+        );
+        options.insert(OPT_KEY_TABLE_NAME.to_string(), self.plan.table_name.clone());
+        options.insert(OPT_KEY_TABLE_ID.to_string(), table_id.to_string());
+        // the changes are tracked from the current snapshot of the table.
+        let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+        if let Some(location) = fuse_table.snapshot_loc().await? {
+            options.insert(OPT_KEY_SNAPSHOT_LOCATION.to_string(), location);
+        }
+
+        let req = CreateTableReq {
+            if_not_exists: self.plan.if_not_exists,
+            name_ident: TableNameIdent {
+                tenant: self.plan.tenant.clone(),
+                db_name: self.plan.database.clone(),
+                table_name: self.plan.stream_name.clone(),
+            },
+            table_meta: TableMeta {
+                engine: STREAM_ENGINE.to_string(),
+                catalog: self.plan.catalog.clone(),
+                schema: Arc::new(schema),
+                options,
+                ..Default::default()
+            },
+        };
+        catalog.create_table(req).await?;
+
+        // keep the snapshot at the offset from the gc of the table.
+        let stream = catalog
+            .get_table(
+                &self.plan.tenant,
+                &self.plan.database,
+                &self.plan.stream_name,
+            )
+            .await?;
+        fuse_table.register_stream(stream.get_id()).await?;
+
+        Ok(PipelineBuildResult::create())
+    }
+}
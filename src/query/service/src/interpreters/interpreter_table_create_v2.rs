@@ -19,12 +19,15 @@ use common_exception::Result;
 use common_expression::TableDataType;
 use common_expression::TableSchemaRefExt;
 use common_meta_app::schema::CreateTableReq;
+use common_meta_app::schema::DropTableByIdReq;
 use common_meta_app::schema::TableMeta;
 use common_meta_app::schema::TableNameIdent;
 use common_meta_types::MatchSeq;
 use common_sql::field_default_value;
+use common_sql::plans::CloneTableSource;
 use common_sql::plans::CreateTablePlanV2;
 use common_users::UserApiProvider;
+use tracing::error;
 
 use crate::interpreters::InsertInterpreterV2;
 use crate::interpreters::Interpreter;
@@ -101,6 +104,14 @@ impl Interpreter for CreateTableInterpreterV2 {
             }
         }
 
+        if let Some(clone_source) = &self.plan.clone_source {
+            if !name_not_duplicate && self.plan.if_not_exists {
+                // the table exists already, do not clone into it
+                return Ok(PipelineBuildResult::create());
+            }
+            return self.create_table_clone(clone_source).await;
+        }
+
         match &self.plan.as_select {
             Some(select_plan_node) => self.create_table_as_select(select_plan_node.clone()).await,
             None => self.create_table().await,
@@ -143,6 +154,44 @@ impl CreateTableInterpreterV2 {
             .await
     }
 
+    async fn create_table_clone(
+        &self,
+        clone_source: &CloneTableSource,
+    ) -> Result<PipelineBuildResult> {
+        let tenant = self.ctx.get_tenant();
+        let source_catalog = self.ctx.get_catalog(&clone_source.catalog)?;
+        let mut source = source_catalog
+            .get_table(tenant.as_str(), &clone_source.database, &clone_source.table)
+            .await?;
+        if let Some(point) = &clone_source.point {
+            source = source.navigate_to(point).await?;
+        }
+
+        let catalog = self.ctx.get_catalog(&self.plan.catalog)?;
+        catalog.create_table(self.build_request()?).await?;
+        let table = catalog
+            .get_table(tenant.as_str(), &self.plan.database, &self.plan.table)
+            .await?;
+
+        if let Err(cause) = table.clone_from_table(self.ctx.clone(), source).await {
+            // the table is created before the data is shared, drop it so that a failed
+            // clone does not leave an empty table behind.
+            let drop_req = DropTableByIdReq {
+                if_exists: true,
+                tb_id: table.get_id(),
+            };
+            if let Err(e) = catalog.drop_table_by_id(drop_req).await {
+                error!(
+                    "failed to drop table {} after the clone failed: {}",
+                    table.get_table_info().desc,
+                    e
+                );
+            }
+            return Err(cause);
+        }
+        Ok(PipelineBuildResult::create())
+    }
+
     async fn create_table(&self) -> Result<PipelineBuildResult> {
         let catalog = self.ctx.get_catalog(self.plan.catalog.as_str())?;
         catalog.create_table(self.build_request()?).await?;
@@ -19,6 +19,7 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_meta_app::schema::DropTableByIdReq;
 use common_sql::plans::DropTablePlan;
+use common_storages_fuse::stream::STREAM_ENGINE;
 use common_storages_view::view_table::VIEW_ENGINE;
 
 use crate::interpreters::Interpreter;
@@ -60,6 +61,12 @@ impl Interpreter for DropTableInterpreter {
                     &self.plan.database, &self.plan.table, &self.plan.database, &self.plan.table
                 )));
             }
+            if tbl.get_table_info().engine() == STREAM_ENGINE {
+                return Err(ErrorCode::TableEngineNotSupported(format!(
+                    "{}.{} engine is STREAM that doesn't support drop, use `DROP STREAM {}.{}` instead",
+                    &self.plan.database, &self.plan.table, &self.plan.database, &self.plan.table
+                )));
+            }
             let catalog = self.ctx.get_catalog(catalog_name)?;
 
             catalog
@@ -14,8 +14,10 @@
 
 use std::sync::Arc;
 
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_sql::plans::UndropTablePlan;
+use storages_common_table_meta::table::OPT_KEY_DATA_PURGED;
 
 use crate::interpreters::Interpreter;
 use crate::pipelines::PipelineBuildResult;
@@ -42,6 +44,27 @@ impl Interpreter for UndropTableInterpreter {
     async fn execute2(&self) -> Result<PipelineBuildResult> {
         let catalog_name = self.plan.catalog.as_str();
         let catalog = self.ctx.get_catalog(catalog_name)?;
+
+        // the latest table of the name is the one to be undropped, refuse it if its data
+        // has been purged beyond the retention period.
+        let latest = catalog
+            .list_tables_history(&self.plan.tenant, &self.plan.database)
+            .await?
+            .into_iter()
+            .filter(|table| table.name() == self.plan.table)
+            .max_by_key(|table| table.get_id());
+        if let Some(table) = latest {
+            let table_info = table.get_table_info();
+            if table_info.meta.drop_on.is_some()
+                && table_info.options().contains_key(OPT_KEY_DATA_PURGED)
+            {
+                return Err(ErrorCode::TableHistoricalDataNotFound(format!(
+                    "table {}.{} has been purged beyond its retention period, can not undrop it",
+                    self.plan.database, self.plan.table
+                )));
+            }
+        }
+
         catalog.undrop_table(self.plan.clone().into()).await?;
 
         Ok(PipelineBuildResult::create())
@@ -29,6 +29,8 @@ use common_sql::ColumnBinding;
 use common_sql::ScalarExpr;
 use common_sql::Visibility;
 
+use crate::interpreters::common::advance_stream_offsets;
+use crate::interpreters::common::check_stream_offsets;
 use crate::interpreters::Interpreter;
 use crate::pipelines::executor::ExecutorSettings;
 use crate::pipelines::executor::PipelineCompleteExecutor;
@@ -136,6 +138,9 @@ impl Interpreter for UpdateInterpreter {
             },
         )?;
 
+        // the streams read by the statement are consumed once it commits.
+        let streams = check_stream_offsets(&self.ctx).await?;
+
         let mut pipeline = Pipeline::create();
         tbl.update(
             self.ctx.clone(),
@@ -156,6 +161,7 @@ impl Interpreter for UpdateInterpreter {
             executor.execute()?;
             drop(executor);
         }
+        advance_stream_offsets(&self.ctx, &streams).await;
 
         Ok(PipelineBuildResult::create())
     }
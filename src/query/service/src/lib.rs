@@ -32,6 +32,9 @@ extern crate core;
 
 pub mod api;
 pub mod auth;
+pub mod auto_compaction;
+pub mod auto_purge;
+pub mod background;
 pub mod catalogs;
 pub mod clusters;
 pub mod databases;
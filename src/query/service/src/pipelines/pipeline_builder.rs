@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 
 use async_channel::Receiver;
@@ -31,14 +33,17 @@ use common_pipeline_core::processors::processor::ProcessorPtr;
 use common_pipeline_sinks::EmptySink;
 use common_pipeline_sinks::Sinker;
 use common_pipeline_sinks::UnionReceiveSink;
+use common_pipeline_sources::BlocksSource;
 use common_pipeline_transforms::processors::transforms::try_add_multi_sort_merge;
 use common_pipeline_transforms::processors::transforms::try_create_transform_sort_merge;
 use common_profile::ProfSpanSetRef;
 use common_sql::evaluator::BlockOperator;
 use common_sql::evaluator::CompoundBlockOperator;
+use common_sql::executor::AggregateExpand;
 use common_sql::executor::AggregateFinal;
 use common_sql::executor::AggregateFunctionDesc;
 use common_sql::executor::AggregatePartial;
+use common_sql::executor::CteScan;
 use common_sql::executor::DistributedInsertSelect;
 use common_sql::executor::EvalScalar;
 use common_sql::executor::ExchangeSink;
@@ -46,11 +51,19 @@ use common_sql::executor::ExchangeSource;
 use common_sql::executor::Filter;
 use common_sql::executor::HashJoin;
 use common_sql::executor::Limit;
+use common_sql::executor::MaterializedCte;
 use common_sql::executor::PhysicalPlan;
 use common_sql::executor::Project;
+use common_sql::executor::ProjectSet;
+use common_sql::executor::RecursiveCteScan;
+use common_sql::executor::RecursiveUnion;
+use common_sql::executor::SetOperation;
 use common_sql::executor::Sort;
+use common_sql::executor::SortMergeJoin;
 use common_sql::executor::TableScan;
 use common_sql::executor::UnionAll;
+use common_sql::executor::Window;
+use common_sql::executor::WindowFunction;
 use common_sql::plans::JoinType;
 use common_sql::ColumnBinding;
 use common_sql::IndexType;
@@ -58,12 +71,22 @@ use common_sql::IndexType;
 use super::processors::ProfileWrapper;
 use crate::pipelines::processors::transforms::efficiently_memory_final_aggregator;
 use crate::pipelines::processors::transforms::HashJoinDesc;
+use crate::pipelines::processors::transforms::MaterializedCteSink;
+use crate::pipelines::processors::transforms::MaterializedCteSource;
+use crate::pipelines::processors::transforms::MaterializedCteState;
+use crate::pipelines::processors::transforms::RecursiveCteWorkingTable;
 use crate::pipelines::processors::transforms::RightSemiAntiJoinCompactor;
+use crate::pipelines::processors::transforms::SetOperationState;
+use crate::pipelines::processors::transforms::SinkBuildSetOperation;
 use crate::pipelines::processors::transforms::TransformLeftJoin;
 use crate::pipelines::processors::transforms::TransformMarkJoin;
 use crate::pipelines::processors::transforms::TransformMergeBlock;
+use crate::pipelines::processors::transforms::TransformProjectSet;
+use crate::pipelines::processors::transforms::TransformRecursiveUnion;
 use crate::pipelines::processors::transforms::TransformRightJoin;
 use crate::pipelines::processors::transforms::TransformRightSemiAntiJoin;
+use crate::pipelines::processors::transforms::TransformSetOperationProbe;
+use crate::pipelines::processors::transforms::TransformSortMergeJoin;
 use crate::pipelines::processors::AggregatorParams;
 use crate::pipelines::processors::AggregatorTransformParams;
 use crate::pipelines::processors::JoinHashTable;
@@ -73,10 +96,14 @@ use crate::pipelines::processors::RightJoinCompactor;
 use crate::pipelines::processors::SinkBuildHashTable;
 use crate::pipelines::processors::TransformAggregator;
 use crate::pipelines::processors::TransformCastSchema;
+use crate::pipelines::processors::TransformEmptyGroupingSet;
+use crate::pipelines::processors::TransformExpandGroupingSets;
 use crate::pipelines::processors::TransformHashJoinProbe;
 use crate::pipelines::processors::TransformLimit;
 use crate::pipelines::processors::TransformResortAddOn;
 use crate::pipelines::processors::TransformSortPartial;
+use crate::pipelines::processors::TransformWindow;
+use crate::pipelines::processors::WindowFunctionImpl;
 use crate::pipelines::Pipeline;
 use crate::pipelines::PipelineBuildResult;
 use crate::sessions::QueryContext;
@@ -90,6 +117,11 @@ pub struct PipelineBuilder {
 
     enable_profiling: bool,
     prof_span_set: ProfSpanSetRef,
+
+    /// The working tables of the recursive common table expressions being executed.
+    recursive_cte_working_tables: HashMap<String, RecursiveCteWorkingTable>,
+    /// The results of the materialized common table expressions being executed.
+    materialized_cte_states: HashMap<IndexType, Arc<MaterializedCteState>>,
 }
 
 impl PipelineBuilder {
@@ -104,9 +136,27 @@ impl PipelineBuilder {
             pipelines: vec![],
             main_pipeline: Pipeline::create(),
             prof_span_set,
+            recursive_cte_working_tables: HashMap::new(),
+            materialized_cte_states: HashMap::new(),
         }
     }
 
+    pub fn with_recursive_cte_working_tables(
+        mut self,
+        working_tables: HashMap<String, RecursiveCteWorkingTable>,
+    ) -> PipelineBuilder {
+        self.recursive_cte_working_tables = working_tables;
+        self
+    }
+
+    pub fn with_materialized_cte_states(
+        mut self,
+        states: HashMap<IndexType, Arc<MaterializedCteState>>,
+    ) -> PipelineBuilder {
+        self.materialized_cte_states = states;
+        self
+    }
+
     pub fn finalize(mut self, plan: &PhysicalPlan) -> Result<PipelineBuildResult> {
         self.build_pipeline(plan)?;
 
@@ -131,14 +181,27 @@ impl PipelineBuilder {
             PhysicalPlan::Filter(filter) => self.build_filter(filter),
             PhysicalPlan::Project(project) => self.build_project(project),
             PhysicalPlan::EvalScalar(eval_scalar) => self.build_eval_scalar(eval_scalar),
+            PhysicalPlan::AggregateExpand(expand) => self.build_aggregate_expand(expand),
             PhysicalPlan::AggregatePartial(aggregate) => self.build_aggregate_partial(aggregate),
             PhysicalPlan::AggregateFinal(aggregate) => self.build_aggregate_final(aggregate),
             PhysicalPlan::Sort(sort) => self.build_sort(sort),
+            PhysicalPlan::Window(window) => self.build_window(window),
             PhysicalPlan::Limit(limit) => self.build_limit(limit),
             PhysicalPlan::HashJoin(join) => self.build_join(join),
+            PhysicalPlan::SortMergeJoin(join) => self.build_sort_merge_join(join),
             PhysicalPlan::ExchangeSink(sink) => self.build_exchange_sink(sink),
             PhysicalPlan::ExchangeSource(source) => self.build_exchange_source(source),
             PhysicalPlan::UnionAll(union_all) => self.build_union_all(union_all),
+            PhysicalPlan::RecursiveUnion(recursive_union) => {
+                self.build_recursive_union(recursive_union)
+            }
+            PhysicalPlan::RecursiveCteScan(scan) => self.build_recursive_cte_scan(scan),
+            PhysicalPlan::MaterializedCte(materialized_cte) => {
+                self.build_materialized_cte(materialized_cte)
+            }
+            PhysicalPlan::CteScan(scan) => self.build_cte_scan(scan),
+            PhysicalPlan::SetOperation(set_operation) => self.build_set_operation(set_operation),
+            PhysicalPlan::ProjectSet(project_set) => self.build_project_set(project_set),
             PhysicalPlan::DistributedInsertSelect(insert_select) => {
                 self.build_distributed_insert_select(insert_select)
             }
@@ -175,7 +238,9 @@ impl PipelineBuilder {
             build_side_context,
             self.enable_profiling,
             self.prof_span_set.clone(),
-        );
+        )
+        .with_recursive_cte_working_tables(self.recursive_cte_working_tables.clone())
+        .with_materialized_cte_states(self.materialized_cte_states.clone());
         let mut build_res = build_side_builder.finalize(build)?;
 
         assert!(build_res.main_pipeline.is_pulling_pipeline()?);
@@ -357,6 +422,84 @@ impl PipelineBuilder {
         Ok(())
     }
 
+    fn build_project_set(&mut self, project_set: &ProjectSet) -> Result<()> {
+        self.build_pipeline(&project_set.input)?;
+
+        let args = project_set
+            .args
+            .iter()
+            .map(|arg| arg.as_expr(&BUILTIN_FUNCTIONS))
+            .collect::<Vec<_>>();
+        let columns = project_set
+            .columns
+            .iter()
+            .map(|(_, data_type)| data_type.clone())
+            .collect::<Vec<_>>();
+        let func_ctx = self.ctx.get_function_context()?;
+
+        self.main_pipeline.add_transform(|input, output| {
+            let transform = TransformProjectSet::try_create(
+                input,
+                output,
+                func_ctx,
+                project_set.srf.clone(),
+                args.clone(),
+                columns.clone(),
+                project_set.outer,
+            )?;
+
+            if self.enable_profiling {
+                Ok(ProcessorPtr::create(ProfileWrapper::create(
+                    transform,
+                    project_set.plan_id,
+                    self.prof_span_set.clone(),
+                )))
+            } else {
+                Ok(ProcessorPtr::create(transform))
+            }
+        })
+    }
+
+    fn build_aggregate_expand(&mut self, expand: &AggregateExpand) -> Result<()> {
+        self.build_pipeline(&expand.input)?;
+
+        let input_schema = expand.input.output_schema()?;
+        let group_bys = expand
+            .group_bys
+            .iter()
+            .map(|index| input_schema.index_of(&index.to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        // Convert the column indices of grouping sets to positions in `group_bys`.
+        let grouping_sets = expand
+            .grouping_sets
+            .iter()
+            .map(|set| {
+                set.iter()
+                    .filter_map(|index| expand.group_bys.iter().position(|i| i == index))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        self.main_pipeline.add_transform(|input, output| {
+            let transform = TransformExpandGroupingSets::try_create(
+                input,
+                output,
+                group_bys.clone(),
+                grouping_sets.clone(),
+            )?;
+
+            if self.enable_profiling {
+                Ok(ProcessorPtr::create(ProfileWrapper::create(
+                    transform,
+                    expand.plan_id,
+                    self.prof_span_set.clone(),
+                )))
+            } else {
+                Ok(ProcessorPtr::create(transform))
+            }
+        })
+    }
+
     fn build_aggregate_partial(&mut self, aggregate: &AggregatePartial) -> Result<()> {
         self.build_pipeline(&aggregate.input)?;
         let params = Self::build_aggregator_params(
@@ -408,26 +551,45 @@ impl PipelineBuilder {
         )?;
 
         if self.enable_memory_efficient_aggregator(&params) {
-            return efficiently_memory_final_aggregator(params, &mut self.main_pipeline);
+            efficiently_memory_final_aggregator(params.clone(), &mut self.main_pipeline)?;
+        } else {
+            self.main_pipeline.resize(1)?;
+            self.main_pipeline.add_transform(|input, output| {
+                let transform = TransformAggregator::try_create_final(
+                    self.ctx.clone(),
+                    AggregatorTransformParams::try_create(input, output, &params)?,
+                )?;
+
+                if self.enable_profiling {
+                    Ok(ProcessorPtr::create(ProfileWrapper::create(
+                        transform,
+                        aggregate.plan_id,
+                        self.prof_span_set.clone(),
+                    )))
+                } else {
+                    Ok(ProcessorPtr::create(transform))
+                }
+            })?;
         }
 
-        self.main_pipeline.resize(1)?;
-        self.main_pipeline.add_transform(|input, output| {
-            let transform = TransformAggregator::try_create_final(
-                self.ctx.clone(),
-                AggregatorTransformParams::try_create(input, output, &params)?,
-            )?;
+        if let Some(grouping_id) = aggregate.empty_grouping_id {
+            let output_schema = aggregate.output_schema()?;
+            let group_data_types = output_schema.fields()[aggregate.agg_funcs.len()..]
+                .iter()
+                .map(|field| field.data_type().clone())
+                .collect::<Vec<_>>();
 
-            if self.enable_profiling {
-                Ok(ProcessorPtr::create(ProfileWrapper::create(
-                    transform,
-                    aggregate.plan_id,
-                    self.prof_span_set.clone(),
-                )))
-            } else {
-                Ok(ProcessorPtr::create(transform))
-            }
-        })?;
+            self.main_pipeline.resize(1)?;
+            self.main_pipeline.add_transform(|input, output| {
+                Ok(ProcessorPtr::create(TransformEmptyGroupingSet::try_create(
+                    input,
+                    output,
+                    params.clone(),
+                    group_data_types.clone(),
+                    grouping_id,
+                )?))
+            })?;
+        }
 
         Ok(())
     }
@@ -497,6 +659,16 @@ impl PipelineBuilder {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        self.build_sort_pipeline(input_schema, sort_desc, sort.plan_id, sort.limit)
+    }
+
+    fn build_sort_pipeline(
+        &mut self,
+        input_schema: DataSchemaRef,
+        sort_desc: Vec<SortColumnDescription>,
+        plan_id: u32,
+        limit: Option<usize>,
+    ) -> Result<()> {
         let max_threads = self.ctx.get_settings().get_max_threads()? as usize;
         let block_size = self.ctx.get_settings().get_max_block_size()? as usize;
 
@@ -508,12 +680,12 @@ impl PipelineBuilder {
         // Sort
         self.main_pipeline.add_transform(|input, output| {
             let transform =
-                TransformSortPartial::try_create(input, output, sort.limit, sort_desc.clone())?;
+                TransformSortPartial::try_create(input, output, limit, sort_desc.clone())?;
 
             if self.enable_profiling {
                 Ok(ProcessorPtr::create(ProfileWrapper::create(
                     transform,
-                    sort.plan_id,
+                    plan_id,
                     self.prof_span_set.clone(),
                 )))
             } else {
@@ -528,14 +700,14 @@ impl PipelineBuilder {
                 output,
                 input_schema.clone(),
                 block_size,
-                sort.limit,
+                limit,
                 sort_desc.clone(),
             )?;
 
             if self.enable_profiling {
                 Ok(ProcessorPtr::create(ProfileWrapper::create(
                     transform,
-                    sort.plan_id,
+                    plan_id,
                     self.prof_span_set.clone(),
                 )))
             } else {
@@ -548,11 +720,113 @@ impl PipelineBuilder {
             &mut self.main_pipeline,
             input_schema,
             block_size,
-            sort.limit,
+            limit,
             sort_desc,
         )
     }
 
+    fn build_window(&mut self, window: &Window) -> Result<()> {
+        self.build_pipeline(&window.input)?;
+
+        let input_schema = window.input.output_schema()?;
+        let offset_of = |index: &IndexType| input_schema.index_of(&index.to_string());
+
+        let partition_by = window
+            .partition_by
+            .iter()
+            .map(offset_of)
+            .collect::<Result<Vec<_>>>()?;
+
+        let order_by = window
+            .order_by
+            .iter()
+            .map(|desc| {
+                Ok(SortColumnDescription {
+                    offset: offset_of(&desc.order_by)?,
+                    asc: desc.asc,
+                    nulls_first: desc.nulls_first,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Sort by partition keys and then order keys, so that rows of a partition are adjacent
+        let sort_desc = partition_by
+            .iter()
+            .map(|offset| SortColumnDescription {
+                offset: *offset,
+                asc: true,
+                nulls_first: false,
+            })
+            .chain(order_by.iter().cloned())
+            .collect::<Vec<_>>();
+        if !sort_desc.is_empty() {
+            self.build_sort_pipeline(input_schema.clone(), sort_desc, window.plan_id, None)?;
+        }
+
+        let func = match &window.func {
+            WindowFunction::Aggregate(agg) => {
+                let params = agg
+                    .sig
+                    .params
+                    .iter()
+                    .map(|p| p.clone().into_scalar())
+                    .collect();
+                WindowFunctionImpl::Aggregate {
+                    agg: AggregateFunctionFactory::instance().get(
+                        agg.sig.name.as_str(),
+                        params,
+                        agg.sig.args.clone(),
+                    )?,
+                    args: agg.args.clone(),
+                }
+            }
+            WindowFunction::RowNumber => WindowFunctionImpl::RowNumber,
+            WindowFunction::Rank => WindowFunctionImpl::Rank,
+            WindowFunction::DenseRank => WindowFunctionImpl::DenseRank,
+            WindowFunction::Lag(func) => WindowFunctionImpl::Lag {
+                arg: offset_of(&func.arg)?,
+                offset: func.offset as usize,
+                default: func.default.as_ref().map(offset_of).transpose()?,
+            },
+            WindowFunction::Lead(func) => WindowFunctionImpl::Lead {
+                arg: offset_of(&func.arg)?,
+                offset: func.offset as usize,
+                default: func.default.as_ref().map(offset_of).transpose()?,
+            },
+            WindowFunction::FirstValue(func) => WindowFunctionImpl::FirstValue {
+                arg: offset_of(&func.arg)?,
+            },
+            WindowFunction::LastValue(func) => WindowFunctionImpl::LastValue {
+                arg: offset_of(&func.arg)?,
+            },
+        };
+
+        // Window function must be evaluated in a single thread
+        self.main_pipeline.resize(1)?;
+
+        self.main_pipeline.add_transform(|input, output| {
+            let transform = TransformWindow::try_create(
+                input,
+                output,
+                func.clone(),
+                window.func.data_type(),
+                partition_by.clone(),
+                order_by.clone(),
+                window.window_frame.clone(),
+            )?;
+
+            if self.enable_profiling {
+                Ok(ProcessorPtr::create(ProfileWrapper::create(
+                    transform,
+                    window.plan_id,
+                    self.prof_span_set.clone(),
+                )))
+            } else {
+                Ok(ProcessorPtr::create(transform))
+            }
+        })
+    }
+
     fn build_limit(&mut self, limit: &Limit) -> Result<()> {
         self.build_pipeline(&limit.input)?;
 
@@ -710,7 +984,9 @@ impl PipelineBuilder {
     ) -> Result<Receiver<DataBlock>> {
         let union_ctx = QueryContext::create_from(self.ctx.clone());
         let pipeline_builder =
-            PipelineBuilder::create(union_ctx, self.enable_profiling, self.prof_span_set.clone());
+            PipelineBuilder::create(union_ctx, self.enable_profiling, self.prof_span_set.clone())
+                .with_recursive_cte_working_tables(self.recursive_cte_working_tables.clone())
+                .with_materialized_cte_states(self.materialized_cte_states.clone());
         let mut build_res = pipeline_builder.finalize(input)?;
 
         assert!(build_res.main_pipeline.is_pulling_pipeline()?);
@@ -764,6 +1040,273 @@ impl PipelineBuilder {
         Ok(())
     }
 
+    /// Build the right side of a sort merge join in a sub-pipeline, the sorted blocks
+    /// are sent through a bounded channel, so the right side only runs ahead of the
+    /// merge by a few blocks.
+    fn expand_sort_merge_join_right(
+        &mut self,
+        join: &SortMergeJoin,
+    ) -> Result<Receiver<DataBlock>> {
+        let right_ctx = QueryContext::create_from(self.ctx.clone());
+        let pipeline_builder =
+            PipelineBuilder::create(right_ctx, self.enable_profiling, self.prof_span_set.clone())
+                .with_recursive_cte_working_tables(self.recursive_cte_working_tables.clone())
+                .with_materialized_cte_states(self.materialized_cte_states.clone());
+        let mut build_res = pipeline_builder.finalize(&join.right)?;
+
+        assert!(build_res.main_pipeline.is_pulling_pipeline()?);
+
+        // The blocks must be received in order.
+        build_res.main_pipeline.resize(1)?;
+        let (tx, rx) = async_channel::bounded(2);
+
+        build_res.main_pipeline.add_sink(|input_port| {
+            let transform = UnionReceiveSink::create(Some(tx.clone()), input_port);
+
+            if self.enable_profiling {
+                Ok(ProcessorPtr::create(ProfileWrapper::create(
+                    transform,
+                    join.plan_id,
+                    self.prof_span_set.clone(),
+                )))
+            } else {
+                Ok(ProcessorPtr::create(transform))
+            }
+        })?;
+
+        self.pipelines.push(build_res.main_pipeline);
+        self.pipelines
+            .extend(build_res.sources_pipelines.into_iter());
+        Ok(rx)
+    }
+
+    pub fn build_sort_merge_join(&mut self, join: &SortMergeJoin) -> Result<()> {
+        self.build_pipeline(&join.left)?;
+        let receiver = self.expand_sort_merge_join_right(join)?;
+
+        let left_keys = join
+            .left_keys
+            .iter()
+            .map(|key| key.as_expr(&BUILTIN_FUNCTIONS))
+            .collect::<Vec<_>>();
+        let right_keys = join
+            .right_keys
+            .iter()
+            .map(|key| key.as_expr(&BUILTIN_FUNCTIONS))
+            .collect::<Vec<_>>();
+        let other_predicate = join
+            .non_equi_conditions
+            .iter()
+            .map(|expr| expr.as_expr(&BUILTIN_FUNCTIONS))
+            .try_reduce(|lhs, rhs| {
+                check_function(None, "and", &[], &[lhs, rhs], &BUILTIN_FUNCTIONS)
+            })?;
+        let func_ctx = self.ctx.get_function_context()?;
+
+        // The blocks of the left side must be merged in order.
+        self.main_pipeline.resize(1)?;
+        self.main_pipeline.add_transform(|input, output| {
+            let transform = TransformSortMergeJoin::try_create(
+                input,
+                output,
+                receiver.clone(),
+                func_ctx,
+                left_keys.clone(),
+                right_keys.clone(),
+                other_predicate.clone(),
+            )?;
+
+            if self.enable_profiling {
+                Ok(ProcessorPtr::create(ProfileWrapper::create(
+                    transform,
+                    join.plan_id,
+                    self.prof_span_set.clone(),
+                )))
+            } else {
+                Ok(ProcessorPtr::create(transform))
+            }
+        })
+    }
+
+    pub fn build_recursive_union(&mut self, recursive_union: &RecursiveUnion) -> Result<()> {
+        // The iterations are executed one after another, so a single source is enough.
+        self.main_pipeline.add_source(
+            |output| {
+                TransformRecursiveUnion::try_create(
+                    self.ctx.clone(),
+                    output,
+                    recursive_union.clone(),
+                    self.enable_profiling,
+                    self.prof_span_set.clone(),
+                    self.recursive_cte_working_tables.clone(),
+                    self.materialized_cte_states.clone(),
+                )
+            },
+            1,
+        )
+    }
+
+    pub fn build_recursive_cte_scan(&mut self, scan: &RecursiveCteScan) -> Result<()> {
+        let working_table = self
+            .recursive_cte_working_tables
+            .get(&scan.cte_name)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorCode::Internal(format!(
+                    "Cannot find the working table of recursive cte {}",
+                    scan.cte_name
+                ))
+            })?;
+        self.main_pipeline.add_source(
+            |output| BlocksSource::create(self.ctx.clone(), output, working_table.clone()),
+            1,
+        )
+    }
+
+    pub fn build_materialized_cte(&mut self, materialized_cte: &MaterializedCte) -> Result<()> {
+        let state = MaterializedCteState::try_create(self.ctx.clone())?;
+        let left_side_context = QueryContext::create_from(self.ctx.clone());
+        let left_side_builder = PipelineBuilder::create(
+            left_side_context,
+            self.enable_profiling,
+            self.prof_span_set.clone(),
+        )
+        .with_recursive_cte_working_tables(self.recursive_cte_working_tables.clone())
+        .with_materialized_cte_states(self.materialized_cte_states.clone());
+        let mut build_res = left_side_builder.finalize(&materialized_cte.left)?;
+
+        let left_schema = materialized_cte.left.output_schema()?;
+        let projections = materialized_cte
+            .columns
+            .iter()
+            .map(|index| left_schema.index_of(&index.to_string()))
+            .collect::<Result<Vec<_>>>()?;
+
+        // The CTE is executed only once, and its result is written to the state,
+        // then read by all the `CteScan`s of the right side.
+        assert!(build_res.main_pipeline.is_pulling_pipeline()?);
+        build_res.main_pipeline.add_sink(|input| {
+            let sink = MaterializedCteSink::create(input, state.clone(), projections.clone());
+
+            if self.enable_profiling {
+                Ok(ProcessorPtr::create(ProfileWrapper::create(
+                    sink,
+                    materialized_cte.plan_id,
+                    self.prof_span_set.clone(),
+                )))
+            } else {
+                Ok(ProcessorPtr::create(sink))
+            }
+        })?;
+
+        self.pipelines.push(build_res.main_pipeline);
+        self.pipelines
+            .extend(build_res.sources_pipelines.into_iter());
+
+        self.materialized_cte_states
+            .insert(materialized_cte.cte_idx, state);
+        self.build_pipeline(&materialized_cte.right)
+    }
+
+    pub fn build_cte_scan(&mut self, scan: &CteScan) -> Result<()> {
+        let state = self
+            .materialized_cte_states
+            .get(&scan.cte_idx)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorCode::Internal(format!(
+                    "Cannot find the result of materialized cte {}",
+                    scan.cte_name
+                ))
+            })?;
+        let cursor = Arc::new(AtomicUsize::new(0));
+        let max_threads = self.ctx.get_settings().get_max_threads()? as usize;
+        self.main_pipeline.add_source(
+            |output| {
+                MaterializedCteSource::create(
+                    self.ctx.clone(),
+                    output,
+                    state.clone(),
+                    cursor.clone(),
+                )
+            },
+            max_threads,
+        )
+    }
+
+    pub fn build_set_operation(&mut self, set_operation: &SetOperation) -> Result<()> {
+        let left_schema = set_operation.left.output_schema()?;
+        let key_types = set_operation
+            .pairs
+            .iter()
+            .map(|(left, _)| Ok(left_schema.field_with_name(left)?.data_type().clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let state = SetOperationState::try_create(set_operation.op_type.clone(), &key_types)?;
+        let right_side_context = QueryContext::create_from(self.ctx.clone());
+        let right_side_builder = PipelineBuilder::create(
+            right_side_context,
+            self.enable_profiling,
+            self.prof_span_set.clone(),
+        )
+        .with_recursive_cte_working_tables(self.recursive_cte_working_tables.clone())
+        .with_materialized_cte_states(self.materialized_cte_states.clone());
+        let mut build_res = right_side_builder.finalize(&set_operation.right)?;
+
+        let right_schema = set_operation.right.output_schema()?;
+        let right_projections = set_operation
+            .pairs
+            .iter()
+            .map(|(_, right)| right_schema.index_of(right))
+            .collect::<Result<Vec<_>>>()?;
+
+        // The rows of the right side are counted first, then the rows of the left
+        // side are matched against the counts.
+        assert!(build_res.main_pipeline.is_pulling_pipeline()?);
+        build_res.main_pipeline.add_sink(|input| {
+            let sink =
+                SinkBuildSetOperation::create(input, state.clone(), right_projections.clone());
+
+            if self.enable_profiling {
+                Ok(ProcessorPtr::create(ProfileWrapper::create(
+                    sink,
+                    set_operation.plan_id,
+                    self.prof_span_set.clone(),
+                )))
+            } else {
+                Ok(ProcessorPtr::create(sink))
+            }
+        })?;
+
+        self.pipelines.push(build_res.main_pipeline);
+        self.pipelines
+            .extend(build_res.sources_pipelines.into_iter());
+
+        self.build_pipeline(&set_operation.left)?;
+        let left_projections = set_operation
+            .pairs
+            .iter()
+            .map(|(left, _)| left_schema.index_of(left))
+            .collect::<Result<Vec<_>>>()?;
+        self.main_pipeline.add_transform(|input, output| {
+            let transform = TransformSetOperationProbe::create(
+                input,
+                output,
+                state.clone(),
+                left_projections.clone(),
+            );
+
+            if self.enable_profiling {
+                Ok(ProcessorPtr::create(ProfileWrapper::create(
+                    transform,
+                    set_operation.plan_id,
+                    self.prof_span_set.clone(),
+                )))
+            } else {
+                Ok(ProcessorPtr::create(transform))
+            }
+        })
+    }
+
     pub fn build_distributed_insert_select(
         &mut self,
         insert_select: &DistributedInsertSelect,
@@ -37,7 +37,11 @@ pub use transforms::TransformCastSchema;
 pub use transforms::TransformCompact;
 pub use transforms::TransformCreateSets;
 pub use transforms::TransformDummy;
+pub use transforms::TransformEmptyGroupingSet;
+pub use transforms::TransformExpandGroupingSets;
 pub use transforms::TransformHashJoinProbe;
 pub use transforms::TransformLimit;
 pub use transforms::TransformResortAddOn;
 pub use transforms::TransformSortPartial;
+pub use transforms::TransformWindow;
+pub use transforms::WindowFunctionImpl;
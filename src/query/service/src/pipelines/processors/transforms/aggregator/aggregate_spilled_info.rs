@@ -0,0 +1,83 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use common_expression::BlockMetaInfo;
+use common_expression::BlockMetaInfoPtr;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::pipelines::processors::transforms::aggregator::OverflowInfo;
+use crate::pipelines::processors::transforms::BlockSpiller;
+
+/// The partitions spilled by a partial aggregator, they are read back bucket by bucket
+/// in the final aggregation. The spilled file is removed once the spiller is dropped,
+/// so it's kept alive by the meta until all the buckets are merged.
+pub struct AggregateSpilledInfo {
+    pub spiller: Arc<BlockSpiller>,
+    pub overflow: OverflowInfo,
+}
+
+impl AggregateSpilledInfo {
+    pub fn create(spiller: Arc<BlockSpiller>, overflow: OverflowInfo) -> BlockMetaInfoPtr {
+        Box::new(AggregateSpilledInfo { spiller, overflow })
+    }
+}
+
+impl Debug for AggregateSpilledInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AggregateSpilledInfo")
+            .field("overflow", &self.overflow)
+            .finish()
+    }
+}
+
+impl Serialize for AggregateSpilledInfo {
+    fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        unreachable!("AggregateSpilledInfo does not support exchanging between multiple nodes")
+    }
+}
+
+impl<'de> Deserialize<'de> for AggregateSpilledInfo {
+    fn deserialize<D>(_: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        unreachable!("AggregateSpilledInfo does not support exchanging between multiple nodes")
+    }
+}
+
+#[typetag::serde(name = "aggregate_spilled_info")]
+impl BlockMetaInfo for AggregateSpilledInfo {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_self(&self) -> Box<dyn BlockMetaInfo> {
+        unimplemented!("Unimplemented clone for AggregateSpilledInfo")
+    }
+
+    fn equals(&self, _: &Box<dyn BlockMetaInfo>) -> bool {
+        unimplemented!("Unimplemented equals for AggregateSpilledInfo")
+    }
+}
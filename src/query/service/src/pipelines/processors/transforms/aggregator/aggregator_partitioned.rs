@@ -33,6 +33,7 @@ use crate::pipelines::processors::transforms::aggregator::aggregate_info::Aggreg
 use crate::pipelines::processors::transforms::aggregator::aggregator_final_parallel::ParallelFinalAggregator;
 use crate::pipelines::processors::transforms::aggregator::AggregateHashStateInfo;
 use crate::pipelines::processors::transforms::aggregator::PartialAggregator;
+use crate::pipelines::processors::transforms::group_by::Area;
 use crate::pipelines::processors::transforms::group_by::HashMethodBounds;
 use crate::pipelines::processors::transforms::group_by::KeysColumnBuilder;
 use crate::pipelines::processors::transforms::group_by::PartitionedHashMethod;
@@ -68,6 +69,21 @@ where Self: Aggregator + Send
             Self::NAME
         )))
     }
+
+    fn get_partitioned_state_bytes(_agg: &Self::PartitionedAggregator) -> usize {
+        0
+    }
+
+    /// Serialize all the partitions to blocks and release their memory, the blocks
+    /// are returned with the bucket of their partitions.
+    fn spill_partitioned_blocks(
+        _agg: &mut Self::PartitionedAggregator,
+    ) -> Result<Vec<(usize, DataBlock)>> {
+        Err(ErrorCode::Unimplemented(format!(
+            "Spilling partitioned aggregator is unimplemented for {}",
+            Self::NAME
+        )))
+    }
 }
 
 impl<Method: HashMethodBounds, const HAS_AGG: bool> PartitionedAggregatorLike
@@ -128,37 +144,6 @@ impl<Method: HashMethodBounds, const HAS_AGG: bool> PartitionedAggregatorLike
     }
 
     fn convert_partitioned_block(agg: &mut Self::PartitionedAggregator) -> Result<Vec<DataBlock>> {
-        let mut data_blocks = Vec::with_capacity(256);
-
-        fn clear_table<T: HashtableLike<Value = usize>>(table: &mut T, params: &AggregatorParams) {
-            let aggregate_functions = &params.aggregate_functions;
-            let offsets_aggregate_states = &params.offsets_aggregate_states;
-
-            let functions = aggregate_functions
-                .iter()
-                .filter(|p| p.need_manual_drop_state())
-                .collect::<Vec<_>>();
-
-            let states = offsets_aggregate_states
-                .iter()
-                .enumerate()
-                .filter(|(idx, _)| aggregate_functions[*idx].need_manual_drop_state())
-                .map(|(_, s)| *s)
-                .collect::<Vec<_>>();
-
-            if !states.is_empty() {
-                for group_entity in table.iter() {
-                    let place = Into::<StateAddr>::into(*group_entity.get());
-
-                    for (function, state_offset) in functions.iter().zip(states.iter()) {
-                        unsafe { function.drop_state(place.next(*state_offset)) }
-                    }
-                }
-            }
-
-            table.clear();
-        }
-
         for (bucket, inner_table) in agg.hash_table.iter_tables_mut().enumerate() {
             if inner_table.len() == 0 {
                 continue;
@@ -178,71 +163,126 @@ impl<Method: HashMethodBounds, const HAS_AGG: bool> PartitionedAggregatorLike
                 return Ok(vec![block]);
             }
 
-            let capacity = inner_table.len();
-            let iterator = inner_table.iter();
+            let block = serialize_partition(&agg.method.method, &agg.params, inner_table)?;
+            let block = block.add_meta(Some(AggregateInfo::create(bucket as isize)))?;
 
-            let aggregator_params = agg.params.as_ref();
-            let funcs = &aggregator_params.aggregate_functions;
-            let aggr_len = funcs.len();
-            let offsets_aggregate_states = &aggregator_params.offsets_aggregate_states;
-
-            // Builders.
-            let mut state_builders: Vec<StringColumnBuilder> = (0..aggr_len)
-                .map(|_| StringColumnBuilder::with_capacity(capacity, capacity * 4))
-                .collect();
+            // streaming return Partitioned blocks by bucket
+            return Ok(vec![block]);
+        }
 
-            let value_size = estimated_key_size(inner_table);
-            let mut group_key_builder = agg.method.keys_column_builder(capacity, value_size);
+        if !agg.pass_state_to_final {
+            drop(agg.area.take());
+            drop(agg.area_holder.take());
+        }
 
-            for group_entity in iterator {
-                let place = Into::<StateAddr>::into(*group_entity.get());
+        Ok(vec![])
+    }
 
-                for (idx, func) in funcs.iter().enumerate() {
-                    let arg_place = place.next(offsets_aggregate_states[idx]);
-                    func.serialize(arg_place, &mut state_builders[idx].data)?;
-                    state_builders[idx].commit_row();
-                }
+    fn get_partitioned_state_bytes(agg: &Self::PartitionedAggregator) -> usize {
+        let area_bytes = agg.area.as_ref().map(Area::allocated_bytes).unwrap_or(0);
+        agg.hash_table.bytes_len() + area_bytes
+    }
 
-                group_key_builder.append_value(group_entity.key());
+    fn spill_partitioned_blocks(
+        agg: &mut Self::PartitionedAggregator,
+    ) -> Result<Vec<(usize, DataBlock)>> {
+        let mut blocks = Vec::new();
+        for (bucket, inner_table) in agg.hash_table.iter_tables_mut().enumerate() {
+            if inner_table.len() != 0 {
+                let block = serialize_partition(&agg.method.method, &agg.params, inner_table)?;
+                blocks.push((bucket, block));
             }
+        }
 
-            let mut columns = Vec::with_capacity(state_builders.len() + 1);
-            for builder in state_builders.into_iter() {
-                let col = builder.build();
-                columns.push(BlockEntry {
-                    value: Value::Column(Column::String(col)),
-                    data_type: DataType::String,
-                });
-            }
+        // All the states have been dropped, so the arena can be released.
+        if agg.area_holder.is_none() {
+            agg.area = Some(Area::create());
+        }
+        Ok(blocks)
+    }
+}
 
-            let col = group_key_builder.finish();
-            let num_rows = col.len();
-            let group_key_type = col.data_type();
+/// Serialize the aggregate states and the keys of a partition into a block,
+/// then drop the states and clear the partition.
+fn serialize_partition<Method: HashMethodBounds>(
+    method: &Method,
+    params: &AggregatorParams,
+    table: &mut Method::HashTable<usize>,
+) -> Result<DataBlock> {
+    let capacity = table.len();
+    let funcs = &params.aggregate_functions;
+    let aggr_len = funcs.len();
+    let offsets_aggregate_states = &params.offsets_aggregate_states;
+
+    // Builders.
+    let mut state_builders: Vec<StringColumnBuilder> = (0..aggr_len)
+        .map(|_| StringColumnBuilder::with_capacity(capacity, capacity * 4))
+        .collect();
+
+    let value_size = estimated_key_size(table);
+    let mut group_key_builder = method.keys_column_builder(capacity, value_size);
+
+    for group_entity in table.iter() {
+        let place = Into::<StateAddr>::into(*group_entity.get());
+
+        for (idx, func) in funcs.iter().enumerate() {
+            let arg_place = place.next(offsets_aggregate_states[idx]);
+            func.serialize(arg_place, &mut state_builders[idx].data)?;
+            state_builders[idx].commit_row();
+        }
 
-            columns.push(BlockEntry {
-                value: Value::Column(col),
-                data_type: group_key_type,
-            });
+        group_key_builder.append_value(group_entity.key());
+    }
 
-            data_blocks.push(DataBlock::new_with_meta(
-                columns,
-                num_rows,
-                Some(AggregateInfo::create(bucket as isize)),
-            ));
+    let mut columns = Vec::with_capacity(state_builders.len() + 1);
+    for builder in state_builders.into_iter() {
+        let col = builder.build();
+        columns.push(BlockEntry {
+            value: Value::Column(Column::String(col)),
+            data_type: DataType::String,
+        });
+    }
 
-            clear_table(inner_table, &agg.params);
+    let col = group_key_builder.finish();
+    let num_rows = col.len();
+    let group_key_type = col.data_type();
 
-            // streaming return Partitioned blocks by bucket
-            return Ok(data_blocks);
-        }
+    columns.push(BlockEntry {
+        value: Value::Column(col),
+        data_type: group_key_type,
+    });
 
-        if !agg.pass_state_to_final {
-            drop(agg.area.take());
-            drop(agg.area_holder.take());
-        }
+    clear_table(table, params);
+    Ok(DataBlock::new(columns, num_rows))
+}
 
-        Ok(data_blocks)
+fn clear_table<T: HashtableLike<Value = usize>>(table: &mut T, params: &AggregatorParams) {
+    let aggregate_functions = &params.aggregate_functions;
+    let offsets_aggregate_states = &params.offsets_aggregate_states;
+
+    let functions = aggregate_functions
+        .iter()
+        .filter(|p| p.need_manual_drop_state())
+        .collect::<Vec<_>>();
+
+    let states = offsets_aggregate_states
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| aggregate_functions[*idx].need_manual_drop_state())
+        .map(|(_, s)| *s)
+        .collect::<Vec<_>>();
+
+    if !states.is_empty() {
+        for group_entity in table.iter() {
+            let place = Into::<StateAddr>::into(*group_entity.get());
+
+            for (function, state_offset) in functions.iter().zip(states.iter()) {
+                unsafe { function.drop_state(place.next(*state_offset)) }
+            }
+        }
     }
+
+    table.clear();
 }
 
 impl<Method: HashMethodBounds, const HAS_AGG: bool> PartitionedAggregatorLike
@@ -273,3 +313,13 @@ impl<T: PartitionedAggregatorLike> Aggregator for PartitionedAggregator<T> {
         T::convert_partitioned_block(&mut self.inner)
     }
 }
+
+impl<T: PartitionedAggregatorLike> PartitionedAggregator<T> {
+    pub fn get_state_bytes(&self) -> usize {
+        T::get_partitioned_state_bytes(&self.inner)
+    }
+
+    pub fn spill_blocks(&mut self) -> Result<Vec<(usize, DataBlock)>> {
+        T::spill_partitioned_blocks(&mut self.inner)
+    }
+}
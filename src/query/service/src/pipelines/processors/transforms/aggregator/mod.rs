@@ -14,6 +14,7 @@
 
 mod aggregate_hashstate_info;
 mod aggregate_info;
+mod aggregate_spilled_info;
 mod aggregator_final_parallel;
 mod aggregator_params;
 mod aggregator_partial;
@@ -24,6 +25,7 @@ mod utils;
 pub use aggregate_hashstate_info::AggregateHashStateInfo;
 pub use aggregate_info::AggregateInfo;
 pub use aggregate_info::OverflowInfo;
+pub use aggregate_spilled_info::AggregateSpilledInfo;
 pub use aggregator_final_parallel::BucketAggregator;
 pub use aggregator_final_parallel::ParallelFinalAggregator;
 pub use aggregator_params::AggregatorParams;
@@ -0,0 +1,174 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::runtime::GlobalIORuntime;
+use common_base::runtime::TrySpawn;
+use common_catalog::plan::SpilledBlockReader;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::utils::arrow::deserialize_column;
+use common_expression::utils::arrow::serialize_column;
+use common_expression::BlockEntry;
+use common_expression::DataBlock;
+use common_expression::Value;
+use opendal::Operator;
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+
+const SPILL_PREFIX: &str = "_query_spill";
+
+/// Spill blocks to the storage, and read them back during the same query.
+///
+/// The spilled files are removed when the spiller is dropped.
+pub struct BlockSpiller {
+    operator: Operator,
+    location_prefix: String,
+    spilled_locations: Mutex<Vec<String>>,
+}
+
+impl BlockSpiller {
+    pub fn try_create(ctx: Arc<QueryContext>) -> Result<Self> {
+        let operator = ctx.get_data_operator()?.operator();
+        let location_prefix = format!("{}/{}/{}", SPILL_PREFIX, ctx.get_tenant(), ctx.get_id());
+        Ok(BlockSpiller {
+            operator,
+            location_prefix,
+            spilled_locations: Mutex::new(vec![]),
+        })
+    }
+
+    /// Write the block to the storage and return the location.
+    pub async fn spill(&self, block: &DataBlock) -> Result<String> {
+        let location = format!("{}/{}", self.location_prefix, Uuid::new_v4().as_simple());
+        let data = serialize_block(block);
+        self.operator.object(&location).write(data).await?;
+        self.spilled_locations.lock().push(location.clone());
+        Ok(location)
+    }
+
+    /// Write the blocks to one file of the storage, returns the location and the
+    /// offset and length of each block in the file.
+    pub async fn spill_blocks(
+        &self,
+        blocks: &[DataBlock],
+    ) -> Result<(String, Vec<(usize, usize)>)> {
+        let location = format!("{}/{}", self.location_prefix, Uuid::new_v4().as_simple());
+        let mut data = Vec::new();
+        let mut ranges = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let bytes = serialize_block(block);
+            ranges.push((data.len(), bytes.len()));
+            data.extend_from_slice(&bytes);
+        }
+        self.operator.object(&location).write(data).await?;
+        self.spilled_locations.lock().push(location.clone());
+        Ok((location, ranges))
+    }
+
+    pub async fn read(&self, location: &str) -> Result<DataBlock> {
+        let data = self.operator.object(location).read().await?;
+        deserialize_block(&data)
+    }
+
+    /// Read a block written by `spill_blocks`.
+    pub async fn read_range(
+        &self,
+        location: &str,
+        offset: usize,
+        length: usize,
+    ) -> Result<DataBlock> {
+        let range = offset as u64..(offset + length) as u64;
+        let data = self.operator.object(location).range_read(range).await?;
+        deserialize_block(&data)
+    }
+}
+
+#[async_trait::async_trait]
+impl SpilledBlockReader for BlockSpiller {
+    async fn read_spilled(&self, location: &str) -> Result<DataBlock> {
+        self.read(location).await
+    }
+}
+
+impl Drop for BlockSpiller {
+    fn drop(&mut self) {
+        let locations = std::mem::take(self.spilled_locations.get_mut());
+        if locations.is_empty() {
+            return;
+        }
+
+        let operator = self.operator.clone();
+        GlobalIORuntime::instance().spawn(async move {
+            for location in locations {
+                if let Err(cause) = operator.object(&location).delete().await {
+                    tracing::warn!("Failed to remove spilled file {}: {:?}", location, cause);
+                }
+            }
+        });
+    }
+}
+
+// The layout of a spilled block is the number of rows and columns, followed by
+// the length and the arrow IPC data of each column.
+fn serialize_block(block: &DataBlock) -> Vec<u8> {
+    let num_rows = block.num_rows();
+    let mut data = Vec::new();
+    data.extend_from_slice(&(num_rows as u64).to_le_bytes());
+    data.extend_from_slice(&(block.num_columns() as u64).to_le_bytes());
+    for entry in block.columns() {
+        let column = entry
+            .value
+            .convert_to_full_column(&entry.data_type, num_rows);
+        let bytes = serialize_column(&column);
+        data.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        data.extend_from_slice(&bytes);
+    }
+    data
+}
+
+fn deserialize_block(data: &[u8]) -> Result<DataBlock> {
+    let mut reader = data;
+    let num_rows = read_u64(&mut reader)? as usize;
+    let num_columns = read_u64(&mut reader)? as usize;
+    let mut columns = Vec::with_capacity(num_columns);
+    for _ in 0..num_columns {
+        let length = read_u64(&mut reader)? as usize;
+        if reader.len() < length {
+            return Err(ErrorCode::Internal("The spilled block is truncated"));
+        }
+        let (bytes, rest) = reader.split_at(length);
+        reader = rest;
+        let column = deserialize_column(bytes)
+            .ok_or_else(|| ErrorCode::Internal("Cannot deserialize the spilled column"))?;
+        columns.push(BlockEntry {
+            data_type: column.data_type(),
+            value: Value::Column(column),
+        });
+    }
+    Ok(DataBlock::new(columns, num_rows))
+}
+
+fn read_u64(reader: &mut &[u8]) -> Result<u64> {
+    if reader.len() < 8 {
+        return Err(ErrorCode::Internal("The spilled block is truncated"));
+    }
+    let (bytes, rest) = reader.split_at(8);
+    *reader = rest;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod aggregator;
+mod block_spiller;
 pub mod group_by;
 pub(crate) mod hash_join;
 mod transform_aggregator;
@@ -23,17 +24,26 @@ mod transform_hash_join;
 mod transform_left_join;
 mod transform_limit;
 mod transform_mark_join;
+mod transform_materialized_cte;
 
 mod profile_wrapper;
+mod sink_barrier;
 mod transform_add_const_columns;
 mod transform_convert_grouping;
+mod transform_expand_grouping_sets;
 mod transform_merge_block;
+mod transform_project_set;
+mod transform_recursive_union;
 mod transform_resort_addon;
 mod transform_right_join;
 mod transform_right_semi_anti_join;
+mod transform_set_operation;
+mod transform_sort_merge_join;
+mod transform_window;
 
 pub use aggregator::AggregatorParams;
 pub use aggregator::AggregatorTransformParams;
+pub use block_spiller::BlockSpiller;
 use common_pipeline_transforms::processors::transforms::transform;
 use common_pipeline_transforms::processors::transforms::transform_block_compact;
 use common_pipeline_transforms::processors::transforms::transform_compact;
@@ -46,6 +56,7 @@ pub use hash_join::HashTable;
 pub use hash_join::JoinHashTable;
 pub use hash_join::SerializerHashTable;
 pub use profile_wrapper::ProfileWrapper;
+pub use sink_barrier::SinkBarrier;
 pub use transform_add_const_columns::TransformAddConstColumns;
 pub use transform_aggregator::TransformAggregator;
 pub use transform_block_compact::BlockCompactor;
@@ -60,6 +71,8 @@ pub use transform_create_sets::TransformCreateSets;
 pub use transform_dummy::create_dummy_item;
 pub use transform_dummy::create_dummy_items;
 pub use transform_dummy::TransformDummy;
+pub use transform_expand_grouping_sets::TransformEmptyGroupingSet;
+pub use transform_expand_grouping_sets::TransformExpandGroupingSets;
 pub use transform_hash_join::SinkBuildHashTable;
 pub use transform_hash_join::TransformHashJoinProbe;
 pub use transform_left_join::LeftJoinCompactor;
@@ -67,11 +80,23 @@ pub use transform_left_join::TransformLeftJoin;
 pub use transform_limit::TransformLimit;
 pub use transform_mark_join::MarkJoinCompactor;
 pub use transform_mark_join::TransformMarkJoin;
+pub use transform_materialized_cte::MaterializedCteSink;
+pub use transform_materialized_cte::MaterializedCteSource;
+pub use transform_materialized_cte::MaterializedCteState;
 pub use transform_merge_block::TransformMergeBlock;
+pub use transform_project_set::TransformProjectSet;
+pub use transform_recursive_union::RecursiveCteWorkingTable;
+pub use transform_recursive_union::TransformRecursiveUnion;
 pub use transform_resort_addon::TransformResortAddOn;
 pub use transform_right_join::RightJoinCompactor;
 pub use transform_right_join::TransformRightJoin;
 pub use transform_right_semi_anti_join::RightSemiAntiJoinCompactor;
 pub use transform_right_semi_anti_join::TransformRightSemiAntiJoin;
+pub use transform_set_operation::SetOperationState;
+pub use transform_set_operation::SinkBuildSetOperation;
+pub use transform_set_operation::TransformSetOperationProbe;
 pub use transform_sort_merge::SortMergeCompactor;
+pub use transform_sort_merge_join::TransformSortMergeJoin;
 pub use transform_sort_partial::TransformSortPartial;
+pub use transform_window::TransformWindow;
+pub use transform_window::WindowFunctionImpl;
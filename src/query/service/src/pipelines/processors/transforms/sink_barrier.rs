@@ -0,0 +1,62 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use common_base::base::tokio::sync::Notify;
+use parking_lot::Mutex;
+
+/// Lets the readers of a shared state wait until all the sinks writing it are finished.
+///
+/// Every sink attaches itself when it's created and detaches when it's finished,
+/// the waiters are woken up once the last sink is detached.
+pub struct SinkBarrier {
+    /// The number of sinks still writing.
+    ref_count: Mutex<usize>,
+    is_finished: AtomicBool,
+    finished_notify: Notify,
+}
+
+impl SinkBarrier {
+    pub fn create() -> SinkBarrier {
+        SinkBarrier {
+            ref_count: Mutex::new(0),
+            is_finished: AtomicBool::new(false),
+            finished_notify: Notify::new(),
+        }
+    }
+
+    pub fn attach(&self) {
+        *self.ref_count.lock() += 1;
+    }
+
+    pub fn detach(&self) {
+        let mut count = self.ref_count.lock();
+        *count -= 1;
+        if *count == 0 {
+            self.is_finished.store(true, Ordering::Release);
+            self.finished_notify.notify_waiters();
+        }
+    }
+
+    pub async fn wait_finish(&self) {
+        // The future must be created before checking the state, so that a
+        // notification sent in between is not missed.
+        let notified = self.finished_notify.notified();
+        if !self.is_finished.load(Ordering::Acquire) {
+            notified.await;
+        }
+    }
+}
@@ -24,6 +24,7 @@ use crate::pipelines::processors::port::InputPort;
 use crate::pipelines::processors::port::OutputPort;
 use crate::pipelines::processors::processor::Event;
 use crate::pipelines::processors::transforms::aggregator::*;
+use crate::pipelines::processors::transforms::BlockSpiller;
 use crate::pipelines::processors::AggregatorTransformParams;
 use crate::pipelines::processors::Processor;
 use crate::sessions::QueryContext;
@@ -51,6 +52,7 @@ impl TransformAggregator {
                     ctx.clone(),
                     transform_params,
                     ParallelFinalAggregator::<false, T>::create(ctx, method, aggregator_params)?,
+                    false,
                 ),
             }),
 
@@ -59,6 +61,7 @@ impl TransformAggregator {
                     ctx.clone(),
                     transform_params,
                     ParallelFinalAggregator::<true, T>::create(ctx, method, aggregator_params)?,
+                    false,
                 ),
             }),
         }
@@ -89,6 +92,8 @@ impl TransformAggregator {
                         aggregator_params,
                         pass_state_to_final,
                     )?,
+                    // Only the memory efficient final aggregator reads the spilled partitions.
+                    pass_state_to_final,
                 ),
             }),
             false => with_mappedhash_method!(|T| match transform_params.method.clone() {
@@ -100,6 +105,8 @@ impl TransformAggregator {
                         aggregator_params,
                         pass_state_to_final,
                     )?,
+                    // Only the memory efficient final aggregator reads the spilled partitions.
+                    pass_state_to_final,
                 ),
             }),
         }
@@ -129,15 +136,27 @@ impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static>
         ctx: Arc<QueryContext>,
         transform_params: AggregatorTransformParams,
         inner: TAggregator,
+        enable_spilling: bool,
     ) -> Result<Box<dyn Processor>> {
         let settings = ctx.get_settings();
         let two_level_threshold = settings.get_group_by_two_level_threshold()? as usize;
+        let spilling_bytes_threshold = settings.get_spilling_bytes_threshold_per_proc()?;
+
+        let spiller = match enable_spilling
+            && TAggregator::SUPPORT_PARTITION
+            && spilling_bytes_threshold != 0
+        {
+            true => Some(Arc::new(BlockSpiller::try_create(ctx)?)),
+            false => None,
+        };
 
         let transformer = AggregatorTransform::<TAggregator>::ConsumeData(ConsumeState {
             inner,
             input_port: transform_params.transform_input_port,
             output_port: transform_params.transform_output_port,
             two_level_threshold,
+            spiller,
+            spilling_bytes_threshold,
             input_data_block: None,
         });
 
@@ -179,7 +198,10 @@ impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static>
                     inner: s.inner.convert_partitioned()?,
                     input_port: s.input_port,
                     output_port: s.output_port,
+                    spiller: s.spiller,
+                    spilling_bytes_threshold: s.spilling_bytes_threshold,
                     input_data_block: None,
+                    spilled_data_block: None,
                 },
             )),
             _ => Err(ErrorCode::Internal("")),
@@ -187,6 +209,7 @@ impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static>
     }
 }
 
+#[async_trait::async_trait]
 impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static> Processor
     for AggregatorTransform<TAggregator>
 {
@@ -217,6 +240,13 @@ impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static> Processor
             AggregatorTransform::PartitionedGenerate(state) => state.generate(),
         }
     }
+
+    async fn async_process(&mut self) -> Result<()> {
+        match self {
+            AggregatorTransform::PartitionedConsumeData(state) => state.spill().await,
+            _ => Err(ErrorCode::Internal("It's a bug")),
+        }
+    }
 }
 
 impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static>
@@ -230,8 +260,10 @@ impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static>
 
                 static TWOL_LEVEL_BYTES_THRESHOLD: usize = 5_000_000;
 
+                let state_bytes = state.inner.get_state_bytes();
                 if cardinality >= state.two_level_threshold
-                    || state.inner.get_state_bytes() >= TWOL_LEVEL_BYTES_THRESHOLD
+                    || state_bytes >= TWOL_LEVEL_BYTES_THRESHOLD
+                    || (state.spiller.is_some() && state_bytes >= state.spilling_bytes_threshold)
                 {
                     let mut temp_state = AggregatorTransform::Finished;
                     std::mem::swap(self, &mut temp_state);
@@ -268,10 +300,24 @@ impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static>
         }
 
         if let AggregatorTransform::PartitionedConsumeData(state) = self {
+            if let Some(block) = state.spilled_data_block.take() {
+                if !state.output_port.can_push() {
+                    state.spilled_data_block = Some(block);
+                    return Ok(Event::NeedConsume);
+                }
+
+                state.output_port.push_data(Ok(block));
+                return Ok(Event::NeedConsume);
+            }
+
             if state.input_data_block.is_some() {
                 return Ok(Event::Sync);
             }
 
+            if state.need_spill() {
+                return Ok(Event::Async);
+            }
+
             if state.input_port.is_finished() {
                 let mut temp_state = AggregatorTransform::Finished;
                 std::mem::swap(self, &mut temp_state);
@@ -364,6 +410,8 @@ impl<TAggregator: Aggregator + PartitionedAggregatorLike + 'static>
 struct ConsumeState<TAggregator: Aggregator> {
     inner: TAggregator,
     two_level_threshold: usize,
+    spiller: Option<Arc<BlockSpiller>>,
+    spilling_bytes_threshold: usize,
 
     input_port: Arc<InputPort>,
     output_port: Arc<OutputPort>,
@@ -382,10 +430,15 @@ impl<TAggregator: Aggregator> ConsumeState<TAggregator> {
 
 struct PartitionedConsumeState<TAggregator: Aggregator + PartitionedAggregatorLike> {
     inner: PartitionedAggregator<TAggregator>,
+    // The partitions are spilled if the memory usage exceeds the threshold, it's
+    // None if spilling is disabled.
+    spiller: Option<Arc<BlockSpiller>>,
+    spilling_bytes_threshold: usize,
 
     input_port: Arc<InputPort>,
     output_port: Arc<OutputPort>,
     input_data_block: Option<DataBlock>,
+    spilled_data_block: Option<DataBlock>,
 }
 
 impl<TAggregator: Aggregator + PartitionedAggregatorLike> PartitionedConsumeState<TAggregator> {
@@ -396,6 +449,31 @@ impl<TAggregator: Aggregator + PartitionedAggregatorLike> PartitionedConsumeStat
 
         Ok(())
     }
+
+    fn need_spill(&self) -> bool {
+        self.spiller.is_some() && self.inner.get_state_bytes() >= self.spilling_bytes_threshold
+    }
+
+    pub async fn spill(&mut self) -> Result<()> {
+        let spiller = match &self.spiller {
+            Some(spiller) => spiller.clone(),
+            None => return Ok(()),
+        };
+
+        let (buckets, blocks): (Vec<_>, Vec<_>) = self.inner.spill_blocks()?.into_iter().unzip();
+        if blocks.is_empty() {
+            return Ok(());
+        }
+
+        let (location, ranges) = spiller.spill_blocks(&blocks).await?;
+        let overflow = OverflowInfo {
+            temporary_path: location,
+            bucket_info: buckets.into_iter().zip(ranges.into_iter()).collect(),
+        };
+        let meta = AggregateSpilledInfo::create(spiller, overflow);
+        self.spilled_data_block = Some(DataBlock::empty_with_meta(meta));
+        Ok(())
+    }
 }
 
 struct GenerateState<TAggregator: Aggregator> {
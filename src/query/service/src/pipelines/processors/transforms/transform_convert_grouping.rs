@@ -38,6 +38,8 @@ use serde::Serialize;
 use serde::Serializer;
 
 use super::aggregator::AggregateHashStateInfo;
+use super::aggregator::AggregateSpilledInfo;
+use super::aggregator::OverflowInfo;
 use super::group_by::BUCKETS_LG2;
 use crate::pipelines::processors::transforms::aggregator::AggregateInfo;
 use crate::pipelines::processors::transforms::aggregator::BucketAggregator;
@@ -46,7 +48,6 @@ use crate::pipelines::processors::transforms::group_by::KeysColumnIter;
 use crate::pipelines::processors::AggregatorParams;
 
 // Overflow to object storage data block
-#[allow(dead_code)]
 static OVERFLOW_BUCKET_NUM: isize = -2;
 // Single level data block
 static SINGLE_LEVEL_BUCKET_NUM: isize = -1;
@@ -207,6 +208,25 @@ impl<Method: HashMethodBounds> TransformConvertGrouping<Method> {
             }
         }
 
+        // The spilled partitions are merged with the other blocks of their buckets.
+        if let Some(info) = data_block
+            .get_meta()
+            .and_then(|meta| meta.as_any().downcast_ref::<AggregateSpilledInfo>())
+        {
+            for (bucket, range) in &info.overflow.bucket_info {
+                let overflow = OverflowInfo {
+                    temporary_path: info.overflow.temporary_path.clone(),
+                    bucket_info: HashMap::from([(*bucket, *range)]),
+                };
+                let meta = AggregateSpilledInfo::create(info.spiller.clone(), overflow);
+                self.buckets_blocks
+                    .entry(*bucket as isize)
+                    .or_default()
+                    .push(DataBlock::empty_with_meta(meta));
+            }
+            return OVERFLOW_BUCKET_NUM;
+        }
+
         // check if it's local state
         if let Some(info) = data_block
             .get_meta()
@@ -443,7 +463,7 @@ struct MergeBucketTransform<Method: HashMethodBounds> {
     input: Arc<InputPort>,
     output: Arc<OutputPort>,
 
-    input_block: Option<DataBlock>,
+    input_blocks: Option<Vec<DataBlock>>,
     output_blocks: Vec<DataBlock>,
 }
 
@@ -459,7 +479,7 @@ impl<Method: HashMethodBounds> MergeBucketTransform<Method> {
             output,
             method,
             params,
-            input_block: None,
+            input_blocks: None,
             output_blocks: vec![],
         })))
     }
@@ -477,7 +497,7 @@ impl<Method: HashMethodBounds> Processor for MergeBucketTransform<Method> {
 
     fn event(&mut self) -> Result<Event> {
         if self.output.is_finished() {
-            self.input_block.take();
+            self.input_blocks.take();
             self.output_blocks.clear();
             self.input.finish();
             return Ok(Event::Finished);
@@ -493,13 +513,23 @@ impl<Method: HashMethodBounds> Processor for MergeBucketTransform<Method> {
             return Ok(Event::NeedConsume);
         }
 
-        if self.input_block.is_some() {
-            return Ok(Event::Sync);
+        if self.input_blocks.is_none() && self.input.has_data() {
+            let mut data_block = self.input.pull_data().unwrap()?;
+            let mut blocks = vec![];
+            if let Some(mut meta) = data_block.take_meta() {
+                if let Some(meta) = meta.as_mut_any().downcast_mut::<ConvertGroupingMetaInfo>() {
+                    std::mem::swap(&mut blocks, &mut meta.blocks);
+                }
+            }
+            self.input_blocks = Some(blocks);
         }
 
-        if self.input.has_data() {
-            self.input_block = Some(self.input.pull_data().unwrap()?);
-            return Ok(Event::Sync);
+        if let Some(blocks) = &self.input_blocks {
+            // Read the spilled partitions of the bucket before merging.
+            return match blocks.iter().any(is_spilled_block) {
+                true => Ok(Event::Async),
+                false => Ok(Event::Sync),
+            };
         }
 
         if self.input.is_finished() {
@@ -512,14 +542,7 @@ impl<Method: HashMethodBounds> Processor for MergeBucketTransform<Method> {
     }
 
     fn process(&mut self) -> Result<()> {
-        if let Some(mut data_block) = self.input_block.take() {
-            let mut blocks = vec![];
-            if let Some(mut meta) = data_block.take_meta() {
-                if let Some(meta) = meta.as_mut_any().downcast_mut::<ConvertGroupingMetaInfo>() {
-                    std::mem::swap(&mut blocks, &mut meta.blocks);
-                }
-            }
-
+        if let Some(blocks) = self.input_blocks.take() {
             match self.params.aggregate_functions.is_empty() {
                 true => {
                     let mut bucket_merger = BucketAggregator::<false, _>::create(
@@ -544,4 +567,32 @@ impl<Method: HashMethodBounds> Processor for MergeBucketTransform<Method> {
 
         Ok(())
     }
+
+    async fn async_process(&mut self) -> Result<()> {
+        if let Some(blocks) = self.input_blocks.as_mut() {
+            for block in blocks.iter_mut() {
+                let spilled = block
+                    .get_meta()
+                    .and_then(|meta| meta.as_any().downcast_ref::<AggregateSpilledInfo>())
+                    .map(|info| (info.spiller.clone(), info.overflow.clone()));
+
+                if let Some((spiller, overflow)) = spilled {
+                    for (offset, length) in overflow.bucket_info.values() {
+                        *block = spiller
+                            .read_range(&overflow.temporary_path, *offset, *length)
+                            .await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_spilled_block(block: &DataBlock) -> bool {
+    block
+        .get_meta()
+        .and_then(|meta| meta.as_any().downcast_ref::<AggregateSpilledInfo>())
+        .is_some()
 }
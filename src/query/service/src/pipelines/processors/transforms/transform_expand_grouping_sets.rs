@@ -0,0 +1,221 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use bumpalo::Bump;
+use common_exception::Result;
+use common_expression::arrow::constant_bitmap;
+use common_expression::types::nullable::NullableColumn;
+use common_expression::types::number::NumberScalar;
+use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
+use common_expression::BlockEntry;
+use common_expression::Column;
+use common_expression::ColumnBuilder;
+use common_expression::DataBlock;
+use common_expression::Scalar;
+use common_expression::Value;
+use common_functions::aggregates::StateAddr;
+use common_pipeline_transforms::processors::transforms::transform_accumulating::AccumulatingTransform;
+use common_pipeline_transforms::processors::transforms::transform_accumulating::AccumulatingTransformer;
+
+use crate::pipelines::processors::port::InputPort;
+use crate::pipelines::processors::port::OutputPort;
+use crate::pipelines::processors::transforms::transform::Transform;
+use crate::pipelines::processors::transforms::transform::Transformer;
+use crate::pipelines::processors::AggregatorParams;
+use crate::pipelines::processors::Processor;
+
+/// Expand every input row into one row per grouping set.
+///
+/// For each grouping set, the group by columns that are not in the set are
+/// replaced with NULL, and a `_grouping_id` column is appended to identify
+/// the grouping set. Bit `i` of the grouping id is set if the `i`-th group by
+/// column is rolled up in the grouping set.
+pub struct TransformExpandGroupingSets {
+    /// Offsets of the group by columns which are rolled up in some grouping sets.
+    nullable_group_bys: Vec<usize>,
+    /// For each grouping set, the grouping id and whether the nullable group by
+    /// columns are rolled up.
+    grouping_sets: Vec<(u32, Vec<bool>)>,
+}
+
+impl TransformExpandGroupingSets
+where Self: Transform
+{
+    /// `group_bys` are the offsets of the group by columns, and each grouping
+    /// set is a list of positions in `group_bys`.
+    pub fn try_create(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        group_bys: Vec<usize>,
+        grouping_sets: Vec<Vec<usize>>,
+    ) -> Result<Box<dyn Processor>> {
+        let nullable_positions = (0..group_bys.len())
+            .filter(|pos| grouping_sets.iter().any(|set| !set.contains(pos)))
+            .collect::<Vec<_>>();
+
+        let grouping_sets = grouping_sets
+            .iter()
+            .map(|set| {
+                let grouping_id = (0..group_bys.len())
+                    .filter(|pos| !set.contains(pos))
+                    .fold(0u32, |acc, pos| acc | (1 << pos));
+                let rolled_up = nullable_positions
+                    .iter()
+                    .map(|pos| !set.contains(pos))
+                    .collect();
+                (grouping_id, rolled_up)
+            })
+            .collect();
+
+        Ok(Transformer::create(input, output, Self {
+            nullable_group_bys: nullable_positions
+                .iter()
+                .map(|pos| group_bys[*pos])
+                .collect(),
+            grouping_sets,
+        }))
+    }
+}
+
+impl Transform for TransformExpandGroupingSets {
+    const NAME: &'static str = "TransformExpandGroupingSets";
+
+    fn transform(&mut self, data: DataBlock) -> Result<DataBlock> {
+        let num_rows = data.num_rows();
+
+        // Wrap the nullable group by columns first, they are shared by all grouping sets.
+        let mut columns = data.columns().to_vec();
+        for offset in self.nullable_group_bys.iter() {
+            let entry = &columns[*offset];
+            if entry.data_type.is_nullable_or_null() {
+                continue;
+            }
+            let data_type = entry.data_type.wrap_nullable();
+            let value = match &entry.value {
+                Value::Column(column) => {
+                    Value::Column(Column::Nullable(Box::new(NullableColumn {
+                        column: column.clone(),
+                        validity: constant_bitmap(true, num_rows).into(),
+                    })))
+                }
+                value => value.clone(),
+            };
+            columns[*offset] = BlockEntry { data_type, value };
+        }
+
+        let mut blocks = Vec::with_capacity(self.grouping_sets.len());
+        for (grouping_id, rolled_up) in self.grouping_sets.iter() {
+            let mut columns = columns.clone();
+            for (offset, rolled_up) in self.nullable_group_bys.iter().zip(rolled_up.iter()) {
+                if *rolled_up {
+                    columns[*offset].value = Value::Scalar(Scalar::Null);
+                }
+            }
+            columns.push(BlockEntry {
+                data_type: DataType::Number(NumberDataType::UInt32),
+                value: Value::Scalar(Scalar::Number(NumberScalar::UInt32(*grouping_id))),
+            });
+            blocks.push(DataBlock::new(columns, num_rows));
+        }
+
+        DataBlock::concat(&blocks)
+    }
+}
+
+/// Emit the row of the empty grouping set `()` if the final aggregation
+/// outputs nothing, i.e. the input is empty.
+///
+/// Like an aggregation without GROUP BY, the empty grouping set aggregates
+/// all the input rows, so it always produces exactly one row.
+pub struct TransformEmptyGroupingSet {
+    params: Arc<AggregatorParams>,
+    /// Data types of the group by columns, the last one is the grouping id.
+    group_data_types: Vec<DataType>,
+    grouping_id: u32,
+    has_output: bool,
+}
+
+impl TransformEmptyGroupingSet {
+    pub fn try_create(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        params: Arc<AggregatorParams>,
+        group_data_types: Vec<DataType>,
+        grouping_id: u32,
+    ) -> Result<Box<dyn Processor>> {
+        Ok(AccumulatingTransformer::create(input, output, Self {
+            params,
+            group_data_types,
+            grouping_id,
+            has_output: false,
+        }))
+    }
+
+    fn empty_grouping_set_block(&self) -> Result<DataBlock> {
+        let funcs = &self.params.aggregate_functions;
+        let mut columns = Vec::with_capacity(funcs.len() + self.group_data_types.len());
+
+        if let Some(layout) = self.params.layout {
+            let arena = Bump::new();
+            let place: StateAddr = arena.alloc_layout(layout).into();
+            for (func, offset) in funcs.iter().zip(&self.params.offsets_aggregate_states) {
+                let data_type = func.return_type()?;
+                let mut builder = ColumnBuilder::with_capacity(&data_type, 1);
+                let state = place.next(*offset);
+                func.init_state(state);
+                func.merge_result(state, &mut builder)?;
+                if func.need_manual_drop_state() {
+                    unsafe { func.drop_state(state) }
+                }
+                columns.push(BlockEntry {
+                    data_type,
+                    value: Value::Column(builder.build()),
+                });
+            }
+        }
+
+        let (grouping_id_type, group_by_types) = self.group_data_types.split_last().unwrap();
+        for data_type in group_by_types {
+            columns.push(BlockEntry {
+                data_type: data_type.clone(),
+                value: Value::Scalar(Scalar::Null),
+            });
+        }
+        columns.push(BlockEntry {
+            data_type: grouping_id_type.clone(),
+            value: Value::Scalar(Scalar::Number(NumberScalar::UInt32(self.grouping_id))),
+        });
+
+        Ok(DataBlock::new(columns, 1))
+    }
+}
+
+impl AccumulatingTransform for TransformEmptyGroupingSet {
+    const NAME: &'static str = "TransformEmptyGroupingSet";
+
+    fn transform(&mut self, data: DataBlock) -> Result<Option<DataBlock>> {
+        self.has_output |= !data.is_empty();
+        Ok(Some(data))
+    }
+
+    fn on_finish(&mut self, output: bool) -> Result<Option<DataBlock>> {
+        if !output || self.has_output {
+            return Ok(None);
+        }
+        Ok(Some(self.empty_grouping_set_block()?))
+    }
+}
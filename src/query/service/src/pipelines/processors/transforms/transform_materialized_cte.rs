@@ -0,0 +1,163 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_expression::DataBlock;
+use common_pipeline_core::processors::port::InputPort;
+use common_pipeline_core::processors::port::OutputPort;
+use common_pipeline_core::processors::processor::ProcessorPtr;
+use common_pipeline_core::processors::Processor;
+use common_pipeline_sinks::AsyncSink;
+use common_pipeline_sinks::AsyncSinker;
+use common_pipeline_sources::AsyncSource;
+use common_pipeline_sources::AsyncSourcer;
+use parking_lot::RwLock;
+
+use crate::pipelines::processors::transforms::BlockSpiller;
+use crate::pipelines::processors::transforms::SinkBarrier;
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+
+enum MaterializedBlock {
+    Memory(DataBlock),
+    Spilled(String),
+}
+
+/// The result of a materialized common table expression, shared by all its references.
+///
+/// The blocks are kept in memory until `materialized_cte_spill_bytes` is reached,
+/// then the rest are spilled to the storage.
+pub struct MaterializedCteState {
+    spiller: BlockSpiller,
+    spill_bytes: usize,
+    memory_bytes: AtomicUsize,
+    blocks: RwLock<Vec<MaterializedBlock>>,
+    /// Waits for all the sinks writing the result.
+    barrier: SinkBarrier,
+}
+
+impl MaterializedCteState {
+    pub fn try_create(ctx: Arc<QueryContext>) -> Result<Arc<MaterializedCteState>> {
+        let spill_bytes = ctx.get_settings().get_materialized_cte_spill_bytes()?;
+        Ok(Arc::new(MaterializedCteState {
+            spiller: BlockSpiller::try_create(ctx)?,
+            spill_bytes,
+            memory_bytes: AtomicUsize::new(0),
+            blocks: RwLock::new(vec![]),
+            barrier: SinkBarrier::create(),
+        }))
+    }
+
+    async fn add_block(&self, block: DataBlock) -> Result<()> {
+        let block_bytes = block.memory_size();
+        let memory_bytes = self.memory_bytes.fetch_add(block_bytes, Ordering::Relaxed);
+        let block = if self.spill_bytes != 0 && memory_bytes + block_bytes > self.spill_bytes {
+            self.memory_bytes.fetch_sub(block_bytes, Ordering::Relaxed);
+            MaterializedBlock::Spilled(self.spiller.spill(&block).await?)
+        } else {
+            MaterializedBlock::Memory(block)
+        };
+        self.blocks.write().push(block);
+        Ok(())
+    }
+
+    async fn read_block(&self, index: usize) -> Result<Option<DataBlock>> {
+        let location = match self.blocks.read().get(index) {
+            None => return Ok(None),
+            Some(MaterializedBlock::Memory(block)) => return Ok(Some(block.clone())),
+            Some(MaterializedBlock::Spilled(location)) => location.clone(),
+        };
+        Ok(Some(self.spiller.read(&location).await?))
+    }
+}
+
+/// Write the result of the definition of a materialized common table expression.
+pub struct MaterializedCteSink {
+    state: Arc<MaterializedCteState>,
+    /// The offsets of the columns of the CTE in the input blocks.
+    projections: Vec<usize>,
+}
+
+impl MaterializedCteSink {
+    pub fn create(
+        input: Arc<InputPort>,
+        state: Arc<MaterializedCteState>,
+        projections: Vec<usize>,
+    ) -> Box<dyn Processor> {
+        state.barrier.attach();
+        AsyncSinker::create(input, MaterializedCteSink { state, projections })
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncSink for MaterializedCteSink {
+    const NAME: &'static str = "MaterializedCteSink";
+
+    async fn on_finish(&mut self) -> Result<()> {
+        self.state.barrier.detach();
+        Ok(())
+    }
+
+    #[async_trait::unboxed_simple]
+    async fn consume(&mut self, data_block: DataBlock) -> Result<bool> {
+        if !data_block.is_empty() {
+            let num_rows = data_block.num_rows();
+            let columns = self
+                .projections
+                .iter()
+                .map(|offset| data_block.get_by_offset(*offset).clone())
+                .collect();
+            self.state
+                .add_block(DataBlock::new(columns, num_rows))
+                .await?;
+        }
+        Ok(false)
+    }
+}
+
+/// Read the result of a materialized common table expression once it's finished.
+///
+/// Every reference of the CTE reads all the blocks, and the sources of the same
+/// reference share the `cursor`.
+pub struct MaterializedCteSource {
+    state: Arc<MaterializedCteState>,
+    cursor: Arc<AtomicUsize>,
+}
+
+impl MaterializedCteSource {
+    pub fn create(
+        ctx: Arc<QueryContext>,
+        output: Arc<OutputPort>,
+        state: Arc<MaterializedCteState>,
+        cursor: Arc<AtomicUsize>,
+    ) -> Result<ProcessorPtr> {
+        AsyncSourcer::create(ctx, output, MaterializedCteSource { state, cursor })
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncSource for MaterializedCteSource {
+    const NAME: &'static str = "MaterializedCteSource";
+
+    #[async_trait::unboxed_simple]
+    async fn generate(&mut self) -> Result<Option<DataBlock>> {
+        self.state.barrier.wait_finish().await;
+        let index = self.cursor.fetch_add(1, Ordering::Relaxed);
+        self.state.read_block(index).await
+    }
+}
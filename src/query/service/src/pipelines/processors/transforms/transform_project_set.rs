@@ -0,0 +1,207 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_expression::types::number::NumberScalar;
+use common_expression::types::DataType;
+use common_expression::BlockEntry;
+use common_expression::ColumnBuilder;
+use common_expression::DataBlock;
+use common_expression::Evaluator;
+use common_expression::Expr;
+use common_expression::FunctionContext;
+use common_expression::ScalarRef;
+use common_expression::Value;
+use common_functions::scalars::BUILTIN_FUNCTIONS;
+use common_sql::plans::FlattenMode;
+use common_sql::plans::FlattenOptions;
+use common_sql::plans::SetReturningFunction;
+
+use crate::pipelines::processors::port::InputPort;
+use crate::pipelines::processors::port::OutputPort;
+use crate::pipelines::processors::transforms::transform::Transform;
+use crate::pipelines::processors::transforms::transform::Transformer;
+use crate::pipelines::processors::Processor;
+
+/// Evaluate a set-returning function on each input row.
+///
+/// Each input row is repeated once for every row returned by the function, and the
+/// columns returned by the function are appended to the block.
+pub struct TransformProjectSet {
+    func_ctx: FunctionContext,
+    srf: SetReturningFunction,
+    args: Vec<Expr>,
+    /// Types of the output columns of the function
+    columns: Vec<DataType>,
+    outer: bool,
+}
+
+impl TransformProjectSet
+where Self: Transform
+{
+    pub fn try_create(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        func_ctx: FunctionContext,
+        srf: SetReturningFunction,
+        args: Vec<Expr>,
+        columns: Vec<DataType>,
+        outer: bool,
+    ) -> Result<Box<dyn Processor>> {
+        Ok(Transformer::create(input, output, Self {
+            func_ctx,
+            srf,
+            args,
+            columns,
+            outer,
+        }))
+    }
+}
+
+impl Transform for TransformProjectSet {
+    const NAME: &'static str = "TransformProjectSet";
+
+    fn transform(&mut self, data: DataBlock) -> Result<DataBlock> {
+        let num_rows = data.num_rows();
+        let evaluator = Evaluator::new(&data, self.func_ctx, &BUILTIN_FUNCTIONS);
+        let args = self
+            .args
+            .iter()
+            .map(|arg| evaluator.run(arg))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut indices = Vec::with_capacity(num_rows);
+        let mut builders = self
+            .columns
+            .iter()
+            .map(|data_type| ColumnBuilder::with_capacity(data_type, num_rows))
+            .collect::<Vec<_>>();
+
+        for row in 0..num_rows {
+            let arg = args[0].index(row).unwrap();
+            let returned = match &self.srf {
+                SetReturningFunction::Unnest => unnest(arg, &mut builders),
+                SetReturningFunction::Flatten(options) => flatten(arg, options, &mut builders),
+            };
+            if returned == 0 && self.outer {
+                match &self.srf {
+                    SetReturningFunction::Unnest => builders[0].push(ScalarRef::Null),
+                    SetReturningFunction::Flatten(_) => {
+                        // The `THIS` column of the outer row is the input itself.
+                        for builder in builders[..4].iter_mut() {
+                            builder.push(ScalarRef::Null);
+                        }
+                        builders[4].push(arg);
+                    }
+                }
+                indices.push(row as u32);
+            } else {
+                indices.extend(std::iter::repeat(row as u32).take(returned));
+            }
+        }
+
+        let mut block = data.take(&indices)?;
+        for (builder, data_type) in builders.into_iter().zip(self.columns.iter()) {
+            block.add_column(BlockEntry {
+                data_type: data_type.clone(),
+                value: Value::Column(builder.build()),
+            });
+        }
+        Ok(block)
+    }
+}
+
+/// Push the elements of an array into the builder, returns the number of rows.
+fn unnest(arg: ScalarRef, builders: &mut [ColumnBuilder]) -> usize {
+    match arg {
+        ScalarRef::Array(column) => {
+            for value in column.iter() {
+                builders[0].push(value);
+            }
+            column.len()
+        }
+        // NULL and empty arrays have no elements.
+        _ => 0,
+    }
+}
+
+/// Push the `KEY`, `PATH`, `INDEX`, `VALUE` and `THIS` columns of the elements of a
+/// variant into the builders, returns the number of rows.
+fn flatten(arg: ScalarRef, options: &FlattenOptions, builders: &mut [ColumnBuilder]) -> usize {
+    match arg {
+        ScalarRef::Variant(value) if !value.is_empty() => {
+            flatten_value(value, &options.path, options, builders)
+        }
+        _ => 0,
+    }
+}
+
+fn flatten_value(
+    this: &[u8],
+    path: &str,
+    options: &FlattenOptions,
+    builders: &mut [ColumnBuilder],
+) -> usize {
+    let mut rows = 0;
+    if common_jsonb::is_object(this) && options.mode != FlattenMode::Array {
+        for (key, value) in common_jsonb::object_each(this).unwrap_or_default() {
+            let path = object_path(path, &key);
+            builders[0].push(ScalarRef::String(key.as_bytes()));
+            builders[1].push(ScalarRef::String(path.as_bytes()));
+            builders[2].push(ScalarRef::Null);
+            builders[3].push(ScalarRef::Variant(&value));
+            builders[4].push(ScalarRef::Variant(this));
+            rows += 1;
+            if options.recursive {
+                rows += flatten_value(&value, &path, options, builders);
+            }
+        }
+    } else if common_jsonb::is_array(this) && options.mode != FlattenMode::Object {
+        for (index, value) in common_jsonb::array_values(this)
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let path = format!("{path}[{index}]");
+            builders[0].push(ScalarRef::Null);
+            builders[1].push(ScalarRef::String(path.as_bytes()));
+            builders[2].push(ScalarRef::Number(NumberScalar::UInt64(index as u64)));
+            builders[3].push(ScalarRef::Variant(&value));
+            builders[4].push(ScalarRef::Variant(this));
+            rows += 1;
+            if options.recursive {
+                rows += flatten_value(&value, &path, options, builders);
+            }
+        }
+    }
+    rows
+}
+
+/// Append a key to a path, keys that are not identifiers are quoted.
+fn object_path(path: &str, key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_identifier {
+        format!("{path}['{key}']")
+    } else if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
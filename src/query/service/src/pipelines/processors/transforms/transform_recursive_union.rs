@@ -0,0 +1,209 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::DataBlock;
+use common_expression::DataSchemaRef;
+use common_expression::Scalar;
+use common_pipeline_core::processors::port::OutputPort;
+use common_pipeline_core::processors::processor::ProcessorPtr;
+use common_pipeline_sources::SyncSource;
+use common_pipeline_sources::SyncSourcer;
+use common_profile::ProfSpanSetRef;
+use common_sql::executor::PhysicalPlan;
+use common_sql::executor::RecursiveUnion;
+use common_sql::IndexType;
+use parking_lot::Mutex;
+
+use crate::pipelines::executor::ExecutorSettings;
+use crate::pipelines::executor::PipelinePullingExecutor;
+use crate::pipelines::processors::transforms::MaterializedCteState;
+use crate::pipelines::PipelineBuilder;
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+
+/// The rows produced by the last iteration of a recursive common table expression,
+/// which are read by the `RecursiveCteScan` of the next iteration.
+pub type RecursiveCteWorkingTable = Arc<Mutex<VecDeque<DataBlock>>>;
+
+/// Execute a recursive common table expression.
+///
+/// The non-recursive term is executed first, then the recursive term is executed
+/// repeatedly on the rows produced by the previous iteration, until an iteration
+/// produces no rows. Each iteration is executed with its own pipeline, and its
+/// output is emitted as one block.
+pub struct TransformRecursiveUnion {
+    ctx: Arc<QueryContext>,
+    plan: RecursiveUnion,
+    enable_profiling: bool,
+    prof_span_set: ProfSpanSetRef,
+    /// The working tables of all the recursive common table expressions being
+    /// executed, including the one of this union.
+    working_tables: HashMap<String, RecursiveCteWorkingTable>,
+    working_table: RecursiveCteWorkingTable,
+    materialized_cte_states: HashMap<IndexType, Arc<MaterializedCteState>>,
+
+    left_executed: bool,
+    max_depth: u64,
+    /// The number of executed iterations of the recursive term.
+    depth: u64,
+    /// The rows produced so far, only used for `UNION`.
+    distinct_rows: Option<HashSet<Vec<Scalar>>>,
+    finished: bool,
+}
+
+impl TransformRecursiveUnion {
+    pub fn try_create(
+        ctx: Arc<QueryContext>,
+        output: Arc<OutputPort>,
+        plan: RecursiveUnion,
+        enable_profiling: bool,
+        prof_span_set: ProfSpanSetRef,
+        mut working_tables: HashMap<String, RecursiveCteWorkingTable>,
+        materialized_cte_states: HashMap<IndexType, Arc<MaterializedCteState>>,
+    ) -> Result<ProcessorPtr> {
+        let working_table = RecursiveCteWorkingTable::default();
+        working_tables.insert(plan.cte_name.clone(), working_table.clone());
+        let max_depth = ctx.get_settings().get_max_cte_recursive_depth()?;
+        let distinct_rows = plan.distinct.then(HashSet::new);
+        SyncSourcer::create(ctx.clone(), output, TransformRecursiveUnion {
+            ctx,
+            plan,
+            enable_profiling,
+            prof_span_set,
+            working_tables,
+            working_table,
+            materialized_cte_states,
+            left_executed: false,
+            max_depth,
+            depth: 0,
+            distinct_rows,
+            finished: false,
+        })
+    }
+
+    /// Execute one term of the union, and project its output to the layout of the union.
+    fn execute_term(&self, plan: &PhysicalPlan, is_left: bool) -> Result<Vec<DataBlock>> {
+        let ctx = QueryContext::create_from(self.ctx.clone());
+        let pipeline_builder =
+            PipelineBuilder::create(ctx.clone(), self.enable_profiling, self.prof_span_set.clone())
+                .with_recursive_cte_working_tables(self.working_tables.clone())
+                .with_materialized_cte_states(self.materialized_cte_states.clone());
+        let mut build_res = pipeline_builder.finalize(plan)?;
+
+        let settings = ctx.get_settings();
+        build_res.set_max_threads(settings.get_max_threads()? as usize);
+        let executor_settings = ExecutorSettings::try_create(&settings, ctx.get_id())?;
+        let mut executor = PipelinePullingExecutor::from_pipelines(build_res, executor_settings)?;
+        executor.start();
+
+        let schema = plan.output_schema()?;
+        let mut blocks = vec![];
+        while let Some(block) = executor.pull_data()? {
+            if !block.is_empty() {
+                blocks.push(self.project_block(block, &schema, is_left)?);
+            }
+        }
+        Ok(blocks)
+    }
+
+    fn project_block(
+        &self,
+        block: DataBlock,
+        schema: &DataSchemaRef,
+        is_left: bool,
+    ) -> Result<DataBlock> {
+        let num_rows = block.num_rows();
+        let columns = self
+            .plan
+            .pairs
+            .iter()
+            .map(|(left, right)| {
+                let name = if is_left { left } else { right };
+                Ok(block.get_by_offset(schema.index_of(name)?).clone())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(DataBlock::new(columns, num_rows))
+    }
+
+    /// Remove the rows that have been produced by the previous iterations.
+    fn remove_duplicated_rows(&mut self, block: DataBlock) -> Result<DataBlock> {
+        let distinct_rows = match &mut self.distinct_rows {
+            Some(distinct_rows) => distinct_rows,
+            None => return Ok(block),
+        };
+
+        let mut indices = Vec::with_capacity(block.num_rows());
+        for row in 0..block.num_rows() {
+            let key = block
+                .columns()
+                .iter()
+                .map(|entry| entry.value.as_ref().index(row).unwrap().to_owned())
+                .collect::<Vec<_>>();
+            if distinct_rows.insert(key) {
+                indices.push(row as u32);
+            }
+        }
+        if indices.len() == block.num_rows() {
+            return Ok(block);
+        }
+        block.take(&indices)
+    }
+}
+
+impl SyncSource for TransformRecursiveUnion {
+    const NAME: &'static str = "RecursiveUnion";
+
+    fn generate(&mut self) -> Result<Option<DataBlock>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let blocks = if !self.left_executed {
+            self.left_executed = true;
+            self.execute_term(&self.plan.left, true)?
+        } else {
+            if self.depth >= self.max_depth {
+                return Err(ErrorCode::Overflow(format!(
+                    "Recursive query aborted after {} iterations. Try increasing max_cte_recursive_depth",
+                    self.max_depth
+                )));
+            }
+            self.depth += 1;
+            self.execute_term(&self.plan.right, false)?
+        };
+
+        let block = if blocks.is_empty() {
+            DataBlock::empty()
+        } else {
+            self.remove_duplicated_rows(DataBlock::concat(&blocks)?)?
+        };
+        if block.is_empty() {
+            // The fixpoint is reached.
+            self.finished = true;
+            return Ok(None);
+        }
+
+        let mut working_table = self.working_table.lock();
+        working_table.clear();
+        working_table.push_back(block.clone());
+        Ok(Some(block))
+    }
+}
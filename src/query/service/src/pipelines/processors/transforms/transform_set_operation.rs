@@ -0,0 +1,308 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_expression::types::DataType;
+use common_expression::with_mappedhash_method;
+use common_expression::Column;
+use common_expression::DataBlock;
+use common_expression::HashMethod;
+use common_expression::HashMethodKind;
+use common_hashtable::HashtableLike;
+use common_pipeline_core::processors::port::InputPort;
+use common_pipeline_core::processors::port::OutputPort;
+use common_pipeline_core::processors::processor::Event;
+use common_pipeline_core::processors::Processor;
+use common_pipeline_sinks::Sink;
+use common_pipeline_sinks::Sinker;
+use common_sql::plans::SetOperationType;
+use parking_lot::Mutex;
+
+use crate::pipelines::processors::transforms::group_by::HashMethodBounds;
+use crate::pipelines::processors::transforms::group_by::PolymorphicKeysHelper;
+use crate::pipelines::processors::transforms::SinkBarrier;
+
+/// The counts of the rows of the right side of `INTERSECT ALL` and `EXCEPT ALL`.
+///
+/// Rows are compared with all their columns, and NULLs are equal to each other,
+/// as the SQL standard requires for set operations.
+pub struct SetOperationState {
+    op_type: SetOperationType,
+    counts: Box<dyn RowCounts>,
+    /// Waits for all the sinks counting the right side.
+    barrier: SinkBarrier,
+}
+
+impl SetOperationState {
+    /// `key_types` are the types of the compared columns, which are the same on
+    /// both sides after the coercion of the set operation.
+    pub fn try_create(
+        op_type: SetOperationType,
+        key_types: &[DataType],
+    ) -> Result<Arc<SetOperationState>> {
+        let method = DataBlock::choose_hash_method_with_types(key_types)?;
+        let counts: Box<dyn RowCounts> = with_mappedhash_method!(|T| match method {
+            HashMethodKind::T(method) => Box::new(HashRowCounts::try_create(method)?),
+        });
+        Ok(Arc::new(SetOperationState {
+            op_type,
+            counts,
+            barrier: SinkBarrier::create(),
+        }))
+    }
+
+    /// Keep the rows of the left side that should be output.
+    ///
+    /// Every matched left row consumes one count of the right side, so a row
+    /// appearing `m` times on the left and `n` times on the right is matched
+    /// `min(m, n)` times, wherever the rows are among the blocks.
+    fn probe(&self, block: DataBlock, projections: &[usize]) -> Result<DataBlock> {
+        let matched = self.counts.consume(&block, projections)?;
+        let indices = matched
+            .into_iter()
+            .enumerate()
+            .filter(|(_, matched)| match self.op_type {
+                SetOperationType::IntersectAll => *matched,
+                SetOperationType::ExceptAll => !*matched,
+            })
+            .map(|(row, _)| row as u32)
+            .collect::<Vec<_>>();
+
+        if indices.len() == block.num_rows() {
+            return Ok(block);
+        }
+        block.take(&indices)
+    }
+}
+
+trait RowCounts: Send + Sync {
+    /// Count the rows of the block.
+    fn add(&self, block: &DataBlock, projections: &[usize]) -> Result<()>;
+
+    /// Consume one count of each row of the block if there is any left, and
+    /// return whether each row is matched.
+    fn consume(&self, block: &DataBlock, projections: &[usize]) -> Result<Vec<bool>>;
+}
+
+/// Counts the rows by the keys built with the hash method, as the hash join does.
+struct HashRowCounts<Method: HashMethodBounds> {
+    method: Method,
+    counts: Mutex<<Method as PolymorphicKeysHelper<Method>>::HashTable<usize>>,
+}
+
+impl<Method: HashMethodBounds> HashRowCounts<Method> {
+    fn try_create(method: Method) -> Result<Self> {
+        let counts = method.create_hash_table()?;
+        Ok(HashRowCounts {
+            method,
+            counts: Mutex::new(counts),
+        })
+    }
+}
+
+impl<Method: HashMethodBounds> RowCounts for HashRowCounts<Method> {
+    fn add(&self, block: &DataBlock, projections: &[usize]) -> Result<()> {
+        let keys_state = self
+            .method
+            .build_keys_state(&key_columns(block, projections), block.num_rows())?;
+        let keys_iter = self.method.build_keys_iter(&keys_state)?;
+
+        let mut counts = self.counts.lock();
+        for key in keys_iter {
+            match unsafe { counts.insert(key) } {
+                Ok(count) => {
+                    count.write(1);
+                }
+                Err(count) => *count += 1,
+            }
+        }
+        Ok(())
+    }
+
+    fn consume(&self, block: &DataBlock, projections: &[usize]) -> Result<Vec<bool>> {
+        let keys_state = self
+            .method
+            .build_keys_state(&key_columns(block, projections), block.num_rows())?;
+        let keys_iter = self.method.build_keys_iter(&keys_state)?;
+
+        let mut counts = self.counts.lock();
+        Ok(keys_iter
+            .map(|key| match counts.get_mut(key) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    true
+                }
+                _ => false,
+            })
+            .collect())
+    }
+}
+
+fn key_columns(block: &DataBlock, projections: &[usize]) -> Vec<(Column, DataType)> {
+    projections
+        .iter()
+        .map(|offset| {
+            let entry = block.get_by_offset(*offset);
+            (
+                entry.value.as_column().unwrap().clone(),
+                entry.data_type.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Count the rows of the right side of a set operation.
+pub struct SinkBuildSetOperation {
+    state: Arc<SetOperationState>,
+    /// The offsets of the compared columns in the input blocks.
+    projections: Vec<usize>,
+}
+
+impl SinkBuildSetOperation {
+    pub fn create(
+        input: Arc<InputPort>,
+        state: Arc<SetOperationState>,
+        projections: Vec<usize>,
+    ) -> Box<dyn Processor> {
+        state.barrier.attach();
+        Sinker::create(input, SinkBuildSetOperation { state, projections })
+    }
+}
+
+impl Sink for SinkBuildSetOperation {
+    const NAME: &'static str = "BuildSetOperation";
+
+    fn on_finish(&mut self) -> Result<()> {
+        self.state.barrier.detach();
+        Ok(())
+    }
+
+    fn consume(&mut self, data_block: DataBlock) -> Result<()> {
+        self.state
+            .counts
+            .add(&data_block.convert_to_full(), &self.projections)
+    }
+}
+
+enum SetOperationStep {
+    Build,
+    Probe,
+}
+
+/// Match the rows of the left side of a set operation against the counts of
+/// the right side, once all of them are counted.
+pub struct TransformSetOperationProbe {
+    input_port: Arc<InputPort>,
+    output_port: Arc<OutputPort>,
+    input_data: Option<DataBlock>,
+    output_data: Option<DataBlock>,
+
+    step: SetOperationStep,
+    state: Arc<SetOperationState>,
+    /// The offsets of the compared columns in the input blocks.
+    projections: Vec<usize>,
+}
+
+impl TransformSetOperationProbe {
+    pub fn create(
+        input_port: Arc<InputPort>,
+        output_port: Arc<OutputPort>,
+        state: Arc<SetOperationState>,
+        projections: Vec<usize>,
+    ) -> Box<dyn Processor> {
+        Box::new(TransformSetOperationProbe {
+            input_port,
+            output_port,
+            input_data: None,
+            output_data: None,
+            step: SetOperationStep::Build,
+            state,
+            projections,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for TransformSetOperationProbe {
+    fn name(&self) -> String {
+        "SetOperation".to_string()
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        match self.step {
+            SetOperationStep::Build => Ok(Event::Async),
+            SetOperationStep::Probe => {
+                if self.output_port.is_finished() {
+                    self.input_port.finish();
+                    return Ok(Event::Finished);
+                }
+
+                if !self.output_port.can_push() {
+                    self.input_port.set_not_need_data();
+                    return Ok(Event::NeedConsume);
+                }
+
+                if let Some(data) = self.output_data.take() {
+                    self.output_port.push_data(Ok(data));
+                    return Ok(Event::NeedConsume);
+                }
+
+                if self.input_data.is_some() {
+                    return Ok(Event::Sync);
+                }
+
+                if self.input_port.has_data() {
+                    let data = self.input_port.pull_data().unwrap()?;
+                    self.input_data = Some(data);
+                    return Ok(Event::Sync);
+                }
+
+                if self.input_port.is_finished() {
+                    self.output_port.finish();
+                    return Ok(Event::Finished);
+                }
+
+                self.input_port.set_need_data();
+                Ok(Event::NeedData)
+            }
+        }
+    }
+
+    fn process(&mut self) -> Result<()> {
+        if let Some(data) = self.input_data.take() {
+            let data = self
+                .state
+                .probe(data.convert_to_full(), &self.projections)?;
+            if !data.is_empty() {
+                self.output_data = Some(data);
+            }
+        }
+        Ok(())
+    }
+
+    async fn async_process(&mut self) -> Result<()> {
+        if let SetOperationStep::Build = &self.step {
+            self.state.barrier.wait_finish().await;
+            self.step = SetOperationStep::Probe;
+        }
+        Ok(())
+    }
+}
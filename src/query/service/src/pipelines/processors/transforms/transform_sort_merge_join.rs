@@ -0,0 +1,399 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use async_channel::Receiver;
+use common_exception::Result;
+use common_expression::types::BooleanType;
+use common_expression::Column;
+use common_expression::DataBlock;
+use common_expression::Evaluator;
+use common_expression::Expr;
+use common_expression::FunctionContext;
+use common_expression::Scalar;
+use common_expression::ScalarRef;
+use common_functions::scalars::BUILTIN_FUNCTIONS;
+
+use crate::pipelines::processors::port::InputPort;
+use crate::pipelines::processors::port::OutputPort;
+use crate::pipelines::processors::processor::Event;
+use crate::pipelines::processors::Processor;
+
+/// A block of a sorted input and the position of the next row to merge.
+struct SortedCursor {
+    block: DataBlock,
+    keys: Vec<Column>,
+    row: usize,
+}
+
+impl SortedCursor {
+    fn try_create(block: DataBlock, keys: &[Expr], func_ctx: FunctionContext) -> Result<Self> {
+        let num_rows = block.num_rows();
+        let evaluator = Evaluator::new(&block, func_ctx, &BUILTIN_FUNCTIONS);
+        let keys = keys
+            .iter()
+            .map(|key| {
+                let value = evaluator.run(key)?;
+                Ok(value.convert_to_full_column(key.data_type(), num_rows))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SortedCursor {
+            block,
+            keys,
+            row: 0,
+        })
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.row >= self.block.num_rows()
+    }
+
+    fn key(&self, row: usize) -> Vec<ScalarRef> {
+        self.keys
+            .iter()
+            .map(|column| column.index(row).unwrap())
+            .collect()
+    }
+
+    /// Move to the end of the rows with the given key, returns the skipped rows.
+    fn skip_key(&mut self, key: &[Scalar]) -> Option<DataBlock> {
+        let start = self.row;
+        while !self.is_exhausted() && compare_keys(&self.key(self.row), key) == Ordering::Equal {
+            self.row += 1;
+        }
+        (self.row > start).then(|| self.block.slice(start..self.row))
+    }
+}
+
+/// Compare two join keys without NULL values.
+fn compare_keys(left: &[ScalarRef], right: &[Scalar]) -> Ordering {
+    for (left, right) in left.iter().zip(right.iter()) {
+        match left.cmp(&right.as_ref()) {
+            Ordering::Equal => continue,
+            ordering => return ordering,
+        }
+    }
+    Ordering::Equal
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Need {
+    /// Merge the blocks already received
+    Process,
+    /// Pull the next block of the left side
+    Left,
+    /// Receive the next block of the right side
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MergeState {
+    /// Look for the next key that exists on both sides
+    Scan,
+    /// Collect the right rows with the current key
+    CollectRight,
+    /// Join the left rows with the current key to the collected right rows
+    MatchLeft,
+}
+
+/// Inner join two inputs sorted by the join keys in ascending order.
+///
+/// The left input is pulled from the input port, and the right input is received
+/// from a sub-pipeline. Only the right rows with the current join key are kept in
+/// memory, rows with NULL keys never match.
+pub struct TransformSortMergeJoin {
+    input: Arc<InputPort>,
+    output: Arc<OutputPort>,
+    receiver: Receiver<DataBlock>,
+
+    func_ctx: FunctionContext,
+    left_keys: Vec<Expr>,
+    right_keys: Vec<Expr>,
+    other_predicate: Option<Expr>,
+
+    need: Need,
+    state: MergeState,
+    left: Option<SortedCursor>,
+    right: Option<SortedCursor>,
+    left_data: Option<DataBlock>,
+    right_data: Option<DataBlock>,
+    left_finished: bool,
+    right_finished: bool,
+    /// The merge is done, the rest of the right side is discarded.
+    finished: bool,
+
+    current_key: Vec<Scalar>,
+    right_rows: Vec<DataBlock>,
+    output_data: VecDeque<DataBlock>,
+}
+
+impl TransformSortMergeJoin {
+    pub fn try_create(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        receiver: Receiver<DataBlock>,
+        func_ctx: FunctionContext,
+        left_keys: Vec<Expr>,
+        right_keys: Vec<Expr>,
+        other_predicate: Option<Expr>,
+    ) -> Result<Box<dyn Processor>> {
+        Ok(Box::new(TransformSortMergeJoin {
+            input,
+            output,
+            receiver,
+            func_ctx,
+            left_keys,
+            right_keys,
+            other_predicate,
+            need: Need::Process,
+            state: MergeState::Scan,
+            left: None,
+            right: None,
+            left_data: None,
+            right_data: None,
+            left_finished: false,
+            right_finished: false,
+            finished: false,
+            current_key: vec![],
+            right_rows: vec![],
+            output_data: VecDeque::new(),
+        }))
+    }
+
+    /// Merge the cursors until a side runs out of rows, returns what is needed
+    /// to continue, or `None` if the merge is done.
+    fn merge(&mut self) -> Result<Option<Need>> {
+        loop {
+            let left = self.left.as_mut().filter(|cursor| !cursor.is_exhausted());
+            let right = self.right.as_mut().filter(|cursor| !cursor.is_exhausted());
+            match self.state {
+                MergeState::Scan => {
+                    let (left, right) = match (left, right) {
+                        (Some(left), Some(right)) => (left, right),
+                        (None, _) if !self.left_finished => return Ok(Some(Need::Left)),
+                        (_, None) if !self.right_finished => return Ok(Some(Need::Right)),
+                        _ => return Ok(None),
+                    };
+                    let left_key = left.key(left.row);
+                    if left_key.contains(&ScalarRef::Null) {
+                        left.row += 1;
+                        continue;
+                    }
+                    let right_key = right.key(right.row);
+                    if right_key.contains(&ScalarRef::Null) {
+                        right.row += 1;
+                        continue;
+                    }
+                    let right_key = right_key
+                        .into_iter()
+                        .map(|scalar| scalar.to_owned())
+                        .collect::<Vec<_>>();
+                    match compare_keys(&left_key, &right_key) {
+                        Ordering::Less => left.row += 1,
+                        Ordering::Greater => right.row += 1,
+                        Ordering::Equal => {
+                            self.current_key = right_key;
+                            self.state = MergeState::CollectRight;
+                        }
+                    }
+                }
+                MergeState::CollectRight => {
+                    let right = match right {
+                        Some(right) => right,
+                        None if !self.right_finished => return Ok(Some(Need::Right)),
+                        None => {
+                            self.state = MergeState::MatchLeft;
+                            continue;
+                        }
+                    };
+                    if let Some(rows) = right.skip_key(&self.current_key) {
+                        self.right_rows.push(rows);
+                    }
+                    if !right.is_exhausted() {
+                        self.state = MergeState::MatchLeft;
+                    }
+                }
+                MergeState::MatchLeft => {
+                    let left = match left {
+                        Some(left) => left,
+                        None if !self.left_finished => return Ok(Some(Need::Left)),
+                        None => return Ok(None),
+                    };
+                    if let Some(rows) = left.skip_key(&self.current_key) {
+                        let blocks = join_rows(
+                            &rows,
+                            &self.right_rows,
+                            self.other_predicate.as_ref(),
+                            self.func_ctx,
+                        )?;
+                        self.output_data.extend(blocks);
+                    }
+                    if !left.is_exhausted() {
+                        self.right_rows.clear();
+                        self.state = MergeState::Scan;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Join the left rows to all the collected right rows, they have the same key.
+fn join_rows(
+    left_rows: &DataBlock,
+    right_rows: &[DataBlock],
+    other_predicate: Option<&Expr>,
+    func_ctx: FunctionContext,
+) -> Result<Vec<DataBlock>> {
+    let mut blocks = Vec::with_capacity(right_rows.len());
+    for right_rows in right_rows.iter() {
+        let num_left = left_rows.num_rows();
+        let num_right = right_rows.num_rows();
+        let mut left_indices = Vec::with_capacity(num_left * num_right);
+        let mut right_indices = Vec::with_capacity(num_left * num_right);
+        for left_index in 0..num_left as u32 {
+            for right_index in 0..num_right as u32 {
+                left_indices.push(left_index);
+                right_indices.push(right_index);
+            }
+        }
+
+        let mut block = left_rows.take(&left_indices)?;
+        for column in right_rows.take(&right_indices)?.columns() {
+            block.add_column(column.clone());
+        }
+
+        if let Some(predicate) = other_predicate {
+            let evaluator = Evaluator::new(&block, func_ctx, &BUILTIN_FUNCTIONS);
+            let filter = evaluator
+                .run(predicate)?
+                .try_downcast::<BooleanType>()
+                .unwrap();
+            block = block.filter_boolean_value(&filter)?;
+        }
+        if !block.is_empty() {
+            blocks.push(block);
+        }
+    }
+    Ok(blocks)
+}
+
+#[async_trait::async_trait]
+impl Processor for TransformSortMergeJoin {
+    fn name(&self) -> String {
+        "TransformSortMergeJoin".to_string()
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        if self.output.is_finished() {
+            self.input.finish();
+            self.finished = true;
+            self.output_data.clear();
+            // Drain the right side, otherwise the sub-pipeline would wait forever.
+            if self.right_finished {
+                return Ok(Event::Finished);
+            }
+            return Ok(Event::Async);
+        }
+
+        if !self.output.can_push() {
+            self.input.set_not_need_data();
+            return Ok(Event::NeedConsume);
+        }
+
+        if let Some(output_data) = self.output_data.pop_front() {
+            self.output.push_data(Ok(output_data));
+            return Ok(Event::NeedConsume);
+        }
+
+        if self.finished {
+            self.input.finish();
+            if !self.right_finished {
+                return Ok(Event::Async);
+            }
+            self.output.finish();
+            return Ok(Event::Finished);
+        }
+
+        match self.need {
+            Need::Process => Ok(Event::Sync),
+            Need::Right => Ok(Event::Async),
+            Need::Left => {
+                if self.input.has_data() {
+                    self.left_data = Some(self.input.pull_data().unwrap()?);
+                    self.need = Need::Process;
+                    return Ok(Event::Sync);
+                }
+                if self.input.is_finished() {
+                    self.left_finished = true;
+                    self.need = Need::Process;
+                    return Ok(Event::Sync);
+                }
+                self.input.set_need_data();
+                Ok(Event::NeedData)
+            }
+        }
+    }
+
+    fn process(&mut self) -> Result<()> {
+        if let Some(block) = self.left_data.take() {
+            self.left = Some(SortedCursor::try_create(
+                block,
+                &self.left_keys,
+                self.func_ctx,
+            )?);
+        }
+        if let Some(block) = self.right_data.take() {
+            self.right = Some(SortedCursor::try_create(
+                block,
+                &self.right_keys,
+                self.func_ctx,
+            )?);
+        }
+
+        match self.merge()? {
+            Some(need) => self.need = need,
+            None => {
+                self.finished = true;
+                self.left = None;
+                self.right = None;
+                self.right_rows.clear();
+            }
+        }
+        Ok(())
+    }
+
+    async fn async_process(&mut self) -> Result<()> {
+        if self.finished {
+            while self.receiver.recv().await.is_ok() {}
+            self.right_finished = true;
+            return Ok(());
+        }
+
+        match self.receiver.recv().await {
+            Ok(block) => self.right_data = Some(block),
+            Err(_) => self.right_finished = true,
+        }
+        self.need = Need::Process;
+        Ok(())
+    }
+}
@@ -0,0 +1,523 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use bumpalo::Bump;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::types::number::NumberScalar;
+use common_expression::types::DataType;
+use common_expression::BlockEntry;
+use common_expression::Column;
+use common_expression::ColumnBuilder;
+use common_expression::DataBlock;
+use common_expression::Literal;
+use common_expression::Scalar;
+use common_expression::ScalarRef;
+use common_expression::SortColumnDescription;
+use common_expression::Value;
+use common_functions::aggregates::AggregateFunctionRef;
+use common_functions::aggregates::StateAddr;
+use common_pipeline_core::processors::port::InputPort;
+use common_pipeline_core::processors::port::OutputPort;
+use common_pipeline_core::processors::Processor;
+use common_pipeline_transforms::processors::transforms::transform_accumulating::AccumulatingTransform;
+use common_pipeline_transforms::processors::transforms::transform_accumulating::AccumulatingTransformer;
+use common_sql::plans::WindowFuncFrame;
+use common_sql::plans::WindowFuncFrameBound;
+use common_sql::plans::WindowFuncFrameUnits;
+
+/// Window function to be evaluated by [`TransformWindow`], the arguments
+/// are offsets of columns in the input block.
+#[derive(Clone)]
+pub enum WindowFunctionImpl {
+    Aggregate {
+        agg: AggregateFunctionRef,
+        args: Vec<usize>,
+    },
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag {
+        arg: usize,
+        offset: usize,
+        default: Option<usize>,
+    },
+    Lead {
+        arg: usize,
+        offset: usize,
+        default: Option<usize>,
+    },
+    FirstValue {
+        arg: usize,
+    },
+    LastValue {
+        arg: usize,
+    },
+}
+
+/// Evaluate a window function over the input, which must have been sorted
+/// by partition keys and then order keys, and be processed by a single processor.
+///
+/// Rows of a partition are buffered until the first row of the next partition
+/// arrives, then the whole partition is evaluated and output with the result
+/// column appended.
+pub struct TransformWindow {
+    func: WindowFunctionImpl,
+    return_type: DataType,
+    partition_by: Vec<usize>,
+    order_by: Vec<SortColumnDescription>,
+    frame: WindowFuncFrame,
+
+    /// Aggregate state, only used by aggregate window functions
+    #[allow(dead_code)]
+    arena: Bump,
+    place: Option<StateAddr>,
+
+    /// Rows of current partition
+    buffer: Vec<DataBlock>,
+}
+
+impl TransformWindow {
+    pub fn try_create(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        func: WindowFunctionImpl,
+        return_type: DataType,
+        partition_by: Vec<usize>,
+        order_by: Vec<SortColumnDescription>,
+        frame: WindowFuncFrame,
+    ) -> Result<Box<dyn Processor>> {
+        let arena = Bump::new();
+        let place = match &func {
+            WindowFunctionImpl::Aggregate { agg, .. } => {
+                let place: StateAddr = arena.alloc_layout(agg.state_layout()).into();
+                agg.init_state(place);
+                Some(place)
+            }
+            _ => None,
+        };
+
+        Ok(AccumulatingTransformer::create(input, output, TransformWindow {
+            func,
+            return_type,
+            partition_by,
+            order_by,
+            frame,
+            arena,
+            place,
+            buffer: vec![],
+        }))
+    }
+
+    fn is_same_partition(
+        &self,
+        lhs: &DataBlock,
+        lhs_row: usize,
+        rhs: &DataBlock,
+        rhs_row: usize,
+    ) -> bool {
+        self.partition_by
+            .iter()
+            .all(|offset| value_at(lhs, *offset, lhs_row) == value_at(rhs, *offset, rhs_row))
+    }
+
+    /// Evaluate the window function over the buffered partition.
+    fn flush_partition(&mut self) -> Result<Option<DataBlock>> {
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+        let mut block = DataBlock::concat(&std::mem::take(&mut self.buffer))?;
+        if block.is_empty() {
+            return Ok(None);
+        }
+
+        let column = self.evaluate(&block)?;
+        block.add_column(BlockEntry {
+            data_type: self.return_type.clone(),
+            value: Value::Column(column),
+        });
+        Ok(Some(block))
+    }
+
+    fn evaluate(&self, block: &DataBlock) -> Result<Column> {
+        let num_rows = block.num_rows();
+        let (peer_start, peer_end) = self.peer_groups(block);
+        let mut builder = ColumnBuilder::with_capacity(&self.return_type, num_rows);
+
+        match &self.func {
+            WindowFunctionImpl::RowNumber => {
+                for row in 0..num_rows {
+                    builder.push(ScalarRef::Number(NumberScalar::UInt64(row as u64 + 1)));
+                }
+            }
+            WindowFunctionImpl::Rank => {
+                for start in peer_start.iter() {
+                    builder.push(ScalarRef::Number(NumberScalar::UInt64(*start as u64 + 1)));
+                }
+            }
+            WindowFunctionImpl::DenseRank => {
+                let mut rank = 0;
+                for (row, start) in peer_start.iter().enumerate() {
+                    if *start == row {
+                        rank += 1;
+                    }
+                    builder.push(ScalarRef::Number(NumberScalar::UInt64(rank)));
+                }
+            }
+            WindowFunctionImpl::Lag {
+                arg,
+                offset,
+                default,
+            } => {
+                for row in 0..num_rows {
+                    if row >= *offset {
+                        builder.push(value_at(block, *arg, row - offset));
+                    } else {
+                        match default {
+                            Some(default) => builder.push(value_at(block, *default, row)),
+                            None => builder.push(ScalarRef::Null),
+                        }
+                    }
+                }
+            }
+            WindowFunctionImpl::Lead {
+                arg,
+                offset,
+                default,
+            } => {
+                for row in 0..num_rows {
+                    if row + offset < num_rows {
+                        builder.push(value_at(block, *arg, row + offset));
+                    } else {
+                        match default {
+                            Some(default) => builder.push(value_at(block, *default, row)),
+                            None => builder.push(ScalarRef::Null),
+                        }
+                    }
+                }
+            }
+            WindowFunctionImpl::FirstValue { arg } => {
+                let frames = self.frames(block, &peer_start, &peer_end)?;
+                for (start, end) in frames {
+                    if start < end {
+                        builder.push(value_at(block, *arg, start));
+                    } else {
+                        builder.push(ScalarRef::Null);
+                    }
+                }
+            }
+            WindowFunctionImpl::LastValue { arg } => {
+                let frames = self.frames(block, &peer_start, &peer_end)?;
+                for (start, end) in frames {
+                    if start < end {
+                        builder.push(value_at(block, *arg, end - 1));
+                    } else {
+                        builder.push(ScalarRef::Null);
+                    }
+                }
+            }
+            WindowFunctionImpl::Aggregate { agg, args } => {
+                let place = self
+                    .place
+                    .ok_or_else(|| ErrorCode::Internal("aggregate state is not initialized"))?;
+                let columns = args
+                    .iter()
+                    .map(|offset| {
+                        block
+                            .get_by_offset(*offset)
+                            .value
+                            .as_column()
+                            .unwrap()
+                            .clone()
+                    })
+                    .collect::<Vec<_>>();
+                let frames = self.frames(block, &peer_start, &peer_end)?;
+
+                reset_state(agg, place);
+                if matches!(self.frame.start_bound, WindowFuncFrameBound::Preceding(None)) {
+                    // The frame always starts from the first row, and the end of frame
+                    // never moves backward, so the state can be accumulated incrementally.
+                    let mut accumulated = 0;
+                    for (_, end) in frames {
+                        while accumulated < end {
+                            agg.accumulate_row(place, &columns, accumulated)?;
+                            accumulated += 1;
+                        }
+                        agg.merge_result(place, &mut builder)?;
+                    }
+                } else {
+                    for (start, end) in frames {
+                        reset_state(agg, place);
+                        for row in start..end {
+                            agg.accumulate_row(place, &columns, row)?;
+                        }
+                        agg.merge_result(place, &mut builder)?;
+                    }
+                }
+                // Reset the state for next partition
+                reset_state(agg, place);
+            }
+        }
+
+        Ok(builder.build())
+    }
+
+    /// Compute the first row (inclusive) and the last row (exclusive) of the
+    /// peer group of each row, rows are peers if their order keys are equal.
+    fn peer_groups(&self, block: &DataBlock) -> (Vec<usize>, Vec<usize>) {
+        let num_rows = block.num_rows();
+        let mut peer_start = vec![0; num_rows];
+        let mut peer_end = vec![num_rows; num_rows];
+
+        let mut start = 0;
+        for row in 1..num_rows {
+            let is_peer = self.order_by.iter().all(|desc| {
+                value_at(block, desc.offset, row - 1) == value_at(block, desc.offset, row)
+            });
+            if !is_peer {
+                for end in peer_end.iter_mut().take(row).skip(start) {
+                    *end = row;
+                }
+                start = row;
+            }
+            peer_start[row] = start;
+        }
+        (peer_start, peer_end)
+    }
+
+    /// Compute the frame `[start, end)` of each row.
+    fn frames(
+        &self,
+        block: &DataBlock,
+        peer_start: &[usize],
+        peer_end: &[usize],
+    ) -> Result<Vec<(usize, usize)>> {
+        let num_rows = block.num_rows();
+        let mut frames = Vec::with_capacity(num_rows);
+
+        match self.frame.units {
+            WindowFuncFrameUnits::Rows => {
+                for row in 0..num_rows {
+                    let start = match &self.frame.start_bound {
+                        WindowFuncFrameBound::Preceding(None) => 0,
+                        WindowFuncFrameBound::Preceding(Some(n)) => {
+                            row.saturating_sub(literal_to_usize(n)?)
+                        }
+                        WindowFuncFrameBound::CurrentRow => row,
+                        WindowFuncFrameBound::Following(Some(n)) => {
+                            (row + literal_to_usize(n)?).min(num_rows)
+                        }
+                        WindowFuncFrameBound::Following(None) => num_rows,
+                    };
+                    let end = match &self.frame.end_bound {
+                        WindowFuncFrameBound::Following(None) => num_rows,
+                        WindowFuncFrameBound::Following(Some(n)) => {
+                            (row + literal_to_usize(n)? + 1).min(num_rows)
+                        }
+                        WindowFuncFrameBound::CurrentRow => row + 1,
+                        WindowFuncFrameBound::Preceding(Some(n)) => {
+                            (row + 1).saturating_sub(literal_to_usize(n)?)
+                        }
+                        WindowFuncFrameBound::Preceding(None) => 0,
+                    };
+                    frames.push((start, end.max(start)));
+                }
+            }
+            WindowFuncFrameUnits::Range => {
+                // Positions of order key along the sort direction, `None` for NULL.
+                // Only used if there is an offset `PRECEDING` or `FOLLOWING`.
+                let positions = match self.order_by.first() {
+                    Some(desc) => (0..num_rows)
+                        .map(|row| {
+                            scalar_to_f64(value_at(block, desc.offset, row))
+                                .map(|v| if desc.asc { v } else { -v })
+                        })
+                        .collect::<Vec<_>>(),
+                    None => vec![None; num_rows],
+                };
+                let non_null_start = positions.iter().position(|v| v.is_some()).unwrap_or(0);
+                let non_null_end = positions
+                    .iter()
+                    .rposition(|v| v.is_some())
+                    .map_or(0, |end| end + 1);
+                let non_null = positions[non_null_start..non_null_end]
+                    .iter()
+                    .map(|v| v.unwrap_or_default())
+                    .collect::<Vec<_>>();
+
+                for row in 0..num_rows {
+                    let start = match &self.frame.start_bound {
+                        WindowFuncFrameBound::Preceding(None) => 0,
+                        WindowFuncFrameBound::CurrentRow => peer_start[row],
+                        WindowFuncFrameBound::Following(None) => num_rows,
+                        WindowFuncFrameBound::Preceding(Some(n))
+                        | WindowFuncFrameBound::Following(Some(n)) => match positions[row] {
+                            // Peers of NULL are all the NULLs
+                            None => peer_start[row],
+                            Some(pos) => {
+                                let target = range_offset_target(
+                                    pos,
+                                    &self.frame.start_bound,
+                                    literal_to_f64(n)?,
+                                );
+                                non_null_start + non_null.partition_point(|v| *v < target)
+                            }
+                        },
+                    };
+                    let end = match &self.frame.end_bound {
+                        WindowFuncFrameBound::Following(None) => num_rows,
+                        WindowFuncFrameBound::CurrentRow => peer_end[row],
+                        WindowFuncFrameBound::Preceding(None) => 0,
+                        WindowFuncFrameBound::Preceding(Some(n))
+                        | WindowFuncFrameBound::Following(Some(n)) => match positions[row] {
+                            None => peer_end[row],
+                            Some(pos) => {
+                                let target = range_offset_target(
+                                    pos,
+                                    &self.frame.end_bound,
+                                    literal_to_f64(n)?,
+                                );
+                                non_null_start + non_null.partition_point(|v| *v <= target)
+                            }
+                        },
+                    };
+                    frames.push((start, end.max(start)));
+                }
+            }
+        }
+
+        Ok(frames)
+    }
+}
+
+impl AccumulatingTransform for TransformWindow {
+    const NAME: &'static str = "TransformWindow";
+
+    fn transform(&mut self, block: DataBlock) -> Result<Option<DataBlock>> {
+        let block = block.convert_to_full();
+        let num_rows = block.num_rows();
+        if num_rows == 0 {
+            return Ok(None);
+        }
+
+        let mut output_blocks = vec![];
+        let mut start = 0;
+        for row in 0..num_rows {
+            let is_new_partition = if row == 0 {
+                match self.buffer.last() {
+                    Some(last) => !self.is_same_partition(last, last.num_rows() - 1, &block, 0),
+                    None => false,
+                }
+            } else {
+                !self.is_same_partition(&block, row - 1, &block, row)
+            };
+
+            if is_new_partition {
+                if row > start {
+                    self.buffer.push(block.slice(start..row));
+                }
+                if let Some(output) = self.flush_partition()? {
+                    output_blocks.push(output);
+                }
+                start = row;
+            }
+        }
+        self.buffer.push(block.slice(start..num_rows));
+
+        match output_blocks.len() {
+            0 => Ok(None),
+            1 => Ok(output_blocks.pop()),
+            _ => Ok(Some(DataBlock::concat(&output_blocks)?)),
+        }
+    }
+
+    fn on_finish(&mut self, output: bool) -> Result<Option<DataBlock>> {
+        let result = if output {
+            self.flush_partition()?
+        } else {
+            None
+        };
+
+        // destroy states
+        if let (WindowFunctionImpl::Aggregate { agg, .. }, Some(place)) = (&self.func, self.place)
+        {
+            if agg.need_manual_drop_state() {
+                unsafe { agg.drop_state(place) }
+            }
+        }
+        self.place = None;
+
+        Ok(result)
+    }
+}
+
+fn value_at(block: &DataBlock, offset: usize, row: usize) -> ScalarRef {
+    match &block.get_by_offset(offset).value {
+        Value::Scalar(scalar) => scalar.as_ref(),
+        Value::Column(column) => column.index(row).unwrap(),
+    }
+}
+
+fn reset_state(agg: &AggregateFunctionRef, place: StateAddr) {
+    if agg.need_manual_drop_state() {
+        unsafe { agg.drop_state(place) }
+    }
+    agg.init_state(place);
+}
+
+/// Position of the boundary of a `RANGE` frame with offset, `pos` is
+/// the position of current row along the sort direction.
+fn range_offset_target(pos: f64, bound: &WindowFuncFrameBound, offset: f64) -> f64 {
+    match bound {
+        WindowFuncFrameBound::Preceding(_) => pos - offset,
+        _ => pos + offset,
+    }
+}
+
+fn scalar_to_f64(scalar: ScalarRef) -> Option<f64> {
+    match scalar {
+        ScalarRef::Number(n) => Some(number_to_f64(n)),
+        _ => None,
+    }
+}
+
+fn number_to_f64(n: NumberScalar) -> f64 {
+    match n {
+        NumberScalar::UInt8(v) => v as f64,
+        NumberScalar::UInt16(v) => v as f64,
+        NumberScalar::UInt32(v) => v as f64,
+        NumberScalar::UInt64(v) => v as f64,
+        NumberScalar::Int8(v) => v as f64,
+        NumberScalar::Int16(v) => v as f64,
+        NumberScalar::Int32(v) => v as f64,
+        NumberScalar::Int64(v) => v as f64,
+        NumberScalar::Float32(v) => v.0 as f64,
+        NumberScalar::Float64(v) => v.0,
+    }
+}
+
+fn literal_to_f64(literal: &Literal) -> Result<f64> {
+    match literal.clone().into_scalar() {
+        Scalar::Number(n) => Ok(number_to_f64(n)),
+        _ => Err(ErrorCode::Internal(format!(
+            "Invalid window frame offset: {literal}"
+        ))),
+    }
+}
+
+fn literal_to_usize(literal: &Literal) -> Result<usize> {
+    Ok(literal_to_f64(literal)? as usize)
+}
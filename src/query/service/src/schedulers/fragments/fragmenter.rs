@@ -32,6 +32,7 @@ use crate::sql::executor::ExchangeSource;
 use crate::sql::executor::HashJoin;
 use crate::sql::executor::PhysicalPlan;
 use crate::sql::executor::PhysicalPlanReplacer;
+use crate::sql::executor::SortMergeJoin;
 use crate::sql::executor::TableScan;
 
 /// Visitor to split a `PhysicalPlan` into fragments.
@@ -154,6 +155,29 @@ impl PhysicalPlanReplacer for Fragmenter {
         }))
     }
 
+    fn replace_sort_merge_join(&mut self, plan: &SortMergeJoin) -> Result<PhysicalPlan> {
+        let mut fragments = vec![];
+        let left_input = self.replace(plan.left.as_ref())?;
+
+        // Consume current fragments to prevent them being consumed by `right_input`.
+        fragments.append(&mut self.fragments);
+        let right_input = self.replace(plan.right.as_ref())?;
+
+        fragments.append(&mut self.fragments);
+        self.fragments = fragments;
+
+        Ok(PhysicalPlan::SortMergeJoin(SortMergeJoin {
+            plan_id: plan.plan_id,
+            left: Box::new(left_input),
+            right: Box::new(right_input),
+            left_keys: plan.left_keys.clone(),
+            right_keys: plan.right_keys.clone(),
+            non_equi_conditions: plan.non_equi_conditions.clone(),
+            join_type: plan.join_type.clone(),
+            stat_info: plan.stat_info.clone(),
+        }))
+    }
+
     fn replace_exchange(&mut self, plan: &Exchange) -> Result<PhysicalPlan> {
         // Recursively rewrite input
         let input = self.replace(plan.input.as_ref())?;
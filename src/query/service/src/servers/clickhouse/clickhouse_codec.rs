@@ -0,0 +1,567 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The packets of the ClickHouse native protocol.
+//!
+//! https://clickhouse.com/docs/en/native-protocol/basics
+
+use std::collections::HashMap;
+
+use bytes::BufMut;
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncReadExt;
+use common_base::base::tokio::io::AsyncWrite;
+use common_base::base::tokio::io::AsyncWriteExt;
+use common_base::base::ProgressValues;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_expression::DataBlock;
+use common_expression::DataSchemaRef;
+use common_formats::field_encoder::FieldEncoderValues;
+use futures::FutureExt;
+use naive_cityhash::cityhash128;
+
+use crate::servers::clickhouse::clickhouse_types::read_block;
+use crate::servers::clickhouse::clickhouse_types::write_block;
+
+/// The latest revision of the protocol supported by the server, the revision used by the
+/// connection is the smaller one of the server and the client.
+pub const DBMS_TCP_PROTOCOL_VERSION: u64 = 54453;
+
+// The revisions which the fields are added to the packets in.
+const DBMS_MIN_REVISION_WITH_CLIENT_INFO: u64 = 54032;
+const DBMS_MIN_REVISION_WITH_SERVER_TIMEZONE: u64 = 54058;
+const DBMS_MIN_REVISION_WITH_QUOTA_KEY_IN_CLIENT_INFO: u64 = 54060;
+const DBMS_MIN_REVISION_WITH_TOTAL_ROWS_IN_PROGRESS: u64 = 51554;
+pub const DBMS_MIN_REVISION_WITH_BLOCK_INFO: u64 = 51903;
+const DBMS_MIN_REVISION_WITH_SERVER_DISPLAY_NAME: u64 = 54372;
+const DBMS_MIN_REVISION_WITH_VERSION_PATCH: u64 = 54401;
+const DBMS_MIN_REVISION_WITH_CLIENT_WRITE_INFO: u64 = 54420;
+const DBMS_MIN_REVISION_WITH_SETTINGS_SERIALIZED_AS_STRINGS: u64 = 54429;
+const DBMS_MIN_REVISION_WITH_INTERSERVER_SECRET: u64 = 54441;
+const DBMS_MIN_REVISION_WITH_OPENTELEMETRY: u64 = 54442;
+const DBMS_MIN_PROTOCOL_VERSION_WITH_DISTRIBUTED_DEPTH: u64 = 54448;
+const DBMS_MIN_PROTOCOL_VERSION_WITH_INITIAL_QUERY_START_TIME: u64 = 54449;
+const DBMS_MIN_REVISION_WITH_PARALLEL_REPLICAS: u64 = 54453;
+
+// The packets sent by the client.
+const CLIENT_HELLO: u64 = 0;
+const CLIENT_QUERY: u64 = 1;
+const CLIENT_DATA: u64 = 2;
+const CLIENT_CANCEL: u64 = 3;
+const CLIENT_PING: u64 = 4;
+
+// The packets sent by the server.
+const SERVER_HELLO: u64 = 0;
+const SERVER_DATA: u64 = 1;
+const SERVER_EXCEPTION: u64 = 2;
+const SERVER_PROGRESS: u64 = 3;
+const SERVER_PONG: u64 = 4;
+const SERVER_END_OF_STREAM: u64 = 5;
+
+// The interface of the query in the client info.
+const INTERFACE_TCP: u8 = 1;
+
+// The methods of the compressed frames.
+const COMPRESSION_METHOD_NONE: u8 = 0x02;
+const COMPRESSION_METHOD_LZ4: u8 = 0x82;
+const COMPRESSION_METHOD_ZSTD: u8 = 0x90;
+// 16 bytes checksum, 1 byte for method, 4 bytes for compressed size, 4 bytes for uncompressed size
+const COMPRESSION_CHECKSUM_SIZE: usize = 16;
+const COMPRESSION_HEADER_SIZE: usize = 9;
+// The block is split into the frames of this size, like max_compress_block_size of ClickHouse.
+const MAX_COMPRESS_BLOCK_SIZE: usize = 1024 * 1024;
+
+// The strings and frames larger than this are rejected instead of being buffered: 1GB
+const MAX_STRING_LENGTH: usize = 1024 * 1024 * 1024;
+
+pub struct ClientHello {
+    pub client_name: String,
+    pub version_major: u64,
+    pub version_minor: u64,
+    pub revision: u64,
+    pub database: String,
+    pub user: String,
+    pub password: String,
+}
+
+pub struct ClientQuery {
+    pub query_id: String,
+    pub settings: HashMap<String, String>,
+    pub compression: bool,
+    pub query: String,
+}
+
+pub enum ClientPacket {
+    Hello(ClientHello),
+    Query(ClientQuery),
+    // The name of the columns and the data.
+    Data(Vec<String>, DataBlock),
+    Cancel,
+    Ping,
+}
+
+/// Reads the packets of the client, the data blocks are decompressed if the compression is on.
+pub struct PacketReader<R: AsyncRead + Unpin> {
+    reader: R,
+    revision: u64,
+    compression: bool,
+    // The decompressed data of the block in reading.
+    block_buf: Vec<u8>,
+    block_pos: usize,
+}
+
+impl<R: AsyncRead + Unpin> PacketReader<R> {
+    pub fn create(reader: R) -> Self {
+        PacketReader {
+            reader,
+            revision: DBMS_TCP_PROTOCOL_VERSION,
+            compression: false,
+            block_buf: vec![],
+            block_pos: 0,
+        }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn set_revision(&mut self, revision: u64) {
+        self.revision = revision;
+    }
+
+    pub fn set_compression(&mut self, compression: bool) {
+        self.compression = compression;
+    }
+
+    /// Read a packet, returns None if the client closed the connection.
+    pub async fn read_packet(&mut self) -> Result<Option<ClientPacket>> {
+        let packet_type = match self.reader.read_u8().await {
+            Ok(packet_type) => packet_type as u64,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        self.read_packet_body(packet_type).await.map(Some)
+    }
+
+    /// Check if the client cancelled the query, without waiting for the packet.
+    pub async fn try_read_cancel(&mut self) -> Result<bool> {
+        // The type of the packets are smaller than 128, which is a single byte varuint.
+        let packet_type = match self.reader.read_u8().now_or_never() {
+            None => return Ok(false),
+            Some(Err(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(true),
+            Some(packet_type) => packet_type? as u64,
+        };
+        match self.read_packet_body(packet_type).await? {
+            ClientPacket::Cancel => Ok(true),
+            _ => Err(ErrorCode::BadBytes(
+                "Unexpected packet during the query execution",
+            )),
+        }
+    }
+
+    async fn read_packet_body(&mut self, packet_type: u64) -> Result<ClientPacket> {
+        match packet_type {
+            CLIENT_HELLO => Ok(ClientPacket::Hello(ClientHello {
+                client_name: self.read_str().await?,
+                version_major: self.read_varuint().await?,
+                version_minor: self.read_varuint().await?,
+                revision: self.read_varuint().await?,
+                database: self.read_str().await?,
+                user: self.read_str().await?,
+                password: self.read_str().await?,
+            })),
+            CLIENT_QUERY => self.read_query().await.map(ClientPacket::Query),
+            CLIENT_DATA => {
+                // The name of the external table, which isn't compressed.
+                let _table_name = self.read_str().await?;
+                let (names, block) = read_block(self).await?;
+                self.block_buf.clear();
+                self.block_pos = 0;
+                Ok(ClientPacket::Data(names, block))
+            }
+            CLIENT_CANCEL => Ok(ClientPacket::Cancel),
+            CLIENT_PING => Ok(ClientPacket::Ping),
+            _ => Err(ErrorCode::Unimplemented(format!(
+                "Unsupported client packet {}",
+                packet_type
+            ))),
+        }
+    }
+
+    async fn read_query(&mut self) -> Result<ClientQuery> {
+        let query_id = self.read_str().await?;
+        if self.revision >= DBMS_MIN_REVISION_WITH_CLIENT_INFO {
+            self.skip_client_info().await?;
+        }
+
+        if self.revision < DBMS_MIN_REVISION_WITH_SETTINGS_SERIALIZED_AS_STRINGS {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Unsupported protocol revision {}, the client is too old",
+                self.revision
+            )));
+        }
+        let mut settings = HashMap::new();
+        loop {
+            let name = self.read_str().await?;
+            if name.is_empty() {
+                break;
+            }
+            let _flags = self.read_varuint().await?;
+            let value = self.read_str().await?;
+            settings.insert(name, value);
+        }
+
+        if self.revision >= DBMS_MIN_REVISION_WITH_INTERSERVER_SECRET {
+            let _secret = self.read_str().await?;
+        }
+        let _stage = self.read_varuint().await?;
+        let compression = self.read_varuint().await? != 0;
+        let query = self.read_str().await?;
+        Ok(ClientQuery {
+            query_id,
+            settings,
+            compression,
+            query,
+        })
+    }
+
+    // The client info is only used by the distributed queries of ClickHouse.
+    async fn skip_client_info(&mut self) -> Result<()> {
+        let query_kind = self.reader.read_u8().await?;
+        if query_kind == 0 {
+            return Ok(());
+        }
+        let _initial_user = self.read_str().await?;
+        let _initial_query_id = self.read_str().await?;
+        let _initial_address = self.read_str().await?;
+        if self.revision >= DBMS_MIN_PROTOCOL_VERSION_WITH_INITIAL_QUERY_START_TIME {
+            let _start_time = self.reader.read_u64_le().await?;
+        }
+
+        let interface = self.reader.read_u8().await?;
+        if interface != INTERFACE_TCP {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Unsupported query interface {}",
+                interface
+            )));
+        }
+        let _os_user = self.read_str().await?;
+        let _client_hostname = self.read_str().await?;
+        let _client_name = self.read_str().await?;
+        for _ in 0..3 {
+            // The major version, the minor version and the revision of the client.
+            self.read_varuint().await?;
+        }
+
+        if self.revision >= DBMS_MIN_REVISION_WITH_QUOTA_KEY_IN_CLIENT_INFO {
+            let _quota_key = self.read_str().await?;
+        }
+        if self.revision >= DBMS_MIN_PROTOCOL_VERSION_WITH_DISTRIBUTED_DEPTH {
+            let _distributed_depth = self.read_varuint().await?;
+        }
+        if self.revision >= DBMS_MIN_REVISION_WITH_VERSION_PATCH {
+            let _version_patch = self.read_varuint().await?;
+        }
+        if self.revision >= DBMS_MIN_REVISION_WITH_OPENTELEMETRY
+            && self.reader.read_u8().await? != 0
+        {
+            // The trace id, the span id, the trace state and the trace flags.
+            let mut trace = [0; 24];
+            self.reader.read_exact(&mut trace).await?;
+            let _trace_state = self.read_str().await?;
+            let _trace_flags = self.reader.read_u8().await?;
+        }
+        if self.revision >= DBMS_MIN_REVISION_WITH_PARALLEL_REPLICAS {
+            for _ in 0..3 {
+                self.read_varuint().await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn read_varuint(&mut self) -> Result<u64> {
+        let mut value = 0;
+        for i in 0..10 {
+            let byte = self.reader.read_u8().await?;
+            value |= ((byte & 0x7f) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ErrorCode::BadBytes("Invalid varuint in the packet"))
+    }
+
+    async fn read_str(&mut self) -> Result<String> {
+        let len = self.read_varuint().await? as usize;
+        if len > MAX_STRING_LENGTH {
+            return Err(ErrorCode::BadBytes(format!(
+                "Invalid length {} of the string",
+                len
+            )));
+        }
+        let mut buf = vec![0; len];
+        self.reader.read_exact(&mut buf).await?;
+        String::from_utf8(buf)
+            .map_err(|e| ErrorCode::BadBytes(format!("Invalid utf8 string: {}", e)))
+    }
+
+    /// Read the bytes of the block, which are decompressed frame by frame if the compression is on.
+    pub async fn read_block_bytes(&mut self, len: usize) -> Result<&[u8]> {
+        if len > MAX_STRING_LENGTH {
+            return Err(ErrorCode::BadBytes(format!(
+                "Invalid length {} of the block data",
+                len
+            )));
+        }
+        while self.block_buf.len() - self.block_pos < len {
+            self.block_buf.drain(..self.block_pos);
+            self.block_pos = 0;
+            if self.compression {
+                let frame = self.read_frame().await?;
+                self.block_buf.extend_from_slice(&frame);
+            } else {
+                let start = self.block_buf.len();
+                self.block_buf.resize(len, 0);
+                self.reader.read_exact(&mut self.block_buf[start..]).await?;
+            }
+        }
+        let bytes = &self.block_buf[self.block_pos..self.block_pos + len];
+        self.block_pos += len;
+        Ok(bytes)
+    }
+
+    pub async fn read_block_varuint(&mut self) -> Result<u64> {
+        let mut value = 0;
+        for i in 0..10 {
+            let byte = self.read_block_bytes(1).await?[0];
+            value |= ((byte & 0x7f) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ErrorCode::BadBytes("Invalid varuint in the block"))
+    }
+
+    pub async fn read_block_str(&mut self) -> Result<String> {
+        let len = self.read_block_varuint().await? as usize;
+        let bytes = self.read_block_bytes(len).await?.to_vec();
+        String::from_utf8(bytes)
+            .map_err(|e| ErrorCode::BadBytes(format!("Invalid utf8 string: {}", e)))
+    }
+
+    async fn read_frame(&mut self) -> Result<Vec<u8>> {
+        let mut checksum = [0; COMPRESSION_CHECKSUM_SIZE];
+        self.reader.read_exact(&mut checksum).await?;
+        let mut frame = vec![0; COMPRESSION_HEADER_SIZE];
+        self.reader.read_exact(&mut frame).await?;
+
+        let method = frame[0];
+        let compressed_size = u32::from_le_bytes(frame[1..5].try_into().unwrap()) as usize;
+        let uncompressed_size = u32::from_le_bytes(frame[5..9].try_into().unwrap()) as usize;
+        if !(COMPRESSION_HEADER_SIZE..=MAX_STRING_LENGTH).contains(&compressed_size)
+            || uncompressed_size > MAX_STRING_LENGTH
+        {
+            return Err(ErrorCode::BadBytes(format!(
+                "Invalid size {} of the compressed frame",
+                compressed_size
+            )));
+        }
+        frame.resize(compressed_size, 0);
+        self.reader
+            .read_exact(&mut frame[COMPRESSION_HEADER_SIZE..])
+            .await?;
+
+        let expected = cityhash128(&frame);
+        if checksum[..8] != expected.lo.to_le_bytes() || checksum[8..] != expected.hi.to_le_bytes()
+        {
+            return Err(ErrorCode::BadBytes(
+                "Checksum mismatch of the compressed frame",
+            ));
+        }
+
+        let data = &frame[COMPRESSION_HEADER_SIZE..];
+        match method {
+            COMPRESSION_METHOD_NONE => Ok(data.to_vec()),
+            COMPRESSION_METHOD_LZ4 => lz4::block::decompress(data, Some(uncompressed_size as i32))
+                .map_err_to_code(ErrorCode::BadBytes, || "lz4 decompress error"),
+            COMPRESSION_METHOD_ZSTD => Err(ErrorCode::Unimplemented(
+                "ZSTD compression is not supported, use LZ4 instead",
+            )),
+            _ => Err(ErrorCode::BadBytes(format!(
+                "Unknown compression method {:#x}",
+                method
+            ))),
+        }
+    }
+}
+
+/// Buffers the server packets, they are sent to the client on flush.
+pub struct PacketWriter<W: AsyncWrite + Unpin> {
+    writer: W,
+    buf: Vec<u8>,
+    revision: u64,
+    compression: bool,
+}
+
+impl<W: AsyncWrite + Unpin> PacketWriter<W> {
+    pub fn create(writer: W) -> Self {
+        PacketWriter {
+            writer,
+            buf: Vec::new(),
+            revision: DBMS_TCP_PROTOCOL_VERSION,
+            compression: false,
+        }
+    }
+
+    pub fn set_revision(&mut self, revision: u64) {
+        self.revision = revision;
+    }
+
+    pub fn set_compression(&mut self, compression: bool) {
+        self.compression = compression;
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        self.writer.write_all(&self.buf).await?;
+        self.writer.flush().await?;
+        self.buf.clear();
+        Ok(())
+    }
+
+    fn put_varuint(&mut self, value: u64) {
+        put_varuint(&mut self.buf, value);
+    }
+
+    fn put_str(&mut self, s: &str) {
+        put_str(&mut self.buf, s.as_bytes());
+    }
+
+    pub fn hello(&mut self, timezone: &str, display_name: &str, version: (u64, u64, u64)) {
+        self.put_varuint(SERVER_HELLO);
+        self.put_str("Databend");
+        self.put_varuint(version.0);
+        self.put_varuint(version.1);
+        self.put_varuint(DBMS_TCP_PROTOCOL_VERSION);
+        if self.revision >= DBMS_MIN_REVISION_WITH_SERVER_TIMEZONE {
+            self.put_str(timezone);
+        }
+        if self.revision >= DBMS_MIN_REVISION_WITH_SERVER_DISPLAY_NAME {
+            self.put_str(display_name);
+        }
+        if self.revision >= DBMS_MIN_REVISION_WITH_VERSION_PATCH {
+            self.put_varuint(version.2);
+        }
+    }
+
+    /// Put a data packet, the block is empty if it's the header of the result set.
+    pub fn data(
+        &mut self,
+        schema: &DataSchemaRef,
+        block: &DataBlock,
+        encoder: &FieldEncoderValues,
+    ) -> Result<()> {
+        self.put_varuint(SERVER_DATA);
+        // The name of the temporary table, which isn't compressed.
+        self.put_str("");
+
+        let mut body = Vec::new();
+        write_block(&mut body, self.revision, schema, block, encoder)?;
+        if self.compression {
+            for chunk in body.chunks(MAX_COMPRESS_BLOCK_SIZE) {
+                self.buf.extend_from_slice(&compress_block(chunk.to_vec())?);
+            }
+        } else {
+            self.buf.extend_from_slice(&body);
+        }
+        Ok(())
+    }
+
+    pub fn exception(&mut self, error: &ErrorCode) {
+        self.put_varuint(SERVER_EXCEPTION);
+        self.buf.put_i32_le(error.code() as i32);
+        self.put_str("DB::Exception");
+        self.put_str(&error.message());
+        // The stack trace and no nested exception.
+        self.put_str("");
+        self.buf.put_u8(0);
+    }
+
+    /// Put the progress since the last progress packet.
+    pub fn progress(&mut self, read: &ProgressValues, written: &ProgressValues) {
+        self.put_varuint(SERVER_PROGRESS);
+        self.put_varuint(read.rows as u64);
+        self.put_varuint(read.bytes as u64);
+        if self.revision >= DBMS_MIN_REVISION_WITH_TOTAL_ROWS_IN_PROGRESS {
+            // The total rows to read is unknown.
+            self.put_varuint(0);
+        }
+        if self.revision >= DBMS_MIN_REVISION_WITH_CLIENT_WRITE_INFO {
+            self.put_varuint(written.rows as u64);
+            self.put_varuint(written.bytes as u64);
+        }
+    }
+
+    pub fn pong(&mut self) {
+        self.put_varuint(SERVER_PONG);
+    }
+
+    pub fn end_of_stream(&mut self) {
+        self.put_varuint(SERVER_END_OF_STREAM);
+    }
+}
+
+pub fn put_varuint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.put_u8((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+pub fn put_str(buf: &mut Vec<u8>, s: &[u8]) {
+    put_varuint(buf, s.len() as u64);
+    buf.put_slice(s);
+}
+
+/// Compress the data into a frame of LZ4, which is the default codec of ClickHouse.
+pub fn compress_block(input: Vec<u8>) -> Result<Vec<u8>> {
+    if input.is_empty() {
+        Ok(vec![])
+    } else {
+        // TODO(youngsofun): optimize buffer usages
+        let uncompressed_size = input.len();
+        let compressed =
+            lz4::block::compress(&input, Some(lz4::block::CompressionMode::FAST(1)), false)
+                .map_err_to_code(ErrorCode::BadBytes, || "lz4 compress error")?;
+
+        let mut compressed_with_header =
+            Vec::with_capacity(compressed.len() + COMPRESSION_HEADER_SIZE);
+        compressed_with_header.push(COMPRESSION_METHOD_LZ4);
+        let compressed_size = (compressed.len() + COMPRESSION_HEADER_SIZE) as u32;
+        let uncompressed_size = uncompressed_size as u32;
+        compressed_with_header.extend_from_slice(&compressed_size.to_le_bytes());
+        compressed_with_header.extend_from_slice(&uncompressed_size.to_le_bytes());
+        compressed_with_header.extend_from_slice(&compressed);
+
+        let mut output =
+            Vec::with_capacity(compressed_with_header.len() + COMPRESSION_CHECKSUM_SIZE);
+        let checksum = cityhash128(&compressed_with_header);
+        output.extend_from_slice(&checksum.lo.to_le_bytes());
+        output.extend_from_slice(&checksum.hi.to_le_bytes());
+        output.extend_from_slice(&compressed_with_header);
+        Ok(output)
+    }
+}
@@ -0,0 +1,464 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncWrite;
+use common_base::base::ProgressValues;
+use common_base::runtime::TrySpawn;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_expression::DataBlock;
+use common_expression::DataSchema;
+use common_expression::DataSchemaRef;
+use common_expression::SendableDataBlockStream;
+use common_formats::clickhouse_type_name;
+use common_formats::field_encoder::FieldEncoderValues;
+use common_formats::parse_clickhouse_type_name;
+use common_pipeline_sources::BlocksSource;
+use common_sql::plans::Insert;
+use common_sql::plans::InsertInputSource;
+use common_sql::plans::Plan;
+use common_sql::Planner;
+use futures_util::StreamExt;
+use metrics::histogram;
+use parking_lot::Mutex;
+use tracing::error;
+use tracing::info;
+use tracing::Instrument;
+
+use crate::auth::Credential;
+use crate::interpreters::InsertInterpreterV2;
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterFactory;
+use crate::interpreters::InterpreterQueryLog;
+use crate::pipelines::processors::port::OutputPort;
+use crate::pipelines::SourcePipeBuilder;
+use crate::servers::clickhouse::clickhouse_codec::ClientPacket;
+use crate::servers::clickhouse::clickhouse_codec::ClientQuery;
+use crate::servers::clickhouse::clickhouse_codec::PacketReader;
+use crate::servers::clickhouse::clickhouse_codec::PacketWriter;
+use crate::servers::clickhouse::clickhouse_codec::DBMS_TCP_PROTOCOL_VERSION;
+use crate::servers::clickhouse::CLICKHOUSE_VERSION;
+use crate::servers::http::ClickHouseFederated;
+use crate::sessions::QueryContext;
+use crate::sessions::Session;
+use crate::sessions::TableContext;
+
+pub struct InteractiveWorker<R: AsyncRead + Send + Unpin, W: AsyncWrite + Send + Unpin> {
+    session: Arc<Session>,
+    client_addr: String,
+    reader: PacketReader<R>,
+    writer: PacketWriter<W>,
+    // The progress sent to the client, the progress packets carry the increments.
+    read_progress: ProgressValues,
+    write_progress: ProgressValues,
+}
+
+impl<R: AsyncRead + Send + Unpin, W: AsyncWrite + Send + Unpin> InteractiveWorker<R, W> {
+    pub fn create(session: Arc<Session>, client_addr: String, reader: R, writer: W) -> Self {
+        InteractiveWorker {
+            session,
+            client_addr,
+            reader: PacketReader::create(reader),
+            writer: PacketWriter::create(writer),
+            read_progress: ProgressValues::default(),
+            write_progress: ProgressValues::default(),
+        }
+    }
+
+    pub async fn run(mut self) -> Result<()> {
+        if !self.handshake().await? {
+            return Ok(());
+        }
+
+        while let Some(packet) = self.reader.read_packet().await? {
+            if self.session.is_aborting() {
+                let error = ErrorCode::AbortedSession(
+                    "Aborting this connection. because we are try aborting server.",
+                );
+                self.writer.exception(&error);
+                self.writer.flush().await?;
+                return Err(error);
+            }
+
+            match packet {
+                ClientPacket::Ping => {
+                    self.writer.pong();
+                    self.writer.flush().await?;
+                }
+                ClientPacket::Query(query) => {
+                    let instant = Instant::now();
+                    let sql = query.query.clone();
+                    if let Err(cause) = self.on_query(query).await {
+                        self.write_error(&cause.display_with_sql(&sql));
+                    }
+                    histogram!(
+                        super::clickhouse_metrics::METRIC_CLICKHOUSE_PROCESSOR_REQUEST_DURATION,
+                        instant.elapsed()
+                    );
+                    self.writer.flush().await?;
+                }
+                // The data sent before the client knows the failure of the insert,
+                // and the cancel of the finished query.
+                ClientPacket::Data(..) | ClientPacket::Cancel => {}
+                ClientPacket::Hello(_) => {
+                    let error = ErrorCode::BadBytes("Unexpected packet Hello");
+                    self.writer.exception(&error);
+                    self.writer.flush().await?;
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The hello handshake, returns false if the connection should be closed.
+    async fn handshake(&mut self) -> Result<bool> {
+        let hello = match self.reader.read_packet().await? {
+            Some(ClientPacket::Hello(hello)) => hello,
+            None => return Ok(false),
+            Some(_) => {
+                let error = ErrorCode::BadBytes("Unexpected packet, expected Hello");
+                self.writer.exception(&error);
+                self.writer.flush().await?;
+                return Ok(false);
+            }
+        };
+        info!(
+            "ClickHouse client {} {}.{} connected, revision: {}",
+            hello.client_name, hello.version_major, hello.version_minor, hello.revision
+        );
+        let revision = hello.revision.min(DBMS_TCP_PROTOCOL_VERSION);
+        self.reader.set_revision(revision);
+        self.writer.set_revision(revision);
+
+        // The user of ClickHouse is default if not specified.
+        let user_name = match hello.user.as_str() {
+            "" => "default".to_string(),
+            user_name => user_name.to_string(),
+        };
+        if let Err(failure) = self.authenticate(&user_name, hello.password).await {
+            error!(
+                "ClickHouse handler authenticate failed, \
+                    user_name: {}, \
+                    client_address: {}, \
+                    failure_cause: {}",
+                user_name, self.client_addr, failure
+            );
+            self.writer.exception(&ErrorCode::AuthenticateFailure(format!(
+                "{}: Authentication failed: password is incorrect or there is no user with such name",
+                user_name
+            )));
+            self.writer.flush().await?;
+            return Ok(false);
+        }
+
+        if !hello.database.is_empty() {
+            let query = format!("USE `{}`", hello.database.replace('`', "``"));
+            if let Err(cause) = self.do_query(&query).await {
+                self.writer.exception(&cause);
+                self.writer.flush().await?;
+                return Ok(false);
+            }
+        }
+
+        let format = self.session.get_format_settings()?;
+        self.writer
+            .hello(format.timezone.name(), "Databend", CLICKHOUSE_VERSION);
+        self.writer.flush().await?;
+        Ok(true)
+    }
+
+    async fn authenticate(&mut self, user_name: &str, password: String) -> Result<()> {
+        let client_ip = self.client_addr.split(':').collect::<Vec<_>>()[0].to_string();
+        let ctx = self.session.create_query_context().await?;
+        let credential = Credential::Password {
+            name: user_name.to_string(),
+            password: Some(password.into_bytes()),
+            hostname: Some(client_ip),
+        };
+        ctx.get_auth_manager()
+            .auth(self.session.clone(), &credential)
+            .await
+    }
+
+    fn write_error(&mut self, error: &ErrorCode) {
+        if error.code() != ErrorCode::ABORTED_QUERY && error.code() != ErrorCode::ABORTED_SESSION {
+            error!("OnQuery Error: {:?}", error);
+        }
+        self.writer.exception(error);
+    }
+
+    async fn on_query(&mut self, query: ClientQuery) -> Result<()> {
+        info!("ClickHouse query {}: {}", query.query_id, query.query);
+        self.reader.set_compression(query.compression);
+        self.writer.set_compression(query.compression);
+        self.read_progress = ProgressValues::default();
+        self.write_progress = ProgressValues::default();
+
+        // The external tables of the query, which end with an empty block.
+        loop {
+            match self.reader.read_packet().await? {
+                Some(ClientPacket::Data(names, _)) if names.is_empty() => break,
+                Some(ClientPacket::Data(..)) => {}
+                Some(ClientPacket::Cancel) => {
+                    return Err(ErrorCode::AbortedQuery(
+                        "Aborted query, because the query was cancelled by the client",
+                    ));
+                }
+                Some(_) => return Err(ErrorCode::BadBytes("Unexpected packet, expected Data")),
+                None => {
+                    return Err(ErrorCode::AbortedSession(
+                        "The client closed the connection",
+                    ));
+                }
+            }
+        }
+
+        let encoder = self.create_encoder()?;
+        if let Some((schema, block)) = ClickHouseFederated::check(&query.query) {
+            let schema = Arc::new(DataSchema::from(schema.as_ref()));
+            self.writer.data(
+                &schema,
+                &DataBlock::empty_with_schema(schema.clone()),
+                &encoder,
+            )?;
+            self.writer.data(&schema, &block, &encoder)?;
+            self.writer.end_of_stream();
+            return Ok(());
+        }
+
+        let context = self.session.create_query_context().await?;
+        context
+            .get_settings()
+            .set_batch_settings(&query.settings, false)?;
+        let mut planner = Planner::new(context.clone());
+        let (plan, _, _) = planner.plan_sql(&query.query).await?;
+        context.attach_query_str(plan.to_string(), &query.query);
+
+        if let Plan::Insert(insert) = &plan {
+            if is_native_insert(insert, &query.query) {
+                return self.on_insert(context, *insert.clone()).await;
+            }
+        }
+
+        let interpreter = match InterpreterFactory::get(context.clone(), &plan).await {
+            Ok(interpreter) => interpreter,
+            Err(e) => {
+                InterpreterQueryLog::fail_to_start(context, e.clone());
+                return Err(e);
+            }
+        };
+        let schema = interpreter.schema();
+        let has_result_set = plan.has_result_set();
+        if has_result_set {
+            // The header of the result set.
+            self.writer.data(
+                &schema,
+                &DataBlock::empty_with_schema(schema.clone()),
+                &encoder,
+            )?;
+            self.writer.flush().await?;
+        }
+
+        let mut blocks = Self::exec_query(interpreter, &context).await?;
+        while let Some(block) = blocks.next().await {
+            let block = block?;
+            if self.reader.try_read_cancel().await? {
+                self.session.force_kill_query(ErrorCode::AbortedQuery(
+                    "Aborted query, because the query was cancelled by the client",
+                ));
+                break;
+            }
+
+            self.write_progress(&context);
+            if has_result_set && block.num_rows() > 0 {
+                self.writer.data(&schema, &block, &encoder)?;
+            }
+            self.writer.flush().await?;
+        }
+        self.write_progress(&context);
+        self.writer.end_of_stream();
+        Ok(())
+    }
+
+    // The data of the insert is sent by the client as the blocks of the sample block,
+    // they are buffered and inserted at once after the last block.
+    async fn on_insert(&mut self, context: Arc<QueryContext>, insert: Insert) -> Result<()> {
+        let schema: DataSchemaRef = Arc::new(DataSchema::from(insert.schema.as_ref()));
+        for field in schema.fields() {
+            let name = clickhouse_type_name(field.data_type());
+            if parse_clickhouse_type_name(&name).ok().as_ref() != Some(field.data_type()) {
+                return Err(ErrorCode::Unimplemented(format!(
+                    "Column {} of type {} can't be inserted by the native protocol",
+                    field.name(),
+                    field.data_type()
+                )));
+            }
+        }
+
+        let encoder = self.create_encoder()?;
+        self.writer.data(
+            &schema,
+            &DataBlock::empty_with_schema(schema.clone()),
+            &encoder,
+        )?;
+        self.writer.flush().await?;
+
+        let mut blocks = VecDeque::new();
+        loop {
+            let block = match self.reader.read_packet().await? {
+                Some(ClientPacket::Data(names, _)) if names.is_empty() => break,
+                Some(ClientPacket::Data(_, block)) => block,
+                Some(ClientPacket::Cancel) => {
+                    return Err(ErrorCode::AbortedQuery(
+                        "Aborted query, because the query was cancelled by the client",
+                    ));
+                }
+                Some(_) => return Err(ErrorCode::BadBytes("Unexpected packet, expected Data")),
+                None => {
+                    return Err(ErrorCode::AbortedSession(
+                        "The client closed the connection",
+                    ));
+                }
+            };
+
+            if block.num_columns() != schema.num_fields() {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Expected {} columns in the block, but got {}",
+                    schema.num_fields(),
+                    block.num_columns()
+                )));
+            }
+            for (field, entry) in schema.fields().iter().zip(block.columns()) {
+                if &entry.data_type != field.data_type() {
+                    return Err(ErrorCode::BadDataValueType(format!(
+                        "Expected type {} of column {}, but got {}",
+                        clickhouse_type_name(field.data_type()),
+                        field.name(),
+                        clickhouse_type_name(&entry.data_type)
+                    )));
+                }
+            }
+            if block.num_rows() > 0 {
+                blocks.push_back(block);
+            }
+        }
+
+        let blocks = Arc::new(Mutex::new(blocks));
+        let mut source_builder = SourcePipeBuilder::create();
+        let output = OutputPort::create();
+        source_builder.add_source(
+            output.clone(),
+            BlocksSource::create(context.clone(), output, blocks)?,
+        );
+        let interpreter = InsertInterpreterV2::try_create(context.clone(), insert, true)?;
+        interpreter.set_source_pipe_builder(Some(source_builder))?;
+
+        let mut stream = Self::exec_query(interpreter, &context).await?;
+        while let Some(block) = stream.next().await {
+            block?;
+        }
+        self.write_progress(&context);
+        self.writer.end_of_stream();
+        Ok(())
+    }
+
+    fn create_encoder(&self) -> Result<FieldEncoderValues> {
+        let format = self.session.get_format_settings()?;
+        Ok(FieldEncoderValues::create_for_http_handler(format.timezone))
+    }
+
+    fn write_progress(&mut self, context: &Arc<QueryContext>) {
+        let read = context.get_scan_progress_value();
+        let written = context.get_write_progress_value();
+        self.writer.progress(
+            &ProgressValues {
+                rows: read.rows.saturating_sub(self.read_progress.rows),
+                bytes: read.bytes.saturating_sub(self.read_progress.bytes),
+            },
+            &ProgressValues {
+                rows: written.rows.saturating_sub(self.write_progress.rows),
+                bytes: written.bytes.saturating_sub(self.write_progress.bytes),
+            },
+        );
+        self.read_progress = read;
+        self.write_progress = written;
+    }
+
+    async fn do_query(&mut self, query: &str) -> Result<()> {
+        let context = self.session.create_query_context().await?;
+        let mut planner = Planner::new(context.clone());
+        let (plan, _, _) = planner.plan_sql(query).await?;
+        context.attach_query_str(plan.to_string(), query);
+        let interpreter = InterpreterFactory::get(context.clone(), &plan).await?;
+        let mut blocks = Self::exec_query(interpreter, &context).await?;
+        while let Some(block) = blocks.next().await {
+            block?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip(interpreter, context))]
+    async fn exec_query(
+        interpreter: Arc<dyn Interpreter>,
+        context: &Arc<QueryContext>,
+    ) -> Result<SendableDataBlockStream> {
+        let instant = Instant::now();
+
+        let query_result = context.try_spawn({
+            let ctx = context.clone();
+            async move {
+                let mut data_stream = interpreter.execute(ctx.clone()).await?;
+                histogram!(
+                    super::clickhouse_metrics::METRIC_INTERPRETER_USEDTIME,
+                    instant.elapsed()
+                );
+
+                // Wrap the data stream, log finish event at the end of stream
+                let intercepted_stream = async_stream::stream! {
+
+                    while let Some(item) = data_stream.next().await {
+                        yield item
+                    };
+                };
+
+                Ok::<_, ErrorCode>(intercepted_stream.boxed())
+            }
+            .in_current_span()
+        })?;
+
+        query_result.await.map_err_to_code(
+            ErrorCode::TokioError,
+            || "Cannot join handle from context's runtime",
+        )?
+    }
+}
+
+// The client sends the query without the data, like `INSERT INTO t VALUES` or
+// `INSERT INTO t FORMAT CSV`, the data is converted to the blocks by the client.
+fn is_native_insert(insert: &Insert, query: &str) -> bool {
+    match &insert.source {
+        InsertInputSource::Values(data) => data.trim().is_empty(),
+        InsertInputSource::StreamingWithFormat(_, start, _) => query
+            .get(*start..)
+            .map_or(true, |rest| rest.trim().trim_end_matches(';').is_empty()),
+        _ => false,
+    }
+}
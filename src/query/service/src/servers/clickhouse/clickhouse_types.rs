@@ -0,0 +1,315 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The columns of the blocks in the Native format.
+//!
+//! The types are sent as the ClickHouse types returned by `clickhouse_type_name`, the types
+//! without a counterpart are sent as `String` in the text format.
+
+use bytes::BufMut;
+use common_base::base::tokio::io::AsyncRead;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::types::decimal::DecimalColumn;
+use common_expression::types::decimal::DecimalDataType;
+use common_expression::types::nullable::NullableColumn;
+use common_expression::types::number::Number;
+use common_expression::types::number::NumberColumn;
+use common_expression::types::number::F32;
+use common_expression::types::number::F64;
+use common_expression::types::string::StringColumnBuilder;
+use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
+use common_expression::with_number_mapped_type;
+use common_expression::BlockEntry;
+use common_expression::Column;
+use common_expression::ColumnBuilder;
+use common_expression::DataBlock;
+use common_expression::DataSchemaRef;
+use common_expression::Value;
+use common_formats::clickhouse_type_name;
+use common_formats::field_encoder::FieldEncoderRowBased;
+use common_formats::field_encoder::FieldEncoderValues;
+use common_formats::parse_clickhouse_type_name;
+use ethnum::i256;
+
+use crate::servers::clickhouse::clickhouse_codec::put_str;
+use crate::servers::clickhouse::clickhouse_codec::put_varuint;
+use crate::servers::clickhouse::clickhouse_codec::PacketReader;
+use crate::servers::clickhouse::clickhouse_codec::DBMS_MIN_REVISION_WITH_BLOCK_INFO;
+
+/// The values stored in little endian with the fixed size.
+trait FixedSizeValue: Copy {
+    const SIZE: usize;
+
+    fn put(self, buf: &mut Vec<u8>);
+
+    fn get(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_fixed_size_value {
+    ($($t:ty),*) => {
+        $(
+            impl FixedSizeValue for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn put(self, buf: &mut Vec<u8>) {
+                    buf.put_slice(&self.to_le_bytes());
+                }
+
+                fn get(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_size_value!(u8, u16, u32, u64, i8, i16, i32, i64, i128, i256);
+
+impl FixedSizeValue for F32 {
+    const SIZE: usize = 4;
+
+    fn put(self, buf: &mut Vec<u8>) {
+        buf.put_f32_le(self.0);
+    }
+
+    fn get(bytes: &[u8]) -> Self {
+        f32::from_le_bytes(bytes.try_into().unwrap()).into()
+    }
+}
+
+impl FixedSizeValue for F64 {
+    const SIZE: usize = 8;
+
+    fn put(self, buf: &mut Vec<u8>) {
+        buf.put_f64_le(self.0);
+    }
+
+    fn get(bytes: &[u8]) -> Self {
+        f64::from_le_bytes(bytes.try_into().unwrap()).into()
+    }
+}
+
+fn put_values<T: FixedSizeValue>(buf: &mut Vec<u8>, values: impl Iterator<Item = T>) {
+    for value in values {
+        value.put(buf);
+    }
+}
+
+/// Write the block without the header of the packet.
+pub fn write_block(
+    buf: &mut Vec<u8>,
+    revision: u64,
+    schema: &DataSchemaRef,
+    block: &DataBlock,
+    encoder: &FieldEncoderValues,
+) -> Result<()> {
+    if revision >= DBMS_MIN_REVISION_WITH_BLOCK_INFO {
+        // The block info: is_overflows is false, bucket_num is -1 and the end of the fields.
+        put_varuint(buf, 1);
+        buf.put_u8(0);
+        put_varuint(buf, 2);
+        buf.put_i32_le(-1);
+        put_varuint(buf, 0);
+    }
+
+    let block = block.convert_to_full();
+    put_varuint(buf, schema.num_fields() as u64);
+    put_varuint(buf, block.num_rows() as u64);
+    for (index, field) in schema.fields().iter().enumerate() {
+        put_str(buf, field.name().as_bytes());
+        put_str(buf, clickhouse_type_name(field.data_type()).as_bytes());
+        // The columns of the empty block have no data.
+        if block.num_rows() > 0 {
+            let column = block.get_by_offset(index).value.as_column().unwrap();
+            write_column(buf, column, encoder);
+        }
+    }
+    Ok(())
+}
+
+fn write_column(buf: &mut Vec<u8>, column: &Column, encoder: &FieldEncoderValues) {
+    match column {
+        // The null map and the values of Nothing, which are written as '0'.
+        Column::Null { len } => {
+            buf.extend(std::iter::repeat(1).take(*len));
+            buf.extend(std::iter::repeat(b'0').take(*len));
+        }
+        Column::Nullable(column) => {
+            buf.extend(column.validity.iter().map(|valid| !valid as u8));
+            write_column(buf, &column.column, encoder);
+        }
+        Column::Boolean(bitmap) => buf.extend(bitmap.iter().map(|v| v as u8)),
+        Column::Number(column) => with_number_mapped_type!(|NUM_TYPE| match column {
+            NumberColumn::NUM_TYPE(values) => put_values(buf, values.iter().copied()),
+        }),
+        Column::Decimal(DecimalColumn::Decimal128(values, size)) => match size.precision {
+            0..=9 => put_values(buf, values.iter().map(|v| *v as i32)),
+            10..=18 => put_values(buf, values.iter().map(|v| *v as i64)),
+            _ => put_values(buf, values.iter().copied()),
+        },
+        Column::Decimal(DecimalColumn::Decimal256(values, _)) => {
+            put_values(buf, values.iter().copied())
+        }
+        Column::String(column) => {
+            for value in column.iter() {
+                put_str(buf, value);
+            }
+        }
+        Column::Date(values) => put_values(buf, values.iter().copied()),
+        Column::Timestamp(values) => put_values(buf, values.iter().copied()),
+        _ => {
+            let mut value = Vec::new();
+            for row_index in 0..column.len() {
+                value.clear();
+                encoder.write_field(column, row_index, &mut value, true);
+                put_str(buf, &value);
+            }
+        }
+    }
+}
+
+/// Read the block sent by the client, returns the names of the columns and the data.
+pub async fn read_block<R: AsyncRead + Unpin>(
+    reader: &mut PacketReader<R>,
+) -> Result<(Vec<String>, DataBlock)> {
+    if reader.revision() >= DBMS_MIN_REVISION_WITH_BLOCK_INFO {
+        loop {
+            match reader.read_block_varuint().await? {
+                0 => break,
+                1 => reader.read_block_bytes(1).await?,
+                2 => reader.read_block_bytes(4).await?,
+                field => {
+                    return Err(ErrorCode::BadBytes(format!(
+                        "Unknown field {} of the block info",
+                        field
+                    )));
+                }
+            };
+        }
+    }
+
+    let num_columns = reader.read_block_varuint().await? as usize;
+    let num_rows = reader.read_block_varuint().await? as usize;
+    let mut names = Vec::with_capacity(num_columns);
+    let mut entries = Vec::with_capacity(num_columns);
+    for _ in 0..num_columns {
+        names.push(reader.read_block_str().await?);
+        let data_type = parse_clickhouse_type_name(&reader.read_block_str().await?)?;
+        let column = if num_rows > 0 {
+            read_column(reader, &data_type, num_rows).await?
+        } else {
+            ColumnBuilder::with_capacity(&data_type, 0).build()
+        };
+        entries.push(BlockEntry {
+            data_type,
+            value: Value::Column(column),
+        });
+    }
+    Ok((names, DataBlock::new(entries, num_rows)))
+}
+
+async fn read_column<R: AsyncRead + Unpin>(
+    reader: &mut PacketReader<R>,
+    data_type: &DataType,
+    rows: usize,
+) -> Result<Column> {
+    match data_type {
+        DataType::Null => {
+            // The null map and the values of Nothing.
+            reader.read_block_bytes(rows * 2).await?;
+            Ok(Column::Null { len: rows })
+        }
+        DataType::Nullable(inner) => {
+            let validity = reader
+                .read_block_bytes(rows)
+                .await?
+                .iter()
+                .map(|is_null| *is_null == 0)
+                .collect::<Vec<_>>();
+            let column = read_not_null_column(reader, inner, rows).await?;
+            Ok(Column::Nullable(Box::new(NullableColumn {
+                column,
+                validity: validity.into(),
+            })))
+        }
+        _ => read_not_null_column(reader, data_type, rows).await,
+    }
+}
+
+async fn read_not_null_column<R: AsyncRead + Unpin>(
+    reader: &mut PacketReader<R>,
+    data_type: &DataType,
+    rows: usize,
+) -> Result<Column> {
+    let column = match data_type {
+        DataType::Boolean => {
+            let values = reader.read_block_bytes(rows).await?;
+            Column::Boolean(values.iter().map(|v| *v != 0).collect::<Vec<_>>().into())
+        }
+        DataType::Number(num_ty) => with_number_mapped_type!(|NUM_TYPE| match num_ty {
+            NumberDataType::NUM_TYPE => {
+                let values = read_values::<_, NUM_TYPE>(reader, rows).await?;
+                Column::Number(<NUM_TYPE as Number>::upcast_column(values.into()))
+            }
+        }),
+        DataType::Decimal(DecimalDataType::Decimal128(size)) => {
+            let values = match size.precision {
+                0..=9 => read_values::<_, i32>(reader, rows)
+                    .await?
+                    .into_iter()
+                    .map(|v| v as i128)
+                    .collect(),
+                10..=18 => read_values::<_, i64>(reader, rows)
+                    .await?
+                    .into_iter()
+                    .map(|v| v as i128)
+                    .collect(),
+                _ => read_values::<_, i128>(reader, rows).await?,
+            };
+            Column::Decimal(DecimalColumn::Decimal128(values.into(), *size))
+        }
+        DataType::Decimal(DecimalDataType::Decimal256(size)) => {
+            let values = read_values::<_, i256>(reader, rows).await?;
+            Column::Decimal(DecimalColumn::Decimal256(values.into(), *size))
+        }
+        DataType::String => {
+            let mut builder = StringColumnBuilder::with_capacity(rows, 0);
+            for _ in 0..rows {
+                let len = reader.read_block_varuint().await? as usize;
+                builder.put_slice(reader.read_block_bytes(len).await?);
+                builder.commit_row();
+            }
+            Column::String(builder.build())
+        }
+        DataType::Date => Column::Date(read_values::<_, i32>(reader, rows).await?.into()),
+        DataType::Timestamp => Column::Timestamp(read_values::<_, i64>(reader, rows).await?.into()),
+        _ => {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Unsupported column type {}",
+                data_type
+            )));
+        }
+    };
+    Ok(column)
+}
+
+async fn read_values<R: AsyncRead + Unpin, T: FixedSizeValue>(
+    reader: &mut PacketReader<R>,
+    rows: usize,
+) -> Result<Vec<T>> {
+    let bytes = reader.read_block_bytes(rows * T::SIZE).await?;
+    Ok(bytes.chunks_exact(T::SIZE).map(T::get).collect())
+}
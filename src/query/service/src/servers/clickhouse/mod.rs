@@ -0,0 +1,28 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod clickhouse_codec;
+mod clickhouse_handler;
+mod clickhouse_interactive_worker;
+mod clickhouse_metrics;
+mod clickhouse_session;
+mod clickhouse_types;
+
+pub use self::clickhouse_codec::compress_block;
+pub use self::clickhouse_handler::ClickHouseHandler;
+pub use self::clickhouse_session::ClickHouseConnection;
+
+// The version of ClickHouse which DBMS_TCP_PROTOCOL_VERSION is released in, the clients
+// check the features of the server by the protocol revision instead of the version.
+const CLICKHOUSE_VERSION: (u64, u64, u64) = (22, 1, 0);
@@ -0,0 +1,101 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The catalog metadata commands of Flight SQL, they are answered by the queries on
+//! information_schema, and the databases of Databend are the schemas of Flight SQL.
+
+use crate::servers::flight_sql::flight_sql_protocol::CommandGetDbSchemas;
+use crate::servers::flight_sql::flight_sql_protocol::CommandGetTables;
+
+pub const TABLE_TYPES: &[&str] = &["BASE TABLE", "VIEW"];
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+// The catalog filter only matches the current catalog, the other catalogs have no schemas.
+fn catalog_filter(catalog: &Option<String>, current_catalog: &str) -> Option<String> {
+    match catalog {
+        Some(catalog) if !catalog.is_empty() && catalog != current_catalog => {
+            Some("1 = 0".to_string())
+        }
+        _ => None,
+    }
+}
+
+fn where_clause(filters: Vec<String>) -> String {
+    if filters.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", filters.join(" AND "))
+    }
+}
+
+pub fn get_catalogs_query(current_catalog: &str) -> String {
+    format!("SELECT {} AS catalog_name", quote(current_catalog))
+}
+
+pub fn get_db_schemas_query(command: &CommandGetDbSchemas, current_catalog: &str) -> String {
+    let mut filters = vec![];
+    filters.extend(catalog_filter(&command.catalog, current_catalog));
+    if let Some(pattern) = &command.db_schema_filter_pattern {
+        filters.push(format!("schema_name LIKE {}", quote(pattern)));
+    }
+    format!(
+        "SELECT {} AS catalog_name, schema_name AS db_schema_name \
+        FROM information_schema.schemata{} ORDER BY db_schema_name",
+        quote(current_catalog),
+        where_clause(filters)
+    )
+}
+
+pub fn get_tables_query(command: &CommandGetTables, current_catalog: &str) -> String {
+    let mut filters = vec![];
+    filters.extend(catalog_filter(&command.catalog, current_catalog));
+    if let Some(pattern) = &command.db_schema_filter_pattern {
+        filters.push(format!("db_schema_name LIKE {}", quote(pattern)));
+    }
+    if let Some(pattern) = &command.table_name_filter_pattern {
+        filters.push(format!("table_name LIKE {}", quote(pattern)));
+    }
+    if !command.table_types.is_empty() {
+        let table_types = command
+            .table_types
+            .iter()
+            .map(|table_type| quote(table_type))
+            .collect::<Vec<_>>();
+        filters.push(format!("table_type IN ({})", table_types.join(", ")));
+    }
+    // The table_type of information_schema.tables is always 'BASE TABLE', the views are
+    // told by the engine.
+    format!(
+        "SELECT catalog_name, db_schema_name, table_name, table_type FROM (\
+        SELECT {} AS catalog_name, table_schema AS db_schema_name, table_name, \
+        if(engine = 'VIEW', 'VIEW', 'BASE TABLE') AS table_type \
+        FROM information_schema.tables) AS t{} ORDER BY db_schema_name, table_name",
+        quote(current_catalog),
+        where_clause(filters)
+    )
+}
+
+pub fn get_table_types_query() -> String {
+    let table_types = TABLE_TYPES
+        .iter()
+        .map(|table_type| format!("SELECT {} AS table_type", quote(table_type)))
+        .collect::<Vec<_>>();
+    format!(
+        "SELECT table_type FROM ({}) AS t ORDER BY table_type",
+        table_types.join(" UNION ALL ")
+    )
+}
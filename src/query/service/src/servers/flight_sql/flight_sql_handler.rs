@@ -0,0 +1,103 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use common_arrow::arrow_format::flight::service::flight_service_server::FlightServiceServer;
+use common_base::base::tokio;
+use common_base::base::tokio::net::TcpListener;
+use common_base::base::tokio::sync::Notify;
+use common_base::base::tokio::task::JoinHandle;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+use tracing::error;
+
+use crate::servers::flight_sql::flight_sql_service::DatabendQueryFlightSqlService;
+use crate::servers::Server as DatabendQueryServer;
+
+/// The Arrow Flight SQL service for the clients, such as ADBC and the JDBC driver of Flight SQL.
+///
+/// It's separated from the Flight service of the cluster, which is only for the
+/// communication between the nodes.
+pub struct FlightSqlHandler {
+    abort_notify: Arc<Notify>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl FlightSqlHandler {
+    pub fn create() -> Result<Box<dyn DatabendQueryServer>> {
+        Ok(Box::new(FlightSqlHandler {
+            abort_notify: Arc::new(Notify::new()),
+            join_handle: None,
+        }))
+    }
+
+    async fn listener_tcp(listening: SocketAddr) -> Result<(TcpListenerStream, SocketAddr)> {
+        let listener = TcpListener::bind(listening).await.map_err(|e| {
+            ErrorCode::TokioError(format!("{{{}:{}}} {}", listening.ip(), listening.port(), e))
+        })?;
+        let listener_addr = listener.local_addr()?;
+        Ok((TcpListenerStream::new(listener), listener_addr))
+    }
+
+    fn shutdown_notify(&self) -> impl Future<Output = ()> + 'static {
+        let notified = self.abort_notify.clone();
+        async move {
+            notified.notified().await;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DatabendQueryServer for FlightSqlHandler {
+    async fn shutdown(&mut self, graceful: bool) {
+        if !graceful {
+            return;
+        }
+
+        self.abort_notify.notify_waiters();
+
+        if let Some(join_handle) = self.join_handle.take() {
+            if let Err(error) = join_handle.await {
+                error!(
+                    "Unexpected error during shutdown FlightSqlHandler. cause {}",
+                    error
+                );
+            }
+        }
+    }
+
+    async fn start(&mut self, listening: SocketAddr) -> Result<SocketAddr> {
+        if self.join_handle.is_some() {
+            return Err(ErrorCode::Internal("FlightSqlHandler already running."));
+        }
+
+        let (listener_stream, listener_addr) = Self::listener_tcp(listening).await?;
+        let flight_sql_service = DatabendQueryFlightSqlService::create();
+        let server = Server::builder()
+            .add_service(FlightServiceServer::new(flight_sql_service))
+            .serve_with_incoming_shutdown(listener_stream, self.shutdown_notify());
+
+        self.join_handle = Some(tokio::spawn(async move {
+            if let Err(error) = server.await {
+                error!("Flight SQL server error: {}", error);
+            }
+        }));
+        Ok(listener_addr)
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The messages of the Arrow Flight SQL protocol, see `FlightSql.proto` of Apache Arrow.
+//!
+//! The commands are packed in `google.protobuf.Any` and sent as the `cmd` of the
+//! `FlightDescriptor`, the `ticket` of the `Ticket` or the `body` of the `Action`.
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use prost::Message;
+
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/arrow.flight.protocol.sql.";
+
+pub const CREATE_PREPARED_STATEMENT: &str = "CreatePreparedStatement";
+pub const CLOSE_PREPARED_STATEMENT: &str = "ClosePreparedStatement";
+
+/// The `google.protobuf.Any` wrapping the commands.
+#[derive(Clone, PartialEq, Message)]
+pub struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+pub trait FlightSqlMessage: Message + Default {
+    const NAME: &'static str;
+
+    fn type_url() -> String {
+        format!("{}{}", TYPE_URL_PREFIX, Self::NAME)
+    }
+
+    fn as_any(&self) -> Any {
+        Any {
+            type_url: Self::type_url(),
+            value: self.encode_to_vec(),
+        }
+    }
+
+    fn to_any_bytes(&self) -> Vec<u8> {
+        self.as_any().encode_to_vec()
+    }
+}
+
+impl Any {
+    pub fn decode_bytes(bytes: &[u8]) -> Result<Any> {
+        Any::decode(bytes)
+            .map_err(|e| ErrorCode::BadBytes(format!("Invalid Flight SQL command: {}", e)))
+    }
+
+    pub fn is<T: FlightSqlMessage>(&self) -> bool {
+        self.type_url == T::type_url()
+    }
+
+    pub fn unpack<T: FlightSqlMessage>(&self) -> Result<T> {
+        T::decode(self.value.as_slice())
+            .map_err(|e| ErrorCode::BadBytes(format!("Invalid {}: {}", T::NAME, e)))
+    }
+}
+
+macro_rules! flight_sql_message {
+    ($($name: ident),* $(,)?) => {
+        $(
+            impl FlightSqlMessage for $name {
+                const NAME: &'static str = stringify!($name);
+            }
+        )*
+    };
+}
+
+flight_sql_message!(
+    CommandStatementQuery,
+    CommandStatementUpdate,
+    TicketStatementQuery,
+    CommandPreparedStatementQuery,
+    CommandPreparedStatementUpdate,
+    CommandGetCatalogs,
+    CommandGetDbSchemas,
+    CommandGetTables,
+    CommandGetTableTypes,
+    ActionCreatePreparedStatementRequest,
+    ActionCreatePreparedStatementResult,
+    ActionClosePreparedStatementRequest,
+);
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandStatementQuery {
+    #[prost(string, tag = "1")]
+    pub query: String,
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub transaction_id: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandStatementUpdate {
+    #[prost(string, tag = "1")]
+    pub query: String,
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub transaction_id: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct TicketStatementQuery {
+    #[prost(bytes = "vec", tag = "1")]
+    pub statement_handle: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandPreparedStatementQuery {
+    #[prost(bytes = "vec", tag = "1")]
+    pub prepared_statement_handle: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandPreparedStatementUpdate {
+    #[prost(bytes = "vec", tag = "1")]
+    pub prepared_statement_handle: Vec<u8>,
+}
+
+/// The `app_metadata` of the `PutResult` of the updates, it's not packed in `Any`.
+#[derive(Clone, PartialEq, Message)]
+pub struct DoPutUpdateResult {
+    #[prost(int64, tag = "1")]
+    pub record_count: i64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandGetCatalogs {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandGetDbSchemas {
+    #[prost(string, optional, tag = "1")]
+    pub catalog: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub db_schema_filter_pattern: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandGetTables {
+    #[prost(string, optional, tag = "1")]
+    pub catalog: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub db_schema_filter_pattern: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub table_name_filter_pattern: Option<String>,
+    #[prost(string, repeated, tag = "4")]
+    pub table_types: Vec<String>,
+    #[prost(bool, tag = "5")]
+    pub include_schema: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandGetTableTypes {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActionCreatePreparedStatementRequest {
+    #[prost(string, tag = "1")]
+    pub query: String,
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub transaction_id: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActionCreatePreparedStatementResult {
+    #[prost(bytes = "vec", tag = "1")]
+    pub prepared_statement_handle: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub dataset_schema: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub parameter_schema: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActionClosePreparedStatementRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub prepared_statement_handle: Vec<u8>,
+}
@@ -0,0 +1,531 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use common_arrow::arrow::array::BinaryArray;
+use common_arrow::arrow::chunk::Chunk as ArrowChunk;
+use common_arrow::arrow::datatypes::DataType as ArrowDataType;
+use common_arrow::arrow::datatypes::Field as ArrowField;
+use common_arrow::arrow::datatypes::Schema as ArrowSchema;
+use common_arrow::arrow_format::flight::data::Action;
+use common_arrow::arrow_format::flight::data::ActionType;
+use common_arrow::arrow_format::flight::data::Criteria;
+use common_arrow::arrow_format::flight::data::Empty;
+use common_arrow::arrow_format::flight::data::FlightData;
+use common_arrow::arrow_format::flight::data::FlightDescriptor;
+use common_arrow::arrow_format::flight::data::FlightEndpoint;
+use common_arrow::arrow_format::flight::data::FlightInfo;
+use common_arrow::arrow_format::flight::data::HandshakeRequest;
+use common_arrow::arrow_format::flight::data::HandshakeResponse;
+use common_arrow::arrow_format::flight::data::PutResult;
+use common_arrow::arrow_format::flight::data::Result as FlightResult;
+use common_arrow::arrow_format::flight::data::SchemaResult;
+use common_arrow::arrow_format::flight::data::Ticket;
+use common_arrow::arrow_format::flight::service::flight_service_server::FlightService;
+use common_ast::ast::Statement;
+use common_base::base::tokio;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::DataBlock;
+use common_expression::DataSchema;
+use common_expression::Scalar;
+use common_expression::ScalarRef;
+use common_sql::PreparedStatement;
+use futures_util::StreamExt;
+use prost::Message;
+use tokio_stream::Stream;
+use tonic::metadata::MetadataValue;
+use tonic::Request;
+use tonic::Response as RawResponse;
+use tonic::Status;
+use tonic::Streaming;
+use tracing::error;
+use tracing::info;
+
+use crate::servers::flight_sql::flight_sql_catalog::get_catalogs_query;
+use crate::servers::flight_sql::flight_sql_catalog::get_db_schemas_query;
+use crate::servers::flight_sql::flight_sql_catalog::get_table_types_query;
+use crate::servers::flight_sql::flight_sql_catalog::get_tables_query;
+use crate::servers::flight_sql::flight_sql_protocol::ActionClosePreparedStatementRequest;
+use crate::servers::flight_sql::flight_sql_protocol::ActionCreatePreparedStatementRequest;
+use crate::servers::flight_sql::flight_sql_protocol::ActionCreatePreparedStatementResult;
+use crate::servers::flight_sql::flight_sql_protocol::Any;
+use crate::servers::flight_sql::flight_sql_protocol::CommandGetCatalogs;
+use crate::servers::flight_sql::flight_sql_protocol::CommandGetDbSchemas;
+use crate::servers::flight_sql::flight_sql_protocol::CommandGetTableTypes;
+use crate::servers::flight_sql::flight_sql_protocol::CommandGetTables;
+use crate::servers::flight_sql::flight_sql_protocol::CommandPreparedStatementQuery;
+use crate::servers::flight_sql::flight_sql_protocol::CommandPreparedStatementUpdate;
+use crate::servers::flight_sql::flight_sql_protocol::CommandStatementQuery;
+use crate::servers::flight_sql::flight_sql_protocol::CommandStatementUpdate;
+use crate::servers::flight_sql::flight_sql_protocol::DoPutUpdateResult;
+use crate::servers::flight_sql::flight_sql_protocol::FlightSqlMessage;
+use crate::servers::flight_sql::flight_sql_protocol::TicketStatementQuery;
+use crate::servers::flight_sql::flight_sql_protocol::CLOSE_PREPARED_STATEMENT;
+use crate::servers::flight_sql::flight_sql_protocol::CREATE_PREPARED_STATEMENT;
+use crate::servers::flight_sql::flight_sql_session::FlightSqlSession;
+use crate::servers::flight_sql::flight_sql_session::FlightSqlSessions;
+use crate::servers::flight_sql::flight_sql_session::PlannedQuery;
+use crate::servers::flight_sql::flight_sql_session::PreparedQuery;
+use crate::servers::flight_sql::flight_sql_session::TicketQuery;
+use crate::servers::flight_sql::flight_sql_types::arrow_chunk;
+use crate::servers::flight_sql::flight_sql_types::arrow_schema;
+use crate::servers::flight_sql::flight_sql_types::decode_params;
+use crate::servers::flight_sql::flight_sql_types::params_schema;
+use crate::servers::flight_sql::flight_sql_types::schema_to_ipc;
+use crate::servers::flight_sql::flight_sql_types::FlightDataEncoder;
+use crate::sessions::QueryContext;
+use crate::sessions::TableContext;
+
+pub type FlightStream<T> =
+    Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send + Sync + 'static>>;
+
+type Response<T> = Result<RawResponse<T>, Status>;
+type StreamReq<T> = Request<Streaming<T>>;
+
+pub struct DatabendQueryFlightSqlService {
+    sessions: FlightSqlSessions,
+}
+
+impl DatabendQueryFlightSqlService {
+    pub fn create() -> Self {
+        DatabendQueryFlightSqlService {
+            sessions: FlightSqlSessions::create(),
+        }
+    }
+
+    fn get_session<T>(&self, request: &Request<T>) -> Result<Arc<FlightSqlSession>, Status> {
+        let authorization = request.metadata().get("authorization");
+        self.sessions
+            .get_session(authorization.map(|value| value.as_bytes()))
+            .map_err(|e| Status::unauthenticated(e.message()))
+    }
+
+    // The query of the command in the FlightDescriptor, and whether the schemas of the
+    // tables are included. The statement of a prepared query is parsed and bound already.
+    fn command_query(
+        session: &FlightSqlSession,
+        cmd: &[u8],
+    ) -> Result<(String, Option<Statement>, bool)> {
+        let command = Any::decode_bytes(cmd)?;
+        let current_catalog = session.session.get_current_catalog();
+        if command.is::<CommandStatementQuery>() {
+            Ok((
+                command.unpack::<CommandStatementQuery>()?.query,
+                None,
+                false,
+            ))
+        } else if command.is::<CommandPreparedStatementQuery>() {
+            let command = command.unpack::<CommandPreparedStatementQuery>()?;
+            let statement = session.get_statement(&command.prepared_statement_handle)?;
+            let mut stmts = statement.bind()?;
+            if stmts.len() != 1 {
+                return Err(ErrorCode::BadArguments(
+                    "Only one row of parameters can be bound to the prepared query",
+                ));
+            }
+            let query = statement.statement.query().to_string();
+            Ok((query, Some(stmts.remove(0)), false))
+        } else if command.is::<CommandGetCatalogs>() {
+            Ok((get_catalogs_query(&current_catalog), None, false))
+        } else if command.is::<CommandGetDbSchemas>() {
+            let command = command.unpack::<CommandGetDbSchemas>()?;
+            Ok((
+                get_db_schemas_query(&command, &current_catalog),
+                None,
+                false,
+            ))
+        } else if command.is::<CommandGetTables>() {
+            let command = command.unpack::<CommandGetTables>()?;
+            let query = get_tables_query(&command, &current_catalog);
+            Ok((query, None, command.include_schema))
+        } else if command.is::<CommandGetTableTypes>() {
+            Ok((get_table_types_query(), None, false))
+        } else {
+            Err(ErrorCode::Unimplemented(format!(
+                "Unsupported Flight SQL command: {}",
+                command.type_url
+            )))
+        }
+    }
+
+    async fn plan_command(
+        session: &FlightSqlSession,
+        descriptor: &FlightDescriptor,
+    ) -> Result<(TicketQuery, ArrowSchema)> {
+        let (query, stmt, include_table_schema) = Self::command_query(session, &descriptor.cmd)?;
+        info!("Flight SQL query: {}", query);
+        let planned = session.plan_query(&query, stmt).await?;
+        let schema = result_schema(&planned, include_table_schema);
+        let ticket = TicketQuery {
+            planned,
+            include_table_schema,
+        };
+        Ok((ticket, schema))
+    }
+
+    async fn flight_info(
+        session: &FlightSqlSession,
+        descriptor: FlightDescriptor,
+    ) -> Result<FlightInfo> {
+        let (ticket, schema) = Self::plan_command(session, &descriptor).await?;
+        let handle = uuid::Uuid::new_v4().as_bytes().to_vec();
+        let ticket_bytes = TicketStatementQuery {
+            statement_handle: handle.clone(),
+        }
+        .to_any_bytes();
+        session.add_ticket(handle, ticket);
+
+        Ok(FlightInfo {
+            schema: schema_to_ipc(&schema)?,
+            flight_descriptor: Some(descriptor),
+            endpoint: vec![FlightEndpoint {
+                ticket: Some(Ticket {
+                    ticket: ticket_bytes,
+                }),
+                location: vec![],
+            }],
+            total_records: -1,
+            total_bytes: -1,
+        })
+    }
+
+    async fn execute_ticket(
+        session: Arc<FlightSqlSession>,
+        ticket: &[u8],
+    ) -> Result<async_channel::Receiver<Result<FlightData, Status>>> {
+        let command = Any::decode_bytes(ticket)?;
+        if !command.is::<TicketStatementQuery>() {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Unsupported Flight SQL ticket: {}",
+                command.type_url
+            )));
+        }
+        let handle = command.unpack::<TicketStatementQuery>()?.statement_handle;
+        let TicketQuery {
+            planned,
+            include_table_schema,
+        } = session.take_ticket(&handle).ok_or_else(|| {
+            ErrorCode::BadArguments("Unknown ticket, the ticket can only be fetched once")
+        })?;
+
+        let ctx = planned.context.clone();
+        let schema = result_schema(&planned, include_table_schema);
+        let mut blocks = FlightSqlSession::execute_query(planned).await?;
+
+        // The bounded channel is the backpressure of the query, the query is blocked
+        // if the client doesn't fetch the data.
+        let (tx, rx) = async_channel::bounded(8);
+        tokio::spawn(async move {
+            let encoder = FlightDataEncoder::create(&schema);
+            if tx.send(Ok(encoder.encode_schema(&schema))).await.is_err() {
+                return;
+            }
+            while let Some(block) = blocks.next().await {
+                let messages = match block {
+                    Ok(block) => encode_block(&encoder, &ctx, block, include_table_schema).await,
+                    Err(cause) => Err(cause),
+                };
+                let messages = match messages {
+                    Ok(messages) => messages,
+                    Err(cause) => {
+                        error!("Flight SQL query error: {:?}", cause);
+                        let _ = tx.send(Err(Status::from(cause))).await;
+                        return;
+                    }
+                };
+                for message in messages {
+                    if tx.send(Ok(message)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    async fn create_prepared_statement(
+        session: &FlightSqlSession,
+        body: &[u8],
+    ) -> Result<FlightResult> {
+        let request = Any::decode_bytes(body)?.unpack::<ActionCreatePreparedStatementRequest>()?;
+        let sql_dialect = session.session.get_settings().get_sql_dialect()?;
+        let statement = PreparedStatement::try_create(&request.query, sql_dialect)
+            .map_err(|e| e.display_with_sql(&request.query))?;
+
+        // The schema of the result set is known by planning the query with the null parameters.
+        let null_params = vec![Scalar::Null; statement.num_params()];
+        let planned = session
+            .plan_query(statement.query(), Some(statement.bind(&null_params)?))
+            .await?;
+        let dataset_schema = if planned.has_result_set {
+            schema_to_ipc(&arrow_schema(&planned.schema))?
+        } else {
+            vec![]
+        };
+        let parameter_schema = schema_to_ipc(&params_schema(statement.num_params()))?;
+
+        let handle = uuid::Uuid::new_v4().as_bytes().to_vec();
+        session.add_statement(handle.clone(), PreparedQuery {
+            statement,
+            params: vec![],
+        });
+        let result = ActionCreatePreparedStatementResult {
+            prepared_statement_handle: handle,
+            dataset_schema,
+            parameter_schema,
+        };
+        Ok(FlightResult {
+            body: result.to_any_bytes(),
+        })
+    }
+
+    async fn do_put_command(
+        session: &FlightSqlSession,
+        messages: Vec<FlightData>,
+    ) -> Result<Option<PutResult>> {
+        let descriptor = messages
+            .first()
+            .and_then(|message| message.flight_descriptor.clone())
+            .ok_or_else(|| ErrorCode::BadArguments("The FlightDescriptor of DoPut is missing"))?;
+        let command = Any::decode_bytes(&descriptor.cmd)?;
+
+        let record_count = if command.is::<CommandStatementUpdate>() {
+            let command = command.unpack::<CommandStatementUpdate>()?;
+            session.execute_update(&command.query, None).await?
+        } else if command.is::<CommandPreparedStatementQuery>() {
+            // Bind the parameters, they are used by the following GetFlightInfo.
+            let command = command.unpack::<CommandPreparedStatementQuery>()?;
+            let params = decode_params(&messages)?;
+            session.set_params(&command.prepared_statement_handle, params)?;
+            return Ok(None);
+        } else if command.is::<CommandPreparedStatementUpdate>() {
+            // The statement is executed once for each row of the parameters.
+            let command = command.unpack::<CommandPreparedStatementUpdate>()?;
+            let handle = &command.prepared_statement_handle;
+            let params = decode_params(&messages)?;
+            if !params.is_empty() {
+                session.set_params(handle, params)?;
+            }
+            let statement = session.get_statement(handle)?;
+            let mut record_count = 0;
+            for stmt in statement.bind()? {
+                record_count += session
+                    .execute_update(statement.statement.query(), Some(stmt))
+                    .await?;
+            }
+            record_count
+        } else {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Unsupported Flight SQL command of DoPut: {}",
+                command.type_url
+            )));
+        };
+
+        Ok(Some(PutResult {
+            app_metadata: DoPutUpdateResult { record_count }.encode_to_vec(),
+        }))
+    }
+}
+
+fn result_schema(planned: &PlannedQuery, include_table_schema: bool) -> ArrowSchema {
+    let mut schema = arrow_schema(&planned.schema);
+    if include_table_schema {
+        schema.fields.push(ArrowField::new(
+            "table_schema",
+            ArrowDataType::Binary,
+            false,
+        ));
+    }
+    schema
+}
+
+async fn encode_block(
+    encoder: &FlightDataEncoder,
+    ctx: &Arc<QueryContext>,
+    block: DataBlock,
+    include_table_schema: bool,
+) -> Result<Vec<FlightData>> {
+    if !include_table_schema {
+        return encoder.encode_chunk(&arrow_chunk(block)?);
+    }
+    let table_schemas = table_schemas(ctx, &block).await?;
+    let mut arrays = arrow_chunk(block)?.into_arrays();
+    arrays.push(Box::new(BinaryArray::<i32>::from_iter_values(
+        table_schemas.into_iter(),
+    )));
+    encoder.encode_chunk(&ArrowChunk::try_new(arrays)?)
+}
+
+// The schemas in the IPC format of the tables in the result of CommandGetTables.
+async fn table_schemas(ctx: &Arc<QueryContext>, block: &DataBlock) -> Result<Vec<Vec<u8>>> {
+    let mut schemas = Vec::with_capacity(block.num_rows());
+    for row in 0..block.num_rows() {
+        let name = |index: usize| match block.get_by_offset(index).value.as_ref().index(row) {
+            Some(ScalarRef::String(name)) => Ok(String::from_utf8_lossy(name).to_string()),
+            _ => Err(ErrorCode::Internal(
+                "The names of the table should be strings",
+            )),
+        };
+        let (catalog, database, table) = (name(0)?, name(1)?, name(2)?);
+        let table = ctx.get_table(&catalog, &database, &table).await?;
+        let schema = arrow_schema(&DataSchema::from(table.schema()));
+        schemas.push(schema_to_ipc(&schema)?);
+    }
+    Ok(schemas)
+}
+
+#[async_trait::async_trait]
+impl FlightService for DatabendQueryFlightSqlService {
+    type HandshakeStream = FlightStream<HandshakeResponse>;
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn handshake(
+        &self,
+        request: StreamReq<HandshakeRequest>,
+    ) -> Response<Self::HandshakeStream> {
+        let authorization = request.metadata().get("authorization");
+        let client_ip = request.remote_addr().map(|addr| addr.ip().to_string());
+        let token = self
+            .sessions
+            .handshake(authorization.map(|value| value.as_bytes()), client_ip)
+            .await
+            .map_err(|e| Status::unauthenticated(e.message()))?;
+
+        let response = HandshakeResponse {
+            protocol_version: 0,
+            payload: token.as_bytes().to_vec(),
+        };
+        let mut response = RawResponse::new(
+            Box::pin(tokio_stream::once(Ok(response))) as FlightStream<HandshakeResponse>
+        );
+        let bearer = MetadataValue::try_from(format!("Bearer {}", token))
+            .map_err(|e| Status::internal(e.to_string()))?;
+        response.metadata_mut().insert("authorization", bearer);
+        Ok(response)
+    }
+
+    type ListFlightsStream = FlightStream<FlightInfo>;
+
+    async fn list_flights(&self, _: Request<Criteria>) -> Response<Self::ListFlightsStream> {
+        Err(Status::unimplemented(
+            "DatabendQuery Flight SQL does not implement list_flights.",
+        ))
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn get_flight_info(&self, request: Request<FlightDescriptor>) -> Response<FlightInfo> {
+        let session = self.get_session(&request)?;
+        let info = Self::flight_info(&session, request.into_inner()).await?;
+        Ok(RawResponse::new(info))
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn get_schema(&self, request: Request<FlightDescriptor>) -> Response<SchemaResult> {
+        let session = self.get_session(&request)?;
+        let (_, schema) = Self::plan_command(&session, request.get_ref()).await?;
+        Ok(RawResponse::new(SchemaResult {
+            schema: schema_to_ipc(&schema)?,
+        }))
+    }
+
+    type DoGetStream = FlightStream<FlightData>;
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn do_get(&self, request: Request<Ticket>) -> Response<Self::DoGetStream> {
+        let session = self.get_session(&request)?;
+        let rx = Self::execute_ticket(session, &request.get_ref().ticket).await?;
+        Ok(RawResponse::new(Box::pin(rx)))
+    }
+
+    type DoPutStream = FlightStream<PutResult>;
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn do_put(&self, request: StreamReq<FlightData>) -> Response<Self::DoPutStream> {
+        let session = self.get_session(&request)?;
+        let mut stream = request.into_inner();
+        let mut messages = vec![];
+        while let Some(message) = stream.next().await {
+            messages.push(message?);
+        }
+
+        let results = Self::do_put_command(&session, messages)
+            .await?
+            .map(Ok)
+            .into_iter()
+            .collect::<Vec<_>>();
+        Ok(RawResponse::new(
+            Box::pin(tokio_stream::iter(results)) as FlightStream<PutResult>
+        ))
+    }
+
+    type DoExchangeStream = FlightStream<FlightData>;
+
+    async fn do_exchange(&self, _: StreamReq<FlightData>) -> Response<Self::DoExchangeStream> {
+        Err(Status::unimplemented(
+            "DatabendQuery Flight SQL does not implement do_exchange.",
+        ))
+    }
+
+    type DoActionStream = FlightStream<FlightResult>;
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn do_action(&self, request: Request<Action>) -> Response<Self::DoActionStream> {
+        let session = self.get_session(&request)?;
+        let action = request.into_inner();
+        let results = match action.r#type.as_str() {
+            CREATE_PREPARED_STATEMENT => {
+                vec![Ok(
+                    Self::create_prepared_statement(&session, &action.body).await?
+                )]
+            }
+            CLOSE_PREPARED_STATEMENT => {
+                let request = Any::decode_bytes(&action.body)?
+                    .unpack::<ActionClosePreparedStatementRequest>()?;
+                session.remove_statement(&request.prepared_statement_handle);
+                vec![]
+            }
+            action_type => {
+                return Err(Status::unimplemented(format!(
+                    "Unsupported Flight SQL action: {}",
+                    action_type
+                )));
+            }
+        };
+
+        Ok(RawResponse::new(
+            Box::pin(tokio_stream::iter(results)) as FlightStream<FlightResult>
+        ))
+    }
+
+    type ListActionsStream = FlightStream<ActionType>;
+
+    async fn list_actions(&self, _: Request<Empty>) -> Response<Self::ListActionsStream> {
+        let actions = vec![
+            Ok(ActionType {
+                r#type: CREATE_PREPARED_STATEMENT.to_string(),
+                description: "Create a prepared statement with the placeholders".to_string(),
+            }),
+            Ok(ActionType {
+                r#type: CLOSE_PREPARED_STATEMENT.to_string(),
+                description: "Close the prepared statement".to_string(),
+            }),
+        ];
+        Ok(RawResponse::new(
+            Box::pin(tokio_stream::iter(actions)) as FlightStream<ActionType>
+        ))
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use common_ast::ast::Statement;
+use common_base::runtime::TrySpawn;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_expression::DataSchemaRef;
+use common_expression::Scalar;
+use common_expression::SendableDataBlockStream;
+use common_sql::Planner;
+use common_sql::PreparedStatement;
+use futures_util::StreamExt;
+use headers::authorization::Basic;
+use headers::authorization::Bearer;
+use headers::authorization::Credentials;
+use http::HeaderValue;
+use parking_lot::Mutex;
+use parking_lot::RwLock;
+use tracing::info;
+use tracing::Instrument;
+
+use crate::auth::Credential;
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterFactory;
+use crate::interpreters::InterpreterQueryLog;
+use crate::sessions::QueryContext;
+use crate::sessions::Session;
+use crate::sessions::SessionManager;
+use crate::sessions::SessionType;
+use crate::sessions::TableContext;
+
+// The sessions not accessed in the duration are closed, with their prepared statements.
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// A query planned by `GetFlightInfo`, it's executed by `DoGet` with the ticket.
+pub struct PlannedQuery {
+    pub context: Arc<QueryContext>,
+    pub interpreter: Arc<dyn Interpreter>,
+    pub schema: DataSchemaRef,
+    pub has_result_set: bool,
+}
+
+/// The query of a ticket, the schemas of the tables are appended to the result of
+/// `CommandGetTables` if `include_schema` is set.
+pub struct TicketQuery {
+    pub planned: PlannedQuery,
+    pub include_table_schema: bool,
+}
+
+/// A statement created by the `CreatePreparedStatement` action.
+pub struct PreparedQuery {
+    pub statement: PreparedStatement,
+    /// The rows of the parameters bound by `DoPut`.
+    pub params: Vec<Vec<Scalar>>,
+}
+
+impl PreparedQuery {
+    /// The statements to execute, one for each row of the parameters.
+    pub fn bind(&self) -> Result<Vec<Statement>> {
+        if self.statement.num_params() == 0 {
+            return Ok(vec![self.statement.bind(&[])?]);
+        }
+        if self.params.is_empty() {
+            return Err(ErrorCode::BadArguments(format!(
+                "The {} parameters of the prepared statement are not bound",
+                self.statement.num_params()
+            )));
+        }
+        self.params
+            .iter()
+            .map(|params| self.statement.bind(params))
+            .collect()
+    }
+}
+
+/// The session of the client authenticated by `Handshake`.
+pub struct FlightSqlSession {
+    pub session: Arc<Session>,
+    last_access: Mutex<Instant>,
+    statements: Mutex<HashMap<Vec<u8>, Arc<PreparedQuery>>>,
+    tickets: Mutex<HashMap<Vec<u8>, TicketQuery>>,
+}
+
+impl FlightSqlSession {
+    fn create(session: Arc<Session>) -> FlightSqlSession {
+        FlightSqlSession {
+            session,
+            last_access: Mutex::new(Instant::now()),
+            statements: Mutex::new(HashMap::new()),
+            tickets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.last_access.lock().elapsed() > SESSION_IDLE_TIMEOUT
+    }
+
+    fn touch(&self) {
+        *self.last_access.lock() = Instant::now();
+    }
+
+    pub fn add_statement(&self, handle: Vec<u8>, statement: PreparedQuery) {
+        self.statements.lock().insert(handle, Arc::new(statement));
+    }
+
+    pub fn get_statement(&self, handle: &[u8]) -> Result<Arc<PreparedQuery>> {
+        self.statements
+            .lock()
+            .get(handle)
+            .cloned()
+            .ok_or_else(|| ErrorCode::BadArguments("Unknown prepared statement handle"))
+    }
+
+    pub fn set_params(&self, handle: &[u8], params: Vec<Vec<Scalar>>) -> Result<()> {
+        let mut statements = self.statements.lock();
+        let statement = statements
+            .get_mut(handle)
+            .ok_or_else(|| ErrorCode::BadArguments("Unknown prepared statement handle"))?;
+        *statement = Arc::new(PreparedQuery {
+            statement: statement.statement.clone(),
+            params,
+        });
+        Ok(())
+    }
+
+    pub fn remove_statement(&self, handle: &[u8]) {
+        self.statements.lock().remove(handle);
+    }
+
+    /// Keep the planned query until it's fetched by `DoGet` with the ticket.
+    pub fn add_ticket(&self, ticket: Vec<u8>, query: TicketQuery) {
+        self.tickets.lock().insert(ticket, query);
+    }
+
+    pub fn take_ticket(&self, ticket: &[u8]) -> Option<TicketQuery> {
+        self.tickets.lock().remove(ticket)
+    }
+
+    /// Plan the query, the statement of a prepared query is parsed and bound already.
+    pub async fn plan_query(&self, query: &str, stmt: Option<Statement>) -> Result<PlannedQuery> {
+        let context = self.session.create_query_context().await?;
+        let mut planner = Planner::new(context.clone());
+        let plan = match stmt {
+            Some(stmt) => planner.plan_stmt(stmt).await?.0,
+            None => planner.plan_sql(query).await?.0,
+        };
+        context.attach_query_str(plan.to_string(), query);
+        let interpreter = match InterpreterFactory::get(context.clone(), &plan).await {
+            Ok(interpreter) => interpreter,
+            Err(e) => {
+                InterpreterQueryLog::fail_to_start(context, e.clone());
+                return Err(e);
+            }
+        };
+        Ok(PlannedQuery {
+            schema: interpreter.schema(),
+            has_result_set: plan.has_result_set(),
+            context,
+            interpreter,
+        })
+    }
+
+    pub async fn execute_query(planned: PlannedQuery) -> Result<SendableDataBlockStream> {
+        let PlannedQuery {
+            context,
+            interpreter,
+            ..
+        } = planned;
+        let query_result = context.try_spawn({
+            let ctx = context.clone();
+            async move {
+                let mut data_stream = interpreter.execute(ctx.clone()).await?;
+                let intercepted_stream = async_stream::stream! {
+                    while let Some(item) = data_stream.next().await {
+                        yield item
+                    };
+                };
+                Ok::<_, ErrorCode>(intercepted_stream.boxed())
+            }
+            .in_current_span()
+        })?;
+
+        query_result.await.map_err_to_code(
+            ErrorCode::TokioError,
+            || "Cannot join handle from context's runtime",
+        )?
+    }
+
+    /// Execute the statement without result set, returns the number of the affected rows.
+    pub async fn execute_update(&self, query: &str, stmt: Option<Statement>) -> Result<i64> {
+        info!("Flight SQL update: {}", query);
+        let planned = self.plan_query(query, stmt).await?;
+        let context = planned.context.clone();
+        let mut blocks = Self::execute_query(planned).await?;
+        while let Some(block) = blocks.next().await {
+            block?;
+        }
+        Ok(context.get_write_progress_value().rows as i64)
+    }
+}
+
+/// The sessions of the clients by the bearer tokens.
+pub struct FlightSqlSessions {
+    sessions: RwLock<HashMap<String, Arc<FlightSqlSession>>>,
+}
+
+impl FlightSqlSessions {
+    pub fn create() -> FlightSqlSessions {
+        FlightSqlSessions {
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Authenticate the client by the `authorization` header of `Handshake`, the user name
+    /// and password in the Basic scheme, or the JWT in the Bearer scheme.
+    ///
+    /// Returns the bearer token of the session for the later requests.
+    pub async fn handshake(
+        &self,
+        authorization: Option<&[u8]>,
+        client_ip: Option<String>,
+    ) -> Result<String> {
+        let credential = match authorization {
+            None => {
+                return Err(ErrorCode::AuthenticateFailure(
+                    "No authorization header detected",
+                ));
+            }
+            Some(value) => parse_credential(value, client_ip)?,
+        };
+
+        let session = SessionManager::instance()
+            .create_session(SessionType::FlightSQL)
+            .await?;
+        let ctx = session.create_query_context().await?;
+        ctx.get_auth_manager()
+            .auth(session.clone(), &credential)
+            .await?;
+
+        let token = uuid::Uuid::new_v4().to_string();
+        let mut sessions = self.sessions.write();
+        sessions.retain(|_, session| !session.is_expired());
+        sessions.insert(token.clone(), Arc::new(FlightSqlSession::create(session)));
+        Ok(token)
+    }
+
+    /// Get the session by the bearer token in the `authorization` header.
+    pub fn get_session(&self, authorization: Option<&[u8]>) -> Result<Arc<FlightSqlSession>> {
+        let token = authorization
+            .and_then(|value| value.strip_prefix(b"Bearer "))
+            .and_then(|token| std::str::from_utf8(token).ok())
+            .ok_or_else(|| {
+                ErrorCode::AuthenticateFailure("Bearer token is required, please handshake first")
+            })?;
+
+        let session = self
+            .sessions
+            .read()
+            .get(token.trim())
+            .cloned()
+            .filter(|session| !session.is_expired())
+            .ok_or_else(|| ErrorCode::AuthenticateFailure("Invalid or expired bearer token"))?;
+        session.touch();
+        Ok(session)
+    }
+}
+
+fn parse_credential(value: &[u8], client_ip: Option<String>) -> Result<Credential> {
+    let value = HeaderValue::from_bytes(value)
+        .map_err(|_| ErrorCode::AuthenticateFailure("bad authorization header"))?;
+    if value.as_bytes().starts_with(b"Basic ") {
+        match Basic::decode(&value) {
+            Some(basic) => {
+                let name = basic.username().to_string();
+                let password = basic.password().to_owned().as_bytes().to_vec();
+                let password = (!password.is_empty()).then_some(password);
+                Ok(Credential::Password {
+                    name,
+                    password,
+                    hostname: client_ip,
+                })
+            }
+            None => Err(ErrorCode::AuthenticateFailure("bad Basic auth header")),
+        }
+    } else if value.as_bytes().starts_with(b"Bearer ") {
+        match Bearer::decode(&value) {
+            Some(bearer) => Ok(Credential::Jwt {
+                token: bearer.token().to_string(),
+                hostname: client_ip,
+            }),
+            None => Err(ErrorCode::AuthenticateFailure("bad Bearer auth header")),
+        }
+    } else {
+        Err(ErrorCode::AuthenticateFailure("bad auth header"))
+    }
+}
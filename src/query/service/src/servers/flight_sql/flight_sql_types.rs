@@ -0,0 +1,211 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_arrow::arrow::array::Array;
+use common_arrow::arrow::array::BinaryArray;
+use common_arrow::arrow::array::MutableUtf8Array;
+use common_arrow::arrow::array::Utf8Array;
+use common_arrow::arrow::chunk::Chunk as ArrowChunk;
+use common_arrow::arrow::datatypes::DataType as ArrowDataType;
+use common_arrow::arrow::datatypes::Field as ArrowField;
+use common_arrow::arrow::datatypes::Schema as ArrowSchema;
+use common_arrow::arrow::io::flight::default_ipc_fields;
+use common_arrow::arrow::io::flight::deserialize_batch;
+use common_arrow::arrow::io::flight::deserialize_schemas;
+use common_arrow::arrow::io::flight::serialize_batch;
+use common_arrow::arrow::io::flight::serialize_schema;
+use common_arrow::arrow::io::flight::serialize_schema_to_info;
+use common_arrow::arrow::io::flight::WriteOptions;
+use common_arrow::arrow::io::ipc::IpcField;
+use common_arrow::arrow_format::flight::data::FlightData;
+use common_arrow::ArrayRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::types::DataType;
+use common_expression::Column;
+use common_expression::DataBlock;
+use common_expression::DataSchema;
+use common_expression::Scalar;
+use common_expression::TableDataType;
+
+/// The arrow schema of the result set sent to the clients.
+///
+/// The strings of Databend are binary in arrow, they are sent as utf8 like the other
+/// databases, and the variants are sent as json text.
+pub fn arrow_schema(schema: &DataSchema) -> ArrowSchema {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type().remove_nullable() {
+            DataType::String | DataType::Variant => {
+                ArrowField::new(field.name(), ArrowDataType::Utf8, field.is_nullable())
+            }
+            _ => ArrowField::from(field),
+        })
+        .collect::<Vec<_>>();
+    ArrowSchema::from(fields)
+}
+
+/// The schema in the IPC format of `FlightInfo` and `ActionCreatePreparedStatementResult`.
+pub fn schema_to_ipc(schema: &ArrowSchema) -> Result<Vec<u8>> {
+    Ok(serialize_schema_to_info(schema, None)?)
+}
+
+/// Encode the result set into the `FlightData` messages, the schema message first.
+pub struct FlightDataEncoder {
+    ipc_fields: Vec<IpcField>,
+    options: WriteOptions,
+}
+
+impl FlightDataEncoder {
+    pub fn create(schema: &ArrowSchema) -> FlightDataEncoder {
+        FlightDataEncoder {
+            ipc_fields: default_ipc_fields(&schema.fields),
+            options: WriteOptions { compression: None },
+        }
+    }
+
+    pub fn encode_schema(&self, schema: &ArrowSchema) -> FlightData {
+        serialize_schema(schema, Some(&self.ipc_fields))
+    }
+
+    pub fn encode_chunk(&self, chunk: &ArrowChunk<ArrayRef>) -> Result<Vec<FlightData>> {
+        let (mut messages, batch) = serialize_batch(chunk, &self.ipc_fields, &self.options)?;
+        messages.push(batch);
+        Ok(messages)
+    }
+}
+
+/// Convert the block to the arrow chunk in the schema of `arrow_schema`.
+pub fn arrow_chunk(block: DataBlock) -> Result<ArrowChunk<ArrayRef>> {
+    let arrays = block
+        .convert_to_full()
+        .columns()
+        .iter()
+        .map(|entry| {
+            let column = entry.value.clone().into_column().unwrap();
+            let array = column.as_arrow();
+            match entry.data_type.remove_nullable() {
+                DataType::String => utf8_array(array.as_ref()),
+                DataType::Variant => json_array(array.as_ref()),
+                _ => Ok(array),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ArrowChunk::try_new(arrays)?)
+}
+
+fn binary_array(array: &dyn Array) -> Result<&BinaryArray<i64>> {
+    array
+        .as_any()
+        .downcast_ref::<BinaryArray<i64>>()
+        .ok_or_else(|| ErrorCode::Internal("The string column should be `BinaryArray<i64>`"))
+}
+
+fn utf8_array(array: &dyn Array) -> Result<ArrayRef> {
+    let array = binary_array(array)?;
+    let mut values = MutableUtf8Array::<i32>::with_capacities(array.len(), array.values().len());
+    for value in array.iter() {
+        let value = value
+            .map(std::str::from_utf8)
+            .transpose()
+            .map_err(|e| ErrorCode::BadBytes(format!("Invalid utf8 string: {}", e)))?;
+        values.push(value);
+    }
+    Ok(Box::new(Utf8Array::from(values)))
+}
+
+fn json_array(array: &dyn Array) -> Result<ArrayRef> {
+    let array = binary_array(array)?
+        .iter()
+        .map(|value| value.map(common_jsonb::to_string))
+        .collect::<Utf8Array<i32>>();
+    Ok(Box::new(array))
+}
+
+/// Decode the rows of parameters sent by `DoPut`, the schema message first.
+pub fn decode_params(messages: &[FlightData]) -> Result<Vec<Vec<Scalar>>> {
+    let (schema, ipc_schema) = match messages.first() {
+        None => return Ok(vec![]),
+        Some(message) => deserialize_schemas(&message.data_header)?,
+    };
+
+    let mut data_types = Vec::with_capacity(schema.fields.len());
+    for field in &schema.fields {
+        if !is_supported_param_type(field.data_type()) {
+            return Err(ErrorCode::BadArguments(format!(
+                "Unsupported type {:?} of the parameter {}",
+                field.data_type(),
+                field.name
+            )));
+        }
+        data_types.push(DataType::from(&TableDataType::from(field)));
+    }
+
+    let mut rows = vec![];
+    for message in messages.iter().skip(1) {
+        if message.data_header.is_empty() {
+            continue;
+        }
+        let chunk = deserialize_batch(message, &schema.fields, &ipc_schema, &Default::default())?;
+        let columns = chunk
+            .arrays()
+            .iter()
+            .zip(data_types.iter())
+            .map(|(array, data_type)| Column::from_arrow(array.as_ref(), data_type))
+            .collect::<Vec<_>>();
+        for row in 0..chunk.len() {
+            rows.push(
+                columns
+                    .iter()
+                    .map(|column| column.index(row).unwrap().to_owned())
+                    .collect(),
+            );
+        }
+    }
+    Ok(rows)
+}
+
+fn is_supported_param_type(data_type: &ArrowDataType) -> bool {
+    matches!(
+        data_type,
+        ArrowDataType::Null
+            | ArrowDataType::Boolean
+            | ArrowDataType::Int8
+            | ArrowDataType::Int16
+            | ArrowDataType::Int32
+            | ArrowDataType::Int64
+            | ArrowDataType::UInt8
+            | ArrowDataType::UInt16
+            | ArrowDataType::UInt32
+            | ArrowDataType::UInt64
+            | ArrowDataType::Float32
+            | ArrowDataType::Float64
+            | ArrowDataType::Decimal(_, _)
+            | ArrowDataType::Utf8
+            | ArrowDataType::LargeUtf8
+            | ArrowDataType::Binary
+            | ArrowDataType::LargeBinary
+            | ArrowDataType::Date32
+            | ArrowDataType::Timestamp(_, _)
+    )
+}
+
+/// The parameters are untyped until they are bound, they're declared as null in the schema.
+pub fn params_schema(num_params: usize) -> ArrowSchema {
+    let fields = (1..=num_params)
+        .map(|index| ArrowField::new(format!("${}", index), ArrowDataType::Null, true))
+        .collect::<Vec<_>>();
+    ArrowSchema::from(fields)
+}
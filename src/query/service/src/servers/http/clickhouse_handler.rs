@@ -37,7 +37,6 @@ use common_sql::plans::Plan;
 use common_sql::Planner;
 use futures::StreamExt;
 use http::HeaderMap;
-use naive_cityhash::cityhash128;
 use poem::error::BadRequest;
 use poem::error::InternalServerError;
 use poem::error::Result as PoemResult;
@@ -56,6 +55,7 @@ use tracing::info;
 
 use crate::interpreters::InterpreterFactory;
 use crate::interpreters::InterpreterPtr;
+use crate::servers::clickhouse::compress_block;
 use crate::servers::http::v1::HttpQueryContext;
 use crate::servers::http::ClickHouseFederated;
 use crate::sessions::QueryContext;
@@ -452,38 +452,6 @@ pub fn clickhouse_router() -> impl Endpoint {
         .with(poem::middleware::Compression)
 }
 
-// default codec is always lz4
-fn compress_block(input: Vec<u8>) -> Result<Vec<u8>> {
-    if input.is_empty() {
-        Ok(vec![])
-    } else {
-        // TODO(youngsofun): optimize buffer usages
-        let uncompressed_size = input.len();
-        let compressed =
-            lz4::block::compress(&input, Some(lz4::block::CompressionMode::FAST(1)), false)
-                .map_err_to_code(ErrorCode::BadBytes, || "lz4 compress error")?;
-
-        // 9 bytes header: 1 byte for method, 4 bytes for compressed size, 4 bytes for uncompressed size
-        let header_size = 9;
-        let method_byte_lz4 = 0x82u8;
-        let mut compressed_with_header = Vec::with_capacity(compressed.len() + header_size);
-        compressed_with_header.push(method_byte_lz4);
-        let compressed_size = (compressed.len() + header_size) as u32;
-        let uncompressed_size = uncompressed_size as u32;
-        compressed_with_header.extend_from_slice(&compressed_size.to_le_bytes());
-        compressed_with_header.extend_from_slice(&uncompressed_size.to_le_bytes());
-        compressed_with_header.extend_from_slice(&compressed);
-
-        // 16 bytes checksum
-        let mut output = Vec::with_capacity(compressed_with_header.len() + 16);
-        let checksum = cityhash128(&compressed_with_header);
-        output.extend_from_slice(&checksum.lo.to_le_bytes());
-        output.extend_from_slice(&checksum.hi.to_le_bytes());
-        output.extend_from_slice(&compressed_with_header);
-        Ok(output)
-    }
-}
-
 fn serialize_one_block(
     ctx: Arc<QueryContext>,
     schema: TableSchemaRef,
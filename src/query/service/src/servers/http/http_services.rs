@@ -36,8 +36,10 @@ use tracing::info;
 use super::v1::upload_to_stage;
 use crate::auth::AuthMgr;
 use crate::servers::http::middleware::HTTPSessionMiddleware;
+use crate::servers::http::v1::async_query_route;
 use crate::servers::http::v1::clickhouse_router;
 use crate::servers::http::v1::query_route;
+use crate::servers::http::v1::start_async_query_result_sweeper;
 use crate::servers::http::v1::streaming_load;
 use crate::servers::Server;
 
@@ -93,6 +95,7 @@ impl HttpHandler {
                 )
                 .nest("/clickhouse", clickhouse_router())
                 .nest("/v1/query", query_route())
+                .nest("/v1/async_query", async_query_route())
                 .at("/v1/streaming_load", put(streaming_load))
                 .at("/v1/upload_to_stage", put(upload_to_stage)),
             HttpHandlerKind::Clickhouse => Route::new().nest("/", clickhouse_router()),
@@ -156,6 +159,9 @@ impl Server for HttpHandler {
 
     async fn start(&mut self, listening: SocketAddr) -> Result<SocketAddr, ErrorCode> {
         let config = GlobalConfig::instance();
+        if let HttpHandlerKind::Query = self.kind {
+            start_async_query_result_sweeper();
+        }
 
         let res = match config.query.http_handler_tls_server_key.is_empty()
             || config.query.http_handler_tls_server_cert.is_empty()
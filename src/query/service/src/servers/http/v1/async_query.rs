@@ -0,0 +1,465 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The queries submitted to run in the background.
+//!
+//! The state of the query is kept in the meta service and the result is written to the
+//! storage, so the query survives the disconnection of the client, and any query node
+//! can answer the state and the result by the query id. The results whose states are
+//! expired are deleted by a sweeper in the background.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use common_base::base::tokio::time::sleep;
+use common_base::runtime::GlobalIORuntime;
+use common_base::runtime::TrySpawn;
+use common_config::GlobalConfig;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::infer_table_schema;
+use common_expression::DataBlock;
+use common_management::AsyncQueryApi;
+use common_meta_app::principal::AsyncQueryField;
+use common_meta_app::principal::AsyncQueryInfo;
+use common_meta_app::principal::AsyncQueryState;
+use common_storage::DataOperator;
+use common_storages_result_cache::ResultCacheReader;
+use common_storages_result_cache::ResultCacheWriter;
+use common_users::UserApiProvider;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use opendal::ObjectMetakey;
+use poem::error::InternalServerError;
+use poem::error::Result as PoemResult;
+use poem::get;
+use poem::http::StatusCode;
+use poem::post;
+use poem::web::Json;
+use poem::web::Path;
+use poem::Route;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tracing::info;
+use tracing::warn;
+
+use super::http_query_handlers::QueryError;
+use super::http_query_handlers::QueryResponseField;
+use super::json_block::block_to_json_value;
+use super::query::plan_in_new_session;
+use super::query::HttpQueryRequest;
+use super::ExecuteStateKind;
+use super::HttpQueryContext;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::QueryContext;
+use crate::sessions::SessionType;
+use crate::sessions::TableContext;
+
+const ASYNC_QUERY_RESULT_PREFIX: &str = "_async_query_result";
+
+// The results are swept once in the interval. The results written in the last interval are
+// kept, the state of a long running query may expire before its result is written.
+const RESULT_SWEEP_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub fn make_async_state_uri(query_id: &str) -> String {
+    format!("/v1/async_query/{}", query_id)
+}
+
+pub fn make_async_result_uri(query_id: &str) -> String {
+    format!("/v1/async_query/{}/result", query_id)
+}
+
+pub fn make_async_result_page_uri(query_id: &str, page_no: usize) -> String {
+    format!("/v1/async_query/{}/result/{}", query_id, page_no)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AsyncQueryResponse {
+    pub id: String,
+    pub state: ExecuteStateKind,
+    pub error: Option<QueryError>,
+    pub schema: Vec<QueryResponseField>,
+    pub num_rows: u64,
+    // only returned by the `result_uri`, one page of the rows.
+    pub data: Vec<Vec<JsonValue>>,
+    // the uri of the next page of the result, None if it's the last page.
+    pub next_uri: Option<String>,
+    pub submit_time: Option<DateTime<Utc>>,
+    pub finish_time: Option<DateTime<Utc>>,
+    pub state_uri: Option<String>,
+    pub result_uri: Option<String>,
+}
+
+impl AsyncQueryResponse {
+    fn from_info(
+        info: &AsyncQueryInfo,
+        data: Vec<Vec<JsonValue>>,
+        next_uri: Option<String>,
+    ) -> Self {
+        let state = match info.state {
+            AsyncQueryState::Running => ExecuteStateKind::Running,
+            AsyncQueryState::Succeeded => ExecuteStateKind::Succeeded,
+            AsyncQueryState::Failed => ExecuteStateKind::Failed,
+        };
+        let error = info.error_code.map(|code| QueryError {
+            code,
+            message: info.error_message.clone().unwrap_or_default(),
+        });
+        let result_uri = match info.state {
+            AsyncQueryState::Running => None,
+            _ => Some(make_async_result_uri(&info.query_id)),
+        };
+        AsyncQueryResponse {
+            id: info.query_id.clone(),
+            state,
+            error,
+            schema: info
+                .schema
+                .iter()
+                .map(|f| QueryResponseField::new(f.name.clone(), f.data_type.clone()))
+                .collect(),
+            num_rows: info.num_rows,
+            data,
+            next_uri,
+            submit_time: Some(info.submit_time),
+            finish_time: info.finish_time,
+            state_uri: Some(make_async_state_uri(&info.query_id)),
+            result_uri,
+        }
+    }
+
+    fn fail_to_start(err: &ErrorCode) -> Self {
+        AsyncQueryResponse {
+            id: "".to_string(),
+            state: ExecuteStateKind::Failed,
+            error: Some(QueryError::from_error_code(err)),
+            schema: vec![],
+            num_rows: 0,
+            data: vec![],
+            next_uri: None,
+            submit_time: None,
+            finish_time: None,
+            state_uri: None,
+            result_uri: None,
+        }
+    }
+}
+
+async fn submit(ctx: &HttpQueryContext, request: HttpQueryRequest) -> Result<AsyncQueryInfo> {
+    let (session, ctx, interpreter) = plan_in_new_session(ctx, &request, "async").await?;
+    let settings = ctx.get_settings();
+    let ttl = Duration::from_secs(settings.get_async_query_result_ttl()?);
+    let api = UserApiProvider::instance().get_async_query_api_client(&ctx.get_tenant())?;
+
+    let info = AsyncQueryInfo {
+        query_id: ctx.get_id(),
+        user: session.get_current_user()?.identity(),
+        sql: request.sql,
+        node_id: ctx.get_cluster().local_id.clone(),
+        state: AsyncQueryState::Running,
+        error_code: None,
+        error_message: None,
+        schema: interpreter
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| AsyncQueryField {
+                name: f.name().to_string(),
+                data_type: f.data_type().wrapped_display(),
+            })
+            .collect(),
+        num_rows: 0,
+        result_location: None,
+        max_rows_per_page: request.pagination.max_rows_per_page as u64,
+        submit_time: Utc::now(),
+        finish_time: None,
+    };
+    api.upsert_query(info.clone(), ttl).await?;
+
+    // the query is not tied to the request, it runs until finished even if the client is gone.
+    ctx.try_spawn({
+        let ctx = ctx.clone();
+        let mut info = info.clone();
+        async move {
+            match write_result(&ctx, interpreter).await {
+                Ok((num_rows, location)) => {
+                    info.state = AsyncQueryState::Succeeded;
+                    info.num_rows = num_rows;
+                    info.result_location = location;
+                }
+                Err(e) => {
+                    info.state = AsyncQueryState::Failed;
+                    info.error_code = Some(e.code());
+                    info.error_message = Some(e.message());
+                }
+            }
+            info.finish_time = Some(Utc::now());
+            info!(
+                "async query {} finished, state={:?}",
+                &info.query_id, info.state
+            );
+            if let Err(e) = api.upsert_query(info, ttl).await {
+                warn!("fail to save the state of async query: {:?}", e);
+            }
+        }
+    })?;
+    Ok(info)
+}
+
+// Execute the query and write the result to the storage, returns the number of rows and the
+// location, there is no file for an empty result.
+async fn write_result(
+    ctx: &Arc<QueryContext>,
+    interpreter: InterpreterPtr,
+) -> Result<(u64, Option<String>)> {
+    let max_bytes = ctx.get_settings().get_max_async_query_result_bytes()?;
+    let schema = infer_table_schema(&interpreter.schema())?;
+    let location = format!(
+        "{}/{}/{}",
+        ASYNC_QUERY_RESULT_PREFIX,
+        ctx.get_tenant(),
+        ctx.get_id()
+    );
+    let operator = ctx.get_data_operator()?.operator();
+    let mut writer = ResultCacheWriter::create(schema, location, operator, max_bytes);
+
+    let mut data_stream = interpreter.execute(ctx.clone()).await?;
+    while let Some(block) = data_stream.next().await {
+        let block = block?;
+        if block.num_rows() == 0 {
+            continue;
+        }
+        writer.append_block(block);
+        if writer.over_limit() {
+            return Err(ErrorCode::BadArguments(format!(
+                "the result of the async query is larger than max_async_query_result_bytes ({} bytes), unload it to a stage with COPY INTO instead",
+                max_bytes
+            )));
+        }
+    }
+
+    if writer.num_rows() == 0 {
+        return Ok((0, None));
+    }
+    let location = writer.write_to_storage().await?;
+    Ok((writer.num_rows() as u64, Some(location)))
+}
+
+// Only the user who submits the query can see it.
+async fn get_async_query(ctx: &HttpQueryContext, query_id: &str) -> PoemResult<AsyncQueryInfo> {
+    let session = ctx.get_session(SessionType::HTTPAPI("AsyncQuery".to_string()));
+    let user = session.get_current_user().map_err(InternalServerError)?;
+    let info = UserApiProvider::instance()
+        .get_async_query_api_client(&session.get_current_tenant())
+        .map_err(InternalServerError)?
+        .get_query(query_id)
+        .await
+        .map_err(InternalServerError)?;
+    match info {
+        Some(info) if info.user == user.identity() => Ok(info),
+        _ => Err(poem::Error::from_string(
+            format!("async query id not found {}", query_id),
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+#[poem::handler]
+async fn async_query_submit_handler(
+    ctx: &HttpQueryContext,
+    Json(req): Json<HttpQueryRequest>,
+) -> Json<AsyncQueryResponse> {
+    info!("receive async http query: {:?}", req);
+    let sql = req.sql.clone();
+    match submit(ctx, req).await {
+        Ok(info) => Json(AsyncQueryResponse::from_info(&info, vec![], None)),
+        Err(e) => Json(AsyncQueryResponse::fail_to_start(&e.display_with_sql(&sql))),
+    }
+}
+
+#[poem::handler]
+async fn async_query_state_handler(
+    ctx: &HttpQueryContext,
+    Path(query_id): Path<String>,
+) -> PoemResult<Json<AsyncQueryResponse>> {
+    let info = get_async_query(ctx, &query_id).await?;
+    Ok(Json(AsyncQueryResponse::from_info(&info, vec![], None)))
+}
+
+#[poem::handler]
+async fn async_query_result_handler(
+    ctx: &HttpQueryContext,
+    Path(query_id): Path<String>,
+) -> PoemResult<Json<AsyncQueryResponse>> {
+    get_result_page(ctx, &query_id, 0).await
+}
+
+#[poem::handler]
+async fn async_query_result_page_handler(
+    ctx: &HttpQueryContext,
+    Path((query_id, page_no)): Path<(String, usize)>,
+) -> PoemResult<Json<AsyncQueryResponse>> {
+    get_result_page(ctx, &query_id, page_no).await
+}
+
+async fn get_result_page(
+    ctx: &HttpQueryContext,
+    query_id: &str,
+    page_no: usize,
+) -> PoemResult<Json<AsyncQueryResponse>> {
+    let info = get_async_query(ctx, query_id).await?;
+    if info.state == AsyncQueryState::Running {
+        return Err(poem::Error::from_string(
+            format!("async query {} is still running", query_id),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let page_size = info.max_rows_per_page.max(1) as usize;
+    let start = page_no.saturating_mul(page_size);
+    if start > 0 && start >= info.num_rows as usize {
+        return Err(poem::Error::from_string(
+            format!("page {} of async query {} not found", page_no, query_id),
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    let mut data = vec![];
+    if let Some(location) = &info.result_location {
+        let session = ctx.get_session(SessionType::HTTPAPI("AsyncQuery".to_string()));
+        let query_ctx = session
+            .create_query_context()
+            .await
+            .map_err(InternalServerError)?;
+        let format_settings = query_ctx
+            .get_format_settings()
+            .map_err(InternalServerError)?;
+        let operator = query_ctx
+            .get_data_operator()
+            .map_err(InternalServerError)?
+            .operator();
+        let blocks = ResultCacheReader::read_result_from_location(&operator, location)
+            .await
+            .map_err(InternalServerError)?;
+        let block = DataBlock::concat(&blocks).map_err(InternalServerError)?;
+        let end = (start + page_size).min(block.num_rows());
+        if start < end {
+            let page = block.slice(start..end);
+            data = block_to_json_value(&page, &format_settings).map_err(InternalServerError)?;
+        }
+    }
+    let next_uri = match (page_no + 1).saturating_mul(page_size) < info.num_rows as usize {
+        true => Some(make_async_result_page_uri(query_id, page_no + 1)),
+        false => None,
+    };
+    Ok(Json(AsyncQueryResponse::from_info(&info, data, next_uri)))
+}
+
+#[poem::handler]
+async fn async_query_drop_handler(
+    ctx: &HttpQueryContext,
+    Path(query_id): Path<String>,
+) -> PoemResult<StatusCode> {
+    let info = get_async_query(ctx, &query_id).await?;
+    if info.state == AsyncQueryState::Running {
+        return Err(poem::Error::from_string(
+            format!("async query {} is still running, can not drop it", query_id),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let session = ctx.get_session(SessionType::HTTPAPI("AsyncQuery".to_string()));
+    let query_ctx = session
+        .create_query_context()
+        .await
+        .map_err(InternalServerError)?;
+    if let Some(location) = &info.result_location {
+        let operator = query_ctx
+            .get_data_operator()
+            .map_err(InternalServerError)?
+            .operator();
+        operator
+            .object(location)
+            .delete()
+            .await
+            .map_err(InternalServerError)?;
+    }
+    UserApiProvider::instance()
+        .get_async_query_api_client(&query_ctx.get_tenant())
+        .map_err(InternalServerError)?
+        .drop_query(&query_id)
+        .await
+        .map_err(InternalServerError)?;
+    Ok(StatusCode::OK)
+}
+
+/// Start the sweeper of the results, which deletes the results whose states are expired,
+/// they can not be read by anyone.
+pub fn start_async_query_result_sweeper() {
+    GlobalIORuntime::instance().spawn(async move {
+        loop {
+            sleep(RESULT_SWEEP_INTERVAL).await;
+            if let Err(e) = sweep_results().await {
+                warn!("fail to sweep the results of async queries: {:?}", e);
+            }
+        }
+    });
+}
+
+async fn sweep_results() -> Result<()> {
+    let tenant = GlobalConfig::instance().query.tenant_id.clone();
+    let api = UserApiProvider::instance().get_async_query_api_client(&tenant)?;
+    let operator = DataOperator::instance().operator();
+    let prefix = format!("{}/{}/", ASYNC_QUERY_RESULT_PREFIX, tenant);
+    let mut dirs = match operator.object(&prefix).list().await {
+        Err(e) if e.kind() == opendal::ErrorKind::ObjectNotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+        Ok(v) => v,
+    };
+
+    let expire_before = Utc::now().timestamp() - RESULT_SWEEP_INTERVAL.as_secs() as i64;
+    while let Some(dir) = dirs.try_next().await? {
+        // The results are written to `{prefix}/{query_id}/`.
+        let query_id = dir.name().trim_end_matches('/');
+        if api.get_query(query_id).await?.is_some() {
+            continue;
+        }
+        let mut files = operator.object(dir.path()).list().await?;
+        while let Some(file) = files.try_next().await? {
+            let meta = file.metadata(ObjectMetakey::LastModified).await?;
+            match meta.last_modified() {
+                Some(t) if t.unix_timestamp() < expire_before => {
+                    info!("delete the expired result of async query {}", file.path());
+                    operator.object(file.path()).delete().await?;
+                }
+                _ => continue,
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn async_query_route() -> Route {
+    Route::new()
+        .at("/", post(async_query_submit_handler))
+        .at(
+            "/:id",
+            get(async_query_state_handler).delete(async_query_drop_handler),
+        )
+        .at("/:id/result", get(async_query_result_handler))
+        .at("/:id/result/:page_no", get(async_query_result_page_handler))
+}
@@ -22,6 +22,7 @@ use poem::post;
 use poem::web::Json;
 use poem::web::Path;
 use poem::IntoResponse;
+use poem::Response;
 use poem::Route;
 use serde::Deserialize;
 use serde::Serialize;
@@ -32,6 +33,8 @@ use tracing::info;
 use super::query::ExecuteStateKind;
 use super::query::HttpQueryRequest;
 use super::query::HttpQueryResponseInternal;
+use crate::servers::http::v1::query::start_stream_query;
+use crate::servers::http::v1::query::PageData;
 use crate::servers::http::v1::query::Progresses;
 use crate::servers::http::v1::HttpQueryContext;
 use crate::servers::http::v1::HttpQueryManager;
@@ -41,6 +44,10 @@ use crate::sessions::QueryAffect;
 const HEADER_QUERY_ID: &str = "X-DATABEND-QUERY-ID";
 const HEADER_QUERY_STATE: &str = "X-DATABEND-QUERY-STATE";
 const HEADER_QUERY_PAGE_ROWS: &str = "X-DATABEND-QUERY-PAGE-ROWS";
+const HEADER_QUERY_NEXT_URI: &str = "X-DATABEND-QUERY-NEXT-URI";
+const HEADER_QUERY_FINAL_URI: &str = "X-DATABEND-QUERY-FINAL-URI";
+const HEADER_SESSION_ID: &str = "X-DATABEND-SESSION-ID";
+const CONTENT_TYPE_JSON_LINES: &str = "application/x-ndjson";
 
 pub fn make_page_uri(query_id: &str, page_no: usize) -> String {
     format!("/v1/query/{}/page/{}", query_id, page_no)
@@ -65,7 +72,7 @@ pub struct QueryError {
 }
 
 impl QueryError {
-    fn from_error_code(e: &ErrorCode) -> Self {
+    pub(crate) fn from_error_code(e: &ErrorCode) -> Self {
         QueryError {
             code: e.code(),
             message: e.message(),
@@ -87,7 +94,11 @@ pub struct QueryResponseField {
 }
 
 impl QueryResponseField {
-    fn from_schema(schema: DataSchemaRef) -> Vec<Self> {
+    pub(crate) fn new(name: String, r#type: String) -> Self {
+        Self { name, r#type }
+    }
+
+    pub(crate) fn from_schema(schema: DataSchemaRef) -> Vec<Self> {
         schema
             .fields()
             .iter()
@@ -123,14 +134,15 @@ impl QueryResponse {
         id: String,
         r: HttpQueryResponseInternal,
         is_final: bool,
-    ) -> impl IntoResponse {
+    ) -> Response {
         let state = r.state.clone();
+        let empty = || PageData::Json(JsonBlock::empty());
         let (data, next_uri) = if is_final {
-            (JsonBlock::empty(), None)
+            (empty(), None)
         } else {
             match state.state {
                 ExecuteStateKind::Running => match r.data {
-                    None => (JsonBlock::empty(), Some(make_state_uri(&id))),
+                    None => (empty(), Some(make_state_uri(&id))),
                     Some(d) => {
                         let uri = match d.next_page_no {
                             Some(n) => Some(make_page_uri(&id, n)),
@@ -139,9 +151,9 @@ impl QueryResponse {
                         (d.page.data, uri)
                     }
                 },
-                ExecuteStateKind::Failed => (JsonBlock::empty(), Some(make_final_uri(&id))),
+                ExecuteStateKind::Failed => (empty(), Some(make_final_uri(&id))),
                 ExecuteStateKind::Succeeded => match r.data {
-                    None => (JsonBlock::empty(), Some(make_final_uri(&id))),
+                    None => (empty(), Some(make_final_uri(&id))),
                     Some(d) => {
                         let uri = match d.next_page_no {
                             Some(n) => Some(make_page_uri(&id, n)),
@@ -153,13 +165,30 @@ impl QueryResponse {
             }
         };
 
-        let schema = data.schema().clone();
         let session_id = r.session_id.clone();
+        let rows = data.num_rows();
+        let data = match data {
+            PageData::Json(data) => data,
+            // the binary pages are the bodies, the states of the query are in the headers.
+            PageData::Binary { body, .. } => {
+                let mut builder = Response::builder()
+                    .content_type(r.result_format.content_type())
+                    .header(HEADER_QUERY_ID, id.clone())
+                    .header(HEADER_QUERY_STATE, state.state.to_string())
+                    .header(HEADER_QUERY_PAGE_ROWS, rows)
+                    .header(HEADER_QUERY_FINAL_URI, make_final_uri(&id))
+                    .header(HEADER_SESSION_ID, session_id);
+                if let Some(next_uri) = next_uri {
+                    builder = builder.header(HEADER_QUERY_NEXT_URI, next_uri);
+                }
+                return builder.body(body);
+            }
+        };
+        let schema = data.schema().clone();
         let stats = QueryStats {
             progresses: state.progresses.clone(),
             running_time_ms: state.running_time_ms,
         };
-        let rows = data.data.len();
         Json(QueryResponse {
             data: data.into(),
             state: state.state,
@@ -178,6 +207,7 @@ impl QueryResponse {
         .with_header(HEADER_QUERY_ID, id.clone())
         .with_header(HEADER_QUERY_STATE, state.state.to_string())
         .with_header(HEADER_QUERY_PAGE_ROWS, rows)
+        .into_response()
     }
 
     pub(crate) fn fail_to_start_sql(err: &ErrorCode) -> impl IntoResponse {
@@ -278,8 +308,23 @@ pub(crate) async fn query_handler(
     Json(req): Json<HttpQueryRequest>,
 ) -> PoemResult<impl IntoResponse> {
     info!("receive http query: {:?}", req);
-    let http_query_manager = HttpQueryManager::instance();
     let sql = req.sql.clone();
+    if req.stream {
+        return match start_stream_query(ctx, req).await {
+            Ok(query) => Ok(Response::builder()
+                .content_type(CONTENT_TYPE_JSON_LINES)
+                .header(HEADER_QUERY_ID, query.id)
+                .header(HEADER_SESSION_ID, query.session_id)
+                .body(query.body)),
+            Err(e) => {
+                let e = e.display_with_sql(&sql);
+                error!("Fail to start streaming sql, Error: {:?}", e);
+                Ok(QueryResponse::fail_to_start_sql(&e).into_response())
+            }
+        };
+    }
+
+    let http_query_manager = HttpQueryManager::instance();
 
     let query = http_query_manager
         .try_create_query(ctx, req)
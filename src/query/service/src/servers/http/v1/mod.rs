@@ -12,12 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod async_query;
 mod http_query_handlers;
 pub mod json_block;
 mod load;
 mod query;
 mod stage;
 
+pub use async_query::async_query_route;
+pub use async_query::make_async_result_page_uri;
+pub use async_query::make_async_result_uri;
+pub use async_query::make_async_state_uri;
+pub use async_query::start_async_query_result_sweeper;
+pub use async_query::AsyncQueryResponse;
 pub use http_query_handlers::make_final_uri;
 pub use http_query_handlers::make_page_uri;
 pub use http_query_handlers::make_state_uri;
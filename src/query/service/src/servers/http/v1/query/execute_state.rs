@@ -25,18 +25,22 @@ use common_exception::Result;
 use common_expression::DataBlock;
 use common_expression::DataSchemaRef;
 use common_sql::Planner;
+use common_users::UserApiProvider;
 use futures::StreamExt;
 use futures_util::FutureExt;
 use serde::Deserialize;
 use serde::Serialize;
 use tracing::error;
 use tracing::info;
+use tracing::warn;
 use ExecuteState::*;
 
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterFactory;
 use crate::interpreters::InterpreterQueryLog;
+use crate::pipelines::processors::transforms::BlockSpiller;
 use crate::servers::http::v1::query::sized_spsc::SizedChannelSender;
+use crate::servers::http::v1::query::ResultBlock;
 use crate::sessions::QueryAffect;
 use crate::sessions::QueryContext;
 use crate::sessions::Session;
@@ -63,7 +67,7 @@ pub struct Progresses {
 }
 
 impl Progresses {
-    fn from_context(ctx: &Arc<QueryContext>) -> Self {
+    pub(crate) fn from_context(ctx: &Arc<QueryContext>) -> Self {
         Progresses {
             scan_progress: ctx.get_scan_progress_value(),
             write_progress: ctx.get_write_progress_value(),
@@ -112,6 +116,26 @@ pub struct Executor {
     pub query_id: String,
     pub start_time: Instant,
     pub state: ExecuteState,
+    pub session_keeper: Option<Arc<SessionStateKeeper>>,
+}
+
+/// Keeps the state of the session by the token of the client, e.g. the changes of `USE db`,
+/// `SET` and `SET ROLE`.
+pub struct SessionStateKeeper {
+    pub session: Arc<Session>,
+    pub token: String,
+    pub ttl: Duration,
+}
+
+impl SessionStateKeeper {
+    async fn keep(&self) -> Result<()> {
+        let state = self.session.export_state()?;
+        UserApiProvider::instance()
+            .get_session_api_client(&self.session.get_current_tenant())?
+            .upsert_session(&self.token, state, self.ttl)
+            .await?;
+        Ok(())
+    }
 }
 
 impl Executor {
@@ -145,7 +169,28 @@ impl Executor {
         }
     }
 
+    // The session state is kept before the query is stopped, so that the next query of
+    // the client sees it once this one is seen finished.
+    async fn keep_session_state(this: &Arc<RwLock<Executor>>) {
+        let (query_id, keeper) = {
+            let guard = this.read().await;
+            match &guard.state {
+                Stopped(_) => return,
+                _ => (guard.query_id.clone(), guard.session_keeper.clone()),
+            }
+        };
+        if let Some(keeper) = keeper {
+            if let Err(e) = keeper.keep().await {
+                warn!(
+                    "http query {}, fail to keep session state: {:?}",
+                    query_id, e
+                );
+            }
+        }
+    }
+
     pub async fn start_to_stop(this: &Arc<RwLock<Executor>>, state: ExecuteState) {
+        Self::keep_session_state(this).await;
         let mut guard = this.write().await;
         if let Starting(_) = &guard.state {
             guard.state = state
@@ -160,6 +205,7 @@ impl Executor {
             );
         }
 
+        Self::keep_session_state(this).await;
         let mut guard = this.write().await;
         match &guard.state {
             Starting(s) => {
@@ -215,7 +261,8 @@ impl ExecuteState {
         sql: &str,
         session: Arc<Session>,
         ctx: Arc<QueryContext>,
-        block_sender: SizedChannelSender<DataBlock>,
+        block_sender: SizedChannelSender<ResultBlock>,
+        spiller: Arc<BlockSpiller>,
     ) -> Result<()> {
         let mut planner = Planner::new(ctx.clone());
         let (plan, _, _) = planner.plan_sql(sql).await?;
@@ -233,7 +280,13 @@ impl ExecuteState {
         let ctx_clone = ctx.clone();
         let block_sender_closer = block_sender.closer();
 
-        let res = execute(interpreter, ctx_clone, block_sender, executor_clone.clone());
+        let res = execute(
+            interpreter,
+            ctx_clone,
+            block_sender,
+            spiller,
+            executor_clone.clone(),
+        );
         match AssertUnwindSafe(res).catch_unwind().await {
             Ok(Err(err)) => {
                 Executor::stop(&executor_clone, Err(err), false).await;
@@ -257,7 +310,8 @@ impl ExecuteState {
 async fn execute(
     interpreter: Arc<dyn Interpreter>,
     ctx: Arc<QueryContext>,
-    block_sender: SizedChannelSender<DataBlock>,
+    block_sender: SizedChannelSender<ResultBlock>,
+    spiller: Arc<BlockSpiller>,
     executor: Arc<RwLock<Executor>>,
 ) -> Result<()> {
     let mut data_stream = interpreter.execute(ctx.clone()).await?;
@@ -265,7 +319,7 @@ async fn execute(
     match data_stream.next().await {
         None => {
             let block = DataBlock::empty_with_schema(interpreter.schema());
-            block_sender.send(block, 0).await;
+            block_sender.send(ResultBlock::Memory(block), 0).await;
             Executor::stop(&executor, Ok(()), false).await;
             block_sender.close();
         }
@@ -274,12 +328,11 @@ async fn execute(
             block_sender.close();
         }
         Some(Ok(block)) => {
-            let size = block.num_rows();
-            block_sender.send(block, size).await;
+            send_block(&block_sender, &spiller, block).await?;
             while let Some(block_r) = data_stream.next().await {
                 match block_r {
                     Ok(block) => {
-                        block_sender.send(block.clone(), block.num_rows()).await;
+                        send_block(&block_sender, &spiller, block).await?;
                     }
                     Err(err) => {
                         block_sender.close();
@@ -293,3 +346,20 @@ async fn execute(
     }
     Ok(())
 }
+
+// The buffer is full when the client reads slower than the query produces, spill the block
+// to the storage and send its location, which takes no room of the buffer.
+async fn send_block(
+    block_sender: &SizedChannelSender<ResultBlock>,
+    spiller: &BlockSpiller,
+    block: DataBlock,
+) -> Result<()> {
+    let num_rows = block.num_rows();
+    if let Some(ResultBlock::Memory(block)) =
+        block_sender.try_send(ResultBlock::Memory(block), num_rows)
+    {
+        let location = spiller.spill(&block).await?;
+        block_sender.send(ResultBlock::Spilled(location), 0).await;
+    }
+    Ok(())
+}
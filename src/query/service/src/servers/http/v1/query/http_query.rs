@@ -21,17 +21,31 @@ use common_base::base::tokio;
 use common_base::base::tokio::sync::Mutex as TokioMutex;
 use common_base::base::tokio::sync::RwLock;
 use common_base::runtime::TrySpawn;
+use common_catalog::table_context::QueryParam;
+use common_catalog::table_context::QueryParams;
 use common_catalog::table_context::StageAttachment;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_expression::infer_table_schema;
+use common_expression::DataSchemaRef;
+use common_formats::output_format::ArrowIpcOutputFormat;
+use common_formats::output_format::OutputFormat;
+use common_formats::FileFormatOptionsExt;
+use common_meta_app::principal::FileFormatOptions;
+use common_meta_app::principal::StageFileFormatType;
+use common_settings::Settings;
+use common_users::UserApiProvider;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value as JsonValue;
 
 use super::HttpQueryContext;
 use crate::interpreters::InterpreterQueryLog;
+use crate::pipelines::processors::transforms::BlockSpiller;
 use crate::servers::http::v1::query::execute_state::ExecuteStarting;
 use crate::servers::http::v1::query::execute_state::ExecuteStopped;
 use crate::servers::http::v1::query::execute_state::Progresses;
+use crate::servers::http::v1::query::execute_state::SessionStateKeeper;
 use crate::servers::http::v1::query::expirable::Expirable;
 use crate::servers::http::v1::query::expirable::ExpiringState;
 use crate::servers::http::v1::query::http_query_manager::HttpQueryConfig;
@@ -44,6 +58,7 @@ use crate::servers::http::v1::query::ResponseData;
 use crate::servers::http::v1::query::Wait;
 use crate::servers::http::v1::HttpQueryManager;
 use crate::sessions::QueryAffect;
+use crate::sessions::Session;
 use crate::sessions::SessionType;
 use crate::sessions::TableContext;
 
@@ -61,6 +76,68 @@ pub struct HttpQueryRequest {
     #[serde(default = "default_as_true")]
     pub string_fields: bool,
     pub stage_attachment: Option<StageAttachmentConf>,
+    #[serde(default)]
+    pub result_format: ResultFormat,
+    pub params: Option<HttpQueryParams>,
+    /// send all the rows in one chunked response instead of the pages
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// The parameters bound to the placeholders of the `sql`, an array for `?` and `$<n>`,
+/// or an object for `:<name>`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum HttpQueryParams {
+    Positional(Vec<HttpQueryParam>),
+    Named(BTreeMap<String, HttpQueryParam>),
+}
+
+/// A json value, or a json value with its SQL type like `{"value": "2023-01-01", "type": "DATE"}`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum HttpQueryParam {
+    Typed(TypedParam),
+    Value(JsonValue),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TypedParam {
+    value: JsonValue,
+    #[serde(rename = "type")]
+    data_type: String,
+}
+
+impl From<HttpQueryParam> for QueryParam {
+    fn from(param: HttpQueryParam) -> Self {
+        match param {
+            HttpQueryParam::Typed(param) => QueryParam {
+                value: param.value,
+                data_type: Some(param.data_type),
+            },
+            HttpQueryParam::Value(value) => QueryParam {
+                value,
+                data_type: None,
+            },
+        }
+    }
+}
+
+impl From<HttpQueryParams> for QueryParams {
+    fn from(params: HttpQueryParams) -> Self {
+        match params {
+            HttpQueryParams::Positional(params) => {
+                QueryParams::Positional(params.into_iter().map(QueryParam::from).collect())
+            }
+            HttpQueryParams::Named(params) => QueryParams::Named(
+                params
+                    .into_iter()
+                    .map(|(name, param)| (name, QueryParam::from(param)))
+                    .collect(),
+            ),
+        }
+    }
 }
 
 const DEFAULT_MAX_ROWS_IN_BUFFER: usize = 5 * 1000 * 1000;
@@ -110,6 +187,52 @@ impl PaginationConf {
     }
 }
 
+/// The format of the rows in the pages, the binary formats are sent as the bodies of the
+/// responses, with the states of the query in the headers.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultFormat {
+    #[default]
+    Json,
+    ArrowIpc,
+    Parquet,
+}
+
+impl ResultFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResultFormat::Json => "application/json",
+            ResultFormat::ArrowIpc => "application/vnd.apache.arrow.stream",
+            ResultFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    fn create_output_format(
+        &self,
+        schema: &DataSchemaRef,
+        settings: &Settings,
+    ) -> Result<Option<Box<dyn OutputFormat>>> {
+        let output_format: Box<dyn OutputFormat> = match self {
+            ResultFormat::Json => return Ok(None),
+            ResultFormat::ArrowIpc => {
+                Box::new(ArrowIpcOutputFormat::create(infer_table_schema(schema)?))
+            }
+            ResultFormat::Parquet => {
+                let options = FileFormatOptions {
+                    format: StageFileFormatType::Parquet,
+                    ..Default::default()
+                };
+                FileFormatOptionsExt::get_output_format_from_format_options(
+                    infer_table_schema(schema)?,
+                    options,
+                    settings,
+                )?
+            }
+        };
+        Ok(Some(output_format))
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Default, PartialEq, Eq, Clone)]
 pub struct HttpSessionConf {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -118,6 +241,9 @@ pub struct HttpSessionConf {
     pub keep_server_session_secs: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub settings: Option<BTreeMap<String, String>>,
+    /// token of the session state kept in the meta service, any node can resume the session by it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
 }
 
 impl HttpSessionConf {
@@ -163,6 +289,7 @@ pub struct ResponseState {
 
 pub struct HttpQueryResponseInternal {
     pub data: Option<ResponseData>,
+    pub result_format: ResultFormat,
     pub session_id: String,
     pub session: Option<HttpSessionConf>,
     pub state: ResponseState,
@@ -183,6 +310,7 @@ pub enum ExpireResult {
 pub struct HttpQuery {
     pub(crate) id: String,
     pub(crate) session_id: String,
+    session_token: Option<String>,
     request: HttpQueryRequest,
     state: Arc<RwLock<Executor>>,
     page_manager: Arc<TokioMutex<PageManager>>,
@@ -198,34 +326,61 @@ impl HttpQuery {
     ) -> Result<Arc<HttpQuery>> {
         let http_query_manager = HttpQueryManager::instance();
 
-        let session = if let Some(id) = &request.session_id {
-            let session = http_query_manager.get_session(id).await.ok_or_else(|| {
-                ErrorCode::UnknownSession(format!("unknown session-id {}, maybe expired", id))
-            })?;
-            let mut n = 1;
-            while let ExpiringState::InUse(query_id) = session.expire_state() {
-                if let Some(last_query) = &http_query_manager.get_query(&query_id).await {
-                    if last_query.get_state().await.state == ExecuteStateKind::Running {
-                        return Err(ErrorCode::BadArguments(
-                            "last query on the session not finished",
-                        ));
-                    } else {
-                        http_query_manager.remove_query(&query_id).await;
+        let token = request.session.as_ref().and_then(|conf| conf.token.clone());
+        let local_session = match &request.session_id {
+            Some(id) => http_query_manager.get_session(id).await,
+            None => None,
+        };
+        let is_new_session = local_session.is_none();
+        let session = match (local_session, &request.session_id, &token) {
+            (Some(session), ..) => {
+                let mut n = 1;
+                while let ExpiringState::InUse(query_id) = session.expire_state() {
+                    if let Some(last_query) = &http_query_manager.get_query(&query_id).await {
+                        if last_query.get_state().await.state == ExecuteStateKind::Running {
+                            return Err(ErrorCode::BadArguments(
+                                "last query on the session not finished",
+                            ));
+                        } else {
+                            http_query_manager.remove_query(&query_id).await;
+                        }
+                    }
+                    // wait for Arc<QueryContextShared> to drop and detach itself from session
+                    // should not take too long
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                    n += 1;
+                    if n > 10 {
+                        return Err(ErrorCode::Internal("last query stop but not released"));
                     }
                 }
-                // wait for Arc<QueryContextShared> to drop and detach itself from session
-                // should not take too long
-                tokio::time::sleep(Duration::from_millis(1)).await;
-                n += 1;
-                if n > 10 {
-                    return Err(ErrorCode::Internal("last query stop but not released"));
-                }
+                session
             }
-            session
-        } else {
-            ctx.get_session(SessionType::HTTPQuery)
+            // the session may be created on another node, resume it from the kept state
+            (None, _, Some(token)) => {
+                let session = ctx.get_session(SessionType::HTTPQuery);
+                let state = UserApiProvider::instance()
+                    .get_session_api_client(&session.get_current_tenant())?
+                    .get_session(token)
+                    .await?
+                    .ok_or_else(|| {
+                        ErrorCode::UnknownSession(format!(
+                            "unknown session token {}, maybe expired",
+                            token
+                        ))
+                    })?;
+                session.restore_state(&state).await?;
+                session
+            }
+            (None, Some(id), None) => {
+                return Err(ErrorCode::UnknownSession(format!(
+                    "unknown session-id {}, maybe expired",
+                    id
+                )));
+            }
+            (None, None, None) => ctx.get_session(SessionType::HTTPQuery),
         };
 
+        let mut session_token = None;
         if let Some(session_conf) = &request.session {
             if let Some(db) = &session_conf.database {
                 session.set_current_database(db.clone());
@@ -237,10 +392,14 @@ impl HttpQuery {
                 }
             }
             if let Some(secs) = session_conf.keep_server_session_secs {
-                if secs > 0 && request.session_id.is_none() {
-                    http_query_manager
-                        .add_session(session.clone(), Duration::from_secs(secs))
-                        .await;
+                if secs > 0 {
+                    if is_new_session {
+                        http_query_manager
+                            .add_session(session.clone(), Duration::from_secs(secs))
+                            .await;
+                    }
+                    let token = token.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+                    session_token = Some((token, Duration::from_secs(secs)));
                 }
             }
         };
@@ -262,12 +421,25 @@ impl HttpQuery {
             None => {}
         };
 
+        if let Some(params) = &request.params {
+            ctx.attach_query_params(params.clone().into());
+        }
+
         let (block_sender, block_receiver) = sized_spsc(request.pagination.max_rows_in_buffer);
+        let spiller = Arc::new(BlockSpiller::try_create(ctx.clone())?);
+        let spiller_clone = spiller.clone();
         let start_time = Instant::now();
         let state = Arc::new(RwLock::new(Executor {
             query_id: id.clone(),
             start_time,
             state: ExecuteState::Starting(ExecuteStarting { ctx: ctx.clone() }),
+            session_keeper: session_token.clone().map(|(token, ttl)| {
+                Arc::new(SessionStateKeeper {
+                    session: session.clone(),
+                    token,
+                    ttl,
+                })
+            }),
         }));
         let block_sender_closer = block_sender.closer();
         let state_clone = state.clone();
@@ -280,9 +452,15 @@ impl HttpQuery {
         let schema = ExecuteState::get_schema(&sql, ctx.clone()).await?;
         ctx.try_spawn(async move {
             let state = state_clone.clone();
-            if let Err(e) =
-                ExecuteState::try_start_query(state, &sql, session, ctx_clone.clone(), block_sender)
-                    .await
+            if let Err(e) = ExecuteState::try_start_query(
+                state,
+                &sql,
+                session,
+                ctx_clone.clone(),
+                block_sender,
+                spiller_clone,
+            )
+            .await
             {
                 InterpreterQueryLog::fail_to_start(ctx_clone.clone(), e.clone());
                 let state = ExecuteStopped {
@@ -302,17 +480,23 @@ impl HttpQuery {
         })?;
 
         let format_settings = ctx.get_format_settings()?;
+        let output_format = request
+            .result_format
+            .create_output_format(&schema, &ctx.get_settings())?;
         let data = Arc::new(TokioMutex::new(PageManager::new(
             query_id_clone,
             request.pagination.max_rows_per_page,
             block_receiver,
+            spiller,
             schema,
             format_settings,
+            output_format,
             ctx_clone2,
         )));
         let query = HttpQuery {
             id,
             session_id,
+            session_token: session_token.map(|(token, _)| token),
             request,
             state,
             page_manager: data,
@@ -326,16 +510,18 @@ impl HttpQuery {
         let data = Some(self.get_page(page_no).await?);
         let state = self.get_state().await;
         let session_conf = self.request.session.clone().unwrap_or_default();
-        let session_conf = if let Some(affect) = &state.affect {
-            Some(session_conf.apply_affect(affect))
+        let mut session_conf = if let Some(affect) = &state.affect {
+            session_conf.apply_affect(affect)
         } else {
-            Some(session_conf)
+            session_conf
         };
+        session_conf.token = self.session_token.clone();
 
         Ok(HttpQueryResponseInternal {
             data,
+            result_format: self.request.result_format,
             state,
-            session: session_conf,
+            session: Some(session_conf),
             session_id: self.session_id.clone(),
         })
     }
@@ -343,6 +529,7 @@ impl HttpQuery {
     pub async fn get_response_state_only(&self) -> HttpQueryResponseInternal {
         HttpQueryResponseInternal {
             data: None,
+            result_format: self.request.result_format,
             session_id: self.session_id.clone(),
             state: self.get_state().await,
             session: None,
@@ -0,0 +1,219 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_stream::stream;
+use common_base::runtime::TrySpawn;
+use common_catalog::table_context::StageAttachment;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::DataBlock;
+use common_io::prelude::FormatSettings;
+use common_sql::Planner;
+use futures::StreamExt;
+use poem::Body;
+use serde::Serialize;
+
+use crate::interpreters::InterpreterFactory;
+use crate::interpreters::InterpreterPtr;
+use crate::interpreters::InterpreterQueryLog;
+use crate::servers::http::v1::http_query_handlers::QueryError;
+use crate::servers::http::v1::http_query_handlers::QueryResponseField;
+use crate::servers::http::v1::json_block::block_to_json_value;
+use crate::servers::http::v1::query::ExecuteStateKind;
+use crate::servers::http::v1::query::HttpQueryRequest;
+use crate::servers::http::v1::query::Progresses;
+use crate::servers::http::v1::query::ResultFormat;
+use crate::servers::http::v1::HttpQueryContext;
+use crate::servers::http::v1::QueryStats;
+use crate::sessions::QueryContext;
+use crate::sessions::Session;
+use crate::sessions::SessionType;
+use crate::sessions::TableContext;
+
+/// The first line of the streaming response.
+#[derive(Serialize)]
+struct StreamHead {
+    id: String,
+    session_id: String,
+    schema: Vec<QueryResponseField>,
+}
+
+/// The last line of the streaming response.
+#[derive(Serialize)]
+struct StreamTail {
+    state: ExecuteStateKind,
+    error: Option<QueryError>,
+    stats: QueryStats,
+}
+
+pub struct StreamQuery {
+    pub id: String,
+    pub session_id: String,
+    pub body: Body,
+}
+
+/// Run the query and send the result in the body of one chunked response.
+///
+/// The body is in JSON lines: the schema, one array for each row, and the state of the
+/// query in the end. The blocks are pulled from the pipeline only when the client has read
+/// the previous ones, so a slow client slows down the query instead of the rows piling up.
+pub async fn start_stream_query(
+    ctx: &HttpQueryContext,
+    request: HttpQueryRequest,
+) -> Result<StreamQuery> {
+    if request.result_format != ResultFormat::Json {
+        return Err(ErrorCode::BadArguments(
+            "only the json result format can be streamed",
+        ));
+    }
+    let (session, ctx, interpreter) = plan_in_new_session(ctx, &request, "streaming").await?;
+    let session_id = session.get_id();
+    let id = ctx.get_id();
+    let schema = interpreter.schema();
+
+    // execute the interpreter in the runtime of the query, see `clickhouse_handler::execute`.
+    let mut data_stream = ctx
+        .try_spawn({
+            let ctx = ctx.clone();
+            async move { interpreter.execute(ctx).await }
+        })?
+        .await
+        .map_err(|err| {
+            ErrorCode::from_string(format!(
+                "http query failed to join interpreter thread: {err:?}"
+            ))
+        })??;
+
+    let format_settings = ctx.get_format_settings()?;
+    let head = StreamHead {
+        id: id.clone(),
+        session_id: session_id.clone(),
+        schema: QueryResponseField::from_schema(schema),
+    };
+    let start_time = Instant::now();
+    let stream = stream! {
+        yield json_line(&head);
+        let mut error = None;
+        while let Some(block) = data_stream.next().await {
+            match block.and_then(|block| encode_rows(&block, &format_settings)) {
+                Ok(rows) => yield Ok(rows),
+                Err(err) => {
+                    error = Some(err);
+                    break;
+                }
+            }
+        }
+        let tail = StreamTail {
+            state: if error.is_none() {
+                ExecuteStateKind::Succeeded
+            } else {
+                ExecuteStateKind::Failed
+            },
+            error: error.as_ref().map(QueryError::from_error_code),
+            stats: QueryStats {
+                progresses: Progresses::from_context(&ctx),
+                running_time_ms: start_time.elapsed().as_secs_f64() * 1000.0,
+            },
+        };
+        yield json_line(&tail);
+        // to hold session ref until stream is all consumed
+        let _ = session.get_id();
+    };
+
+    Ok(StreamQuery {
+        id,
+        session_id,
+        body: Body::from_bytes_stream(stream),
+    })
+}
+
+/// Plan the query in a one-off session, for the queries that outlive or bypass the pages
+/// of `HttpQuery`, only the database and the settings of the `session` are applied.
+pub(crate) async fn plan_in_new_session(
+    ctx: &HttpQueryContext,
+    request: &HttpQueryRequest,
+    kind: &str,
+) -> Result<(Arc<Session>, Arc<QueryContext>, InterpreterPtr)> {
+    let keep_session = request.session.as_ref().map_or(false, |conf| {
+        conf.token.is_some() || conf.keep_server_session_secs.unwrap_or(0) > 0
+    });
+    if request.session_id.is_some() || keep_session {
+        return Err(ErrorCode::BadArguments(format!(
+            "the {kind} query can not run in a server side session"
+        )));
+    }
+
+    let session = ctx.get_session(SessionType::HTTPQuery);
+    if let Some(session_conf) = &request.session {
+        if let Some(db) = &session_conf.database {
+            session.set_current_database(db.clone());
+        }
+        if let Some(conf_settings) = &session_conf.settings {
+            let settings = session.get_settings();
+            for (k, v) in conf_settings {
+                settings.set_settings(k.to_string(), v.to_string(), false)?;
+            }
+        }
+    }
+
+    let ctx = session.create_query_context().await?;
+    let sql = &request.sql;
+    tracing::info!(
+        "run {kind} query_id={} in session_id={}, sql='{sql}'",
+        ctx.get_id(),
+        session.get_id()
+    );
+
+    if let Some(attachment) = &request.stage_attachment {
+        ctx.attach_stage(StageAttachment {
+            location: attachment.location.clone(),
+            file_format_options: attachment.file_format_options.clone(),
+            copy_options: attachment.copy_options.clone(),
+            values_str: "".to_string(),
+        });
+    }
+    if let Some(params) = &request.params {
+        ctx.attach_query_params(params.clone().into());
+    }
+
+    let mut planner = Planner::new(ctx.clone());
+    let plan = match planner.plan_sql(sql).await {
+        Ok((plan, _, _)) => plan,
+        Err(e) => {
+            InterpreterQueryLog::fail_to_start(ctx.clone(), e.clone());
+            return Err(e);
+        }
+    };
+    ctx.attach_query_str(plan.to_string(), sql);
+    let interpreter = InterpreterFactory::get(ctx.clone(), &plan).await?;
+    Ok((session, ctx, interpreter))
+}
+
+fn encode_rows(block: &DataBlock, format: &FormatSettings) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    for row in block_to_json_value(block, format)? {
+        buf.extend(json_line(&row)?);
+    }
+    Ok(buf)
+}
+
+fn json_line<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut buf = serde_json::to_vec(value)
+        .map_err(|e| ErrorCode::Internal(format!("fail to encode the json line: {e}")))?;
+    buf.push(b'\n');
+    Ok(buf)
+}
@@ -18,6 +18,7 @@ mod expiring_map;
 mod http_query;
 mod http_query_context;
 mod http_query_manager;
+mod http_query_stream;
 mod page_manager;
 pub mod sized_spsc;
 
@@ -26,14 +27,22 @@ pub use execute_state::ExecuteStateKind;
 pub(crate) use execute_state::Executor;
 pub use execute_state::Progresses;
 pub use http_query::HttpQuery;
+pub use http_query::HttpQueryParam;
+pub use http_query::HttpQueryParams;
 pub use http_query::HttpQueryRequest;
 pub use http_query::HttpQueryResponseInternal;
 pub use http_query::HttpSessionConf;
 pub use http_query::PaginationConf;
 pub use http_query::ResponseState;
+pub use http_query::ResultFormat;
 pub use http_query_context::HttpQueryContext;
 pub use http_query_manager::HttpQueryManager;
+pub(crate) use http_query_stream::plan_in_new_session;
+pub use http_query_stream::start_stream_query;
+pub use http_query_stream::StreamQuery;
 pub use page_manager::Page;
+pub use page_manager::PageData;
 pub use page_manager::PageManager;
 pub use page_manager::ResponseData;
+pub use page_manager::ResultBlock;
 pub use page_manager::Wait;
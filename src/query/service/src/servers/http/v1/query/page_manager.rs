@@ -12,7 +12,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -23,24 +22,48 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_expression::DataBlock;
 use common_expression::DataSchemaRef;
+use common_formats::output_format::OutputFormat;
 use common_io::prelude::FormatSettings;
-use serde_json::Value as JsonValue;
 use tracing::info;
 
+use crate::pipelines::processors::transforms::BlockSpiller;
 use crate::servers::http::v1::json_block::block_to_json_value;
 use crate::servers::http::v1::query::sized_spsc::SizedChannelReceiver;
 use crate::servers::http::v1::JsonBlock;
 use crate::sessions::QueryContext;
 
+/// The blocks of the result sent to the `PageManager`, the blocks are spilled to the
+/// storage instead of waiting in the memory when the client reads slowly.
+pub enum ResultBlock {
+    Memory(DataBlock),
+    Spilled(String),
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Wait {
     Async,
     Deadline(Instant),
 }
 
+/// The rows of a page, in json or in the binary format asked by the client.
+#[derive(Clone)]
+pub enum PageData {
+    Json(JsonBlock),
+    Binary { body: Vec<u8>, num_rows: usize },
+}
+
+impl PageData {
+    pub fn num_rows(&self) -> usize {
+        match self {
+            PageData::Json(block) => block.num_rows(),
+            PageData::Binary { num_rows, .. } => *num_rows,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Page {
-    pub data: JsonBlock,
+    pub data: PageData,
     pub total_rows: usize,
 }
 
@@ -58,9 +81,12 @@ pub struct PageManager {
     block_end: bool,
     schema: DataSchemaRef,
     last_page: Option<Page>,
-    row_buffer: VecDeque<Vec<JsonValue>>,
-    block_receiver: SizedChannelReceiver<DataBlock>,
+    block_buffer: Option<DataBlock>,
+    block_receiver: SizedChannelReceiver<ResultBlock>,
+    spiller: Arc<BlockSpiller>,
     format_settings: FormatSettings,
+    // encode the pages in the binary format, the pages are in json if it's None.
+    output_format: Option<Box<dyn OutputFormat>>,
     query_ctx_ref: Option<Arc<QueryContext>>,
 }
 
@@ -68,9 +94,11 @@ impl PageManager {
     pub fn new(
         query_id: String,
         max_rows_per_page: usize,
-        block_receiver: SizedChannelReceiver<DataBlock>,
+        block_receiver: SizedChannelReceiver<ResultBlock>,
+        spiller: Arc<BlockSpiller>,
         schema: DataSchemaRef,
         format_settings: FormatSettings,
+        output_format: Option<Box<dyn OutputFormat>>,
         query_ctx_ref: Arc<QueryContext>,
     ) -> PageManager {
         PageManager {
@@ -80,11 +108,13 @@ impl PageManager {
             total_pages: 0,
             end: false,
             block_end: false,
-            row_buffer: Default::default(),
+            block_buffer: None,
             schema,
             block_receiver,
+            spiller,
             max_rows_per_page,
             format_settings,
+            output_format,
             query_ctx_ref: Some(query_ctx_ref),
         }
     }
@@ -100,11 +130,11 @@ impl PageManager {
     pub async fn get_a_page(&mut self, page_no: usize, tp: &Wait) -> Result<Page> {
         let next_no = self.total_pages;
         if page_no == next_no && !self.end {
-            let (block, end) = self.collect_new_page(tp).await?;
-            let num_row = block.num_rows();
+            let (data, end) = self.collect_new_page(tp).await?;
+            let num_row = data.num_rows();
             self.total_rows += num_row;
             let page = Page {
-                data: block,
+                data,
                 total_rows: self.total_rows,
             };
             if num_row > 0 {
@@ -127,40 +157,81 @@ impl PageManager {
         }
     }
 
+    // keep the rows beyond `remain` for the next page, returns the number of rows appended.
     fn append_block(
         &mut self,
-        rows: &mut Vec<Vec<JsonValue>>,
+        blocks: &mut Vec<DataBlock>,
         block: DataBlock,
         remain: usize,
-    ) -> Result<()> {
-        let format_settings = &self.format_settings;
-        let mut iter = block_to_json_value(&block, format_settings)?
-            .into_iter()
-            .peekable();
-        let chunk: Vec<_> = iter.by_ref().take(remain).collect();
-        rows.extend(chunk);
-        self.row_buffer = iter.by_ref().collect();
-        Ok(())
+    ) -> usize {
+        let num_rows = block.num_rows();
+        if num_rows <= remain {
+            blocks.push(block);
+            num_rows
+        } else {
+            blocks.push(block.slice(0..remain));
+            self.block_buffer = Some(block.slice(remain..num_rows));
+            remain
+        }
     }
 
-    async fn collect_new_page(&mut self, tp: &Wait) -> Result<(JsonBlock, bool)> {
-        let mut res: Vec<Vec<JsonValue>> = Vec::with_capacity(self.max_rows_per_page);
-        while res.len() < self.max_rows_per_page {
-            if let Some(row) = self.row_buffer.pop_front() {
-                res.push(row)
-            } else {
-                break;
+    fn encode_page(&mut self, blocks: Vec<DataBlock>) -> Result<PageData> {
+        match &mut self.output_format {
+            None => {
+                let mut data = vec![];
+                for block in &blocks {
+                    data.extend(block_to_json_value(block, &self.format_settings)?);
+                }
+                Ok(PageData::Json(JsonBlock {
+                    schema: self.schema.clone(),
+                    data,
+                }))
+            }
+            Some(output_format) => {
+                let mut num_rows = 0;
+                for block in &blocks {
+                    num_rows += block.num_rows();
+                    output_format.serialize_block(block)?;
+                }
+                Ok(PageData::Binary {
+                    body: output_format.finalize()?,
+                    num_rows,
+                })
             }
         }
+    }
+
+    async fn load_block(&self, block: ResultBlock) -> Result<DataBlock> {
+        match block {
+            ResultBlock::Memory(block) => Ok(block),
+            ResultBlock::Spilled(location) => {
+                info!(
+                    "http query {} read spilled block from {}",
+                    &self.query_id, &location
+                );
+                self.spiller.read(&location).await
+            }
+        }
+    }
+
+    async fn collect_new_page(&mut self, tp: &Wait) -> Result<(PageData, bool)> {
+        let mut res: Vec<DataBlock> = vec![];
+        let mut num_rows = 0;
+        if let Some(block) = self.block_buffer.take() {
+            num_rows += self.append_block(&mut res, block, self.max_rows_per_page);
+        }
         loop {
-            assert!(self.max_rows_per_page >= res.len());
-            let remain = self.max_rows_per_page - res.len();
+            assert!(self.max_rows_per_page >= num_rows);
+            let remain = self.max_rows_per_page - num_rows;
             if remain == 0 {
                 break;
             }
             match tp {
                 Wait::Async => match self.block_receiver.try_recv() {
-                    Some(block) => self.append_block(&mut res, block, remain)?,
+                    Some(block) => {
+                        let block = self.load_block(block).await?;
+                        num_rows += self.append_block(&mut res, block, remain);
+                    }
                     None => break,
                 },
                 Wait::Deadline(t) => {
@@ -168,12 +239,13 @@ impl PageManager {
                     let d = *t - now;
                     match tokio::time::timeout(d, self.block_receiver.recv()).await {
                         Ok(Some(block)) => {
+                            let block = self.load_block(block).await?;
                             info!(
                                 "http query {} got new block with {} rows",
                                 &self.query_id,
                                 block.num_rows()
                             );
-                            self.append_block(&mut res, block, remain)?;
+                            num_rows += self.append_block(&mut res, block, remain);
                         }
                         Ok(None) => {
                             info!("http query {} reach end of blocks", &self.query_id);
@@ -188,10 +260,7 @@ impl PageManager {
             }
         }
 
-        let block = JsonBlock {
-            schema: self.schema.clone(),
-            data: res,
-        };
+        let data = self.encode_page(res)?;
 
         // try to report 'no more data' earlier to client to avoid unnecessary http call
         if !self.block_end {
@@ -203,8 +272,8 @@ impl PageManager {
                 drop(ctx);
             });
         }
-        let end = self.block_end && self.row_buffer.is_empty();
-        Ok((block, end))
+        let end = self.block_end && self.block_buffer.is_none();
+        Ok((data, end))
     }
 
     pub async fn detach(&self) {
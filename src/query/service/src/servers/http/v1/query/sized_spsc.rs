@@ -114,6 +114,17 @@ impl<T> SizedChannel<T> {
         guard.try_recv()
     }
 
+    pub fn try_send_or_return(&self, value: T, size: usize) -> Option<T> {
+        match self.try_send(value, size) {
+            Ok(Some(v)) => Some(v),
+            Ok(None) => {
+                self.notify_on_sent.notify_one();
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
     pub async fn send(&self, value: T, size: usize) -> bool {
         let mut to_send = value;
         loop {
@@ -199,6 +210,12 @@ impl<T> SizedChannelSender<T> {
         self.chan.send(value, size).await
     }
 
+    /// Send the value without waiting, returns the value back if the channel is full.
+    /// The value is dropped if the receiver is closed.
+    pub fn try_send(&self, value: T, size: usize) -> Option<T> {
+        self.chan.try_send_or_return(value, size)
+    }
+
     pub fn close(&self) {
         self.chan.stop_send()
     }
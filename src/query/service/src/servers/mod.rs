@@ -12,18 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-// The servers module used for external communication with user, such as MySQL wired protocol, etc.
+// The servers module used for external communication with user, such as MySQL, PostgreSQL and ClickHouse wired protocol, Arrow Flight SQL, etc.
 
 pub use server::Server;
 pub use server::ShutdownHandle;
 
+pub use self::clickhouse::ClickHouseConnection;
+pub use self::clickhouse::ClickHouseHandler;
+pub use self::flight_sql::FlightSqlHandler;
 pub use self::http::HttpHandler;
 pub use self::http::HttpHandlerKind;
 pub use self::mysql::MySQLConnection;
 pub use self::mysql::MySQLFederated;
 pub use self::mysql::MySQLHandler;
+pub use self::postgres::PostgresConnection;
+pub use self::postgres::PostgresFederated;
+pub use self::postgres::PostgresHandler;
 
+mod clickhouse;
 pub(crate) mod federated_helper;
+mod flight_sql;
 pub mod http;
 mod mysql;
+mod postgres;
 pub(crate) mod server;
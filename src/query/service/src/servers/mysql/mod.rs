@@ -17,6 +17,7 @@ mod mysql_handler;
 mod mysql_interactive_worker;
 mod mysql_metrics;
 mod mysql_session;
+mod mysql_tls;
 #[allow(clippy::unused_io_amount)]
 mod reject_connection;
 mod writers;
@@ -21,6 +21,7 @@ use common_base::base::tokio::net::TcpStream;
 use common_base::base::tokio::task::JoinHandle;
 use common_base::runtime::Runtime;
 use common_base::runtime::TrySpawn;
+use common_config::GlobalConfig;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use futures::future::AbortHandle;
@@ -36,6 +37,7 @@ use tracing::info;
 use tracing::warn;
 
 use crate::servers::mysql::mysql_session::MySQLConnection;
+use crate::servers::mysql::mysql_tls::MySQLTlsConfig;
 use crate::servers::mysql::reject_connection::RejectConnection;
 use crate::servers::server::ListeningStream;
 use crate::servers::server::Server;
@@ -72,17 +74,23 @@ impl MySQLHandler {
         Ok((TcpListenerStream::new(listener), listener_addr))
     }
 
-    fn listen_loop(&self, stream: ListeningStream, rt: Arc<Runtime>) -> impl Future<Output = ()> {
+    fn listen_loop(
+        &self,
+        stream: ListeningStream,
+        rt: Arc<Runtime>,
+        tls: Option<Arc<MySQLTlsConfig>>,
+    ) -> impl Future<Output = ()> {
         let keepalive = self.keepalive.clone();
         stream.for_each(move |accept_socket| {
             let keepalive = keepalive.clone();
             let executor = rt.clone();
+            let tls = tls.clone();
             let sessions = SessionManager::instance();
             async move {
                 match accept_socket {
                     Err(error) => error!("Broken session connection: {}", error),
                     Ok(socket) => {
-                        MySQLHandler::accept_socket(sessions, executor, socket, keepalive)
+                        MySQLHandler::accept_socket(sessions, executor, socket, keepalive, tls)
                     }
                 };
             }
@@ -94,6 +102,7 @@ impl MySQLHandler {
         executor: Arc<Runtime>,
         socket: TcpStream,
         keepalive: TcpKeepalive,
+        tls: Option<Arc<MySQLTlsConfig>>,
     ) {
         executor.spawn(async move {
             match sessions.create_session(SessionType::MySQL).await {
@@ -108,7 +117,7 @@ impl MySQLHandler {
                         warn!("failed to set socket option keepalive {}", e);
                     }
 
-                    if let Err(error) = MySQLConnection::run_on_stream(session, socket) {
+                    if let Err(error) = MySQLConnection::run_on_stream(session, socket, tls) {
                         error!("Unexpected error occurred during query: {:?}", error);
                     };
                 }
@@ -154,13 +163,14 @@ impl Server for MySQLHandler {
         match self.abort_registration.take() {
             None => Err(ErrorCode::Internal("MySQLHandler already running.")),
             Some(registration) => {
+                let tls = MySQLTlsConfig::try_create(&GlobalConfig::instance())?.map(Arc::new);
                 let rejected_rt = Arc::new(Runtime::with_worker_threads(
                     1,
                     Some("mysql-handler".to_string()),
                 )?);
                 let (stream, listener) = Self::listener_tcp(listening).await?;
                 let stream = Abortable::new(stream, registration);
-                self.join_handle = Some(tokio::spawn(self.listen_loop(stream, rejected_rt)));
+                self.join_handle = Some(tokio::spawn(self.listen_loop(stream, rejected_rt, tls)));
                 Ok(listener)
             }
         }
@@ -16,6 +16,7 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Instant;
 
+use common_ast::ast::Statement;
 use common_base::base::convert_byte_size;
 use common_base::base::convert_number_size;
 use common_base::base::tokio::io::AsyncWrite;
@@ -24,21 +25,30 @@ use common_config::DATABEND_COMMIT_VERSION;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_exception::ToErrorCode;
+use common_expression::types::number::NumberScalar;
 use common_expression::DataBlock;
 use common_expression::DataSchemaRef;
+use common_expression::Scalar;
 use common_expression::SendableDataBlockStream;
-use common_sql::plans::Plan;
+use common_meta_app::principal::AuthInfo;
+use common_meta_app::principal::ClientCert;
 use common_sql::Planner;
+use common_sql::PreparedStatement;
 use common_users::CertifiedInfo;
 use common_users::UserApiProvider;
 use futures_util::StreamExt;
 use metrics::histogram;
 use opensrv_mysql::AsyncMysqlShim;
+use opensrv_mysql::Column;
+use opensrv_mysql::ColumnFlags;
+use opensrv_mysql::ColumnType;
 use opensrv_mysql::ErrorKind;
 use opensrv_mysql::InitWriter;
 use opensrv_mysql::ParamParser;
 use opensrv_mysql::QueryResultWriter;
 use opensrv_mysql::StatementMetaWriter;
+use opensrv_mysql::ValueInner;
+use parking_lot::Mutex;
 use rand::RngCore;
 use tracing::error;
 use tracing::info;
@@ -58,28 +68,70 @@ use crate::sessions::Session;
 use crate::sessions::TableContext;
 use crate::stream::DataBlockStream;
 
-fn has_result_set_by_plan(plan: &Plan) -> bool {
-    matches!(
-        plan,
-        Plan::Query { .. }
-            | Plan::Explain { .. }
-            | Plan::ExplainAst { .. }
-            | Plan::ExplainSyntax { .. }
-            | Plan::ExplainAnalyze { .. }
-            | Plan::Call(_)
-            | Plan::ShowCreateDatabase(_)
-            | Plan::ShowCreateTable(_)
-            | Plan::ShowFileFormats(_)
-            | Plan::ShowRoles(_)
-            | Plan::DescShare(_)
-            | Plan::ShowShares(_)
-            | Plan::ShowObjectGrantPrivileges(_)
-            | Plan::ShowGrantTenantsOfShare(_)
-            | Plan::DescribeTable(_)
-            | Plan::ShowGrants(_)
-            | Plan::ListStage(_)
-            | Plan::Presign(_)
-    )
+/// Decode the parameter of a prepared statement sent in the binary protocol.
+fn param_to_scalar(value: ValueInner<'_>) -> Result<Scalar> {
+    match value {
+        ValueInner::NULL => Ok(Scalar::Null),
+        ValueInner::Int(v) => Ok(Scalar::Number(NumberScalar::Int64(v))),
+        ValueInner::UInt(v) => Ok(Scalar::Number(NumberScalar::UInt64(v))),
+        ValueInner::Double(v) => Ok(Scalar::Number(NumberScalar::Float64(v.into()))),
+        ValueInner::Bytes(v) => Ok(Scalar::String(v.to_vec())),
+        // The date and time are bound as strings, they are cast in the session timezone
+        // the same as the literals in the query.
+        ValueInner::Date(v) | ValueInner::Datetime(v) => {
+            Ok(Scalar::String(decode_datetime(v)?.into_bytes()))
+        }
+        ValueInner::Time(v) => Ok(Scalar::String(decode_time(v)?.into_bytes())),
+    }
+}
+
+/// https://dev.mysql.com/doc/internals/en/binary-protocol-value.html#packet-ProtocolBinary::MYSQL_TYPE_DATETIME
+fn decode_datetime(v: &[u8]) -> Result<String> {
+    match v.len() {
+        0 => Ok("0000-00-00 00:00:00".to_string()),
+        4 | 7 | 11 => {
+            let year = u16::from_le_bytes([v[0], v[1]]);
+            let date = format!("{:04}-{:02}-{:02}", year, v[2], v[3]);
+            match v.len() {
+                4 => Ok(date),
+                7 => Ok(format!("{} {:02}:{:02}:{:02}", date, v[4], v[5], v[6])),
+                _ => {
+                    let micros = u32::from_le_bytes([v[7], v[8], v[9], v[10]]);
+                    Ok(format!(
+                        "{} {:02}:{:02}:{:02}.{:06}",
+                        date, v[4], v[5], v[6], micros
+                    ))
+                }
+            }
+        }
+        len => Err(ErrorCode::BadBytes(format!(
+            "Invalid length {} of the datetime parameter",
+            len
+        ))),
+    }
+}
+
+/// https://dev.mysql.com/doc/internals/en/binary-protocol-value.html#packet-ProtocolBinary::MYSQL_TYPE_TIME
+fn decode_time(v: &[u8]) -> Result<String> {
+    match v.len() {
+        0 => Ok("00:00:00".to_string()),
+        8 | 12 => {
+            let sign = if v[0] == 1 { "-" } else { "" };
+            let days = u32::from_le_bytes([v[1], v[2], v[3], v[4]]);
+            let hours = days * 24 + v[5] as u32;
+            let time = format!("{}{:02}:{:02}:{:02}", sign, hours, v[6], v[7]);
+            if v.len() == 8 {
+                Ok(time)
+            } else {
+                let micros = u32::from_le_bytes([v[8], v[9], v[10], v[11]]);
+                Ok(format!("{}.{:06}", time, micros))
+            }
+        }
+        len => Err(ErrorCode::BadBytes(format!(
+            "Invalid length {} of the time parameter",
+            len
+        ))),
+    }
 }
 
 struct InteractiveWorkerBase<W: AsyncWrite + Send + Unpin> {
@@ -92,6 +144,8 @@ pub struct InteractiveWorker<W: AsyncWrite + Send + Unpin> {
     version: String,
     salt: [u8; 20],
     client_addr: String,
+    // The client certificate verified in the TLS handshake.
+    client_cert: Arc<Mutex<Option<ClientCert>>>,
 }
 
 #[async_trait::async_trait]
@@ -135,7 +189,8 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for InteractiveWorke
         let client_addr = self.client_addr.clone();
         let info = CertifiedInfo::create(&username, auth_data, &client_addr);
 
-        let authenticate = self.base.authenticate(salt, info);
+        let client_cert = self.client_cert.lock().clone();
+        let authenticate = self.base.authenticate(salt, info, client_cert);
         match authenticate.await {
             Ok(res) => res,
             Err(failure) => {
@@ -191,7 +246,20 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for InteractiveWorke
             ));
         }
 
-        self.base.do_execute(id, param, writer).await
+        let mut writer = DFQueryResultWriter::create(writer, true);
+
+        let instant = Instant::now();
+        let query_result = self.base.do_execute(id, param).await;
+
+        let format = self.base.session.get_format_settings()?;
+        let write_result = writer.write(query_result, &format).await;
+
+        histogram!(
+            super::mysql_metrics::METRIC_MYSQL_PROCESSOR_REQUEST_DURATION,
+            instant.elapsed()
+        );
+
+        write_result
     }
 
     /// https://dev.mysql.com/doc/internals/en/com-stmt-close.html
@@ -218,7 +286,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for InteractiveWorke
             ));
         }
 
-        let mut writer = DFQueryResultWriter::create(writer);
+        let mut writer = DFQueryResultWriter::create(writer, false);
 
         let instant = Instant::now();
         let query_result = self
@@ -268,7 +336,12 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for InteractiveWorke
 }
 
 impl<W: AsyncWrite + Send + Unpin> InteractiveWorkerBase<W> {
-    async fn authenticate(&self, salt: &[u8], info: CertifiedInfo) -> Result<bool> {
+    async fn authenticate(
+        &self,
+        salt: &[u8],
+        info: CertifiedInfo,
+        client_cert: Option<ClientCert>,
+    ) -> Result<bool> {
         let user_name = &info.user_name;
         let client_ip = info.user_client_address.split(':').collect::<Vec<_>>()[0];
 
@@ -277,39 +350,69 @@ impl<W: AsyncWrite + Send + Unpin> InteractiveWorkerBase<W> {
             .get_user_with_client_ip(&ctx.get_tenant(), user_name, client_ip)
             .await?;
 
-        let authed = user_info.auth_info.auth_mysql(&info.user_password, salt)?;
+        let authed = match &user_info.auth_info {
+            AuthInfo::X509 { .. } => user_info
+                .auth_info
+                .auth_x509(user_name, client_cert.as_ref())?,
+            auth_info => auth_info.auth_mysql(&info.user_password, salt)?,
+        };
         if authed {
             self.session.set_authed_user(user_info, None).await?;
         }
         Ok(authed)
     }
 
-    async fn do_prepare(&mut self, _: &str, writer: StatementMetaWriter<'_, W>) -> Result<()> {
-        writer
-            .error(
-                ErrorKind::ER_UNKNOWN_ERROR,
-                "Prepare is not support in Databend.".as_bytes(),
-            )
-            .await?;
+    async fn do_prepare(&mut self, query: &str, writer: StatementMetaWriter<'_, W>) -> Result<()> {
+        let sql_dialect = self.session.get_settings().get_sql_dialect()?;
+        let statement = match PreparedStatement::try_create(query, sql_dialect) {
+            Ok(statement) => statement,
+            Err(cause) => {
+                let cause = cause.display_with_sql(query);
+                error!("OnPrepare Error: {:?}", cause);
+                writer
+                    .error(ErrorKind::ER_UNKNOWN_ERROR, cause.to_string().as_bytes())
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        // The types of the parameters are decided by the client on execution,
+        // and the columns of the result set are sent with the rows.
+        let params = (0..statement.num_params())
+            .map(|_| Column {
+                table: "".to_string(),
+                column: "?".to_string(),
+                coltype: ColumnType::MYSQL_TYPE_VAR_STRING,
+                colflags: ColumnFlags::empty(),
+            })
+            .collect::<Vec<_>>();
+        info!("Prepare query: {}", query);
+        let id = self.session.add_prepared_statement(statement);
+        writer.reply(id, &params, &[]).await?;
         Ok(())
     }
 
-    async fn do_execute(
-        &mut self,
-        _: u32,
-        _: ParamParser<'_>,
-        writer: QueryResultWriter<'_, W>,
-    ) -> Result<()> {
-        writer
-            .error(
-                ErrorKind::ER_UNKNOWN_ERROR,
-                "Execute is not support in Databend.".as_bytes(),
-            )
-            .await?;
-        Ok(())
+    async fn do_execute(&mut self, id: u32, params: ParamParser<'_>) -> Result<QueryResult> {
+        let statement = self.session.get_prepared_statement(id).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Unknown prepared statement id {}", id))
+        })?;
+        let params = params
+            .into_iter()
+            .map(|param| param_to_scalar(param.value.into_inner()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let query = statement.query();
+        let stmt = statement
+            .bind(&params)
+            .map_err(|err| err.display_with_sql(query))?;
+        self.run_query(query, Some(stmt))
+            .await
+            .map_err(|err| err.display_with_sql(query))
     }
 
-    async fn do_close(&mut self, _: u32) {}
+    async fn do_close(&mut self, id: u32) {
+        self.session.remove_prepared_statement(id);
+    }
 
     // Check the query is a federated or driver setup command.
     // Here we fake some values for the command which Databend not supported.
@@ -324,6 +427,11 @@ impl<W: AsyncWrite + Send + Unpin> InteractiveWorkerBase<W> {
 
     #[tracing::instrument(level = "debug", skip(self))]
     async fn do_query(&mut self, query: &str) -> Result<QueryResult> {
+        self.run_query(query, None).await
+    }
+
+    // Run the query, the statement of a prepared query is parsed and bound already.
+    async fn run_query(&mut self, query: &str, stmt: Option<Statement>) -> Result<QueryResult> {
         match self.federated_server_command_check(query) {
             Some((schema, data_block)) => {
                 info!("Federated query: {}", query);
@@ -343,11 +451,14 @@ impl<W: AsyncWrite + Send + Unpin> InteractiveWorkerBase<W> {
                 let context = self.session.create_query_context().await?;
 
                 let mut planner = Planner::new(context.clone());
-                let (plan, _, _) = planner.plan_sql(query).await?;
+                let plan = match stmt {
+                    Some(stmt) => planner.plan_stmt(stmt).await?.0,
+                    None => planner.plan_sql(query).await?.0,
+                };
 
                 context.attach_query_str(plan.to_string(), query);
                 let interpreter = InterpreterFactory::get(context.clone(), &plan).await;
-                let has_result_set = has_result_set_by_plan(&plan);
+                let has_result_set = plan.has_result_set();
 
                 match interpreter {
                     Ok(interpreter) => {
@@ -426,7 +537,11 @@ impl<W: AsyncWrite + Send + Unpin> InteractiveWorkerBase<W> {
 }
 
 impl<W: AsyncWrite + Send + Unpin> InteractiveWorker<W> {
-    pub fn create(session: Arc<Session>, client_addr: String) -> InteractiveWorker<W> {
+    pub fn create(
+        session: Arc<Session>,
+        client_addr: String,
+        client_cert: Arc<Mutex<Option<ClientCert>>>,
+    ) -> InteractiveWorker<W> {
         let mut bs = vec![0u8; 20];
         let mut rng = rand::thread_rng();
         rng.fill_bytes(bs.as_mut());
@@ -447,6 +562,7 @@ impl<W: AsyncWrite + Send + Unpin> InteractiveWorker<W> {
             salt: scramble,
             version: format!("{}-{}", MYSQL_VERSION, *DATABEND_COMMIT_VERSION),
             client_addr,
+            client_cert,
         }
     }
 }
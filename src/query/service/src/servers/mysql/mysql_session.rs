@@ -23,12 +23,16 @@ use common_base::runtime::TrySpawn;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_exception::ToErrorCode;
+use opensrv_mysql::plain_run_with_options;
+use opensrv_mysql::secure_run_with_options;
 use opensrv_mysql::AsyncMysqlIntermediary;
 use opensrv_mysql::IntermediaryOptions;
+use parking_lot::Mutex;
 use tracing::error;
 use tracing::warn;
 
 use crate::servers::mysql::mysql_interactive_worker::InteractiveWorker;
+use crate::servers::mysql::mysql_tls::MySQLTlsConfig;
 use crate::sessions::Session;
 
 // default size of resultset write buffer: 100KB
@@ -37,7 +41,11 @@ const DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE: usize = 100 * 1024;
 pub struct MySQLConnection;
 
 impl MySQLConnection {
-    pub fn run_on_stream(session: Arc<Session>, stream: TcpStream) -> Result<()> {
+    pub fn run_on_stream(
+        session: Arc<Session>,
+        stream: TcpStream,
+        tls: Option<Arc<MySQLTlsConfig>>,
+    ) -> Result<()> {
         let blocking_stream = Self::convert_stream(stream)?;
         MySQLConnection::attach_session(&session, &blocking_stream)?;
 
@@ -57,13 +65,40 @@ impl MySQLConnection {
                     }
                 };
 
-                let interactive_worker = InteractiveWorker::create(session, client_addr);
+                let client_cert = Arc::new(Mutex::new(None));
+                let tls_config = match &tls {
+                    None => None,
+                    Some(tls) => Some(tls.server_config(client_cert.clone())?),
+                };
+                let mut interactive_worker =
+                    InteractiveWorker::create(session, client_addr, client_cert);
                 let opts = IntermediaryOptions {
                     process_use_statement_on_query: true,
                 };
                 let (r, w) = non_blocking_stream.into_split();
-                let w = BufWriter::with_capacity(DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE, w);
-                AsyncMysqlIntermediary::run_with_options(interactive_worker, r, w, &opts).await
+                let mut w = BufWriter::with_capacity(DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE, w);
+
+                // The client switches to TLS after the SSL request if the server supports it.
+                let (is_ssl, init_params) = AsyncMysqlIntermediary::init_before_ssl(
+                    &mut interactive_worker,
+                    r,
+                    &mut w,
+                    &tls_config,
+                )
+                .await?;
+                match tls_config {
+                    Some(tls_config) if is_ssl => {
+                        secure_run_with_options(
+                            interactive_worker,
+                            w,
+                            &opts,
+                            tls_config,
+                            init_params,
+                        )
+                        .await
+                    }
+                    _ => plain_run_with_options(interactive_worker, w, &opts, init_params).await,
+                }
             });
             let _ = futures::executor::block_on(join_handle);
         });
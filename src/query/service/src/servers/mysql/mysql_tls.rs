@@ -0,0 +1,177 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use common_config::InnerConfig;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_app::principal::ClientCert;
+use parking_lot::Mutex;
+use rustls_pemfile::Item;
+use tokio_rustls::rustls::server::AllowAnyAnonymousOrAuthenticatedClient;
+use tokio_rustls::rustls::server::ClientCertVerified;
+use tokio_rustls::rustls::server::ClientCertVerifier;
+use tokio_rustls::rustls::Certificate;
+use tokio_rustls::rustls::DistinguishedNames;
+use tokio_rustls::rustls::PrivateKey;
+use tokio_rustls::rustls::RootCertStore;
+use tokio_rustls::rustls::ServerConfig;
+
+/// The TLS of the MySQL handler, it is started by the SSL request of the client in the
+/// handshake, so the plain text and the TLS connections are served on the same port.
+pub struct MySQLTlsConfig {
+    certs: Vec<Certificate>,
+    key: PrivateKey,
+    // The client certificates are verified only if the root CA is configured.
+    client_roots: Option<RootCertStore>,
+}
+
+impl MySQLTlsConfig {
+    /// Returns None if the certificate or the key of the server is not configured.
+    pub fn try_create(config: &InnerConfig) -> Result<Option<MySQLTlsConfig>> {
+        let config = &config.query;
+        if config.mysql_handler_tls_server_cert.is_empty()
+            || config.mysql_handler_tls_server_key.is_empty()
+        {
+            return Ok(None);
+        }
+
+        let certs = load_certs(&config.mysql_handler_tls_server_cert)?;
+        let key = load_key(&config.mysql_handler_tls_server_key)?;
+        let client_roots = match config.mysql_handler_tls_server_root_ca_cert.is_empty() {
+            true => None,
+            false => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(&config.mysql_handler_tls_server_root_ca_cert)? {
+                    roots.add(&cert).map_err(|e| {
+                        ErrorCode::TLSConfigurationFailure(format!(
+                            "invalid root ca cert for mysql handler: {e}"
+                        ))
+                    })?;
+                }
+                Some(roots)
+            }
+        };
+
+        let tls_config = MySQLTlsConfig {
+            certs,
+            key,
+            client_roots,
+        };
+        // Check the key matches the certificate when starting, not when the client comes.
+        tls_config.server_config(Arc::new(Mutex::new(None)))?;
+        Ok(Some(tls_config))
+    }
+
+    /// Build the config for one connection, the client certificate verified in the handshake
+    /// is kept in `client_cert` for the authentication of the x509 users.
+    pub fn server_config(
+        &self,
+        client_cert: Arc<Mutex<Option<ClientCert>>>,
+    ) -> Result<Arc<ServerConfig>> {
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = match &self.client_roots {
+            None => builder.with_no_client_auth(),
+            Some(roots) => builder.with_client_cert_verifier(Arc::new(ClientCertRecorder {
+                inner: AllowAnyAnonymousOrAuthenticatedClient::new(roots.clone()),
+                client_cert,
+            })),
+        };
+        let config = builder
+            .with_single_cert(self.certs.clone(), self.key.clone())
+            .map_err(|e| {
+                ErrorCode::TLSConfigurationFailure(format!(
+                    "invalid tls cert or key for mysql handler: {e}"
+                ))
+            })?;
+        Ok(Arc::new(config))
+    }
+}
+
+/// Verify the client certificate with the root CA if the client sends one, and record the
+/// subject of it. The clients without certificates can still login with the passwords.
+struct ClientCertRecorder {
+    inner: Arc<dyn ClientCertVerifier>,
+    client_cert: Arc<Mutex<Option<ClientCert>>>,
+}
+
+impl ClientCertVerifier for ClientCertRecorder {
+    fn offer_client_auth(&self) -> bool {
+        self.inner.offer_client_auth()
+    }
+
+    fn client_auth_mandatory(&self) -> Option<bool> {
+        self.inner.client_auth_mandatory()
+    }
+
+    fn client_auth_root_subjects(&self) -> Option<DistinguishedNames> {
+        self.inner.client_auth_root_subjects()
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        now: SystemTime,
+    ) -> std::result::Result<ClientCertVerified, tokio_rustls::rustls::Error> {
+        let verified = self
+            .inner
+            .verify_client_cert(end_entity, intermediates, now)?;
+        let (_, cert) = x509_parser::parse_x509_certificate(&end_entity.0).map_err(|e| {
+            tokio_rustls::rustls::Error::InvalidCertificateData(format!(
+                "fail to parse client cert: {e}"
+            ))
+        })?;
+        let subject = cert.subject();
+        *self.client_cert.lock() = Some(ClientCert {
+            subject: subject.to_string(),
+            common_name: subject
+                .iter_common_name()
+                .next()
+                .and_then(|cn| cn.as_str().ok())
+                .map(|cn| cn.to_string()),
+        });
+        Ok(verified)
+    }
+}
+
+fn load_certs(path: &str) -> Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    if certs.is_empty() {
+        return Err(ErrorCode::TLSConfigurationFailure(format!(
+            "no certificate found in {path}"
+        )));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_key(path: &str) -> Result<PrivateKey> {
+    let mut reader = BufReader::new(File::open(path)?);
+    while let Some(item) = rustls_pemfile::read_one(&mut reader)? {
+        match item {
+            Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key) => {
+                return Ok(PrivateKey(key));
+            }
+            _ => continue,
+        }
+    }
+    Err(ErrorCode::TLSConfigurationFailure(format!(
+        "no private key found in {path}"
+    )))
+}
@@ -15,6 +15,7 @@
 use common_base::base::tokio::io::AsyncWrite;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_expression::date_helper::DateConverter;
 use common_expression::types::number::NumberScalar;
 use common_expression::types::DataType;
 use common_expression::types::NumberDataType;
@@ -65,6 +66,8 @@ impl QueryResult {
 
 pub struct DFQueryResultWriter<'a, W: AsyncWrite + Send + Unpin> {
     inner: Option<QueryResultWriter<'a, W>>,
+    // The results of the prepared statements are sent in the binary protocol.
+    binary: bool,
 }
 
 fn write_field<W: AsyncWrite + Unpin>(
@@ -81,8 +84,11 @@ fn write_field<W: AsyncWrite + Unpin>(
 }
 
 impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
-    pub fn create(inner: QueryResultWriter<'a, W>) -> DFQueryResultWriter<'a, W> {
-        DFQueryResultWriter::<'a, W> { inner: Some(inner) }
+    pub fn create(inner: QueryResultWriter<'a, W>, binary: bool) -> DFQueryResultWriter<'a, W> {
+        DFQueryResultWriter::<'a, W> {
+            inner: Some(inner),
+            binary,
+        }
     }
 
     pub async fn write(
@@ -92,7 +98,7 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
     ) -> Result<()> {
         if let Some(writer) = self.inner.take() {
             match query_result {
-                Ok(query_result) => Self::ok(query_result, writer, format).await?,
+                Ok(query_result) => Self::ok(query_result, writer, format, self.binary).await?,
                 Err(error) => Self::err(&error, writer).await?,
             }
         }
@@ -103,6 +109,7 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
         mut query_result: QueryResult,
         dataset_writer: QueryResultWriter<'a, W>,
         format: &FormatSettings,
+        binary: bool,
     ) -> Result<()> {
         // XXX: num_columns == 0 may is error?
         if !query_result.has_result_set {
@@ -248,6 +255,12 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
                                     NumberScalar::Int64(v) => {
                                         row_writer.write_col(v)?;
                                     }
+                                    NumberScalar::Float32(v) if binary => {
+                                        row_writer.write_col(v.0)?;
+                                    }
+                                    NumberScalar::Float64(v) if binary => {
+                                        row_writer.write_col(v.0)?;
+                                    }
                                     _ => {
                                         write_field(
                                             &mut row_writer,
@@ -258,6 +271,14 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
                                         )?;
                                     }
                                 },
+                                ScalarRef::Date(v) if binary => {
+                                    let date = v.to_date(format.timezone).naive_local();
+                                    row_writer.write_col(date)?;
+                                }
+                                ScalarRef::Timestamp(v) if binary => {
+                                    let datetime = v.to_timestamp(format.timezone).naive_local();
+                                    row_writer.write_col(datetime)?;
+                                }
                                 _ => write_field(
                                     &mut row_writer,
                                     column,
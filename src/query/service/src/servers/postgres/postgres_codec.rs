@@ -0,0 +1,436 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The messages of the PostgreSQL frontend/backend protocol version 3.
+//!
+//! https://www.postgresql.org/docs/current/protocol-message-formats.html
+
+use std::collections::HashMap;
+
+use bytes::BufMut;
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncReadExt;
+use common_base::base::tokio::io::AsyncWrite;
+use common_base::base::tokio::io::AsyncWriteExt;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+pub const PROTOCOL_VERSION_3: i32 = 196608;
+pub const CANCEL_REQUEST_CODE: i32 = 80877102;
+pub const SSL_REQUEST_CODE: i32 = 80877103;
+pub const GSSENC_REQUEST_CODE: i32 = 80877104;
+
+// The messages larger than this are rejected instead of being buffered: 1GB
+const MAX_MESSAGE_LENGTH: usize = 1024 * 1024 * 1024;
+
+// The status indicators of the ReadyForQuery message.
+pub const TRANSACTION_IDLE: u8 = b'I';
+
+pub const FORMAT_TEXT: i16 = 0;
+pub const FORMAT_BINARY: i16 = 1;
+
+pub enum StartupMessage {
+    SslRequest,
+    GssEncRequest,
+    CancelRequest,
+    Startup { params: HashMap<String, String> },
+}
+
+pub enum FrontendMessage {
+    Query(String),
+    Parse {
+        name: String,
+        query: String,
+        param_types: Vec<u32>,
+    },
+    Bind {
+        portal: String,
+        statement: String,
+        param_formats: Vec<i16>,
+        params: Vec<Option<Vec<u8>>>,
+        result_formats: Vec<i16>,
+    },
+    Describe {
+        kind: u8,
+        name: String,
+    },
+    Execute {
+        portal: String,
+        max_rows: i32,
+    },
+    Close {
+        kind: u8,
+        name: String,
+    },
+    Password(String),
+    Sync,
+    Flush,
+    Terminate,
+}
+
+/// Read the first message of the connection, which has no type byte.
+pub async fn read_startup_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<StartupMessage> {
+    let len = reader.read_i32().await? as usize;
+    if !(8..=MAX_MESSAGE_LENGTH).contains(&len) {
+        return Err(ErrorCode::BadBytes(format!(
+            "Invalid length {} of the startup message",
+            len
+        )));
+    }
+    let mut body = vec![0; len - 4];
+    reader.read_exact(&mut body).await?;
+
+    let mut body = MessageBody::new(&body);
+    match body.get_i32()? {
+        SSL_REQUEST_CODE => Ok(StartupMessage::SslRequest),
+        GSSENC_REQUEST_CODE => Ok(StartupMessage::GssEncRequest),
+        CANCEL_REQUEST_CODE => Ok(StartupMessage::CancelRequest),
+        PROTOCOL_VERSION_3 => {
+            let mut params = HashMap::new();
+            loop {
+                let name = body.get_cstr()?;
+                if name.is_empty() {
+                    break;
+                }
+                let value = body.get_cstr()?;
+                params.insert(name, value);
+            }
+            Ok(StartupMessage::Startup { params })
+        }
+        version => Err(ErrorCode::Unimplemented(format!(
+            "Unsupported frontend protocol {}.{}",
+            version >> 16,
+            version & 0xffff
+        ))),
+    }
+}
+
+/// Read a message after the startup, returns None if the client closed the connection.
+pub async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<FrontendMessage>> {
+    let tag = match reader.read_u8().await {
+        Ok(tag) => tag,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let len = reader.read_i32().await? as usize;
+    if !(4..=MAX_MESSAGE_LENGTH).contains(&len) {
+        return Err(ErrorCode::BadBytes(format!(
+            "Invalid length {} of the message '{}'",
+            len, tag as char
+        )));
+    }
+    let mut body = vec![0; len - 4];
+    reader.read_exact(&mut body).await?;
+
+    let mut body = MessageBody::new(&body);
+    let message = match tag {
+        b'Q' => FrontendMessage::Query(body.get_cstr()?),
+        b'P' => {
+            let name = body.get_cstr()?;
+            let query = body.get_cstr()?;
+            let num_types = body.get_i16()?;
+            let param_types = (0..num_types)
+                .map(|_| body.get_i32().map(|oid| oid as u32))
+                .collect::<Result<_>>()?;
+            FrontendMessage::Parse {
+                name,
+                query,
+                param_types,
+            }
+        }
+        b'B' => {
+            let portal = body.get_cstr()?;
+            let statement = body.get_cstr()?;
+            let num_formats = body.get_i16()?;
+            let param_formats = (0..num_formats)
+                .map(|_| body.get_i16())
+                .collect::<Result<_>>()?;
+            let num_params = body.get_i16()?;
+            let params = (0..num_params)
+                .map(|_| match body.get_i32()? {
+                    -1 => Ok(None),
+                    len => body.get_bytes(len as usize).map(|v| Some(v.to_vec())),
+                })
+                .collect::<Result<_>>()?;
+            let num_formats = body.get_i16()?;
+            let result_formats = (0..num_formats)
+                .map(|_| body.get_i16())
+                .collect::<Result<_>>()?;
+            FrontendMessage::Bind {
+                portal,
+                statement,
+                param_formats,
+                params,
+                result_formats,
+            }
+        }
+        b'D' => FrontendMessage::Describe {
+            kind: body.get_u8()?,
+            name: body.get_cstr()?,
+        },
+        b'E' => FrontendMessage::Execute {
+            portal: body.get_cstr()?,
+            max_rows: body.get_i32()?,
+        },
+        b'C' => FrontendMessage::Close {
+            kind: body.get_u8()?,
+            name: body.get_cstr()?,
+        },
+        b'p' => FrontendMessage::Password(body.get_cstr()?),
+        b'S' => FrontendMessage::Sync,
+        b'H' => FrontendMessage::Flush,
+        b'X' => FrontendMessage::Terminate,
+        _ => {
+            return Err(ErrorCode::Unimplemented(format!(
+                "Unsupported frontend message '{}'",
+                tag as char
+            )));
+        }
+    };
+    Ok(Some(message))
+}
+
+struct MessageBody<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> MessageBody<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        MessageBody { buf }
+    }
+
+    fn get_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(ErrorCode::BadBytes("Unexpected end of the message"));
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    fn get_u8(&mut self) -> Result<u8> {
+        Ok(self.get_bytes(1)?[0])
+    }
+
+    fn get_i16(&mut self) -> Result<i16> {
+        let bytes = self.get_bytes(2)?;
+        Ok(i16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn get_i32(&mut self) -> Result<i32> {
+        let bytes = self.get_bytes(4)?;
+        Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn get_cstr(&mut self) -> Result<String> {
+        let end = self
+            .buf
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| ErrorCode::BadBytes("Unterminated string in the message"))?;
+        let s = String::from_utf8(self.buf[..end].to_vec())
+            .map_err(|e| ErrorCode::BadBytes(format!("Invalid utf8 string: {}", e)))?;
+        self.buf = &self.buf[end + 1..];
+        Ok(s)
+    }
+}
+
+pub struct FieldDescription {
+    pub name: String,
+    pub type_oid: u32,
+    pub type_size: i16,
+    pub format: i16,
+}
+
+/// Buffers the backend messages, they are sent to the client on flush.
+pub struct MessageWriter<W: AsyncWrite + Unpin> {
+    writer: W,
+    buf: Vec<u8>,
+    // The position of the message in writing, its length is filled on finish.
+    message_start: usize,
+}
+
+impl<W: AsyncWrite + Unpin> MessageWriter<W> {
+    pub fn create(writer: W) -> Self {
+        MessageWriter {
+            writer,
+            buf: Vec::new(),
+            message_start: 0,
+        }
+    }
+
+    pub fn buffered_size(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        self.writer.write_all(&self.buf).await?;
+        self.writer.flush().await?;
+        self.buf.clear();
+        Ok(())
+    }
+
+    fn begin(&mut self, tag: u8) {
+        self.buf.put_u8(tag);
+        self.message_start = self.buf.len();
+        self.buf.put_i32(0);
+    }
+
+    fn finish(&mut self) {
+        let len = (self.buf.len() - self.message_start) as i32;
+        self.buf[self.message_start..self.message_start + 4].copy_from_slice(&len.to_be_bytes());
+    }
+
+    fn put_cstr(&mut self, s: &str) {
+        self.buf.put_slice(s.as_bytes());
+        self.buf.put_u8(0);
+    }
+
+    /// The single byte answer of the SSLRequest and GSSENCRequest, which isn't a message.
+    pub fn encryption_not_supported(&mut self) {
+        self.buf.put_u8(b'N');
+    }
+
+    pub fn authentication_ok(&mut self) {
+        self.begin(b'R');
+        self.buf.put_i32(0);
+        self.finish();
+    }
+
+    pub fn authentication_cleartext_password(&mut self) {
+        self.begin(b'R');
+        self.buf.put_i32(3);
+        self.finish();
+    }
+
+    pub fn parameter_status(&mut self, name: &str, value: &str) {
+        self.begin(b'S');
+        self.put_cstr(name);
+        self.put_cstr(value);
+        self.finish();
+    }
+
+    pub fn backend_key_data(&mut self, process_id: i32, secret_key: i32) {
+        self.begin(b'K');
+        self.buf.put_i32(process_id);
+        self.buf.put_i32(secret_key);
+        self.finish();
+    }
+
+    pub fn ready_for_query(&mut self, status: u8) {
+        self.begin(b'Z');
+        self.buf.put_u8(status);
+        self.finish();
+    }
+
+    pub fn row_description(&mut self, fields: &[FieldDescription]) {
+        self.begin(b'T');
+        self.buf.put_i16(fields.len() as i16);
+        for field in fields {
+            self.put_cstr(&field.name);
+            // The oid of the table and the attribute number of the column.
+            self.buf.put_i32(0);
+            self.buf.put_i16(0);
+            self.buf.put_u32(field.type_oid);
+            self.buf.put_i16(field.type_size);
+            // The type modifier.
+            self.buf.put_i32(-1);
+            self.buf.put_i16(field.format);
+        }
+        self.finish();
+    }
+
+    pub fn parameter_description(&mut self, type_oids: &[u32]) {
+        self.begin(b't');
+        self.buf.put_i16(type_oids.len() as i16);
+        for oid in type_oids {
+            self.buf.put_u32(*oid);
+        }
+        self.finish();
+    }
+
+    pub fn begin_data_row(&mut self, num_columns: usize) {
+        self.begin(b'D');
+        self.buf.put_i16(num_columns as i16);
+    }
+
+    pub fn put_null(&mut self) {
+        self.buf.put_i32(-1);
+    }
+
+    /// Put a value of the data row, the value is written by the `write` function.
+    pub fn put_value(&mut self, write: impl FnOnce(&mut Vec<u8>) -> Result<()>) -> Result<()> {
+        let value_start = self.buf.len();
+        self.buf.put_i32(0);
+        write(&mut self.buf)?;
+        let len = (self.buf.len() - value_start - 4) as i32;
+        self.buf[value_start..value_start + 4].copy_from_slice(&len.to_be_bytes());
+        Ok(())
+    }
+
+    pub fn end_data_row(&mut self) {
+        self.finish();
+    }
+
+    pub fn command_complete(&mut self, tag: &str) {
+        self.begin(b'C');
+        self.put_cstr(tag);
+        self.finish();
+    }
+
+    pub fn empty_query_response(&mut self) {
+        self.begin(b'I');
+        self.finish();
+    }
+
+    pub fn parse_complete(&mut self) {
+        self.begin(b'1');
+        self.finish();
+    }
+
+    pub fn bind_complete(&mut self) {
+        self.begin(b'2');
+        self.finish();
+    }
+
+    pub fn close_complete(&mut self) {
+        self.begin(b'3');
+        self.finish();
+    }
+
+    pub fn no_data(&mut self) {
+        self.begin(b'n');
+        self.finish();
+    }
+
+    pub fn portal_suspended(&mut self) {
+        self.begin(b's');
+        self.finish();
+    }
+
+    pub fn error_response(&mut self, severity: &str, sqlstate: &str, message: &str) {
+        self.begin(b'E');
+        self.buf.put_u8(b'S');
+        self.put_cstr(severity);
+        // The non-localized severity, since PostgreSQL 9.6.
+        self.buf.put_u8(b'V');
+        self.put_cstr(severity);
+        self.buf.put_u8(b'C');
+        self.put_cstr(sqlstate);
+        self.buf.put_u8(b'M');
+        self.put_cstr(message);
+        self.buf.put_u8(0);
+        self.finish();
+    }
+}
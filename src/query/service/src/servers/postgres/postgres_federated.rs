@@ -0,0 +1,297 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_ast::parser::token::TokenKind;
+use common_ast::parser::tokenize_sql;
+use common_config::DATABEND_COMMIT_VERSION;
+use common_expression::types::StringType;
+use common_expression::utils::FromData;
+use common_expression::DataBlock;
+use common_expression::DataSchema;
+use common_expression::DataSchemaRef;
+use common_expression::TableDataType;
+use common_expression::TableField;
+use common_expression::TableSchemaRef;
+use common_expression::TableSchemaRefExt;
+use regex::Regex;
+
+use crate::servers::federated_helper::FederatedHelper;
+use crate::servers::federated_helper::LazyBlockFunc;
+use crate::servers::postgres::POSTGRES_VERSION;
+
+// The tables of pg_catalog emulated by the views of information_schema,
+// they are substituted into the queries of the clients.
+const CATALOG_TABLES: &[(&str, &str)] = &[
+    (
+        "pg_namespace",
+        "SELECT schema_name AS oid, schema_name AS nspname, schema_owner AS nspowner \
+        FROM information_schema.schemata",
+    ),
+    (
+        "pg_database",
+        "SELECT schema_name AS oid, schema_name AS datname, schema_owner AS datdba, \
+        'UTF8' AS encoding, true AS datallowconn \
+        FROM information_schema.schemata",
+    ),
+    (
+        "pg_class",
+        "SELECT concat(table_schema, '.', table_name) AS oid, table_name AS relname, \
+        table_schema AS relnamespace, \
+        CASE WHEN lower(engine) LIKE '%view' THEN 'v' ELSE 'r' END AS relkind \
+        FROM information_schema.tables",
+    ),
+    (
+        "pg_attribute",
+        "SELECT concat(table_schema, '.', table_name) AS attrelid, column_name AS attname, \
+        data_type AS atttypid, ordinal_position AS attnum, is_nullable = 'NO' AS attnotnull, \
+        false AS attisdropped \
+        FROM information_schema.columns",
+    ),
+    (
+        "pg_tables",
+        "SELECT table_schema AS schemaname, table_name AS tablename, 'default' AS tableowner \
+        FROM information_schema.tables WHERE lower(engine) NOT LIKE '%view'",
+    ),
+    (
+        "pg_views",
+        "SELECT table_schema AS schemaname, table_name AS viewname, 'default' AS viewowner, \
+        view_definition AS definition \
+        FROM information_schema.views",
+    ),
+];
+
+pub struct PostgresFederated {
+    postgres_version: String,
+    databend_version: String,
+}
+
+impl PostgresFederated {
+    pub fn create() -> Self {
+        PostgresFederated {
+            postgres_version: POSTGRES_VERSION.to_string(),
+            databend_version: DATABEND_COMMIT_VERSION.to_string(),
+        }
+    }
+
+    // Build block for select function.
+    // Format:
+    // |function_name|
+    // |value|
+    fn select_function_block(name: &str, value: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        let schema = TableSchemaRefExt::create(vec![TableField::new(name, TableDataType::String)]);
+        let block = DataBlock::new_from_columns(vec![StringType::from_data(vec![
+            value.as_bytes().to_vec(),
+        ])]);
+        Some((schema, block))
+    }
+
+    // The run-time parameters reported by SHOW and current_setting(), the same as the
+    // parameters sent to the client on startup.
+    fn parameter_value(name: &str) -> Option<&'static str> {
+        match name.to_lowercase().as_str() {
+            "server_version" => Some(POSTGRES_VERSION),
+            "server_version_num" => Some("110003"),
+            "server_encoding" | "client_encoding" => Some("UTF8"),
+            "datestyle" => Some("ISO, MDY"),
+            "intervalstyle" => Some("postgres"),
+            "integer_datetimes" | "standard_conforming_strings" => Some("on"),
+            "transaction_isolation" | "default_transaction_isolation" => Some("read committed"),
+            "transaction_read_only" => Some("off"),
+            "search_path" => Some("\"$user\", public"),
+            "max_identifier_length" => Some("63"),
+            _ => None,
+        }
+    }
+
+    // SHOW <parameter>
+    fn show_parameter_block(query: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        let re = Regex::new(r"(?i)^SHOW\s+(TRANSACTION\s+ISOLATION\s+LEVEL|\w+)\s*;?\s*$").ok()?;
+        let name = re.captures(query)?.get(1)?.as_str().to_lowercase();
+        let name = if name.starts_with("transaction") {
+            "transaction_isolation".to_string()
+        } else {
+            name
+        };
+        let value = Self::parameter_value(&name)?;
+        Self::select_function_block(&name, value)
+    }
+
+    // SELECT current_setting('<parameter>')
+    fn current_setting_block(query: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        let re = Regex::new(r"(?i)current_setting\(\s*'(\w+)'\s*\)").ok()?;
+        let name = re.captures(query)?.get(1)?.as_str();
+        let value = Self::parameter_value(name)?;
+        Self::select_function_block("current_setting", value)
+    }
+
+    // Check SHOW <parameter> and current_setting().
+    fn federated_parameter_check(&self, query: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        let rules: Vec<(&str, LazyBlockFunc)> = vec![
+            (
+                r"(?i)^(SHOW\s+(TRANSACTION\s+ISOLATION\s+LEVEL|\w+)\s*;?\s*$)",
+                Self::show_parameter_block,
+            ),
+            (
+                r"(?i)^(SELECT\s+(pg_catalog\.)?current_setting\((.*))",
+                Self::current_setting_block,
+            ),
+        ];
+        FederatedHelper::lazy_block_match_rule(query, rules)
+            .filter(|(schema, _)| schema.num_fields() > 0)
+    }
+
+    // Check for SET or others query, this is the final check of the federated query.
+    fn federated_mixed_check(&self, query: &str) -> Option<(TableSchemaRef, DataBlock)> {
+        let rules: Vec<(&str, Option<(TableSchemaRef, DataBlock)>)> = vec![
+            (
+                r"(?i)^(SELECT\s+(pg_catalog\.)?version\(\s*\))",
+                Self::select_function_block(
+                    "version",
+                    format!(
+                        "PostgreSQL {} on Databend {}",
+                        self.postgres_version, self.databend_version
+                    )
+                    .as_str(),
+                ),
+            ),
+            // Txn.
+            ("(?i)^(BEGIN(.*))", None),
+            ("(?i)^(START TRANSACTION(.*))", None),
+            ("(?i)^(COMMIT(.*))", None),
+            ("(?i)^(END(.*))", None),
+            ("(?i)^(ROLLBACK(.*))", None),
+            // Set, the session parameters of the drivers.
+            (
+                "(?i)^(SET\\s+(SESSION\\s+|LOCAL\\s+)?(search_path|client_encoding|datestyle|intervalstyle|extra_float_digits|application_name|statement_timeout|client_min_messages|TIME ZONE)(.*))",
+                None,
+            ),
+            ("(?i)^(SET SESSION CHARACTERISTICS(.*))", None),
+            // Connection pools.
+            ("(?i)^(DISCARD(.*))", None),
+            ("(?i)^(DEALLOCATE(.*))", None),
+        ];
+
+        FederatedHelper::block_match_rule(query, rules)
+    }
+
+    // Check the query is a federated or driver setup command.
+    // Here we fake some values for the command which Databend not supported.
+    pub fn check(&self, query: &str) -> Option<(DataSchemaRef, DataBlock)> {
+        // First to check the parameters.
+        let parameter = self
+            .federated_parameter_check(query)
+            .map(|(schema, chunk)| (Arc::new(DataSchema::from(schema)), chunk));
+        if parameter.is_some() {
+            return parameter;
+        }
+
+        // Last check.
+        self.federated_mixed_check(query)
+            .map(|(schema, chunk)| (Arc::new(DataSchema::from(schema)), chunk))
+    }
+
+    // The meta-commands of psql, they call the functions of PostgreSQL which Databend not supported.
+    fn rewrite_psql_command(query: &str) -> Option<String> {
+        let rules = [
+            // \l
+            (
+                r#"(?is)^SELECT\s+d\.datname\s+as\s+"Name".*FROM\s+pg_catalog\.pg_database\s+d"#,
+                "SELECT schema_name AS \"Name\", schema_owner AS \"Owner\", 'UTF8' AS \"Encoding\", \
+                'C' AS \"Collate\", 'C' AS \"Ctype\", NULL AS \"Access privileges\" \
+                FROM information_schema.schemata ORDER BY 1",
+            ),
+            // \d and \dt
+            (
+                r#"(?is)^SELECT\s+n\.nspname\s+as\s+"Schema",\s*c\.relname\s+as\s+"Name".*FROM\s+pg_catalog\.pg_class\s+c"#,
+                "SELECT table_schema AS \"Schema\", table_name AS \"Name\", \
+                CASE WHEN lower(engine) LIKE '%view' THEN 'view' ELSE 'table' END AS \"Type\", \
+                'default' AS \"Owner\" \
+                FROM information_schema.tables WHERE table_schema = current_database() ORDER BY 1, 2",
+            ),
+        ];
+        rules.iter().find_map(|(pattern, rewritten)| {
+            let re = Regex::new(pattern).ok()?;
+            re.is_match(query).then(|| rewritten.to_string())
+        })
+    }
+
+    /// Rewrite the query on the catalog of PostgreSQL to the query on information_schema.
+    ///
+    /// The tables of pg_catalog are substituted by the subqueries on information_schema,
+    /// and the `pg_catalog.` prefix of the functions is removed.
+    pub fn rewrite(&self, query: &str) -> Option<String> {
+        let lowercase = query.to_lowercase();
+        if !lowercase.contains("pg_") && !lowercase.contains("current_schema") {
+            return None;
+        }
+        if let Some(rewritten) = Self::rewrite_psql_command(query) {
+            return Some(rewritten);
+        }
+
+        let tokens = tokenize_sql(query).ok()?;
+        let mut rewritten = String::with_capacity(query.len());
+        let mut position = 0;
+        // Whether the `pg_catalog.` prefix of the current name is removed.
+        let mut prefix_removed = false;
+        for (index, token) in tokens.iter().enumerate() {
+            let kind_at = |index: usize| tokens.get(index).map(|t| t.kind);
+            let is_qualified = index > 0 && kind_at(index - 1) == Some(TokenKind::Period);
+            let is_unqualified = !is_qualified || std::mem::take(&mut prefix_removed);
+            if token.kind != TokenKind::Ident || !is_unqualified {
+                continue;
+            }
+
+            let name = token.text().to_lowercase();
+            let replacement = if kind_at(index + 1) == Some(TokenKind::Period) {
+                // pg_catalog.<name> is replaced by <name>, and the other qualifiers are kept.
+                if name == "pg_catalog" && kind_at(index + 2) == Some(TokenKind::Ident) {
+                    prefix_removed = true;
+                    Some((tokens[index + 2].span.start, String::new()))
+                } else {
+                    None
+                }
+            } else if let Some((table, subquery)) = CATALOG_TABLES.iter().find(|(t, _)| *t == name)
+            {
+                // Keep the alias of the table, or use the name of the table as its alias.
+                let has_alias = matches!(
+                    kind_at(index + 1),
+                    Some(TokenKind::AS) | Some(TokenKind::Ident) | Some(TokenKind::QuotedString)
+                );
+                if has_alias {
+                    Some((token.span.end, format!("({})", subquery)))
+                } else {
+                    Some((token.span.end, format!("({}) AS {}", subquery, table)))
+                }
+            } else if name == "current_schema" {
+                if kind_at(index + 1) == Some(TokenKind::LParen) {
+                    Some((token.span.end, "current_database".to_string()))
+                } else {
+                    Some((token.span.end, "current_database()".to_string()))
+                }
+            } else {
+                None
+            };
+
+            if let Some((end, text)) = replacement {
+                rewritten.push_str(&query[position..token.span.start]);
+                rewritten.push_str(&text);
+                position = end;
+            }
+        }
+        rewritten.push_str(&query[position..]);
+        Some(rewritten)
+    }
+}
@@ -0,0 +1,773 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use common_ast::ast::Statement as AstStatement;
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncWrite;
+use common_base::runtime::TrySpawn;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_expression::Column;
+use common_expression::DataSchemaRef;
+use common_expression::Scalar;
+use common_expression::ScalarRef;
+use common_expression::SendableDataBlockStream;
+use common_formats::field_encoder::FieldEncoderValues;
+use common_io::prelude::FormatSettings;
+use common_meta_app::principal::AuthInfo;
+use common_sql::Planner;
+use common_sql::PreparedStatement;
+use common_users::UserApiProvider;
+use futures_util::StreamExt;
+use metrics::histogram;
+use rand::RngCore;
+use tracing::error;
+use tracing::info;
+use tracing::Instrument;
+
+use crate::auth::Credential;
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterFactory;
+use crate::interpreters::InterpreterQueryLog;
+use crate::servers::postgres::postgres_codec::read_message;
+use crate::servers::postgres::postgres_codec::read_startup_message;
+use crate::servers::postgres::postgres_codec::FrontendMessage;
+use crate::servers::postgres::postgres_codec::MessageWriter;
+use crate::servers::postgres::postgres_codec::StartupMessage;
+use crate::servers::postgres::postgres_codec::TRANSACTION_IDLE;
+use crate::servers::postgres::postgres_federated::PostgresFederated;
+use crate::servers::postgres::postgres_types::field_descriptions;
+use crate::servers::postgres::postgres_types::format_at;
+use crate::servers::postgres::postgres_types::param_to_scalar;
+use crate::servers::postgres::postgres_types::write_value;
+use crate::servers::postgres::postgres_types::TEXT_OID;
+use crate::servers::postgres::postgres_types::UNSPECIFIED_OID;
+use crate::servers::postgres::POSTGRES_VERSION;
+use crate::sessions::QueryContext;
+use crate::sessions::Session;
+use crate::sessions::TableContext;
+use crate::stream::DataBlockStream;
+
+// default size of resultset write buffer: 100KB
+const DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE: usize = 100 * 1024;
+
+/// A statement created by the Parse message of the extended query protocol.
+struct Statement {
+    query: String,
+    // None if the statement is a federated command, which isn't parsed by Databend.
+    prepared: Option<PreparedStatement>,
+    param_types: Vec<u32>,
+}
+
+/// A query ready to execute, its result set is described before execution.
+struct PlannedQuery {
+    query: String,
+    schema: DataSchemaRef,
+    has_result_set: bool,
+    // None if the query is a federated command.
+    interpreter: Option<(Arc<QueryContext>, Arc<dyn Interpreter>)>,
+}
+
+/// The result set in reading, it's kept in the portal when the execution is suspended.
+struct ResultCursor {
+    blocks: SendableDataBlockStream,
+    columns: Vec<Column>,
+    row_index: usize,
+    rows: usize,
+    has_result_set: bool,
+    // The first keyword of the query, which is the tag of the CommandComplete message.
+    command: String,
+    context: Option<Arc<QueryContext>>,
+}
+
+/// A portal created by the Bind message, which is a statement with the bound parameters.
+struct Portal {
+    query: String,
+    // The parsed statement with the bound parameters, None if the query is a federated command.
+    stmt: Option<AstStatement>,
+    result_formats: Vec<i16>,
+    planned: Option<PlannedQuery>,
+    cursor: Option<ResultCursor>,
+}
+
+pub struct InteractiveWorker<R: AsyncRead + Send + Unpin, W: AsyncWrite + Send + Unpin> {
+    session: Arc<Session>,
+    client_addr: String,
+    reader: R,
+    writer: MessageWriter<W>,
+    statements: HashMap<String, Statement>,
+    portals: HashMap<String, Portal>,
+    // After an error of the extended query protocol, the messages are discarded until Sync.
+    discard_until_sync: bool,
+}
+
+impl<R: AsyncRead + Send + Unpin, W: AsyncWrite + Send + Unpin> InteractiveWorker<R, W> {
+    pub fn create(session: Arc<Session>, client_addr: String, reader: R, writer: W) -> Self {
+        InteractiveWorker {
+            session,
+            client_addr,
+            reader,
+            writer: MessageWriter::create(writer),
+            statements: HashMap::new(),
+            portals: HashMap::new(),
+            discard_until_sync: false,
+        }
+    }
+
+    pub async fn run(mut self) -> Result<()> {
+        if !self.startup().await? {
+            return Ok(());
+        }
+
+        while let Some(message) = read_message(&mut self.reader).await? {
+            if self.session.is_aborting() {
+                self.writer.error_response(
+                    "FATAL",
+                    "57P01",
+                    "Aborting this connection. because we are try aborting server.",
+                );
+                self.writer.flush().await?;
+                return Err(ErrorCode::AbortedSession(
+                    "Aborting this connection. because we are try aborting server.",
+                ));
+            }
+
+            match message {
+                FrontendMessage::Terminate => break,
+                FrontendMessage::Sync => {
+                    self.discard_until_sync = false;
+                    self.writer.ready_for_query(TRANSACTION_IDLE);
+                    self.writer.flush().await?;
+                }
+                _ if self.discard_until_sync => {}
+                FrontendMessage::Flush => self.writer.flush().await?,
+                FrontendMessage::Query(query) => {
+                    let instant = Instant::now();
+                    if let Err(cause) = self.on_query(&query).await {
+                        self.write_error(&cause.display_with_sql(&query));
+                    }
+                    histogram!(
+                        super::postgres_metrics::METRIC_POSTGRES_PROCESSOR_REQUEST_DURATION,
+                        instant.elapsed()
+                    );
+                    self.writer.ready_for_query(TRANSACTION_IDLE);
+                    self.writer.flush().await?;
+                }
+                message => {
+                    if let Err(cause) = self.on_extended_query(message).await {
+                        self.write_error(&cause);
+                        self.discard_until_sync = true;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The startup phase, returns false if the connection should be closed.
+    async fn startup(&mut self) -> Result<bool> {
+        let params = loop {
+            match read_startup_message(&mut self.reader).await? {
+                // The connection is not encrypted, the client may continue without encryption.
+                StartupMessage::SslRequest | StartupMessage::GssEncRequest => {
+                    self.writer.encryption_not_supported();
+                    self.writer.flush().await?;
+                }
+                // The queries can't be cancelled by another connection yet.
+                StartupMessage::CancelRequest => return Ok(false),
+                StartupMessage::Startup { params } => break params,
+            }
+        };
+
+        let user_name = match params.get("user") {
+            Some(user_name) => user_name.clone(),
+            None => {
+                self.writer.error_response(
+                    "FATAL",
+                    "28000",
+                    "no PostgreSQL user name specified in startup packet",
+                );
+                self.writer.flush().await?;
+                return Ok(false);
+            }
+        };
+
+        if let Err(failure) = self.authenticate(&user_name).await {
+            error!(
+                "PostgreSQL handler authenticate failed, \
+                    user_name: {}, \
+                    client_address: {}, \
+                    failure_cause: {}",
+                user_name, self.client_addr, failure
+            );
+            self.writer.error_response(
+                "FATAL",
+                "28P01",
+                &format!("password authentication failed for user \"{}\"", user_name),
+            );
+            self.writer.flush().await?;
+            return Ok(false);
+        }
+
+        if let Some(database) = params.get("database") {
+            let query = format!("USE `{}`", database.replace('`', "``"));
+            // The database is the user name by default of libpq, which may not exist.
+            match self.do_query(&query).await {
+                Err(_) if database == &user_name => {}
+                Err(cause) => {
+                    self.writer
+                        .error_response("FATAL", "3D000", &cause.message());
+                    self.writer.flush().await?;
+                    return Ok(false);
+                }
+                Ok(_) => {}
+            }
+        }
+
+        let format = self.session.get_format_settings()?;
+        self.writer.authentication_ok();
+        for (name, value) in [
+            ("server_version", POSTGRES_VERSION),
+            ("server_encoding", "UTF8"),
+            ("client_encoding", "UTF8"),
+            ("DateStyle", "ISO, MDY"),
+            ("IntervalStyle", "postgres"),
+            ("TimeZone", format.timezone.name()),
+            ("integer_datetimes", "on"),
+            ("standard_conforming_strings", "on"),
+            ("is_superuser", "off"),
+            ("session_authorization", user_name.as_str()),
+            (
+                "application_name",
+                params.get("application_name").map_or("", |s| s.as_str()),
+            ),
+        ] {
+            self.writer.parameter_status(name, value);
+        }
+        let mut rng = rand::thread_rng();
+        self.writer
+            .backend_key_data(rng.next_u32() as i32, rng.next_u32() as i32);
+        self.writer.ready_for_query(TRANSACTION_IDLE);
+        self.writer.flush().await?;
+        Ok(true)
+    }
+
+    async fn authenticate(&mut self, user_name: &str) -> Result<()> {
+        let client_ip = self.client_addr.split(':').collect::<Vec<_>>()[0].to_string();
+        let ctx = self.session.create_query_context().await?;
+        let user_info = UserApiProvider::instance()
+            .get_user_with_client_ip(&ctx.get_tenant(), user_name, &client_ip)
+            .await;
+
+        // The password is only asked for the users with password.
+        let password = match user_info {
+            Ok(user_info) if matches!(user_info.auth_info, AuthInfo::None) => None,
+            _ => {
+                self.writer.authentication_cleartext_password();
+                self.writer.flush().await?;
+                match read_message(&mut self.reader).await? {
+                    Some(FrontendMessage::Password(password)) => Some(password.into_bytes()),
+                    _ => {
+                        return Err(ErrorCode::AuthenticateFailure(
+                            "Expected the password response",
+                        ));
+                    }
+                }
+            }
+        };
+
+        let credential = Credential::Password {
+            name: user_name.to_string(),
+            password,
+            hostname: Some(client_ip),
+        };
+        ctx.get_auth_manager()
+            .auth(self.session.clone(), &credential)
+            .await
+    }
+
+    fn write_error(&mut self, error: &ErrorCode) {
+        let sqlstate = match error.code() {
+            ErrorCode::SYNTAX_EXCEPTION => "42601",
+            ErrorCode::SEMANTIC_ERROR => "42000",
+            ErrorCode::UNKNOWN_DATABASE => "3D000",
+            ErrorCode::UNKNOWN_TABLE => "42P01",
+            ErrorCode::UNKNOWN_COLUMN => "42703",
+            ErrorCode::PERMISSION_DENIED => "42501",
+            ErrorCode::ABORTED_QUERY | ErrorCode::ABORTED_SESSION => "57014",
+            _ => "XX000",
+        };
+        if error.code() != ErrorCode::ABORTED_QUERY && error.code() != ErrorCode::ABORTED_SESSION {
+            error!("OnQuery Error: {:?}", error);
+        }
+        self.writer
+            .error_response("ERROR", sqlstate, &error.to_string());
+    }
+
+    // The simple query protocol, the query is planned and executed at once.
+    async fn on_query(&mut self, query: &str) -> Result<()> {
+        if query.trim().trim_end_matches(';').trim().is_empty() {
+            self.writer.empty_query_response();
+            return Ok(());
+        }
+
+        let planned = self.plan_query(query, None).await?;
+        if planned.has_result_set {
+            let fields = field_descriptions(&planned.schema, &[])?;
+            self.writer.row_description(&fields);
+        }
+        let mut cursor = self.execute_query(planned).await?;
+        self.write_rows(&mut cursor, &[], 0).await?;
+        self.write_command_complete(&cursor);
+        Ok(())
+    }
+
+    async fn on_extended_query(&mut self, message: FrontendMessage) -> Result<()> {
+        match message {
+            FrontendMessage::Parse {
+                name,
+                query,
+                param_types,
+            } => {
+                let statement = self.do_parse(&query, param_types)?;
+                self.statements.insert(name, statement);
+                self.writer.parse_complete();
+            }
+            FrontendMessage::Bind {
+                portal,
+                statement,
+                param_formats,
+                params,
+                result_formats,
+            } => {
+                let (query, stmt) = self.do_bind(&statement, &param_formats, &params)?;
+                self.portals.insert(portal, Portal {
+                    query,
+                    stmt,
+                    result_formats,
+                    planned: None,
+                    cursor: None,
+                });
+                self.writer.bind_complete();
+            }
+            FrontendMessage::Describe { kind: b'S', name } => {
+                self.describe_statement(&name).await?
+            }
+            FrontendMessage::Describe { kind: b'P', name } => self.describe_portal(&name).await?,
+            FrontendMessage::Execute { portal, max_rows } => {
+                self.do_execute(&portal, max_rows.max(0) as usize).await?
+            }
+            FrontendMessage::Close { kind, name } => {
+                if kind == b'S' {
+                    self.statements.remove(&name);
+                } else {
+                    self.portals.remove(&name);
+                }
+                self.writer.close_complete();
+            }
+            FrontendMessage::Describe { kind, .. } => {
+                return Err(ErrorCode::BadBytes(format!(
+                    "Invalid describe kind '{}'",
+                    kind as char
+                )));
+            }
+            _ => {
+                return Err(ErrorCode::BadBytes(
+                    "Unexpected message of the extended query protocol",
+                ));
+            }
+        }
+
+        if self.writer.buffered_size() > DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE {
+            self.writer.flush().await?;
+        }
+        Ok(())
+    }
+
+    fn do_parse(&mut self, query: &str, param_types: Vec<u32>) -> Result<Statement> {
+        info!("Prepare query: {}", query);
+        let federated = PostgresFederated::create();
+        let query = federated
+            .rewrite(query)
+            .unwrap_or_else(|| query.to_string());
+        if federated.check(&query).is_some() {
+            return Ok(Statement {
+                query,
+                prepared: None,
+                param_types: vec![],
+            });
+        }
+
+        let sql_dialect = self.session.get_settings().get_sql_dialect()?;
+        let prepared = PreparedStatement::try_create(&query, sql_dialect)
+            .map_err(|e| e.display_with_sql(&query))?;
+        // The types of the parameters not specified by the client are text,
+        // they are cast by the query after bound as strings.
+        let param_types = (0..prepared.num_params())
+            .map(|i| match param_types.get(i) {
+                Some(oid) if *oid != UNSPECIFIED_OID => *oid,
+                _ => TEXT_OID,
+            })
+            .collect();
+        Ok(Statement {
+            query,
+            prepared: Some(prepared),
+            param_types,
+        })
+    }
+
+    fn do_bind(
+        &self,
+        statement: &str,
+        param_formats: &[i16],
+        params: &[Option<Vec<u8>>],
+    ) -> Result<(String, Option<AstStatement>)> {
+        let statement = self.statements.get(statement).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Unknown prepared statement \"{}\"", statement))
+        })?;
+        let prepared = match &statement.prepared {
+            None if params.is_empty() => return Ok((statement.query.clone(), None)),
+            None => {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Statement requires 0 parameters, but got {}",
+                    params.len()
+                )));
+            }
+            Some(prepared) => prepared,
+        };
+
+        let params = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let type_oid = statement.param_types.get(i).copied().unwrap_or(TEXT_OID);
+                param_to_scalar(type_oid, format_at(param_formats, i), param.as_deref())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let stmt = prepared
+            .bind(&params)
+            .map_err(|e| e.display_with_sql(&statement.query))?;
+        Ok((statement.query.clone(), Some(stmt)))
+    }
+
+    async fn describe_statement(&mut self, name: &str) -> Result<()> {
+        let statement = self.statements.get(name).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Unknown prepared statement \"{}\"", name))
+        })?;
+        let param_types = statement.param_types.clone();
+        // The result set is described by the query with NULL parameters.
+        let query = statement.query.clone();
+        let stmt = match &statement.prepared {
+            None => None,
+            Some(prepared) => {
+                let params = vec![Scalar::Null; prepared.num_params()];
+                Some(prepared.bind(&params)?)
+            }
+        };
+
+        let planned = self.plan_query(&query, stmt).await?;
+        self.writer.parameter_description(&param_types);
+        if planned.has_result_set {
+            let fields = field_descriptions(&planned.schema, &[])?;
+            self.writer.row_description(&fields);
+        } else {
+            self.writer.no_data();
+        }
+        Ok(())
+    }
+
+    async fn describe_portal(&mut self, name: &str) -> Result<()> {
+        let (query, stmt) = match self.portals.get(name) {
+            Some(portal) => (portal.query.clone(), portal.stmt.clone()),
+            None => {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Unknown portal \"{}\"",
+                    name
+                )));
+            }
+        };
+
+        // The query is planned on describe, and executed with the same plan.
+        let planned = self.plan_query(&query, stmt).await?;
+        let portal = self.portals.get_mut(name).unwrap();
+        if planned.has_result_set {
+            let fields = field_descriptions(&planned.schema, &portal.result_formats)?;
+            self.writer.row_description(&fields);
+        } else {
+            self.writer.no_data();
+        }
+        portal.planned = Some(planned);
+        Ok(())
+    }
+
+    async fn do_execute(&mut self, name: &str, max_rows: usize) -> Result<()> {
+        let mut portal = self
+            .portals
+            .remove(name)
+            .ok_or_else(|| ErrorCode::BadArguments(format!("Unknown portal \"{}\"", name)))?;
+
+        let mut cursor = match portal.cursor.take() {
+            Some(cursor) => cursor,
+            None => {
+                let planned = match portal.planned.take() {
+                    Some(planned) => planned,
+                    None => self.plan_query(&portal.query, portal.stmt.clone()).await?,
+                };
+                // Check the result formats before execution.
+                field_descriptions(&planned.schema, &portal.result_formats)?;
+                self.execute_query(planned).await?
+            }
+        };
+
+        let completed = self
+            .write_rows(&mut cursor, &portal.result_formats, max_rows)
+            .await?;
+        if completed {
+            self.write_command_complete(&cursor);
+        } else {
+            self.writer.portal_suspended();
+            portal.cursor = Some(cursor);
+        }
+        // The unnamed portal is kept until the next Bind or the end of the transaction.
+        self.portals.insert(name.to_string(), portal);
+        Ok(())
+    }
+
+    // Write the rows of the result set, returns whether all the rows are written.
+    async fn write_rows(
+        &mut self,
+        cursor: &mut ResultCursor,
+        result_formats: &[i16],
+        max_rows: usize,
+    ) -> Result<bool> {
+        let format = self.session.get_format_settings()?;
+        let encoder = FieldEncoderValues::create_for_postgres_handler(format.timezone);
+        let mut written = 0;
+        loop {
+            if cursor.row_index >= cursor.columns.first().map_or(0, |c| c.len()) {
+                match cursor.blocks.next().await {
+                    None => return Ok(true),
+                    Some(block) => {
+                        cursor.columns = block?
+                            .convert_to_full()
+                            .columns()
+                            .iter()
+                            .map(|column| column.value.clone().into_column().unwrap())
+                            .collect();
+                        cursor.row_index = 0;
+                        continue;
+                    }
+                }
+            }
+            if max_rows > 0 && written >= max_rows {
+                return Ok(false);
+            }
+
+            self.write_row(cursor, result_formats, &encoder, &format)?;
+            cursor.row_index += 1;
+            cursor.rows += 1;
+            written += 1;
+            if self.writer.buffered_size() > DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE {
+                self.writer.flush().await?;
+            }
+        }
+    }
+
+    fn write_row(
+        &mut self,
+        cursor: &ResultCursor,
+        result_formats: &[i16],
+        encoder: &FieldEncoderValues,
+        format: &FormatSettings,
+    ) -> Result<()> {
+        self.writer.begin_data_row(cursor.columns.len());
+        for (index, column) in cursor.columns.iter().enumerate() {
+            let row_index = cursor.row_index;
+            match unsafe { column.index_unchecked(row_index) } {
+                ScalarRef::Null => self.writer.put_null(),
+                _ => {
+                    let result_format = format_at(result_formats, index);
+                    self.writer.put_value(|out| {
+                        write_value(
+                            column,
+                            row_index,
+                            result_format,
+                            encoder,
+                            format.timezone,
+                            out,
+                        )
+                    })?;
+                }
+            }
+        }
+        self.writer.end_data_row();
+        Ok(())
+    }
+
+    fn write_command_complete(&mut self, cursor: &ResultCursor) {
+        if cursor.has_result_set {
+            self.writer
+                .command_complete(&format!("SELECT {}", cursor.rows));
+            return;
+        }
+
+        let rows = cursor
+            .context
+            .as_ref()
+            .map_or(0, |ctx| ctx.get_write_progress_value().rows);
+        let tag = match cursor.command.as_str() {
+            "UPDATE" | "DELETE" | "COPY" => format!("{} {}", cursor.command, rows),
+            "INSERT" | "REPLACE" => format!("INSERT 0 {}", rows),
+            _ => cursor.command.clone(),
+        };
+        self.writer.command_complete(&tag);
+    }
+
+    // Plan the query, the result set is known before the execution. The statement of
+    // a prepared query is parsed and bound already.
+    async fn plan_query(
+        &mut self,
+        query: &str,
+        stmt: Option<AstStatement>,
+    ) -> Result<PlannedQuery> {
+        let federated = PostgresFederated::create();
+        let query = federated
+            .rewrite(query)
+            .unwrap_or_else(|| query.to_string());
+        if let Some((schema, _)) = federated.check(&query) {
+            return Ok(PlannedQuery {
+                has_result_set: schema.num_fields() > 0,
+                query,
+                schema,
+                interpreter: None,
+            });
+        }
+
+        let context = self.session.create_query_context().await?;
+        let mut planner = Planner::new(context.clone());
+        let plan = match stmt {
+            Some(stmt) => planner.plan_stmt(stmt).await?.0,
+            None => planner.plan_sql(&query).await?.0,
+        };
+        context.attach_query_str(plan.to_string(), &query);
+        let interpreter = match InterpreterFactory::get(context.clone(), &plan).await {
+            Ok(interpreter) => interpreter,
+            Err(e) => {
+                InterpreterQueryLog::fail_to_start(context, e.clone());
+                return Err(e);
+            }
+        };
+        Ok(PlannedQuery {
+            schema: interpreter.schema(),
+            has_result_set: plan.has_result_set(),
+            query,
+            interpreter: Some((context, interpreter)),
+        })
+    }
+
+    async fn execute_query(&mut self, planned: PlannedQuery) -> Result<ResultCursor> {
+        let (blocks, context) = match planned.interpreter {
+            None => {
+                info!("Federated query: {}", planned.query);
+                let federated = PostgresFederated::create();
+                let blocks = match federated.check(&planned.query) {
+                    Some((_, block)) if block.num_rows() > 0 => vec![block],
+                    _ => vec![],
+                };
+                (DataBlockStream::create(None, blocks).boxed(), None)
+            }
+            Some((context, interpreter)) => {
+                info!("Normal query: {}", planned.query);
+                let blocks = Self::exec_query(interpreter, &context).await?;
+                (blocks, Some(context))
+            }
+        };
+
+        let mut cursor = ResultCursor {
+            blocks,
+            columns: vec![],
+            row_index: 0,
+            rows: 0,
+            has_result_set: planned.has_result_set,
+            command: command_of_query(&planned.query),
+            context,
+        };
+        // For statements without result sets, we still need to pull the stream because errors may occur in the stream.
+        if !cursor.has_result_set {
+            while let Some(block) = cursor.blocks.next().await {
+                block?;
+            }
+        }
+        Ok(cursor)
+    }
+
+    async fn do_query(&mut self, query: &str) -> Result<()> {
+        let planned = self.plan_query(query, None).await?;
+        let mut cursor = self.execute_query(planned).await?;
+        while let Some(block) = cursor.blocks.next().await {
+            block?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip(interpreter, context))]
+    async fn exec_query(
+        interpreter: Arc<dyn Interpreter>,
+        context: &Arc<QueryContext>,
+    ) -> Result<SendableDataBlockStream> {
+        let instant = Instant::now();
+
+        let query_result = context.try_spawn({
+            let ctx = context.clone();
+            async move {
+                let mut data_stream = interpreter.execute(ctx.clone()).await?;
+                histogram!(
+                    super::postgres_metrics::METRIC_INTERPRETER_USEDTIME,
+                    instant.elapsed()
+                );
+
+                // Wrap the data stream, log finish event at the end of stream
+                let intercepted_stream = async_stream::stream! {
+
+                    while let Some(item) = data_stream.next().await {
+                        yield item
+                    };
+                };
+
+                Ok::<_, ErrorCode>(intercepted_stream.boxed())
+            }
+            .in_current_span()
+        })?;
+
+        query_result.await.map_err_to_code(
+            ErrorCode::TokioError,
+            || "Cannot join handle from context's runtime",
+        )?
+    }
+}
+
+// The command tag of the CommandComplete message is the first keyword of the query.
+fn command_of_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(';')
+        .to_uppercase()
+}
@@ -0,0 +1,244 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::Offset;
+use chrono_tz::Tz;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::date_helper::DateConverter;
+use common_expression::types::number::NumberScalar;
+use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
+use common_expression::Column;
+use common_expression::DataSchemaRef;
+use common_expression::Scalar;
+use common_expression::ScalarRef;
+use common_formats::field_encoder::FieldEncoderRowBased;
+use common_formats::field_encoder::FieldEncoderValues;
+
+use crate::servers::postgres::postgres_codec::FieldDescription;
+use crate::servers::postgres::postgres_codec::FORMAT_BINARY;
+use crate::servers::postgres::postgres_codec::FORMAT_TEXT;
+
+// The oids of the types in pg_catalog.pg_type.
+pub const UNSPECIFIED_OID: u32 = 0;
+pub const BOOL_OID: u32 = 16;
+pub const INT8_OID: u32 = 20;
+pub const INT2_OID: u32 = 21;
+pub const INT4_OID: u32 = 23;
+pub const TEXT_OID: u32 = 25;
+pub const JSON_OID: u32 = 114;
+pub const FLOAT4_OID: u32 = 700;
+pub const FLOAT8_OID: u32 = 701;
+pub const VARCHAR_OID: u32 = 1043;
+pub const DATE_OID: u32 = 1082;
+pub const TIMESTAMP_OID: u32 = 1114;
+pub const NUMERIC_OID: u32 = 1700;
+
+// The epoch of PostgreSQL is 2000-01-01, which is 10957 days after the unix epoch.
+const PG_EPOCH_DAYS: i32 = 10957;
+const PG_EPOCH_MICROS: i64 = PG_EPOCH_DAYS as i64 * 24 * 3600 * 1_000_000;
+
+/// The unsigned integers are widen to the next signed type, and the nested types are sent as text.
+pub fn type_oid(data_type: &DataType) -> u32 {
+    match data_type.remove_nullable() {
+        DataType::Boolean => BOOL_OID,
+        DataType::Number(num_ty) => match num_ty {
+            NumberDataType::Int8 | NumberDataType::UInt8 | NumberDataType::Int16 => INT2_OID,
+            NumberDataType::UInt16 | NumberDataType::Int32 => INT4_OID,
+            NumberDataType::UInt32 | NumberDataType::Int64 | NumberDataType::UInt64 => INT8_OID,
+            NumberDataType::Float32 => FLOAT4_OID,
+            NumberDataType::Float64 => FLOAT8_OID,
+        },
+        DataType::Decimal(_) => NUMERIC_OID,
+        DataType::Date => DATE_OID,
+        DataType::Timestamp => TIMESTAMP_OID,
+        DataType::Variant => JSON_OID,
+        _ => TEXT_OID,
+    }
+}
+
+fn type_size(oid: u32) -> i16 {
+    match oid {
+        BOOL_OID => 1,
+        INT2_OID => 2,
+        INT4_OID | FLOAT4_OID | DATE_OID => 4,
+        INT8_OID | FLOAT8_OID | TIMESTAMP_OID => 8,
+        _ => -1,
+    }
+}
+
+/// The format of the column or the parameter at the index, an empty `formats` means text
+/// for all, and a single format applies to all.
+pub fn format_at(formats: &[i16], index: usize) -> i16 {
+    match formats.len() {
+        0 => FORMAT_TEXT,
+        1 => formats[0],
+        _ => formats.get(index).copied().unwrap_or(FORMAT_TEXT),
+    }
+}
+
+pub fn field_descriptions(
+    schema: &DataSchemaRef,
+    result_formats: &[i16],
+) -> Result<Vec<FieldDescription>> {
+    schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let type_oid = type_oid(field.data_type());
+            let format = format_at(result_formats, index);
+            if format == FORMAT_BINARY && type_oid == NUMERIC_OID {
+                return Err(ErrorCode::Unimplemented(format!(
+                    "Binary format of the column {} with type {} is not supported",
+                    field.name(),
+                    field.data_type()
+                )));
+            }
+            Ok(FieldDescription {
+                name: field.name().to_string(),
+                type_oid,
+                type_size: type_size(type_oid),
+                format,
+            })
+        })
+        .collect()
+}
+
+/// Encode a not null value of the column in the text or binary format.
+pub fn write_value(
+    column: &Column,
+    row_index: usize,
+    format: i16,
+    encoder: &FieldEncoderValues,
+    tz: Tz,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    if format == FORMAT_TEXT {
+        encoder.write_field(column, row_index, out, true);
+        return Ok(());
+    }
+
+    match unsafe { column.index_unchecked(row_index) } {
+        ScalarRef::Boolean(v) => out.push(v as u8),
+        ScalarRef::Number(number) => match number {
+            NumberScalar::Int8(v) => out.extend_from_slice(&(v as i16).to_be_bytes()),
+            NumberScalar::UInt8(v) => out.extend_from_slice(&(v as i16).to_be_bytes()),
+            NumberScalar::Int16(v) => out.extend_from_slice(&v.to_be_bytes()),
+            NumberScalar::UInt16(v) => out.extend_from_slice(&(v as i32).to_be_bytes()),
+            NumberScalar::Int32(v) => out.extend_from_slice(&v.to_be_bytes()),
+            NumberScalar::UInt32(v) => out.extend_from_slice(&(v as i64).to_be_bytes()),
+            NumberScalar::Int64(v) => out.extend_from_slice(&v.to_be_bytes()),
+            NumberScalar::UInt64(v) => {
+                let v = i64::try_from(v).map_err(|_| {
+                    ErrorCode::BadArguments(format!("Value {} is out of range of int8", v))
+                })?;
+                out.extend_from_slice(&v.to_be_bytes())
+            }
+            NumberScalar::Float32(v) => out.extend_from_slice(&v.0.to_be_bytes()),
+            NumberScalar::Float64(v) => out.extend_from_slice(&v.0.to_be_bytes()),
+        },
+        ScalarRef::Date(v) => out.extend_from_slice(&(v - PG_EPOCH_DAYS).to_be_bytes()),
+        ScalarRef::Timestamp(v) => {
+            // The timestamp without time zone is the local time in the session timezone,
+            // the same as the text format.
+            let offset = v.to_timestamp(tz).offset().fix().local_minus_utc() as i64;
+            let micros = v + offset * 1_000_000 - PG_EPOCH_MICROS;
+            out.extend_from_slice(&micros.to_be_bytes())
+        }
+        ScalarRef::String(v) => out.extend_from_slice(v),
+        // The binary format of text and json is the same as the text format.
+        _ => encoder.write_field(column, row_index, out, true),
+    }
+    Ok(())
+}
+
+/// Decode the parameter of the Bind message by the type specified in the Parse message.
+pub fn param_to_scalar(type_oid: u32, format: i16, value: Option<&[u8]>) -> Result<Scalar> {
+    let value = match value {
+        None => return Ok(Scalar::Null),
+        Some(value) => value,
+    };
+
+    if format == FORMAT_TEXT {
+        let text = std::str::from_utf8(value)
+            .map_err(|e| ErrorCode::BadBytes(format!("Invalid utf8 parameter: {}", e)))?;
+        let scalar = match type_oid {
+            BOOL_OID => match text {
+                "t" | "true" | "1" => Scalar::Boolean(true),
+                "f" | "false" | "0" => Scalar::Boolean(false),
+                _ => return Err(invalid_param(type_oid, text)),
+            },
+            INT2_OID | INT4_OID | INT8_OID => text
+                .parse::<i64>()
+                .map(|v| Scalar::Number(NumberScalar::Int64(v)))
+                .map_err(|_| invalid_param(type_oid, text))?,
+            FLOAT4_OID | FLOAT8_OID => text
+                .parse::<f64>()
+                .map(|v| Scalar::Number(NumberScalar::Float64(v.into())))
+                .map_err(|_| invalid_param(type_oid, text))?,
+            // The others are bound as strings, they are cast by the query.
+            _ => Scalar::String(value.to_vec()),
+        };
+        return Ok(scalar);
+    }
+
+    let scalar = match (type_oid, value.len()) {
+        (BOOL_OID, 1) => Scalar::Boolean(value[0] != 0),
+        (INT2_OID, 2) => Scalar::Number(NumberScalar::Int16(i16::from_be_bytes(
+            value.try_into().unwrap(),
+        ))),
+        (INT4_OID, 4) => Scalar::Number(NumberScalar::Int32(i32::from_be_bytes(
+            value.try_into().unwrap(),
+        ))),
+        (INT8_OID, 8) => Scalar::Number(NumberScalar::Int64(i64::from_be_bytes(
+            value.try_into().unwrap(),
+        ))),
+        (FLOAT4_OID, 4) => Scalar::Number(NumberScalar::Float32(
+            f32::from_be_bytes(value.try_into().unwrap()).into(),
+        )),
+        (FLOAT8_OID, 8) => Scalar::Number(NumberScalar::Float64(
+            f64::from_be_bytes(value.try_into().unwrap()).into(),
+        )),
+        (DATE_OID, 4) => {
+            let days = i32::from_be_bytes(value.try_into().unwrap());
+            Scalar::Date(days.checked_add(PG_EPOCH_DAYS).ok_or_else(|| {
+                ErrorCode::BadBytes(format!("Date parameter {} out of range", days))
+            })?)
+        }
+        (TIMESTAMP_OID, 8) => {
+            let micros = i64::from_be_bytes(value.try_into().unwrap());
+            Scalar::Timestamp(micros.checked_add(PG_EPOCH_MICROS).ok_or_else(|| {
+                ErrorCode::BadBytes(format!("Timestamp parameter {} out of range", micros))
+            })?)
+        }
+        (UNSPECIFIED_OID | TEXT_OID | VARCHAR_OID | JSON_OID, _) => Scalar::String(value.to_vec()),
+        _ => {
+            return Err(ErrorCode::BadBytes(format!(
+                "Unsupported binary parameter of type oid {} with length {}",
+                type_oid,
+                value.len()
+            )));
+        }
+    };
+    Ok(scalar)
+}
+
+fn invalid_param(type_oid: u32, text: &str) -> ErrorCode {
+    ErrorCode::BadArguments(format!(
+        "Invalid parameter '{}' of type oid {}",
+        text, type_oid
+    ))
+}
@@ -28,6 +28,8 @@ use tokio_stream::wrappers::TcpListenerStream;
 use tracing::error;
 use tracing::info;
 
+use crate::auto_compaction::AutoCompactionService;
+use crate::auto_purge::AutoPurgeService;
 use crate::clusters::ClusterDiscovery;
 use crate::sessions::SessionManager;
 
@@ -63,6 +65,8 @@ impl ShutdownHandle {
 
     pub async fn shutdown(&mut self, mut signal: SignalStream) {
         self.shutdown_services(true).await;
+        AutoCompactionService::instance().shutdown().await;
+        AutoPurgeService::instance().shutdown().await;
         ClusterDiscovery::instance()
             .unregister_to_metastore(&mut signal)
             .await;
@@ -34,6 +34,7 @@ use common_catalog::plan::PartInfoPtr;
 use common_catalog::plan::Partitions;
 use common_catalog::plan::StageTableInfo;
 use common_catalog::table_args::TableArgs;
+use common_catalog::table_context::QueryParams;
 use common_catalog::table_context::StageAttachment;
 use common_config::DATABEND_COMMIT_VERSION;
 use common_exception::ErrorCode;
@@ -181,6 +182,11 @@ impl QueryContext {
         self.shared.get_data_metrics()
     }
 
+    /// Get the tables accessed by the query.
+    pub fn get_tables_refs(&self) -> Vec<Arc<dyn Table>> {
+        self.shared.get_tables_refs()
+    }
+
     pub fn set_affect(self: &Arc<Self>, affect: QueryAffect) {
         self.shared.set_affect(affect)
     }
@@ -193,6 +199,10 @@ impl QueryContext {
         self.shared.attach_stage(attachment);
     }
 
+    pub fn attach_query_params(&self, params: QueryParams) {
+        self.shared.attach_query_params(params);
+    }
+
     pub fn get_created_time(&self) -> SystemTime {
         self.shared.created_time
     }
@@ -376,6 +386,10 @@ impl TableContext for QueryContext {
         self.shared.get_stage_attachment()
     }
 
+    fn get_query_params(&self) -> Option<QueryParams> {
+        self.shared.get_query_params()
+    }
+
     fn set_on_error_map(&self, map: Option<HashMap<String, ErrorCode>>) {
         self.shared.set_on_error_map(map);
     }
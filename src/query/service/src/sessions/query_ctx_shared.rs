@@ -22,6 +22,7 @@ use std::time::SystemTime;
 
 use common_base::base::Progress;
 use common_base::runtime::Runtime;
+use common_catalog::table_context::QueryParams;
 use common_catalog::table_context::StageAttachment;
 use common_config::InnerConfig;
 use common_exception::ErrorCode;
@@ -78,6 +79,7 @@ pub struct QueryContextShared {
     pub(in crate::sessions) executor: Arc<RwLock<Weak<PipelineExecutor>>>,
     pub(in crate::sessions) precommit_blocks: Arc<RwLock<Vec<DataBlock>>>,
     pub(in crate::sessions) stage_attachment: Arc<RwLock<Option<StageAttachment>>>,
+    pub(in crate::sessions) query_params: Arc<RwLock<Option<QueryParams>>>,
     pub(in crate::sessions) created_time: SystemTime,
     pub(in crate::sessions) on_error_map: Arc<RwLock<Option<HashMap<String, ErrorCode>>>>,
     /// partitions_sha for each table in the query. Not empty only when enabling query result cache.
@@ -110,6 +112,7 @@ impl QueryContextShared {
             executor: Arc::new(RwLock::new(Weak::new())),
             precommit_blocks: Arc::new(RwLock::new(vec![])),
             stage_attachment: Arc::new(RwLock::new(None)),
+            query_params: Arc::new(RwLock::new(None)),
             created_time: SystemTime::now(),
             on_error_map: Arc::new(RwLock::new(None)),
             partitions_shas: Arc::new(RwLock::new(vec![])),
@@ -331,6 +334,14 @@ impl QueryContextShared {
         *stage_attachment = Some(attachment);
     }
 
+    pub fn get_query_params(&self) -> Option<QueryParams> {
+        self.query_params.read().clone()
+    }
+
+    pub fn attach_query_params(&self, params: QueryParams) {
+        *self.query_params.write() = Some(params);
+    }
+
     pub fn get_created_time(&self) -> SystemTime {
         self.created_time
     }
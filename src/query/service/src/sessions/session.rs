@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -22,9 +23,11 @@ use common_exception::Result;
 use common_io::prelude::FormatSettings;
 use common_meta_app::principal::GrantObject;
 use common_meta_app::principal::RoleInfo;
+use common_meta_app::principal::SessionState;
 use common_meta_app::principal::UserInfo;
 use common_meta_app::principal::UserPrivilegeType;
 use common_settings::Settings;
+use common_sql::PreparedStatement;
 use common_users::RoleCacheManager;
 use common_users::BUILTIN_ROLE_PUBLIC;
 use futures::channel::*;
@@ -344,6 +347,39 @@ impl Session {
         self.session_ctx.apply_changed_settings(changed_settings)
     }
 
+    // Export the state of the session, so it can be resumed by `restore_state` on any node.
+    pub fn export_state(self: &Arc<Self>) -> Result<SessionState> {
+        let mut settings = BTreeMap::new();
+        for (name, value, ..) in self.get_changed_settings().get_setting_values() {
+            settings.insert(name, value.as_string()?);
+        }
+        Ok(SessionState {
+            user: self.get_current_user()?.identity(),
+            database: self.get_current_database(),
+            role: self.get_current_role().map(|role| role.name),
+            settings,
+        })
+    }
+
+    // Restore the state exported by `export_state`. The session must be authenticated as the
+    // user who owns the state, and the role is checked again since the grants may be changed.
+    pub async fn restore_state(self: &Arc<Self>, state: &SessionState) -> Result<()> {
+        if self.get_current_user()?.identity() != state.user {
+            return Err(ErrorCode::PermissionDenied(
+                "Permission denied, the session state belongs to another user",
+            ));
+        }
+        self.set_current_database(state.database.clone());
+        if let Some(role) = &state.role {
+            self.set_current_role_checked(role).await?;
+        }
+        let settings = self.get_settings();
+        for (name, value) in &state.settings {
+            settings.set_settings(name.clone(), value.clone(), false)?;
+        }
+        Ok(())
+    }
+
     pub fn get_memory_usage(self: &Arc<Self>) -> usize {
         // TODO(winter): use thread memory tracker
         0
@@ -352,6 +388,18 @@ impl Session {
     pub fn get_status(self: &Arc<Self>) -> Arc<RwLock<SessionStatus>> {
         self.status.clone()
     }
+
+    pub fn add_prepared_statement(self: &Arc<Self>, statement: PreparedStatement) -> u32 {
+        self.session_ctx.add_prepared_statement(statement)
+    }
+
+    pub fn get_prepared_statement(self: &Arc<Self>, id: u32) -> Option<Arc<PreparedStatement>> {
+        self.session_ctx.get_prepared_statement(id)
+    }
+
+    pub fn remove_prepared_statement(self: &Arc<Self>, id: u32) {
+        self.session_ctx.remove_prepared_statement(id)
+    }
 }
 
 impl Drop for Session {
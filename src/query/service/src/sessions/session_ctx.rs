@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Weak;
@@ -23,6 +25,7 @@ use common_exception::Result;
 use common_meta_app::principal::RoleInfo;
 use common_meta_app::principal::UserInfo;
 use common_settings::Settings;
+use common_sql::PreparedStatement;
 use futures::channel::oneshot::Sender;
 use parking_lot::RwLock;
 
@@ -51,6 +54,10 @@ pub struct SessionContext {
     client_host: RwLock<Option<SocketAddr>>,
     io_shutdown_tx: RwLock<Option<Sender<Sender<()>>>>,
     query_context_shared: RwLock<Weak<QueryContextShared>>,
+    // The statements prepared by the client, they are kept until the client closes them
+    // or the session is destroyed.
+    prepared_statements: RwLock<HashMap<u32, Arc<PreparedStatement>>>,
+    next_statement_id: AtomicU32,
 }
 
 impl SessionContext {
@@ -67,6 +74,8 @@ impl SessionContext {
             current_database: RwLock::new("default".to_string()),
             io_shutdown_tx: Default::default(),
             query_context_shared: Default::default(),
+            prepared_statements: Default::default(),
+            next_statement_id: AtomicU32::new(1),
         }))
     }
 
@@ -212,4 +221,22 @@ impl SessionContext {
         let mut lock = self.query_context_shared.write();
         *lock = ctx
     }
+
+    // Cache the prepared statement, returns the statement id.
+    pub fn add_prepared_statement(&self, statement: PreparedStatement) -> u32 {
+        let id = self.next_statement_id.fetch_add(1, Ordering::Relaxed);
+        let mut lock = self.prepared_statements.write();
+        lock.insert(id, Arc::new(statement));
+        id
+    }
+
+    pub fn get_prepared_statement(&self, id: u32) -> Option<Arc<PreparedStatement>> {
+        let lock = self.prepared_statements.read();
+        lock.get(&id).cloned()
+    }
+
+    pub fn remove_prepared_statement(&self, id: u32) {
+        let mut lock = self.prepared_statements.write();
+        lock.remove(&id);
+    }
 }
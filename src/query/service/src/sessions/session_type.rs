@@ -18,6 +18,8 @@ use std::fmt;
 pub enum SessionType {
     Clickhouse,
     MySQL,
+    Postgres,
+    FlightSQL,
     HTTPQuery,
     HTTPStreamingLoad,
     ClickHouseHttpHandler,
@@ -26,13 +28,19 @@ pub enum SessionType {
     Dummy,
     Fuzz,
     Local,
+    AutoCompaction,
+    AutoPurge,
 }
 
 impl SessionType {
     pub fn is_user_session(&self) -> bool {
         !matches!(
             self,
-            SessionType::HTTPAPI(_) | SessionType::Dummy | SessionType::Fuzz
+            SessionType::HTTPAPI(_)
+                | SessionType::Dummy
+                | SessionType::Fuzz
+                | SessionType::AutoCompaction
+                | SessionType::AutoPurge
         )
     }
 }
@@ -43,6 +51,8 @@ impl fmt::Display for SessionType {
             SessionType::ClickHouseHttpHandler => "ClickhouseHTTPHandler".to_string(),
             SessionType::Clickhouse => "Clickhouse".to_string(),
             SessionType::MySQL => "MySQL".to_string(),
+            SessionType::Postgres => "Postgres".to_string(),
+            SessionType::FlightSQL => "FlightSQL".to_string(),
             SessionType::HTTPQuery => "HTTPQuery".to_string(),
             SessionType::HTTPStreamingLoad => "HTTPStreamingLoad".to_string(),
             SessionType::Dummy => "Dummy".to_string(),
@@ -50,6 +60,8 @@ impl fmt::Display for SessionType {
             SessionType::HTTPAPI(usage) => format!("HTTPAPI({})", usage),
             SessionType::Fuzz => "Fuzz".to_string(),
             SessionType::Local => "Local".to_string(),
+            SessionType::AutoCompaction => "AutoCompaction".to_string(),
+            SessionType::AutoPurge => "AutoPurge".to_string(),
         };
         write!(f, "{}", name)
     }
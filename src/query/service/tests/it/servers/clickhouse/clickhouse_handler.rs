@@ -0,0 +1,364 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use common_base::base::tokio;
+use common_base::base::tokio::io::AsyncReadExt;
+use common_base::base::tokio::io::AsyncWriteExt;
+use common_base::base::tokio::net::TcpStream;
+use common_exception::Result;
+use databend_query::servers::ClickHouseHandler;
+use databend_query::servers::Server;
+
+use crate::tests::ConfigBuilder;
+use crate::tests::TestGlobalServices;
+
+const REVISION: u64 = 54453;
+
+/// The packets of the server, the blocks only have the columns of UInt64, Int32 and String.
+#[derive(Debug, PartialEq)]
+enum ServerPacket {
+    Hello(String),
+    Data(Vec<(String, String, Vec<String>)>),
+    Exception(String),
+    Progress,
+    EndOfStream,
+}
+
+/// A client of the native protocol without compression.
+struct Client {
+    stream: TcpStream,
+}
+
+impl Client {
+    async fn connect(addr: SocketAddr, user: &str) -> Result<(Client, ServerPacket)> {
+        let mut client = Client {
+            stream: TcpStream::connect(addr).await?,
+        };
+        let mut buf = vec![];
+        put_varuint(&mut buf, 0);
+        put_str(&mut buf, "test client");
+        put_varuint(&mut buf, 22);
+        put_varuint(&mut buf, 1);
+        put_varuint(&mut buf, REVISION);
+        put_str(&mut buf, "default");
+        put_str(&mut buf, user);
+        put_str(&mut buf, "");
+        client.stream.write_all(&buf).await?;
+        let hello = client.read_packet().await?;
+        Ok((client, hello))
+    }
+
+    async fn send_query(&mut self, query: &str) -> Result<()> {
+        let mut buf = vec![];
+        put_varuint(&mut buf, 1);
+        put_str(&mut buf, "test-query-id");
+        // The client info of the initial query.
+        buf.push(1);
+        for s in ["", "", "127.0.0.1:0"] {
+            put_str(&mut buf, s);
+        }
+        buf.extend_from_slice(&0u64.to_le_bytes());
+        buf.push(1);
+        for s in ["user", "localhost", "test client"] {
+            put_str(&mut buf, s);
+        }
+        for v in [22, 1, REVISION] {
+            put_varuint(&mut buf, v);
+        }
+        put_str(&mut buf, "");
+        put_varuint(&mut buf, 0);
+        put_varuint(&mut buf, 0);
+        buf.push(0);
+        for v in [0, 0, 0] {
+            put_varuint(&mut buf, v);
+        }
+        // The settings, the unknown settings are ignored.
+        put_str(&mut buf, "max_threads");
+        put_varuint(&mut buf, 0);
+        put_str(&mut buf, "2");
+        put_str(&mut buf, "unknown_setting");
+        put_varuint(&mut buf, 0);
+        put_str(&mut buf, "1");
+        put_str(&mut buf, "");
+        // The interserver secret, the stage, the compression and the query.
+        put_str(&mut buf, "");
+        put_varuint(&mut buf, 2);
+        put_varuint(&mut buf, 0);
+        put_str(&mut buf, query);
+        self.stream.write_all(&buf).await?;
+        // No external tables.
+        self.send_data(&[], 0).await
+    }
+
+    // Send a block of the columns of Int32 and String.
+    async fn send_data(&mut self, columns: &[(&str, &str, Vec<u8>)], rows: u64) -> Result<()> {
+        let mut buf = vec![];
+        put_varuint(&mut buf, 2);
+        put_str(&mut buf, "");
+        put_varuint(&mut buf, 1);
+        buf.push(0);
+        put_varuint(&mut buf, 2);
+        buf.extend_from_slice(&(-1i32).to_le_bytes());
+        put_varuint(&mut buf, 0);
+        put_varuint(&mut buf, columns.len() as u64);
+        put_varuint(&mut buf, rows);
+        for (name, type_name, data) in columns {
+            put_str(&mut buf, name);
+            put_str(&mut buf, type_name);
+            buf.extend_from_slice(data);
+        }
+        self.stream.write_all(&buf).await?;
+        Ok(())
+    }
+
+    // Read the packets until the end of the query.
+    async fn read_result(&mut self) -> Result<Vec<ServerPacket>> {
+        let mut packets = vec![];
+        loop {
+            let packet = self.read_packet().await?;
+            let end = matches!(
+                packet,
+                ServerPacket::EndOfStream | ServerPacket::Exception(_)
+            );
+            if packet != ServerPacket::Progress {
+                packets.push(packet);
+            }
+            if end {
+                return Ok(packets);
+            }
+        }
+    }
+
+    async fn read_packet(&mut self) -> Result<ServerPacket> {
+        let packet = match self.read_varuint().await? {
+            0 => {
+                let name = self.read_str().await?;
+                for _ in 0..3 {
+                    self.read_varuint().await?;
+                }
+                let _timezone = self.read_str().await?;
+                let _display_name = self.read_str().await?;
+                let _patch = self.read_varuint().await?;
+                ServerPacket::Hello(name)
+            }
+            1 => ServerPacket::Data(self.read_block().await?),
+            2 => {
+                let _code = self.stream.read_i32_le().await?;
+                let _name = self.read_str().await?;
+                let message = self.read_str().await?;
+                let _stack_trace = self.read_str().await?;
+                let _has_nested = self.stream.read_u8().await?;
+                ServerPacket::Exception(message)
+            }
+            3 => {
+                for _ in 0..5 {
+                    self.read_varuint().await?;
+                }
+                ServerPacket::Progress
+            }
+            5 => ServerPacket::EndOfStream,
+            packet => panic!("unexpected packet {}", packet),
+        };
+        Ok(packet)
+    }
+
+    async fn read_block(&mut self) -> Result<Vec<(String, String, Vec<String>)>> {
+        let _table_name = self.read_str().await?;
+        // The block info.
+        assert_eq!(self.read_varuint().await?, 1);
+        self.stream.read_u8().await?;
+        assert_eq!(self.read_varuint().await?, 2);
+        self.stream.read_i32_le().await?;
+        assert_eq!(self.read_varuint().await?, 0);
+
+        let num_columns = self.read_varuint().await?;
+        let num_rows = self.read_varuint().await?;
+        let mut columns = vec![];
+        for _ in 0..num_columns {
+            let name = self.read_str().await?;
+            let type_name = self.read_str().await?;
+            let mut values = vec![];
+            for _ in 0..num_rows {
+                let value = match type_name.as_str() {
+                    "UInt64" => self.stream.read_u64_le().await?.to_string(),
+                    "Int32" => self.stream.read_i32_le().await?.to_string(),
+                    "String" => self.read_str().await?,
+                    _ => panic!("unexpected type {}", type_name),
+                };
+                values.push(value);
+            }
+            columns.push((name, type_name, values));
+        }
+        Ok(columns)
+    }
+
+    async fn read_varuint(&mut self) -> Result<u64> {
+        let mut value = 0;
+        for i in 0..10 {
+            let byte = self.stream.read_u8().await?;
+            value |= ((byte & 0x7f) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        Ok(value)
+    }
+
+    async fn read_str(&mut self) -> Result<String> {
+        let len = self.read_varuint().await? as usize;
+        let mut buf = vec![0; len];
+        self.stream.read_exact(&mut buf).await?;
+        Ok(String::from_utf8(buf).unwrap())
+    }
+}
+
+fn put_varuint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    put_varuint(buf, s.len() as u64);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn column(name: &str, type_name: &str, values: &[&str]) -> (String, String, Vec<String>) {
+    (
+        name.to_string(),
+        type_name.to_string(),
+        values.iter().map(|v| v.to_string()).collect(),
+    )
+}
+
+async fn start_handler() -> Result<(Box<dyn Server>, SocketAddr)> {
+    let mut handler = ClickHouseHandler::create()?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let addr = handler.start(listening).await?;
+    Ok((handler, addr))
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_clickhouse_handler_query() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+    let (_handler, addr) = start_handler().await?;
+
+    let (mut client, hello) = Client::connect(addr, "root").await?;
+    assert_eq!(hello, ServerPacket::Hello("Databend".to_string()));
+
+    client
+        .send_query("SELECT number, to_string(number) AS s FROM numbers(3) ORDER BY number")
+        .await?;
+    assert_eq!(client.read_result().await?, vec![
+        ServerPacket::Data(vec![
+            column("number", "UInt64", &[]),
+            column("s", "String", &[]),
+        ]),
+        ServerPacket::Data(vec![
+            column("number", "UInt64", &["0", "1", "2"]),
+            column("s", "String", &["0", "1", "2"]),
+        ]),
+        ServerPacket::EndOfStream,
+    ]);
+
+    // The errors are sent as the exceptions, the connection is still usable.
+    client.send_query("SELECT * FROM not_exists").await?;
+    let packets = client.read_result().await?;
+    assert!(
+        matches!(&packets[..], [ServerPacket::Exception(message)] if message.contains("not_exists"))
+    );
+
+    client.send_query("SELECT version()").await?;
+    let packets = client.read_result().await?;
+    assert_eq!(packets.len(), 3);
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_clickhouse_handler_insert() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+    let (_handler, addr) = start_handler().await?;
+    let (mut client, _) = Client::connect(addr, "root").await?;
+
+    client
+        .send_query("CREATE TABLE t_native(a INT, b VARCHAR)")
+        .await?;
+    assert_eq!(client.read_result().await?, vec![ServerPacket::EndOfStream]);
+
+    // The server replies the sample block, then the client sends the data.
+    client.send_query("INSERT INTO t_native VALUES").await?;
+    assert_eq!(
+        client.read_packet().await?,
+        ServerPacket::Data(vec![column("a", "Int32", &[]), column("b", "String", &[]),])
+    );
+    let mut a = vec![];
+    a.extend_from_slice(&1i32.to_le_bytes());
+    a.extend_from_slice(&2i32.to_le_bytes());
+    let mut b = vec![];
+    put_str(&mut b, "x");
+    put_str(&mut b, "y");
+    client
+        .send_data(&[("a", "Int32", a), ("b", "String", b)], 2)
+        .await?;
+    client.send_data(&[], 0).await?;
+    assert_eq!(client.read_result().await?, vec![ServerPacket::EndOfStream]);
+
+    // The type of the columns must be the same as the sample block.
+    client.send_query("INSERT INTO t_native VALUES").await?;
+    client.read_packet().await?;
+    client
+        .send_data(
+            &[
+                ("a", "UInt64", 3u64.to_le_bytes().to_vec()),
+                ("b", "String", vec![0]),
+            ],
+            1,
+        )
+        .await?;
+    let packets = client.read_result().await?;
+    assert!(
+        matches!(&packets[..], [ServerPacket::Exception(message)] if message.contains("Int32"))
+    );
+    // The end of the data, which is ignored after the failure.
+    client.send_data(&[], 0).await?;
+
+    client
+        .send_query("SELECT a, b FROM t_native ORDER BY a")
+        .await?;
+    let packets = client.read_result().await?;
+    assert_eq!(
+        packets[1],
+        ServerPacket::Data(vec![
+            column("a", "Int32", &["1", "2"]),
+            column("b", "String", &["x", "y"]),
+        ])
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_clickhouse_handler_authenticate_failed() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+    let (_handler, addr) = start_handler().await?;
+
+    let (_, packet) = Client::connect(addr, "unknown_user").await?;
+    assert!(
+        matches!(packet, ServerPacket::Exception(message) if message.contains("Authentication failed"))
+    );
+    Ok(())
+}
@@ -0,0 +1,166 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use common_arrow::arrow::io::flight::deserialize_batch;
+use common_arrow::arrow::io::flight::deserialize_schemas;
+use common_arrow::arrow_format::flight::data::FlightDescriptor;
+use common_arrow::arrow_format::flight::data::HandshakeRequest;
+use common_arrow::arrow_format::flight::service::flight_service_client::FlightServiceClient;
+use common_base::base::tokio;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use databend_query::servers::FlightSqlHandler;
+use futures::StreamExt;
+use prost::Message;
+use tonic::transport::Channel;
+use tonic::Code;
+use tonic::Request;
+
+use crate::tests::ConfigBuilder;
+use crate::tests::TestGlobalServices;
+
+// The google.protobuf.Any of the Flight SQL commands.
+#[derive(Clone, PartialEq, Message)]
+struct Any {
+    #[prost(string, tag = "1")]
+    type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct CommandStatementQuery {
+    #[prost(string, tag = "1")]
+    query: String,
+}
+
+fn statement_query(query: &str) -> FlightDescriptor {
+    let command = CommandStatementQuery {
+        query: query.to_string(),
+    };
+    let any = Any {
+        type_url: "type.googleapis.com/arrow.flight.protocol.sql.CommandStatementQuery".to_string(),
+        value: command.encode_to_vec(),
+    };
+    FlightDescriptor {
+        // CMD
+        r#type: 2,
+        cmd: any.encode_to_vec(),
+        path: vec![],
+    }
+}
+
+async fn create_client(addr: SocketAddr) -> Result<FlightServiceClient<Channel>> {
+    FlightServiceClient::connect(format!("http://{}", addr))
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Cannot connect")
+}
+
+// Handshake as root with the Basic scheme, returns the bearer token.
+async fn handshake(client: &mut FlightServiceClient<Channel>) -> Result<String> {
+    let mut request = Request::new(tokio_stream::iter(vec![HandshakeRequest {
+        protocol_version: 0,
+        payload: vec![],
+    }]));
+    // base64("root:")
+    request
+        .metadata_mut()
+        .insert("authorization", "Basic cm9vdDo=".parse().unwrap());
+    let response = client.handshake(request).await?;
+    let authorization = response.metadata().get("authorization").unwrap();
+    Ok(authorization.to_str().unwrap().to_string())
+}
+
+fn with_token<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("authorization", token.parse().unwrap());
+    request
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_flight_sql_statement_query() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let mut handler = FlightSqlHandler::create()?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let addr = handler.start(listening).await?;
+    let mut client = create_client(addr).await?;
+
+    let token = handshake(&mut client).await?;
+    assert!(token.starts_with("Bearer "));
+
+    let descriptor = statement_query("SELECT number, 'x' AS s FROM numbers(3) ORDER BY number");
+    let info = client
+        .get_flight_info(with_token(descriptor, &token))
+        .await?
+        .into_inner();
+    assert_eq!(info.endpoint.len(), 1);
+
+    let ticket = info.endpoint[0].ticket.clone().unwrap();
+    let mut stream = client
+        .do_get(with_token(ticket, &token))
+        .await?
+        .into_inner();
+
+    let schema_message = stream.next().await.unwrap()?;
+    let (schema, ipc_schema) = deserialize_schemas(&schema_message.data_header)?;
+    let names = schema
+        .fields
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["number", "s"]);
+
+    let mut num_rows = 0;
+    while let Some(message) = stream.next().await {
+        let chunk = deserialize_batch(&message?, &schema.fields, &ipc_schema, &Default::default())?;
+        num_rows += chunk.len();
+    }
+    assert_eq!(num_rows, 3);
+
+    handler.shutdown(true).await;
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_flight_sql_unauthenticated() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let mut handler = FlightSqlHandler::create()?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let addr = handler.start(listening).await?;
+    let mut client = create_client(addr).await?;
+
+    // Without handshake.
+    let status = client
+        .get_flight_info(Request::new(statement_query("SELECT 1")))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    // With a token not issued by the handshake.
+    let status = client
+        .get_flight_info(with_token(statement_query("SELECT 1"), "Bearer unknown"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    handler.shutdown(true).await;
+    Ok(())
+}
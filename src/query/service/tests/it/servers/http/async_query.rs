@@ -0,0 +1,205 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_base::base::tokio;
+use common_base::base::tokio::time::sleep;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use databend_query::auth::AuthMgr;
+use databend_query::servers::http::middleware::HTTPSessionEndpoint;
+use databend_query::servers::http::middleware::HTTPSessionMiddleware;
+use databend_query::servers::http::v1::async_query_route;
+use databend_query::servers::http::v1::make_async_result_page_uri;
+use databend_query::servers::http::v1::make_async_result_uri;
+use databend_query::servers::http::v1::make_async_state_uri;
+use databend_query::servers::http::v1::AsyncQueryResponse;
+use databend_query::servers::http::v1::ExecuteStateKind;
+use databend_query::servers::HttpHandlerKind;
+use poem::http::header;
+use poem::http::Method;
+use poem::http::StatusCode;
+use poem::Endpoint;
+use poem::EndpointExt;
+use poem::Request;
+use poem::Response;
+use poem::Route;
+
+use crate::tests::ConfigBuilder;
+use crate::tests::TestGlobalServices;
+
+type EndpointType = HTTPSessionEndpoint<Route>;
+
+fn create_endpoint() -> EndpointType {
+    let config = ConfigBuilder::create().build();
+    let session_middleware =
+        HTTPSessionMiddleware::create(HttpHandlerKind::Query, AuthMgr::create(&config));
+    Route::new()
+        .nest("/v1/async_query", async_query_route())
+        .with(session_middleware)
+}
+
+async fn call(ep: &EndpointType, method: Method, uri: &str, body: Vec<u8>) -> Result<Response> {
+    let basic = headers::Authorization::basic("root", "");
+    let req = Request::builder()
+        .uri(uri.parse().unwrap())
+        .method(method)
+        .header(header::CONTENT_TYPE, "application/json")
+        .typed_header(basic)
+        .body(body);
+    ep.call(req)
+        .await
+        .map_err(|e| ErrorCode::Internal(e.to_string()))
+}
+
+async fn call_checked(ep: &EndpointType, method: Method, uri: &str) -> Result<AsyncQueryResponse> {
+    let response = call(ep, method, uri, vec![]).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().into_string().await.unwrap();
+    Ok(serde_json::from_str(&body)?)
+}
+
+async fn submit(ep: &EndpointType, sql: &str) -> Result<AsyncQueryResponse> {
+    submit_json(ep, serde_json::json!({ "sql": sql })).await
+}
+
+async fn submit_json(ep: &EndpointType, json: serde_json::Value) -> Result<AsyncQueryResponse> {
+    let body = serde_json::to_vec(&json)?;
+    let response = call(ep, Method::POST, "/v1/async_query", body).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().into_string().await.unwrap();
+    Ok(serde_json::from_str(&body)?)
+}
+
+async fn wait_finished(ep: &EndpointType, query_id: &str) -> Result<AsyncQueryResponse> {
+    let uri = make_async_state_uri(query_id);
+    for _ in 0..300 {
+        let result = call_checked(ep, Method::GET, &uri).await?;
+        if result.state != ExecuteStateKind::Running {
+            return Ok(result);
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    unreachable!("async query {} run for more than 3 secs", query_id);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_async_query() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+    let ep = create_endpoint();
+
+    let result = submit_json(
+        &ep,
+        serde_json::json!({
+            "sql": "select number, to_string(number) from numbers(3) order by number",
+            "pagination": { "max_rows_per_page": 2 }
+        }),
+    )
+    .await?;
+    assert!(result.error.is_none(), "{:?}", result);
+    assert_eq!(result.schema.len(), 2, "{:?}", result);
+    let query_id = result.id.clone();
+
+    let result = wait_finished(&ep, &query_id).await?;
+    assert_eq!(result.state, ExecuteStateKind::Succeeded, "{:?}", result);
+    assert_eq!(result.num_rows, 3);
+    assert!(result.data.is_empty());
+    assert_eq!(result.result_uri, Some(make_async_result_uri(&query_id)));
+
+    // the result is read by another endpoint, as if it's on another node.
+    let result = call_checked(
+        &create_endpoint(),
+        Method::GET,
+        &make_async_result_uri(&query_id),
+    )
+    .await?;
+    assert_eq!(result.data, vec![
+        vec![serde_json::json!("0"), serde_json::json!("0")],
+        vec![serde_json::json!("1"), serde_json::json!("1")],
+    ]);
+    assert_eq!(
+        result.next_uri,
+        Some(make_async_result_page_uri(&query_id, 1))
+    );
+
+    // the last page.
+    let result = call_checked(&ep, Method::GET, &make_async_result_page_uri(&query_id, 1)).await?;
+    assert_eq!(result.data, vec![vec![
+        serde_json::json!("2"),
+        serde_json::json!("2")
+    ]]);
+    assert_eq!(result.next_uri, None);
+    let response = call(
+        &ep,
+        Method::GET,
+        &make_async_result_page_uri(&query_id, 2),
+        vec![],
+    )
+    .await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // drop the state and the result.
+    let response = call(
+        &ep,
+        Method::DELETE,
+        &make_async_state_uri(&query_id),
+        vec![],
+    )
+    .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = call(&ep, Method::GET, &make_async_state_uri(&query_id), vec![]).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_async_query_failed() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+    let ep = create_endpoint();
+
+    // the errors of planning are returned at once.
+    let result = submit(&ep, "select * from not_exists").await?;
+    assert_eq!(result.state, ExecuteStateKind::Failed);
+    assert!(result.error.is_some(), "{:?}", result);
+
+    // the errors of execution are kept in the state.
+    let sql = "select to_uint64(concat(to_string(number), 'x')) from numbers(3)";
+    let result = submit(&ep, sql).await?;
+    let result = wait_finished(&ep, &result.id).await?;
+    assert_eq!(result.state, ExecuteStateKind::Failed, "{:?}", result);
+    assert!(result.error.is_some(), "{:?}", result);
+
+    // the result larger than the limit is not kept.
+    let body = serde_json::to_vec(&serde_json::json!({
+        "sql": "select * from numbers(10000)",
+        "session": { "settings": { "max_async_query_result_bytes": "100" } }
+    }))?;
+    let response = call(&ep, Method::POST, "/v1/async_query", body).await?;
+    let body = response.into_body().into_string().await.unwrap();
+    let result = serde_json::from_str::<AsyncQueryResponse>(&body)?;
+    let result = wait_finished(&ep, &result.id).await?;
+    assert_eq!(result.state, ExecuteStateKind::Failed, "{:?}", result);
+    let message = result.error.map(|e| e.message).unwrap_or_default();
+    assert!(
+        message.contains("max_async_query_result_bytes"),
+        "{}",
+        message
+    );
+
+    // unknown query id.
+    let response = call(&ep, Method::GET, &make_async_state_uri("unknown"), vec![]).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
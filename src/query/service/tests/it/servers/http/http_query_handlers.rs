@@ -14,11 +14,16 @@
 
 use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::Cursor;
 use std::io::Read;
 use std::time::Duration;
 
 use base64::encode_config;
 use base64::URL_SAFE_NO_PAD;
+use common_arrow::arrow::io::ipc::read::read_stream_metadata;
+use common_arrow::arrow::io::ipc::read::StreamReader;
+use common_arrow::arrow::io::ipc::read::StreamState;
+use common_arrow::parquet::read::read_metadata;
 use common_base::base::get_free_tcp_port;
 use common_base::base::tokio;
 use common_exception::ErrorCode;
@@ -396,6 +401,157 @@ async fn test_pagination() -> Result<()> {
     Ok(())
 }
 
+async fn collect_binary_pages(
+    ep: &EndpointType,
+    json: &serde_json::Value,
+    content_type: &str,
+) -> Result<Vec<Vec<u8>>> {
+    let mut response = post_json_to_endpoint_raw(ep, json).await?;
+    let mut pages = vec![];
+    loop {
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.content_type(), Some(content_type));
+        let next_uri = response
+            .header("X-DATABEND-QUERY-NEXT-URI")
+            .map(|uri| uri.to_string());
+        let rows = response
+            .header("X-DATABEND-QUERY-PAGE-ROWS")
+            .unwrap()
+            .parse::<usize>()?;
+        let body = response.into_body().into_vec().await.unwrap();
+        if rows > 0 {
+            pages.push(body);
+        }
+        match next_uri {
+            Some(uri) if uri.contains("/page/") => response = get_uri(ep, &uri).await,
+            _ => break,
+        }
+    }
+    Ok(pages)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_arrow_ipc_pagination() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let ep = create_endpoint().await?;
+    let sql = "select number, to_string(number) from numbers(10)";
+    let json = serde_json::json!({"sql": sql.to_string(), "result_format": "arrow_ipc", "pagination": {"wait_time_secs": 5, "max_rows_per_page": 4}});
+    let pages = collect_binary_pages(&ep, &json, "application/vnd.apache.arrow.stream").await?;
+    assert_eq!(pages.len(), 3);
+
+    let mut rows = vec![];
+    for page in pages {
+        let mut cursor = Cursor::new(page);
+        let metadata = read_stream_metadata(&mut cursor)?;
+        assert_eq!(metadata.schema.fields.len(), 2);
+        for state in StreamReader::new(cursor, metadata, None) {
+            if let StreamState::Some(chunk) = state? {
+                rows.push(chunk.len());
+            }
+        }
+    }
+    assert_eq!(rows.iter().sum::<usize>(), 10);
+    assert!(rows.iter().all(|n| *n <= 4));
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_parquet_pagination() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let ep = create_endpoint().await?;
+    let sql = "select * from numbers(10)";
+    let json = serde_json::json!({"sql": sql.to_string(), "result_format": "parquet", "pagination": {"wait_time_secs": 5, "max_rows_per_page": 4}});
+    let pages = collect_binary_pages(&ep, &json, "application/vnd.apache.parquet").await?;
+
+    let rows = pages
+        .into_iter()
+        .map(|page| Ok(read_metadata(&mut Cursor::new(page))?.num_rows))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(rows, vec![4, 4, 2]);
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_spill_unread_pages() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let ep = create_endpoint().await?;
+    // the blocks beyond the buffer are spilled since the client reads nothing until the end.
+    let sql = "select * from numbers(100)";
+    let json = serde_json::json!({"sql": sql.to_string(), "pagination": {"wait_time_secs": 0, "max_rows_in_buffer": 10, "max_rows_per_page": 30}, "session": { "settings": {"max_block_size": "10"}}});
+    let (status, result) = post_json_to_endpoint(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK, "{:?}", result);
+    let state_uri = make_state_uri(&result.id);
+    for _ in 0..300 {
+        let (_, state) = get_uri_checked(&ep, &state_uri).await?;
+        if state.state != ExecuteStateKind::Running {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    let mut numbers = result.data.clone();
+    let mut next_uri = result.next_uri;
+    while let Some(uri) = next_uri.filter(|uri| uri.contains("/page/")) {
+        let (status, result) = get_uri_checked(&ep, &uri).await?;
+        assert_eq!(status, StatusCode::OK, "{:?}", result);
+        assert!(result.error.is_none(), "{:?}", result);
+        assert!(result.data.len() <= 30, "{:?}", result);
+        numbers.extend(result.data);
+        next_uri = result.next_uri;
+    }
+    let numbers = numbers
+        .iter()
+        .map(|row| row[0].as_str().unwrap().parse::<u64>().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(numbers, (0..100).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_stream_query() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let ep = create_endpoint().await?;
+    let sql = "select number, to_string(number) from numbers(5) order by number";
+    let json = serde_json::json!({"sql": sql.to_string(), "stream": true, "session": { "settings": {"max_block_size": "2"}}});
+    let response = post_json_to_endpoint_raw(&ep, &json).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.content_type(), Some("application/x-ndjson"));
+    let query_id = response.header("X-DATABEND-QUERY-ID").unwrap().to_string();
+    let body = response.into_body().into_string().await.unwrap();
+    let lines = body
+        .lines()
+        .map(serde_json::from_str::<serde_json::Value>)
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    assert_eq!(lines.len(), 7, "{}", body);
+    assert_eq!(lines[0]["id"], query_id.as_str());
+    assert_eq!(lines[0]["schema"].as_array().unwrap().len(), 2);
+    for (i, row) in lines[1..6].iter().enumerate() {
+        assert_eq!(row, &serde_json::json!([i.to_string(), i.to_string()]));
+    }
+    assert_eq!(lines[6]["state"], "Succeeded");
+    assert!(lines[6]["error"].is_null(), "{}", body);
+
+    // the errors of planning are returned as the paginated queries do.
+    let json = serde_json::json!({"sql": "select * from not_exists", "stream": true});
+    let (status, result) = post_json_to_endpoint(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result.state, ExecuteStateKind::Failed);
+    assert!(result.error.is_some(), "{:?}", result);
+
+    // the failure after the start is in the last line.
+    let json = serde_json::json!({"sql": "select to_uint64(concat(to_string(number), 'x')) from numbers(5)", "stream": true});
+    let response = post_json_to_endpoint_raw(&ep, &json).await?;
+    let body = response.into_body().into_string().await.unwrap();
+    let last = serde_json::from_str::<serde_json::Value>(body.lines().last().unwrap())?;
+    assert_eq!(last["state"], "Failed", "{}", body);
+    assert!(last["error"]["message"].is_string(), "{}", body);
+    Ok(())
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn test_http_session() -> Result<()> {
     let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
@@ -433,6 +589,50 @@ async fn test_http_session() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn test_http_session_resume() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let ep = create_endpoint().await?;
+    let json =
+        serde_json::json!({"sql":  "use system", "session": {"keep_server_session_secs": 10}});
+    let (status, result) = post_json_to_endpoint(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK, "{:?}", result);
+    assert!(result.error.is_none(), "{:?}", result);
+    let session_id = result.session_id.clone().unwrap();
+    let session_conf = result.session.unwrap();
+    let token = session_conf
+        .token
+        .clone()
+        .expect("kept session has a token");
+
+    let json = serde_json::json!({"sql": "set max_threads=3", "session_id": session_id, "session": session_conf});
+    let (status, result) = post_json_to_endpoint(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK, "{:?}", result);
+    assert!(result.error.is_none(), "{:?}", result);
+    assert_eq!(result.session.as_ref().unwrap().token, Some(token.clone()));
+
+    // the state is kept after the query is finished
+    sleep(std::time::Duration::from_millis(500)).await;
+
+    // the other node does not know the session, it is resumed by the token
+    let sql = "select database(), value from system.settings where name = 'max_threads'";
+    let json = serde_json::json!({"sql": sql, "session": {"token": token}});
+    let (status, result) = post_json_to_endpoint(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK, "{:?}", result);
+    assert!(result.error.is_none(), "{:?}", result);
+    assert_ne!(result.session_id, Some(session_id), "{:?}", result);
+    assert_eq!(result.data.len(), 1, "{:?}", result);
+    assert_eq!(result.data[0][0], "system", "{:?}", result);
+    assert_eq!(result.data[0][1], "3", "{:?}", result);
+
+    let json = serde_json::json!({"sql": "select 1", "session": {"token": "unknown"}});
+    let (status, result) = post_json_to_endpoint(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK, "{:?}", result);
+    assert!(result.error.is_some(), "{:?}", result);
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_result_timeout() -> Result<()> {
     let config = ConfigBuilder::create()
@@ -540,6 +740,89 @@ async fn test_insert() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn test_query_params() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let route = create_endpoint().await?;
+
+    let cases = vec![
+        (
+            "create table t(a int, b string, c date null) engine=fuse",
+            serde_json::Value::Null,
+            serde_json::json!([]),
+        ),
+        (
+            "insert into t values (?, ?, ?), (?, ?, ?)",
+            serde_json::json!([1, "x", {"value": "2023-01-01", "type": "DATE"}, -2, "' or 1 = 1 --", null]),
+            serde_json::json!([]),
+        ),
+        (
+            "select a, b, c from t where b = $2 or a = $1 order by a",
+            serde_json::json!([-2, "x"]),
+            serde_json::json!([["-2", "' or 1 = 1 --", "NULL"], ["1", "x", "2023-01-01"]]),
+        ),
+        (
+            "select count(*) from t where b = :b and a > :a",
+            serde_json::json!({"a": {"value": 0, "type": "INT"}, "b": "' or 1 = 1 --"}),
+            serde_json::json!([["0"]]),
+        ),
+        (
+            "merge into t using (select ? as a) s on t.a = s.a when matched and t.a > ? then delete when not matched then insert values (s.a, ?, null)",
+            serde_json::json!([3, 0, "merged"]),
+            serde_json::json!([]),
+        ),
+        (
+            "select a, b from t where a = ?",
+            serde_json::json!([3]),
+            serde_json::json!([["3", "merged"]]),
+        ),
+    ];
+
+    for (sql, params, data) in cases {
+        let json =
+            serde_json::json!({"sql": sql, "params": params, "pagination": {"wait_time_secs": 3}});
+        let (status, result) = post_json_to_endpoint(&route, &json).await?;
+        assert_eq!(status, StatusCode::OK, "{:?}", result);
+        assert!(result.error.is_none(), "{}: {:?}", sql, result.error);
+        assert_eq!(
+            serde_json::to_value(&result.data)?,
+            data,
+            "{}: {:?}",
+            sql,
+            result
+        );
+    }
+
+    let errors = vec![
+        ("select ? + ?", serde_json::json!([1])),
+        ("select :a", serde_json::json!([1])),
+        ("select ?", serde_json::json!([1, 2])),
+        ("select 1", serde_json::json!([1])),
+        ("select :a", serde_json::json!({"a": 1, "b": 2})),
+        (
+            "select ?",
+            serde_json::json!([{"value": "x", "type": "INT"}]),
+        ),
+        (
+            "select ?",
+            serde_json::json!([{"value": null, "type": "INT"}]),
+        ),
+        (
+            "select ?",
+            serde_json::json!([{"value": 1, "type": "NOT A TYPE"}]),
+        ),
+    ];
+    for (sql, params) in errors {
+        let json =
+            serde_json::json!({"sql": sql, "params": params, "pagination": {"wait_time_secs": 3}});
+        let (status, result) = post_json_to_endpoint(&route, &json).await?;
+        assert_eq!(status, StatusCode::OK, "{:?}", result);
+        assert!(result.error.is_some(), "{}: {:?}", sql, result);
+    }
+    Ok(())
+}
+
 // Wait for https://github.com/datafuselabs/databend/issues/7831 to be fixed, then remove ignore
 #[ignore]
 #[tokio::test(flavor = "current_thread")]
@@ -717,6 +1000,14 @@ async fn post_json_to_endpoint(
     ep: &EndpointType,
     json: &serde_json::Value,
 ) -> Result<(StatusCode, QueryResponse)> {
+    let response = post_json_to_endpoint_raw(ep, json).await?;
+    check_response(response).await
+}
+
+async fn post_json_to_endpoint_raw(
+    ep: &EndpointType,
+    json: &serde_json::Value,
+) -> Result<Response> {
     let uri = "/v1/query";
     let content_type = "application/json";
     let body = serde_json::to_vec(&json)?;
@@ -728,12 +1019,9 @@ async fn post_json_to_endpoint(
         .header(header::CONTENT_TYPE, content_type)
         .typed_header(basic)
         .body(body);
-    let response = ep
-        .call(req)
+    ep.call(req)
         .await
-        .map_err(|e| ErrorCode::Internal(e.to_string()))?;
-
-    check_response(response).await
+        .map_err(|e| ErrorCode::Internal(e.to_string()))
 }
 
 #[tokio::test(flavor = "current_thread")]
@@ -1150,6 +1438,7 @@ async fn test_affect() -> Result<()> {
             Some(HttpSessionConf {
                 database: None,
                 keep_server_session_secs: None,
+                token: None,
                 settings: Some(BTreeMap::from([
                     ("max_threads".to_string(), "1".to_string()),
                     ("timezone".to_string(), "Asia/Shanghai".to_string()),
@@ -1162,6 +1451,7 @@ async fn test_affect() -> Result<()> {
             Some(HttpSessionConf {
                 database: None,
                 keep_server_session_secs: None,
+                token: None,
                 settings: Some(BTreeMap::from([(
                     "max_threads".to_string(),
                     "6".to_string(),
@@ -1176,6 +1466,7 @@ async fn test_affect() -> Result<()> {
             Some(HttpSessionConf {
                 database: Some("db2".to_string()),
                 keep_server_session_secs: None,
+                token: None,
                 settings: Some(BTreeMap::from([(
                     "max_threads".to_string(),
                     "6".to_string(),
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod async_query;
 mod clickhouse_handler;
 mod http_query_handlers;
 mod json_block;
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -29,6 +30,7 @@ use mysql_async::FromRowError;
 use mysql_async::Row;
 use tokio::sync::Barrier;
 
+use crate::tests::tls_constants::*;
 use crate::tests::ConfigBuilder;
 use crate::tests::TestGlobalServices;
 
@@ -50,6 +52,63 @@ async fn test_generic_code_with_on_query() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn test_prepared_statement() -> Result<()> {
+    let _guard = TestGlobalServices::setup(ConfigBuilder::create().build()).await?;
+
+    let tcp_keepalive_timeout_secs = 120;
+    let mut handler = MySQLHandler::create(tcp_keepalive_timeout_secs)?;
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut connection = create_connection(runnable_server.port()).await?;
+
+    let statement = connection
+        .prep("SELECT number, ? FROM numbers(5) WHERE number < ? ORDER BY number")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Prepare failed")?;
+    assert_eq!(statement.num_params(), 2);
+
+    let rows: Vec<(u64, String)> = connection
+        .exec(&statement, ("it's", 2u64))
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Execute failed")?;
+    assert_eq!(rows, vec![(0, "it's".to_string()), (1, "it's".to_string())]);
+
+    // The statement is executed again with other parameters.
+    let rows: Vec<(u64, String)> = connection
+        .exec(&statement, ("\\?", 1u64))
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Execute failed")?;
+    assert_eq!(rows, vec![(0, "\\?".to_string())]);
+
+    connection
+        .close(statement)
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Close failed")?;
+
+    // The floats without SQL literals are bound as they are.
+    let statement = connection
+        .prep("SELECT count() FROM numbers(5) WHERE number < ?")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Prepare failed")?;
+    for (param, expected) in [(f64::INFINITY, 5u64), (f64::NEG_INFINITY, 0)] {
+        let count: Option<u64> = connection
+            .exec_first(&statement, (param,))
+            .await
+            .map_err_to_code(ErrorCode::UnknownException, || "Execute failed")?;
+        assert_eq!(count, Some(expected));
+    }
+    let result: std::result::Result<Vec<u64>, _> = connection.exec(&statement, (f64::NAN,)).await;
+    assert!(result.is_ok());
+
+    // Placeholders are not allowed out of prepared statements.
+    let result = connection.query_drop("SELECT ?").await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn test_rejected_session_with_sequence() -> Result<()> {
     let _guard =
@@ -157,6 +216,106 @@ async fn test_rejected_session_with_parallel() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn test_tls_with_client_cert() -> Result<()> {
+    let _guard = TestGlobalServices::setup(
+        ConfigBuilder::create()
+            .mysql_handler_tls_server_key(TEST_TLS_SERVER_KEY)
+            .mysql_handler_tls_server_cert(TEST_TLS_SERVER_CERT)
+            .mysql_handler_tls_server_root_ca_cert(TEST_TLS_CA_CERT)
+            .build(),
+    )
+    .await?;
+
+    let tcp_keepalive_timeout_secs = 120;
+    let mut handler = MySQLHandler::create(tcp_keepalive_timeout_secs)?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let port = handler.start(listening).await?.port();
+
+    // The plain text connection is still accepted.
+    let mut connection = create_connection(port).await?;
+    connection
+        .query_drop("CREATE USER 'Databend Client' REQUIRE X509")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Create user failed")?;
+    connection
+        .query_drop(
+            "CREATE USER u_subject REQUIRE SUBJECT 'C=US, ST=CA, L=San Francisco, CN=Databend Client'",
+        )
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Create user failed")?;
+    connection
+        .query_drop("CREATE USER u_other REQUIRE SUBJECT 'CN=other'")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Create user failed")?;
+
+    // The common name of the client certificate is the user name, or the subject matches.
+    for user in ["Databend Client", "u_subject"] {
+        let mut connection = create_tls_connection(port, user, true).await?;
+        let rows: Vec<u64> = connection
+            .query("SELECT 1")
+            .await
+            .map_err_to_code(ErrorCode::UnknownException, || "Query failed")?;
+        assert_eq!(rows, vec![1]);
+    }
+
+    assert!(create_tls_connection(port, "u_other", true).await.is_err());
+    // The x509 user requires the client certificate.
+    assert!(
+        create_tls_connection(port, "u_subject", false)
+            .await
+            .is_err()
+    );
+    assert!(
+        create_connection_with_user(port, "u_subject")
+            .await
+            .is_err()
+    );
+
+    // The user with password can login by TLS without the client certificate.
+    let mut connection = create_tls_connection(port, "root", false).await?;
+    let rows: Vec<u64> = connection
+        .query("SELECT 1")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Query failed")?;
+    assert_eq!(rows, vec![1]);
+
+    Ok(())
+}
+
+async fn create_tls_connection(
+    port: u16,
+    user: &str,
+    with_client_cert: bool,
+) -> Result<mysql_async::Conn> {
+    let mut ssl_opts =
+        mysql_async::SslOpts::default().with_root_cert_path(Some(Path::new(TEST_TLS_CA_CERT)));
+    if with_client_cert {
+        ssl_opts = ssl_opts.with_client_identity(Some(mysql_async::ClientIdentity::new(
+            Path::new(TEST_TLS_CLIENT_CERT),
+            Path::new(TEST_TLS_CLIENT_KEY),
+        )));
+    }
+    let opts = mysql_async::OptsBuilder::default()
+        .ip_or_hostname(TEST_CN_NAME)
+        .tcp_port(port)
+        .user(Some(user))
+        .ssl_opts(Some(ssl_opts));
+    mysql_async::Conn::new(opts)
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Reject connection")
+}
+
+async fn create_connection_with_user(port: u16, user: &str) -> Result<mysql_async::Conn> {
+    let opts = mysql_async::OptsBuilder::default()
+        .ip_or_hostname("127.0.0.1")
+        .tcp_port(port)
+        .user(Some(user));
+    mysql_async::Conn::new(opts)
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Reject connection")
+}
+
 async fn create_connection(port: u16) -> Result<mysql_async::Conn> {
     let uri = &format!("mysql://root@127.0.0.1:{}", port);
     let opts = mysql_async::Opts::from_url(uri).unwrap();
@@ -0,0 +1,132 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+use common_expression::block_debug::assert_blocks_eq;
+use databend_query::servers::PostgresFederated;
+
+#[test]
+fn test_postgres_federated() -> Result<()> {
+    let federated = PostgresFederated::create();
+
+    //
+    {
+        let query = "select 1";
+        let result = federated.check(query);
+        assert!(result.is_none());
+    }
+
+    // select version()
+    {
+        let query = "select version()";
+        let result = federated.check(query);
+        assert!(result.is_some());
+
+        if let Some((_, block)) = result {
+            assert!(!block.is_empty())
+        }
+    }
+
+    // parameters
+    {
+        let query = "SHOW TRANSACTION ISOLATION LEVEL";
+        let result = federated.check(query);
+        assert!(result.is_some());
+
+        if let Some((_, block)) = result {
+            let expect = vec![
+                "+------------------+",
+                "| Column 0         |",
+                "+------------------+",
+                "| \"read committed\" |",
+                "+------------------+",
+            ];
+
+            assert_blocks_eq(expect, &[block]);
+        }
+
+        let query = "select current_setting('server_version_num')";
+        let result = federated.check(query);
+        assert!(result.is_some());
+
+        if let Some((_, block)) = result {
+            let expect = vec![
+                "+----------+",
+                "| Column 0 |",
+                "+----------+",
+                "| \"110003\" |",
+                "+----------+",
+            ];
+
+            assert_blocks_eq(expect, &[block]);
+        }
+
+        let query = "show tables";
+        let result = federated.check(query);
+        assert!(result.is_none());
+    }
+
+    // set and txn
+    {
+        for query in [
+            "SET extra_float_digits = 3",
+            "SET application_name = 'PostgreSQL JDBC Driver'",
+            "BEGIN",
+            "COMMIT",
+            "DISCARD ALL",
+        ] {
+            let result = federated.check(query);
+            assert!(result.is_some(), "{}", query);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_postgres_federated_rewrite() -> Result<()> {
+    let federated = PostgresFederated::create();
+
+    // Not on the catalog.
+    {
+        let query = "select * from t";
+        assert!(federated.rewrite(query).is_none());
+    }
+
+    // pg_catalog tables.
+    {
+        let query = "select nspname from pg_catalog.pg_namespace";
+        let rewritten = federated.rewrite(query).unwrap();
+        assert!(rewritten.starts_with("select nspname from (SELECT schema_name AS oid"));
+        assert!(rewritten.ends_with("FROM information_schema.schemata) AS pg_namespace"));
+
+        let query = "select c.relname from pg_class c where c.relkind = 'r'";
+        let rewritten = federated.rewrite(query).unwrap();
+        assert!(rewritten.starts_with("select c.relname from (SELECT "));
+        assert!(rewritten.ends_with("FROM information_schema.tables) c where c.relkind = 'r'"));
+    }
+
+    // pg_catalog functions.
+    {
+        let query = "select pg_catalog.current_schema()";
+        let rewritten = federated.rewrite(query).unwrap();
+        assert_eq!(rewritten, "select current_database()");
+
+        let query = "select current_schema";
+        let rewritten = federated.rewrite(query).unwrap();
+        assert_eq!(rewritten, "select current_database()");
+    }
+
+    Ok(())
+}
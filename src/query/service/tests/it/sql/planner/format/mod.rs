@@ -22,6 +22,7 @@ use common_meta_app::schema::TableIdent;
 use common_meta_app::schema::TableInfo;
 use common_meta_app::schema::TableMeta;
 use common_sql::plans::Join;
+use common_sql::plans::JoinAlgorithm;
 use common_sql::plans::Scan;
 use common_sql::plans::Statistics;
 use databend_query::sql::optimizer::SExpr;
@@ -137,6 +138,7 @@ fn test_format() {
             join_type: JoinType::Inner,
             marker_index: None,
             from_correlated_subquery: false,
+            algorithm: JoinAlgorithm::Hash,
         }
         .into(),
         SExpr::create_unary(
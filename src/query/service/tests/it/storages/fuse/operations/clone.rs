@@ -0,0 +1,177 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use common_base::base::tokio;
+use common_catalog::table::Table;
+use common_exception::Result;
+use common_sql::plans::CloneTableSource;
+use common_storages_fuse::FuseTable;
+use databend_query::interpreters::CreateTableInterpreterV2;
+use databend_query::interpreters::Interpreter;
+use databend_query::sessions::TableContext;
+
+use crate::storages::fuse::table_test_fixture::check_data_dir;
+use crate::storages::fuse::table_test_fixture::execute_command;
+use crate::storages::fuse::table_test_fixture::execute_query;
+use crate::storages::fuse::table_test_fixture::expects_ok;
+use crate::storages::fuse::table_test_fixture::TestFixture;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_clone_table_purge() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    // orphan snapshots are collected at once, the setting is kept by the session.
+    fixture.ctx().get_settings().set_retention_period(0)?;
+    fixture.create_normal_table().await?;
+
+    let qry = format!("insert into {}.{}(id) values(1),(2)", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let snapshot_id = {
+        let table = fixture.latest_default_table().await?;
+        let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+        let snapshot = fuse_table.read_table_snapshot().await?.unwrap();
+        snapshot.snapshot_id.simple().to_string()
+    };
+    let qry = format!("insert into {}.{}(id) values(3)", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+
+    // 1. clone the table at the first snapshot, no data files are copied.
+    let qry = format!(
+        "create table {}.t_clone clone {}.{} at (snapshot => '{}')",
+        db, db, tbl, snapshot_id
+    );
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_data_dir(&fixture, "clone", 3, 0, 2, 2, 2, None, None).await?;
+
+    let expected = vec![
+        "+----------+",
+        "| Column 0 |",
+        "+----------+",
+        "| 1        |",
+        "| 2        |",
+        "+----------+",
+    ];
+    let qry_clone = format!("select id from {}.t_clone order by id", db);
+    expects_ok(
+        "read clone",
+        execute_query(fixture.new_query_ctx().await?, qry_clone.as_str()).await,
+        expected.clone(),
+    )
+    .await?;
+
+    // 2. purge the table cloned from, the files referenced by the clone are kept,
+    // so are the snapshots refer to them.
+    let qry = format!("truncate table {}.{} purge", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_data_dir(&fixture, "purge source", 4, 0, 1, 1, 1, None, None).await?;
+    expects_ok(
+        "read clone after purging source",
+        execute_query(fixture.new_query_ctx().await?, qry_clone.as_str()).await,
+        expected,
+    )
+    .await?;
+
+    // 3. purge the clone, the files of the table cloned from are not touched.
+    let qry = format!("insert into {}.t_clone(id) values(4)", db);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("truncate table {}.t_clone purge", db);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_data_dir(&fixture, "purge clone", 4, 0, 1, 1, 1, None, None).await?;
+
+    // 4. the files are no longer referenced by the clone, they can be collected now.
+    let qry = format!("optimize table {}.{} purge", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_data_dir(&fixture, "purge source again", 2, 0, 0, 0, 0, None, None).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_clone_table_failed() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+
+    let qry = format!("create table {}.t_memory(id int) engine = memory", db);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+
+    // the binder rejects the non-fuse source, the plan is built by hand to fail the clone
+    // after the table is created.
+    let mut create_table_plan = fixture.normal_create_table_plan();
+    create_table_plan.table = "t_clone".to_owned();
+    create_table_plan.clone_source = Some(CloneTableSource {
+        catalog: fixture.default_catalog_name(),
+        database: db.clone(),
+        table: "t_memory".to_owned(),
+        point: None,
+    });
+    let ctx = fixture.new_query_ctx().await?;
+    let interpreter = CreateTableInterpreterV2::try_create(ctx.clone(), create_table_plan)?;
+    assert!(interpreter.execute(ctx).await.is_err());
+
+    // the table created for the clone is dropped.
+    let res = fixture
+        .ctx()
+        .get_table(&fixture.default_catalog_name(), &db, "t_clone")
+        .await;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_clone_table_not_committed() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    fixture.ctx().get_settings().set_retention_period(0)?;
+    fixture.create_normal_table().await?;
+
+    let qry = format!("insert into {}.{}(id) values(1),(2)", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("create table {}.t_clone(id int)", db);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+
+    // the clone is registered to the table cloned from, but its first snapshot is not
+    // committed yet.
+    let table = fixture.latest_default_table().await?;
+    let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+    let segments = fuse_table
+        .read_table_snapshot()
+        .await?
+        .unwrap()
+        .segments
+        .clone();
+    let clone = fixture
+        .ctx()
+        .get_table(&fixture.default_catalog_name(), &db, "t_clone")
+        .await?;
+    let marker = fuse_table
+        .meta_location_generator()
+        .clone_marker_location(clone.get_id());
+    fuse_table
+        .get_operator()
+        .object(&marker)
+        .write(serde_json::to_vec(&segments)?)
+        .await?;
+
+    // the segments shared by the clone are kept by the purge of the table cloned from.
+    let qry = format!("truncate table {}.{} purge", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    for (segment, _) in &segments {
+        assert!(fuse_table.get_operator().object(segment).is_exist().await?);
+    }
+
+    Ok(())
+}
@@ -28,6 +28,7 @@ use common_catalog::plan::PartInfoPtr;
 use common_catalog::plan::Partitions;
 use common_catalog::table::Table;
 use common_catalog::table_context::ProcessInfo;
+use common_catalog::table_context::QueryParams;
 use common_catalog::table_context::StageAttachment;
 use common_catalog::table_context::TableContext;
 use common_exception::ErrorCode;
@@ -444,6 +445,10 @@ impl TableContext for CtxDelegation {
         todo!()
     }
 
+    fn get_query_params(&self) -> Option<QueryParams> {
+        todo!()
+    }
+
     fn set_on_error_map(&self, _map: Option<HashMap<String, ErrorCode>>) {
         todo!()
     }
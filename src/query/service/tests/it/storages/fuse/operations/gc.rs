@@ -18,12 +18,15 @@ use std::sync::Arc;
 use chrono::Duration;
 use common_base::base::tokio;
 use common_catalog::table_context::TableContext;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_expression::DataBlock;
 use common_storages_fuse::io::MetaWriter;
 use common_storages_fuse::io::SegmentWriter;
+use common_storages_fuse::operations::PurgeStatistics;
 use common_storages_fuse::statistics::gen_columns_statistics;
 use common_storages_fuse::FuseTable;
+use databend_query::auto_purge::AutoPurgeService;
 use futures_util::TryStreamExt;
 use storages_common_table_meta::meta::Location;
 use storages_common_table_meta::meta::SegmentInfo;
@@ -35,6 +38,9 @@ use uuid::Uuid;
 use crate::storages::fuse::block_writer::BlockWriter;
 use crate::storages::fuse::table_test_fixture::append_sample_data;
 use crate::storages::fuse::table_test_fixture::check_data_dir;
+use crate::storages::fuse::table_test_fixture::execute_command;
+use crate::storages::fuse::table_test_fixture::execute_query;
+use crate::storages::fuse::table_test_fixture::expects_ok;
 use crate::storages::fuse::table_test_fixture::TestFixture;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -243,6 +249,168 @@ async fn test_fuse_purge_orphan_retention() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_purge_within_data_retention_time() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    let qry = format!(
+        "create table {}.{}(id int) data_retention_time = 1",
+        db, tbl
+    );
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    for i in 0..3 {
+        let qry = format!("insert into {}.{}(id) values({})", db, tbl, i);
+        execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    }
+    let qry = format!("optimize table {}.{} compact", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+
+    // the history of the table is within the retention period, nothing is purged.
+    let table = fixture.latest_default_table().await?;
+    let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+    let keep_last_snapshot = true;
+    let table_ctx: Arc<dyn TableContext> = fixture.new_query_ctx().await?;
+    let statistics = fuse_table.do_purge(&table_ctx, keep_last_snapshot).await?;
+    assert_eq!(statistics, PurgeStatistics::default());
+    check_data_dir(&fixture, "within retention", 4, 0, 4, 4, 4, Some(()), None).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_purge_beyond_data_retention_time() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    let qry = format!(
+        "create table {}.{}(id int) data_retention_time = 0",
+        db, tbl
+    );
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    for i in 0..3 {
+        let qry = format!("insert into {}.{}(id) values({})", db, tbl, i);
+        execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    }
+    let qry = format!("optimize table {}.{} compact", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+
+    // only the last snapshot is kept, along with the files it refers to.
+    let table = fixture.latest_default_table().await?;
+    let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+    let keep_last_snapshot = true;
+    let table_ctx: Arc<dyn TableContext> = fixture.new_query_ctx().await?;
+    let statistics = fuse_table.do_purge(&table_ctx, keep_last_snapshot).await?;
+    assert_eq!(
+        (
+            statistics.snapshot_count,
+            statistics.segment_count,
+            statistics.block_count,
+            statistics.bloom_count
+        ),
+        (3, 3, 3, 3)
+    );
+    assert!(statistics.reclaimed_bytes > 0);
+    check_data_dir(&fixture, "beyond retention", 1, 0, 1, 1, 1, Some(()), None).await?;
+
+    // the table option is not a valid number of hours.
+    let qry = format!(
+        "create table {}.t_invalid(id int) data_retention_time = 'x'",
+        db
+    );
+    let res = execute_command(fixture.new_query_ctx().await?, qry.as_str()).await;
+    assert_eq!(
+        res.unwrap_err().code(),
+        ErrorCode::TableOptionInvalid("").code()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_auto_purge_dropped_table() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    let qry = format!(
+        "create table {}.{}(id int) data_retention_time = 0",
+        db, tbl
+    );
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("insert into {}.{}(id) values(1)", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("drop table {}.{}", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_data_dir(&fixture, "dropped", 1, 0, 1, 1, 1, None, None).await?;
+
+    // the dropped table is beyond its retention period, it is truncated and purged.
+    AutoPurgeService::instance().purge_round().await?;
+    check_data_dir(&fixture, "auto purge", 1, 0, 0, 0, 0, None, None).await?;
+    let qry = format!(
+        "select count(*), sum(purged_files) from system.purge_history where database = '{}' and table = '{}'",
+        db, tbl
+    );
+    expects_ok(
+        "purge history",
+        execute_query(fixture.new_query_ctx().await?, qry.as_str()).await,
+        vec![
+            "+----------+----------+",
+            "| Column 0 | Column 1 |",
+            "+----------+----------+",
+            "| 1        | 4        |",
+            "+----------+----------+",
+        ],
+    )
+    .await?;
+
+    // the table is empty now, the next round does nothing.
+    AutoPurgeService::instance().purge_round().await?;
+    check_data_dir(&fixture, "auto purge again", 1, 0, 0, 0, 0, None, None).await?;
+
+    // the data is gone, the table can not be undropped.
+    let qry = format!("undrop table {}.{}", db, tbl);
+    let res = execute_command(fixture.new_query_ctx().await?, qry.as_str()).await;
+    assert_eq!(
+        res.unwrap_err().code(),
+        ErrorCode::TableHistoricalDataNotFound("").code()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_auto_purge_skip_dropped_table_without_retention() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    let qry = format!("create table {}.{}(id int)", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("insert into {}.{}(id) values(1)", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("drop table {}.{}", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+
+    // the table does not specify data_retention_time, it is kept for UNDROP.
+    AutoPurgeService::instance().purge_round().await?;
+    let qry = format!("undrop table {}.{}", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("select count(*) from {}.{}", db, tbl);
+    expects_ok(
+        "undrop",
+        execute_query(fixture.new_query_ctx().await?, qry.as_str()).await,
+        vec![
+            "+----------+",
+            "| Column 0 |",
+            "+----------+",
+            "| 1        |",
+            "+----------+",
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
 mod utils {
     use std::sync::Arc;
 
@@ -0,0 +1,120 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use common_base::base::tokio;
+use common_config::QueryConfig;
+use common_exception::Result;
+use common_storages_fuse::operations::MaintenanceStatistics;
+use common_storages_fuse::FuseTable;
+use databend_query::auto_compaction::plan_maintenance;
+use databend_query::auto_compaction::MaintenanceAction;
+use databend_query::sessions::TableContext;
+
+use crate::storages::fuse::table_test_fixture::execute_command;
+use crate::storages::fuse::table_test_fixture::TestFixture;
+
+async fn maintenance_statistics(
+    fixture: &TestFixture,
+    table: &str,
+) -> Result<Option<MaintenanceStatistics>> {
+    let ctx = fixture.new_query_ctx().await?;
+    let table = ctx
+        .get_table("default", &fixture.default_db_name(), table)
+        .await?;
+    FuseTable::try_from_table(table.as_ref())?
+        .maintenance_statistics(ctx.clone())
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_maintenance_compaction() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    fixture.create_normal_table().await?;
+    assert_eq!(maintenance_statistics(&fixture, &tbl).await?, None);
+
+    for i in 0..3 {
+        let qry = format!("insert into {}.{}(id) values({})", db, tbl, i);
+        execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    }
+    let stats = maintenance_statistics(&fixture, &tbl).await?.unwrap();
+    assert_eq!(stats, MaintenanceStatistics {
+        segment_count: 3,
+        expected_segment_count: 1,
+        block_count: 3,
+        undersized_block_count: 3,
+        average_depth: None,
+    });
+
+    let conf = |block_threshold, segment_threshold| QueryConfig {
+        auto_compaction_block_threshold: block_threshold,
+        auto_compaction_segment_threshold: segment_threshold,
+        ..Default::default()
+    };
+    assert_eq!(
+        plan_maintenance(&conf(3, 2), &stats),
+        Some(MaintenanceAction::CompactBlocks)
+    );
+    assert_eq!(
+        plan_maintenance(&conf(4, 2), &stats),
+        Some(MaintenanceAction::CompactSegments)
+    );
+    assert_eq!(plan_maintenance(&conf(4, 3), &stats), None);
+
+    // the small blocks are merged, nothing is left to be done.
+    let qry = format!("optimize table {}.{} compact", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let stats = maintenance_statistics(&fixture, &tbl).await?.unwrap();
+    assert_eq!(stats, MaintenanceStatistics {
+        segment_count: 1,
+        expected_segment_count: 1,
+        block_count: 1,
+        undersized_block_count: 1,
+        average_depth: None,
+    });
+    assert_eq!(plan_maintenance(&conf(2, 1), &stats), None);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_maintenance_recluster() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+
+    let qry = format!("create table {}.t_cluster(id int) cluster by(id)", db);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    // the ranges of the two blocks overlap.
+    for values in ["(1),(3)", "(2),(4)"] {
+        let qry = format!("insert into {}.t_cluster values{}", db, values);
+        execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    }
+    let stats = maintenance_statistics(&fixture, "t_cluster")
+        .await?
+        .unwrap();
+    assert_eq!(stats.average_depth, Some(2.0));
+
+    let conf = |depth_threshold| QueryConfig {
+        auto_recluster_depth_threshold: depth_threshold,
+        ..Default::default()
+    };
+    assert_eq!(
+        plan_maintenance(&conf(1), &stats),
+        Some(MaintenanceAction::Recluster)
+    );
+    assert_eq!(plan_maintenance(&conf(2), &stats), None);
+
+    Ok(())
+}
@@ -15,14 +15,17 @@
 #![allow(clippy::too_many_arguments)]
 mod alter_table;
 mod analyze;
+mod clone;
 mod clustering;
 mod commit;
 mod gc;
+mod maintenance;
 mod mutation;
 mod navigate;
 mod optimize;
 mod purge_drop;
 mod purge_truncate;
 mod read_plan;
+mod stream;
 mod table_analyze;
 mod truncate;
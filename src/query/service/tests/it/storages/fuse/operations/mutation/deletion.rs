@@ -15,16 +15,21 @@
 use std::sync::Arc;
 
 use common_base::base::tokio;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_sql::plans::DeletePlan;
 use common_sql::plans::Plan;
 use common_sql::Planner;
 use common_storages_factory::Table;
+use common_storages_fuse::io::SegmentsIO;
 use common_storages_fuse::FuseTable;
+use databend_query::interpreters::CreateTableInterpreterV2;
+use databend_query::interpreters::Interpreter;
 use databend_query::pipelines::executor::ExecutorSettings;
 use databend_query::pipelines::executor::PipelineCompleteExecutor;
 use databend_query::sessions::QueryContext;
 use databend_query::sessions::TableContext;
+use storages_common_table_meta::table::OPT_KEY_MERGE_ON_READ;
 
 use crate::storages::fuse::table_test_fixture::execute_command;
 use crate::storages::fuse::table_test_fixture::execute_query;
@@ -79,6 +84,151 @@ async fn test_deletion_mutator_multiple_empty_segments() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deletion_merge_on_read() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let tbl_name = fixture.default_table_name();
+    let db_name = fixture.default_db_name();
+
+    let mut create_table_plan = fixture.normal_create_table_plan();
+    create_table_plan
+        .options
+        .insert(OPT_KEY_MERGE_ON_READ.to_owned(), "true".to_owned());
+    let interpreter = CreateTableInterpreterV2::try_create(fixture.ctx(), create_table_plan)?;
+    interpreter.execute(fixture.ctx()).await?;
+    for values in ["(1),(2),(3)", "(4),(5),(6)"] {
+        let qry = format!("insert into {}.{}(id) values{}", db_name, tbl_name, values);
+        execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    }
+
+    // the rows are marked in the deletion vector, the block is kept.
+    let qry = format!("delete from {}.{} where id=2", db_name, tbl_name);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_deleted_rows(&fixture, "delete one row", vec![0, 1], vec![
+        "+----------+",
+        "| Column 0 |",
+        "+----------+",
+        "| 1        |",
+        "| 3        |",
+        "| 4        |",
+        "| 5        |",
+        "| 6        |",
+        "+----------+",
+    ])
+    .await?;
+
+    // the block is removed if all the rows left are deleted.
+    let qry = format!("delete from {}.{} where id in (1, 3, 4)", db_name, tbl_name);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_deleted_rows(&fixture, "delete the rest of the block", vec![1], vec![
+        "+----------+",
+        "| Column 0 |",
+        "+----------+",
+        "| 5        |",
+        "| 6        |",
+        "+----------+",
+    ])
+    .await?;
+
+    // UPDATE always rewrites the block, without the deleted rows.
+    let qry = format!("update {}.{} set id=50 where id=5", db_name, tbl_name);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("delete from {}.{} where id=6", db_name, tbl_name);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_deleted_rows(&fixture, "update the block", vec![1], vec![
+        "+----------+",
+        "| Column 0 |",
+        "+----------+",
+        "| 50       |",
+        "+----------+",
+    ])
+    .await?;
+
+    // the deleted rows are folded into the data by the compaction.
+    let qry = format!("optimize table {}.{} compact", db_name, tbl_name);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    check_deleted_rows(&fixture, "compact", vec![0], vec![
+        "+----------+",
+        "| Column 0 |",
+        "+----------+",
+        "| 50       |",
+        "+----------+",
+    ])
+    .await?;
+
+    // the deletion vectors are not supported by the native storage format.
+    for options in [
+        "storage_format = 'native' merge_on_read = 'true'",
+        "storage_format = 'parquet' merge_on_read = 'x'",
+    ] {
+        let qry = format!("create table {}.t_invalid(id int) {}", db_name, options);
+        let res = execute_command(fixture.new_query_ctx().await?, qry.as_str()).await;
+        assert_eq!(
+            res.unwrap_err().code(),
+            ErrorCode::TableOptionInvalid("").code(),
+            "case [{}]",
+            options
+        );
+    }
+    Ok(())
+}
+
+// Check the count of the deleted rows in each block of the latest snapshot, and the rows read.
+async fn check_deleted_rows(
+    fixture: &TestFixture,
+    case_name: &str,
+    deleted_rows: Vec<u64>,
+    expected: Vec<&str>,
+) -> Result<()> {
+    let table = fixture.latest_default_table().await?;
+    let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+    let snapshot = fuse_table.read_table_snapshot().await?.unwrap();
+    let segments = SegmentsIO::create(
+        fixture.new_query_ctx().await?,
+        fuse_table.get_operator(),
+        table.schema(),
+    )
+    .read_segments(&snapshot.segments)
+    .await?;
+
+    let mut counts = vec![];
+    for segment in segments {
+        for block in &segment?.blocks {
+            assert_eq!(
+                block.deletion_location.is_some(),
+                block.deleted_row_count > 0
+            );
+            counts.push(block.deleted_row_count);
+        }
+    }
+    counts.sort();
+    assert_eq!(
+        counts, deleted_rows,
+        "case [{}], check deleted rows",
+        case_name
+    );
+
+    // the deleted rows are not counted in the summary.
+    let num_rows = expected.len() as u64 - 4;
+    assert_eq!(
+        snapshot.summary.row_count, num_rows,
+        "case [{}], check row count",
+        case_name
+    );
+
+    let qry = format!(
+        "select id from {}.{}",
+        fixture.default_db_name(),
+        fixture.default_table_name()
+    );
+    expects_ok(
+        case_name,
+        execute_query(fixture.new_query_ctx().await?, qry.as_str()).await,
+        expected,
+    )
+    .await
+}
+
 pub async fn do_deletion(
     ctx: Arc<QueryContext>,
     table: Arc<dyn Table>,
@@ -0,0 +1,183 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use common_base::base::tokio;
+use common_exception::Result;
+use common_storages_fuse::FuseTable;
+use databend_query::sessions::TableContext;
+
+use crate::storages::fuse::table_test_fixture::execute_command;
+use crate::storages::fuse::table_test_fixture::execute_query;
+use crate::storages::fuse::table_test_fixture::expects_ok;
+use crate::storages::fuse::table_test_fixture::TestFixture;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_stream_consume() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    fixture.create_normal_table().await?;
+
+    let qry = format!("create stream {}.s on table {}.{}", db, db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!("insert into {}.{}(id) values(1),(2)", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+
+    // 1. reading the stream does not consume the changes.
+    let expected = vec![
+        "+----------+----------+",
+        "| Column 0 | Column 1 |",
+        "+----------+----------+",
+        "| 1        | INSERT   |",
+        "| 2        | INSERT   |",
+        "+----------+----------+",
+    ];
+    let qry_stream = format!("select id, change$action from {}.s order by id", db);
+    for case in ["read stream", "read stream again"] {
+        expects_ok(
+            case,
+            execute_query(fixture.new_query_ctx().await?, qry_stream.as_str()).await,
+            expected.clone(),
+        )
+        .await?;
+    }
+
+    // 2. inserting from the stream advances its offset.
+    let qry = format!("create table {}.sink(id int, action varchar)", db);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let qry = format!(
+        "insert into {}.sink select id, change$action from {}.s",
+        db, db
+    );
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    expects_ok(
+        "read sink",
+        execute_query(
+            fixture.new_query_ctx().await?,
+            format!("select id, action from {}.sink order by id", db).as_str(),
+        )
+        .await,
+        expected,
+    )
+    .await?;
+    let qry_count = format!("select count(*) from {}.s", db);
+    expects_ok(
+        "stream consumed",
+        execute_query(fixture.new_query_ctx().await?, qry_count.as_str()).await,
+        vec![
+            "+----------+",
+            "| Column 0 |",
+            "+----------+",
+            "| 0        |",
+            "+----------+",
+        ],
+    )
+    .await?;
+
+    // 3. the rows kept by the rewritten block are not reported as changed.
+    let qry = format!("delete from {}.{} where id = 1", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    expects_ok(
+        "read deletion",
+        execute_query(fixture.new_query_ctx().await?, qry_stream.as_str()).await,
+        vec![
+            "+----------+----------+",
+            "| Column 0 | Column 1 |",
+            "+----------+----------+",
+            "| 1        | DELETE   |",
+            "+----------+----------+",
+        ],
+    )
+    .await?;
+
+    // 4. the snapshot at the offset is kept by the gc, the changes can still be read.
+    fixture.ctx().get_settings().set_retention_period(0)?;
+    let qry = format!("optimize table {}.{} purge", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    expects_ok(
+        "read deletion after purge",
+        execute_query(fixture.new_query_ctx().await?, qry_stream.as_str()).await,
+        vec![
+            "+----------+----------+",
+            "| Column 0 | Column 1 |",
+            "+----------+----------+",
+            "| 1        | DELETE   |",
+            "+----------+----------+",
+        ],
+    )
+    .await?;
+
+    // 5. MERGE INTO reading the stream advances its offset as well.
+    let qry = format!(
+        "merge into {}.sink t using {}.s s on t.id = s.id when matched then delete",
+        db, db
+    );
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    expects_ok(
+        "stream consumed by merge",
+        execute_query(fixture.new_query_ctx().await?, qry_count.as_str()).await,
+        vec![
+            "+----------+",
+            "| Column 0 |",
+            "+----------+",
+            "| 0        |",
+            "+----------+",
+        ],
+    )
+    .await?;
+
+    // 6. a stream is not dropped as a table.
+    let qry = format!("drop table {}.s", db);
+    assert!(
+        execute_command(fixture.new_query_ctx().await?, qry.as_str())
+            .await
+            .is_err()
+    );
+    let qry = format!("drop stream {}.s", db);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuse_stream_skip_compacted_rows() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+    let tbl = fixture.default_table_name();
+    fixture.create_normal_table().await?;
+
+    for id in 1..=3 {
+        let qry = format!("insert into {}.{}(id) values({})", db, tbl, id);
+        execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    }
+    let table = fixture.latest_default_table().await?;
+    let base = FuseTable::try_from_table(table.as_ref())?
+        .snapshot_loc()
+        .await?;
+
+    // the blocks compacted are matched by their origins without being read.
+    let qry = format!("optimize table {}.{} compact", db, tbl);
+    execute_command(fixture.new_query_ctx().await?, qry.as_str()).await?;
+    let table = fixture.latest_default_table().await?;
+    let changes = FuseTable::try_from_table(table.as_ref())?
+        .changes_since(fixture.new_query_ctx().await?, base.as_deref())
+        .await?;
+    assert!(changes.removed.is_empty());
+    assert!(changes.marked.is_empty());
+    assert_eq!(changes.added.len(), 1);
+    let (block, moved) = &changes.added[0];
+    assert_eq!(moved, &vec![0..block.row_count]);
+
+    Ok(())
+}
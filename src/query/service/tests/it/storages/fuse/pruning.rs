@@ -100,6 +100,7 @@ async fn test_block_pruner() -> Result<()> {
         field_default_exprs: vec![],
         field_comments: vec![],
         as_select: None,
+        clone_source: None,
         cluster_key: None,
     };
 
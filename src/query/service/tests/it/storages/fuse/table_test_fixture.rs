@@ -124,6 +124,11 @@ impl TestFixture {
         self.ctx.clone()
     }
 
+    /// A new query context of the same session, which sees the latest version of the tables.
+    pub async fn new_query_ctx(&self) -> Result<Arc<QueryContext>> {
+        self.ctx.get_current_session().create_query_context().await
+    }
+
     pub fn default_tenant(&self) -> String {
         self.ctx().get_tenant()
     }
@@ -175,6 +180,7 @@ impl TestFixture {
             field_default_exprs: vec![],
             field_comments: vec![],
             as_select: None,
+            clone_source: None,
             cluster_key: Some("(id)".to_string()),
         }
     }
@@ -199,6 +205,7 @@ impl TestFixture {
             field_default_exprs: vec![],
             field_comments: vec![],
             as_select: None,
+            clone_source: None,
             cluster_key: None,
         }
     }
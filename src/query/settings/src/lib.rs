@@ -286,6 +286,16 @@ impl Settings {
                 desc: "The threshold of keys to open two-level aggregation, default value: 20000.",
                 possible_values: None,
             },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "spilling_bytes_threshold_per_proc",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The maximum bytes of the hash table of an aggregation processor kept in memory on a standalone node, the partitions are spilled to the storage once it's exceeded, 0 means never spill, default value: 0.",
+                possible_values: None,
+            },
             SettingValue {
                 default_value: UserSettingValue::UInt64(3),
                 user_setting: UserSetting::create("max_inlist_to_or", UserSettingValue::UInt64(3)),
@@ -400,6 +410,46 @@ impl Settings {
                 desc: "the max number of rows each read from parquet to databend processor",
                 possible_values: None,
             },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(1000),
+                user_setting: UserSetting::create(
+                    "max_cte_recursive_depth",
+                    UserSettingValue::UInt64(1000),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The maximum number of iterations of a recursive common table expression, default value: 1000.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(1024 * 1024 * 1024),
+                user_setting: UserSetting::create(
+                    "max_merge_into_source_bytes",
+                    UserSettingValue::UInt64(1024 * 1024 * 1024),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The maximum bytes of the source of MERGE INTO kept in memory, the rest is spilled to the storage, default value: 1073741824 (1GB).",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "enable_materialized_cte",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Execute the common table expressions referenced more than once only once, and share the result among the references. It's disabled by default.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(104857600), // 100MB
+                user_setting: UserSetting::create(
+                    "materialized_cte_spill_bytes",
+                    UserSettingValue::UInt64(104857600),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The maximum bytes of a materialized common table expression kept in memory, the rest are spilled to the storage, 0 means never spill, default: 104857600 bytes (100MB).",
+                possible_values: None,
+            },
             SettingValue {
                 default_value: UserSettingValue::UInt64(0),
                 user_setting: UserSetting::create("max_result_rows", UserSettingValue::UInt64(0)),
@@ -484,6 +534,16 @@ impl Settings {
                 desc: "Enable generating bushy join plan in optimizer",
                 possible_values: None,
             },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(1),
+                user_setting: UserSetting::create(
+                    "enable_sort_merge_join",
+                    UserSettingValue::UInt64(1),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Enable choosing sort merge join by cost for inner equi-joins, default value: 1.",
+                possible_values: None,
+            },
             SettingValue {
                 default_value: UserSettingValue::UInt64(0),
                 user_setting: UserSetting::create(
@@ -524,6 +584,26 @@ impl Settings {
                 desc: "Tolerate inconsistent result cache. It's disabled by default.",
                 possible_values: None,
             },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(104857600), // 100MB
+                user_setting: UserSetting::create(
+                    "max_async_query_result_bytes",
+                    UserSettingValue::UInt64(104857600),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The maximum bytes of the result kept for a query submitted asynchronously over HTTP, default: 104857600 bytes (100MB).",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(86400), // seconds
+                user_setting: UserSetting::create(
+                    "async_query_result_ttl",
+                    UserSettingValue::UInt64(86400),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Time-to-live of the state and the result of a query submitted asynchronously over HTTP, default: 86400 seconds (1 day).",
+                possible_values: None,
+            },
         ];
 
         let settings: Arc<DashMap<String, SettingValue>> = Arc::new(DashMap::default());
@@ -683,6 +763,12 @@ impl Settings {
         self.try_get_u64(KEY)
     }
 
+    pub fn get_enable_sort_merge_join(&self) -> Result<bool> {
+        static KEY: &str = "enable_sort_merge_join";
+        let v = self.try_get_u64(KEY)?;
+        Ok(v != 0)
+    }
+
     pub fn get_timezone(&self) -> Result<String> {
         let key = "timezone";
         self.check_and_get_setting_value(key)
@@ -701,6 +787,11 @@ impl Settings {
         self.try_set_u64(key, val, false)
     }
 
+    pub fn get_spilling_bytes_threshold_per_proc(&self) -> Result<usize> {
+        static KEY: &str = "spilling_bytes_threshold_per_proc";
+        self.try_get_u64(KEY).map(|v| v as usize)
+    }
+
     pub fn get_max_inlist_to_or(&self) -> Result<u64> {
         let key = "max_inlist_to_or";
         self.try_get_u64(key)
@@ -772,6 +863,26 @@ impl Settings {
         Ok(v)
     }
 
+    pub fn get_max_cte_recursive_depth(&self) -> Result<u64> {
+        static KEY: &str = "max_cte_recursive_depth";
+        self.try_get_u64(KEY)
+    }
+
+    pub fn get_max_merge_into_source_bytes(&self) -> Result<usize> {
+        static KEY: &str = "max_merge_into_source_bytes";
+        self.try_get_u64(KEY).map(|v| v as usize)
+    }
+
+    pub fn get_enable_materialized_cte(&self) -> Result<bool> {
+        static KEY: &str = "enable_materialized_cte";
+        self.try_get_u64(KEY).map(|v| v != 0)
+    }
+
+    pub fn get_materialized_cte_spill_bytes(&self) -> Result<usize> {
+        static KEY: &str = "materialized_cte_spill_bytes";
+        self.try_get_u64(KEY).map(|v| v as usize)
+    }
+
     pub fn set_enable_distributed_eval_index(&self, val: bool) -> Result<()> {
         static KEY: &str = "enable_distributed_eval_index";
         let v = u64::from(val);
@@ -875,6 +986,16 @@ impl Settings {
         self.try_get_u64(key).map(|v| v != 0)
     }
 
+    pub fn get_max_async_query_result_bytes(&self) -> Result<usize> {
+        let key = "max_async_query_result_bytes";
+        self.try_get_u64(key).map(|v| v as usize)
+    }
+
+    pub fn get_async_query_result_ttl(&self) -> Result<u64> {
+        let key = "async_query_result_ttl";
+        self.try_get_u64(key)
+    }
+
     pub fn has_setting(&self, key: &str) -> bool {
         self.settings.get(key).is_some()
     }
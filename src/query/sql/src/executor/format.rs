@@ -19,19 +19,28 @@ use common_functions::scalars::BUILTIN_FUNCTIONS;
 use common_profile::ProfSpanSetRef;
 use itertools::Itertools;
 
+use super::AggregateExpand;
 use super::AggregateFinal;
 use super::AggregateFunctionDesc;
 use super::AggregatePartial;
+use super::CteScan;
 use super::EvalScalar;
 use super::Exchange;
 use super::Filter;
 use super::HashJoin;
 use super::Limit;
+use super::MaterializedCte;
 use super::PhysicalPlan;
 use super::Project;
+use super::ProjectSet;
+use super::RecursiveCteScan;
+use super::RecursiveUnion;
+use super::SetOperation;
 use super::Sort;
+use super::SortMergeJoin;
 use super::TableScan;
 use super::UnionAll;
+use super::Window;
 use crate::executor::explain::PlanStatsInfo;
 use crate::executor::DistributedInsertSelect;
 use crate::executor::ExchangeSink;
@@ -63,6 +72,9 @@ fn to_format_tree(
         PhysicalPlan::Filter(plan) => filter_to_format_tree(plan, metadata, prof_span_set),
         PhysicalPlan::Project(plan) => project_to_format_tree(plan, metadata, prof_span_set),
         PhysicalPlan::EvalScalar(plan) => eval_scalar_to_format_tree(plan, metadata, prof_span_set),
+        PhysicalPlan::AggregateExpand(plan) => {
+            aggregate_expand_to_format_tree(plan, metadata, prof_span_set)
+        }
         PhysicalPlan::AggregatePartial(plan) => {
             aggregate_partial_to_format_tree(plan, metadata, prof_span_set)
         }
@@ -70,10 +82,26 @@ fn to_format_tree(
             aggregate_final_to_format_tree(plan, metadata, prof_span_set)
         }
         PhysicalPlan::Sort(plan) => sort_to_format_tree(plan, metadata, prof_span_set),
+        PhysicalPlan::Window(plan) => window_to_format_tree(plan, metadata, prof_span_set),
         PhysicalPlan::Limit(plan) => limit_to_format_tree(plan, metadata, prof_span_set),
         PhysicalPlan::HashJoin(plan) => hash_join_to_format_tree(plan, metadata, prof_span_set),
+        PhysicalPlan::SortMergeJoin(plan) => {
+            sort_merge_join_to_format_tree(plan, metadata, prof_span_set)
+        }
         PhysicalPlan::Exchange(plan) => exchange_to_format_tree(plan, metadata, prof_span_set),
         PhysicalPlan::UnionAll(plan) => union_all_to_format_tree(plan, metadata, prof_span_set),
+        PhysicalPlan::RecursiveUnion(plan) => {
+            recursive_union_to_format_tree(plan, metadata, prof_span_set)
+        }
+        PhysicalPlan::RecursiveCteScan(plan) => recursive_cte_scan_to_format_tree(plan),
+        PhysicalPlan::MaterializedCte(plan) => {
+            materialized_cte_to_format_tree(plan, metadata, prof_span_set)
+        }
+        PhysicalPlan::CteScan(plan) => cte_scan_to_format_tree(plan),
+        PhysicalPlan::SetOperation(plan) => {
+            set_operation_to_format_tree(plan, metadata, prof_span_set)
+        }
+        PhysicalPlan::ProjectSet(plan) => project_set_to_format_tree(plan, metadata, prof_span_set),
         PhysicalPlan::ExchangeSource(plan) => exchange_source_to_format_tree(plan),
         PhysicalPlan::ExchangeSink(plan) => {
             exchange_sink_to_format_tree(plan, metadata, prof_span_set)
@@ -275,6 +303,54 @@ pub fn pretty_display_agg_desc(desc: &AggregateFunctionDesc, metadata: &Metadata
     )
 }
 
+fn aggregate_expand_to_format_tree(
+    plan: &AggregateExpand,
+    metadata: &MetadataRef,
+    prof_span_set: &ProfSpanSetRef,
+) -> Result<FormatTreeNode<String>> {
+    let sets = plan
+        .grouping_sets
+        .iter()
+        .map(|set| {
+            set.iter()
+                .map(|column| {
+                    let column = metadata.read().column(*column).clone();
+                    match column {
+                        ColumnEntry::BaseTableColumn(BaseTableColumn { column_name, .. }) => {
+                            column_name
+                        }
+                        ColumnEntry::DerivedColumn(DerivedColumn { alias, .. }) => alias,
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .map(|s| format!("({})", s))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut children = vec![FormatTreeNode::new(format!("grouping sets: [{sets}]"))];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    if let Some(prof_span) = prof_span_set.lock().unwrap().get(&plan.plan_id) {
+        let process_time = prof_span.process_time / 1000 / 1000; // milliseconds
+        children.push(FormatTreeNode::new(format!(
+            "total process time: {process_time}ms"
+        )));
+    }
+
+    children.push(to_format_tree(&plan.input, metadata, prof_span_set)?);
+
+    Ok(FormatTreeNode::with_children(
+        "AggregateExpand".to_string(),
+        children,
+    ))
+}
+
 fn aggregate_partial_to_format_tree(
     plan: &AggregatePartial,
     metadata: &MetadataRef,
@@ -429,6 +505,64 @@ fn sort_to_format_tree(
     Ok(FormatTreeNode::with_children("Sort".to_string(), children))
 }
 
+fn window_to_format_tree(
+    plan: &Window,
+    metadata: &MetadataRef,
+    prof_span_set: &ProfSpanSetRef,
+) -> Result<FormatTreeNode<String>> {
+    let column_name = |index| match metadata.read().column(index).clone() {
+        ColumnEntry::BaseTableColumn(BaseTableColumn { column_name, .. }) => column_name,
+        ColumnEntry::DerivedColumn(DerivedColumn { alias, .. }) => alias,
+    };
+
+    let partition_by = plan
+        .partition_by
+        .iter()
+        .map(|index| column_name(*index))
+        .join(", ");
+
+    let order_by = plan
+        .order_by
+        .iter()
+        .map(|sort_key| {
+            format!(
+                "{} {} {}",
+                column_name(sort_key.order_by),
+                if sort_key.asc { "ASC" } else { "DESC" },
+                if sort_key.nulls_first {
+                    "NULLS FIRST"
+                } else {
+                    "NULLS LAST"
+                }
+            )
+        })
+        .join(", ");
+
+    let mut children = vec![
+        FormatTreeNode::new(format!("output column: [{}]", column_name(plan.index))),
+        FormatTreeNode::new(format!("function: [{}]", plan.func)),
+        FormatTreeNode::new(format!("partition by: [{partition_by}]")),
+        FormatTreeNode::new(format!("order by: [{order_by}]")),
+        FormatTreeNode::new(format!("frame: [{}]", plan.window_frame)),
+    ];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    if let Some(prof_span) = prof_span_set.lock().unwrap().get(&plan.plan_id) {
+        let process_time = prof_span.process_time / 1000 / 1000; // milliseconds
+        children.push(FormatTreeNode::new(format!(
+            "total process time: {process_time}ms"
+        )));
+    }
+
+    children.push(to_format_tree(&plan.input, metadata, prof_span_set)?);
+
+    Ok(FormatTreeNode::with_children("Window".to_string(), children))
+}
+
 fn limit_to_format_tree(
     plan: &Limit,
     metadata: &MetadataRef,
@@ -518,6 +652,58 @@ fn hash_join_to_format_tree(
     ))
 }
 
+fn sort_merge_join_to_format_tree(
+    plan: &SortMergeJoin,
+    metadata: &MetadataRef,
+    prof_span_set: &ProfSpanSetRef,
+) -> Result<FormatTreeNode<String>> {
+    let left_keys = plan
+        .left_keys
+        .iter()
+        .map(|scalar| scalar.as_expr(&BUILTIN_FUNCTIONS).sql_display())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let right_keys = plan
+        .right_keys
+        .iter()
+        .map(|scalar| scalar.as_expr(&BUILTIN_FUNCTIONS).sql_display())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let filters = plan
+        .non_equi_conditions
+        .iter()
+        .map(|filter| filter.as_expr(&BUILTIN_FUNCTIONS).sql_display())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut children = vec![
+        FormatTreeNode::new(format!("join type: {}", plan.join_type)),
+        FormatTreeNode::new(format!("left keys: [{left_keys}]")),
+        FormatTreeNode::new(format!("right keys: [{right_keys}]")),
+        FormatTreeNode::new(format!("filters: [{filters}]")),
+    ];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    if let Some(prof_span) = prof_span_set.lock().unwrap().get(&plan.plan_id) {
+        let process_time = prof_span.process_time / 1000 / 1000; // milliseconds
+        children.push(FormatTreeNode::new(format!(
+            "total process time: {process_time}ms"
+        )));
+    }
+
+    children.push(to_format_tree(&plan.left, metadata, prof_span_set)?);
+    children.push(to_format_tree(&plan.right, metadata, prof_span_set)?);
+
+    Ok(FormatTreeNode::with_children(
+        "SortMergeJoin".to_string(),
+        children,
+    ))
+}
+
 fn exchange_to_format_tree(
     plan: &Exchange,
     metadata: &MetadataRef,
@@ -571,6 +757,154 @@ fn union_all_to_format_tree(
     ))
 }
 
+fn recursive_union_to_format_tree(
+    plan: &RecursiveUnion,
+    metadata: &MetadataRef,
+    prof_span_set: &ProfSpanSetRef,
+) -> Result<FormatTreeNode<String>> {
+    let mut children = vec![
+        FormatTreeNode::new(format!("cte: {}", plan.cte_name)),
+        FormatTreeNode::new(format!("distinct: {}", plan.distinct)),
+    ];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    if let Some(prof_span) = prof_span_set.lock().unwrap().get(&plan.plan_id) {
+        let process_time = prof_span.process_time / 1000 / 1000; // milliseconds
+        children.push(FormatTreeNode::new(format!(
+            "total process time: {process_time}ms"
+        )));
+    }
+
+    children.extend(vec![
+        to_format_tree(&plan.left, metadata, prof_span_set)?,
+        to_format_tree(&plan.right, metadata, prof_span_set)?,
+    ]);
+
+    Ok(FormatTreeNode::with_children(
+        "RecursiveUnion".to_string(),
+        children,
+    ))
+}
+
+fn recursive_cte_scan_to_format_tree(plan: &RecursiveCteScan) -> Result<FormatTreeNode<String>> {
+    Ok(FormatTreeNode::with_children(
+        "RecursiveCteScan".to_string(),
+        vec![FormatTreeNode::new(format!("cte: {}", plan.cte_name))],
+    ))
+}
+
+fn materialized_cte_to_format_tree(
+    plan: &MaterializedCte,
+    metadata: &MetadataRef,
+    prof_span_set: &ProfSpanSetRef,
+) -> Result<FormatTreeNode<String>> {
+    let mut children = vec![FormatTreeNode::new(format!("cte: {}", plan.cte_name))];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    if let Some(prof_span) = prof_span_set.lock().unwrap().get(&plan.plan_id) {
+        let process_time = prof_span.process_time / 1000 / 1000; // milliseconds
+        children.push(FormatTreeNode::new(format!(
+            "total process time: {process_time}ms"
+        )));
+    }
+
+    children.extend(vec![
+        to_format_tree(&plan.left, metadata, prof_span_set)?,
+        to_format_tree(&plan.right, metadata, prof_span_set)?,
+    ]);
+
+    Ok(FormatTreeNode::with_children(
+        "MaterializedCte".to_string(),
+        children,
+    ))
+}
+
+fn cte_scan_to_format_tree(plan: &CteScan) -> Result<FormatTreeNode<String>> {
+    let mut children = vec![FormatTreeNode::new(format!("cte: {}", plan.cte_name))];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    Ok(FormatTreeNode::with_children("CteScan".to_string(), children))
+}
+
+fn set_operation_to_format_tree(
+    plan: &SetOperation,
+    metadata: &MetadataRef,
+    prof_span_set: &ProfSpanSetRef,
+) -> Result<FormatTreeNode<String>> {
+    let op_type = &plan.op_type;
+    let mut children = vec![FormatTreeNode::new(format!("set operation: {op_type}"))];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    if let Some(prof_span) = prof_span_set.lock().unwrap().get(&plan.plan_id) {
+        let process_time = prof_span.process_time / 1000 / 1000; // milliseconds
+        children.push(FormatTreeNode::new(format!(
+            "total process time: {process_time}ms"
+        )));
+    }
+
+    children.extend(vec![
+        to_format_tree(&plan.left, metadata, prof_span_set)?,
+        to_format_tree(&plan.right, metadata, prof_span_set)?,
+    ]);
+
+    Ok(FormatTreeNode::with_children(
+        "SetOperation".to_string(),
+        children,
+    ))
+}
+
+fn project_set_to_format_tree(
+    plan: &ProjectSet,
+    metadata: &MetadataRef,
+    prof_span_set: &ProfSpanSetRef,
+) -> Result<FormatTreeNode<String>> {
+    let args = plan
+        .args
+        .iter()
+        .map(|expr| expr.as_expr(&BUILTIN_FUNCTIONS).sql_display())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut children = vec![
+        FormatTreeNode::new(format!("set returning function: {}({args})", plan.srf)),
+        FormatTreeNode::new(format!("outer: {}", plan.outer)),
+    ];
+
+    if let Some(info) = &plan.stat_info {
+        let items = plan_stats_info_to_format_tree(info);
+        children.extend(items);
+    }
+
+    if let Some(prof_span) = prof_span_set.lock().unwrap().get(&plan.plan_id) {
+        let process_time = prof_span.process_time / 1000 / 1000; // milliseconds
+        children.push(FormatTreeNode::new(format!(
+            "total process time: {process_time}ms"
+        )));
+    }
+
+    children.push(to_format_tree(&plan.input, metadata, prof_span_set)?);
+
+    Ok(FormatTreeNode::with_children(
+        "ProjectSet".to_string(),
+        children,
+    ))
+}
+
 fn part_stats_info_to_format_tree(info: &PartStatistics) -> Vec<FormatTreeNode<String>> {
     let mut items = vec![
         FormatTreeNode::new(format!("read rows: {}", info.read_rows)),
@@ -13,10 +13,13 @@
 // limitations under the License.
 
 use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
 
 use common_catalog::plan::DataSourcePlan;
 use common_exception::Result;
 use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
 use common_expression::DataBlock;
 use common_expression::DataField;
 use common_expression::DataSchemaRef;
@@ -29,6 +32,9 @@ use common_meta_app::schema::TableInfo;
 use crate::executor::explain::PlanStatsInfo;
 use crate::optimizer::ColumnSet;
 use crate::plans::JoinType;
+use crate::plans::SetOperationType;
+use crate::plans::SetReturningFunction;
+use crate::plans::WindowFuncFrame;
 use crate::ColumnBinding;
 use crate::IndexType;
 
@@ -133,6 +139,44 @@ impl EvalScalar {
     }
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AggregateExpand {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    pub input: Box<PhysicalPlan>,
+    pub group_bys: Vec<IndexType>,
+    pub grouping_id_index: IndexType,
+    pub grouping_sets: Vec<Vec<IndexType>>,
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl AggregateExpand {
+    /// Check if the group by column will be filled with NULL in some grouping sets.
+    pub fn is_nullable_group_by(&self, index: &IndexType) -> bool {
+        self.grouping_sets.iter().any(|set| !set.contains(index))
+    }
+
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        let input_schema = self.input.output_schema()?;
+        let mut fields = input_schema.fields().clone();
+        for group_by in self.group_bys.iter() {
+            if self.is_nullable_group_by(group_by) {
+                let offset = input_schema.index_of(&group_by.to_string())?;
+                let data_type = fields[offset].data_type().wrap_nullable();
+                fields[offset] = DataField::new(&group_by.to_string(), data_type);
+            }
+        }
+        fields.push(DataField::new(
+            &self.grouping_id_index.to_string(),
+            DataType::Number(NumberDataType::UInt32),
+        ));
+        Ok(DataSchemaRefExt::create(fields))
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct AggregatePartial {
     /// A unique id of operator in a `PhysicalPlan` tree.
@@ -185,6 +229,9 @@ pub struct AggregateFinal {
     pub group_by: Vec<IndexType>,
     pub agg_funcs: Vec<AggregateFunctionDesc>,
     pub before_group_by_schema: DataSchemaRef,
+    /// The grouping id of the empty grouping set `()` if there is one, a row
+    /// will be emitted for it even if the input is empty.
+    pub empty_grouping_id: Option<u32>,
 
     pub limit: Option<usize>,
     /// Only used for explain
@@ -231,6 +278,35 @@ impl Sort {
     }
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Window {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    /// Output column of the window function
+    pub index: IndexType,
+    pub input: Box<PhysicalPlan>,
+    pub func: WindowFunction,
+    pub partition_by: Vec<IndexType>,
+    pub order_by: Vec<SortDesc>,
+    pub window_frame: WindowFuncFrame,
+
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl Window {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        let mut fields = self.input.output_schema()?.fields().clone();
+        fields.push(DataField::new(
+            &self.index.to_string(),
+            self.func.data_type(),
+        ));
+        Ok(DataSchemaRefExt::create(fields))
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Limit {
     /// A unique id of operator in a `PhysicalPlan` tree.
@@ -351,6 +427,32 @@ impl HashJoin {
     }
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SortMergeJoin {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    /// Both children are sorted by the join keys in ascending order, with nulls last.
+    pub left: Box<PhysicalPlan>,
+    pub right: Box<PhysicalPlan>,
+    pub left_keys: Vec<RemoteExpr>,
+    pub right_keys: Vec<RemoteExpr>,
+    pub non_equi_conditions: Vec<RemoteExpr>,
+    pub join_type: JoinType,
+
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl SortMergeJoin {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        let mut fields = self.left.output_schema()?.fields().clone();
+        fields.extend(self.right.output_schema()?.fields().clone());
+        Ok(DataSchemaRefExt::create(fields))
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Exchange {
     pub input: Box<PhysicalPlan>,
@@ -433,6 +535,148 @@ impl UnionAll {
     }
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RecursiveUnion {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    pub cte_name: String,
+    /// The non-recursive term
+    pub left: Box<PhysicalPlan>,
+    /// The recursive term, executed once per iteration
+    pub right: Box<PhysicalPlan>,
+    pub pairs: Vec<(String, String)>,
+    pub distinct: bool,
+    pub schema: DataSchemaRef,
+
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl RecursiveUnion {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        Ok(self.schema.clone())
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RecursiveCteScan {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    pub cte_name: String,
+    pub schema: DataSchemaRef,
+
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl RecursiveCteScan {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        Ok(self.schema.clone())
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MaterializedCte {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    pub cte_idx: IndexType,
+    pub cte_name: String,
+    /// The definition of the CTE, executed only once
+    pub left: Box<PhysicalPlan>,
+    /// The query referencing the CTE
+    pub right: Box<PhysicalPlan>,
+    /// Output columns of the left child, in the order of the columns of the CTE
+    pub columns: Vec<IndexType>,
+
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl MaterializedCte {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        self.right.output_schema()
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CteScan {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    pub cte_idx: IndexType,
+    pub cte_name: String,
+    pub schema: DataSchemaRef,
+
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl CteScan {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        Ok(self.schema.clone())
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SetOperation {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    pub op_type: SetOperationType,
+    /// The rows to output
+    pub left: Box<PhysicalPlan>,
+    /// The rows to count
+    pub right: Box<PhysicalPlan>,
+    /// Pairs of compared columns
+    pub pairs: Vec<(String, String)>,
+
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl SetOperation {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        self.left.output_schema()
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProjectSet {
+    /// A unique id of operator in a `PhysicalPlan` tree.
+    /// Only used for display.
+    pub plan_id: u32,
+
+    pub input: Box<PhysicalPlan>,
+    pub srf: SetReturningFunction,
+    pub args: Vec<RemoteExpr>,
+    /// Output columns of the function
+    pub columns: Vec<(IndexType, DataType)>,
+    /// Keep the input rows for which the function returns no rows
+    pub outer: bool,
+
+    /// Only used for explain
+    pub stat_info: Option<PlanStatsInfo>,
+}
+
+impl ProjectSet {
+    pub fn output_schema(&self) -> Result<DataSchemaRef> {
+        let input_schema = self.input.output_schema()?;
+        let mut fields = input_schema.fields().clone();
+        for (index, data_type) in self.columns.iter() {
+            fields.push(DataField::new(&index.to_string(), data_type.clone()));
+        }
+        Ok(DataSchemaRefExt::create(fields))
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct DistributedInsertSelect {
     pub input: Box<PhysicalPlan>,
@@ -459,13 +703,22 @@ pub enum PhysicalPlan {
     Filter(Filter),
     Project(Project),
     EvalScalar(EvalScalar),
+    AggregateExpand(AggregateExpand),
     AggregatePartial(AggregatePartial),
     AggregateFinal(AggregateFinal),
     Sort(Sort),
+    Window(Window),
     Limit(Limit),
     HashJoin(HashJoin),
+    SortMergeJoin(SortMergeJoin),
     Exchange(Exchange),
     UnionAll(UnionAll),
+    RecursiveUnion(RecursiveUnion),
+    RecursiveCteScan(RecursiveCteScan),
+    MaterializedCte(MaterializedCte),
+    CteScan(CteScan),
+    SetOperation(SetOperation),
+    ProjectSet(ProjectSet),
 
     /// For insert into ... select ... in cluster
     DistributedInsertSelect(Box<DistributedInsertSelect>),
@@ -490,15 +743,24 @@ impl PhysicalPlan {
             PhysicalPlan::Filter(plan) => plan.output_schema(),
             PhysicalPlan::Project(plan) => plan.output_schema(),
             PhysicalPlan::EvalScalar(plan) => plan.output_schema(),
+            PhysicalPlan::AggregateExpand(plan) => plan.output_schema(),
             PhysicalPlan::AggregatePartial(plan) => plan.output_schema(),
             PhysicalPlan::AggregateFinal(plan) => plan.output_schema(),
             PhysicalPlan::Sort(plan) => plan.output_schema(),
+            PhysicalPlan::Window(plan) => plan.output_schema(),
             PhysicalPlan::Limit(plan) => plan.output_schema(),
             PhysicalPlan::HashJoin(plan) => plan.output_schema(),
+            PhysicalPlan::SortMergeJoin(plan) => plan.output_schema(),
             PhysicalPlan::Exchange(plan) => plan.output_schema(),
             PhysicalPlan::ExchangeSource(plan) => plan.output_schema(),
             PhysicalPlan::ExchangeSink(plan) => plan.output_schema(),
             PhysicalPlan::UnionAll(plan) => plan.output_schema(),
+            PhysicalPlan::RecursiveUnion(plan) => plan.output_schema(),
+            PhysicalPlan::RecursiveCteScan(plan) => plan.output_schema(),
+            PhysicalPlan::MaterializedCte(plan) => plan.output_schema(),
+            PhysicalPlan::CteScan(plan) => plan.output_schema(),
+            PhysicalPlan::SetOperation(plan) => plan.output_schema(),
+            PhysicalPlan::ProjectSet(plan) => plan.output_schema(),
             PhysicalPlan::DistributedInsertSelect(plan) => plan.output_schema(),
         }
     }
@@ -509,13 +771,22 @@ impl PhysicalPlan {
             PhysicalPlan::Filter(_) => "Filter".to_string(),
             PhysicalPlan::Project(_) => "Project".to_string(),
             PhysicalPlan::EvalScalar(_) => "EvalScalar".to_string(),
+            PhysicalPlan::AggregateExpand(_) => "AggregateExpand".to_string(),
             PhysicalPlan::AggregatePartial(_) => "AggregatePartial".to_string(),
             PhysicalPlan::AggregateFinal(_) => "AggregateFinal".to_string(),
             PhysicalPlan::Sort(_) => "Sort".to_string(),
+            PhysicalPlan::Window(_) => "Window".to_string(),
             PhysicalPlan::Limit(_) => "Limit".to_string(),
             PhysicalPlan::HashJoin(_) => "HashJoin".to_string(),
+            PhysicalPlan::SortMergeJoin(_) => "SortMergeJoin".to_string(),
             PhysicalPlan::Exchange(_) => "Exchange".to_string(),
             PhysicalPlan::UnionAll(_) => "UnionAll".to_string(),
+            PhysicalPlan::RecursiveUnion(_) => "RecursiveUnion".to_string(),
+            PhysicalPlan::RecursiveCteScan(_) => "RecursiveCteScan".to_string(),
+            PhysicalPlan::MaterializedCte(_) => "MaterializedCte".to_string(),
+            PhysicalPlan::CteScan(_) => "CteScan".to_string(),
+            PhysicalPlan::SetOperation(_) => "SetOperation".to_string(),
+            PhysicalPlan::ProjectSet(_) => "ProjectSet".to_string(),
             PhysicalPlan::DistributedInsertSelect(_) => "DistributedInsertSelect".to_string(),
             PhysicalPlan::ExchangeSource(_) => "Exchange Source".to_string(),
             PhysicalPlan::ExchangeSink(_) => "Exchange Sink".to_string(),
@@ -528,19 +799,36 @@ impl PhysicalPlan {
             PhysicalPlan::Filter(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::Project(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::EvalScalar(plan) => Box::new(std::iter::once(plan.input.as_ref())),
+            PhysicalPlan::AggregateExpand(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::AggregatePartial(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::AggregateFinal(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::Sort(plan) => Box::new(std::iter::once(plan.input.as_ref())),
+            PhysicalPlan::Window(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::Limit(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::HashJoin(plan) => Box::new(
                 std::iter::once(plan.probe.as_ref()).chain(std::iter::once(plan.build.as_ref())),
             ),
+            PhysicalPlan::SortMergeJoin(plan) => Box::new(
+                std::iter::once(plan.left.as_ref()).chain(std::iter::once(plan.right.as_ref())),
+            ),
             PhysicalPlan::Exchange(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::ExchangeSource(_) => Box::new(std::iter::empty()),
             PhysicalPlan::ExchangeSink(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::UnionAll(plan) => Box::new(
                 std::iter::once(plan.left.as_ref()).chain(std::iter::once(plan.right.as_ref())),
             ),
+            PhysicalPlan::RecursiveUnion(plan) => Box::new(
+                std::iter::once(plan.left.as_ref()).chain(std::iter::once(plan.right.as_ref())),
+            ),
+            PhysicalPlan::RecursiveCteScan(_) => Box::new(std::iter::empty()),
+            PhysicalPlan::MaterializedCte(plan) => Box::new(
+                std::iter::once(plan.left.as_ref()).chain(std::iter::once(plan.right.as_ref())),
+            ),
+            PhysicalPlan::CteScan(_) => Box::new(std::iter::empty()),
+            PhysicalPlan::SetOperation(plan) => Box::new(
+                std::iter::once(plan.left.as_ref()).chain(std::iter::once(plan.right.as_ref())),
+            ),
+            PhysicalPlan::ProjectSet(plan) => Box::new(std::iter::once(plan.input.as_ref())),
             PhysicalPlan::DistributedInsertSelect(plan) => {
                 Box::new(std::iter::once(plan.input.as_ref()))
             }
@@ -564,6 +852,62 @@ pub struct AggregateFunctionSignature {
     pub return_type: DataType,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum WindowFunction {
+    Aggregate(AggregateFunctionDesc),
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag(LagLeadFunctionDesc),
+    Lead(LagLeadFunctionDesc),
+    FirstValue(FirstLastValueFunctionDesc),
+    LastValue(FirstLastValueFunctionDesc),
+}
+
+impl WindowFunction {
+    pub fn data_type(&self) -> DataType {
+        match self {
+            WindowFunction::Aggregate(agg) => agg.sig.return_type.clone(),
+            WindowFunction::RowNumber | WindowFunction::Rank | WindowFunction::DenseRank => {
+                DataType::Number(NumberDataType::UInt64)
+            }
+            WindowFunction::Lag(func) | WindowFunction::Lead(func) => func.return_type.clone(),
+            WindowFunction::FirstValue(func) | WindowFunction::LastValue(func) => {
+                func.return_type.clone()
+            }
+        }
+    }
+}
+
+impl Display for WindowFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowFunction::Aggregate(agg) => write!(f, "{}", agg.sig.name),
+            WindowFunction::RowNumber => write!(f, "row_number"),
+            WindowFunction::Rank => write!(f, "rank"),
+            WindowFunction::DenseRank => write!(f, "dense_rank"),
+            WindowFunction::Lag(_) => write!(f, "lag"),
+            WindowFunction::Lead(_) => write!(f, "lead"),
+            WindowFunction::FirstValue(_) => write!(f, "first_value"),
+            WindowFunction::LastValue(_) => write!(f, "last_value"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LagLeadFunctionDesc {
+    pub arg: IndexType,
+    pub offset: u64,
+    pub default: Option<IndexType>,
+    pub return_type: DataType,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FirstLastValueFunctionDesc {
+    pub arg: IndexType,
+    pub return_type: DataType,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct SortDesc {
     pub asc: bool,
@@ -27,7 +27,8 @@ use common_exception::Result;
 use common_expression::type_check::check_function;
 use common_expression::types::DataType;
 use common_expression::ConstantFolder;
-use common_expression::DataBlock;
+use common_expression::DataField;
+use common_expression::DataSchemaRef;
 use common_expression::DataSchemaRefExt;
 use common_expression::Expr;
 use common_expression::RemoteExpr;
@@ -36,16 +37,28 @@ use common_functions::scalars::BUILTIN_FUNCTIONS;
 use itertools::Itertools;
 
 use super::cast_expr_to_non_null_boolean;
+use super::AggregateExpand;
 use super::AggregateFinal;
 use super::AggregateFunctionDesc;
 use super::AggregateFunctionSignature;
 use super::AggregatePartial;
+use super::CteScan;
 use super::Exchange as PhysicalExchange;
 use super::Filter;
+use super::FirstLastValueFunctionDesc;
 use super::HashJoin;
+use super::LagLeadFunctionDesc;
 use super::Limit;
+use super::MaterializedCte;
+use super::ProjectSet;
+use super::RecursiveCteScan;
+use super::RecursiveUnion;
+use super::SetOperation;
 use super::Sort;
+use super::SortMergeJoin;
 use super::TableScan;
+use super::Window;
+use super::WindowFunction;
 use crate::executor::explain::PlanStatsInfo;
 use crate::executor::table_read_plan::ToReadDataSourcePlan;
 use crate::executor::EvalScalar;
@@ -59,9 +72,11 @@ use crate::optimizer::SExpr;
 use crate::plans::AggregateMode;
 use crate::plans::AndExpr;
 use crate::plans::Exchange;
+use crate::plans::JoinAlgorithm;
 use crate::plans::RelOperator;
 use crate::plans::ScalarExpr;
 use crate::plans::Scan;
+use crate::plans::WindowFuncType;
 use crate::BaseTableColumn;
 use crate::ColumnEntry;
 use crate::DerivedColumn;
@@ -222,6 +237,95 @@ impl PhysicalPlanBuilder {
                     }),
                 }))
             }
+            RelOperator::Join(join) if join.algorithm == JoinAlgorithm::SortMerge => {
+                // Both sides are sorted by the join keys, which are column references
+                // guaranteed by `RuleSortMergeJoin`.
+                let sort_side = |input: PhysicalPlan,
+                                 plan_id: u32,
+                                 keys: &[ScalarExpr],
+                                 stat_info: PlanStatsInfo| {
+                    let order_by = keys
+                        .iter()
+                        .map(|key| match key {
+                            ScalarExpr::BoundColumnRef(column) => Ok(SortDesc {
+                                asc: true,
+                                nulls_first: false,
+                                order_by: column.column.index,
+                            }),
+                            _ => Err(ErrorCode::Internal(
+                                "Sort merge join key must be a BoundColumnRef".to_string(),
+                            )),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    // Skip the sort if the input is already ordered, e.g. by a sorted
+                    // subquery or by the previous sort merge join.
+                    if is_sorted_by(&input, &order_by) {
+                        return Ok(input);
+                    }
+                    Ok::<_, ErrorCode>(PhysicalPlan::Sort(Sort {
+                        plan_id,
+                        input: Box::new(input),
+                        order_by,
+                        limit: None,
+                        stat_info: Some(stat_info),
+                    }))
+                };
+                let left_input = self.build(s_expr.child(0)?).await?;
+                let left_side = sort_side(
+                    left_input,
+                    self.next_plan_id(),
+                    &join.left_conditions,
+                    self.build_plan_stat_info(s_expr.child(0)?)?,
+                )?;
+                let right_input = self.build(s_expr.child(1)?).await?;
+                let right_side = sort_side(
+                    right_input,
+                    self.next_plan_id(),
+                    &join.right_conditions,
+                    self.build_plan_stat_info(s_expr.child(1)?)?,
+                )?;
+                let left_schema = left_side.output_schema()?;
+                let right_schema = right_side.output_schema()?;
+                let merged_schema = DataSchemaRefExt::create(
+                    left_schema
+                        .fields()
+                        .iter()
+                        .chain(right_schema.fields())
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                );
+                let func_ctx = self.ctx.get_function_context()?;
+                let to_remote_expr = |scalar: &ScalarExpr, schema: &DataSchemaRef| {
+                    let expr = scalar
+                        .as_expr_with_col_index()?
+                        .project_column_ref(|index| schema.index_of(&index.to_string()).unwrap());
+                    let (expr, _) = ConstantFolder::fold(&expr, func_ctx, &BUILTIN_FUNCTIONS);
+                    Ok::<_, ErrorCode>(expr.as_remote_expr())
+                };
+                Ok(PhysicalPlan::SortMergeJoin(SortMergeJoin {
+                    plan_id: self.next_plan_id(),
+                    left: Box::new(left_side),
+                    right: Box::new(right_side),
+                    left_keys: join
+                        .left_conditions
+                        .iter()
+                        .map(|scalar| to_remote_expr(scalar, &left_schema))
+                        .collect::<Result<_>>()?,
+                    right_keys: join
+                        .right_conditions
+                        .iter()
+                        .map(|scalar| to_remote_expr(scalar, &right_schema))
+                        .collect::<Result<_>>()?,
+                    non_equi_conditions: join
+                        .non_equi_conditions
+                        .iter()
+                        .map(|scalar| to_remote_expr(scalar, &merged_schema))
+                        .collect::<Result<_>>()?,
+                    join_type: join.join_type.clone(),
+
+                    stat_info: Some(stat_info),
+                }))
+            }
             RelOperator::Join(join) => {
                 let build_side = self.build(s_expr.child(1)?).await?;
                 let probe_side = self.build(s_expr.child(0)?).await?;
@@ -363,7 +467,12 @@ impl PhysicalPlanBuilder {
             RelOperator::Aggregate(agg) => {
                 let input = self.build(s_expr.child(0)?).await?;
                 let input_schema = input.output_schema()?;
-                let group_items = agg.group_items.iter().map(|v| v.index).collect::<Vec<_>>();
+                let mut group_items = agg.group_items.iter().map(|v| v.index).collect::<Vec<_>>();
+                if let Some(grouping_sets) = &agg.grouping_sets {
+                    // The grouping id is the last group by key, rows of
+                    // different grouping sets will never be merged together.
+                    group_items.push(grouping_sets.grouping_id_index);
+                }
 
                 let result = match &agg.mode {
                     AggregateMode::Partial => {
@@ -404,6 +513,38 @@ impl PhysicalPlanBuilder {
                             }
                         }).collect::<Result<_>>()?;
 
+                        let input = match &agg.grouping_sets {
+                            Some(grouping_sets) => {
+                                let expand = |input: Box<PhysicalPlan>, plan_id: u32| {
+                                    Box::new(PhysicalPlan::AggregateExpand(AggregateExpand {
+                                        plan_id,
+                                        input,
+                                        group_bys: agg
+                                            .group_items
+                                            .iter()
+                                            .map(|v| v.index)
+                                            .collect(),
+                                        grouping_id_index: grouping_sets.grouping_id_index,
+                                        grouping_sets: grouping_sets.sets.clone(),
+                                        stat_info: Some(stat_info.clone()),
+                                    }))
+                                };
+                                match input {
+                                    PhysicalPlan::Exchange(PhysicalExchange {
+                                        input,
+                                        kind,
+                                        keys,
+                                    }) => PhysicalPlan::Exchange(PhysicalExchange {
+                                        input: expand(input, self.next_plan_id()),
+                                        kind,
+                                        keys,
+                                    }),
+                                    _ => *expand(Box::new(input), self.next_plan_id()),
+                                }
+                            }
+                            None => input,
+                        };
+
                         match input {
                             PhysicalPlan::Exchange(PhysicalExchange { input, kind, .. }) => {
                                 let aggregate_partial = AggregatePartial {
@@ -416,14 +557,11 @@ impl PhysicalPlanBuilder {
 
                                 let group_by_key_index =
                                     aggregate_partial.output_schema()?.num_fields() - 1;
-                                let group_by_key_data_type =
-                                    DataBlock::choose_hash_method_with_types(
-                                        &agg.group_items
-                                            .iter()
-                                            .map(|v| v.scalar.data_type())
-                                            .collect::<Vec<_>>(),
-                                    )?
-                                    .data_type();
+                                let group_by_key_data_type = aggregate_partial
+                                    .output_schema()?
+                                    .field(group_by_key_index)
+                                    .data_type()
+                                    .clone();
 
                                 PhysicalPlan::Exchange(PhysicalExchange {
                                     kind,
@@ -503,6 +641,11 @@ impl PhysicalPlanBuilder {
                             }
                         }).collect::<Result<_>>()?;
 
+                        // Bit `i` of the grouping id is set if the `i`-th group item is rolled up.
+                        let empty_grouping_id = agg.has_empty_grouping_set().then(|| {
+                            (0..agg.group_items.len()).fold(0u32, |acc, pos| acc | (1 << pos))
+                        });
+
                         match input {
                             PhysicalPlan::AggregatePartial(ref partial) => {
                                 let before_group_by_schema = partial.input.output_schema()?;
@@ -513,6 +656,7 @@ impl PhysicalPlanBuilder {
                                     group_by: group_items,
                                     agg_funcs,
                                     before_group_by_schema,
+                                    empty_grouping_id,
 
                                     stat_info: Some(stat_info),
                                     limit,
@@ -532,6 +676,7 @@ impl PhysicalPlanBuilder {
                                     group_by: group_items,
                                     agg_funcs,
                                     before_group_by_schema,
+                                    empty_grouping_id,
 
                                     stat_info: Some(stat_info),
                                     limit,
@@ -569,6 +714,101 @@ impl PhysicalPlanBuilder {
 
                 stat_info: Some(stat_info),
             })),
+            RelOperator::Window(window) => {
+                let input = self.build(s_expr.child(0)?).await?;
+                let input_schema = input.output_schema()?;
+                let column_index = |scalar: &ScalarExpr| {
+                    if let ScalarExpr::BoundColumnRef(col) = scalar {
+                        Ok(col.column.index)
+                    } else {
+                        Err(ErrorCode::Internal(
+                            "Window function argument must be a BoundColumnRef".to_string(),
+                        ))
+                    }
+                };
+
+                let func = match &window.function {
+                    WindowFuncType::Aggregate(agg) => {
+                        WindowFunction::Aggregate(AggregateFunctionDesc {
+                            sig: AggregateFunctionSignature {
+                                name: agg.func_name.clone(),
+                                args: agg.args.iter().map(|s| s.data_type()).collect(),
+                                params: agg.params.clone(),
+                                return_type: *agg.return_type.clone(),
+                            },
+                            output_column: window.index,
+                            args: agg
+                                .args
+                                .iter()
+                                .map(|arg| {
+                                    let index = column_index(arg)?;
+                                    input_schema.index_of(&index.to_string())
+                                })
+                                .collect::<Result<_>>()?,
+                            arg_indices: agg
+                                .args
+                                .iter()
+                                .map(column_index)
+                                .collect::<Result<_>>()?,
+                        })
+                    }
+                    WindowFuncType::RowNumber => WindowFunction::RowNumber,
+                    WindowFuncType::Rank => WindowFunction::Rank,
+                    WindowFuncType::DenseRank => WindowFunction::DenseRank,
+                    WindowFuncType::Lag(func) | WindowFuncType::Lead(func) => {
+                        let desc = LagLeadFunctionDesc {
+                            arg: column_index(&func.arg)?,
+                            offset: func.offset,
+                            default: func
+                                .default
+                                .as_ref()
+                                .map(|default| column_index(default.as_ref()))
+                                .transpose()?,
+                            return_type: *func.return_type.clone(),
+                        };
+                        if matches!(window.function, WindowFuncType::Lag(_)) {
+                            WindowFunction::Lag(desc)
+                        } else {
+                            WindowFunction::Lead(desc)
+                        }
+                    }
+                    WindowFuncType::FirstValue(func) | WindowFuncType::LastValue(func) => {
+                        let desc = FirstLastValueFunctionDesc {
+                            arg: column_index(&func.arg)?,
+                            return_type: *func.return_type.clone(),
+                        };
+                        if matches!(window.function, WindowFuncType::FirstValue(_)) {
+                            WindowFunction::FirstValue(desc)
+                        } else {
+                            WindowFunction::LastValue(desc)
+                        }
+                    }
+                };
+
+                // null is the largest value in databend, smallest in hive
+                let default_nulls_first =
+                    !self.ctx.get_settings().get_sql_dialect()?.is_null_biggest();
+
+                Ok(PhysicalPlan::Window(Window {
+                    plan_id: self.next_plan_id(),
+                    index: window.index,
+                    input: Box::new(input),
+                    func,
+                    partition_by: window.partition_by.iter().map(|v| v.index).collect(),
+                    order_by: window
+                        .order_by
+                        .iter()
+                        .map(|v| SortDesc {
+                            asc: v.asc.unwrap_or(true),
+                            nulls_first: v.nulls_first.unwrap_or(default_nulls_first),
+                            order_by: v.order_by_item.index,
+                        })
+                        .collect(),
+                    window_frame: window.frame.clone(),
+
+                    stat_info: Some(stat_info),
+                }))
+            }
             RelOperator::Limit(limit) => Ok(PhysicalPlan::Limit(Limit {
                 plan_id: self.next_plan_id(),
                 input: Box::new(self.build(s_expr.child(0)?).await?),
@@ -631,6 +871,141 @@ impl PhysicalPlanBuilder {
                     stat_info: Some(stat_info),
                 }))
             }
+            RelOperator::RecursiveUnion(op) => {
+                let left = self.build(s_expr.child(0)?).await?;
+                let left_schema = left.output_schema()?;
+                let pairs = op
+                    .pairs
+                    .iter()
+                    .map(|(l, r)| (l.to_string(), r.to_string()))
+                    .collect::<Vec<_>>();
+                let fields = pairs
+                    .iter()
+                    .map(|(left, _)| Ok(left_schema.field_with_name(left)?.clone()))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(PhysicalPlan::RecursiveUnion(RecursiveUnion {
+                    plan_id: self.next_plan_id(),
+                    cte_name: op.cte_name.clone(),
+                    left: Box::new(left),
+                    right: Box::new(self.build(s_expr.child(1)?).await?),
+                    pairs,
+                    distinct: op.distinct,
+                    schema: DataSchemaRefExt::create(fields),
+
+                    stat_info: Some(stat_info),
+                }))
+            }
+            RelOperator::RecursiveCteScan(scan) => {
+                let fields = scan
+                    .fields
+                    .iter()
+                    .map(|(index, data_type)| DataField::new(&index.to_string(), data_type.clone()))
+                    .collect();
+                Ok(PhysicalPlan::RecursiveCteScan(RecursiveCteScan {
+                    plan_id: self.next_plan_id(),
+                    cte_name: scan.cte_name.clone(),
+                    schema: DataSchemaRefExt::create(fields),
+
+                    stat_info: Some(stat_info),
+                }))
+            }
+            RelOperator::MaterializedCte(op) => {
+                let cte_name = self
+                    .metadata
+                    .read()
+                    .materialized_cte_name(op.cte_idx)
+                    .to_string();
+                Ok(PhysicalPlan::MaterializedCte(MaterializedCte {
+                    plan_id: self.next_plan_id(),
+                    cte_idx: op.cte_idx,
+                    cte_name,
+                    left: Box::new(self.build(s_expr.child(0)?).await?),
+                    right: Box::new(self.build(s_expr.child(1)?).await?),
+                    columns: op.columns.clone(),
+
+                    stat_info: Some(stat_info),
+                }))
+            }
+            RelOperator::CteScan(scan) => {
+                let cte_name = self
+                    .metadata
+                    .read()
+                    .materialized_cte_name(scan.cte_idx)
+                    .to_string();
+                let fields = scan
+                    .fields
+                    .iter()
+                    .map(|(index, data_type)| DataField::new(&index.to_string(), data_type.clone()))
+                    .collect();
+                Ok(PhysicalPlan::CteScan(CteScan {
+                    plan_id: self.next_plan_id(),
+                    cte_idx: scan.cte_idx,
+                    cte_name,
+                    schema: DataSchemaRefExt::create(fields),
+
+                    stat_info: Some(stat_info),
+                }))
+            }
+            RelOperator::SetOperation(op) => {
+                let pairs = op
+                    .pairs
+                    .iter()
+                    .map(|(l, r)| (l.to_string(), r.to_string()))
+                    .collect::<Vec<_>>();
+                Ok(PhysicalPlan::SetOperation(SetOperation {
+                    plan_id: self.next_plan_id(),
+                    op_type: op.op_type.clone(),
+                    left: Box::new(self.build(s_expr.child(0)?).await?),
+                    right: Box::new(self.build(s_expr.child(1)?).await?),
+                    pairs,
+
+                    stat_info: Some(stat_info),
+                }))
+            }
+            RelOperator::ProjectSet(project_set) => {
+                let input = Box::new(self.build(s_expr.child(0)?).await?);
+                let input_schema = input.output_schema()?;
+                let args = project_set
+                    .args
+                    .iter()
+                    .map(|arg| {
+                        let expr = arg.as_expr_with_col_index()?.project_column_ref(|index| {
+                            input_schema.index_of(&index.to_string()).unwrap()
+                        });
+                        let (expr, _) = ConstantFolder::fold(
+                            &expr,
+                            self.ctx.get_function_context()?,
+                            &BUILTIN_FUNCTIONS,
+                        );
+                        Ok(expr.as_remote_expr())
+                    })
+                    .collect::<Result<_>>()?;
+                let columns = {
+                    let metadata = self.metadata.read();
+                    project_set
+                        .columns
+                        .iter()
+                        .map(|index| match metadata.column(*index) {
+                            ColumnEntry::DerivedColumn(DerivedColumn { data_type, .. }) => {
+                                Ok((*index, data_type.clone()))
+                            }
+                            ColumnEntry::BaseTableColumn(_) => Err(ErrorCode::Internal(
+                                "Output column of ProjectSet must be a derived column",
+                            )),
+                        })
+                        .collect::<Result<_>>()?
+                };
+                Ok(PhysicalPlan::ProjectSet(ProjectSet {
+                    plan_id: self.next_plan_id(),
+                    input,
+                    srf: project_set.srf.clone(),
+                    args,
+                    columns,
+                    outer: project_set.outer,
+
+                    stat_info: Some(stat_info),
+                }))
+            }
             _ => Err(ErrorCode::Internal(format!(
                 "Unsupported physical plan: {:?}",
                 s_expr.plan()
@@ -788,3 +1163,23 @@ impl PhysicalPlanBuilder {
         })
     }
 }
+
+/// Returns true if the output of `plan` is already sorted by `order_by`.
+fn is_sorted_by(plan: &PhysicalPlan, order_by: &[SortDesc]) -> bool {
+    match plan {
+        PhysicalPlan::Sort(sort) => {
+            order_by.len() <= sort.order_by.len()
+                && order_by.iter().zip(sort.order_by.iter()).all(|(l, r)| {
+                    l.order_by == r.order_by && l.asc == r.asc && l.nulls_first == r.nulls_first
+                })
+        }
+        // The output of a sort merge join is ordered by its left keys.
+        PhysicalPlan::SortMergeJoin(join) => is_sorted_by(&join.left, order_by),
+        // The order of the rows is kept by these operators.
+        PhysicalPlan::Filter(filter) => is_sorted_by(&filter.input, order_by),
+        PhysicalPlan::EvalScalar(eval_scalar) => is_sorted_by(&eval_scalar.input, order_by),
+        PhysicalPlan::Project(project) => is_sorted_by(&project.input, order_by),
+        PhysicalPlan::Limit(limit) => is_sorted_by(&limit.input, order_by),
+        _ => false,
+    }
+}
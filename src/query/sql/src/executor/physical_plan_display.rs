@@ -19,8 +19,10 @@ use common_functions::scalars::BUILTIN_FUNCTIONS;
 use itertools::Itertools;
 
 use super::DistributedInsertSelect;
+use crate::executor::AggregateExpand;
 use crate::executor::AggregateFinal;
 use crate::executor::AggregatePartial;
+use crate::executor::CteScan;
 use crate::executor::EvalScalar;
 use crate::executor::Exchange;
 use crate::executor::ExchangeSink;
@@ -28,11 +30,18 @@ use crate::executor::ExchangeSource;
 use crate::executor::Filter;
 use crate::executor::HashJoin;
 use crate::executor::Limit;
+use crate::executor::MaterializedCte;
 use crate::executor::PhysicalPlan;
 use crate::executor::Project;
+use crate::executor::ProjectSet;
+use crate::executor::RecursiveCteScan;
+use crate::executor::RecursiveUnion;
+use crate::executor::SetOperation;
 use crate::executor::Sort;
+use crate::executor::SortMergeJoin;
 use crate::executor::TableScan;
 use crate::executor::UnionAll;
+use crate::executor::Window;
 use crate::plans::JoinType;
 
 impl PhysicalPlan {
@@ -55,15 +64,24 @@ impl<'a> Display for PhysicalPlanIndentFormatDisplay<'a> {
             PhysicalPlan::Filter(filter) => write!(f, "{}", filter)?,
             PhysicalPlan::Project(project) => write!(f, "{}", project)?,
             PhysicalPlan::EvalScalar(eval_scalar) => write!(f, "{}", eval_scalar)?,
+            PhysicalPlan::AggregateExpand(expand) => write!(f, "{}", expand)?,
             PhysicalPlan::AggregatePartial(aggregate) => write!(f, "{}", aggregate)?,
             PhysicalPlan::AggregateFinal(aggregate) => write!(f, "{}", aggregate)?,
             PhysicalPlan::Sort(sort) => write!(f, "{}", sort)?,
+            PhysicalPlan::Window(window) => write!(f, "{}", window)?,
             PhysicalPlan::Limit(limit) => write!(f, "{}", limit)?,
             PhysicalPlan::HashJoin(join) => write!(f, "{}", join)?,
+            PhysicalPlan::SortMergeJoin(join) => write!(f, "{}", join)?,
             PhysicalPlan::Exchange(exchange) => write!(f, "{}", exchange)?,
             PhysicalPlan::ExchangeSource(source) => write!(f, "{}", source)?,
             PhysicalPlan::ExchangeSink(sink) => write!(f, "{}", sink)?,
             PhysicalPlan::UnionAll(union_all) => write!(f, "{}", union_all)?,
+            PhysicalPlan::RecursiveUnion(recursive_union) => write!(f, "{}", recursive_union)?,
+            PhysicalPlan::RecursiveCteScan(scan) => write!(f, "{}", scan)?,
+            PhysicalPlan::MaterializedCte(materialized_cte) => write!(f, "{}", materialized_cte)?,
+            PhysicalPlan::CteScan(scan) => write!(f, "{}", scan)?,
+            PhysicalPlan::SetOperation(set_operation) => write!(f, "{}", set_operation)?,
+            PhysicalPlan::ProjectSet(project_set) => write!(f, "{}", project_set)?,
             PhysicalPlan::DistributedInsertSelect(insert_select) => write!(f, "{}", insert_select)?,
         }
 
@@ -130,6 +148,35 @@ impl Display for Sort {
     }
 }
 
+impl Display for Window {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let partition_by = self
+            .partition_by
+            .iter()
+            .map(|index| index.to_string())
+            .collect::<Vec<String>>();
+        let order_by = self
+            .order_by
+            .iter()
+            .map(|item| {
+                format!(
+                    "{} {}",
+                    item.order_by,
+                    if item.asc { "ASC" } else { "DESC" }
+                )
+            })
+            .collect::<Vec<String>>();
+        write!(
+            f,
+            "Window: [{}], Partition By: [{}], Order By: [{}], Frame: [{}]",
+            self.func,
+            partition_by.join(", "),
+            order_by.join(", "),
+            self.window_frame
+        )
+    }
+}
+
 impl Display for EvalScalar {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let scalars = self
@@ -142,6 +189,24 @@ impl Display for EvalScalar {
     }
 }
 
+impl Display for AggregateExpand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sets = self
+            .grouping_sets
+            .iter()
+            .map(|set| {
+                set.iter()
+                    .map(|index| index.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .map(|s| format!("({})", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "Aggregate(Expand): grouping sets: [{}]", sets)
+    }
+}
+
 impl Display for AggregateFinal {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let group_items = self
@@ -253,6 +318,32 @@ impl Display for HashJoin {
     }
 }
 
+impl Display for SortMergeJoin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let left_keys = self
+            .left_keys
+            .iter()
+            .map(|scalar| scalar.as_expr(&BUILTIN_FUNCTIONS).sql_display())
+            .join(", ");
+        let right_keys = self
+            .right_keys
+            .iter()
+            .map(|scalar| scalar.as_expr(&BUILTIN_FUNCTIONS).sql_display())
+            .join(", ");
+        let join_filters = self
+            .non_equi_conditions
+            .iter()
+            .map(|scalar| scalar.as_expr(&BUILTIN_FUNCTIONS).sql_display())
+            .join(", ");
+
+        write!(
+            f,
+            "SortMergeJoin: {}, left keys: [{}], right keys: [{}], join filters: [{}]",
+            &self.join_type, left_keys, right_keys, join_filters,
+        )
+    }
+}
+
 impl Display for Exchange {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let keys = self
@@ -291,6 +382,48 @@ impl Display for UnionAll {
     }
 }
 
+impl Display for RecursiveUnion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RecursiveUnion: [{}]", self.cte_name)
+    }
+}
+
+impl Display for RecursiveCteScan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RecursiveCteScan: [{}]", self.cte_name)
+    }
+}
+
+impl Display for MaterializedCte {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MaterializedCte: [{}]", self.cte_name)
+    }
+}
+
+impl Display for CteScan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CteScan: [{}]", self.cte_name)
+    }
+}
+
+impl Display for SetOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SetOperation: [{}]", self.op_type)
+    }
+}
+
+impl Display for ProjectSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let args = self
+            .args
+            .iter()
+            .map(|expr| expr.as_expr(&BUILTIN_FUNCTIONS).to_string())
+            .collect::<Vec<String>>();
+
+        write!(f, "ProjectSet: {}({})", self.srf, args.join(", "))
+    }
+}
+
 impl Display for DistributedInsertSelect {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "DistributedInsertSelect")
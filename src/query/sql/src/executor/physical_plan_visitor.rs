@@ -14,8 +14,10 @@
 
 use common_exception::Result;
 
+use super::AggregateExpand;
 use super::AggregateFinal;
 use super::AggregatePartial;
+use super::CteScan;
 use super::DistributedInsertSelect;
 use super::EvalScalar;
 use super::Exchange;
@@ -24,10 +26,17 @@ use super::ExchangeSource;
 use super::Filter;
 use super::HashJoin;
 use super::Limit;
+use super::MaterializedCte;
 use super::PhysicalPlan;
 use super::Project;
+use super::ProjectSet;
+use super::RecursiveCteScan;
+use super::RecursiveUnion;
+use super::SetOperation;
 use super::Sort;
+use super::SortMergeJoin;
 use super::TableScan;
+use super::Window;
 use crate::executor::UnionAll;
 
 pub trait PhysicalPlanReplacer {
@@ -37,15 +46,24 @@ pub trait PhysicalPlanReplacer {
             PhysicalPlan::Filter(plan) => self.replace_filter(plan),
             PhysicalPlan::Project(plan) => self.replace_project(plan),
             PhysicalPlan::EvalScalar(plan) => self.replace_eval_scalar(plan),
+            PhysicalPlan::AggregateExpand(plan) => self.replace_aggregate_expand(plan),
             PhysicalPlan::AggregatePartial(plan) => self.replace_aggregate_partial(plan),
             PhysicalPlan::AggregateFinal(plan) => self.replace_aggregate_final(plan),
             PhysicalPlan::Sort(plan) => self.replace_sort(plan),
+            PhysicalPlan::Window(plan) => self.replace_window(plan),
             PhysicalPlan::Limit(plan) => self.replace_limit(plan),
             PhysicalPlan::HashJoin(plan) => self.replace_hash_join(plan),
+            PhysicalPlan::SortMergeJoin(plan) => self.replace_sort_merge_join(plan),
             PhysicalPlan::Exchange(plan) => self.replace_exchange(plan),
             PhysicalPlan::ExchangeSource(plan) => self.replace_exchange_source(plan),
             PhysicalPlan::ExchangeSink(plan) => self.replace_exchange_sink(plan),
             PhysicalPlan::UnionAll(plan) => self.replace_union(plan),
+            PhysicalPlan::RecursiveUnion(plan) => self.replace_recursive_union(plan),
+            PhysicalPlan::RecursiveCteScan(plan) => self.replace_recursive_cte_scan(plan),
+            PhysicalPlan::MaterializedCte(plan) => self.replace_materialized_cte(plan),
+            PhysicalPlan::CteScan(plan) => self.replace_cte_scan(plan),
+            PhysicalPlan::SetOperation(plan) => self.replace_set_operation(plan),
+            PhysicalPlan::ProjectSet(plan) => self.replace_project_set(plan),
             PhysicalPlan::DistributedInsertSelect(plan) => self.replace_insert_select(plan),
         }
     }
@@ -88,6 +106,19 @@ pub trait PhysicalPlanReplacer {
         }))
     }
 
+    fn replace_aggregate_expand(&mut self, plan: &AggregateExpand) -> Result<PhysicalPlan> {
+        let input = self.replace(&plan.input)?;
+
+        Ok(PhysicalPlan::AggregateExpand(AggregateExpand {
+            plan_id: plan.plan_id,
+            input: Box::new(input),
+            group_bys: plan.group_bys.clone(),
+            grouping_id_index: plan.grouping_id_index,
+            grouping_sets: plan.grouping_sets.clone(),
+            stat_info: plan.stat_info.clone(),
+        }))
+    }
+
     fn replace_aggregate_partial(&mut self, plan: &AggregatePartial) -> Result<PhysicalPlan> {
         let input = self.replace(&plan.input)?;
 
@@ -107,6 +138,7 @@ pub trait PhysicalPlanReplacer {
             plan_id: plan.plan_id,
             input: Box::new(input),
             before_group_by_schema: plan.before_group_by_schema.clone(),
+            empty_grouping_id: plan.empty_grouping_id,
             group_by: plan.group_by.clone(),
             agg_funcs: plan.agg_funcs.clone(),
             stat_info: plan.stat_info.clone(),
@@ -132,6 +164,22 @@ pub trait PhysicalPlanReplacer {
         }))
     }
 
+    fn replace_sort_merge_join(&mut self, plan: &SortMergeJoin) -> Result<PhysicalPlan> {
+        let left = self.replace(&plan.left)?;
+        let right = self.replace(&plan.right)?;
+
+        Ok(PhysicalPlan::SortMergeJoin(SortMergeJoin {
+            plan_id: plan.plan_id,
+            left: Box::new(left),
+            right: Box::new(right),
+            left_keys: plan.left_keys.clone(),
+            right_keys: plan.right_keys.clone(),
+            non_equi_conditions: plan.non_equi_conditions.clone(),
+            join_type: plan.join_type.clone(),
+            stat_info: plan.stat_info.clone(),
+        }))
+    }
+
     fn replace_sort(&mut self, plan: &Sort) -> Result<PhysicalPlan> {
         let input = self.replace(&plan.input)?;
 
@@ -144,6 +192,21 @@ pub trait PhysicalPlanReplacer {
         }))
     }
 
+    fn replace_window(&mut self, plan: &Window) -> Result<PhysicalPlan> {
+        let input = self.replace(&plan.input)?;
+
+        Ok(PhysicalPlan::Window(Window {
+            plan_id: plan.plan_id,
+            index: plan.index,
+            input: Box::new(input),
+            func: plan.func.clone(),
+            partition_by: plan.partition_by.clone(),
+            order_by: plan.order_by.clone(),
+            window_frame: plan.window_frame.clone(),
+            stat_info: plan.stat_info.clone(),
+        }))
+    }
+
     fn replace_limit(&mut self, plan: &Limit) -> Result<PhysicalPlan> {
         let input = self.replace(&plan.input)?;
 
@@ -197,6 +260,70 @@ pub trait PhysicalPlanReplacer {
         }))
     }
 
+    fn replace_recursive_union(&mut self, plan: &RecursiveUnion) -> Result<PhysicalPlan> {
+        let left = self.replace(&plan.left)?;
+        let right = self.replace(&plan.right)?;
+        Ok(PhysicalPlan::RecursiveUnion(RecursiveUnion {
+            plan_id: plan.plan_id,
+            cte_name: plan.cte_name.clone(),
+            left: Box::new(left),
+            right: Box::new(right),
+            pairs: plan.pairs.clone(),
+            distinct: plan.distinct,
+            schema: plan.schema.clone(),
+            stat_info: plan.stat_info.clone(),
+        }))
+    }
+
+    fn replace_recursive_cte_scan(&mut self, plan: &RecursiveCteScan) -> Result<PhysicalPlan> {
+        Ok(PhysicalPlan::RecursiveCteScan(plan.clone()))
+    }
+
+    fn replace_materialized_cte(&mut self, plan: &MaterializedCte) -> Result<PhysicalPlan> {
+        let left = self.replace(&plan.left)?;
+        let right = self.replace(&plan.right)?;
+        Ok(PhysicalPlan::MaterializedCte(MaterializedCte {
+            plan_id: plan.plan_id,
+            cte_idx: plan.cte_idx,
+            cte_name: plan.cte_name.clone(),
+            left: Box::new(left),
+            right: Box::new(right),
+            columns: plan.columns.clone(),
+            stat_info: plan.stat_info.clone(),
+        }))
+    }
+
+    fn replace_cte_scan(&mut self, plan: &CteScan) -> Result<PhysicalPlan> {
+        Ok(PhysicalPlan::CteScan(plan.clone()))
+    }
+
+    fn replace_set_operation(&mut self, plan: &SetOperation) -> Result<PhysicalPlan> {
+        let left = self.replace(&plan.left)?;
+        let right = self.replace(&plan.right)?;
+        Ok(PhysicalPlan::SetOperation(SetOperation {
+            plan_id: plan.plan_id,
+            op_type: plan.op_type.clone(),
+            left: Box::new(left),
+            right: Box::new(right),
+            pairs: plan.pairs.clone(),
+            stat_info: plan.stat_info.clone(),
+        }))
+    }
+
+    fn replace_project_set(&mut self, plan: &ProjectSet) -> Result<PhysicalPlan> {
+        let input = self.replace(&plan.input)?;
+
+        Ok(PhysicalPlan::ProjectSet(ProjectSet {
+            plan_id: plan.plan_id,
+            input: Box::new(input),
+            srf: plan.srf.clone(),
+            args: plan.args.clone(),
+            columns: plan.columns.clone(),
+            outer: plan.outer,
+            stat_info: plan.stat_info.clone(),
+        }))
+    }
+
     fn replace_insert_select(&mut self, plan: &DistributedInsertSelect) -> Result<PhysicalPlan> {
         let input = self.replace(&plan.input)?;
 
@@ -234,6 +361,9 @@ impl PhysicalPlan {
                 PhysicalPlan::EvalScalar(plan) => {
                     Self::traverse(&plan.input, pre_visit, visit, post_visit);
                 }
+                PhysicalPlan::AggregateExpand(plan) => {
+                    Self::traverse(&plan.input, pre_visit, visit, post_visit);
+                }
                 PhysicalPlan::AggregatePartial(plan) => {
                     Self::traverse(&plan.input, pre_visit, visit, post_visit);
                 }
@@ -243,6 +373,9 @@ impl PhysicalPlan {
                 PhysicalPlan::Sort(plan) => {
                     Self::traverse(&plan.input, pre_visit, visit, post_visit);
                 }
+                PhysicalPlan::Window(plan) => {
+                    Self::traverse(&plan.input, pre_visit, visit, post_visit);
+                }
                 PhysicalPlan::Limit(plan) => {
                     Self::traverse(&plan.input, pre_visit, visit, post_visit);
                 }
@@ -250,6 +383,10 @@ impl PhysicalPlan {
                     Self::traverse(&plan.build, pre_visit, visit, post_visit);
                     Self::traverse(&plan.probe, pre_visit, visit, post_visit);
                 }
+                PhysicalPlan::SortMergeJoin(plan) => {
+                    Self::traverse(&plan.left, pre_visit, visit, post_visit);
+                    Self::traverse(&plan.right, pre_visit, visit, post_visit);
+                }
                 PhysicalPlan::Exchange(plan) => {
                     Self::traverse(&plan.input, pre_visit, visit, post_visit);
                 }
@@ -261,6 +398,23 @@ impl PhysicalPlan {
                     Self::traverse(&plan.left, pre_visit, visit, post_visit);
                     Self::traverse(&plan.right, pre_visit, visit, post_visit);
                 }
+                PhysicalPlan::RecursiveUnion(plan) => {
+                    Self::traverse(&plan.left, pre_visit, visit, post_visit);
+                    Self::traverse(&plan.right, pre_visit, visit, post_visit);
+                }
+                PhysicalPlan::RecursiveCteScan(_) => {}
+                PhysicalPlan::MaterializedCte(plan) => {
+                    Self::traverse(&plan.left, pre_visit, visit, post_visit);
+                    Self::traverse(&plan.right, pre_visit, visit, post_visit);
+                }
+                PhysicalPlan::CteScan(_) => {}
+                PhysicalPlan::SetOperation(plan) => {
+                    Self::traverse(&plan.left, pre_visit, visit, post_visit);
+                    Self::traverse(&plan.right, pre_visit, visit, post_visit);
+                }
+                PhysicalPlan::ProjectSet(plan) => {
+                    Self::traverse(&plan.input, pre_visit, visit, post_visit);
+                }
                 PhysicalPlan::DistributedInsertSelect(plan) => {
                     Self::traverse(&plan.input, pre_visit, visit, post_visit);
                 }
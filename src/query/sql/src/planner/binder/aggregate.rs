@@ -17,11 +17,13 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 
 use common_ast::ast::Expr;
+use common_ast::ast::GroupBy;
 use common_ast::ast::Literal;
 use common_ast::ast::SelectTarget;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
 
 use super::prune_by_children;
 use crate::binder::scalar::ScalarBinder;
@@ -39,13 +41,21 @@ use crate::plans::CastExpr;
 use crate::plans::ComparisonExpr;
 use crate::plans::EvalScalar;
 use crate::plans::FunctionCall;
+use crate::plans::GroupingSets;
 use crate::plans::NotExpr;
 use crate::plans::OrExpr;
 use crate::plans::ScalarExpr;
 use crate::plans::ScalarItem;
+use crate::plans::WindowFunc;
+use crate::plans::WindowOrderBy;
 use crate::BindContext;
+use crate::IndexType;
 use crate::MetadataRef;
 
+/// The maximum number of expressions in `CUBE`, which will be expanded
+/// into `2^n` grouping sets.
+const MAX_CUBE_ITEMS: usize = 12;
+
 #[derive(Default, Clone, PartialEq, Eq, Debug)]
 pub struct AggregateInfo {
     /// Aggregation functions
@@ -73,6 +83,28 @@ pub struct AggregateInfo {
     /// TODO(leiysky): so far we are using `Debug` string of `Scalar` as identifier,
     /// maybe a more reasonable way is needed
     pub group_items_map: HashMap<String, usize>,
+
+    /// Information of grouping sets, `None` if there are no grouping sets,
+    /// i.e. `GROUPING SETS`, `ROLLUP` or `CUBE` in `GROUP BY` clause.
+    pub grouping_sets: Option<GroupingSetsInfo>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GroupingSetsInfo {
+    /// The virtual column `_grouping_id`, which is generated when expanding input rows
+    /// with grouping sets. The i-th bit of `_grouping_id` is set if the i-th group item
+    /// is not in the grouping set of the row.
+    pub grouping_id_column: ColumnBinding,
+
+    /// Each grouping set is a list of column indices of group items.
+    pub sets: Vec<Vec<IndexType>>,
+}
+
+impl GroupingSetsInfo {
+    /// Check if the group item column will be filled with NULL in some grouping sets.
+    pub fn is_nullable_group_item(&self, index: IndexType) -> bool {
+        self.sets.iter().any(|set| !set.contains(&index))
+    }
 }
 
 pub(super) struct AggregateRewriter<'a> {
@@ -117,6 +149,14 @@ impl<'a> AggregateRewriter<'a> {
             }
             .into()),
             ScalarExpr::FunctionCall(func) => {
+                if func.func_name == "grouping"
+                    && self.bind_context.aggregate_info.grouping_sets.is_none()
+                {
+                    return Err(ErrorCode::SemanticError(
+                        "grouping function can only be used with GROUPING SETS, ROLLUP or CUBE"
+                            .to_string(),
+                    ));
+                }
                 let new_args = func
                     .arguments
                     .iter()
@@ -142,18 +182,58 @@ impl<'a> AggregateRewriter<'a> {
             ScalarExpr::SubqueryExpr(_) => Ok(scalar.clone()),
 
             ScalarExpr::AggregateFunction(agg_func) => self.replace_aggregate_function(agg_func),
+
+            // The window function itself is not an aggregation, even if it's
+            // an aggregate function with `OVER` clause, so we only rewrite its
+            // arguments, partition keys and order keys.
+            ScalarExpr::WindowFunction(window) => Ok(WindowFunc {
+                display_name: window.display_name.clone(),
+                func: window.func.try_map_arguments(|arg| self.visit(arg))?,
+                partition_by: window
+                    .partition_by
+                    .iter()
+                    .map(|p| self.visit(p))
+                    .collect::<Result<Vec<_>>>()?,
+                order_by: window
+                    .order_by
+                    .iter()
+                    .map(|o| {
+                        Ok(WindowOrderBy {
+                            expr: self.visit(&o.expr)?,
+                            asc: o.asc,
+                            nulls_first: o.nulls_first,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                frame: window.frame.clone(),
+            }
+            .into()),
         }
     }
 
     /// Replace the arguments of aggregate function with a BoundColumnRef, and
     /// add the replaced aggregate function and the arguments into `AggregateInfo`.
+    ///
+    /// With grouping sets, the group item columns may be filled with NULL before
+    /// aggregation, so an argument referencing such column is evaluated into a new column.
     fn replace_aggregate_function(&mut self, aggregate: &AggregateFunction) -> Result<ScalarExpr> {
         let agg_info = &mut self.bind_context.aggregate_info;
         let mut replaced_args: Vec<ScalarExpr> = Vec::with_capacity(aggregate.args.len());
 
         for (i, arg) in aggregate.args.iter().enumerate() {
             let name = format!("{}_arg_{}", &aggregate.func_name, i);
-            if let ScalarExpr::BoundColumnRef(column_ref) = arg {
+            let column_ref = match arg {
+                ScalarExpr::BoundColumnRef(column_ref) => match &agg_info.grouping_sets {
+                    Some(grouping_sets)
+                        if grouping_sets.is_nullable_group_item(column_ref.column.index) =>
+                    {
+                        None
+                    }
+                    _ => Some(column_ref),
+                },
+                _ => None,
+            };
+            if let Some(column_ref) = column_ref {
                 replaced_args.push(column_ref.clone().into());
                 agg_info.aggregate_arguments.push(ScalarItem {
                     index: column_ref.column.index,
@@ -242,11 +322,13 @@ impl Binder {
     ///     `SELECT a as b, COUNT(a) FROM t GROUP BY b`.
     ///   - Scalar expressions that can be evaluated in current scope(doesn't contain aliases), e.g.
     ///     column `a` and expression `a+1` in `SELECT a as b, COUNT(a) FROM t GROUP BY a, a+1`.
+    ///
+    /// The group items can also be specified with `GROUPING SETS`, `ROLLUP` or `CUBE`.
     pub async fn analyze_group_items<'a>(
         &mut self,
         bind_context: &mut BindContext,
         select_list: &SelectList<'a>,
-        group_by: &[Expr],
+        group_by: &GroupBy,
     ) -> Result<()> {
         let mut available_aliases = vec![];
 
@@ -269,8 +351,49 @@ impl Binder {
             }
         }
 
-        self.resolve_group_items(bind_context, select_list, group_by, &available_aliases)
-            .await
+        match group_by {
+            GroupBy::Normal(exprs) => {
+                self.resolve_group_items(bind_context, select_list, exprs, &available_aliases)
+                    .await?;
+                Self::prune_group_items(bind_context);
+                Ok(())
+            }
+            GroupBy::GroupingSets(sets) => {
+                self.resolve_grouping_sets(bind_context, select_list, sets, &available_aliases)
+                    .await
+            }
+            GroupBy::Rollup(exprs) => {
+                // ROLLUP (a, b, c) => GROUPING SETS ((a, b, c), (a, b), (a), ())
+                let sets = (0..=exprs.len())
+                    .rev()
+                    .map(|i| exprs[0..i].to_vec())
+                    .collect::<Vec<_>>();
+                self.resolve_grouping_sets(bind_context, select_list, &sets, &available_aliases)
+                    .await
+            }
+            GroupBy::Cube(exprs) => {
+                if exprs.len() > MAX_CUBE_ITEMS {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "CUBE supports at most {MAX_CUBE_ITEMS} expressions"
+                    )));
+                }
+                // CUBE (a, b) => GROUPING SETS ((a, b), (a), (b), ())
+                let n = exprs.len();
+                let sets = (0..1usize << n)
+                    .rev()
+                    .map(|mask| {
+                        exprs
+                            .iter()
+                            .enumerate()
+                            .filter(|(i, _)| mask & (1 << (n - 1 - i)) != 0)
+                            .map(|(_, expr)| expr.clone())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                self.resolve_grouping_sets(bind_context, select_list, &sets, &available_aliases)
+                    .await
+            }
+        }
     }
 
     pub(super) async fn bind_aggregate(
@@ -300,25 +423,38 @@ impl Binder {
             new_expr = SExpr::create_unary(eval_scalar.into(), new_expr);
         }
 
+        let grouping_sets = bind_context
+            .aggregate_info
+            .grouping_sets
+            .as_ref()
+            .map(|info| GroupingSets {
+                grouping_id_index: info.grouping_id_column.index,
+                sets: info.sets.clone(),
+            });
+
         let aggregate_plan = Aggregate {
             mode: AggregateMode::Initial,
             group_items: bind_context.aggregate_info.group_items.clone(),
             aggregate_functions: bind_context.aggregate_info.aggregate_functions.clone(),
             from_distinct: false,
             limit: None,
+            grouping_sets,
         };
         new_expr = SExpr::create_unary(aggregate_plan.into(), new_expr);
 
         Ok(new_expr)
     }
 
+    /// Resolve group items and add them into `AggregateInfo`, returns the positions
+    /// of the group items in `AggregateInfo::group_items` for each expression.
     async fn resolve_group_items(
         &mut self,
         bind_context: &mut BindContext,
         select_list: &SelectList<'_>,
         group_by: &[Expr],
         available_aliases: &[(ColumnBinding, ScalarExpr)],
-    ) -> Result<()> {
+    ) -> Result<Vec<usize>> {
+        let mut positions = Vec::with_capacity(group_by.len());
         // Resolve group items with `FROM` context. Since the alias item can not be resolved
         // from the context, we can detect the failure and fallback to resolving with `available_aliases`.
         for expr in group_by.iter() {
@@ -330,7 +466,10 @@ impl Binder {
             {
                 let (scalar, alias) = Self::resolve_index_item(expr, *index, select_list)?;
                 let key = format!("{:?}", &scalar);
-                if let Entry::Vacant(entry) = bind_context.aggregate_info.group_items_map.entry(key)
+                if let Entry::Vacant(entry) = bind_context
+                    .aggregate_info
+                    .group_items_map
+                    .entry(key.clone())
                 {
                     // Add group item if it's not duplicated
                     let column_binding = if let ScalarExpr::BoundColumnRef(ref column_ref) = scalar
@@ -345,6 +484,7 @@ impl Binder {
                     });
                     entry.insert(bind_context.aggregate_info.group_items.len() - 1);
                 }
+                positions.push(bind_context.aggregate_info.group_items_map[&key]);
                 continue;
             }
 
@@ -361,13 +501,13 @@ impl Binder {
                 .await
                 .or_else(|e| Self::resolve_alias_item(bind_context, expr, available_aliases, e))?;
 
-            if bind_context
+            if let Some(position) = bind_context
                 .aggregate_info
                 .group_items_map
                 .get(&format!("{:?}", &scalar_expr))
-                .is_some()
             {
                 // The group key is duplicated
+                positions.push(*position);
                 continue;
             }
 
@@ -391,9 +531,14 @@ impl Binder {
                 format!("{:?}", &scalar_expr),
                 bind_context.aggregate_info.group_items.len() - 1,
             );
+            positions.push(bind_context.aggregate_info.group_items.len() - 1);
         }
 
-        // Remove dependent group items, group by a, f(a, b), f(a), b ---> group by a,b
+        Ok(positions)
+    }
+
+    /// Remove dependent group items, group by a, f(a, b), f(a), b ---> group by a,b
+    fn prune_group_items(bind_context: &mut BindContext) {
         let mut results = vec![];
         for item in bind_context.aggregate_info.group_items.iter() {
             let columns: HashSet<ScalarExpr> = bind_context
@@ -418,6 +563,57 @@ impl Binder {
                 .insert(format!("{:?}", &item.scalar), i);
         }
         bind_context.aggregate_info.group_items = results;
+    }
+
+    /// Resolve the group items of all the grouping sets, and build `GroupingSetsInfo`.
+    async fn resolve_grouping_sets(
+        &mut self,
+        bind_context: &mut BindContext,
+        select_list: &SelectList<'_>,
+        sets: &[Vec<Expr>],
+        available_aliases: &[(ColumnBinding, ScalarExpr)],
+    ) -> Result<()> {
+        let mut grouping_sets = Vec::with_capacity(sets.len());
+        for set in sets.iter() {
+            let mut positions = self
+                .resolve_group_items(bind_context, select_list, set, available_aliases)
+                .await?;
+            positions.sort();
+            positions.dedup();
+            grouping_sets.push(positions);
+        }
+
+        let agg_info = &bind_context.aggregate_info;
+        if agg_info.group_items.is_empty() {
+            // All the grouping sets are empty, it's the same as a scalar aggregation.
+            return Ok(());
+        }
+        if agg_info.group_items.len() > u32::BITS as usize {
+            return Err(ErrorCode::SemanticError(format!(
+                "grouping sets support at most {} group items",
+                u32::BITS
+            )));
+        }
+
+        let sets = grouping_sets
+            .into_iter()
+            .map(|set| {
+                set.into_iter()
+                    .map(|position| agg_info.group_items[position].index)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let grouping_id_column = self.create_column_binding(
+            None,
+            None,
+            "_grouping_id".to_string(),
+            DataType::Number(NumberDataType::UInt32),
+        );
+        bind_context.aggregate_info.grouping_sets = Some(GroupingSetsInfo {
+            grouping_id_column,
+            sets,
+        });
+
         Ok(())
     }
 
@@ -25,6 +25,7 @@ use common_expression::DataSchemaRefExt;
 use dashmap::DashMap;
 
 use super::AggregateInfo;
+use super::WindowInfo;
 use crate::normalize_identifier;
 use crate::optimizer::SExpr;
 use crate::plans::ScalarExpr;
@@ -88,6 +89,8 @@ pub struct BindContext {
 
     pub aggregate_info: AggregateInfo,
 
+    pub windows: WindowInfo,
+
     /// True if there is aggregation in current context, which means
     /// non-grouping columns cannot be referenced outside aggregation
     /// functions, otherwise a grouping error will be raised.
@@ -103,6 +106,9 @@ pub struct CteInfo {
     pub columns_alias: Vec<String>,
     pub s_expr: SExpr,
     pub bind_context: BindContext,
+    /// The index of the CTE if it's materialized, then `s_expr` is only executed once
+    /// and each reference scans its result.
+    pub materialized_cte_index: Option<IndexType>,
 }
 
 impl BindContext {
@@ -111,6 +117,7 @@ impl BindContext {
             parent: None,
             columns: Vec::new(),
             aggregate_info: AggregateInfo::default(),
+            windows: WindowInfo::default(),
             in_grouping: false,
             ctes_map: Box::new(DashMap::new()),
             is_view: false,
@@ -122,6 +129,7 @@ impl BindContext {
             parent: Some(parent.clone()),
             columns: vec![],
             aggregate_info: Default::default(),
+            windows: Default::default(),
             in_grouping: false,
             ctes_map: parent.ctes_map.clone(),
             is_view: false,
@@ -184,6 +184,10 @@ impl<'a> Binder {
             Statement::AlterView(stmt) => self.bind_alter_view(stmt).await?,
             Statement::DropView(stmt) => self.bind_drop_view(stmt).await?,
 
+            // Streams
+            Statement::CreateStream(stmt) => self.bind_create_stream(bind_context, stmt).await?,
+            Statement::DropStream(stmt) => self.bind_drop_stream(stmt).await?,
+
             // Users
             Statement::CreateUser(stmt) => self.bind_create_user(stmt).await?,
             Statement::DropUser { if_exists, user } => Plan::DropUser(Box::new(DropUserPlan {
@@ -236,6 +240,7 @@ impl<'a> Binder {
                     .await?
             }
             Statement::Update(stmt) => self.bind_update(bind_context, stmt).await?,
+            Statement::MergeInto(stmt) => self.bind_merge_into(bind_context, stmt).await?,
 
             // Permissions
             Statement::Grant(stmt) => self.bind_grant(stmt).await?,
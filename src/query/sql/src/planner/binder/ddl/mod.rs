@@ -18,5 +18,6 @@ mod database;
 mod role;
 mod share;
 mod stage;
+mod stream;
 mod table;
 mod view;
@@ -39,6 +39,7 @@ use common_ast::ast::ShowTablesStatusStmt;
 use common_ast::ast::ShowTablesStmt;
 use common_ast::ast::Statement;
 use common_ast::ast::TableReference;
+use common_ast::ast::TimeTravelPoint;
 use common_ast::ast::TruncateTableStmt;
 use common_ast::ast::UndropTableStmt;
 use common_ast::ast::UriLocation;
@@ -47,6 +48,7 @@ use common_ast::parser::tokenize_sql;
 use common_ast::walk_expr_mut;
 use common_ast::Backtrace;
 use common_ast::Dialect;
+use common_catalog::table::Table;
 use common_config::GlobalConfig;
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -64,8 +66,12 @@ use common_meta_app::storage::StorageParams;
 use common_storage::DataOperator;
 use common_storages_view::view_table::QUERY;
 use common_storages_view::view_table::VIEW_ENGINE;
+use storages_common_table_meta::table::is_internal_opt_key;
 use storages_common_table_meta::table::is_reserved_opt_key;
 use storages_common_table_meta::table::OPT_KEY_DATABASE_ID;
+use storages_common_table_meta::table::OPT_KEY_DATA_RETENTION_TIME;
+use storages_common_table_meta::table::OPT_KEY_MERGE_ON_READ;
+use storages_common_table_meta::table::OPT_KEY_SNAPSHOT_LOCATION;
 use storages_common_table_meta::table::OPT_KEY_STORAGE_FORMAT;
 use storages_common_table_meta::table::OPT_KEY_TABLE_COMPRESSION;
 use tracing::debug;
@@ -84,6 +90,7 @@ use crate::plans::AddTableColumnPlan;
 use crate::plans::AlterTableClusterKeyPlan;
 use crate::plans::AnalyzeTablePlan;
 use crate::plans::CastExpr;
+use crate::plans::CloneTableSource;
 use crate::plans::CreateTablePlanV2;
 use crate::plans::DescribeTablePlan;
 use crate::plans::DropTableClusterKeyPlan;
@@ -345,6 +352,29 @@ impl Binder {
 
         // Take FUSE engine AS default engine
         let engine = engine.unwrap_or(Engine::Fuse);
+
+        // `CREATE TABLE ... CLONE ...` shares the data files of the source table, thus the
+        // table is created in the same storage, with the options of the source table.
+        let clone_source = match source {
+            Some(CreateTableSource::Clone {
+                catalog,
+                database,
+                table,
+                travel_point,
+            }) => {
+                if engine != Engine::Fuse || uri_location.is_some() || as_query.is_some() {
+                    return Err(ErrorCode::BadArguments(
+                        "Incorrect CREATE query: CLONE can not be used with ENGINE, location or AS SELECT",
+                    ));
+                }
+                Some(
+                    self.resolve_clone_source(catalog, database, table, travel_point)
+                        .await?,
+                )
+            }
+            _ => None,
+        };
+
         let mut options: BTreeMap<String, String> = BTreeMap::new();
         for table_option in table_options.iter() {
             self.insert_table_option_with_validation(
@@ -377,9 +407,26 @@ impl Binder {
 
                 (Some(sp), fp)
             }
-            None => (None, "".to_string()),
+            None => match &clone_source {
+                Some((_, source_table)) => {
+                    let source_meta = &source_table.get_table_info().meta;
+                    (
+                        source_meta.storage_params.clone(),
+                        source_meta.part_prefix.clone(),
+                    )
+                }
+                None => (None, "".to_string()),
+            },
         };
 
+        if let Some((_, source_table)) = &clone_source {
+            for (key, value) in source_table.options() {
+                if key != OPT_KEY_SNAPSHOT_LOCATION && !is_internal_opt_key(key) {
+                    options.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+        }
+
         // If table is TRANSIENT, set a flag in table option
         if *transient {
             options.insert("TRANSIENT".to_owned(), "T".to_owned());
@@ -387,6 +434,15 @@ impl Binder {
 
         // Build table schema
         let (schema, field_default_exprs, field_comments) = match (&source, &as_query) {
+            (Some(_), None) if clone_source.is_some() => {
+                // `CREATE TABLE ... CLONE ...`, the schema of the snapshot cloned
+                let (_, source_table) = clone_source.as_ref().unwrap();
+                (
+                    source_table.schema(),
+                    vec![],
+                    source_table.field_comments().clone(),
+                )
+            }
             (Some(source), None) => {
                 // `CREATE TABLE` without `AS SELECT ...`
                 self.analyze_create_table_schema(source).await?
@@ -492,16 +548,35 @@ impl Binder {
                     default_compression.to_owned(),
                 );
             }
+
+            // the deletion vectors can only be applied to the blocks in parquet format.
+            let merge_on_read = options
+                .get(OPT_KEY_MERGE_ON_READ)
+                .map_or(false, |v| v.eq_ignore_ascii_case("true"));
+            let native = options
+                .get(OPT_KEY_STORAGE_FORMAT)
+                .map_or(false, |v| v.eq_ignore_ascii_case("native"));
+            if merge_on_read && native {
+                return Err(ErrorCode::TableOptionInvalid(format!(
+                    "table option {OPT_KEY_MERGE_ON_READ} is not supported by the native storage format, please specify {OPT_KEY_STORAGE_FORMAT} = 'parquet'",
+                )));
+            }
         }
 
         let cluster_key = {
             let keys = self
                 .analyze_cluster_keys(cluster_by, schema.clone())
                 .await?;
-            if keys.is_empty() {
-                None
-            } else {
+            if !keys.is_empty() {
                 Some(format!("({})", keys.join(", ")))
+            } else if let Some((_, source_table)) = &clone_source {
+                source_table
+                    .get_table_info()
+                    .meta
+                    .default_cluster_key
+                    .clone()
+            } else {
+                None
             }
         };
 
@@ -530,6 +605,7 @@ impl Binder {
             } else {
                 None
             },
+            clone_source: clone_source.map(|(source, _)| source),
         };
         Ok(Plan::CreateTable(Box::new(plan)))
     }
@@ -1000,6 +1076,17 @@ impl Binder {
             CreateTableSource::Columns(columns) => {
                 self.analyze_create_table_schema_by_columns(columns).await
             }
+            CreateTableSource::Clone {
+                catalog,
+                database,
+                table,
+                travel_point,
+            } => {
+                let (_, table) = self
+                    .resolve_clone_source(catalog, database, table, travel_point)
+                    .await?;
+                Ok((table.schema(), vec![], table.field_comments().clone()))
+            }
             CreateTableSource::Like {
                 catalog,
                 database,
@@ -1028,6 +1115,51 @@ impl Binder {
         }
     }
 
+    /// Resolve the table to be cloned, at the given time travel point if any.
+    async fn resolve_clone_source(
+        &self,
+        catalog: &Option<Identifier>,
+        database: &Option<Identifier>,
+        table: &Identifier,
+        travel_point: &Option<TimeTravelPoint>,
+    ) -> Result<(CloneTableSource, Arc<dyn Table>)> {
+        let catalog = catalog
+            .as_ref()
+            .map(|catalog| normalize_identifier(catalog, &self.name_resolution_ctx).name)
+            .unwrap_or_else(|| self.ctx.get_current_catalog());
+        let database = database.as_ref().map_or_else(
+            || self.ctx.get_current_database(),
+            |ident| normalize_identifier(ident, &self.name_resolution_ctx).name,
+        );
+        let table_name = normalize_identifier(table, &self.name_resolution_ctx).name;
+        let point = match travel_point {
+            Some(tp) => Some(
+                self.resolve_data_travel_point(&BindContext::new(), tp)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let mut table = self.ctx.get_table(&catalog, &database, &table_name).await?;
+        if table.engine() != Engine::Fuse.to_string() {
+            return Err(ErrorCode::TableEngineNotSupported(format!(
+                "Table {database}.{table_name} of engine {} can not be cloned, only FUSE tables can be",
+                table.engine()
+            )));
+        }
+        if let Some(point) = &point {
+            table = table.navigate_to(point).await?;
+        }
+
+        let source = CloneTableSource {
+            catalog,
+            database,
+            table: table_name,
+            point,
+        };
+        Ok((source, table))
+    }
+
     /// Validate the schema of the table to be created.
     fn validate_create_table_schema(schema: &TableSchemaRef) -> Result<()> {
         // Check if there are duplicated column names
@@ -1054,6 +1186,14 @@ impl Binder {
             Err(ErrorCode::TableOptionInvalid(format!(
                 "table option {key} reserved, please do not specify in the CREATE TABLE statement",
             )))
+        } else if key == OPT_KEY_MERGE_ON_READ && value.parse::<bool>().is_err() {
+            Err(ErrorCode::TableOptionInvalid(format!(
+                "invalid value of table option {key}: {value}, expect true or false",
+            )))
+        } else if key == OPT_KEY_DATA_RETENTION_TIME && value.parse::<u64>().is_err() {
+            Err(ErrorCode::TableOptionInvalid(format!(
+                "invalid value of table option {key}: {value}, expect the number of hours",
+            )))
         } else if options.insert(key.clone(), value).is_some() {
             Err(ErrorCode::TableOptionInvalid(format!(
                 "table option {key} duplicated"
@@ -48,7 +48,11 @@ impl Binder {
                         index: item.index,
                     })
                 } else {
-                    Ok(item)
+                    let scalar = self.replace_window_functions(bind_context, &item.scalar)?;
+                    Ok(ScalarItem {
+                        scalar,
+                        index: item.index,
+                    })
                 }
             })
             .collect::<Result<_>>()?;
@@ -76,6 +80,7 @@ impl Binder {
             aggregate_functions: vec![],
             from_distinct: true,
             limit: None,
+            grouping_sets: None,
         };
 
         Ok(SExpr::create_unary(distinct_plan.into(), new_expr))
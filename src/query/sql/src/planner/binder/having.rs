@@ -13,11 +13,13 @@
 // limitations under the License.
 
 use common_ast::ast::Expr;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_exception::Span;
 
 use super::select::SelectList;
 use crate::binder::aggregate::AggregateRewriter;
+use crate::binder::find_window_functions;
 use crate::binder::split_conjunctions;
 use crate::binder::ScalarBinder;
 use crate::optimizer::SExpr;
@@ -49,6 +51,12 @@ impl Binder {
             &aliases,
         );
         let (scalar, _) = scalar_binder.bind(having).await?;
+        if !find_window_functions(&scalar)?.is_empty() {
+            return Err(ErrorCode::SemanticError(
+                "window functions are not allowed in HAVING clause".to_string(),
+            )
+            .set_span(having.span()));
+        }
         let mut rewriter = AggregateRewriter::new(bind_context, self.metadata.clone());
         Ok((rewriter.visit(&scalar)?, having.span()))
     }
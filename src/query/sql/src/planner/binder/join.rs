@@ -20,6 +20,7 @@ use common_ast::ast::split_equivalent_predicate_expr;
 use common_ast::ast::Expr;
 use common_ast::ast::JoinCondition;
 use common_ast::ast::JoinOperator;
+use common_ast::ast::TableReference;
 use common_catalog::table_context::TableContext;
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -39,6 +40,7 @@ use crate::planner::semantic::NameResolutionContext;
 use crate::plans::BoundColumnRef;
 use crate::plans::Filter;
 use crate::plans::Join;
+use crate::plans::JoinAlgorithm;
 use crate::plans::JoinType;
 use crate::plans::ScalarExpr;
 use crate::BindContext;
@@ -58,6 +60,14 @@ impl Binder {
         bind_context: &BindContext,
         join: &common_ast::ast::Join,
     ) -> Result<(SExpr, BindContext)> {
+        if matches!(
+            join.right.as_ref(),
+            TableReference::TableFunction { lateral: true, .. }
+                | TableReference::Subquery { lateral: true, .. }
+        ) {
+            return self.bind_lateral_join(bind_context, join).await;
+        }
+
         let (left_child, left_context) =
             self.bind_table_reference(bind_context, &join.left).await?;
         let (right_child, right_context) =
@@ -200,6 +210,7 @@ impl Binder {
             join_type,
             marker_index: None,
             from_correlated_subquery: false,
+            algorithm: JoinAlgorithm::Hash,
         };
         Ok(SExpr::create_binary(
             logical_join.into(),
@@ -0,0 +1,392 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_ast::ast::Expr;
+use common_ast::ast::MatchOperation;
+use common_ast::ast::MergeClause;
+use common_ast::ast::MergeIntoStmt;
+use common_ast::ast::TableReference;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::type_check::common_super_type;
+use common_functions::scalars::BUILTIN_FUNCTIONS;
+
+use crate::binder::check_duplicate_join_tables;
+use crate::binder::split_conjunctions;
+use crate::binder::split_equivalent_predicate;
+use crate::binder::wrap_cast;
+use crate::binder::Binder;
+use crate::binder::ScalarBinder;
+use crate::normalize_identifier;
+use crate::optimizer::ColumnSet;
+use crate::plans::AggregateFunction;
+use crate::plans::AndExpr;
+use crate::plans::CastExpr;
+use crate::plans::ComparisonExpr;
+use crate::plans::FirstLastValueFunction;
+use crate::plans::FunctionCall;
+use crate::plans::LagLeadFunction;
+use crate::plans::MatchedClause;
+use crate::plans::MatchedOperation;
+use crate::plans::MergeIntoPlan;
+use crate::plans::NotExpr;
+use crate::plans::NotMatchedClause;
+use crate::plans::OrExpr;
+use crate::plans::Plan;
+use crate::plans::ScalarExpr;
+use crate::plans::WindowFunc;
+use crate::plans::WindowFuncType;
+use crate::BindContext;
+
+impl Binder {
+    pub(in crate::planner::binder) async fn bind_merge_into(
+        &mut self,
+        bind_context: &BindContext,
+        stmt: &MergeIntoStmt,
+    ) -> Result<Plan> {
+        let MergeIntoStmt {
+            table,
+            source,
+            join_expr,
+            merge_clauses,
+        } = stmt;
+
+        let (catalog_name, database_name, table_name) = if let TableReference::Table {
+            catalog,
+            database,
+            table,
+            ..
+        } = table
+        {
+            (
+                catalog
+                    .as_ref()
+                    .map_or_else(|| self.ctx.get_current_catalog(), |i| i.name.clone()),
+                database
+                    .as_ref()
+                    .map_or_else(|| self.ctx.get_current_database(), |i| i.name.clone()),
+                table.name.clone(),
+            )
+        } else {
+            return Err(ErrorCode::Internal(
+                "should not happen, parser should have report error already",
+            ));
+        };
+
+        let (_, target_context) = self.bind_table_reference(bind_context, table).await?;
+        let (source_expr, source_context) = self.bind_table_reference(bind_context, source).await?;
+        check_duplicate_join_tables(&target_context, &source_context)?;
+
+        let table = self
+            .ctx
+            .get_table(&catalog_name, &database_name, &table_name)
+            .await?;
+        let schema = table.schema();
+
+        let target_columns = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                target_context
+                    .columns
+                    .iter()
+                    .find(|column| column.column_name == field.name().as_str())
+                    .map(|column| column.index)
+                    .ok_or_else(|| {
+                        ErrorCode::Internal(format!(
+                            "Column {} of target table not found",
+                            field.name()
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The matched clauses and the ON clause see the columns of both tables.
+        let mut join_context = bind_context.replace();
+        for column in target_context
+            .columns
+            .iter()
+            .chain(source_context.columns.iter())
+        {
+            join_context.add_column_binding(column.clone());
+        }
+        let target_column_set: ColumnSet = target_context.columns.iter().map(|c| c.index).collect();
+        let source_column_set: ColumnSet = source_context.columns.iter().map(|c| c.index).collect();
+
+        let mut scalar_binder = ScalarBinder::new(
+            &join_context,
+            self.ctx.clone(),
+            &self.name_resolution_ctx,
+            self.metadata.clone(),
+            &[],
+        );
+        let (join_scalar, _) = scalar_binder.bind(join_expr).await?;
+        let mut target_keys = vec![];
+        let mut source_keys = vec![];
+        let mut join_filters = vec![];
+        for predicate in split_conjunctions(&join_scalar) {
+            check_subquery(&predicate)?;
+            let keys = split_equivalent_predicate(&predicate).and_then(|(left, right)| {
+                let (left_columns, right_columns) = (left.used_columns(), right.used_columns());
+                if !left_columns.is_empty()
+                    && left_columns.is_subset(&target_column_set)
+                    && right_columns.is_subset(&source_column_set)
+                {
+                    Some((left, right))
+                } else if !right_columns.is_empty()
+                    && right_columns.is_subset(&target_column_set)
+                    && left_columns.is_subset(&source_column_set)
+                {
+                    Some((right, left))
+                } else {
+                    None
+                }
+            });
+            match keys {
+                Some((mut target_key, mut source_key)) => {
+                    // Both keys are hashed, so they must be of the same type.
+                    let target_type = target_key.data_type();
+                    let source_type = source_key.data_type();
+                    if target_type != source_type {
+                        let least_super_type = common_super_type(
+                            target_type.clone(),
+                            source_type.clone(),
+                            &BUILTIN_FUNCTIONS.default_cast_rules,
+                        )
+                        .ok_or_else(|| {
+                            ErrorCode::SemanticError(format!(
+                                "Target type {target_type} and source type {source_type} cannot be matched"
+                            ))
+                        })?;
+                        target_key = wrap_cast(&target_key, &least_super_type);
+                        source_key = wrap_cast(&source_key, &least_super_type);
+                    }
+                    target_keys.push(target_key);
+                    source_keys.push(source_key);
+                }
+                None => join_filters.push(predicate),
+            }
+        }
+        if target_keys.is_empty() {
+            return Err(ErrorCode::SemanticError(
+                "MERGE INTO requires an equality condition between the target and the source in the ON clause",
+            ));
+        }
+
+        let mut matched_clauses = vec![];
+        let mut not_matched_clauses = vec![];
+        for clause in merge_clauses {
+            match clause {
+                MergeClause::Matched {
+                    selection,
+                    operation,
+                } => {
+                    let condition = self.bind_merge_condition(&join_context, selection).await?;
+                    let operation = match operation {
+                        MatchOperation::Update { update_list } => {
+                            let mut scalar_binder = ScalarBinder::new(
+                                &join_context,
+                                self.ctx.clone(),
+                                &self.name_resolution_ctx,
+                                self.metadata.clone(),
+                                &[],
+                            );
+                            let mut update_columns = Vec::with_capacity(update_list.len());
+                            for update_expr in update_list {
+                                let col_name = normalize_identifier(
+                                    &update_expr.name,
+                                    &self.name_resolution_ctx,
+                                )
+                                .name;
+                                let index = schema.index_of(&col_name)?;
+                                if update_columns.iter().any(|(i, _)| *i == index) {
+                                    return Err(ErrorCode::BadArguments(format!(
+                                        "Multiple assignments in the single statement to column `{}`",
+                                        col_name
+                                    )));
+                                }
+                                let (scalar, _) = scalar_binder.bind(&update_expr.expr).await?;
+                                check_subquery(&scalar)?;
+                                update_columns.push((index, scalar));
+                            }
+                            MatchedOperation::Update(update_columns)
+                        }
+                        MatchOperation::Delete => MatchedOperation::Delete,
+                    };
+                    matched_clauses.push(MatchedClause {
+                        condition,
+                        operation,
+                    });
+                }
+                MergeClause::NotMatched {
+                    selection,
+                    columns,
+                    values,
+                } => {
+                    // The not matched clauses only see the columns of the source.
+                    let condition = self
+                        .bind_merge_condition(&source_context, selection)
+                        .await?;
+                    let indices = if columns.is_empty() {
+                        (0..schema.num_fields()).collect::<Vec<_>>()
+                    } else {
+                        let mut indices = Vec::with_capacity(columns.len());
+                        for column in columns {
+                            let col_name =
+                                normalize_identifier(column, &self.name_resolution_ctx).name;
+                            let index = schema.index_of(&col_name)?;
+                            if indices.contains(&index) {
+                                return Err(ErrorCode::BadArguments(format!(
+                                    "Column `{}` specified more than once in the INSERT clause",
+                                    col_name
+                                )));
+                            }
+                            indices.push(index);
+                        }
+                        indices
+                    };
+                    if indices.len() != values.len() {
+                        return Err(ErrorCode::SemanticError(format!(
+                            "INSERT clause has {} columns but {} values",
+                            indices.len(),
+                            values.len()
+                        )));
+                    }
+
+                    let mut scalar_binder = ScalarBinder::new(
+                        &source_context,
+                        self.ctx.clone(),
+                        &self.name_resolution_ctx,
+                        self.metadata.clone(),
+                        &[],
+                    );
+                    let mut field_values = vec![None; schema.num_fields()];
+                    for (index, value) in indices.into_iter().zip(values.iter()) {
+                        let (scalar, _) = scalar_binder.bind(value).await?;
+                        check_subquery(&scalar)?;
+                        field_values[index] = Some(scalar);
+                    }
+                    not_matched_clauses.push(NotMatchedClause {
+                        condition,
+                        values: field_values,
+                    });
+                }
+            }
+        }
+
+        let source = Plan::Query {
+            s_expr: Box::new(source_expr),
+            metadata: self.metadata.clone(),
+            bind_context: Box::new(source_context),
+            rewrite_kind: None,
+            formatted_ast: None,
+            ignore_result: false,
+        };
+
+        let plan = MergeIntoPlan {
+            catalog: catalog_name,
+            database: database_name,
+            table: table_name,
+            source: Box::new(source),
+            target_columns,
+            target_keys,
+            source_keys,
+            join_filters,
+            matched_clauses,
+            not_matched_clauses,
+        };
+        Ok(Plan::MergeInto(Box::new(plan)))
+    }
+
+    async fn bind_merge_condition(
+        &mut self,
+        bind_context: &BindContext,
+        selection: &Option<Expr>,
+    ) -> Result<Option<ScalarExpr>> {
+        let expr = match selection {
+            Some(expr) => expr,
+            None => return Ok(None),
+        };
+        let mut scalar_binder = ScalarBinder::new(
+            bind_context,
+            self.ctx.clone(),
+            &self.name_resolution_ctx,
+            self.metadata.clone(),
+            &[],
+        );
+        let (scalar, _) = scalar_binder.bind(expr).await?;
+        check_subquery(&scalar)?;
+        Ok(Some(scalar))
+    }
+}
+
+/// The subqueries are only supported in the source of MERGE INTO, not in the join
+/// condition, the matched conditions or the values.
+fn check_subquery(scalar: &ScalarExpr) -> Result<()> {
+    if contains_subquery_expr(scalar) {
+        return Err(ErrorCode::Unimplemented(
+            "MERGE INTO does not support subquery in the join condition, the clause conditions or the values",
+        ));
+    }
+    Ok(())
+}
+
+fn contains_subquery_expr(scalar: &ScalarExpr) -> bool {
+    match scalar {
+        ScalarExpr::SubqueryExpr(_) => true,
+        ScalarExpr::BoundColumnRef(_) | ScalarExpr::ConstantExpr(_) => false,
+        ScalarExpr::AndExpr(AndExpr { left, right, .. })
+        | ScalarExpr::OrExpr(OrExpr { left, right, .. })
+        | ScalarExpr::ComparisonExpr(ComparisonExpr { left, right, .. }) => {
+            contains_subquery_expr(left) || contains_subquery_expr(right)
+        }
+        ScalarExpr::NotExpr(NotExpr { argument, .. })
+        | ScalarExpr::CastExpr(CastExpr { argument, .. }) => contains_subquery_expr(argument),
+        ScalarExpr::FunctionCall(FunctionCall { arguments, .. }) => {
+            arguments.iter().any(contains_subquery_expr)
+        }
+        ScalarExpr::AggregateFunction(AggregateFunction { args, .. }) => {
+            args.iter().any(contains_subquery_expr)
+        }
+        ScalarExpr::WindowFunction(WindowFunc {
+            func,
+            partition_by,
+            order_by,
+            ..
+        }) => {
+            let func_contains = match func {
+                WindowFuncType::Aggregate(AggregateFunction { args, .. }) => {
+                    args.iter().any(contains_subquery_expr)
+                }
+                WindowFuncType::Lag(LagLeadFunction { arg, default, .. })
+                | WindowFuncType::Lead(LagLeadFunction { arg, default, .. }) => {
+                    contains_subquery_expr(arg)
+                        || default.as_deref().map_or(false, contains_subquery_expr)
+                }
+                WindowFuncType::FirstValue(FirstLastValueFunction { arg, .. })
+                | WindowFuncType::LastValue(FirstLastValueFunction { arg, .. }) => {
+                    contains_subquery_expr(arg)
+                }
+                WindowFuncType::RowNumber | WindowFuncType::Rank | WindowFuncType::DenseRank => {
+                    false
+                }
+            };
+            func_contains
+                || partition_by.iter().any(contains_subquery_expr)
+                || order_by
+                    .iter()
+                    .any(|order_by| contains_subquery_expr(&order_by.expr))
+        }
+    }
+}
@@ -28,8 +28,11 @@ mod join;
 mod kill;
 mod limit;
 mod location;
+mod merge_into;
+mod pivot;
 mod presign;
 mod project;
+mod project_set;
 mod scalar;
 mod scalar_common;
 mod scalar_visitor;
@@ -40,8 +43,10 @@ mod sort;
 mod table;
 mod table_args;
 mod update;
+mod window;
 
 pub use aggregate::AggregateInfo;
+pub use aggregate::GroupingSetsInfo;
 pub use bind_context::*;
 pub use binder::Binder;
 pub use builders::*;
@@ -49,3 +54,4 @@ pub use copy::parse_stage_location;
 pub use location::parse_uri_location;
 pub use scalar::ScalarBinder;
 pub use scalar_common::*;
+pub use window::WindowInfo;
@@ -0,0 +1,321 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use async_recursion::async_recursion;
+use common_ast::ast::BinaryOperator;
+use common_ast::ast::Expr;
+use common_ast::ast::GroupBy;
+use common_ast::ast::Identifier;
+use common_ast::ast::Indirection;
+use common_ast::ast::Literal;
+use common_ast::ast::Pivot;
+use common_ast::ast::Query;
+use common_ast::ast::SelectStmt;
+use common_ast::ast::SelectTarget;
+use common_ast::ast::SetExpr;
+use common_ast::ast::SetOperation;
+use common_ast::ast::SetOperator;
+use common_ast::ast::TableAlias;
+use common_ast::ast::TableReference;
+use common_ast::ast::Unpivot;
+use common_ast::walk_expr;
+use common_ast::Visitor;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::Span;
+use common_functions::aggregates::AggregateFunctionFactory;
+
+use crate::binder::Binder;
+use crate::binder::Visibility;
+use crate::optimizer::SExpr;
+use crate::planner::semantic::normalize_identifier;
+use crate::BindContext;
+
+impl Binder {
+    /// Bind `table_ref PIVOT (aggregate FOR column IN (values))`.
+    ///
+    /// It's rewritten into an aggregation grouped by the columns of `table_ref` which
+    /// are not used by the pivot, with one aggregate function for each of the `values`,
+    /// which only aggregates the rows of that value:
+    ///
+    /// `SELECT <group columns>, aggregate(if(column = value, arg, NULL)) AS value, ...
+    ///  FROM table_ref GROUP BY <group columns>`
+    #[async_recursion]
+    pub(super) async fn bind_pivot(
+        &mut self,
+        bind_context: &BindContext,
+        span: Span,
+        table: &TableReference,
+        pivot: &Pivot,
+        alias: &Option<TableAlias>,
+    ) -> Result<(SExpr, BindContext)> {
+        let (s_expr, from_context) = self.bind_table_reference(bind_context, table).await?;
+
+        let (func_name, distinct, args, params) = match &pivot.aggregate {
+            Expr::FunctionCall {
+                distinct,
+                name,
+                args,
+                params,
+                window: None,
+                ..
+            } if AggregateFunctionFactory::instance().contains(&name.name) => {
+                (name.clone(), *distinct, args.clone(), params.clone())
+            }
+            Expr::CountAll { span } => (
+                Identifier {
+                    name: "count".to_string(),
+                    quote: None,
+                    span: *span,
+                },
+                false,
+                vec![],
+                vec![],
+            ),
+            aggregate => {
+                return Err(ErrorCode::SemanticError(format!(
+                    "PIVOT requires an aggregate function, but got {aggregate}"
+                ))
+                .set_span(aggregate.span()));
+            }
+        };
+
+        // The columns used by the pivot are not grouped.
+        let value_column = normalize_identifier(&pivot.value_column, &self.name_resolution_ctx);
+        let mut collector = ColumnRefCollector::default();
+        walk_expr(&mut collector, &pivot.aggregate);
+        let mut pivot_columns = HashSet::new();
+        pivot_columns.insert(value_column.name.clone());
+        for column in collector.columns {
+            pivot_columns.insert(normalize_identifier(column, &self.name_resolution_ctx).name);
+        }
+        if !from_context
+            .columns
+            .iter()
+            .any(|column| column.column_name == value_column.name)
+        {
+            return Err(ErrorCode::SemanticError(format!(
+                "column {} doesn't exist",
+                pivot.value_column
+            ))
+            .set_span(pivot.value_column.span));
+        }
+
+        let group_items = from_context
+            .columns
+            .iter()
+            .filter(|column| {
+                column.visibility == Visibility::Visible
+                    && !pivot_columns.contains(&column.column_name)
+            })
+            .map(|column| Expr::ColumnRef {
+                span,
+                database: None,
+                table: column
+                    .table_name
+                    .as_ref()
+                    .map(|table| quoted_identifier(table)),
+                column: quoted_identifier(&column.column_name),
+            })
+            .collect::<Vec<_>>();
+
+        let mut select_list = group_items
+            .iter()
+            .map(|item| SelectTarget::AliasedExpr {
+                expr: Box::new(item.clone()),
+                alias: None,
+            })
+            .collect::<Vec<_>>();
+        for value in pivot.values.iter() {
+            let condition = Expr::BinaryOp {
+                span,
+                op: BinaryOperator::Eq,
+                left: Box::new(Expr::ColumnRef {
+                    span,
+                    database: None,
+                    table: None,
+                    column: pivot.value_column.clone(),
+                }),
+                right: Box::new(value.clone()),
+            };
+            let if_expr = |arg: Expr| Expr::FunctionCall {
+                span,
+                distinct: false,
+                name: Identifier {
+                    name: "if".to_string(),
+                    quote: None,
+                    span,
+                },
+                args: vec![condition.clone(), arg, Expr::Literal {
+                    span,
+                    lit: Literal::Null,
+                }],
+                params: vec![],
+                window: None,
+            };
+            let args = if args.is_empty() {
+                vec![if_expr(Expr::Literal {
+                    span,
+                    lit: Literal::Integer(1),
+                })]
+            } else {
+                args.iter().cloned().map(if_expr).collect()
+            };
+            let column_name = match value {
+                Expr::Literal {
+                    lit: Literal::String(value),
+                    ..
+                } => value.clone(),
+                _ => value.to_string(),
+            };
+            select_list.push(SelectTarget::AliasedExpr {
+                expr: Box::new(Expr::FunctionCall {
+                    span,
+                    distinct,
+                    name: func_name.clone(),
+                    args,
+                    params: params.clone(),
+                    window: None,
+                }),
+                alias: Some(quoted_identifier(&column_name)),
+            });
+        }
+
+        let stmt = SelectStmt {
+            span,
+            distinct: false,
+            select_list,
+            from: vec![],
+            selection: None,
+            group_by: (!group_items.is_empty()).then_some(GroupBy::Normal(group_items)),
+            having: None,
+        };
+        let (s_expr, mut bind_context) = self.bind_select(&stmt, &[], s_expr, from_context).await?;
+        if let Some(alias) = alias {
+            bind_context.apply_table_alias(alias, &self.name_resolution_ctx)?;
+        }
+        Ok((s_expr, bind_context))
+    }
+
+    /// Bind `table_ref UNPIVOT (value_column FOR name_column IN (columns))`.
+    ///
+    /// It's rewritten into a `UNION ALL` with one branch for each of the `columns`,
+    /// skipping the rows whose value is NULL:
+    ///
+    /// `SELECT * EXCLUDE (columns), 'column' AS name_column, column AS value_column
+    ///  FROM table_ref WHERE column IS NOT NULL UNION ALL ...`
+    #[async_recursion]
+    pub(super) async fn bind_unpivot(
+        &mut self,
+        bind_context: &BindContext,
+        span: Span,
+        table: &TableReference,
+        unpivot: &Unpivot,
+        alias: &Option<TableAlias>,
+    ) -> Result<(SExpr, BindContext)> {
+        let branches = unpivot.columns.iter().map(|column| {
+            let column_name = normalize_identifier(column, &self.name_resolution_ctx).name;
+            let column_ref = Expr::ColumnRef {
+                span: column.span,
+                database: None,
+                table: None,
+                column: column.clone(),
+            };
+            SetExpr::Select(Box::new(SelectStmt {
+                span,
+                distinct: false,
+                select_list: vec![
+                    SelectTarget::QualifiedName {
+                        qualified: vec![Indirection::Star],
+                        exclude: Some(unpivot.columns.clone()),
+                    },
+                    SelectTarget::AliasedExpr {
+                        expr: Box::new(Expr::Literal {
+                            span: column.span,
+                            lit: Literal::String(column_name),
+                        }),
+                        alias: Some(unpivot.name_column.clone()),
+                    },
+                    SelectTarget::AliasedExpr {
+                        expr: Box::new(column_ref.clone()),
+                        alias: Some(unpivot.value_column.clone()),
+                    },
+                ],
+                from: vec![table.clone()],
+                selection: Some(Expr::IsNull {
+                    span: column.span,
+                    expr: Box::new(column_ref),
+                    not: true,
+                }),
+                group_by: None,
+                having: None,
+            }))
+        });
+        let body = branches
+            .reduce(|left, right| {
+                SetExpr::SetOperation(Box::new(SetOperation {
+                    span,
+                    op: SetOperator::Union,
+                    all: true,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }))
+            })
+            .unwrap();
+
+        let subquery = TableReference::Subquery {
+            span,
+            lateral: false,
+            subquery: Box::new(Query {
+                span,
+                with: None,
+                body,
+                order_by: vec![],
+                limit: vec![],
+                offset: None,
+                ignore_result: false,
+            }),
+            alias: alias.clone(),
+        };
+        self.bind_table_reference(bind_context, &subquery).await
+    }
+}
+
+fn quoted_identifier(name: &str) -> Identifier {
+    Identifier {
+        name: name.to_string(),
+        quote: Some('"'),
+        span: None,
+    }
+}
+
+/// Collect the columns referenced by an expression.
+#[derive(Default)]
+struct ColumnRefCollector<'a> {
+    columns: Vec<&'a Identifier>,
+}
+
+impl<'a> Visitor<'a> for ColumnRefCollector<'a> {
+    fn visit_column_ref(
+        &mut self,
+        _span: Span,
+        _database: &'a Option<Identifier>,
+        _table: &'a Option<Identifier>,
+        column: &'a Identifier,
+    ) {
+        self.columns.push(column);
+    }
+}
@@ -113,18 +113,33 @@ impl Binder {
                         index: item.index,
                     })
                 } else {
-                    Ok(item.clone())
+                    let scalar = self.replace_window_functions(bind_context, &item.scalar)?;
+                    Ok(ScalarItem {
+                        scalar,
+                        index: item.index,
+                    })
                 }
             })
             .collect::<Result<Vec<_>>>()?;
 
+        let mut columns = columns.to_vec();
+        if bind_context.aggregate_info.grouping_sets.is_some() {
+            // The group items may be filled with NULL by grouping sets,
+            // so the data types of output columns should be updated.
+            for column in columns.iter_mut() {
+                if let Some(item) = scalars.iter().find(|item| item.index == column.index) {
+                    column.data_type = Box::new(item.scalar.data_type());
+                }
+            }
+        }
+
         scalars.sort_by_key(|s| s.index);
         let eval_scalar = EvalScalar { items: scalars };
 
         let new_expr = SExpr::create_unary(eval_scalar.into(), child);
 
         // Set output columns
-        bind_context.columns = columns.to_vec();
+        bind_context.columns = columns;
 
         Ok(new_expr)
     }
@@ -0,0 +1,383 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_ast::ast::Expr;
+use common_ast::ast::Identifier;
+use common_ast::ast::Join;
+use common_ast::ast::JoinCondition;
+use common_ast::ast::JoinOperator;
+use common_ast::ast::Literal as AstLiteral;
+use common_ast::ast::TableAlias;
+use common_ast::ast::TableReference;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::Span;
+use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
+use common_expression::Literal;
+
+use crate::binder::scalar::ScalarBinder;
+use crate::binder::split_conjunctions;
+use crate::binder::Binder;
+use crate::binder::ColumnBinding;
+use crate::binder::Visibility;
+use crate::optimizer::SExpr;
+use crate::planner::semantic::normalize_identifier;
+use crate::plans::CastExpr;
+use crate::plans::ConstantExpr;
+use crate::plans::DummyTableScan;
+use crate::plans::Filter;
+use crate::plans::FlattenMode;
+use crate::plans::FlattenOptions;
+use crate::plans::FunctionCall;
+use crate::plans::ProjectSet;
+use crate::plans::ScalarExpr;
+use crate::plans::SetReturningFunction;
+use crate::BindContext;
+
+/// Returns true if the table function is a set-returning function, which is
+/// evaluated on each row of its input by a `ProjectSet`.
+pub(super) fn is_set_returning_function(name: &str) -> bool {
+    matches!(name, "flatten" | "unnest")
+}
+
+impl Binder {
+    /// Bind a set-returning function in the `FROM` clause that is not on the right
+    /// side of a lateral join, so its arguments can't reference other tables.
+    pub(super) async fn bind_set_returning_function(
+        &mut self,
+        bind_context: &BindContext,
+        span: Span,
+        name: &Identifier,
+        params: &[Expr],
+        named_params: &[(String, Expr)],
+        alias: &Option<TableAlias>,
+    ) -> Result<(SExpr, BindContext)> {
+        let child = SExpr::create_leaf(DummyTableScan.into());
+        let mut srf_context = BindContext::with_parent(Box::new(bind_context.clone()));
+        let s_expr = self
+            .bind_project_set(
+                bind_context,
+                &mut srf_context,
+                child,
+                span,
+                name,
+                params,
+                named_params,
+                alias,
+                false,
+            )
+            .await?;
+        Ok((s_expr, srf_context))
+    }
+
+    /// Bind a join whose right side is a `LATERAL` table function, the arguments of
+    /// the function can reference the columns of the left side.
+    ///
+    /// The function is evaluated on each row of the left side by a `ProjectSet`,
+    /// so only cross joins, inner joins and left outer joins without a join
+    /// condition are supported.
+    pub(super) async fn bind_lateral_join(
+        &mut self,
+        bind_context: &BindContext,
+        join: &Join,
+    ) -> Result<(SExpr, BindContext)> {
+        let (span, name, params, named_params, alias) = match join.right.as_ref() {
+            TableReference::TableFunction {
+                span,
+                name,
+                params,
+                named_params,
+                alias,
+                ..
+            } => (*span, name, params, named_params, alias),
+            TableReference::Subquery { span, .. } => {
+                return Err(ErrorCode::Unimplemented(
+                    "LATERAL subquery is not supported yet".to_string(),
+                )
+                .set_span(*span));
+            }
+            _ => unreachable!(),
+        };
+        let func_name = normalize_identifier(name, &self.name_resolution_ctx).name;
+        if !is_set_returning_function(&func_name) {
+            return Err(ErrorCode::SemanticError(format!(
+                "LATERAL is only supported for the table functions FLATTEN and UNNEST, but got {func_name}"
+            ))
+            .set_span(span));
+        }
+
+        let predicate = match (&join.op, &join.condition) {
+            (JoinOperator::CrossJoin, _) | (JoinOperator::Inner, JoinCondition::None) => None,
+            (JoinOperator::Inner, JoinCondition::On(expr)) => Some(expr.as_ref()),
+            (JoinOperator::LeftOuter, JoinCondition::None) => None,
+            (JoinOperator::LeftOuter, JoinCondition::On(expr))
+                if matches!(expr.as_ref(), Expr::Literal {
+                    lit: AstLiteral::Boolean(true),
+                    ..
+                }) =>
+            {
+                None
+            }
+            _ => {
+                return Err(ErrorCode::SemanticError(
+                    "LATERAL table function only supports CROSS JOIN, INNER JOIN and LEFT JOIN ON TRUE"
+                        .to_string(),
+                )
+                .set_span(span));
+            }
+        };
+        let outer = join.op == JoinOperator::LeftOuter;
+
+        let (left_child, left_context) =
+            self.bind_table_reference(bind_context, &join.left).await?;
+        let mut output_context = left_context.clone();
+        let mut s_expr = self
+            .bind_project_set(
+                &left_context,
+                &mut output_context,
+                left_child,
+                span,
+                name,
+                params,
+                named_params,
+                alias,
+                outer,
+            )
+            .await?;
+
+        if let Some(expr) = predicate {
+            let mut scalar_binder = ScalarBinder::new(
+                &output_context,
+                self.ctx.clone(),
+                &self.name_resolution_ctx,
+                self.metadata.clone(),
+                &[],
+            );
+            let (scalar, _) = scalar_binder.bind(expr).await?;
+            let filter = Filter {
+                predicates: split_conjunctions(&scalar),
+                is_having: false,
+            };
+            s_expr = SExpr::create_unary(filter.into(), s_expr);
+        }
+
+        Ok((s_expr, output_context))
+    }
+
+    /// Bind a `ProjectSet` on top of `child`, the arguments of the function are
+    /// bound in `bind_context`, and the output columns of the function are added
+    /// to `output_context`.
+    #[allow(clippy::too_many_arguments)]
+    async fn bind_project_set(
+        &mut self,
+        bind_context: &BindContext,
+        output_context: &mut BindContext,
+        child: SExpr,
+        span: Span,
+        name: &Identifier,
+        params: &[Expr],
+        named_params: &[(String, Expr)],
+        alias: &Option<TableAlias>,
+        mut outer: bool,
+    ) -> Result<SExpr> {
+        let func_name = normalize_identifier(name, &self.name_resolution_ctx).name;
+        let mut scalar_binder = ScalarBinder::new(
+            bind_context,
+            self.ctx.clone(),
+            &self.name_resolution_ctx,
+            self.metadata.clone(),
+            &[],
+        );
+
+        let mut input = None;
+        let mut path = String::new();
+        let mut recursive = false;
+        let mut mode = FlattenMode::Both;
+        match (params, named_params) {
+            ([param], []) => input = Some(scalar_binder.bind(param).await?),
+            ([], named_params) if func_name == "flatten" => {
+                for (param_name, param) in named_params.iter() {
+                    let param_name = param_name.to_lowercase();
+                    let (scalar, data_type) = scalar_binder.bind(param).await?;
+                    match param_name.as_str() {
+                        "input" => input = Some((scalar, data_type)),
+                        "path" => path = constant_string(&func_name, "path", &scalar, span)?,
+                        "outer" => outer |= constant_bool(&func_name, "outer", &scalar, span)?,
+                        "recursive" => {
+                            recursive = constant_bool(&func_name, "recursive", &scalar, span)?
+                        }
+                        "mode" => {
+                            mode = match constant_string(&func_name, "mode", &scalar, span)?
+                                .to_uppercase()
+                                .as_str()
+                            {
+                                "OBJECT" => FlattenMode::Object,
+                                "ARRAY" => FlattenMode::Array,
+                                "BOTH" => FlattenMode::Both,
+                                other => {
+                                    return Err(ErrorCode::SemanticError(format!(
+                                        "Invalid mode '{other}' of table function {func_name}, expected 'OBJECT', 'ARRAY' or 'BOTH'"
+                                    ))
+                                    .set_span(span));
+                                }
+                            }
+                        }
+                        _ => {
+                            return Err(ErrorCode::SemanticError(format!(
+                                "Unknown argument '{param_name}' of table function {func_name}"
+                            ))
+                            .set_span(span));
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+        let (input, input_type) = input.ok_or_else(|| {
+            ErrorCode::SemanticError(format!("Table function {func_name} requires one input"))
+                .set_span(span)
+        })?;
+
+        let (srf, arg, columns) = if func_name == "unnest" {
+            let element_type = match input_type.remove_nullable() {
+                DataType::Array(element_type) => *element_type,
+                DataType::EmptyArray | DataType::Null => DataType::Null,
+                data_type => {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "Table function unnest requires an array, but got {data_type}"
+                    ))
+                    .set_span(span));
+                }
+            };
+            let element_type = if outer && element_type != DataType::Null {
+                element_type.wrap_nullable()
+            } else {
+                element_type
+            };
+            (SetReturningFunction::Unnest, input, vec![(
+                func_name.clone(),
+                element_type,
+            )])
+        } else {
+            let mut input = if input_type.remove_nullable() == DataType::Variant {
+                input
+            } else {
+                let target_type = if input_type.is_nullable_or_null() {
+                    DataType::Nullable(Box::new(DataType::Variant))
+                } else {
+                    DataType::Variant
+                };
+                ScalarExpr::CastExpr(CastExpr {
+                    is_try: false,
+                    argument: Box::new(input),
+                    from_type: Box::new(input_type),
+                    target_type: Box::new(target_type),
+                })
+            };
+            if !path.is_empty() {
+                input = ScalarExpr::FunctionCall(FunctionCall {
+                    params: vec![],
+                    arguments: vec![
+                        input,
+                        ScalarExpr::ConstantExpr(ConstantExpr {
+                            value: Literal::String(path.as_bytes().to_vec()),
+                            data_type: Box::new(DataType::String),
+                        }),
+                    ],
+                    func_name: "get_path".to_string(),
+                    return_type: Box::new(DataType::Nullable(Box::new(DataType::Variant))),
+                });
+            }
+            let nullable = |data_type| DataType::Nullable(Box::new(data_type));
+            let columns = vec![
+                ("key".to_string(), nullable(DataType::String)),
+                ("path".to_string(), nullable(DataType::String)),
+                (
+                    "index".to_string(),
+                    nullable(DataType::Number(NumberDataType::UInt64)),
+                ),
+                ("value".to_string(), nullable(DataType::Variant)),
+                ("this".to_string(), nullable(DataType::Variant)),
+            ];
+            let options = FlattenOptions {
+                path,
+                recursive,
+                mode,
+            };
+            (SetReturningFunction::Flatten(options), input, columns)
+        };
+
+        let mut srf_context = BindContext::new();
+        let mut indices = Vec::with_capacity(columns.len());
+        for (column_name, data_type) in columns {
+            let index = self
+                .metadata
+                .write()
+                .add_derived_column(column_name.clone(), data_type.clone());
+            srf_context.add_column_binding(ColumnBinding {
+                database_name: None,
+                table_name: Some(func_name.clone()),
+                column_name,
+                index,
+                data_type: Box::new(data_type),
+                visibility: Visibility::Visible,
+            });
+            indices.push(index);
+        }
+        if let Some(alias) = alias {
+            srf_context.apply_table_alias(alias, &self.name_resolution_ctx)?;
+        }
+        output_context.columns.extend(srf_context.columns);
+
+        let project_set = ProjectSet {
+            srf,
+            args: vec![arg],
+            columns: indices,
+            outer,
+        };
+        Ok(SExpr::create_unary(project_set.into(), child))
+    }
+}
+
+fn constant_string(
+    func_name: &str,
+    arg_name: &str,
+    scalar: &ScalarExpr,
+    span: Span,
+) -> Result<String> {
+    match scalar {
+        ScalarExpr::ConstantExpr(ConstantExpr {
+            value: Literal::String(value),
+            ..
+        }) => Ok(String::from_utf8_lossy(value).to_string()),
+        _ => Err(ErrorCode::SemanticError(format!(
+            "Argument '{arg_name}' of table function {func_name} must be a constant string"
+        ))
+        .set_span(span)),
+    }
+}
+
+fn constant_bool(func_name: &str, arg_name: &str, scalar: &ScalarExpr, span: Span) -> Result<bool> {
+    match scalar {
+        ScalarExpr::ConstantExpr(ConstantExpr {
+            value: Literal::Boolean(value),
+            ..
+        }) => Ok(*value),
+        _ => Err(ErrorCode::SemanticError(format!(
+            "Argument '{arg_name}' of table function {func_name} must be a constant boolean"
+        ))
+        .set_span(span)),
+    }
+}
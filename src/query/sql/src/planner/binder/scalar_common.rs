@@ -42,7 +42,6 @@ impl<'a, F> Finder<'a, F>
 where F: Fn(&ScalarExpr) -> bool
 {
     /// Create a new finder with the `test_fn`
-    #[allow(dead_code)]
     fn new(find_fn: &'a F) -> Self {
         Self {
             find_fn,
@@ -67,6 +66,12 @@ where F: Fn(&ScalarExpr) -> bool
     }
 }
 
+/// Find window functions in a scalar expression, the duplicated ones are removed.
+pub fn find_window_functions(scalar: &ScalarExpr) -> Result<Vec<ScalarExpr>> {
+    let finder = Finder::new(&|scalar: &ScalarExpr| matches!(scalar, ScalarExpr::WindowFunction(_)));
+    Ok(scalar.accept(finder)?.scalars)
+}
+
 pub fn split_conjunctions(scalar: &ScalarExpr) -> Vec<ScalarExpr> {
     match scalar {
         ScalarExpr::AndExpr(AndExpr { left, right, .. }) => {
@@ -204,6 +209,7 @@ pub fn prune_by_children(scalar: &ScalarExpr, columns: &HashSet<ScalarExpr>) ->
             .all(|arg| prune_by_children(arg, columns)),
         ScalarExpr::CastExpr(expr) => prune_by_children(expr.argument.as_ref(), columns),
         ScalarExpr::SubqueryExpr(_) => false,
+        ScalarExpr::WindowFunction(_) => false,
     }
 }
 
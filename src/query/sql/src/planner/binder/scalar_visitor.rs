@@ -22,6 +22,7 @@ use crate::plans::FunctionCall;
 use crate::plans::NotExpr;
 use crate::plans::OrExpr;
 use crate::plans::ScalarExpr;
+use crate::plans::WindowFunc;
 
 /// Controls how the visitor recursion should proceed.
 pub enum Recursion<V: ScalarVisitor> {
@@ -87,6 +88,22 @@ pub trait ScalarVisitor: Sized {
                                     stack.push(RecursionProcessing::Call(argument))
                                 }
                                 ScalarExpr::SubqueryExpr(_) => {}
+                                ScalarExpr::WindowFunction(WindowFunc {
+                                    func,
+                                    partition_by,
+                                    order_by,
+                                    ..
+                                }) => {
+                                    for arg in func.arguments() {
+                                        stack.push(RecursionProcessing::Call(arg));
+                                    }
+                                    for arg in partition_by.iter() {
+                                        stack.push(RecursionProcessing::Call(arg));
+                                    }
+                                    for order in order_by.iter() {
+                                        stack.push(RecursionProcessing::Call(&order.expr));
+                                    }
+                                }
                             }
 
                             visitor
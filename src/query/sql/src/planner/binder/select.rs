@@ -26,13 +26,16 @@ use common_ast::ast::SelectTarget;
 use common_ast::ast::SetExpr;
 use common_ast::ast::SetOperator;
 use common_ast::ast::TableReference;
+use common_ast::ast::CTE;
+use common_ast::walk_table_reference;
+use common_ast::Visitor;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_expression::type_check::common_super_type;
 use common_expression::types::DataType;
 use common_functions::scalars::BUILTIN_FUNCTIONS;
 
-use crate::binder::join::JoinConditions;
+use crate::binder::scalar_common::find_window_functions;
 use crate::binder::scalar_common::split_conjunctions;
 use crate::binder::CteInfo;
 use crate::binder::Visibility;
@@ -40,13 +43,19 @@ use crate::optimizer::SExpr;
 use crate::planner::binder::scalar::ScalarBinder;
 use crate::planner::binder::BindContext;
 use crate::planner::binder::Binder;
+use crate::planner::semantic::normalize_identifier;
+use crate::planner::semantic::NameResolutionContext;
 use crate::plans::BoundColumnRef;
 use crate::plans::CastExpr;
 use crate::plans::EvalScalar;
 use crate::plans::Filter;
-use crate::plans::JoinType;
+use crate::plans::MaterializedCte;
+use crate::plans::RecursiveCteScan;
+use crate::plans::RecursiveUnion;
 use crate::plans::ScalarExpr;
 use crate::plans::ScalarItem;
+use crate::plans::SetOperation;
+use crate::plans::SetOperationType;
 use crate::plans::UnionAll;
 use crate::ColumnBinding;
 use crate::IndexType;
@@ -71,7 +80,7 @@ impl Binder {
         stmt: &SelectStmt,
         order_by: &[OrderByExpr],
     ) -> Result<(SExpr, BindContext)> {
-        let (mut s_expr, mut from_context) = if stmt.from.is_empty() {
+        let (s_expr, from_context) = if stmt.from.is_empty() {
             self.bind_one_table(bind_context, stmt).await?
         } else {
             let cross_joins = stmt
@@ -92,6 +101,18 @@ impl Binder {
                 .await?
         };
 
+        self.bind_select(stmt, order_by, s_expr, from_context).await
+    }
+
+    /// Bind the clauses of a `SELECT` statement except `FROM`, on top of the
+    /// already bound `FROM` clause.
+    pub(super) async fn bind_select(
+        &mut self,
+        stmt: &SelectStmt,
+        order_by: &[OrderByExpr],
+        mut s_expr: SExpr,
+        mut from_context: BindContext,
+    ) -> Result<(SExpr, BindContext)> {
         if let Some(expr) = &stmt.selection {
             s_expr = self.bind_where(&from_context, expr, s_expr).await?;
         }
@@ -104,11 +125,14 @@ impl Binder {
         let (mut scalar_items, projections) = self.analyze_projection(&select_list)?;
 
         // This will potentially add some alias group items to `from_context` if find some.
-        self.analyze_group_items(&mut from_context, &select_list, &stmt.group_by)
-            .await?;
+        if let Some(group_by) = &stmt.group_by {
+            self.analyze_group_items(&mut from_context, &select_list, group_by).await?;
+        }
 
         self.analyze_aggregate_select(&mut from_context, &mut select_list)?;
 
+        self.analyze_window_select(&mut from_context, &select_list)?;
+
         let having = if let Some(having) = &stmt.having {
             Some(
                 self.analyze_aggregate_having(&mut from_context, &select_list, having)
@@ -140,6 +164,10 @@ impl Binder {
                 .await?;
         }
 
+        if !from_context.windows.window_functions.is_empty() {
+            s_expr = self.bind_window(&from_context, s_expr)?;
+        }
+
         if stmt.distinct {
             s_expr = self.bind_distinct(&from_context, &projections, &mut scalar_items, s_expr)?;
         }
@@ -195,23 +223,53 @@ impl Binder {
         bind_context: &BindContext,
         query: &Query,
     ) -> Result<(SExpr, BindContext)> {
+        // The common table expressions to be materialized, with their indexes and columns.
+        let mut materialized_ctes = vec![];
         if let Some(with) = &query.with {
-            for cte in with.ctes.iter() {
+            // The CTEs of `WITH RECURSIVE` are always expanded at each reference.
+            let enable_materialized_cte =
+                !with.recursive && self.ctx.get_settings().get_enable_materialized_cte()?;
+            for (cte_index, cte) in with.ctes.iter().enumerate() {
                 let table_name = cte.alias.name.name.clone();
                 if bind_context.ctes_map.contains_key(&table_name) {
                     return Err(ErrorCode::SemanticError(format!(
                         "duplicate cte {table_name}"
                     )));
                 }
-                let (s_expr, cte_bind_context) = self.bind_query(bind_context, &cte.query).await?;
+                let (s_expr, cte_bind_context) =
+                    if with.recursive && self.count_cte_references(&table_name, &cte.query) > 0 {
+                        self.bind_recursive_cte(bind_context, &table_name, cte)
+                            .await?
+                    } else {
+                        self.bind_query(bind_context, &cte.query).await?
+                    };
+                let materialized_cte_index = if enable_materialized_cte
+                    && self.count_query_cte_references(&table_name, query, cte_index) > 1
+                {
+                    let cte_idx = self
+                        .metadata
+                        .write()
+                        .add_materialized_cte(table_name.clone());
+                    let columns = cte_bind_context
+                        .columns
+                        .iter()
+                        .map(|column| column.index)
+                        .collect::<Vec<_>>();
+                    materialized_ctes.push((table_name.clone(), cte_idx, columns, s_expr.clone()));
+                    Some(cte_idx)
+                } else {
+                    None
+                };
                 let cte_info = CteInfo {
                     columns_alias: cte.alias.columns.iter().map(|c| c.name.clone()).collect(),
                     s_expr,
                     bind_context: cte_bind_context.clone(),
+                    materialized_cte_index,
                 };
                 bind_context.ctes_map.insert(table_name, cte_info);
             }
         }
+        let ctes_map = &bind_context.ctes_map;
         let (mut s_expr, bind_context) = match query.body {
             SetExpr::Select(_) | SetExpr::Query(_) => {
                 self.bind_set_expr(bind_context, &query.body, &query.order_by)
@@ -250,9 +308,161 @@ impl Binder {
                 .await?;
         }
 
+        // A materialized CTE is executed before the query referencing it, and the
+        // CTEs defined later may reference the earlier ones, so the last one is the
+        // innermost.
+        for (table_name, cte_idx, columns, cte_s_expr) in materialized_ctes.into_iter().rev() {
+            ctes_map.remove(&table_name);
+            s_expr = SExpr::create_binary(
+                MaterializedCte { cte_idx, columns }.into(),
+                cte_s_expr,
+                s_expr,
+            );
+        }
+
         Ok((s_expr, bind_context))
     }
 
+    /// Bind a recursive common table expression, which must be of the form
+    /// `non-recursive term UNION [ALL] recursive term`.
+    ///
+    /// The recursive term reads the rows produced by the previous iteration
+    /// through a `RecursiveCteScan`, and the types of the columns are inferred
+    /// from the non-recursive term only.
+    async fn bind_recursive_cte(
+        &mut self,
+        bind_context: &BindContext,
+        table_name: &str,
+        cte: &CTE,
+    ) -> Result<(SExpr, BindContext)> {
+        let query = &cte.query;
+        let set_operation = match &query.body {
+            SetExpr::SetOperation(set_operation)
+                if set_operation.op == SetOperator::Union
+                    && query.order_by.is_empty()
+                    && query.limit.is_empty()
+                    && query.offset.is_none() =>
+            {
+                set_operation
+            }
+            _ => {
+                return Err(ErrorCode::SemanticError(format!(
+                    "recursive cte {table_name} must be of the form `non-recursive term UNION [ALL] recursive term`"
+                ))
+                .set_span(cte.span));
+            }
+        };
+        if self.count_set_expr_cte_references(table_name, &set_operation.left) > 0 {
+            return Err(ErrorCode::SemanticError(format!(
+                "recursive cte {table_name} cannot be referenced in the non-recursive term"
+            ))
+            .set_span(cte.span));
+        }
+        if self.count_set_expr_cte_references(table_name, &set_operation.right) > 1 {
+            return Err(ErrorCode::SemanticError(format!(
+                "recursive cte {table_name} can only be referenced once in the recursive term"
+            ))
+            .set_span(cte.span));
+        }
+
+        let (left_expr, left_bind_context) = self
+            .bind_set_expr(bind_context, &set_operation.left, &[])
+            .await?;
+
+        // The working table of the recursion, whose columns are of the types of
+        // the non-recursive term.
+        let mut fields = Vec::with_capacity(left_bind_context.columns.len());
+        let mut scan_bind_context = BindContext::new();
+        for column in left_bind_context.columns.iter() {
+            let index = self
+                .metadata
+                .write()
+                .add_derived_column(column.column_name.clone(), *column.data_type.clone());
+            fields.push((index, *column.data_type.clone()));
+            scan_bind_context.add_column_binding(ColumnBinding {
+                database_name: None,
+                table_name: None,
+                column_name: column.column_name.clone(),
+                index,
+                data_type: column.data_type.clone(),
+                visibility: Visibility::Visible,
+            });
+        }
+        let scan = RecursiveCteScan {
+            cte_name: table_name.to_string(),
+            fields,
+        };
+        bind_context.ctes_map.insert(table_name.to_string(), CteInfo {
+            columns_alias: cte.alias.columns.iter().map(|c| c.name.clone()).collect(),
+            s_expr: SExpr::create_leaf(scan.into()),
+            bind_context: scan_bind_context,
+            materialized_cte_index: None,
+        });
+        let right = self
+            .bind_set_expr(bind_context, &set_operation.right, &[])
+            .await;
+        bind_context.ctes_map.remove(table_name);
+        let (right_expr, right_bind_context) = right?;
+
+        if left_bind_context.columns.len() != right_bind_context.columns.len() {
+            return Err(ErrorCode::SemanticError(
+                "SetOperation must have the same number of columns",
+            ));
+        }
+        let coercion_types = left_bind_context
+            .columns
+            .iter()
+            .map(|column| *column.data_type.clone())
+            .collect();
+        let (new_bind_context, pairs, left_expr, right_expr) = self.coercion_union_type(
+            left_bind_context,
+            right_bind_context,
+            left_expr,
+            right_expr,
+            coercion_types,
+        )?;
+        let recursive_union = RecursiveUnion {
+            cte_name: table_name.to_string(),
+            pairs,
+            distinct: !set_operation.all,
+        };
+        let s_expr = SExpr::create_binary(recursive_union.into(), left_expr, right_expr);
+        Ok((s_expr, new_bind_context))
+    }
+
+    fn count_cte_references(&self, table_name: &str, query: &Query) -> usize {
+        let mut visitor = CteReferenceCounter::new(table_name, &self.name_resolution_ctx);
+        visitor.visit_query(query);
+        visitor.count
+    }
+
+    /// Count the references to the `cte_index`-th common table expression of `query`,
+    /// in the CTEs defined after it and in the query itself.
+    fn count_query_cte_references(
+        &self,
+        table_name: &str,
+        query: &Query,
+        cte_index: usize,
+    ) -> usize {
+        let mut visitor = CteReferenceCounter::new(table_name, &self.name_resolution_ctx);
+        if let Some(with) = &query.with {
+            for cte in with.ctes.iter().skip(cte_index + 1) {
+                visitor.visit_query(&cte.query);
+            }
+        }
+        visitor.visit_set_expr(&query.body);
+        for order_by in query.order_by.iter() {
+            visitor.visit_order_by(order_by);
+        }
+        visitor.count
+    }
+
+    fn count_set_expr_cte_references(&self, table_name: &str, set_expr: &SetExpr) -> usize {
+        let mut visitor = CteReferenceCounter::new(table_name, &self.name_resolution_ctx);
+        visitor.visit_set_expr(set_expr);
+        visitor.count
+    }
+
     pub(super) async fn bind_where(
         &mut self,
         bind_context: &BindContext,
@@ -267,6 +477,12 @@ impl Binder {
             &[],
         );
         let (scalar, _) = scalar_binder.bind(expr).await?;
+        if !find_window_functions(&scalar)?.is_empty() {
+            return Err(ErrorCode::SemanticError(
+                "window functions are not allowed in WHERE clause".to_string(),
+            )
+            .set_span(expr.span()));
+        }
         let filter_plan = Filter {
             predicates: split_conjunctions(&scalar),
             is_having: false,
@@ -302,7 +518,12 @@ impl Binder {
                         *right_col.data_type.clone(),
                         &BUILTIN_FUNCTIONS.default_cast_rules,
                     )
-                    .expect("SetOperation's types cannot be matched");
+                    .ok_or_else(|| {
+                        ErrorCode::SemanticError(format!(
+                            "SetOperation's types cannot be matched, left: {}, right: {}",
+                            left_col.data_type, right_col.data_type
+                        ))
+                    })?;
                     coercion_types.push(data_type);
                 } else {
                     coercion_types.push(*left_col.data_type.clone());
@@ -310,14 +531,24 @@ impl Binder {
             }
         }
         match (op, all) {
-            (SetOperator::Intersect, false) => {
-                // Transfer Intersect to Semi join
-                self.bind_intersect(left_bind_context, right_bind_context, left_expr, right_expr)
-            }
-            (SetOperator::Except, false) => {
-                // Transfer Except to Anti join
-                self.bind_except(left_bind_context, right_bind_context, left_expr, right_expr)
-            }
+            (SetOperator::Intersect, _) => self.bind_intersect_or_except(
+                left_bind_context,
+                right_bind_context,
+                coercion_types,
+                left_expr,
+                right_expr,
+                SetOperationType::IntersectAll,
+                !*all,
+            ),
+            (SetOperator::Except, _) => self.bind_intersect_or_except(
+                left_bind_context,
+                right_bind_context,
+                coercion_types,
+                left_expr,
+                right_expr,
+                SetOperationType::ExceptAll,
+                !*all,
+            ),
             (SetOperator::Union, true) => self.bind_union(
                 left_bind_context,
                 right_bind_context,
@@ -334,9 +565,6 @@ impl Binder {
                 right_expr,
                 true,
             ),
-            _ => Err(ErrorCode::Unimplemented(
-                "Unsupported query type, currently, databend only support intersect distinct and except distinct",
-            )),
         }
     }
 
@@ -371,81 +599,40 @@ impl Binder {
         Ok((new_expr, new_bind_context))
     }
 
-    fn bind_intersect(
-        &mut self,
-        left_context: BindContext,
-        right_context: BindContext,
-        left_expr: SExpr,
-        right_expr: SExpr,
-    ) -> Result<(SExpr, BindContext)> {
-        self.bind_intersect_or_except(
-            left_context,
-            right_context,
-            left_expr,
-            right_expr,
-            JoinType::LeftSemi,
-        )
-    }
-
-    fn bind_except(
+    /// `INTERSECT [ALL]` and `EXCEPT [ALL]` are bound to a `SetOperation` with bag
+    /// semantics. For the `DISTINCT` variants, the duplicated rows of the left side
+    /// are removed first, so each row is output at most once.
+    #[allow(clippy::too_many_arguments)]
+    fn bind_intersect_or_except(
         &mut self,
         left_context: BindContext,
         right_context: BindContext,
+        coercion_types: Vec<DataType>,
         left_expr: SExpr,
         right_expr: SExpr,
+        op_type: SetOperationType,
+        distinct: bool,
     ) -> Result<(SExpr, BindContext)> {
-        self.bind_intersect_or_except(
+        let (new_bind_context, pairs, mut left_expr, right_expr) = self.coercion_union_type(
             left_context,
             right_context,
             left_expr,
             right_expr,
-            JoinType::LeftAnti,
-        )
-    }
-
-    fn bind_intersect_or_except(
-        &mut self,
-        left_context: BindContext,
-        right_context: BindContext,
-        left_expr: SExpr,
-        right_expr: SExpr,
-        join_type: JoinType,
-    ) -> Result<(SExpr, BindContext)> {
-        let left_expr = self.bind_distinct(
-            &left_context,
-            left_context.all_column_bindings(),
-            &mut HashMap::new(),
-            left_expr,
+            coercion_types,
         )?;
-        let mut left_conditions = Vec::with_capacity(left_context.columns.len());
-        let mut right_conditions = Vec::with_capacity(right_context.columns.len());
-        assert_eq!(left_context.columns.len(), right_context.columns.len());
-        for (left_column, right_column) in left_context
-            .columns
-            .iter()
-            .zip(right_context.columns.iter())
-        {
-            left_conditions.push(
-                BoundColumnRef {
-                    column: left_column.clone(),
-                }
-                .into(),
-            );
-            right_conditions.push(
-                BoundColumnRef {
-                    column: right_column.clone(),
-                }
-                .into(),
-            );
+
+        if distinct {
+            left_expr = self.bind_distinct(
+                &new_bind_context,
+                new_bind_context.all_column_bindings(),
+                &mut HashMap::new(),
+                left_expr,
+            )?;
         }
-        let join_conditions = JoinConditions {
-            left_conditions,
-            right_conditions,
-            non_equi_conditions: vec![],
-            other_conditions: vec![],
-        };
-        let s_expr = self.bind_join_with_type(join_type, join_conditions, left_expr, right_expr)?;
-        Ok((s_expr, left_context))
+
+        let set_operation = SetOperation { op_type, pairs };
+        let new_expr = SExpr::create_binary(set_operation.into(), left_expr, right_expr);
+        Ok((new_expr, new_bind_context))
     }
 
     #[allow(clippy::type_complexity)]
@@ -548,3 +735,31 @@ impl Binder {
         Ok((new_bind_context, pairs, left_expr, right_expr))
     }
 }
+
+/// Count the table references to a common table expression.
+struct CteReferenceCounter<'a> {
+    table_name: &'a str,
+    name_resolution_ctx: &'a NameResolutionContext,
+    count: usize,
+}
+
+impl<'a> CteReferenceCounter<'a> {
+    fn new(table_name: &'a str, name_resolution_ctx: &'a NameResolutionContext) -> Self {
+        Self {
+            table_name,
+            name_resolution_ctx,
+            count: 0,
+        }
+    }
+}
+
+impl<'a, 'ast> Visitor<'ast> for CteReferenceCounter<'a> {
+    fn visit_table_reference(&mut self, table_ref: &'ast TableReference) {
+        if let TableReference::Table { table, .. } = table_ref {
+            if normalize_identifier(table, self.name_resolution_ctx).name == self.table_name {
+                self.count += 1;
+            }
+        }
+        walk_table_reference(self, table_ref);
+    }
+}
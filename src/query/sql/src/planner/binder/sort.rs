@@ -265,6 +265,8 @@ impl Binder {
                     if from_context.in_grouping || need_group_check {
                         let mut group_checker = GroupingChecker::new(from_context);
                         scalar = group_checker.resolve(&scalar, None)?;
+                    } else {
+                        scalar = self.replace_window_functions(from_context, &scalar)?;
                     }
                     scalars.push(ScalarItem { scalar, index });
                 }
@@ -350,7 +352,7 @@ impl Binder {
     }
 
     #[allow(clippy::only_used_in_recursion)]
-    fn rewrite_scalar_with_replacement<F>(
+    pub(super) fn rewrite_scalar_with_replacement<F>(
         &self,
         original_scalar: &ScalarExpr,
         replacement_fn: &F,
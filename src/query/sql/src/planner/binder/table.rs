@@ -52,15 +52,18 @@ use common_storages_view::view_table::QUERY;
 
 use crate::binder::copy::parse_stage_location_v2;
 use crate::binder::location::parse_uri_location;
+use crate::binder::project_set::is_set_returning_function;
 use crate::binder::scalar::ScalarBinder;
 use crate::binder::table_args::bind_table_args;
 use crate::binder::Binder;
 use crate::binder::ColumnBinding;
 use crate::binder::CteInfo;
 use crate::binder::Visibility;
+use crate::optimizer::RelExpr;
 use crate::optimizer::SExpr;
 use crate::planner::semantic::normalize_identifier;
 use crate::planner::semantic::TypeChecker;
+use crate::plans::CteScan;
 use crate::plans::Scan;
 use crate::plans::Statistics;
 use crate::BaseTableColumn;
@@ -225,12 +228,27 @@ impl Binder {
                 }
             }
             TableReference::TableFunction {
-                span: _,
+                span,
+                lateral: _,
                 name,
                 params,
                 named_params,
                 alias,
             } => {
+                let func_name = normalize_identifier(name, &self.name_resolution_ctx).name;
+                if is_set_returning_function(&func_name) {
+                    return self
+                        .bind_set_returning_function(
+                            bind_context,
+                            *span,
+                            name,
+                            params,
+                            named_params,
+                            alias,
+                        )
+                        .await;
+                }
+
                 let mut scalar_binder = ScalarBinder::new(
                     bind_context,
                     self.ctx.clone(),
@@ -244,10 +262,7 @@ impl Binder {
                 let table_meta: Arc<dyn TableFunction> = self
                     .catalogs
                     .get_catalog(CATALOG_DEFAULT)?
-                    .get_table_function(
-                        &normalize_identifier(name, &self.name_resolution_ctx).name,
-                        table_args,
-                    )?;
+                    .get_table_function(&func_name, table_args)?;
                 let table = table_meta.as_table();
                 let table_alias_name = if let Some(table_alias) = alias {
                     Some(normalize_identifier(&table_alias.name, &self.name_resolution_ctx).name)
@@ -271,11 +286,36 @@ impl Binder {
                 Ok((s_expr, bind_context))
             }
             TableReference::Join { span: _, join } => self.bind_join(bind_context, join).await,
+            TableReference::Pivot {
+                span,
+                table,
+                pivot,
+                alias,
+            } => {
+                self.bind_pivot(bind_context, *span, table, pivot, alias)
+                    .await
+            }
+            TableReference::Unpivot {
+                span,
+                table,
+                unpivot,
+                alias,
+            } => {
+                self.bind_unpivot(bind_context, *span, table, unpivot, alias)
+                    .await
+            }
             TableReference::Subquery {
-                span: _,
+                span,
+                lateral,
                 subquery,
                 alias,
             } => {
+                if *lateral {
+                    return Err(ErrorCode::Unimplemented(
+                        "LATERAL subquery is not supported yet".to_string(),
+                    )
+                    .set_span(*span));
+                }
                 // For subquery, we need use a new context to bind it.
                 let new_bind_context = BindContext::with_parent(Box::new(bind_context.clone()));
                 let (s_expr, mut new_bind_context) =
@@ -397,6 +437,28 @@ impl Binder {
         for (index, column_name) in cols_alias.iter().enumerate() {
             new_bind_context.columns[index].column_name = column_name.clone();
         }
+
+        if let Some(cte_idx) = cte_info.materialized_cte_index {
+            // Each reference scans the materialized result with its own columns.
+            let cardinality = RelExpr::with_s_expr(&cte_info.s_expr)
+                .derive_relational_prop()?
+                .cardinality;
+            let mut fields = Vec::with_capacity(new_bind_context.columns.len());
+            for column in new_bind_context.columns.iter_mut() {
+                let data_type = *column.data_type.clone();
+                column.index = self
+                    .metadata
+                    .write()
+                    .add_derived_column(column.column_name.clone(), data_type.clone());
+                fields.push((column.index, data_type));
+            }
+            let scan = CteScan {
+                cte_idx,
+                fields,
+                cardinality: cardinality as u64,
+            };
+            return Ok((SExpr::create_leaf(scan.into()), new_bind_context));
+        }
         Ok((cte_info.s_expr.clone(), new_bind_context))
     }
 
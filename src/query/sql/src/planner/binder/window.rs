@@ -0,0 +1,232 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::binder::find_window_functions;
+use crate::binder::select::SelectList;
+use crate::binder::Binder;
+use crate::binder::ColumnBinding;
+use crate::binder::Visibility;
+use crate::optimizer::SExpr;
+use crate::planner::semantic::GroupingChecker;
+use crate::plans::BoundColumnRef;
+use crate::plans::EvalScalar;
+use crate::plans::ScalarExpr;
+use crate::plans::ScalarItem;
+use crate::plans::Window;
+use crate::plans::WindowFunc;
+use crate::plans::WindowOrderByInfo;
+use crate::BindContext;
+use crate::IndexType;
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct WindowInfo {
+    /// Window functions, with the index of their output columns
+    pub window_functions: Vec<(IndexType, WindowFunc)>,
+
+    /// Mapping: (window function display name) -> (index of window function in `window_functions`)
+    /// This is used to find a window function in current context.
+    pub window_functions_map: HashMap<String, usize>,
+}
+
+impl WindowInfo {
+    /// Get the column reference to the output of a bound window function.
+    pub fn resolve_window_function(&self, window: &WindowFunc) -> Result<ScalarExpr> {
+        match self.window_functions_map.get(&window.display_name) {
+            Some(i) => {
+                let (index, window) = &self.window_functions[*i];
+                Ok(BoundColumnRef {
+                    column: ColumnBinding {
+                        database_name: None,
+                        table_name: None,
+                        column_name: window.display_name.clone(),
+                        index: *index,
+                        data_type: Box::new(window.func.return_type()),
+                        visibility: Visibility::Visible,
+                    },
+                }
+                .into())
+            }
+            None => Err(ErrorCode::SemanticError(format!(
+                "window function {} must appear in the SELECT list",
+                window.display_name
+            ))),
+        }
+    }
+}
+
+impl Binder {
+    /// Analyze window functions in select clause, every distinct window function
+    /// will be allocated an output column.
+    pub(super) fn analyze_window_select(
+        &mut self,
+        bind_context: &mut BindContext,
+        select_list: &SelectList,
+    ) -> Result<()> {
+        for item in select_list.items.iter() {
+            for scalar in find_window_functions(&item.scalar)? {
+                let window = WindowFunc::try_from(scalar)?;
+                let window_info = &mut bind_context.windows;
+                if window_info
+                    .window_functions_map
+                    .contains_key(&window.display_name)
+                {
+                    continue;
+                }
+                let index = self
+                    .metadata
+                    .write()
+                    .add_derived_column(window.display_name.clone(), window.func.return_type());
+                window_info
+                    .window_functions_map
+                    .insert(window.display_name.clone(), window_info.window_functions.len());
+                window_info.window_functions.push((index, window));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build a `Window` plan for each window function in current context.
+    ///
+    /// The arguments, partition keys and order keys of the window function are
+    /// evaluated by an `EvalScalar` below the `Window` if they are not columns.
+    pub(super) fn bind_window(
+        &mut self,
+        bind_context: &BindContext,
+        child: SExpr,
+    ) -> Result<SExpr> {
+        let mut new_expr = child;
+        for (index, window) in bind_context.windows.window_functions.iter() {
+            let mut scalar_items = vec![];
+
+            let mut arg_count = 0;
+            let function = window.func.try_map_arguments(|arg| {
+                arg_count += 1;
+                let item = self.materialize_window_scalar(
+                    bind_context,
+                    arg,
+                    format!("{}_arg_{}", window.func.func_name(), arg_count - 1),
+                    &mut scalar_items,
+                )?;
+                Ok(item.scalar)
+            })?;
+
+            let partition_by = window
+                .partition_by
+                .iter()
+                .enumerate()
+                .map(|(i, scalar)| {
+                    self.materialize_window_scalar(
+                        bind_context,
+                        scalar,
+                        format!("{}_partition_by_{}", window.func.func_name(), i),
+                        &mut scalar_items,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let order_by = window
+                .order_by
+                .iter()
+                .enumerate()
+                .map(|(i, order)| {
+                    Ok(WindowOrderByInfo {
+                        order_by_item: self.materialize_window_scalar(
+                            bind_context,
+                            &order.expr,
+                            format!("{}_order_by_{}", window.func.func_name(), i),
+                            &mut scalar_items,
+                        )?,
+                        asc: order.asc,
+                        nulls_first: order.nulls_first,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if !scalar_items.is_empty() {
+                let eval_scalar = EvalScalar {
+                    items: scalar_items,
+                };
+                new_expr = SExpr::create_unary(eval_scalar.into(), new_expr);
+            }
+
+            let window_plan = Window {
+                index: *index,
+                function,
+                partition_by,
+                order_by,
+                frame: window.frame.clone(),
+            };
+            new_expr = SExpr::create_unary(window_plan.into(), new_expr);
+        }
+
+        Ok(new_expr)
+    }
+
+    /// Replace window functions in `scalar` with the columns of their outputs.
+    pub(super) fn replace_window_functions(
+        &self,
+        bind_context: &BindContext,
+        scalar: &ScalarExpr,
+    ) -> Result<ScalarExpr> {
+        self.rewrite_scalar_with_replacement(scalar, &|nest_scalar| {
+            if let ScalarExpr::WindowFunction(window) = nest_scalar {
+                return Ok(Some(bind_context.windows.resolve_window_function(window)?));
+            }
+            Ok(None)
+        })
+    }
+
+    /// Make `scalar` a column reference, the original expression will be pushed
+    /// into `scalar_items` if it's not a column.
+    fn materialize_window_scalar(
+        &mut self,
+        bind_context: &BindContext,
+        scalar: &ScalarExpr,
+        name: String,
+        scalar_items: &mut Vec<ScalarItem>,
+    ) -> Result<ScalarItem> {
+        let scalar = if bind_context.in_grouping {
+            let mut grouping_checker = GroupingChecker::new(bind_context);
+            grouping_checker.resolve(scalar, None)?
+        } else {
+            scalar.clone()
+        };
+
+        if let ScalarExpr::BoundColumnRef(column_ref) = &scalar {
+            return Ok(ScalarItem {
+                index: column_ref.column.index,
+                scalar,
+            });
+        }
+
+        let column_binding = self.create_column_binding(None, None, name, scalar.data_type());
+        scalar_items.push(ScalarItem {
+            index: column_binding.index,
+            scalar,
+        });
+        Ok(ScalarItem {
+            index: column_binding.index,
+            scalar: BoundColumnRef {
+                column: column_binding,
+            }
+            .into(),
+        })
+    }
+}
@@ -28,6 +28,7 @@ use crate::plans::EvalScalar;
 use crate::plans::Exchange;
 use crate::plans::Filter;
 use crate::plans::Join;
+use crate::plans::JoinAlgorithm;
 use crate::plans::JoinType;
 use crate::plans::Limit;
 use crate::plans::RelOperator;
@@ -78,6 +79,13 @@ impl Display for FormatContext {
                 RelOperator::UnionAll(_) => write!(f, "Union"),
                 RelOperator::Pattern(_) => write!(f, "Pattern"),
                 RelOperator::DummyTableScan(_) => write!(f, "DummyTableScan"),
+                RelOperator::Window(_) => write!(f, "Window"),
+                RelOperator::RecursiveUnion(_) => write!(f, "RecursiveUnion"),
+                RelOperator::RecursiveCteScan(_) => write!(f, "RecursiveCteScan"),
+                RelOperator::MaterializedCte(_) => write!(f, "MaterializedCte"),
+                RelOperator::CteScan(_) => write!(f, "CteScan"),
+                RelOperator::SetOperation(op) => write!(f, "SetOperation: {}", op.op_type),
+                RelOperator::ProjectSet(op) => write!(f, "ProjectSet: {}", op.srf),
             },
             Self::Text(text) => write!(f, "{}", text),
         }
@@ -137,6 +145,7 @@ pub fn format_scalar(_metadata: &MetadataRef, scalar: &ScalarExpr) -> String {
             )
         }
         ScalarExpr::SubqueryExpr(_) => "SUBQUERY".to_string(),
+        ScalarExpr::WindowFunction(window) => window.display_name.clone(),
     }
 }
 
@@ -149,6 +158,9 @@ pub fn format_join(
         JoinType::Cross => {
             write!(f, "CrossJoin")
         }
+        _ if op.algorithm == JoinAlgorithm::SortMerge => {
+            write!(f, "SortMergeJoin: {}", &op.join_type)
+        }
         _ => {
             write!(f, "HashJoin: {}", &op.join_type)
         }
@@ -56,6 +56,8 @@ pub type MetadataRef = Arc<RwLock<Metadata>>;
 pub struct Metadata {
     tables: Vec<TableEntry>,
     columns: Vec<ColumnEntry>,
+    /// Names of the materialized common table expressions, indexed by their unique index.
+    materialized_ctes: Vec<String>,
 }
 
 impl Metadata {
@@ -128,6 +130,18 @@ impl Metadata {
         column_index
     }
 
+    pub fn materialized_cte_name(&self, index: IndexType) -> &str {
+        self.materialized_ctes
+            .get(index)
+            .expect("metadata must contain materialized cte")
+    }
+
+    pub fn add_materialized_cte(&mut self, name: String) -> IndexType {
+        let cte_index = self.materialized_ctes.len();
+        self.materialized_ctes.push(name);
+        cte_index
+    }
+
     pub fn add_table(
         &mut self,
         catalog: String,
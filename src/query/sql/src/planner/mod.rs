@@ -16,6 +16,7 @@ mod format;
 mod metadata;
 #[allow(clippy::module_inception)]
 mod planner;
+mod prepared_statement;
 mod semantic;
 
 pub mod binder;
@@ -33,6 +34,7 @@ pub use binder::Visibility;
 pub use expression_parser::*;
 pub use metadata::*;
 pub use planner::Planner;
+pub use prepared_statement::PreparedStatement;
 pub use plans::ScalarExpr;
 pub use semantic::normalize_identifier;
 pub use semantic::validate_function_arg;
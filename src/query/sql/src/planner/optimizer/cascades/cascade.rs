@@ -50,7 +50,8 @@ impl CascadesOptimizer {
     pub fn create(ctx: Arc<dyn TableContext>) -> Result<Self> {
         let explore_rules = if ctx.get_settings().get_enable_cbo()? {
             let enable_bushy_join = ctx.get_settings().get_enable_bushy_join()? != 0;
-            get_explore_rule_set(enable_bushy_join)
+            let enable_sort_merge_join = ctx.get_settings().get_enable_sort_merge_join()?;
+            get_explore_rule_set(enable_bushy_join, enable_sort_merge_join)
         } else {
             RuleSet::create_with_ids(vec![]).unwrap()
         };
@@ -15,28 +15,31 @@
 use crate::optimizer::RuleID;
 use crate::optimizer::RuleSet;
 
-pub fn get_explore_rule_set(enable_bushy_join: bool) -> RuleSet {
-    if enable_bushy_join {
+pub fn get_explore_rule_set(enable_bushy_join: bool, enable_sort_merge_join: bool) -> RuleSet {
+    let mut rules = if enable_bushy_join {
         join_rule_set_rs_b2()
     } else {
         join_rule_set_rs_l1()
+    };
+    if enable_sort_merge_join {
+        rules.push(RuleID::SortMergeJoin);
     }
+    RuleSet::create_with_ids(rules).unwrap()
 }
 
 /// Get rule set of join order RS-B2, which may generate bushy trees.
 /// Read paper "The Complexity of Transformation-Based Join Enumeration" for more details.
-fn join_rule_set_rs_b2() -> RuleSet {
-    RuleSet::create_with_ids(vec![
+fn join_rule_set_rs_b2() -> Vec<RuleID> {
+    vec![
         RuleID::CommuteJoin,
         RuleID::LeftAssociateJoin,
         RuleID::RightAssociateJoin,
         RuleID::ExchangeJoin,
-    ])
-    .unwrap()
+    ]
 }
 
 /// Get rule set of join order RS-L1, which will only generate left-deep trees.
 /// Read paper "The Complexity of Transformation-Based Join Enumeration" for more details.
-fn join_rule_set_rs_l1() -> RuleSet {
-    RuleSet::create_with_ids(vec![RuleID::CommuteJoinBaseTable, RuleID::LeftExchangeJoin]).unwrap()
+fn join_rule_set_rs_l1() -> Vec<RuleID> {
+    vec![RuleID::CommuteJoinBaseTable, RuleID::LeftExchangeJoin]
 }
@@ -17,11 +17,14 @@ use common_exception::Result;
 
 use super::Cost;
 use super::CostModel;
+use crate::optimizer::group::Group;
 use crate::optimizer::MExpr;
 use crate::optimizer::Memo;
 use crate::plans::Join;
+use crate::plans::JoinAlgorithm;
 use crate::plans::JoinType;
 use crate::plans::RelOperator;
+use crate::plans::ScalarExpr;
 use crate::plans::Scan;
 
 static COST_FACTOR_COMPUTE_PER_ROW: f64 = 1.0;
@@ -30,24 +33,92 @@ static COST_FACTOR_HASH_TABLE_PER_ROW: f64 = 10.0;
 #[derive(Default)]
 pub struct DefaultCostModel;
 
+impl DefaultCostModel {
+    /// Returns true if the rows of the group are already sorted by `keys` in
+    /// ascending order with NULLs last, which is the order of the inputs of a
+    /// sort merge join.
+    fn is_sorted_by(&self, memo: &Memo, group: &Group, keys: &[ScalarExpr]) -> Result<bool> {
+        match group.m_exprs.first() {
+            Some(m_expr) => match &m_expr.plan {
+                RelOperator::Sort(sort) => Ok(keys.len() <= sort.items.len()
+                    && keys
+                        .iter()
+                        .zip(sort.items.iter())
+                        .all(|(key, item)| match key {
+                            ScalarExpr::BoundColumnRef(column) => {
+                                column.column.index == item.index && item.asc && !item.nulls_first
+                            }
+                            _ => false,
+                        })),
+                // The order of the rows is kept by these operators.
+                RelOperator::Filter(_) | RelOperator::EvalScalar(_) | RelOperator::Limit(_) => {
+                    self.is_sorted_by(memo, m_expr.child_group(memo, 0)?, keys)
+                }
+                _ => Ok(false),
+            },
+            None => Ok(false),
+        }
+    }
+
+    fn compute_cost_sort(&self, memo: &Memo, group: &Group, keys: &[ScalarExpr]) -> Result<f64> {
+        if self.is_sorted_by(memo, group, keys)? {
+            // The sort is skipped by the physical plan.
+            return Ok(0.0);
+        }
+        // Sorting holds all the rows in memory like a hash table does, and is
+        // more expensive to compute.
+        let card = group.relational_prop.cardinality;
+        Ok(card * (COST_FACTOR_HASH_TABLE_PER_ROW + card.max(1.0).log2()))
+    }
+
+    fn compute_cost_sort_merge_join(
+        &self,
+        memo: &Memo,
+        m_expr: &MExpr,
+        plan: &Join,
+    ) -> Result<Cost> {
+        let left_group = m_expr.child_group(memo, 0)?;
+        let right_group = m_expr.child_group(memo, 1)?;
+        let left_card = left_group.relational_prop.cardinality;
+        let right_card = right_group.relational_prop.cardinality;
+
+        let cost = self.compute_cost_sort(memo, left_group, &plan.left_conditions)?
+            + self.compute_cost_sort(memo, right_group, &plan.right_conditions)?
+            + (left_card + right_card) * COST_FACTOR_COMPUTE_PER_ROW;
+        Ok(Cost(cost))
+    }
+}
+
 impl CostModel for DefaultCostModel {
     fn compute_cost(&self, memo: &Memo, m_expr: &MExpr) -> Result<Cost> {
-        compute_cost_impl(memo, m_expr)
+        match &m_expr.plan {
+            RelOperator::Join(plan) if plan.algorithm == JoinAlgorithm::SortMerge => {
+                self.compute_cost_sort_merge_join(memo, m_expr, plan)
+            }
+            _ => compute_cost_impl(memo, m_expr),
+        }
     }
 }
 
 fn compute_cost_impl(memo: &Memo, m_expr: &MExpr) -> Result<Cost> {
     match &m_expr.plan {
         RelOperator::Scan(plan) => compute_cost_scan(memo, m_expr, plan),
-        RelOperator::DummyTableScan(_) => Ok(Cost(0.0)),
+        RelOperator::DummyTableScan(_) | RelOperator::RecursiveCteScan(_) => Ok(Cost(0.0)),
+        RelOperator::CteScan(_) => compute_cost_cte_scan(memo, m_expr),
         RelOperator::Join(plan) => compute_cost_join(memo, m_expr, plan),
-        RelOperator::UnionAll(_) => compute_cost_union_all(memo, m_expr),
+        RelOperator::UnionAll(_) | RelOperator::RecursiveUnion(_) => {
+            compute_cost_union_all(memo, m_expr)
+        }
+        RelOperator::MaterializedCte(_) => compute_cost_materialized_cte(memo, m_expr),
+        RelOperator::SetOperation(_) => compute_cost_set_operation(memo, m_expr),
 
         RelOperator::EvalScalar(_)
         | RelOperator::Filter(_)
         | RelOperator::Aggregate(_)
         | RelOperator::Sort(_)
-        | RelOperator::Limit(_) => compute_cost_unary_common_operator(memo, m_expr),
+        | RelOperator::Limit(_)
+        | RelOperator::Window(_)
+        | RelOperator::ProjectSet(_) => compute_cost_unary_common_operator(memo, m_expr),
 
         _ => Err(ErrorCode::Internal("Cannot compute cost from logical plan")),
     }
@@ -62,6 +133,14 @@ fn compute_cost_scan(memo: &Memo, m_expr: &MExpr, _plan: &Scan) -> Result<Cost>
     Ok(Cost(cost))
 }
 
+fn compute_cost_cte_scan(memo: &Memo, m_expr: &MExpr) -> Result<Cost> {
+    // The materialized rows are read from memory, or from the storage if they are spilled.
+    let group = memo.group(m_expr.group_index)?;
+    let prop = &group.relational_prop;
+    let cost = prop.cardinality * COST_FACTOR_COMPUTE_PER_ROW;
+    Ok(Cost(cost))
+}
+
 fn compute_cost_join(memo: &Memo, m_expr: &MExpr, plan: &Join) -> Result<Cost> {
     let build_group = m_expr.child_group(memo, 1)?;
     let probe_group = m_expr.child_group(memo, 0)?;
@@ -97,3 +176,20 @@ fn compute_cost_union_all(memo: &Memo, m_expr: &MExpr) -> Result<Cost> {
     let cost = card * COST_FACTOR_COMPUTE_PER_ROW;
     Ok(Cost(cost))
 }
+
+fn compute_cost_set_operation(memo: &Memo, m_expr: &MExpr) -> Result<Cost> {
+    // The rows of the right child are counted in a hash table, and the rows of
+    // the left child are matched against it.
+    let left_group = m_expr.child_group(memo, 0)?;
+    let right_group = m_expr.child_group(memo, 1)?;
+    let cost = right_group.relational_prop.cardinality * COST_FACTOR_HASH_TABLE_PER_ROW
+        + left_group.relational_prop.cardinality * COST_FACTOR_COMPUTE_PER_ROW;
+    Ok(Cost(cost))
+}
+
+fn compute_cost_materialized_cte(memo: &Memo, m_expr: &MExpr) -> Result<Cost> {
+    // Only the cost of materializing the CTE, the cost of scanning it is counted in `CteScan`.
+    let left_group = m_expr.child_group(memo, 0)?;
+    let cost = left_group.relational_prop.cardinality * COST_FACTOR_COMPUTE_PER_ROW;
+    Ok(Cost(cost))
+}
@@ -42,6 +42,7 @@ use crate::plans::EvalScalar;
 use crate::plans::Filter;
 use crate::plans::FunctionCall;
 use crate::plans::Join;
+use crate::plans::JoinAlgorithm;
 use crate::plans::JoinType;
 use crate::plans::NotExpr;
 use crate::plans::OrExpr;
@@ -198,6 +199,7 @@ impl SubqueryRewriter {
             },
             marker_index: None,
             from_correlated_subquery: true,
+            algorithm: JoinAlgorithm::Hash,
         };
 
         // Rewrite plan to semi-join.
@@ -264,6 +266,7 @@ impl SubqueryRewriter {
                     join_type: JoinType::Single,
                     marker_index: None,
                     from_correlated_subquery: true,
+                    algorithm: JoinAlgorithm::Hash,
                 };
                 let s_expr = SExpr::create_binary(join_plan.into(), left.clone(), flatten_plan);
                 Ok((s_expr, UnnestResult::SingleJoin))
@@ -301,6 +304,7 @@ impl SubqueryRewriter {
                     join_type: JoinType::RightMark,
                     marker_index: Some(marker_index),
                     from_correlated_subquery: true,
+                    algorithm: JoinAlgorithm::Hash,
                 };
                 let s_expr = SExpr::create_binary(join_plan.into(), left.clone(), flatten_plan);
                 Ok((s_expr, UnnestResult::MarkJoin { marker_index }))
@@ -356,6 +360,7 @@ impl SubqueryRewriter {
                     join_type: JoinType::RightMark,
                     marker_index: Some(marker_index),
                     from_correlated_subquery: true,
+                    algorithm: JoinAlgorithm::Hash,
                 }
                 .into();
                 Ok((
@@ -429,6 +434,7 @@ impl SubqueryRewriter {
                 join_type: JoinType::Cross,
                 marker_index: None,
                 from_correlated_subquery: false,
+                algorithm: JoinAlgorithm::Hash,
             }
             .into();
             return Ok(SExpr::create_binary(cross_join, logical_get, plan.clone()));
@@ -542,6 +548,7 @@ impl SubqueryRewriter {
                         join_type: join.join_type.clone(),
                         marker_index: join.marker_index,
                         from_correlated_subquery: false,
+                        algorithm: JoinAlgorithm::Hash,
                     }
                     .into(),
                     left_flatten_plan,
@@ -562,6 +569,7 @@ impl SubqueryRewriter {
                     flatten_info,
                     need_cross_join,
                 )?;
+                let mut grouping_sets = aggregate.grouping_sets.clone();
                 let mut group_items = Vec::with_capacity(aggregate.group_items.len());
                 for item in aggregate.group_items.iter() {
                     let scalar = self.flatten_scalar(&item.scalar, correlated_columns)?;
@@ -597,6 +605,12 @@ impl SubqueryRewriter {
                         }),
                         index: *derived_column,
                     });
+                    // The derived columns should be grouped in all the grouping sets.
+                    if let Some(grouping_sets) = &mut grouping_sets {
+                        for set in grouping_sets.sets.iter_mut() {
+                            set.push(*derived_column);
+                        }
+                    }
                 }
                 let mut agg_items = Vec::with_capacity(aggregate.aggregate_functions.len());
                 for item in aggregate.aggregate_functions.iter() {
@@ -621,6 +635,7 @@ impl SubqueryRewriter {
                         aggregate_functions: agg_items,
                         from_distinct: aggregate.from_distinct,
                         limit: aggregate.limit,
+                        grouping_sets,
                     }
                     .into(),
                     flatten_plan,
@@ -150,6 +150,7 @@ impl UnusedColumnPruner {
                         from_distinct: p.from_distinct,
                         mode: p.mode,
                         limit: p.limit,
+                        grouping_sets: p.grouping_sets.clone(),
                     }),
                     Self::keep_required_columns(expr.child(0)?, required)?,
                 ))
@@ -184,7 +185,80 @@ impl UnusedColumnPruner {
                 ))
             }
 
-            RelOperator::DummyTableScan(_) => Ok(expr.clone()),
+            RelOperator::Window(p) => {
+                if !required.contains(&p.index) {
+                    // The window function is not used by parent plan
+                    return Self::keep_required_columns(expr.child(0)?, required);
+                }
+                let mut used = p.used_columns()?;
+                used.extend(required);
+                used.remove(&p.index);
+                Ok(SExpr::create_unary(
+                    RelOperator::Window(p.clone()),
+                    Self::keep_required_columns(expr.child(0)?, used)?,
+                ))
+            }
+
+            RelOperator::RecursiveUnion(p) => {
+                // The recursive term reads the rows of the previous iteration in the
+                // layout of the union, so all the unioned columns must be kept.
+                let left_used = p.pairs.iter().fold(required, |mut acc, v| {
+                    acc.insert(v.0);
+                    acc
+                });
+                let right_used = p.pairs.iter().map(|v| v.1).collect();
+                Ok(SExpr::create_binary(
+                    RelOperator::RecursiveUnion(p.clone()),
+                    Self::keep_required_columns(expr.child(0)?, left_used)?,
+                    Self::keep_required_columns(expr.child(1)?, right_used)?,
+                ))
+            }
+
+            RelOperator::MaterializedCte(p) => {
+                // The whole result of the CTE is materialized and shared by all the
+                // references, so all the columns of the CTE must be kept.
+                let left_used = p.columns.iter().cloned().collect();
+                Ok(SExpr::create_binary(
+                    RelOperator::MaterializedCte(p.clone()),
+                    Self::keep_required_columns(expr.child(0)?, left_used)?,
+                    Self::keep_required_columns(expr.child(1)?, required)?,
+                ))
+            }
+
+            RelOperator::SetOperation(p) => {
+                // Rows are compared on all the paired columns, so they must be kept
+                // even if they are not required by the parent plan.
+                let left_used = p.pairs.iter().fold(required, |mut acc, v| {
+                    acc.insert(v.0);
+                    acc
+                });
+                let right_used = p.pairs.iter().map(|v| v.1).collect();
+                Ok(SExpr::create_binary(
+                    RelOperator::SetOperation(p.clone()),
+                    Self::keep_required_columns(expr.child(0)?, left_used)?,
+                    Self::keep_required_columns(expr.child(1)?, right_used)?,
+                ))
+            }
+
+            RelOperator::ProjectSet(p) => {
+                // All the output columns of the function are produced together, so
+                // only the arguments decide which input columns are needed.
+                let mut used = required;
+                for arg in p.args.iter() {
+                    used.extend(arg.used_columns());
+                }
+                for column in p.columns.iter() {
+                    used.remove(column);
+                }
+                Ok(SExpr::create_unary(
+                    RelOperator::ProjectSet(p.clone()),
+                    Self::keep_required_columns(expr.child(0)?, used)?,
+                ))
+            }
+
+            RelOperator::DummyTableScan(_)
+            | RelOperator::RecursiveCteScan(_)
+            | RelOperator::CteScan(_) => Ok(expr.clone()),
 
             _ => Err(ErrorCode::Internal(
                 "Attempting to prune columns of a physical plan is not allowed",
@@ -40,6 +40,7 @@ use crate::plans::ConstantExpr;
 use crate::plans::Filter;
 use crate::plans::FunctionCall;
 use crate::plans::Join;
+use crate::plans::JoinAlgorithm;
 use crate::plans::JoinType;
 use crate::plans::Limit;
 use crate::plans::NotExpr;
@@ -119,18 +120,30 @@ impl SubqueryRewriter {
                 Ok(SExpr::create_unary(plan.into(), input))
             }
 
-            RelOperator::Join(_) | RelOperator::UnionAll(_) => Ok(SExpr::create_binary(
-                s_expr.plan().clone(),
-                self.rewrite(s_expr.child(0)?)?,
-                self.rewrite(s_expr.child(1)?)?,
-            )),
+            RelOperator::Join(_)
+            | RelOperator::UnionAll(_)
+            | RelOperator::RecursiveUnion(_)
+            | RelOperator::MaterializedCte(_)
+            | RelOperator::SetOperation(_) => {
+                Ok(SExpr::create_binary(
+                    s_expr.plan().clone(),
+                    self.rewrite(s_expr.child(0)?)?,
+                    self.rewrite(s_expr.child(1)?)?,
+                ))
+            }
 
-            RelOperator::Limit(_) | RelOperator::Sort(_) => Ok(SExpr::create_unary(
+            RelOperator::Limit(_)
+            | RelOperator::Sort(_)
+            | RelOperator::Window(_)
+            | RelOperator::ProjectSet(_) => Ok(SExpr::create_unary(
                 s_expr.plan().clone(),
                 self.rewrite(s_expr.child(0)?)?,
             )),
 
-            RelOperator::DummyTableScan(_) | RelOperator::Scan(_) => Ok(s_expr.clone()),
+            RelOperator::DummyTableScan(_)
+            | RelOperator::Scan(_)
+            | RelOperator::RecursiveCteScan(_)
+            | RelOperator::CteScan(_) => Ok(s_expr.clone()),
 
             _ => Err(ErrorCode::Internal("Invalid plan type")),
         }
@@ -209,6 +222,8 @@ impl SubqueryRewriter {
 
             ScalarExpr::AggregateFunction(_) => Ok((scalar.clone(), s_expr.clone())),
 
+            ScalarExpr::WindowFunction(_) => Ok((scalar.clone(), s_expr.clone())),
+
             ScalarExpr::FunctionCall(func) => {
                 let mut args = vec![];
                 let mut s_expr = s_expr.clone();
@@ -378,6 +393,7 @@ impl SubqueryRewriter {
                     join_type: JoinType::Single,
                     marker_index: None,
                     from_correlated_subquery: false,
+                    algorithm: JoinAlgorithm::Hash,
                 }
                 .into();
                 let s_expr =
@@ -419,6 +435,7 @@ impl SubqueryRewriter {
                     from_distinct: false,
                     mode: AggregateMode::Initial,
                     limit: None,
+                    grouping_sets: None,
                 };
 
                 let compare = ComparisonExpr {
@@ -467,6 +484,7 @@ impl SubqueryRewriter {
                     join_type: JoinType::Cross,
                     marker_index: None,
                     from_correlated_subquery: false,
+                    algorithm: JoinAlgorithm::Hash,
                 }
                 .into();
                 Ok((
@@ -529,6 +547,7 @@ impl SubqueryRewriter {
                     join_type: JoinType::RightMark,
                     marker_index: Some(marker_index),
                     from_correlated_subquery: false,
+                    algorithm: JoinAlgorithm::Hash,
                 }
                 .into();
                 let s_expr =
@@ -26,6 +26,7 @@ use super::Memo;
 use crate::optimizer::cascades::CascadesOptimizer;
 use crate::optimizer::distributed::optimize_distributed_query;
 use crate::optimizer::heuristic::RuleList;
+use crate::optimizer::util::contains_local_cte;
 use crate::optimizer::util::contains_local_table_scan;
 use crate::optimizer::HeuristicOptimizer;
 use crate::optimizer::SExpr;
@@ -134,6 +135,11 @@ pub fn optimize(
                 into_table => into_table,
             })))
         }
+        Plan::MergeInto(mut plan) => {
+            // Make sure the source query has been optimized.
+            plan.source = Box::new(optimize(ctx, opt_ctx, *plan.source)?);
+            Ok(Plan::MergeInto(plan))
+        }
         // Passthrough statements
         _ => Ok(plan),
     }
@@ -149,6 +155,7 @@ pub fn optimize_query(
     let rules = RuleList::create(DEFAULT_REWRITE_RULES.clone(), Some(metadata.clone()))?;
 
     let contains_local_table_scan = contains_local_table_scan(&s_expr, &metadata);
+    let contains_local_cte = contains_local_cte(&s_expr);
 
     let mut heuristic = HeuristicOptimizer::new(ctx.clone(), bind_context, metadata, rules);
     let mut result = heuristic.optimize(s_expr)?;
@@ -157,9 +164,11 @@ pub fn optimize_query(
     result = cascades.optimize(result)?;
 
     // So far, we don't have ability to execute distributed query
-    // with reading data from local tales(e.g. system tables).
-    let enable_distributed_query =
-        opt_ctx.config.enable_distributed_optimization && !contains_local_table_scan;
+    // with reading data from local tales(e.g. system tables),
+    // or with recursive or materialized common table expressions.
+    let enable_distributed_query = opt_ctx.config.enable_distributed_optimization
+        && !contains_local_table_scan
+        && !contains_local_cte;
     if enable_distributed_query {
         result = optimize_distributed_query(ctx.clone(), &result)?;
     }
@@ -149,6 +149,8 @@ fn replace_column(scalar: &mut ScalarExpr, col_to_scalar: &HashMap<&IndexType, &
         ScalarExpr::CastExpr(expr) => {
             replace_column(&mut expr.argument, col_to_scalar);
         }
-        ScalarExpr::ConstantExpr(_) | ScalarExpr::SubqueryExpr(_) => {}
+        ScalarExpr::ConstantExpr(_)
+        | ScalarExpr::SubqueryExpr(_)
+        | ScalarExpr::WindowFunction(_) => {}
     }
 }
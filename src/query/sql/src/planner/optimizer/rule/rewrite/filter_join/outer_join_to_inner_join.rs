@@ -383,6 +383,8 @@ fn remove_column_nullable(
                 target_type: expr.target_type.clone(),
             })
         }
-        ScalarExpr::ConstantExpr(_) | ScalarExpr::SubqueryExpr(_) => scalar_expr.clone(),
+        ScalarExpr::ConstantExpr(_)
+        | ScalarExpr::SubqueryExpr(_)
+        | ScalarExpr::WindowFunction(_) => scalar_expr.clone(),
     })
 }
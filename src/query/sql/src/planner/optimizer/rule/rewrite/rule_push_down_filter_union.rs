@@ -202,5 +202,8 @@ fn replace_column_binding(
         ScalarExpr::SubqueryExpr(_) => Err(ErrorCode::Unimplemented(
             "replace_column_binding: don't support subquery",
         )),
+        ScalarExpr::WindowFunction(_) => Err(ErrorCode::Unimplemented(
+            "replace_column_binding: don't support window function",
+        )),
     }
 }
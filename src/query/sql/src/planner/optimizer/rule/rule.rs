@@ -61,6 +61,7 @@ pub enum RuleID {
     LeftExchangeJoin,
     RightExchangeJoin,
     ExchangeJoin,
+    SortMergeJoin,
 }
 
 impl Display for RuleID {
@@ -93,6 +94,7 @@ impl Display for RuleID {
             RuleID::LeftExchangeJoin => write!(f, "LeftExchangeJoin"),
             RuleID::RightExchangeJoin => write!(f, "RightExchangeJoin"),
             RuleID::ExchangeJoin => write!(f, "ExchangeJoin"),
+            RuleID::SortMergeJoin => write!(f, "SortMergeJoin"),
         }
     }
 }
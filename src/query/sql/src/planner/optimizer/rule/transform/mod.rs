@@ -19,6 +19,7 @@ mod rule_left_associate_join;
 mod rule_left_exchange_join;
 mod rule_right_associate_join;
 mod rule_right_exchange_join;
+mod rule_sort_merge_join;
 mod util;
 
 pub use rule_commute_join::RuleCommuteJoin;
@@ -28,3 +29,4 @@ pub use rule_left_associate_join::RuleLeftAssociateJoin;
 pub use rule_left_exchange_join::RuleLeftExchangeJoin;
 pub use rule_right_associate_join::RuleRightAssociateJoin;
 pub use rule_right_exchange_join::RuleRightExchangeJoin;
+pub use rule_sort_merge_join::RuleSortMergeJoin;
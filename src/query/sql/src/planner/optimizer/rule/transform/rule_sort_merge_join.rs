@@ -0,0 +1,124 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+use common_expression::types::DataType;
+
+use crate::optimizer::rule::Rule;
+use crate::optimizer::rule::TransformResult;
+use crate::optimizer::RuleID;
+use crate::optimizer::SExpr;
+use crate::plans::Join;
+use crate::plans::JoinAlgorithm;
+use crate::plans::JoinType;
+use crate::plans::PatternPlan;
+use crate::plans::RelOp;
+use crate::plans::ScalarExpr;
+
+/// Rule to generate a sort merge join alternative of an inner equi-join, the
+/// cost model decides which one of hash join and sort merge join is used.
+pub struct RuleSortMergeJoin {
+    id: RuleID,
+    pattern: SExpr,
+}
+
+impl RuleSortMergeJoin {
+    pub fn new() -> Self {
+        Self {
+            id: RuleID::SortMergeJoin,
+
+            // LogicalJoin
+            // | \
+            // *  *
+            pattern: SExpr::create_binary(
+                PatternPlan {
+                    plan_type: RelOp::Join,
+                }
+                .into(),
+                SExpr::create_pattern_leaf(),
+                SExpr::create_pattern_leaf(),
+            ),
+        }
+    }
+}
+
+impl Rule for RuleSortMergeJoin {
+    fn id(&self) -> RuleID {
+        self.id
+    }
+
+    fn apply(&self, s_expr: &SExpr, state: &mut TransformResult) -> Result<()> {
+        let mut join: Join = s_expr.plan().clone().try_into()?;
+        if join.algorithm != JoinAlgorithm::Hash
+            || join.join_type != JoinType::Inner
+            || join.left_conditions.is_empty()
+            || join.marker_index.is_some()
+            || join.from_correlated_subquery
+        {
+            return Ok(());
+        }
+
+        // The inputs are sorted by the join keys, so the keys must be columns of
+        // the same orderable type on both sides.
+        for (left, right) in join
+            .left_conditions
+            .iter()
+            .zip(join.right_conditions.iter())
+        {
+            match (left, right) {
+                (ScalarExpr::BoundColumnRef(left), ScalarExpr::BoundColumnRef(right)) => {
+                    let data_type = left.column.data_type.remove_nullable();
+                    if data_type != right.column.data_type.remove_nullable()
+                        || !matches!(
+                            data_type,
+                            DataType::Boolean
+                                | DataType::Number(_)
+                                | DataType::String
+                                | DataType::Date
+                                | DataType::Timestamp
+                        )
+                    {
+                        return Ok(());
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+
+        join.algorithm = JoinAlgorithm::SortMerge;
+        let mut result = SExpr::create_binary(
+            join.into(),
+            s_expr.child(0)?.clone(),
+            s_expr.child(1)?.clone(),
+        );
+
+        // The join orders are explored with the hash join alternatives
+        result.set_applied_rule(&RuleID::SortMergeJoin);
+        result.set_applied_rule(&RuleID::CommuteJoin);
+        result.set_applied_rule(&RuleID::CommuteJoinBaseTable);
+        result.set_applied_rule(&RuleID::LeftAssociateJoin);
+        result.set_applied_rule(&RuleID::LeftExchangeJoin);
+        result.set_applied_rule(&RuleID::RightAssociateJoin);
+        result.set_applied_rule(&RuleID::RightExchangeJoin);
+        result.set_applied_rule(&RuleID::ExchangeJoin);
+
+        state.add_result(result);
+
+        Ok(())
+    }
+
+    fn pattern(&self) -> &SExpr {
+        &self.pattern
+    }
+}
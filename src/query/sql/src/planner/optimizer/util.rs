@@ -28,3 +28,12 @@ pub fn contains_local_table_scan(s_expr: &SExpr, metadata: &MetadataRef) -> bool
             false
         }
 }
+
+/// Check if a query contains a recursive or materialized common table expression,
+/// which can only be executed on the local node.
+pub fn contains_local_cte(s_expr: &SExpr) -> bool {
+    matches!(
+        s_expr.plan(),
+        RelOperator::RecursiveUnion(_) | RelOperator::MaterializedCte(_)
+    ) || s_expr.children().iter().any(contains_local_cte)
+}
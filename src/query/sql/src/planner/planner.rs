@@ -28,6 +28,7 @@ use common_catalog::table_context::TableContext;
 use common_exception::Result;
 use parking_lot::RwLock;
 
+use super::semantic::bind_query_params;
 use super::semantic::DistinctToGroupBy;
 use crate::optimizer::optimize;
 use crate::optimizer::OptimizerConfig;
@@ -82,26 +83,11 @@ impl Planner {
                 // Step 2: Parse the SQL.
                 let backtrace = Backtrace::new();
                 let (mut stmt, format) = parse_sql(&tokens, sql_dialect, &backtrace)?;
-                self.replace_stmt(&mut stmt);
-
-                // Step 3: Bind AST with catalog, and generate a pure logical SExpr
-                let metadata = Arc::new(RwLock::new(Metadata::default()));
-                let name_resolution_ctx = NameResolutionContext::try_from(settings.as_ref())?;
-                let binder = Binder::new(
-                    self.ctx.clone(),
-                    CatalogManager::instance(),
-                    name_resolution_ctx,
-                    metadata.clone(),
-                );
-                let plan = binder.bind(&stmt).await?;
-
-                // Step 4: Optimize the SExpr with optimizers, and generate optimized physical SExpr
-                let opt_ctx = Arc::new(OptimizerContext::new(OptimizerConfig {
-                    enable_distributed_optimization: !self.ctx.get_cluster().is_empty(),
-                }));
-
-                let optimized_plan = optimize(self.ctx.clone(), opt_ctx, plan)?;
-                Ok((optimized_plan, metadata.clone(), format))
+                if let Some(params) = self.ctx.get_query_params() {
+                    bind_query_params(&mut stmt, sql, sql_dialect, &params)?;
+                }
+                let (plan, metadata) = self.plan_stmt(stmt).await?;
+                Ok((plan, metadata, format))
             }
             .await;
 
@@ -132,6 +118,31 @@ impl Planner {
         }
     }
 
+    /// Plan the parsed statement, e.g. a prepared statement with its parameters bound.
+    pub async fn plan_stmt(&mut self, mut stmt: Statement) -> Result<(Plan, MetadataRef)> {
+        let settings = self.ctx.get_settings();
+        self.replace_stmt(&mut stmt);
+
+        // Step 3: Bind AST with catalog, and generate a pure logical SExpr
+        let metadata = Arc::new(RwLock::new(Metadata::default()));
+        let name_resolution_ctx = NameResolutionContext::try_from(settings.as_ref())?;
+        let binder = Binder::new(
+            self.ctx.clone(),
+            CatalogManager::instance(),
+            name_resolution_ctx,
+            metadata.clone(),
+        );
+        let plan = binder.bind(&stmt).await?;
+
+        // Step 4: Optimize the SExpr with optimizers, and generate optimized physical SExpr
+        let opt_ctx = Arc::new(OptimizerContext::new(OptimizerConfig {
+            enable_distributed_optimization: !self.ctx.get_cluster().is_empty(),
+        }));
+
+        let optimized_plan = optimize(self.ctx.clone(), opt_ctx, plan)?;
+        Ok((optimized_plan, metadata))
+    }
+
     fn add_max_rows_limit(&self, statement: &mut Statement) {
         let max_rows = self.ctx.get_settings().get_max_result_rows().unwrap();
         if max_rows == 0 {
@@ -27,6 +27,7 @@ use crate::optimizer::Statistics;
 use crate::plans::Operator;
 use crate::plans::RelOp;
 use crate::plans::ScalarItem;
+use crate::IndexType;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)]
 pub enum AggregateMode {
@@ -48,6 +49,17 @@ pub struct Aggregate {
     // True if the plan is generated from distinct, else the plan is a normal aggregate;
     pub from_distinct: bool,
     pub limit: Option<usize>,
+    // Grouping sets of the aggregation, such as: group by rollup(col1, col2);
+    pub grouping_sets: Option<GroupingSets>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GroupingSets {
+    /// Index of the virtual column `_grouping_id`, which identifies
+    /// the grouping set of each row.
+    pub grouping_id_index: IndexType,
+    /// Each grouping set is a list of column indices of group items.
+    pub sets: Vec<Vec<IndexType>>,
 }
 
 impl Aggregate {
@@ -63,6 +75,14 @@ impl Aggregate {
         }
         Ok(used_columns)
     }
+
+    /// Check if the aggregation has an empty grouping set `()`, which
+    /// aggregates all the input rows like a scalar aggregation.
+    pub fn has_empty_grouping_set(&self) -> bool {
+        self.grouping_sets.as_ref().map_or(false, |grouping_sets| {
+            grouping_sets.sets.iter().any(|set| set.is_empty())
+        })
+    }
 }
 
 impl Operator for Aggregate {
@@ -90,8 +110,9 @@ impl Operator for Aggregate {
 
         match self.mode {
             AggregateMode::Partial => {
-                if self.group_items.is_empty() {
-                    // Scalar aggregation
+                if self.group_items.is_empty() || self.has_empty_grouping_set() {
+                    // Scalar aggregation, or the empty grouping set must be
+                    // finalized on a single node to emit a row for empty input
                     required.distribution = Distribution::Any;
                 } else {
                     // Group aggregation, enforce `Hash` distribution
@@ -101,8 +122,9 @@ impl Operator for Aggregate {
             }
 
             AggregateMode::Final => {
-                if self.group_items.is_empty() {
-                    // Scalar aggregation
+                if self.group_items.is_empty() || self.has_empty_grouping_set() {
+                    // Scalar aggregation, or the empty grouping set must be
+                    // finalized on a single node to emit a row for empty input
                     required.distribution = Distribution::Serial;
                 } else {
                     // The distribution should have been derived by partial aggregation
@@ -126,6 +148,9 @@ impl Operator for Aggregate {
         for agg in self.aggregate_functions.iter() {
             output_columns.insert(agg.index);
         }
+        if let Some(grouping_sets) = &self.grouping_sets {
+            output_columns.insert(grouping_sets.grouping_id_index);
+        }
 
         // Derive outer columns
         let outer_columns = input_prop
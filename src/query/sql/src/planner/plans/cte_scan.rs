@@ -0,0 +1,83 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_catalog::table_context::TableContext;
+use common_exception::Result;
+use common_expression::types::DataType;
+
+use crate::optimizer::ColumnSet;
+use crate::optimizer::Distribution;
+use crate::optimizer::PhysicalProperty;
+use crate::optimizer::RelExpr;
+use crate::optimizer::RelationalProperty;
+use crate::optimizer::RequiredProperty;
+use crate::optimizer::Statistics;
+use crate::plans::Operator;
+use crate::plans::RelOp;
+use crate::IndexType;
+
+/// Scan the result of a materialized common table expression, it can only appear
+/// in the right child of the `MaterializedCte` of the same `cte_idx`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CteScan {
+    pub cte_idx: IndexType,
+    // Output columns and their types, in the order of the columns of the CTE
+    pub fields: Vec<(IndexType, DataType)>,
+    // Estimated number of rows of the CTE
+    pub cardinality: u64,
+}
+
+impl CteScan {
+    pub fn used_columns(&self) -> Result<ColumnSet> {
+        Ok(ColumnSet::new())
+    }
+}
+
+impl Operator for CteScan {
+    fn rel_op(&self) -> RelOp {
+        RelOp::CteScan
+    }
+
+    fn derive_relational_prop(&self, _rel_expr: &RelExpr) -> Result<RelationalProperty> {
+        Ok(RelationalProperty {
+            output_columns: self.fields.iter().map(|(index, _)| *index).collect(),
+            outer_columns: ColumnSet::new(),
+            used_columns: ColumnSet::new(),
+            cardinality: self.cardinality as f64,
+            statistics: Statistics {
+                precise_cardinality: None,
+                column_stats: Default::default(),
+                is_accurate: false,
+            },
+        })
+    }
+
+    fn derive_physical_prop(&self, _rel_expr: &RelExpr) -> Result<PhysicalProperty> {
+        Ok(PhysicalProperty {
+            distribution: Distribution::Serial,
+        })
+    }
+
+    fn compute_required_prop_child(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        _rel_expr: &RelExpr,
+        _child_index: usize,
+        required: &RequiredProperty,
+    ) -> Result<RequiredProperty> {
+        Ok(required.clone())
+    }
+}
@@ -16,6 +16,7 @@ mod catalog;
 mod database;
 mod file_format;
 mod stage;
+mod stream;
 mod table;
 mod udf;
 mod view;
@@ -25,6 +26,7 @@ pub use catalog::*;
 pub use database::*;
 pub use file_format::*;
 pub use stage::*;
+pub use stream::*;
 pub use table::*;
 pub use udf::*;
 pub use view::*;
@@ -49,6 +49,7 @@ pub struct CreateTablePlanV2 {
     pub field_comments: Vec<String>,
     pub cluster_key: Option<String>,
     pub as_select: Option<Box<Plan>>,
+    pub clone_source: Option<CloneTableSource>,
 }
 
 impl CreateTablePlanV2 {
@@ -57,6 +58,15 @@ impl CreateTablePlanV2 {
     }
 }
 
+/// The table to be cloned by `CREATE TABLE ... CLONE ...`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CloneTableSource {
+    pub catalog: String,
+    pub database: String,
+    pub table: String,
+    pub point: Option<NavigationPoint>,
+}
+
 /// Desc.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DescribeTablePlan {
@@ -125,6 +125,18 @@ impl Display for JoinType {
     }
 }
 
+/// The algorithm used to execute a join.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub enum JoinAlgorithm {
+    /// Build a hash table with the right child and probe it with the left child.
+    #[default]
+    Hash,
+    /// Sort both children by the join keys and merge them. Only the rows with
+    /// the same join key are held in memory, it's chosen by the optimizer for
+    /// inner equi-joins whose children are already sorted by the join keys.
+    SortMerge,
+}
+
 /// Join operator. We will choose hash join by default.
 /// In the case that using hash join, the right child
 /// is always the build side, and the left child is always
@@ -138,6 +150,7 @@ pub struct Join {
     // marker_index is for MarkJoin only.
     pub marker_index: Option<IndexType>,
     pub from_correlated_subquery: bool,
+    pub algorithm: JoinAlgorithm,
 }
 
 impl Default for Join {
@@ -149,6 +162,7 @@ impl Default for Join {
             join_type: JoinType::Cross,
             marker_index: Default::default(),
             from_correlated_subquery: Default::default(),
+            algorithm: Default::default(),
         }
     }
 }
@@ -0,0 +1,67 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_expression::DataSchema;
+use common_expression::DataSchemaRef;
+use common_expression::FieldIndex;
+
+use crate::plans::Plan;
+use crate::plans::ScalarExpr;
+use crate::IndexType;
+
+#[derive(Clone, Debug)]
+pub struct MergeIntoPlan {
+    pub catalog: String,
+    pub database: String,
+    pub table: String,
+    /// The query producing the source rows, its output columns are the columns
+    /// of the source bind context.
+    pub source: Box<Plan>,
+    /// Column index of each field of the target table, in schema order.
+    pub target_columns: Vec<IndexType>,
+    /// Equi conditions of the ON clause, both sides are of the same type.
+    pub target_keys: Vec<ScalarExpr>,
+    pub source_keys: Vec<ScalarExpr>,
+    /// The remaining conditions of the ON clause.
+    pub join_filters: Vec<ScalarExpr>,
+    pub matched_clauses: Vec<MatchedClause>,
+    pub not_matched_clauses: Vec<NotMatchedClause>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MatchedClause {
+    pub condition: Option<ScalarExpr>,
+    pub operation: MatchedOperation,
+}
+
+#[derive(Clone, Debug)]
+pub enum MatchedOperation {
+    Update(Vec<(FieldIndex, ScalarExpr)>),
+    Delete,
+}
+
+#[derive(Clone, Debug)]
+pub struct NotMatchedClause {
+    pub condition: Option<ScalarExpr>,
+    /// The value of each field of the target table, `None` means the default value.
+    pub values: Vec<Option<ScalarExpr>>,
+}
+
+impl MergeIntoPlan {
+    pub fn schema(&self) -> DataSchemaRef {
+        Arc::new(DataSchema::empty())
+    }
+}
@@ -15,6 +15,7 @@
 mod aggregate;
 mod call;
 mod copy_v2;
+mod cte_scan;
 mod ddl;
 mod delete;
 mod dummy_table_scan;
@@ -26,23 +27,31 @@ mod join;
 mod kill;
 mod limit;
 mod list;
+mod materialized_cte;
+mod merge_into;
 mod operator;
 mod pattern;
 mod plan;
 mod presign;
+mod project_set;
 mod recluster_table;
+mod recursive_cte_scan;
+mod recursive_union;
 mod revert_table;
 mod scalar;
 mod scan;
+mod set_operation;
 mod setting;
 pub mod share;
 mod sort;
 mod union_all;
 mod update;
+mod window;
 
 pub use aggregate::*;
 pub use call::CallPlan;
 pub use copy_v2::*;
+pub use cte_scan::CteScan;
 pub use ddl::*;
 pub use delete::DeletePlan;
 pub use dummy_table_scan::DummyTableScan;
@@ -55,18 +64,25 @@ pub use join::*;
 pub use kill::KillPlan;
 pub use limit::*;
 pub use list::ListPlan;
+pub use materialized_cte::MaterializedCte;
+pub use merge_into::*;
 pub use operator::*;
 pub use pattern::PatternPlan;
 pub use plan::Plan::*;
 pub use plan::RewriteKind::*;
 pub use plan::*;
 pub use presign::*;
+pub use project_set::*;
 pub use recluster_table::ReclusterTablePlan;
+pub use recursive_cte_scan::RecursiveCteScan;
+pub use recursive_union::RecursiveUnion;
 pub use revert_table::RevertTablePlan;
 pub use scalar::*;
 pub use scan::*;
+pub use set_operation::*;
 pub use setting::*;
 pub use share::*;
 pub use sort::*;
 pub use union_all::UnionAll;
 pub use update::UpdatePlan;
+pub use window::*;
@@ -19,15 +19,22 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 
 use super::aggregate::Aggregate;
+use super::cte_scan::CteScan;
 use super::dummy_table_scan::DummyTableScan;
 use super::eval_scalar::EvalScalar;
 use super::filter::Filter;
 use super::join::Join;
 use super::limit::Limit;
+use super::materialized_cte::MaterializedCte;
 use super::pattern::PatternPlan;
+use super::project_set::ProjectSet;
+use super::recursive_cte_scan::RecursiveCteScan;
+use super::recursive_union::RecursiveUnion;
 use super::scan::Scan;
+use super::set_operation::SetOperation;
 use super::sort::Sort;
 use super::union_all::UnionAll;
+use super::window::Window;
 use crate::optimizer::PhysicalProperty;
 use crate::optimizer::RelExpr;
 use crate::optimizer::RelationalProperty;
@@ -67,6 +74,13 @@ pub enum RelOp {
     Exchange,
     UnionAll,
     DummyTableScan,
+    Window,
+    RecursiveUnion,
+    RecursiveCteScan,
+    MaterializedCte,
+    CteScan,
+    SetOperation,
+    ProjectSet,
 
     // Pattern
     Pattern,
@@ -85,6 +99,13 @@ pub enum RelOperator {
     Exchange(Exchange),
     UnionAll(UnionAll),
     DummyTableScan(DummyTableScan),
+    Window(Window),
+    RecursiveUnion(RecursiveUnion),
+    RecursiveCteScan(RecursiveCteScan),
+    MaterializedCte(MaterializedCte),
+    CteScan(CteScan),
+    SetOperation(SetOperation),
+    ProjectSet(ProjectSet),
 
     Pattern(PatternPlan),
 }
@@ -103,6 +124,13 @@ impl Operator for RelOperator {
             RelOperator::Exchange(rel_op) => rel_op.rel_op(),
             RelOperator::UnionAll(rel_op) => rel_op.rel_op(),
             RelOperator::DummyTableScan(rel_op) => rel_op.rel_op(),
+            RelOperator::Window(rel_op) => rel_op.rel_op(),
+            RelOperator::RecursiveUnion(rel_op) => rel_op.rel_op(),
+            RelOperator::RecursiveCteScan(rel_op) => rel_op.rel_op(),
+            RelOperator::MaterializedCte(rel_op) => rel_op.rel_op(),
+            RelOperator::CteScan(rel_op) => rel_op.rel_op(),
+            RelOperator::SetOperation(rel_op) => rel_op.rel_op(),
+            RelOperator::ProjectSet(rel_op) => rel_op.rel_op(),
         }
     }
 
@@ -119,6 +147,13 @@ impl Operator for RelOperator {
             RelOperator::Exchange(rel_op) => rel_op.derive_relational_prop(rel_expr),
             RelOperator::UnionAll(rel_op) => rel_op.derive_relational_prop(rel_expr),
             RelOperator::DummyTableScan(rel_op) => rel_op.derive_relational_prop(rel_expr),
+            RelOperator::Window(rel_op) => rel_op.derive_relational_prop(rel_expr),
+            RelOperator::RecursiveUnion(rel_op) => rel_op.derive_relational_prop(rel_expr),
+            RelOperator::RecursiveCteScan(rel_op) => rel_op.derive_relational_prop(rel_expr),
+            RelOperator::MaterializedCte(rel_op) => rel_op.derive_relational_prop(rel_expr),
+            RelOperator::CteScan(rel_op) => rel_op.derive_relational_prop(rel_expr),
+            RelOperator::SetOperation(rel_op) => rel_op.derive_relational_prop(rel_expr),
+            RelOperator::ProjectSet(rel_op) => rel_op.derive_relational_prop(rel_expr),
         }
     }
 
@@ -135,6 +170,13 @@ impl Operator for RelOperator {
             RelOperator::Exchange(rel_op) => rel_op.derive_physical_prop(rel_expr),
             RelOperator::UnionAll(rel_op) => rel_op.derive_physical_prop(rel_expr),
             RelOperator::DummyTableScan(rel_op) => rel_op.derive_physical_prop(rel_expr),
+            RelOperator::Window(rel_op) => rel_op.derive_physical_prop(rel_expr),
+            RelOperator::RecursiveUnion(rel_op) => rel_op.derive_physical_prop(rel_expr),
+            RelOperator::RecursiveCteScan(rel_op) => rel_op.derive_physical_prop(rel_expr),
+            RelOperator::MaterializedCte(rel_op) => rel_op.derive_physical_prop(rel_expr),
+            RelOperator::CteScan(rel_op) => rel_op.derive_physical_prop(rel_expr),
+            RelOperator::SetOperation(rel_op) => rel_op.derive_physical_prop(rel_expr),
+            RelOperator::ProjectSet(rel_op) => rel_op.derive_physical_prop(rel_expr),
         }
     }
 
@@ -179,6 +221,27 @@ impl Operator for RelOperator {
             RelOperator::DummyTableScan(rel_op) => {
                 rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
             }
+            RelOperator::Window(rel_op) => {
+                rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
+            }
+            RelOperator::RecursiveUnion(rel_op) => {
+                rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
+            }
+            RelOperator::RecursiveCteScan(rel_op) => {
+                rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
+            }
+            RelOperator::MaterializedCte(rel_op) => {
+                rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
+            }
+            RelOperator::CteScan(rel_op) => {
+                rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
+            }
+            RelOperator::SetOperation(rel_op) => {
+                rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
+            }
+            RelOperator::ProjectSet(rel_op) => {
+                rel_op.compute_required_prop_child(ctx, rel_expr, child_index, required)
+            }
         }
     }
 }
@@ -386,3 +449,136 @@ impl TryFrom<RelOperator> for DummyTableScan {
         }
     }
 }
+
+impl From<Window> for RelOperator {
+    fn from(v: Window) -> Self {
+        Self::Window(v)
+    }
+}
+
+impl TryFrom<RelOperator> for Window {
+    type Error = ErrorCode;
+    fn try_from(value: RelOperator) -> Result<Self> {
+        if let RelOperator::Window(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal(
+                "Cannot downcast RelOperator to Window",
+            ))
+        }
+    }
+}
+
+impl From<RecursiveUnion> for RelOperator {
+    fn from(v: RecursiveUnion) -> Self {
+        Self::RecursiveUnion(v)
+    }
+}
+
+impl TryFrom<RelOperator> for RecursiveUnion {
+    type Error = ErrorCode;
+    fn try_from(value: RelOperator) -> Result<Self> {
+        if let RelOperator::RecursiveUnion(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal(
+                "Cannot downcast RelOperator to RecursiveUnion",
+            ))
+        }
+    }
+}
+
+impl From<RecursiveCteScan> for RelOperator {
+    fn from(v: RecursiveCteScan) -> Self {
+        Self::RecursiveCteScan(v)
+    }
+}
+
+impl TryFrom<RelOperator> for RecursiveCteScan {
+    type Error = ErrorCode;
+    fn try_from(value: RelOperator) -> Result<Self> {
+        if let RelOperator::RecursiveCteScan(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal(
+                "Cannot downcast RelOperator to RecursiveCteScan",
+            ))
+        }
+    }
+}
+
+impl From<MaterializedCte> for RelOperator {
+    fn from(v: MaterializedCte) -> Self {
+        Self::MaterializedCte(v)
+    }
+}
+
+impl TryFrom<RelOperator> for MaterializedCte {
+    type Error = ErrorCode;
+    fn try_from(value: RelOperator) -> Result<Self> {
+        if let RelOperator::MaterializedCte(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal(
+                "Cannot downcast RelOperator to MaterializedCte",
+            ))
+        }
+    }
+}
+
+impl From<CteScan> for RelOperator {
+    fn from(v: CteScan) -> Self {
+        Self::CteScan(v)
+    }
+}
+
+impl TryFrom<RelOperator> for CteScan {
+    type Error = ErrorCode;
+    fn try_from(value: RelOperator) -> Result<Self> {
+        if let RelOperator::CteScan(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal(
+                "Cannot downcast RelOperator to CteScan",
+            ))
+        }
+    }
+}
+
+impl From<SetOperation> for RelOperator {
+    fn from(v: SetOperation) -> Self {
+        Self::SetOperation(v)
+    }
+}
+
+impl TryFrom<RelOperator> for SetOperation {
+    type Error = ErrorCode;
+    fn try_from(value: RelOperator) -> Result<Self> {
+        if let RelOperator::SetOperation(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal(
+                "Cannot downcast RelOperator to SetOperation",
+            ))
+        }
+    }
+}
+
+impl From<ProjectSet> for RelOperator {
+    fn from(v: ProjectSet) -> Self {
+        Self::ProjectSet(v)
+    }
+}
+
+impl TryFrom<RelOperator> for ProjectSet {
+    type Error = ErrorCode;
+    fn try_from(value: RelOperator) -> Result<Self> {
+        if let RelOperator::ProjectSet(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal(
+                "Cannot downcast RelOperator to ProjectSet",
+            ))
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use common_catalog::table_context::TableContext;
+use common_exception::Result;
+
+use crate::optimizer::ColumnSet;
+use crate::optimizer::PhysicalProperty;
+use crate::optimizer::RelExpr;
+use crate::optimizer::RelationalProperty;
+use crate::optimizer::RequiredProperty;
+use crate::optimizer::Statistics;
+use crate::plans::Operator;
+use crate::plans::RelOp;
+use crate::plans::ScalarExpr;
+use crate::IndexType;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum FlattenMode {
+    Object,
+    Array,
+    Both,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct FlattenOptions {
+    /// Path prefix of the flattened value, reported in the `PATH` column.
+    pub path: String,
+    pub recursive: bool,
+    pub mode: FlattenMode,
+}
+
+/// Set-returning function evaluated by `ProjectSet`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum SetReturningFunction {
+    /// `UNNEST(array)`, outputs a column `unnest` with one row per element.
+    Unnest,
+    /// `FLATTEN(input => variant)`, outputs the columns `key`, `path`, `index`,
+    /// `value` and `this` with one row per element of the variant.
+    Flatten(FlattenOptions),
+}
+
+impl SetReturningFunction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SetReturningFunction::Unnest => "unnest",
+            SetReturningFunction::Flatten(_) => "flatten",
+        }
+    }
+}
+
+impl Display for SetReturningFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Evaluate a set-returning function on each row of the input.
+///
+/// Every input row is repeated once for each row returned by the function, and the
+/// returned values are appended as new columns. This is the operator a `LATERAL`
+/// table function is bound to. If `outer` is true, an input row for which the
+/// function returns no rows is kept once, with NULLs in the new columns.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ProjectSet {
+    pub srf: SetReturningFunction,
+    pub args: Vec<ScalarExpr>,
+    // Output columns of the function, in the order of the function's output
+    pub columns: Vec<IndexType>,
+    pub outer: bool,
+}
+
+impl ProjectSet {
+    pub fn used_columns(&self) -> Result<ColumnSet> {
+        let mut used_columns = ColumnSet::new();
+        for arg in self.args.iter() {
+            used_columns.extend(arg.used_columns());
+        }
+        used_columns.extend(self.columns.iter().cloned());
+        Ok(used_columns)
+    }
+}
+
+impl Operator for ProjectSet {
+    fn rel_op(&self) -> RelOp {
+        RelOp::ProjectSet
+    }
+
+    fn derive_physical_prop(&self, rel_expr: &RelExpr) -> Result<PhysicalProperty> {
+        rel_expr.derive_physical_prop_child(0)
+    }
+
+    fn compute_required_prop_child(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        _rel_expr: &RelExpr,
+        _child_index: usize,
+        required: &RequiredProperty,
+    ) -> Result<RequiredProperty> {
+        Ok(required.clone())
+    }
+
+    fn derive_relational_prop(&self, rel_expr: &RelExpr) -> Result<RelationalProperty> {
+        let input_prop = rel_expr.derive_relational_prop_child(0)?;
+
+        // Derive output columns
+        let mut output_columns = input_prop.output_columns;
+        output_columns.extend(self.columns.iter().cloned());
+
+        // Derive outer columns
+        let mut outer_columns = input_prop.outer_columns;
+        for arg in self.args.iter() {
+            let used_columns = arg.used_columns();
+            let outer = used_columns
+                .difference(&output_columns)
+                .cloned()
+                .collect::<ColumnSet>();
+            outer_columns = outer_columns.union(&outer).cloned().collect();
+        }
+        outer_columns = outer_columns.difference(&output_columns).cloned().collect();
+
+        // The number of rows returned per input row is unknown.
+        let cardinality = input_prop.cardinality;
+
+        // Derive used columns
+        let mut used_columns = self.used_columns()?;
+        used_columns.extend(input_prop.used_columns);
+
+        Ok(RelationalProperty {
+            output_columns,
+            outer_columns,
+            used_columns,
+            cardinality,
+            statistics: Statistics {
+                precise_cardinality: None,
+                column_stats: Default::default(),
+                is_accurate: false,
+            },
+        })
+    }
+}
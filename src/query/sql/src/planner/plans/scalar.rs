@@ -18,11 +18,13 @@ use common_ast::ast::BinaryOperator;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_expression::types::DataType;
+use common_expression::types::NumberDataType;
 use common_expression::Literal;
 
 use crate::binder::ColumnBinding;
 use crate::optimizer::ColumnSet;
 use crate::optimizer::SExpr;
+use crate::plans::WindowFuncFrame;
 use crate::IndexType;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -39,6 +41,7 @@ pub enum ScalarExpr {
     // after making functions static typed?
     CastExpr(CastExpr),
     SubqueryExpr(SubqueryExpr),
+    WindowFunction(WindowFunc),
 }
 
 impl ScalarExpr {
@@ -54,6 +57,7 @@ impl ScalarExpr {
             ScalarExpr::FunctionCall(scalar) => (*scalar.return_type).clone(),
             ScalarExpr::CastExpr(scalar) => (*scalar.target_type).clone(),
             ScalarExpr::SubqueryExpr(scalar) => scalar.data_type(),
+            ScalarExpr::WindowFunction(scalar) => scalar.func.return_type(),
         }
     }
 
@@ -93,6 +97,19 @@ impl ScalarExpr {
             }
             ScalarExpr::CastExpr(scalar) => scalar.argument.used_columns(),
             ScalarExpr::SubqueryExpr(scalar) => scalar.outer_columns.clone(),
+            ScalarExpr::WindowFunction(scalar) => {
+                let mut result = ColumnSet::new();
+                for arg in scalar.func.arguments() {
+                    result = result.union(&arg.used_columns()).cloned().collect();
+                }
+                for part in scalar.partition_by.iter() {
+                    result = result.union(&part.used_columns()).cloned().collect();
+                }
+                for order in scalar.order_by.iter() {
+                    result = result.union(&order.expr.used_columns()).cloned().collect();
+                }
+                result
+            }
         }
     }
 }
@@ -279,6 +296,23 @@ impl TryFrom<ScalarExpr> for SubqueryExpr {
     }
 }
 
+impl From<WindowFunc> for ScalarExpr {
+    fn from(v: WindowFunc) -> Self {
+        Self::WindowFunction(v)
+    }
+}
+
+impl TryFrom<ScalarExpr> for WindowFunc {
+    type Error = ErrorCode;
+    fn try_from(value: ScalarExpr) -> Result<Self> {
+        if let ScalarExpr::WindowFunction(value) = value {
+            Ok(value)
+        } else {
+            Err(ErrorCode::Internal("Cannot downcast Scalar to WindowFunc"))
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct BoundColumnRef {
     pub column: ColumnBinding,
@@ -379,6 +413,139 @@ pub struct AggregateFunction {
     pub return_type: Box<DataType>,
 }
 
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct WindowFunc {
+    pub display_name: String,
+    pub func: WindowFuncType,
+    pub partition_by: Vec<ScalarExpr>,
+    pub order_by: Vec<WindowOrderBy>,
+    pub frame: WindowFuncFrame,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct WindowOrderBy {
+    pub expr: ScalarExpr,
+    pub asc: Option<bool>,
+    pub nulls_first: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum WindowFuncType {
+    /// Any aggregate function evaluated over the window frame, e.g. `SUM(a) OVER (...)`
+    Aggregate(AggregateFunction),
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag(LagLeadFunction),
+    Lead(LagLeadFunction),
+    FirstValue(FirstLastValueFunction),
+    LastValue(FirstLastValueFunction),
+}
+
+impl WindowFuncType {
+    pub fn func_name(&self) -> String {
+        match self {
+            WindowFuncType::Aggregate(agg) => agg.func_name.clone(),
+            WindowFuncType::RowNumber => "row_number".to_string(),
+            WindowFuncType::Rank => "rank".to_string(),
+            WindowFuncType::DenseRank => "dense_rank".to_string(),
+            WindowFuncType::Lag(_) => "lag".to_string(),
+            WindowFuncType::Lead(_) => "lead".to_string(),
+            WindowFuncType::FirstValue(_) => "first_value".to_string(),
+            WindowFuncType::LastValue(_) => "last_value".to_string(),
+        }
+    }
+
+    pub fn return_type(&self) -> DataType {
+        match self {
+            WindowFuncType::Aggregate(agg) => (*agg.return_type).clone(),
+            WindowFuncType::RowNumber | WindowFuncType::Rank | WindowFuncType::DenseRank => {
+                DataType::Number(NumberDataType::UInt64)
+            }
+            WindowFuncType::Lag(func) | WindowFuncType::Lead(func) => (*func.return_type).clone(),
+            WindowFuncType::FirstValue(func) | WindowFuncType::LastValue(func) => {
+                (*func.return_type).clone()
+            }
+        }
+    }
+
+    pub fn arguments(&self) -> Vec<&ScalarExpr> {
+        match self {
+            WindowFuncType::Aggregate(agg) => agg.args.iter().collect(),
+            WindowFuncType::RowNumber | WindowFuncType::Rank | WindowFuncType::DenseRank => {
+                vec![]
+            }
+            WindowFuncType::Lag(func) | WindowFuncType::Lead(func) => {
+                let mut args = vec![func.arg.as_ref()];
+                if let Some(default) = &func.default {
+                    args.push(default.as_ref());
+                }
+                args
+            }
+            WindowFuncType::FirstValue(func) | WindowFuncType::LastValue(func) => {
+                vec![func.arg.as_ref()]
+            }
+        }
+    }
+
+    /// Rewrite the arguments of the window function with `f`.
+    pub fn try_map_arguments<F>(&self, mut f: F) -> Result<Self>
+    where F: FnMut(&ScalarExpr) -> Result<ScalarExpr> {
+        Ok(match self {
+            WindowFuncType::Aggregate(agg) => WindowFuncType::Aggregate(AggregateFunction {
+                args: agg.args.iter().map(&mut f).collect::<Result<Vec<_>>>()?,
+                ..agg.clone()
+            }),
+            WindowFuncType::RowNumber | WindowFuncType::Rank | WindowFuncType::DenseRank => {
+                self.clone()
+            }
+            WindowFuncType::Lag(func) | WindowFuncType::Lead(func) => {
+                let func = LagLeadFunction {
+                    arg: Box::new(f(&func.arg)?),
+                    offset: func.offset,
+                    default: match &func.default {
+                        Some(default) => Some(Box::new(f(default)?)),
+                        None => None,
+                    },
+                    return_type: func.return_type.clone(),
+                };
+                if matches!(self, WindowFuncType::Lag(_)) {
+                    WindowFuncType::Lag(func)
+                } else {
+                    WindowFuncType::Lead(func)
+                }
+            }
+            WindowFuncType::FirstValue(func) | WindowFuncType::LastValue(func) => {
+                let func = FirstLastValueFunction {
+                    arg: Box::new(f(&func.arg)?),
+                    return_type: func.return_type.clone(),
+                };
+                if matches!(self, WindowFuncType::FirstValue(_)) {
+                    WindowFuncType::FirstValue(func)
+                } else {
+                    WindowFuncType::LastValue(func)
+                }
+            }
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LagLeadFunction {
+    pub arg: Box<ScalarExpr>,
+    /// Number of rows to look backward (LAG) or forward (LEAD), defaults to 1
+    pub offset: u64,
+    /// Value returned if the target row is out of the partition, defaults to NULL
+    pub default: Option<Box<ScalarExpr>>,
+    pub return_type: Box<DataType>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FirstLastValueFunction {
+    pub arg: Box<ScalarExpr>,
+    pub return_type: Box<DataType>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FunctionCall {
     pub params: Vec<usize>,
@@ -0,0 +1,134 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use common_catalog::table_context::TableContext;
+use common_exception::Result;
+
+use crate::optimizer::ColumnSet;
+use crate::optimizer::Distribution;
+use crate::optimizer::PhysicalProperty;
+use crate::optimizer::RelExpr;
+use crate::optimizer::RelationalProperty;
+use crate::optimizer::RequiredProperty;
+use crate::optimizer::Statistics;
+use crate::plans::Operator;
+use crate::plans::RelOp;
+use crate::IndexType;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum SetOperationType {
+    /// Each row of the left child is output as many times as it appears in
+    /// both children, that is `min(m, n)`.
+    IntersectAll,
+    /// Each row of the left child is output as many times as it appears in
+    /// the left child more than in the right child, that is `max(m - n, 0)`.
+    ExceptAll,
+}
+
+impl Display for SetOperationType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetOperationType::IntersectAll => write!(f, "INTERSECT ALL"),
+            SetOperationType::ExceptAll => write!(f, "EXCEPT ALL"),
+        }
+    }
+}
+
+/// `INTERSECT` and `EXCEPT` with bag semantics.
+///
+/// The rows of the right child are counted, and the rows of the left child are
+/// matched against the counts. Two rows are equal if all their columns are not
+/// distinct, so NULLs are equal to each other. The output columns are the columns
+/// of the left child. The `DISTINCT` variants are bound as a `DISTINCT` on the left
+/// child followed by this operator.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SetOperation {
+    pub op_type: SetOperationType,
+    // Pairs of compared columns
+    pub pairs: Vec<(IndexType, IndexType)>,
+}
+
+impl SetOperation {
+    pub fn used_columns(&self) -> Result<ColumnSet> {
+        let mut used_columns = ColumnSet::new();
+        for (left, right) in &self.pairs {
+            used_columns.insert(*left);
+            used_columns.insert(*right);
+        }
+        Ok(used_columns)
+    }
+}
+
+impl Operator for SetOperation {
+    fn rel_op(&self) -> RelOp {
+        RelOp::SetOperation
+    }
+
+    fn derive_relational_prop(&self, rel_expr: &RelExpr) -> Result<RelationalProperty> {
+        let left_prop = rel_expr.derive_relational_prop_child(0)?;
+        let right_prop = rel_expr.derive_relational_prop_child(1)?;
+
+        // Derive outer columns
+        let mut outer_columns = left_prop.outer_columns;
+        outer_columns = outer_columns
+            .union(&right_prop.outer_columns)
+            .cloned()
+            .collect();
+
+        let cardinality = match self.op_type {
+            SetOperationType::IntersectAll => left_prop.cardinality.min(right_prop.cardinality),
+            SetOperationType::ExceptAll => left_prop.cardinality,
+        };
+
+        // Derive used columns
+        let mut used_columns = self.used_columns()?;
+        used_columns.extend(left_prop.used_columns);
+        used_columns.extend(right_prop.used_columns);
+
+        Ok(RelationalProperty {
+            output_columns: left_prop.output_columns,
+            outer_columns,
+            used_columns,
+            cardinality,
+            statistics: Statistics {
+                precise_cardinality: None,
+                column_stats: Default::default(),
+                is_accurate: false,
+            },
+        })
+    }
+
+    fn derive_physical_prop(&self, _rel_expr: &RelExpr) -> Result<PhysicalProperty> {
+        Ok(PhysicalProperty {
+            distribution: Distribution::Serial,
+        })
+    }
+
+    fn compute_required_prop_child(
+        &self,
+        _ctx: Arc<dyn TableContext>,
+        _rel_expr: &RelExpr,
+        _child_index: usize,
+        required: &RequiredProperty,
+    ) -> Result<RequiredProperty> {
+        // The counts of the right child are kept on the local node.
+        let mut required = required.clone();
+        required.distribution = Distribution::Serial;
+        Ok(required)
+    }
+}
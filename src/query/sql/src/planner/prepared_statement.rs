@@ -0,0 +1,106 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_ast::ast::Statement;
+use common_ast::parser::parse_sql;
+use common_ast::parser::token::TokenKind;
+use common_ast::parser::tokenize_sql;
+use common_ast::Backtrace;
+use common_ast::Dialect;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::Scalar;
+
+use crate::planner::semantic::bind_scalar_params;
+
+/// A statement prepared by the client with `?` or `$<n>` placeholders.
+///
+/// The statement is parsed once when it's prepared and kept by its id, the parameters
+/// are bound to the placeholders as literals in a copy of the AST on each execution.
+#[derive(Clone, Debug)]
+pub struct PreparedStatement {
+    query: String,
+    dialect: Dialect,
+    stmt: Statement,
+    num_params: usize,
+}
+
+impl PreparedStatement {
+    pub fn try_create(query: &str, dialect: Dialect) -> Result<PreparedStatement> {
+        let tokens = tokenize_sql(query)?;
+        let backtrace = Backtrace::new();
+        let (stmt, _) = parse_sql(&tokens, dialect, &backtrace)?;
+
+        let mut num_anonymous = 0;
+        let mut num_params = 0;
+        let mut numbered = None;
+        for token in tokens.iter().filter(|t| t.kind == TokenKind::Placeholder) {
+            let is_numbered = token.text().starts_with('$');
+            if *numbered.get_or_insert(is_numbered) != is_numbered {
+                return Err(ErrorCode::SyntaxException(
+                    "Cannot mix `?` and `$<n>` placeholders in a statement",
+                )
+                .set_span(Some(token.span)));
+            }
+            // `?` is bound to the parameters in order, `$<n>` is bound to the n-th parameter.
+            let index = if is_numbered {
+                match token.text()[1..].parse::<usize>() {
+                    Ok(n) if n > 0 => n - 1,
+                    _ => {
+                        return Err(ErrorCode::SyntaxException(format!(
+                            "Invalid placeholder {}",
+                            token.text()
+                        ))
+                        .set_span(Some(token.span)));
+                    }
+                }
+            } else {
+                num_anonymous += 1;
+                num_anonymous - 1
+            };
+            num_params = num_params.max(index + 1);
+        }
+        Ok(PreparedStatement {
+            query: query.to_string(),
+            dialect,
+            stmt,
+            num_params,
+        })
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn num_params(&self) -> usize {
+        self.num_params
+    }
+
+    /// Bind the parameters to the placeholders, returns the statement to plan.
+    pub fn bind(&self, params: &[Scalar]) -> Result<Statement> {
+        if params.len() != self.num_params {
+            return Err(ErrorCode::BadArguments(format!(
+                "Prepared statement requires {} parameters, but got {}",
+                self.num_params,
+                params.len()
+            )));
+        }
+
+        let mut stmt = self.stmt.clone();
+        if self.num_params > 0 {
+            bind_scalar_params(&mut stmt, &self.query, self.dialect, params)?;
+        }
+        Ok(stmt)
+    }
+}
@@ -48,12 +48,18 @@ impl<'a> GroupingChecker<'a> {
             .get(&format!("{:?}", scalar))
         {
             let column = &self.bind_context.aggregate_info.group_items[*index];
+            let mut data_type = column.scalar.data_type();
+            if let Some(grouping_sets) = &self.bind_context.aggregate_info.grouping_sets {
+                if grouping_sets.is_nullable_group_item(column.index) {
+                    data_type = data_type.wrap_nullable();
+                }
+            }
             let column_binding = ColumnBinding {
                 database_name: None,
                 table_name: None,
                 column_name: "group_item".to_string(),
                 index: column.index,
-                data_type: Box::new(column.scalar.data_type()),
+                data_type: Box::new(data_type),
                 visibility: Visibility::Visible,
             };
             return Ok(BoundColumnRef {
@@ -95,6 +101,9 @@ impl<'a> GroupingChecker<'a> {
                 return_type: scalar.return_type.clone(),
             }
             .into()),
+            ScalarExpr::FunctionCall(func) if func.func_name == "grouping" => {
+                self.resolve_grouping(func, span)
+            }
             ScalarExpr::FunctionCall(func) => {
                 let args = func
                     .arguments
@@ -144,6 +153,59 @@ impl<'a> GroupingChecker<'a> {
                 }
                 Err(ErrorCode::Internal("Invalid aggregate function"))
             }
+
+            ScalarExpr::WindowFunction(window) => {
+                self.bind_context.windows.resolve_window_function(window)
+            }
+        }
+    }
+    /// Rewrite `GROUPING(a, b, ...)` into `grouping<params>(_grouping_id)`, the params
+    /// are the positions of the arguments in group items. For example, with `GROUP BY
+    /// GROUPING SETS ((a, b), (a))`, `GROUPING(b, a)` will be rewritten into
+    /// `grouping<1, 0>(_grouping_id)`.
+    fn resolve_grouping(&mut self, func: &FunctionCall, span: Span) -> Result<ScalarExpr> {
+        if !func.params.is_empty() {
+            // Already rewritten
+            return Ok(func.clone().into());
+        }
+
+        let agg_info = &self.bind_context.aggregate_info;
+        let grouping_sets = agg_info.grouping_sets.as_ref().ok_or_else(|| {
+            ErrorCode::SemanticError(
+                "grouping function can only be used with GROUPING SETS, ROLLUP or CUBE"
+                    .to_string(),
+            )
+            .set_span(span)
+        })?;
+
+        let params = func
+            .arguments
+            .iter()
+            .map(|arg| {
+                agg_info
+                    .group_items_map
+                    .get(&format!("{:?}", arg))
+                    .cloned()
+                    .ok_or_else(|| {
+                        ErrorCode::SemanticError(
+                            "arguments of grouping function must be group items".to_string(),
+                        )
+                        .set_span(span)
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(FunctionCall {
+            params,
+            arguments: vec![
+                BoundColumnRef {
+                    column: grouping_sets.grouping_id_column.clone(),
+                }
+                .into(),
+            ],
+            func_name: func.func_name.clone(),
+            return_type: func.return_type.clone(),
         }
+        .into())
     }
 }
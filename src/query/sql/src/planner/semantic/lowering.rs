@@ -108,6 +108,12 @@ impl ScalarExpr {
                 data_type: subquery.data_type(),
                 display_name: DUMMY_NAME.to_string(),
             },
+            ScalarExpr::WindowFunction(window) => RawExpr::ColumnRef {
+                span: None,
+                id: window.display_name.clone(),
+                data_type: window.func.return_type(),
+                display_name: window.display_name.clone(),
+            },
         }
     }
 
@@ -199,6 +205,12 @@ impl ScalarExpr {
                 data_type: subquery.data_type(),
                 display_name: DUMMY_NAME.to_string(),
             },
+            ScalarExpr::WindowFunction(window) => RawExpr::ColumnRef {
+                span: None,
+                id: DUMMY_INDEX,
+                data_type: window.func.return_type(),
+                display_name: window.display_name.clone(),
+            },
         }
     }
 
@@ -16,6 +16,7 @@ mod distinct_to_groupby;
 mod grouping_check;
 mod lowering;
 mod name_resolution;
+mod query_params;
 mod type_check;
 
 pub use distinct_to_groupby::DistinctToGroupBy;
@@ -24,5 +25,7 @@ pub use name_resolution::compare_table_name;
 pub use name_resolution::normalize_identifier;
 pub use name_resolution::IdentifierNormalizer;
 pub use name_resolution::NameResolutionContext;
+pub use query_params::bind_query_params;
+pub use query_params::bind_scalar_params;
 pub use type_check::validate_function_arg;
 pub use type_check::TypeChecker;
@@ -0,0 +1,618 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use common_ast::ast::AlterViewStmt;
+use common_ast::ast::CopyStmt;
+use common_ast::ast::CopyUnit;
+use common_ast::ast::CreateTableStmt;
+use common_ast::ast::CreateViewStmt;
+use common_ast::ast::Expr;
+use common_ast::ast::Identifier;
+use common_ast::ast::InsertSource;
+use common_ast::ast::InsertStmt;
+use common_ast::ast::Literal;
+use common_ast::ast::MatchOperation;
+use common_ast::ast::MergeClause;
+use common_ast::ast::MergeIntoStmt;
+use common_ast::ast::Query;
+use common_ast::ast::SelectStmt;
+use common_ast::ast::SelectTarget;
+use common_ast::ast::SetExpr;
+use common_ast::ast::SetOperation;
+use common_ast::ast::SetOperator;
+use common_ast::ast::Statement;
+use common_ast::ast::TableReference;
+use common_ast::ast::TypeName;
+use common_ast::ast::UnaryOperator;
+use common_ast::ast::UpdateStmt;
+use common_ast::parser::parse_comma_separated_exprs;
+use common_ast::parser::parse_type_name;
+use common_ast::parser::token::TokenKind;
+use common_ast::parser::tokenize_sql;
+use common_ast::walk_expr_mut;
+use common_ast::walk_query_mut;
+use common_ast::walk_statement_mut;
+use common_ast::walk_table_reference_mut;
+use common_ast::Backtrace;
+use common_ast::Dialect;
+use common_ast::VisitorMut;
+use common_catalog::table_context::QueryParam;
+use common_catalog::table_context::QueryParams;
+use common_exception::ErrorCode;
+use common_exception::Range;
+use common_exception::Result;
+use common_exception::Span;
+use common_expression::types::date::DATE_FORMAT;
+use common_expression::types::decimal::DecimalScalar;
+use common_expression::types::number::NumberScalar;
+use common_expression::types::timestamp::MICROS_IN_A_SEC;
+use common_expression::types::timestamp::TIMESTAMP_FORMAT;
+use common_expression::types::DataType;
+use common_expression::Scalar;
+use serde_json::Value as JsonValue;
+
+use crate::planner::semantic::TypeChecker;
+
+/// Bind the parameters of the query to the placeholders of the statement.
+///
+/// The placeholders are replaced by the typed literals of the parameters in the AST, the
+/// parameters are never spliced into the SQL text. The rows of `INSERT INTO ... VALUES`
+/// are bound as `INSERT INTO ... SELECT`, since they are not parsed with the statement.
+/// Each parameter must be bound to some placeholder, and each placeholder to a parameter.
+pub fn bind_query_params(
+    stmt: &mut Statement,
+    sql: &str,
+    dialect: Dialect,
+    params: &QueryParams,
+) -> Result<()> {
+    bind_params(stmt, sql, dialect, params)
+}
+
+/// Bind the parameters of a prepared statement, sent by the clients of the wire protocols,
+/// to the placeholders of the statement by position.
+pub fn bind_scalar_params(
+    stmt: &mut Statement,
+    sql: &str,
+    dialect: Dialect,
+    params: &[Scalar],
+) -> Result<()> {
+    bind_params(stmt, sql, dialect, &params)
+}
+
+fn bind_params(
+    stmt: &mut Statement,
+    sql: &str,
+    dialect: Dialect,
+    params: &dyn PlaceholderParams,
+) -> Result<()> {
+    // `?` is bound to the parameters in order, so all of them are counted in the SQL.
+    let tokens = tokenize_sql(sql)?;
+    let anonymous = tokens
+        .iter()
+        .filter(|token| token.kind == TokenKind::Placeholder && token.text() == "?")
+        .map(|token| token.span.start)
+        .collect::<Vec<_>>();
+    let num_positional = tokens
+        .iter()
+        .filter(|token| token.kind == TokenKind::Placeholder)
+        .count();
+
+    if let Statement::Insert(insert) = stmt {
+        if let InsertSource::Values { rest_str } = &insert.source {
+            let offset = sql.len() - rest_str.len();
+            let values = rest_str.trim_end().trim_end_matches(';');
+            let query = bind_values(values, offset, dialect, &anonymous, num_positional, params)?;
+            insert.source = InsertSource::Select {
+                query: Box::new(query),
+            };
+            return Ok(());
+        }
+    }
+
+    let mut binder = QueryParamsBinder::new(sql, 0, &anonymous, num_positional, params);
+    walk_statement_mut(&mut binder, stmt);
+    binder.finish()
+}
+
+fn bind_values(
+    values: &str,
+    offset: usize,
+    dialect: Dialect,
+    anonymous: &[usize],
+    num_positional: usize,
+    params: &dyn PlaceholderParams,
+) -> Result<Query> {
+    let tokens = tokenize_sql(values)?;
+    let backtrace = Backtrace::new();
+    let rows = parse_comma_separated_exprs(&tokens, dialect, &backtrace)?;
+
+    let mut binder = QueryParamsBinder::new(values, offset, anonymous, num_positional, params);
+    let mut body = None;
+    for mut row in rows {
+        binder.visit_expr(&mut row);
+        let exprs = match row {
+            Expr::Tuple { exprs, .. } => exprs,
+            expr => vec![expr],
+        };
+        let select = SetExpr::Select(Box::new(SelectStmt {
+            span: None,
+            distinct: false,
+            select_list: exprs
+                .into_iter()
+                .map(|expr| SelectTarget::AliasedExpr {
+                    expr: Box::new(expr),
+                    alias: None,
+                })
+                .collect(),
+            from: vec![],
+            selection: None,
+            group_by: None,
+            having: None,
+        }));
+        body = Some(match body {
+            None => select,
+            Some(left) => SetExpr::SetOperation(Box::new(SetOperation {
+                span: None,
+                op: SetOperator::Union,
+                all: true,
+                left: Box::new(left),
+                right: Box::new(select),
+            })),
+        });
+    }
+    binder.finish()?;
+
+    Ok(Query {
+        span: None,
+        with: None,
+        body: body.ok_or_else(|| ErrorCode::BadArguments("INSERT without VALUES"))?,
+        order_by: vec![],
+        limit: vec![],
+        offset: None,
+        ignore_result: false,
+    })
+}
+
+struct QueryParamsBinder<'a> {
+    // The SQL text of the spans in the AST, from the `offset` of the whole SQL.
+    sql: &'a str,
+    offset: usize,
+    anonymous: &'a [usize],
+    // The number of `?` and `$<n>` placeholders in the SQL.
+    num_positional: usize,
+    params: &'a dyn PlaceholderParams,
+    // The `?` and `$<n>` placeholders bound, and the distinct parameters they refer to.
+    num_positional_bound: usize,
+    bound_params: BTreeSet<String>,
+    error: Option<ErrorCode>,
+}
+
+impl<'a> QueryParamsBinder<'a> {
+    fn new(
+        sql: &'a str,
+        offset: usize,
+        anonymous: &'a [usize],
+        num_positional: usize,
+        params: &'a dyn PlaceholderParams,
+    ) -> QueryParamsBinder<'a> {
+        QueryParamsBinder {
+            sql,
+            offset,
+            anonymous,
+            num_positional,
+            params,
+            num_positional_bound: 0,
+            bound_params: BTreeSet::new(),
+            error: None,
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        // The placeholders out of the expressions walked by the binder are never bound.
+        if self.num_positional_bound != self.num_positional {
+            return Err(ErrorCode::BadArguments(
+                "The placeholders are not supported in this part of the statement",
+            ));
+        }
+        if self.bound_params.len() != self.params.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "The statement has {} placeholders, but got {} parameters",
+                self.bound_params.len(),
+                self.params.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn bind_placeholder(&mut self, span: Range) -> Result<Expr> {
+        let text = &self.sql[span.start..span.end];
+        let (key, label) = match text.as_bytes()[0] {
+            b':' => {
+                let name = text[1..].trim();
+                (PlaceholderKey::Named(name), format!("`:{name}`"))
+            }
+            b'$' => {
+                let index = text[1..].parse::<usize>().unwrap_or(0);
+                (
+                    PlaceholderKey::Positional(index.checked_sub(1)),
+                    format!("`{text}`"),
+                )
+            }
+            _ => {
+                let start = self.offset + span.start;
+                let index = self.anonymous.iter().position(|pos| *pos == start);
+                let label = format!("`?` #{}", index.map(|i| i + 1).unwrap_or(0));
+                (PlaceholderKey::Positional(index), label)
+            }
+        };
+        let bound_param = match &key {
+            PlaceholderKey::Named(name) => format!(":{name}"),
+            PlaceholderKey::Positional(index) => {
+                self.num_positional_bound += 1;
+                format!("{}", index.map(|i| i + 1).unwrap_or(0))
+            }
+        };
+        match self.params.bind(text, key, &label, Some(span))? {
+            Some(expr) => {
+                self.bound_params.insert(bound_param);
+                Ok(expr)
+            }
+            None => Err(ErrorCode::BadArguments(format!(
+                "The parameter of the placeholder {label} is not given"
+            ))),
+        }
+    }
+}
+
+/// The placeholder `:<name>` is bound by name, `?` and `$<n>` are bound by position.
+enum PlaceholderKey<'a> {
+    Named(&'a str),
+    Positional(Option<usize>),
+}
+
+trait PlaceholderParams {
+    fn len(&self) -> usize;
+
+    /// Returns the literal of the parameter bound to the placeholder, `None` if it's not given.
+    fn bind(
+        &self,
+        text: &str,
+        key: PlaceholderKey,
+        label: &str,
+        span: Span,
+    ) -> Result<Option<Expr>>;
+}
+
+impl PlaceholderParams for QueryParams {
+    fn len(&self) -> usize {
+        match self {
+            QueryParams::Named(params) => params.len(),
+            QueryParams::Positional(params) => params.len(),
+        }
+    }
+
+    fn bind(
+        &self,
+        text: &str,
+        key: PlaceholderKey,
+        label: &str,
+        span: Span,
+    ) -> Result<Option<Expr>> {
+        let param = match (self, key) {
+            (QueryParams::Named(params), PlaceholderKey::Named(name)) => params.get(name),
+            (QueryParams::Positional(params), PlaceholderKey::Positional(index)) => {
+                index.and_then(|i| params.get(i))
+            }
+            (QueryParams::Named(_), _) => {
+                return Err(ErrorCode::BadArguments(format!(
+                    "The placeholder {text} requires the parameters by position, but got them by name"
+                )));
+            }
+            (QueryParams::Positional(_), _) => {
+                return Err(ErrorCode::BadArguments(format!(
+                    "The placeholder {text} requires the parameters by name, but got them by position"
+                )));
+            }
+        };
+        param
+            .map(|param| param_to_expr(param, label, span))
+            .transpose()
+    }
+}
+
+impl PlaceholderParams for &[Scalar] {
+    fn len(&self) -> usize {
+        <[Scalar]>::len(self)
+    }
+
+    fn bind(
+        &self,
+        text: &str,
+        key: PlaceholderKey,
+        _label: &str,
+        span: Span,
+    ) -> Result<Option<Expr>> {
+        match key {
+            PlaceholderKey::Positional(index) => index
+                .and_then(|i| self.get(i))
+                .map(|scalar| scalar_to_expr(scalar, span))
+                .transpose(),
+            PlaceholderKey::Named(_) => Err(ErrorCode::BadArguments(format!(
+                "The placeholder {text} requires the parameters by name, but got them by position"
+            ))),
+        }
+    }
+}
+
+impl<'a> VisitorMut for QueryParamsBinder<'a> {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        if let Expr::Placeholder { span: Some(span) } = expr {
+            match self.bind_placeholder(*span) {
+                Ok(bound) => *expr = bound,
+                Err(e) => {
+                    let span = Range {
+                        start: self.offset + span.start,
+                        end: self.offset + span.end,
+                    };
+                    self.error.get_or_insert(e.set_span(Some(span)));
+                }
+            }
+            return;
+        }
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_insert_source(&mut self, insert_source: &mut InsertSource) {
+        if let InsertSource::Select { query } = insert_source {
+            walk_query_mut(self, query);
+        }
+    }
+
+    fn visit_insert(&mut self, insert: &mut InsertStmt) {
+        self.visit_insert_source(&mut insert.source);
+    }
+
+    fn visit_delete(&mut self, table_reference: &mut TableReference, selection: &mut Option<Expr>) {
+        walk_table_reference_mut(self, table_reference);
+        if let Some(selection) = selection {
+            self.visit_expr(selection);
+        }
+    }
+
+    fn visit_update(&mut self, update: &mut UpdateStmt) {
+        walk_table_reference_mut(self, &mut update.table);
+        for update_expr in update.update_list.iter_mut() {
+            self.visit_expr(&mut update_expr.expr);
+        }
+        if let Some(selection) = &mut update.selection {
+            self.visit_expr(selection);
+        }
+    }
+
+    fn visit_merge_into(&mut self, merge_into: &mut MergeIntoStmt) {
+        walk_table_reference_mut(self, &mut merge_into.table);
+        walk_table_reference_mut(self, &mut merge_into.source);
+        self.visit_expr(&mut merge_into.join_expr);
+        for clause in merge_into.merge_clauses.iter_mut() {
+            match clause {
+                MergeClause::Matched {
+                    selection,
+                    operation,
+                } => {
+                    if let Some(selection) = selection {
+                        self.visit_expr(selection);
+                    }
+                    if let MatchOperation::Update { update_list } = operation {
+                        for update_expr in update_list.iter_mut() {
+                            self.visit_expr(&mut update_expr.expr);
+                        }
+                    }
+                }
+                MergeClause::NotMatched {
+                    selection, values, ..
+                } => {
+                    if let Some(selection) = selection {
+                        self.visit_expr(selection);
+                    }
+                    for value in values.iter_mut() {
+                        self.visit_expr(value);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_copy(&mut self, copy: &mut CopyStmt) {
+        if let CopyUnit::Query(query) = &mut copy.src {
+            walk_query_mut(self, query);
+        }
+    }
+
+    fn visit_set_variable(
+        &mut self,
+        _is_global: bool,
+        _variable: &mut Identifier,
+        value: &mut Box<Expr>,
+    ) {
+        self.visit_expr(value);
+    }
+
+    fn visit_create_table(&mut self, stmt: &mut CreateTableStmt) {
+        if let Some(query) = &mut stmt.as_query {
+            walk_query_mut(self, query);
+        }
+    }
+
+    fn visit_create_view(&mut self, stmt: &mut CreateViewStmt) {
+        walk_query_mut(self, &mut stmt.query);
+    }
+
+    fn visit_alter_view(&mut self, stmt: &mut AlterViewStmt) {
+        walk_query_mut(self, &mut stmt.query);
+    }
+}
+
+/// Convert the parameter to a literal, casted to the type of the parameter.
+fn param_to_expr(param: &QueryParam, label: &str, span: Span) -> Result<Expr> {
+    let target_type = match &param.data_type {
+        Some(data_type) => {
+            let type_name = parse_param_type(data_type)?;
+            check_param_type(&param.value, &type_name, label)?;
+            Some(type_name)
+        }
+        // The arrays and objects are bound as variants if the type is not given.
+        None if param.value.is_array() || param.value.is_object() => Some(TypeName::Variant),
+        None => None,
+    };
+
+    let literal = |lit| Expr::Literal { span, lit };
+    let expr = match &param.value {
+        JsonValue::Null => literal(Literal::Null),
+        JsonValue::Bool(v) => literal(Literal::Boolean(*v)),
+        JsonValue::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(v), _) => literal(Literal::Integer(v)),
+            (None, Some(v)) => Expr::UnaryOp {
+                span,
+                op: UnaryOperator::Minus,
+                expr: Box::new(literal(Literal::Integer(v.unsigned_abs()))),
+            },
+            _ => literal(Literal::Float(n.as_f64().unwrap_or_default())),
+        },
+        JsonValue::String(v) => literal(Literal::String(v.clone())),
+        value @ (JsonValue::Array(_) | JsonValue::Object(_)) => {
+            literal(Literal::String(value.to_string()))
+        }
+    };
+    Ok(match target_type {
+        Some(target_type) => Expr::Cast {
+            span,
+            expr: Box::new(expr),
+            target_type,
+            pg_style: false,
+        },
+        None => expr,
+    })
+}
+
+/// Convert the parameter of a prepared statement to a literal.
+///
+/// The literal is built in the AST, so that the floats like `NaN` and `inf` are bound as
+/// they are, which have no literals in SQL.
+fn scalar_to_expr(scalar: &Scalar, span: Span) -> Result<Expr> {
+    let literal = |lit| Expr::Literal { span, lit };
+    let cast = |lit, target_type| Expr::Cast {
+        span,
+        expr: Box::new(literal(lit)),
+        target_type,
+        pg_style: false,
+    };
+    let integer = |v: i64| match v < 0 {
+        true => Expr::UnaryOp {
+            span,
+            op: UnaryOperator::Minus,
+            expr: Box::new(literal(Literal::Integer(v.unsigned_abs()))),
+        },
+        false => literal(Literal::Integer(v as u64)),
+    };
+    match scalar {
+        Scalar::Null => Ok(literal(Literal::Null)),
+        Scalar::Boolean(v) => Ok(literal(Literal::Boolean(*v))),
+        Scalar::Number(NumberScalar::UInt8(v)) => Ok(literal(Literal::Integer(*v as u64))),
+        Scalar::Number(NumberScalar::UInt16(v)) => Ok(literal(Literal::Integer(*v as u64))),
+        Scalar::Number(NumberScalar::UInt32(v)) => Ok(literal(Literal::Integer(*v as u64))),
+        Scalar::Number(NumberScalar::UInt64(v)) => Ok(literal(Literal::Integer(*v))),
+        Scalar::Number(NumberScalar::Int8(v)) => Ok(integer(*v as i64)),
+        Scalar::Number(NumberScalar::Int16(v)) => Ok(integer(*v as i64)),
+        Scalar::Number(NumberScalar::Int32(v)) => Ok(integer(*v as i64)),
+        Scalar::Number(NumberScalar::Int64(v)) => Ok(integer(*v)),
+        Scalar::Number(NumberScalar::Float32(v)) => Ok(literal(Literal::Float(v.0 as f64))),
+        Scalar::Number(NumberScalar::Float64(v)) => Ok(literal(Literal::Float(v.0))),
+        Scalar::Decimal(decimal) => {
+            let (DecimalScalar::Decimal128(_, size) | DecimalScalar::Decimal256(_, size)) = decimal;
+            Ok(cast(
+                Literal::String(scalar.to_string()),
+                TypeName::Decimal {
+                    precision: size.precision,
+                    scale: size.scale,
+                },
+            ))
+        }
+        Scalar::String(s) => match String::from_utf8(s.clone()) {
+            Ok(s) => Ok(literal(Literal::String(s))),
+            Err(_) => Err(ErrorCode::BadArguments(
+                "Invalid utf8 string of the prepared statement parameter",
+            )),
+        },
+        Scalar::Date(d) => {
+            let date = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + Duration::days(*d as i64);
+            Ok(cast(
+                Literal::String(date.format(DATE_FORMAT).to_string()),
+                TypeName::Date,
+            ))
+        }
+        Scalar::Timestamp(ts) => {
+            // The timestamp is bound with the UTC offset, it's independent of the session timezone.
+            let datetime = NaiveDateTime::from_timestamp_opt(
+                ts.div_euclid(MICROS_IN_A_SEC),
+                (ts.rem_euclid(MICROS_IN_A_SEC) * 1_000) as u32,
+            )
+            .ok_or_else(|| ErrorCode::BadArguments(format!("Invalid timestamp {ts}")))?;
+            Ok(cast(
+                Literal::String(format!("{}+00:00", datetime.format(TIMESTAMP_FORMAT))),
+                TypeName::Timestamp,
+            ))
+        }
+        _ => Err(ErrorCode::BadArguments(format!(
+            "Unsupported prepared statement parameter: {scalar}"
+        ))),
+    }
+}
+
+fn parse_param_type(data_type: &str) -> Result<TypeName> {
+    let tokens = tokenize_sql(data_type)?;
+    let backtrace = Backtrace::new();
+    parse_type_name(&tokens, Dialect::PostgreSQL, &backtrace)
+        .map_err(|e| ErrorCode::BadArguments(format!("Invalid parameter type {data_type}: {e}")))
+}
+
+/// Check the json value against the type of the parameter.
+fn check_param_type(value: &JsonValue, type_name: &TypeName, label: &str) -> Result<()> {
+    let data_type = DataType::from(&TypeChecker::resolve_type_name(type_name)?);
+    let inner_type = data_type.remove_nullable();
+    let matched = match (value, &inner_type) {
+        (_, DataType::Variant) => true,
+        (JsonValue::Null, _) => data_type.is_nullable_or_null(),
+        (JsonValue::Bool(_), DataType::Boolean) => true,
+        (JsonValue::Number(n), _) if inner_type.is_integer() => n.is_i64() || n.is_u64(),
+        (JsonValue::Number(_), DataType::Number(_) | DataType::Decimal(_)) => true,
+        (JsonValue::String(_), DataType::Decimal(_)) => true,
+        (JsonValue::String(_), DataType::String | DataType::Date | DataType::Timestamp) => true,
+        _ => false,
+    };
+    if matched {
+        Ok(())
+    } else {
+        Err(ErrorCode::BadArguments(format!(
+            "The parameter of the placeholder {label} expects {data_type}, but got {value}"
+        )))
+    }
+}
@@ -28,6 +28,10 @@ use common_ast::ast::SubqueryModifier;
 use common_ast::ast::TrimWhere;
 use common_ast::ast::TypeName;
 use common_ast::ast::UnaryOperator;
+use common_ast::ast::Window;
+use common_ast::ast::WindowFrame;
+use common_ast::ast::WindowFrameBound;
+use common_ast::ast::WindowFrameUnits;
 use common_ast::parser::parse_expr;
 use common_ast::parser::tokenize_sql;
 use common_ast::Backtrace;
@@ -69,12 +73,20 @@ use crate::plans::CastExpr;
 use crate::plans::ComparisonExpr;
 use crate::plans::ComparisonOp;
 use crate::plans::ConstantExpr;
+use crate::plans::FirstLastValueFunction;
 use crate::plans::FunctionCall;
+use crate::plans::LagLeadFunction;
 use crate::plans::NotExpr;
 use crate::plans::OrExpr;
 use crate::plans::ScalarExpr;
 use crate::plans::SubqueryExpr;
 use crate::plans::SubqueryType;
+use crate::plans::WindowFunc;
+use crate::plans::WindowFuncFrame;
+use crate::plans::WindowFuncFrameBound;
+use crate::plans::WindowFuncFrameUnits;
+use crate::plans::WindowFuncType;
+use crate::plans::WindowOrderBy;
 use crate::BaseTableColumn;
 use crate::BindContext;
 use crate::ColumnBinding;
@@ -101,6 +113,10 @@ pub struct TypeChecker<'a> {
     // true if current expr is inside an aggregate function.
     // This is used to check if there is nested aggregate function.
     in_aggregate_function: bool,
+
+    // true if current expr is inside a window function.
+    // This is used to check if there is nested window function.
+    in_window_function: bool,
 }
 
 impl<'a> TypeChecker<'a> {
@@ -118,6 +134,7 @@ impl<'a> TypeChecker<'a> {
             metadata,
             aliases,
             in_aggregate_function: false,
+            in_window_function: false,
         }
     }
 
@@ -306,6 +323,7 @@ impl<'a> TypeChecker<'a> {
                                 },
                                 args: args.iter().copied().cloned().collect(),
                                 params: vec![],
+                                window: None,
                             },
                             None,
                         )
@@ -579,6 +597,7 @@ impl<'a> TypeChecker<'a> {
                                 },
                                 args: vec![*operand.clone(), c.clone()],
                                 params: vec![],
+                                window: None,
                             };
                             arguments.push(equal_expr)
                         }
@@ -644,10 +663,18 @@ impl<'a> TypeChecker<'a> {
                 name,
                 args,
                 params,
-                ..
+                window,
             } => {
                 let func_name = name.name.to_lowercase();
                 let func_name = func_name.as_str();
+                if let Some(window) = window {
+                    return self
+                        .resolve_window_function(
+                            *span, expr, func_name, *distinct, name, args, params, window,
+                        )
+                        .await;
+                }
+
                 if !is_builtin_function(func_name)
                     && !Self::all_rewritable_scalar_function().contains(&func_name)
                 {
@@ -740,8 +767,18 @@ impl<'a> TypeChecker<'a> {
                         })
                         .collect::<Result<Vec<_>>>()?;
 
-                    self.resolve_function(*span, func_name, params, &args, required_type)
-                        .await?
+                    if func_name == "grouping" {
+                        if !params.is_empty() {
+                            return Err(ErrorCode::SemanticError(
+                                "grouping function does not accept parameters".to_string(),
+                            )
+                            .set_span(*span));
+                        }
+                        self.resolve_grouping(*span, &args).await?
+                    } else {
+                        self.resolve_function(*span, func_name, params, &args, required_type)
+                            .await?
+                    }
                 }
             }
 
@@ -932,6 +969,13 @@ impl<'a> TypeChecker<'a> {
             }
 
             Expr::Tuple { span, exprs, .. } => self.resolve_tuple(*span, exprs).await?,
+
+            Expr::Placeholder { span } => {
+                return Err(ErrorCode::SemanticError(
+                    "Placeholder `?` is only allowed in prepared statements".to_string(),
+                )
+                .set_span(*span));
+            }
         };
 
         Ok(Box::new(self.post_resolve(&scalar, &data_type)?))
@@ -950,6 +994,330 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
+    /// Resolve window function call, e.g. `rank() OVER (PARTITION BY a ORDER BY b)`.
+    #[allow(clippy::too_many_arguments)]
+    #[async_recursion::async_recursion]
+    async fn resolve_window_function(
+        &mut self,
+        span: Span,
+        expr: &Expr,
+        func_name: &str,
+        distinct: bool,
+        name: &Identifier,
+        args: &[Expr],
+        params: &[Literal],
+        window: &Window,
+    ) -> Result<Box<(ScalarExpr, DataType)>> {
+        if self.in_window_function {
+            // Reset the state
+            self.in_window_function = false;
+            return Err(ErrorCode::SemanticError(
+                "window function calls cannot be nested".to_string(),
+            )
+            .set_span(span));
+        }
+        self.in_window_function = true;
+
+        let mut partition_by = Vec::with_capacity(window.partition_by.len());
+        for p in window.partition_by.iter() {
+            let box (scalar, _) = self.resolve(p, None).await?;
+            partition_by.push(scalar);
+        }
+
+        let mut order_by = Vec::with_capacity(window.order_by.len());
+        for o in window.order_by.iter() {
+            let box (scalar, _) = self.resolve(&o.expr, None).await?;
+            order_by.push(WindowOrderBy {
+                expr: scalar,
+                asc: o.asc,
+                nulls_first: o.nulls_first,
+            });
+        }
+
+        let frame = self.resolve_window_frame(span, &order_by, window.window_frame.as_ref())?;
+
+        let func = if AggregateFunctionFactory::instance().contains(func_name) {
+            // Resolve as a normal aggregate function without the `OVER` clause.
+            let agg_expr = Expr::FunctionCall {
+                span,
+                distinct,
+                name: name.clone(),
+                args: args.to_vec(),
+                params: params.to_vec(),
+                window: None,
+            };
+            match *self.resolve(&agg_expr, None).await? {
+                (ScalarExpr::AggregateFunction(agg), _) => WindowFuncType::Aggregate(agg),
+                _ => {
+                    return Err(ErrorCode::Internal(format!(
+                        "{func_name} should be resolved as an aggregate function"
+                    )));
+                }
+            }
+        } else {
+            if distinct || !params.is_empty() {
+                return Err(ErrorCode::SemanticError(format!(
+                    "window function {func_name} doesn't support DISTINCT or parameters"
+                ))
+                .set_span(span));
+            }
+            self.resolve_general_window_function(span, func_name, args)
+                .await?
+        };
+
+        self.in_window_function = false;
+
+        let data_type = func.return_type();
+        let window_func = WindowFunc {
+            display_name: format!("{:#}", expr),
+            func,
+            partition_by,
+            order_by,
+            frame,
+        };
+        Ok(Box::new((window_func.into(), data_type)))
+    }
+
+    /// Resolve ranking and value window functions, which can only be used with `OVER` clause.
+    async fn resolve_general_window_function(
+        &mut self,
+        span: Span,
+        func_name: &str,
+        args: &[Expr],
+    ) -> Result<WindowFuncType> {
+        match func_name {
+            "row_number" | "rank" | "dense_rank" => {
+                if !args.is_empty() {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "window function {func_name} doesn't accept arguments"
+                    ))
+                    .set_span(span));
+                }
+                Ok(match func_name {
+                    "row_number" => WindowFuncType::RowNumber,
+                    "rank" => WindowFuncType::Rank,
+                    _ => WindowFuncType::DenseRank,
+                })
+            }
+            "lag" | "lead" => {
+                if args.is_empty() || args.len() > 3 {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "window function {func_name} accepts 1 to 3 arguments"
+                    ))
+                    .set_span(span));
+                }
+                let box (arg, arg_type) = self.resolve(&args[0], None).await?;
+                let offset = match args.get(1) {
+                    None => 1,
+                    Some(Expr::Literal {
+                        lit: Literal::Integer(n),
+                        ..
+                    }) => *n,
+                    Some(offset) => {
+                        return Err(ErrorCode::SemanticError(format!(
+                            "offset of window function {func_name} must be a non-negative integer constant"
+                        ))
+                        .set_span(offset.span()));
+                    }
+                };
+                let return_type = arg_type.wrap_nullable();
+                let default = match args.get(2) {
+                    None => None,
+                    Some(default) => {
+                        let box (default, _) = self.resolve(default, None).await?;
+                        Some(Box::new(wrap_cast_if_needed(&default, &return_type)))
+                    }
+                };
+                let func = LagLeadFunction {
+                    arg: Box::new(arg),
+                    offset,
+                    default,
+                    return_type: Box::new(return_type),
+                };
+                Ok(if func_name == "lag" {
+                    WindowFuncType::Lag(func)
+                } else {
+                    WindowFuncType::Lead(func)
+                })
+            }
+            "first_value" | "last_value" => {
+                if args.len() != 1 {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "window function {func_name} accepts exactly 1 argument"
+                    ))
+                    .set_span(span));
+                }
+                let box (arg, arg_type) = self.resolve(&args[0], None).await?;
+                let func = FirstLastValueFunction {
+                    arg: Box::new(arg),
+                    return_type: Box::new(arg_type.wrap_nullable()),
+                };
+                Ok(if func_name == "first_value" {
+                    WindowFuncType::FirstValue(func)
+                } else {
+                    WindowFuncType::LastValue(func)
+                })
+            }
+            _ => Err(ErrorCode::SemanticError(format!(
+                "unknown window function: {func_name}"
+            ))
+            .set_span(span)),
+        }
+    }
+
+    fn resolve_window_frame(
+        &self,
+        span: Span,
+        order_by: &[WindowOrderBy],
+        frame: Option<&WindowFrame>,
+    ) -> Result<WindowFuncFrame> {
+        let frame = match frame {
+            Some(frame) => frame,
+            None => return Ok(WindowFuncFrame::default_frame(!order_by.is_empty())),
+        };
+
+        let units = match frame.units {
+            WindowFrameUnits::Rows => WindowFuncFrameUnits::Rows,
+            WindowFrameUnits::Range => WindowFuncFrameUnits::Range,
+        };
+        let start_bound = self.resolve_window_frame_bound(units, &frame.start_bound)?;
+        let end_bound = self.resolve_window_frame_bound(units, &frame.end_bound)?;
+
+        if matches!(start_bound, WindowFuncFrameBound::Following(None)) {
+            return Err(ErrorCode::SemanticError(
+                "frame start cannot be UNBOUNDED FOLLOWING".to_string(),
+            )
+            .set_span(span));
+        }
+        if matches!(end_bound, WindowFuncFrameBound::Preceding(None)) {
+            return Err(ErrorCode::SemanticError(
+                "frame end cannot be UNBOUNDED PRECEDING".to_string(),
+            )
+            .set_span(span));
+        }
+
+        let has_offset = |bound: &WindowFuncFrameBound| {
+            matches!(
+                bound,
+                WindowFuncFrameBound::Preceding(Some(_)) | WindowFuncFrameBound::Following(Some(_))
+            )
+        };
+        if units == WindowFuncFrameUnits::Range
+            && (has_offset(&start_bound) || has_offset(&end_bound))
+        {
+            if order_by.len() != 1 {
+                return Err(ErrorCode::SemanticError(
+                    "RANGE with offset PRECEDING/FOLLOWING requires exactly one ORDER BY column"
+                        .to_string(),
+                )
+                .set_span(span));
+            }
+            if !order_by[0].expr.data_type().remove_nullable().is_numeric() {
+                return Err(ErrorCode::SemanticError(
+                    "RANGE with offset PRECEDING/FOLLOWING requires a numeric ORDER BY column"
+                        .to_string(),
+                )
+                .set_span(span));
+            }
+        }
+
+        Ok(WindowFuncFrame {
+            units,
+            start_bound,
+            end_bound,
+        })
+    }
+
+    fn resolve_window_frame_bound(
+        &self,
+        units: WindowFuncFrameUnits,
+        bound: &WindowFrameBound,
+    ) -> Result<WindowFuncFrameBound> {
+        match bound {
+            WindowFrameBound::CurrentRow => Ok(WindowFuncFrameBound::CurrentRow),
+            WindowFrameBound::Preceding(offset) => Ok(WindowFuncFrameBound::Preceding(
+                offset
+                    .as_ref()
+                    .map(|offset| self.resolve_window_frame_offset(units, offset))
+                    .transpose()?,
+            )),
+            WindowFrameBound::Following(offset) => Ok(WindowFuncFrameBound::Following(
+                offset
+                    .as_ref()
+                    .map(|offset| self.resolve_window_frame_offset(units, offset))
+                    .transpose()?,
+            )),
+        }
+    }
+
+    fn resolve_window_frame_offset(
+        &self,
+        units: WindowFuncFrameUnits,
+        offset: &Expr,
+    ) -> Result<common_expression::Literal> {
+        match (units, offset) {
+            (
+                WindowFuncFrameUnits::Rows,
+                Expr::Literal {
+                    lit: lit @ Literal::Integer(_),
+                    ..
+                },
+            )
+            | (
+                WindowFuncFrameUnits::Range,
+                Expr::Literal {
+                    lit: lit @ (Literal::Integer(_) | Literal::Float(_)),
+                    ..
+                },
+            ) => {
+                let box (value, _) = self.resolve_literal(lit, None)?;
+                Ok(value)
+            }
+            (WindowFuncFrameUnits::Rows, _) => Err(ErrorCode::SemanticError(
+                "ROWS frame offset must be a non-negative integer constant".to_string(),
+            )
+            .set_span(offset.span())),
+            (WindowFuncFrameUnits::Range, _) => Err(ErrorCode::SemanticError(
+                "RANGE frame offset must be a non-negative numeric constant".to_string(),
+            )
+            .set_span(offset.span())),
+        }
+    }
+
+    /// Resolve `GROUPING(expr [, expr]*)`. The arguments are kept as is, and the function
+    /// will be rewritten to compute the result from `_grouping_id` in `GroupingChecker`.
+    #[async_recursion::async_recursion]
+    async fn resolve_grouping(
+        &mut self,
+        span: Span,
+        args: &[&Expr],
+    ) -> Result<Box<(ScalarExpr, DataType)>> {
+        if args.is_empty() {
+            return Err(ErrorCode::SemanticError(
+                "grouping function requires at least one argument".to_string(),
+            )
+            .set_span(span));
+        }
+
+        let mut arguments = Vec::with_capacity(args.len());
+        for arg in args.iter() {
+            let box (argument, _) = self.resolve(arg, None).await?;
+            arguments.push(argument);
+        }
+
+        let data_type = DataType::Number(NumberDataType::UInt32);
+        Ok(Box::new((
+            FunctionCall {
+                params: vec![],
+                arguments,
+                func_name: "grouping".to_string(),
+                return_type: Box::new(data_type.clone()),
+            }
+            .into(),
+            data_type,
+        )))
+    }
+
     /// Resolve function call.
     #[async_recursion::async_recursion]
     pub async fn resolve_function(
@@ -1589,6 +1957,7 @@ impl<'a> TypeChecker<'a> {
                             },
                             args: vec![arg_x.clone()],
                             params: vec![],
+                            window: None,
                         },
                         None,
                     )
@@ -1626,6 +1995,7 @@ impl<'a> TypeChecker<'a> {
                         },
                         args: vec![(*arg).clone()],
                         params: vec![],
+                        window: None,
                     };
 
                     new_args.push(is_not_null_expr);
@@ -2219,6 +2589,7 @@ impl<'a> TypeChecker<'a> {
                     name,
                     args,
                     params,
+                    window,
                 } => Ok(Expr::FunctionCall {
                     span: *span,
                     distinct: *distinct,
@@ -2228,6 +2599,7 @@ impl<'a> TypeChecker<'a> {
                         .map(|arg| self.clone_expr_with_replacement(arg, replacement_fn))
                         .collect::<Result<Vec<Expr>>>()?,
                     params: params.clone(),
+                    window: window.clone(),
                 }),
                 Expr::Case {
                     span,
@@ -17,6 +17,7 @@ use std::collections::HashSet;
 use common_ast::ast::Expr;
 use common_ast::ast::Identifier;
 use common_ast::ast::Literal;
+use common_ast::ast::Window;
 use common_ast::walk_expr;
 use common_ast::Visitor;
 use common_exception::ErrorCode;
@@ -86,6 +87,7 @@ impl<'ast> Visitor<'ast> for UDFValidator {
         name: &'ast Identifier,
         args: &'ast [Expr],
         _params: &'ast [Literal],
+        _window: &'ast Option<Window>,
     ) {
         let name = name.to_string();
         if !is_builtin_function(&name) && self.name.eq_ignore_ascii_case(&name) {
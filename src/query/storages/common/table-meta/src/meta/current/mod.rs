@@ -15,6 +15,7 @@
 pub use v0::ColumnMeta as SingleColumnMeta;
 pub use v1::TableSnapshotStatistics;
 pub use v2::BlockMeta;
+pub use v2::BlockOrigin;
 pub use v2::ColumnMeta;
 pub use v2::SegmentInfo;
 pub use v2::TableSnapshot;
@@ -16,6 +16,7 @@ mod segment;
 mod snapshot;
 
 pub use segment::BlockMeta;
+pub use segment::BlockOrigin;
 pub use segment::ColumnMeta;
 pub use segment::SegmentInfo;
 pub use snapshot::TableSnapshot;
@@ -59,6 +59,27 @@ pub struct BlockMeta {
     #[serde(default)]
     pub bloom_filter_index_size: u64,
     pub compression: Compression,
+    /// location of the deletion vector, which marks the rows deleted from the block
+    /// by DELETE in merge-on-read mode
+    #[serde(default)]
+    pub deletion_location: Option<Location>,
+    /// number of the rows marked in the deletion vector
+    #[serde(default)]
+    pub deleted_row_count: u64,
+    /// the blocks this block is compacted from, in the order of their rows
+    #[serde(default)]
+    pub compacted_from: Vec<BlockOrigin>,
+}
+
+/// A block which the rows of a compacted block come from.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlockOrigin {
+    /// location of the block compacted
+    pub location: Location,
+    /// location of the deletion vector the block had when it was compacted
+    pub deletion_location: Option<Location>,
+    /// number of the rows of the block kept by the compaction
+    pub row_count: u64,
 }
 
 impl BlockMeta {
@@ -86,12 +107,38 @@ impl BlockMeta {
             bloom_filter_index_location,
             bloom_filter_index_size,
             compression,
+            deletion_location: None,
+            deleted_row_count: 0,
+            compacted_from: vec![],
         }
     }
 
     pub fn compression(&self) -> Compression {
         self.compression
     }
+
+    /// Number of the rows not deleted by the deletion vector.
+    pub fn live_row_count(&self) -> u64 {
+        self.row_count - self.deleted_row_count
+    }
+
+    /// The origins of the rows of the block compacted from the blocks, a block compacted
+    /// before is replaced by its own origins, unless rows are deleted from it since.
+    pub fn compacted_origins(blocks: &[Arc<BlockMeta>]) -> Vec<BlockOrigin> {
+        let mut origins = vec![];
+        for block in blocks {
+            if block.deletion_location.is_none() && !block.compacted_from.is_empty() {
+                origins.extend(block.compacted_from.iter().cloned());
+            } else {
+                origins.push(BlockOrigin {
+                    location: block.location.clone(),
+                    deletion_location: block.deletion_location.clone(),
+                    row_count: block.live_row_count(),
+                });
+            }
+        }
+        origins
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq, EnumAsInner)]
@@ -194,6 +241,9 @@ impl BlockMeta {
             bloom_filter_index_location: None,
             bloom_filter_index_size: 0,
             compression: Compression::Lz4,
+            deletion_location: None,
+            deleted_row_count: 0,
+            compacted_from: vec![],
         }
     }
 
@@ -224,6 +274,9 @@ impl BlockMeta {
             bloom_filter_index_location: s.bloom_filter_index_location.clone(),
             bloom_filter_index_size: s.bloom_filter_index_size,
             compression: s.compression,
+            deletion_location: None,
+            deleted_row_count: 0,
+            compacted_from: vec![],
         }
     }
 }
@@ -20,6 +20,17 @@ pub const OPT_KEY_DATABASE_ID: &str = "database_id";
 pub const OPT_KEY_SNAPSHOT_LOCATION: &str = "snapshot_location";
 pub const OPT_KEY_STORAGE_FORMAT: &str = "storage_format";
 pub const OPT_KEY_TABLE_COMPRESSION: &str = "compression";
+/// Storage prefixes of the tables whose data files are shared by a cloned table,
+/// the table it is cloned from comes first. Separated by commas.
+pub const OPT_KEY_CLONED_FROM: &str = "cloned_from";
+/// Mark the rows deleted by DELETE in deletion vectors instead of rewriting the blocks,
+/// for the tables in parquet format only.
+pub const OPT_KEY_MERGE_ON_READ: &str = "merge_on_read";
+/// The number of hours the history of the table is kept for.
+pub const OPT_KEY_DATA_RETENTION_TIME: &str = "data_retention_time";
+/// Set on a dropped table whose data has been purged beyond its retention period,
+/// such a table can not be undropped.
+pub const OPT_KEY_DATA_PURGED: &str = "data_purged";
 
 /// Legacy table snapshot location key
 ///
@@ -38,6 +49,8 @@ pub static RESERVED_TABLE_OPTION_KEYS: Lazy<HashSet<&'static str>> = Lazy::new(|
     let mut r = HashSet::new();
     r.insert(OPT_KEY_DATABASE_ID);
     r.insert(OPT_KEY_LEGACY_SNAPSHOT_LOC);
+    r.insert(OPT_KEY_CLONED_FROM);
+    r.insert(OPT_KEY_DATA_PURGED);
     r
 });
 
@@ -46,6 +59,8 @@ pub static INTERNAL_TABLE_OPTION_KEYS: Lazy<HashSet<&'static str>> = Lazy::new(|
     let mut r = HashSet::new();
     r.insert(OPT_KEY_LEGACY_SNAPSHOT_LOC);
     r.insert(OPT_KEY_DATABASE_ID);
+    r.insert(OPT_KEY_CLONED_FROM);
+    r.insert(OPT_KEY_DATA_PURGED);
     r
 });
 
@@ -28,6 +28,7 @@ use common_expression::ColumnId;
 use common_expression::Scalar;
 use storages_common_table_meta::meta::ColumnMeta;
 use storages_common_table_meta::meta::Compression;
+use storages_common_table_meta::meta::Location;
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq)]
 pub struct FusePartInfo {
@@ -42,6 +43,8 @@ pub struct FusePartInfo {
     pub sort_min_max: Option<(Scalar, Scalar)>,
     /// page range in the file
     pub range: Option<Range<usize>>,
+    /// the rows deleted from the block, `nums_rows` still counts them
+    pub deletion_location: Option<Location>,
 }
 
 #[typetag::serde(name = "fuse")]
@@ -65,6 +68,7 @@ impl PartInfo for FusePartInfo {
 }
 
 impl FusePartInfo {
+    #[allow(clippy::too_many_arguments)]
     pub fn create(
         location: String,
         format_version: u64,
@@ -73,6 +77,7 @@ impl FusePartInfo {
         compression: Compression,
         sort_min_max: Option<(Scalar, Scalar)>,
         range: Option<Range<usize>>,
+        deletion_location: Option<Location>,
     ) -> Arc<Box<dyn PartInfo>> {
         Arc::new(Box::new(FusePartInfo {
             location,
@@ -82,6 +87,7 @@ impl FusePartInfo {
             compression,
             sort_min_max,
             range,
+            deletion_location,
         }))
     }
 
@@ -21,6 +21,8 @@ use std::sync::Arc;
 
 use common_catalog::catalog::StorageDescription;
 use common_catalog::plan::DataSourcePlan;
+use common_catalog::plan::MergeIntoInfo;
+use common_catalog::plan::MergeIntoSource;
 use common_catalog::plan::PartStatistics;
 use common_catalog::plan::Partitions;
 use common_catalog::plan::PushDownInfo;
@@ -495,7 +497,8 @@ impl Table for FuseTable {
 
     #[tracing::instrument(level = "debug", name = "fuse_table_optimize", skip(self, ctx), fields(ctx.id = ctx.get_id().as_str()))]
     async fn purge(&self, ctx: Arc<dyn TableContext>, keep_last_snapshot: bool) -> Result<()> {
-        self.do_purge(&ctx, keep_last_snapshot).await
+        self.do_purge(&ctx, keep_last_snapshot).await?;
+        Ok(())
     }
 
     #[tracing::instrument(level = "debug", name = "analyze", skip(self, ctx), fields(ctx.id = ctx.get_id().as_str()))]
@@ -571,6 +574,16 @@ impl Table for FuseTable {
             .await
     }
 
+    async fn merge_into(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        merge_info: MergeIntoInfo,
+        source: MergeIntoSource,
+        pipeline: &mut Pipeline,
+    ) -> Result<()> {
+        self.do_merge_into(ctx, merge_info, source, pipeline).await
+    }
+
     fn get_block_compact_thresholds(&self) -> BlockThresholds {
         let max_rows_per_block =
             self.get_option(FUSE_OPT_KEY_ROW_PER_BLOCK, DEFAULT_BLOCK_MAX_ROWS);
@@ -609,6 +622,15 @@ impl Table for FuseTable {
         self.do_revert_to(ctx.as_ref(), point).await
     }
 
+    async fn clone_from_table(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        source: Arc<dyn Table>,
+    ) -> Result<()> {
+        self.do_clone_from_table(ctx.as_ref(), source.as_ref())
+            .await
+    }
+
     fn support_prewhere(&self) -> bool {
         matches!(self.storage_format, FuseStorageFormat::Native)
     }
@@ -0,0 +1,145 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_arrow::arrow::bitmap::Bitmap;
+use common_arrow::arrow::bitmap::MutableBitmap;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::DataBlock;
+use opendal::Operator;
+use storages_common_table_meta::meta::Location;
+
+/// The rows deleted from a block by DELETE in merge-on-read mode.
+///
+/// The set bits mark the deleted rows, the length is the row count of the block. It is
+/// stored in its own file as the bytes of the bitmap, and applied as a filter when the
+/// block is read, until the block is rewritten by the compaction.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(into = "EncodedDeletionVector", try_from = "EncodedDeletionVector")]
+pub struct DeletionVector {
+    deleted: Bitmap,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EncodedDeletionVector {
+    num_rows: usize,
+    data: Vec<u8>,
+}
+
+impl From<DeletionVector> for EncodedDeletionVector {
+    fn from(v: DeletionVector) -> Self {
+        EncodedDeletionVector {
+            num_rows: v.deleted.len(),
+            data: v.to_bytes(),
+        }
+    }
+}
+
+impl TryFrom<EncodedDeletionVector> for DeletionVector {
+    type Error = ErrorCode;
+
+    fn try_from(v: EncodedDeletionVector) -> Result<Self> {
+        DeletionVector::from_bytes(v.data, v.num_rows)
+    }
+}
+
+impl DeletionVector {
+    pub const VERSION: u64 = 0;
+
+    pub fn from_bytes(data: Vec<u8>, num_rows: usize) -> Result<Self> {
+        let deleted = Bitmap::try_new(data, num_rows)
+            .map_err(|e| ErrorCode::StorageOther(format!("invalid deletion vector: {e}")))?;
+        Ok(DeletionVector { deleted })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (data, offset, _) = self.deleted.as_slice();
+        if offset == 0 {
+            return data.to_vec();
+        }
+        let aligned = MutableBitmap::from_iter(self.deleted.iter());
+        aligned.as_slice().to_vec()
+    }
+
+    /// Read the deletion vector of a block, returns None if no rows are deleted from it.
+    pub async fn try_read(
+        operator: &Operator,
+        location: Option<&Location>,
+        num_rows: usize,
+    ) -> Result<Option<Self>> {
+        match location {
+            None => Ok(None),
+            Some((path, _)) => {
+                let data = operator.object(path).read().await?;
+                Ok(Some(Self::from_bytes(data, num_rows)?))
+            }
+        }
+    }
+
+    pub fn try_sync_read(
+        operator: &Operator,
+        location: Option<&Location>,
+        num_rows: usize,
+    ) -> Result<Option<Self>> {
+        match location {
+            None => Ok(None),
+            Some((path, _)) => {
+                let data = operator.object(path).blocking_read()?;
+                Ok(Some(Self::from_bytes(data, num_rows)?))
+            }
+        }
+    }
+
+    /// Mark more rows as deleted, `predicates` tells which of the rows not deleted yet are
+    /// deleted this time, so its length is the count of the live rows in `origin`.
+    pub fn merge(origin: Option<&DeletionVector>, predicates: &Bitmap) -> DeletionVector {
+        let deleted = match origin {
+            None => predicates.clone(),
+            Some(origin) => {
+                let mut predicates = predicates.iter();
+                origin
+                    .deleted
+                    .iter()
+                    .map(|deleted| deleted || predicates.next().unwrap_or(false))
+                    .collect()
+            }
+        };
+        DeletionVector { deleted }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.deleted.len()
+    }
+
+    pub fn num_deleted(&self) -> usize {
+        self.deleted.len() - self.deleted.unset_bits()
+    }
+
+    /// The rows marked in this deletion vector but not in `previous`, which is an earlier
+    /// version of the deletion vector of the same block.
+    pub fn marked_since(&self, previous: Option<&DeletionVector>) -> Bitmap {
+        match previous {
+            None => self.deleted.clone(),
+            Some(previous) => &self.deleted & &!&previous.deleted,
+        }
+    }
+
+    /// Remove the deleted rows from the block read from the file.
+    pub fn filter(&self, block: DataBlock) -> Result<DataBlock> {
+        if self.num_deleted() == 0 {
+            return Ok(block);
+        }
+        block.filter_with_bitmap(&!&self.deleted)
+    }
+}
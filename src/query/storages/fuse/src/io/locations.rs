@@ -24,10 +24,14 @@ use storages_common_table_meta::meta::Versioned;
 use uuid::Uuid;
 
 use crate::constants::FUSE_TBL_BLOCK_PREFIX;
+use crate::constants::FUSE_TBL_CLONE_PREFIX;
+use crate::constants::FUSE_TBL_DELETION_VECTOR_PREFIX;
 use crate::constants::FUSE_TBL_SEGMENT_PREFIX;
 use crate::constants::FUSE_TBL_SNAPSHOT_PREFIX;
 use crate::constants::FUSE_TBL_SNAPSHOT_STATISTICS_PREFIX;
+use crate::constants::FUSE_TBL_STREAM_PREFIX;
 use crate::index::filters::BlockFilter;
+use crate::io::DeletionVector;
 use crate::FUSE_TBL_LAST_SNAPSHOT_HINT;
 use crate::FUSE_TBL_XOR_BLOOM_INDEX_PREFIX;
 
@@ -91,6 +95,20 @@ impl TableMetaLocationGenerator {
         )
     }
 
+    pub fn gen_deletion_vector_location(&self) -> Location {
+        let uuid = Uuid::new_v4();
+        (
+            format!(
+                "{}/{}/{}_v{}.bin",
+                &self.prefix,
+                FUSE_TBL_DELETION_VECTOR_PREFIX,
+                uuid.as_simple(),
+                DeletionVector::VERSION,
+            ),
+            DeletionVector::VERSION,
+        )
+    }
+
     pub fn gen_segment_info_location(&self) -> String {
         let segment_uuid = Uuid::new_v4().simple().to_string();
         format!(
@@ -133,6 +151,24 @@ impl TableMetaLocationGenerator {
     pub fn gen_last_snapshot_hint_location(&self) -> String {
         format!("{}/{}", &self.prefix, FUSE_TBL_LAST_SNAPSHOT_HINT)
     }
+
+    /// The directory of the markers, which register the tables cloned from this table.
+    pub fn clone_marker_prefix(&self) -> String {
+        format!("{}/{}/", &self.prefix, FUSE_TBL_CLONE_PREFIX)
+    }
+
+    pub fn clone_marker_location(&self, table_id: u64) -> String {
+        format!("{}{}", self.clone_marker_prefix(), table_id)
+    }
+
+    /// The directory of the markers, which register the streams on this table.
+    pub fn stream_marker_prefix(&self) -> String {
+        format!("{}/{}/", &self.prefix, FUSE_TBL_STREAM_PREFIX)
+    }
+
+    pub fn stream_marker_location(&self, stream_id: u64) -> String {
+        format!("{}{}", self.stream_marker_prefix(), stream_id)
+    }
 }
 
 trait SnapshotLocationCreator {
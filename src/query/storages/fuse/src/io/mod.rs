@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod deletion_vector;
 mod files;
 mod locations;
 mod read;
@@ -19,6 +20,7 @@ mod segments;
 mod snapshots;
 mod write;
 
+pub use deletion_vector::DeletionVector;
 pub use files::Files;
 pub use locations::TableMetaLocationGenerator;
 pub use read::BlockReader;
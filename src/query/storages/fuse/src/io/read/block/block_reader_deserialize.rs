@@ -26,6 +26,7 @@ use storages_common_table_meta::meta::Compression;
 
 use super::BlockReader;
 use crate::io::read::block::block_reader_merge_io::DataItem;
+use crate::io::DeletionVector;
 use crate::io::ReadSettings;
 use crate::io::UncompressedBuffer;
 use crate::FuseStorageFormat;
@@ -77,7 +78,7 @@ impl BlockReader {
 
         let num_rows = meta.row_count as usize;
 
-        match storage_format {
+        let block = match storage_format {
             FuseStorageFormat::Parquet => self.deserialize_parquet_chunks_with_buffer(
                 &meta.location.0,
                 num_rows,
@@ -94,6 +95,15 @@ impl BlockReader {
                 column_chunks,
                 None,
             ),
+        }?;
+
+        // The deleted rows are removed, so they are gone once the block is rewritten.
+        let deletion =
+            DeletionVector::try_read(&self.operator, meta.deletion_location.as_ref(), num_rows)
+                .await?;
+        match deletion {
+            None => Ok(block),
+            Some(deletion) => deletion.filter(block),
         }
     }
 }
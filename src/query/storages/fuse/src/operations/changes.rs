@@ -0,0 +1,162 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
+
+use common_catalog::table::Table;
+use common_catalog::table_context::TableContext;
+use common_exception::Result;
+use storages_common_cache::LoadParams;
+use storages_common_table_meta::meta::BlockMeta;
+use storages_common_table_meta::meta::Location;
+use storages_common_table_meta::meta::TableSnapshot;
+
+use crate::io::MetaReaders;
+use crate::io::SegmentsIO;
+use crate::io::TableMetaLocationGenerator;
+use crate::FuseTable;
+
+/// The blocks changed between two snapshots of a table.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockChanges {
+    /// The blocks of the latest snapshot only, paired with the ranges of their rows moved
+    /// from the blocks of the base snapshot by compaction, which are not changes.
+    pub added: Vec<(BlockMeta, Vec<Range<u64>>)>,
+    /// The blocks of the base snapshot only, with the deletion vectors they had then.
+    pub removed: Vec<BlockMeta>,
+    /// The blocks of both snapshots which have more rows marked as deleted since the base
+    /// snapshot, paired with the location of the deletion vector they had then.
+    pub marked: Vec<(BlockMeta, Option<Location>)>,
+}
+
+impl BlockChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.marked.is_empty()
+    }
+}
+
+impl FuseTable {
+    /// Diff the blocks of the current snapshot of this table against the snapshot at
+    /// `base_location`, all the blocks are added if there is no base snapshot.
+    ///
+    /// The segments shared by both snapshots are skipped without being read, since they
+    /// are immutable, only the blocks of the segments which differ are compared. The blocks
+    /// compacted from the blocks of the base snapshot are matched by their origins, the rows
+    /// moved are neither added nor removed.
+    pub async fn changes_since(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        base_location: Option<&str>,
+    ) -> Result<BlockChanges> {
+        let latest = self.read_table_snapshot().await?;
+        let base = match base_location {
+            None => None,
+            Some(location) => Some(self.read_snapshot_by_location(location).await?),
+        };
+
+        let latest_segments = latest.as_ref().map_or(&[][..], |s| s.segments.as_slice());
+        let base_segments = base.as_ref().map_or(&[][..], |s| s.segments.as_slice());
+        let latest_set = latest_segments.iter().collect::<HashSet<_>>();
+        let base_set = base_segments.iter().collect::<HashSet<_>>();
+        let latest_only = latest_segments
+            .iter()
+            .filter(|loc| !base_set.contains(loc))
+            .cloned()
+            .collect::<Vec<_>>();
+        let base_only = base_segments
+            .iter()
+            .filter(|loc| !latest_set.contains(loc))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let latest_blocks = self.read_segment_blocks(ctx.clone(), &latest_only).await?;
+        let mut base_blocks = self
+            .read_segment_blocks(ctx, &base_only)
+            .await?
+            .into_iter()
+            .map(|block| (block.location.0.clone(), block))
+            .collect::<HashMap<_, _>>();
+
+        let mut changes = BlockChanges::default();
+        let mut added = vec![];
+        for block in latest_blocks {
+            match base_blocks.remove(&block.location.0) {
+                None => added.push(block),
+                // the block is moved to another segment, e.g. by the compaction of segments.
+                Some(base) if base.deletion_location == block.deletion_location => {}
+                Some(base) => changes.marked.push((block, base.deletion_location)),
+            }
+        }
+        for block in added {
+            let moved = Self::take_compacted_origins(&block, &mut base_blocks);
+            changes.added.push((block, moved));
+        }
+        changes.removed = base_blocks.into_values().collect();
+        Ok(changes)
+    }
+
+    // Take the blocks of the base snapshot which the block is compacted from as they were,
+    // returns the ranges of the rows moved from them.
+    fn take_compacted_origins(
+        block: &BlockMeta,
+        base_blocks: &mut HashMap<String, BlockMeta>,
+    ) -> Vec<Range<u64>> {
+        let mut moved: Vec<Range<u64>> = vec![];
+        let mut start = 0;
+        for origin in &block.compacted_from {
+            let end = start + origin.row_count;
+            let unchanged = base_blocks.get(&origin.location.0).map_or(false, |base| {
+                base.deletion_location == origin.deletion_location
+            });
+            if unchanged {
+                base_blocks.remove(&origin.location.0);
+                match moved.last_mut() {
+                    Some(last) if last.end == start => last.end = end,
+                    _ => moved.push(start..end),
+                }
+            }
+            start = end;
+        }
+        moved
+    }
+
+    pub(crate) async fn read_snapshot_by_location(
+        &self,
+        location: &str,
+    ) -> Result<Arc<TableSnapshot>> {
+        let reader = MetaReaders::table_snapshot_reader(self.get_operator());
+        let params = LoadParams {
+            location: location.to_owned(),
+            len_hint: None,
+            ver: TableMetaLocationGenerator::snapshot_version(location),
+        };
+        reader.read(&params).await
+    }
+
+    async fn read_segment_blocks(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        segment_locations: &[Location],
+    ) -> Result<Vec<BlockMeta>> {
+        let segments_io = SegmentsIO::create(ctx, self.operator.clone(), self.schema());
+        let mut blocks = vec![];
+        for segment in segments_io.read_segments(segment_locations).await? {
+            blocks.extend(segment?.blocks.iter().map(|block| block.as_ref().clone()));
+        }
+        Ok(blocks)
+    }
+}
@@ -0,0 +1,105 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use common_catalog::table::Table;
+use common_catalog::table_context::TableContext;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use storages_common_table_meta::meta::TableSnapshot;
+use storages_common_table_meta::table::OPT_KEY_CLONED_FROM;
+use uuid::Uuid;
+
+use crate::io::TableMetaLocationGenerator;
+use crate::FuseTable;
+
+impl FuseTable {
+    /// Make this newly created table share the data of `source`.
+    ///
+    /// The first snapshot of this table references the segments of the snapshot of `source`,
+    /// no data files are copied. The data files are still owned by the tables which wrote
+    /// them, this table is registered to each of them by a marker file, so that their gc
+    /// keeps the files referenced by this table. The marker lists the segments shared, they
+    /// are kept until the first snapshot of this table is committed.
+    pub async fn do_clone_from_table(
+        &self,
+        ctx: &dyn TableContext,
+        source: &dyn Table,
+    ) -> Result<()> {
+        let source = FuseTable::try_from_table(source)?;
+        if source.table_info.meta.storage_params != self.table_info.meta.storage_params {
+            return Err(ErrorCode::StorageOther(format!(
+                "can not clone table {} into table {}, they are not in the same storage",
+                source.table_info.desc, self.table_info.desc,
+            )));
+        }
+        if self.snapshot_loc().await?.is_some() {
+            return Err(ErrorCode::StorageOther(format!(
+                "can not clone table {} into table {}, which is not empty",
+                source.table_info.desc, self.table_info.desc,
+            )));
+        }
+
+        let snapshot = match source.read_table_snapshot().await? {
+            Some(snapshot) => snapshot,
+            // nothing to share
+            None => return Ok(()),
+        };
+
+        // 1. register this table to the owners of the data files, the table cloned from
+        // and the tables it is cloned from, if any.
+        let mut owners = vec![source.meta_location_generator.prefix().to_owned()];
+        if let Some(cloned_from) = source.table_info.options().get(OPT_KEY_CLONED_FROM) {
+            owners.extend(cloned_from.split(',').map(|prefix| prefix.to_owned()));
+        }
+        let table_id = self.table_info.ident.table_id;
+        let shared_segments = serde_json::to_vec(&snapshot.segments)?;
+        for owner in &owners {
+            let marker = TableMetaLocationGenerator::with_prefix(owner.clone())
+                .clone_marker_location(table_id);
+            self.operator
+                .object(&marker)
+                .write(shared_segments.clone())
+                .await?;
+        }
+
+        // 2. commit the first snapshot, which shares the segments.
+        //
+        // the table statistics are not shared, they are collected by the gc of the
+        // table cloned from as a whole.
+        let new_snapshot = TableSnapshot::new(
+            Uuid::new_v4(),
+            &None,
+            None,
+            snapshot.schema.clone(),
+            snapshot.summary.clone(),
+            snapshot.segments.clone(),
+            self.cluster_key_meta.clone(),
+            None,
+        );
+        let mut table_info = self.table_info.clone();
+        table_info
+            .meta
+            .options
+            .insert(OPT_KEY_CLONED_FROM.to_owned(), owners.join(","));
+        FuseTable::commit_to_meta_server(
+            ctx,
+            &table_info,
+            &self.meta_location_generator,
+            new_snapshot,
+            None,
+            &self.operator,
+        )
+        .await
+    }
+}
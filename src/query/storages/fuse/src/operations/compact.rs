@@ -22,6 +22,7 @@ use common_pipeline_core::pipe::Pipe;
 use common_pipeline_core::pipe::PipeItem;
 use storages_common_table_meta::meta::TableSnapshot;
 
+use crate::io::SegmentsIO;
 use crate::operations::mutation::BlockCompactMutator;
 use crate::operations::mutation::CompactSource;
 use crate::operations::mutation::CompactTransform;
@@ -62,7 +63,10 @@ impl FuseTable {
             return Ok(false);
         };
 
-        if base_snapshot.summary.block_count <= 1 {
+        // a single block is still compacted if it has the deleted rows.
+        if base_snapshot.summary.block_count <= 1
+            && !self.has_deleted_rows(ctx.clone(), &base_snapshot).await?
+        {
             return Ok(false);
         }
 
@@ -81,6 +85,29 @@ impl FuseTable {
         }
     }
 
+    async fn has_deleted_rows(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        snapshot: &TableSnapshot,
+    ) -> Result<bool> {
+        if snapshot.summary.block_count == 0 {
+            return Ok(false);
+        }
+
+        let segments_io = SegmentsIO::create(ctx, self.operator.clone(), self.schema());
+        let segments = segments_io.read_segments(&snapshot.segments).await?;
+        for segment in segments {
+            if segment?
+                .blocks
+                .iter()
+                .any(|block| block.deletion_location.is_some())
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     async fn compact_segments(
         &self,
         ctx: Arc<dyn TableContext>,
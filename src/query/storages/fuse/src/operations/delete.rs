@@ -43,6 +43,7 @@ use common_pipeline_core::pipe::PipeItem;
 use common_sql::evaluator::BlockOperator;
 use storages_common_table_meta::meta::Location;
 use storages_common_table_meta::meta::TableSnapshot;
+use storages_common_table_meta::table::OPT_KEY_MERGE_ON_READ;
 
 use crate::operations::mutation::MutationAction;
 use crate::operations::mutation::MutationPartInfo;
@@ -234,6 +235,7 @@ impl FuseTable {
         projection.sort_by_key(|&i| source_col_indices[i]);
         let ops = vec![BlockOperator::Project { projection }];
 
+        let merge_on_read = self.get_option(OPT_KEY_MERGE_ON_READ, false);
         let max_threads = ctx.get_settings().get_max_threads()? as usize;
         // Add source pipe.
         pipeline.add_source(
@@ -247,6 +249,7 @@ impl FuseTable {
                     remain_reader.clone(),
                     ops.clone(),
                     self.storage_format,
+                    merge_on_read,
                 )
             },
             max_threads,
@@ -24,6 +24,8 @@ use common_catalog::table::Table;
 use common_catalog::table_context::TableContext;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_meta_app::schema::TableInfo;
+use futures::TryStreamExt;
 use storages_common_cache::CacheAccessor;
 use storages_common_cache_manager::BloomIndexMeta;
 use storages_common_cache_manager::CachedObject;
@@ -33,6 +35,7 @@ use storages_common_table_meta::meta::SnapshotId;
 use storages_common_table_meta::meta::TableSnapshot;
 use storages_common_table_meta::meta::TableSnapshotLite;
 use storages_common_table_meta::meta::TableSnapshotStatistics;
+use storages_common_table_meta::table::OPT_KEY_DATA_RETENTION_TIME;
 use tracing::info;
 use tracing::warn;
 
@@ -46,6 +49,31 @@ use crate::FuseTable;
 struct LocationTuple {
     block_location: HashSet<String>,
     bloom_location: HashSet<String>,
+    deletion_location: HashSet<String>,
+    // sizes of the block and bloom index files, by location
+    file_sizes: HashMap<String, u64>,
+}
+
+/// The files removed by a purge of the table.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PurgeStatistics {
+    pub snapshot_count: usize,
+    pub segment_count: usize,
+    pub block_count: usize,
+    pub bloom_count: usize,
+    pub deletion_count: usize,
+    /// Total size of the block and bloom index files removed.
+    pub reclaimed_bytes: u64,
+}
+
+impl PurgeStatistics {
+    pub fn file_count(&self) -> usize {
+        self.snapshot_count
+            + self.segment_count
+            + self.block_count
+            + self.bloom_count
+            + self.deletion_count
+    }
 }
 
 impl FuseTable {
@@ -53,7 +81,7 @@ impl FuseTable {
         &self,
         ctx: &Arc<dyn TableContext>,
         keep_last_snapshot: bool,
-    ) -> Result<()> {
+    ) -> Result<PurgeStatistics> {
         let snapshot_opt = match self.read_table_snapshot().await {
             Err(e) if e.code() == ErrorCode::STORAGE_NOT_FOUND => {
                 // concurrent gc: someone else has already collected this snapshot, ignore it
@@ -63,7 +91,7 @@ impl FuseTable {
                     self.table_info.desc,
                     self.table_info.ident,
                 );
-                return Ok(());
+                return Ok(PurgeStatistics::default());
             }
             Err(e) => return Err(e),
             Ok(v) => v,
@@ -87,10 +115,36 @@ impl FuseTable {
                 (SnapshotId::new_v4(), None, None)
             };
 
+        // 1.1 Files referenced by the tables cloned from this table, which must be kept.
+        let (mut segments_referenced_by_clones, mut locations_referenced_by_clones) =
+            self.get_clone_references(ctx).await?;
+
+        // 1.2 Snapshots at the offsets of the streams on this table, which are kept with
+        // their files, the changes of the streams are read against them.
+        let mut snapshots_pinned_by_streams = HashSet::new();
+        for offset in self.get_stream_offsets(ctx.as_ref()).await? {
+            let referenced = self
+                .get_block_locations(ctx.clone(), &offset.segments)
+                .await?;
+            segments_referenced_by_clones.extend(offset.segments.iter().map(|loc| loc.0.clone()));
+            locations_referenced_by_clones
+                .block_location
+                .extend(referenced.block_location);
+            locations_referenced_by_clones
+                .bloom_location
+                .extend(referenced.bloom_location);
+            locations_referenced_by_clones
+                .deletion_location
+                .extend(referenced.deletion_location);
+            snapshots_pinned_by_streams.insert(offset.snapshot_id);
+        }
+
         // 2. Get all snapshot(including root snapshot).
         let mut chained_snapshots = vec![];
         let mut all_segment_locations = HashSet::new();
         let mut orphan_snapshots = vec![];
+        let mut segment_referrers = HashMap::new();
+        let mut locations_referenced_by_retained = LocationTuple::default();
 
         let mut status_snapshot_scan_count = 0;
         let mut status_snapshot_scan_cost = 0;
@@ -120,22 +174,68 @@ impl FuseTable {
                 )
                 .await?;
 
+            let retention_interval = self.data_retention_interval(ctx.as_ref())?;
+            let mut snapshots_within_retention = HashSet::new();
+
             chained_snapshots = snapshot_lites_extended.chained_snapshot_lites;
 
+            // with the table option `data_retention_time`, the history of the table within
+            // retention period is kept as well, for time travel.
+            if keep_last_snapshot
+                && self
+                    .table_info
+                    .options()
+                    .contains_key(OPT_KEY_DATA_RETENTION_TIME)
+            {
+                let partitioned_snapshots = Self::apply_retention_rule(
+                    retention_interval,
+                    min_snapshot_timestamp,
+                    chained_snapshots,
+                );
+                chained_snapshots = partitioned_snapshots.beyond_retention;
+                snapshots_within_retention.extend(
+                    partitioned_snapshots
+                        .within_retention
+                        .into_iter()
+                        .map(|snapshot| snapshot.snapshot_id),
+                );
+            }
+
             // partition the orphan snapshots by retention interval
             let partitioned_snapshots = Self::apply_retention_rule(
-                ctx.as_ref(),
+                retention_interval,
                 min_snapshot_timestamp,
                 snapshot_lites_extended.orphan_snapshot_lites,
-            )?;
-
-            // filter out segments that still referenced by snapshot that within retention period
-            all_segment_locations = Self::filter_out_segments_within_retention(
+            );
+            snapshots_within_retention.extend(
                 partitioned_snapshots
                     .within_retention
                     .into_iter()
-                    .map(|snapshot| snapshot.snapshot_id)
-                    .collect(),
+                    .map(|snapshot| snapshot.snapshot_id),
+            );
+
+            // the snapshots which refer to the segments shared with the clones are kept
+            if !segments_referenced_by_clones.is_empty() {
+                segment_referrers = snapshot_lites_extended.segment_locations.clone();
+            }
+
+            // files of the segments referenced by snapshots within retention period are kept,
+            // the segments to be purged might still share them.
+            let segments_referenced_by_retained = snapshot_lites_extended
+                .segment_locations
+                .iter()
+                .filter(|(_, referrers)| !snapshots_within_retention.is_disjoint(referrers))
+                .map(|(location, _)| location.clone())
+                .collect::<Vec<_>>();
+            if !segments_referenced_by_retained.is_empty() {
+                locations_referenced_by_retained = self
+                    .get_block_locations(ctx.clone(), &segments_referenced_by_retained)
+                    .await?;
+            }
+
+            // filter out segments that still referenced by snapshot that within retention period
+            all_segment_locations = Self::filter_out_segments_within_retention(
+                snapshots_within_retention,
                 snapshot_lites_extended.segment_locations,
             );
 
@@ -173,16 +273,52 @@ impl FuseTable {
             }
         }
 
+        let chunk_size = ctx.get_settings().get_max_storage_io_requests()? as usize;
+
         // 3.2 Find all the segments need to be deleted.
+        let mut segments_shared_with_clones = HashSet::new();
         {
             for segment in &all_segment_locations {
                 // Skip the root snapshot segments if the keep_last_snapshot is true.
                 if keep_last_snapshot && segments_referenced_by_root.contains(segment) {
                     continue;
                 }
+                // Skip the segments of other tables.
+                if !self.is_owned_location(&segment.0) {
+                    continue;
+                }
+                if segments_referenced_by_clones.contains(&segment.0) {
+                    segments_shared_with_clones.insert(segment.clone());
+                    continue;
+                }
                 segments_to_be_purged.insert(segment.clone());
             }
+
+            // Segments whose files are still referenced by the clones are kept, along with the
+            // snapshots refer to them, the files can be found and collected by the later gc.
+            if !segments_referenced_by_clones.is_empty() {
+                let segment_locations = Vec::from_iter(segments_to_be_purged.iter().cloned());
+                for chunk in segment_locations.chunks(chunk_size) {
+                    let shared = self
+                        .get_segments_sharing_files(
+                            ctx.clone(),
+                            chunk,
+                            &locations_referenced_by_clones,
+                        )
+                        .await?;
+                    for segment in shared {
+                        segments_to_be_purged.remove(&segment);
+                        segments_shared_with_clones.insert(segment);
+                    }
+                }
+            }
         }
+        let snapshots_shared_with_clones: HashSet<SnapshotId> = segments_shared_with_clones
+            .iter()
+            .filter_map(|segment| segment_referrers.get(segment))
+            .flatten()
+            .cloned()
+            .collect();
 
         // 3.3 Find all the table statistic files need to be deleted
         {
@@ -207,14 +343,10 @@ impl FuseTable {
             }
         }
 
-        let chunk_size = ctx.get_settings().get_max_storage_io_requests()? as usize;
+        let mut statistics = PurgeStatistics::default();
 
         // 4. Purge segments&blocks by chunk size
         {
-            let mut status_block_to_be_purged_count = 0;
-            let mut status_bloom_to_be_purged_count = 0;
-            let mut status_segment_to_be_purged_count = 0;
-
             let start = Instant::now();
             let segment_locations = Vec::from_iter(segments_to_be_purged);
             for chunk in segment_locations.chunks(chunk_size) {
@@ -229,9 +361,21 @@ impl FuseTable {
                         {
                             continue;
                         }
+                        if !self.is_owned_location(loc)
+                            || locations_referenced_by_clones.block_location.contains(loc)
+                            || locations_referenced_by_retained
+                                .block_location
+                                .contains(loc)
+                        {
+                            continue;
+                        }
                         block_locations_to_be_pruged.insert(loc.to_string());
                     }
-                    status_block_to_be_purged_count += block_locations_to_be_pruged.len();
+                    statistics.block_count += block_locations_to_be_pruged.len();
+                    statistics.reclaimed_bytes += block_locations_to_be_pruged
+                        .iter()
+                        .filter_map(|loc| locations.file_sizes.get(loc))
+                        .sum::<u64>();
                     self.try_purge_location_files(ctx.clone(), block_locations_to_be_pruged)
                         .await?;
                 }
@@ -245,9 +389,21 @@ impl FuseTable {
                         {
                             continue;
                         }
+                        if !self.is_owned_location(loc)
+                            || locations_referenced_by_clones.bloom_location.contains(loc)
+                            || locations_referenced_by_retained
+                                .bloom_location
+                                .contains(loc)
+                        {
+                            continue;
+                        }
                         bloom_locations_to_be_pruged.insert(loc.to_string());
                     }
-                    status_bloom_to_be_purged_count += bloom_locations_to_be_pruged.len();
+                    statistics.bloom_count += bloom_locations_to_be_pruged.len();
+                    statistics.reclaimed_bytes += bloom_locations_to_be_pruged
+                        .iter()
+                        .filter_map(|loc| locations.file_sizes.get(loc))
+                        .sum::<u64>();
                     self.try_purge_location_files_and_cache::<BloomIndexMeta>(
                         ctx.clone(),
                         bloom_locations_to_be_pruged,
@@ -255,7 +411,33 @@ impl FuseTable {
                     .await?;
                 }
 
-                // 3. Try to purge segment file chunks.
+                // 3. Try to purge deletion vector file chunks.
+                {
+                    let mut deletion_locations_to_be_pruged = HashSet::new();
+                    for loc in &locations.deletion_location {
+                        if keep_last_snapshot
+                            && locations_referenced_by_root.deletion_location.contains(loc)
+                        {
+                            continue;
+                        }
+                        if !self.is_owned_location(loc)
+                            || locations_referenced_by_clones
+                                .deletion_location
+                                .contains(loc)
+                            || locations_referenced_by_retained
+                                .deletion_location
+                                .contains(loc)
+                        {
+                            continue;
+                        }
+                        deletion_locations_to_be_pruged.insert(loc.to_string());
+                    }
+                    statistics.deletion_count += deletion_locations_to_be_pruged.len();
+                    self.try_purge_location_files(ctx.clone(), deletion_locations_to_be_pruged)
+                        .await?;
+                }
+
+                // 4. Try to purge segment file chunks.
                 {
                     let segment_locations_to_be_purged = HashSet::from_iter(
                         chunk
@@ -272,14 +454,14 @@ impl FuseTable {
 
                 // Refresh status.
                 {
-                    status_segment_to_be_purged_count += chunk.len();
+                    statistics.segment_count += chunk.len();
                     let status = format!(
                         "gc: scan snapshot:{} takes:{} sec. block files purged:{}, bloom files purged:{}, segment files purged:{}, take:{} sec",
                         status_snapshot_scan_count,
                         status_snapshot_scan_cost,
-                        status_block_to_be_purged_count,
-                        status_bloom_to_be_purged_count,
-                        status_segment_to_be_purged_count,
+                        statistics.block_count,
+                        statistics.bloom_count,
+                        statistics.segment_count,
                         start.elapsed().as_secs()
                     );
                     self.data_metrics.set_status(&status);
@@ -290,15 +472,19 @@ impl FuseTable {
 
         // 5. Purge snapshots by chunk size(max_storage_io_requests).
         {
-            let mut status_purged_count = 0;
-
             let location_gen = self.meta_location_generator();
             let snapshots_to_be_purged_vec = Vec::from_iter(
-                snapshots_to_be_purged.into_iter().chain(
-                    orphan_snapshots
-                        .into_iter()
-                        .map(|lite| (lite.snapshot_id, lite.format_version)),
-                ),
+                snapshots_to_be_purged
+                    .into_iter()
+                    .chain(
+                        orphan_snapshots
+                            .into_iter()
+                            .map(|lite| (lite.snapshot_id, lite.format_version)),
+                    )
+                    .filter(|(id, _)| {
+                        !snapshots_shared_with_clones.contains(id)
+                            && !snapshots_pinned_by_streams.contains(id)
+                    }),
             );
 
             // let snapshots_to_be_purged_vec = Vec::from_iter(snapshots_to_be_purged);
@@ -320,11 +506,11 @@ impl FuseTable {
 
                 // Refresh status.
                 {
-                    status_purged_count += chunk.len();
+                    statistics.snapshot_count += chunk.len();
                     let status = format!(
                         "gc: snapshots need to be purged:{}, have purged:{}, take:{} sec",
                         status_need_purged_count,
-                        status_purged_count,
+                        statistics.snapshot_count,
                         start.elapsed().as_secs()
                     );
                     self.data_metrics.set_status(&status);
@@ -363,26 +549,40 @@ impl FuseTable {
             }
         }
 
-        Ok(())
+        Ok(statistics)
+    }
+
+    // The retention period of the table, given by the table option `data_retention_time`
+    // in hours, or by the setting `retention_period` if the option is not specified.
+    pub fn data_retention_interval(&self, ctx: &dyn TableContext) -> Result<Duration> {
+        let hours = match self.table_info.options().get(OPT_KEY_DATA_RETENTION_TIME) {
+            Some(value) => value.parse::<u64>().map_err(|_| {
+                ErrorCode::TableOptionInvalid(format!(
+                    "invalid value of table option {}: {}, expect the number of hours",
+                    OPT_KEY_DATA_RETENTION_TIME, value
+                ))
+            })?,
+            None => ctx.get_settings().get_retention_period()?,
+        };
+        Ok(Duration::hours(hours as i64))
     }
 
     // Partition snapshot_lites into two parts
     // - those are beyond retention period
     // - those are within retention period
     fn apply_retention_rule(
-        ctx: &dyn TableContext,
+        retention_interval: Duration,
         base_timestamp: Option<DateTime<Utc>>,
         snapshot_lites: Vec<TableSnapshotLite>,
-    ) -> Result<RetentionPartition> {
-        let retention_interval = Duration::hours(ctx.get_settings().get_retention_period()? as i64);
+    ) -> RetentionPartition {
         let retention_point = base_timestamp.map(|s| s - retention_interval);
         let (beyond_retention, within_retention) = snapshot_lites
             .into_iter()
             .partition(|lite| lite.timestamp < retention_point);
-        Ok(RetentionPartition {
+        RetentionPartition {
             beyond_retention,
             within_retention,
-        })
+        }
     }
 
     // filter out segments that are referenced by orphan snapshots
@@ -425,6 +625,137 @@ impl FuseTable {
             .await
     }
 
+    // Files written by other tables might be referenced by a cloned table, they are
+    // never collected by the gc of the clone.
+    fn is_owned_location(&self, location: &str) -> bool {
+        location
+            .strip_prefix(self.meta_location_generator.prefix())
+            .map_or(false, |path| path.starts_with('/'))
+    }
+
+    // Collect the files referenced by the tables cloned from this table, in any of
+    // their snapshots. The clones are registered by the markers in the `_clone`
+    // directory of this table.
+    async fn get_clone_references(
+        &self,
+        ctx: &Arc<dyn TableContext>,
+    ) -> Result<(HashSet<String>, LocationTuple)> {
+        let mut segments = HashSet::new();
+        let mut locations = LocationTuple::default();
+
+        let marker_prefix = self.meta_location_generator.clone_marker_prefix();
+        let mut markers = match self.operator.object(&marker_prefix).list().await {
+            Err(e) if e.kind() == opendal::ErrorKind::ObjectNotFound => {
+                return Ok((segments, locations));
+            }
+            Err(e) => return Err(e.into()),
+            Ok(v) => v,
+        };
+
+        let catalog = ctx.get_catalog(self.table_info.catalog())?;
+        while let Some(marker) = markers.try_next().await? {
+            let table_id = match marker.name().parse::<u64>() {
+                Ok(table_id) => table_id,
+                Err(_) => {
+                    warn!("invalid clone marker {}, ignored", marker.path());
+                    continue;
+                }
+            };
+
+            let (ident, meta) = match catalog.get_table_meta_by_id(table_id).await {
+                Err(e) if e.code() == ErrorCode::UNKNOWN_TABLE_ID => {
+                    // the clone has been dropped and collected, unregister it.
+                    self.operator.object(marker.path()).delete().await?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+                Ok(v) => v,
+            };
+            let cloned_table = FuseTable::do_create(TableInfo {
+                ident,
+                meta: meta.as_ref().clone(),
+                ..Default::default()
+            })?;
+
+            let segment_locations = match (
+                cloned_table.snapshot_loc().await?,
+                cloned_table.read_table_snapshot().await?,
+            ) {
+                (Some(root_snapshot_location), Some(root_snapshot)) => {
+                    let snapshots_io = SnapshotsIO::create(
+                        ctx.clone(),
+                        cloned_table.operator.clone(),
+                        cloned_table.snapshot_format_version().await?,
+                    );
+                    let snapshot_lites_extended = snapshots_io
+                        .read_snapshot_lites_ext(
+                            root_snapshot_location,
+                            None,
+                            ListSnapshotLiteOption::NeedSegmentsWithExclusion(None),
+                            root_snapshot.timestamp,
+                            |_| {},
+                        )
+                        .await?;
+                    Vec::from_iter(snapshot_lites_extended.segment_locations.into_keys())
+                }
+                // the clone is not committed yet, keep the segments listed by the marker.
+                _ => {
+                    let data = self.operator.object(marker.path()).read().await?;
+                    if data.is_empty() {
+                        continue;
+                    }
+                    serde_json::from_slice::<Vec<Location>>(&data)?
+                }
+            };
+            let referenced = cloned_table
+                .get_block_locations(ctx.clone(), &segment_locations)
+                .await?;
+
+            segments.extend(segment_locations.into_iter().map(|loc| loc.0));
+            locations.block_location.extend(referenced.block_location);
+            locations.bloom_location.extend(referenced.bloom_location);
+            locations
+                .deletion_location
+                .extend(referenced.deletion_location);
+        }
+
+        Ok((segments, locations))
+    }
+
+    // Find the segments which refer to the files referenced by the clones.
+    async fn get_segments_sharing_files(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        segment_locations: &[Location],
+        locations_referenced_by_clones: &LocationTuple,
+    ) -> Result<Vec<Location>> {
+        let fuse_segments = SegmentsIO::create(ctx, self.operator.clone(), self.schema());
+        let segments = fuse_segments.read_segments(segment_locations).await?;
+
+        let mut shared = vec![];
+        for (location, segment) in segment_locations.iter().zip(segments) {
+            let segment_info = match segment {
+                Err(e) if e.code() == ErrorCode::STORAGE_NOT_FOUND => continue,
+                Err(e) => return Err(e),
+                Ok(v) => v,
+            };
+            let is_shared = segment_info.blocks.iter().any(|block_meta| {
+                locations_referenced_by_clones
+                    .block_location
+                    .contains(&block_meta.location.0)
+                    || block_meta.deletion_location.as_ref().map_or(false, |loc| {
+                        locations_referenced_by_clones
+                            .deletion_location
+                            .contains(&loc.0)
+                    })
+            });
+            if is_shared {
+                shared.push(location.clone());
+            }
+        }
+        Ok(shared)
+    }
+
     async fn get_block_locations(
         &self,
         ctx: Arc<dyn TableContext>,
@@ -432,6 +763,8 @@ impl FuseTable {
     ) -> Result<LocationTuple> {
         let mut blocks = HashSet::new();
         let mut blooms = HashSet::new();
+        let mut deletions = HashSet::new();
+        let mut file_sizes = HashMap::new();
 
         let fuse_segments = SegmentsIO::create(ctx.clone(), self.operator.clone(), self.schema());
         let segments = fuse_segments.read_segments(segment_locations).await?;
@@ -452,19 +785,25 @@ impl FuseTable {
             };
             for block_meta in &segment_info.blocks {
                 blocks.insert(block_meta.location.0.clone());
-                blooms.insert(
-                    block_meta
-                        .bloom_filter_index_location
-                        .clone()
-                        .unwrap_or_default()
-                        .0,
-                );
+                file_sizes.insert(block_meta.location.0.clone(), block_meta.file_size);
+                let bloom_location = block_meta
+                    .bloom_filter_index_location
+                    .clone()
+                    .unwrap_or_default()
+                    .0;
+                file_sizes.insert(bloom_location.clone(), block_meta.bloom_filter_index_size);
+                blooms.insert(bloom_location);
+                if let Some(deletion_location) = &block_meta.deletion_location {
+                    deletions.insert(deletion_location.0.clone());
+                }
             }
         }
 
         Ok(LocationTuple {
             block_location: blocks,
             bloom_location: blooms,
+            deletion_location: deletions,
+            file_sizes,
         })
     }
 }
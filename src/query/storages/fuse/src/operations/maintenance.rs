@@ -0,0 +1,96 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use tracing::debug;
+
+use crate::io::SegmentsIO;
+use crate::table_functions::ClusteringInformation;
+use crate::FuseTable;
+use crate::Table;
+use crate::TableContext;
+use crate::DEFAULT_BLOCK_PER_SEGMENT;
+use crate::FUSE_OPT_KEY_BLOCK_PER_SEGMENT;
+
+/// The statistics telling whether a table is worth compacting or reclustering.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MaintenanceStatistics {
+    pub segment_count: usize,
+    // the number of segments the blocks take if all the segments are full.
+    pub expected_segment_count: usize,
+    pub block_count: usize,
+    // the blocks which are not large enough, they are merged by the block compaction.
+    pub undersized_block_count: usize,
+    // the average depth of the blocks on the cluster key, none if the table is not clustered.
+    pub average_depth: Option<f64>,
+}
+
+impl FuseTable {
+    /// Collects the maintenance statistics of the current snapshot, none if the table is empty.
+    ///
+    /// Only the snapshot is read for the compaction statistics, the segments are read
+    /// for the clustering depth of the clustered tables.
+    pub async fn maintenance_statistics(
+        &self,
+        ctx: Arc<dyn TableContext>,
+    ) -> Result<Option<MaintenanceStatistics>> {
+        let snapshot = match self.read_table_snapshot().await? {
+            Some(snapshot) => snapshot,
+            None => return Ok(None),
+        };
+
+        let block_per_seg =
+            self.get_option(FUSE_OPT_KEY_BLOCK_PER_SEGMENT, DEFAULT_BLOCK_PER_SEGMENT);
+        let block_count = snapshot.summary.block_count as usize;
+        let mut stats = MaintenanceStatistics {
+            segment_count: snapshot.segments.len(),
+            expected_segment_count: (block_count + block_per_seg - 1) / block_per_seg,
+            block_count,
+            undersized_block_count: (snapshot.summary.block_count
+                - snapshot.summary.perfect_block_count)
+                as usize,
+            average_depth: None,
+        };
+
+        let cluster_key = self.cluster_key_meta.as_ref().filter(|_| block_count > 0);
+        if let Some((_, plain_cluster_keys)) = cluster_key {
+            let segments_io = SegmentsIO::create(ctx.clone(), self.operator.clone(), self.schema());
+            let segments = segments_io
+                .read_segments(&snapshot.segments)
+                .await?
+                .into_iter()
+                .collect::<Result<Vec<_>>>()?;
+            let info = ClusteringInformation::new(
+                ctx.clone(),
+                self,
+                plain_cluster_keys.clone(),
+                self.cluster_keys(ctx.clone()),
+            );
+            // the blocks written before the cluster key is altered have no statistics
+            // of the current key, the depth is unknown then.
+            match info.average_depth(segments.iter().flat_map(|s| s.blocks.iter())) {
+                Ok(depth) => stats.average_depth = Some(depth),
+                Err(cause) => debug!(
+                    "clustering depth of table {} unknown: {}",
+                    self.name(),
+                    cause
+                ),
+            }
+        }
+
+        Ok(Some(stats))
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_catalog::plan::MergeIntoInfo;
+use common_catalog::plan::MergeIntoSource;
+use common_catalog::plan::Projection;
+use common_catalog::table::AppendMode;
+use common_catalog::table::Table;
+use common_catalog::table_context::TableContext;
+use common_exception::Result;
+use common_expression::type_check::check_function;
+use common_expression::types::DataType;
+use common_expression::Expr;
+use common_expression::RemoteExpr;
+use common_expression::Scalar;
+use common_functions::scalars::BUILTIN_FUNCTIONS;
+use common_pipeline_sources::AsyncSourcer;
+use storages_common_index::Index;
+use storages_common_index::RangeIndex;
+
+use crate::operations::mutation::MergeIntoAppendSource;
+use crate::operations::mutation::MergeIntoState;
+use crate::operations::mutation::MergeIntoTransform;
+use crate::operations::mutation::MutationAction;
+use crate::operations::mutation::MutationSink;
+use crate::operations::mutation::MutationSource;
+use crate::operations::mutation::SerializeDataTransform;
+use crate::pipelines::Pipeline;
+use crate::statistics::ClusterStatsGenerator;
+use crate::FuseTable;
+
+impl FuseTable {
+    /// MERGE INTO target USING source ON condition WHEN [NOT] MATCHED THEN ...
+    ///
+    /// The flow of Pipeline is the same as that of update, with a `MergeIntoTransform`
+    /// joining the target blocks with the source. The rows inserted by the not matched
+    /// clauses are written as new blocks, and committed in the same snapshot as the
+    /// rewritten blocks.
+    ///
+    /// The source blocks beyond the memory limit are spilled, only their join keys
+    /// are kept in memory, and they are read back when their rows are matched or
+    /// inserted.
+    pub async fn do_merge_into(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        merge_info: MergeIntoInfo,
+        source: MergeIntoSource,
+        pipeline: &mut Pipeline,
+    ) -> Result<()> {
+        let func_ctx = ctx.get_function_context()?;
+        let target_types = self
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| DataType::from(field.data_type()))
+            .collect::<Vec<_>>();
+
+        let target_keys = merge_info.target_keys.clone();
+        let state = MergeIntoState::try_create(func_ctx, target_types, merge_info, source).await?;
+
+        let snapshot = match self.read_table_snapshot().await? {
+            Some(snapshot) if snapshot.summary.row_count != 0 => snapshot,
+            _ => {
+                // The table is empty, all the source rows are not matched.
+                pipeline.add_source(
+                    |output| {
+                        AsyncSourcer::create(
+                            ctx.clone(),
+                            output,
+                            MergeIntoAppendSource::create(state.clone()),
+                        )
+                    },
+                    1,
+                )?;
+                self.do_append_data(ctx, pipeline, AppendMode::Normal, false)?;
+                return Ok(());
+            }
+        };
+
+        let projection = Projection::Columns(self.all_column_indices());
+        let block_reader = self.create_block_reader(projection.clone(), ctx.clone())?;
+        let filter = self.merge_into_pruning_filter(&target_keys, state.key_ranges())?;
+        self.mutation_block_pruning(ctx.clone(), filter, projection, &snapshot)
+            .await?;
+
+        let max_threads = ctx.get_settings().get_max_threads()? as usize;
+        pipeline.add_source(
+            |output| {
+                MutationSource::try_create(
+                    ctx.clone(),
+                    MutationAction::Update,
+                    output,
+                    Arc::new(None),
+                    block_reader.clone(),
+                    Arc::new(None),
+                    vec![],
+                    self.storage_format,
+                    false,
+                )
+            },
+            max_threads,
+        )?;
+
+        let max_rows_per_block = self.get_block_compact_thresholds().max_rows_per_block;
+        pipeline.add_transform(|input, output| {
+            MergeIntoTransform::try_create(state.clone(), input, output, max_rows_per_block)
+        })?;
+
+        pipeline.add_transform(|input, output| {
+            SerializeDataTransform::try_create(
+                ctx.clone(),
+                input,
+                output,
+                self,
+                ClusterStatsGenerator::default(),
+            )
+        })?;
+
+        self.try_add_mutation_transform(ctx.clone(), snapshot.segments.clone(), pipeline)?;
+
+        pipeline.add_sink(|input| {
+            MutationSink::try_create(self, ctx.clone(), snapshot.clone(), input)
+        })?;
+        Ok(())
+    }
+
+    /// Build a filter from the ranges of the source join keys to prune the target
+    /// blocks, a target row can only be matched if each of its keys is within the
+    /// range of the corresponding source keys.
+    fn merge_into_pruning_filter(
+        &self,
+        target_keys: &[RemoteExpr],
+        key_ranges: &[Option<(Scalar, Scalar)>],
+    ) -> Result<Option<RemoteExpr<String>>> {
+        let schema = self.schema();
+
+        let mut filter: Option<Expr<String>> = None;
+        for (target_key, key_range) in target_keys.iter().zip(key_ranges.iter()) {
+            let target_key = target_key
+                .as_expr(&BUILTIN_FUNCTIONS)
+                .project_column_ref(|index| schema.field(*index).name().clone());
+            let data_type = target_key.data_type().remove_nullable();
+            if !RangeIndex::supported_type(&data_type) {
+                continue;
+            }
+            let (min, max) = match key_range {
+                Some((min, max)) => (min.clone(), max.clone()),
+                // All the source keys are NULL, leave the pruning to the other keys.
+                None => continue,
+            };
+
+            let constant = |scalar: Scalar| Expr::Constant {
+                span: None,
+                scalar,
+                data_type: data_type.clone(),
+            };
+            let ge = check_function(
+                None,
+                "gte",
+                &[],
+                &[target_key.clone(), constant(min)],
+                &BUILTIN_FUNCTIONS,
+            )?;
+            let le = check_function(
+                None,
+                "lte",
+                &[],
+                &[target_key, constant(max)],
+                &BUILTIN_FUNCTIONS,
+            )?;
+            for predicate in [ge, le] {
+                filter = Some(match filter {
+                    Some(acc) => {
+                        check_function(None, "and", &[], &[acc, predicate], &BUILTIN_FUNCTIONS)?
+                    }
+                    None => predicate,
+                });
+            }
+        }
+
+        Ok(filter.map(|filter| filter.as_remote_expr()))
+    }
+}
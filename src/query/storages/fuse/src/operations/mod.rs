@@ -14,11 +14,15 @@
 
 mod analyze;
 mod append;
+mod changes;
+mod clone;
 mod commit;
 mod compact;
 mod delete;
 mod fuse_sink;
 mod gc;
+mod maintenance;
+mod merge_into;
 mod mutation;
 mod navigate;
 mod operation_log;
@@ -33,9 +37,12 @@ mod read;
 mod revert;
 pub mod util;
 
+pub use changes::BlockChanges;
 pub use compact::CompactOptions;
 pub use fuse_sink::BloomIndexState;
 pub use fuse_sink::FuseTableSink;
+pub use gc::PurgeStatistics;
+pub use maintenance::MaintenanceStatistics;
 pub use mutation::ReclusterMutator;
 pub use mutation::SegmentCompactMutator;
 pub use mutation::SegmentCompactionState;
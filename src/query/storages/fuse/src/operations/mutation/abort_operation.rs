@@ -26,6 +26,7 @@ pub struct AbortOperation {
     pub segments: Vec<String>,
     pub blocks: Vec<String>,
     pub bloom_filter_indexes: Vec<String>,
+    pub deletion_vectors: Vec<String>,
 }
 
 impl AbortOperation {
@@ -34,6 +35,7 @@ impl AbortOperation {
         self.blocks.extend(rhs.blocks.clone());
         self.bloom_filter_indexes
             .extend(rhs.bloom_filter_indexes.clone());
+        self.deletion_vectors.extend(rhs.deletion_vectors.clone());
     }
 
     pub fn add_block(&mut self, block: &BlockMeta) {
@@ -44,6 +46,10 @@ impl AbortOperation {
         }
     }
 
+    pub fn add_deletion_vector(&mut self, location: String) {
+        self.deletion_vectors.push(location);
+    }
+
     pub fn add_segment(&mut self, segment: String) {
         self.segments.push(segment);
     }
@@ -54,6 +60,7 @@ impl AbortOperation {
             .blocks
             .into_iter()
             .chain(self.bloom_filter_indexes.into_iter())
+            .chain(self.deletion_vectors.into_iter())
             .chain(self.segments.into_iter());
         fuse_file.remove_file_in_batch(locations).await
     }
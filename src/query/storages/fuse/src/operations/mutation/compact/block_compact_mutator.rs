@@ -167,6 +167,11 @@ impl CompactPartBuilder {
         segments.len() != 1
             || (segments[0].summary.block_count > 1
                 && segments[0].summary.perfect_block_count != segments[0].summary.block_count)
+            // the deleted rows are folded into the data by the compaction.
+            || segments[0]
+                .blocks
+                .iter()
+                .any(|b| b.deletion_location.is_some())
     }
 
     fn add(&mut self, segment: Arc<SegmentInfo>) -> Vec<Vec<Arc<SegmentInfo>>> {
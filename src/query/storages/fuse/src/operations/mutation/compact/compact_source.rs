@@ -160,7 +160,7 @@ impl CompactTaskBuilder {
     }
 
     fn add(&mut self, block: &Arc<BlockMeta>, thresholds: BlockThresholds) -> Vec<CompactTask> {
-        self.total_rows += block.row_count as usize;
+        self.total_rows += block.live_row_count() as usize;
         self.total_size += block.block_size as usize;
 
         if !thresholds.check_large_enough(self.total_rows, self.total_size) {
@@ -171,7 +171,7 @@ impl CompactTaskBuilder {
 
         let tasks = if !thresholds.check_for_compact(self.total_rows, self.total_size) {
             // blocks > 2N
-            let trivial_task = CompactTask::Trivial(block.clone());
+            let trivial_task = Self::create_task(vec![block.clone()]);
             if !self.blocks.is_empty() {
                 let compact_task = Self::create_task(std::mem::take(&mut self.blocks));
                 vec![compact_task, trivial_task]
@@ -198,7 +198,8 @@ impl CompactTaskBuilder {
     fn create_task(blocks: Vec<Arc<BlockMeta>>) -> CompactTask {
         match blocks.len() {
             0 => panic!("the blocks is empty"),
-            1 => CompactTask::Trivial(blocks[0].clone()),
+            // the block with the deletion vector is rewritten even if it is alone.
+            1 if blocks[0].deletion_location.is_none() => CompactTask::Trivial(blocks[0].clone()),
             _ => CompactTask::Normal(blocks),
         }
     }
@@ -32,6 +32,7 @@ use storages_common_cache::CacheAccessor;
 use storages_common_cache_manager::CacheManager;
 use storages_common_index::BloomIndex;
 use storages_common_table_meta::meta::BlockMeta;
+use storages_common_table_meta::meta::BlockOrigin;
 use storages_common_table_meta::meta::SegmentInfo;
 use storages_common_table_meta::meta::StatisticsOfColumns;
 use storages_common_table_meta::table::TableCompression;
@@ -63,6 +64,7 @@ enum State {
     CompactBlocks {
         blocks: Vec<DataBlock>,
         stats_of_columns: Vec<Vec<StatisticsOfColumns>>,
+        origins: Vec<Vec<BlockOrigin>>,
         trivals: VecDeque<Arc<BlockMeta>>,
     },
     SerializedBlocks(Vec<SerializeState>),
@@ -212,10 +214,11 @@ impl Processor for CompactTransform {
             State::CompactBlocks {
                 mut blocks,
                 stats_of_columns,
+                origins,
                 mut trivals,
             } => {
                 let mut serialize_states = Vec::new();
-                for stats in stats_of_columns {
+                for (stats, origins) in stats_of_columns.into_iter().zip(origins) {
                     let block_num = stats.len();
                     if block_num == 0 {
                         self.block_metas.push(trivals.pop_front().unwrap());
@@ -274,7 +277,7 @@ impl Processor for CompactTransform {
                     )?;
 
                     // new block meta.
-                    let new_meta = BlockMeta::new(
+                    let mut new_meta = BlockMeta::new(
                         row_count,
                         block_size,
                         file_size,
@@ -286,6 +289,8 @@ impl Processor for CompactTransform {
                         index_size,
                         self.write_settings.table_compression.into(),
                     );
+                    // the streams tell the rows moved by the compaction from the changes.
+                    new_meta.compacted_from = origins;
                     self.abort_operation.add_block(&new_meta);
                     self.block_metas.push(Arc::new(new_meta));
 
@@ -338,16 +343,18 @@ impl Processor for CompactTransform {
                 let mut trivals = VecDeque::new();
                 let mut memory_usage = 0;
                 let mut stats_of_columns = Vec::new();
+                let mut origins = Vec::new();
 
                 let block_reader = self.block_reader.as_ref();
                 while let Some(task) = self.compact_tasks.pop_front() {
-                    let metas = task.get_block_metas();
                     // Only one block, no need to do a compact.
-                    if metas.len() == 1 {
+                    if let CompactTask::Trivial(meta) = &task {
                         stats_of_columns.push(vec![]);
-                        trivals.push_back(metas[0].clone());
+                        origins.push(vec![]);
+                        trivals.push_back(meta.clone());
                         continue;
                     }
+                    let metas = task.get_block_metas();
 
                     memory_usage += metas.iter().fold(0, |acc, meta| {
                         let memory = meta.bloom_filter_index_size + meta.block_size;
@@ -362,6 +369,7 @@ impl Processor for CompactTransform {
                         break;
                     }
 
+                    origins.push(BlockMeta::compacted_origins(&metas));
                     let mut meta_stats = Vec::with_capacity(metas.len());
                     for meta in metas {
                         let progress_values = ProgressValues {
@@ -401,6 +409,7 @@ impl Processor for CompactTransform {
                 self.state = State::CompactBlocks {
                     blocks,
                     stats_of_columns,
+                    origins,
                     trivals,
                 }
             }
@@ -0,0 +1,587 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_catalog::plan::MergeIntoInfo;
+use common_catalog::plan::MergeIntoSource;
+use common_catalog::plan::MergeIntoSourceBlock;
+use common_catalog::plan::MergeMatchedOperation;
+use common_catalog::plan::SpilledBlockReader;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::types::DataType;
+use common_expression::BlockEntry;
+use common_expression::Column;
+use common_expression::ColumnBuilder;
+use common_expression::DataBlock;
+use common_expression::Evaluator;
+use common_expression::Expr;
+use common_expression::FieldIndex;
+use common_expression::FunctionContext;
+use common_expression::RemoteExpr;
+use common_expression::Scalar;
+use common_expression::Value;
+use common_functions::scalars::BUILTIN_FUNCTIONS;
+use common_pipeline_sources::AsyncSource;
+
+use crate::operations::mutation::AppendDataMeta;
+use crate::pipelines::processors::port::InputPort;
+use crate::pipelines::processors::port::OutputPort;
+use crate::pipelines::processors::processor::Event;
+use crate::pipelines::processors::processor::ProcessorPtr;
+use crate::pipelines::processors::Processor;
+
+enum MatchedAction {
+    Update(Vec<(FieldIndex, Expr)>),
+    Delete,
+}
+
+/// The source blocks of `MERGE INTO`, whose rows are numbered across the blocks.
+struct SourceBlocks {
+    blocks: Vec<MergeIntoSourceBlock>,
+    /// The number of the first row of each block.
+    offsets: Vec<usize>,
+    spill_reader: Option<Arc<dyn SpilledBlockReader>>,
+}
+
+impl SourceBlocks {
+    async fn read_block(&self, index: usize) -> Result<DataBlock> {
+        match (&self.blocks[index], &self.spill_reader) {
+            (MergeIntoSourceBlock::Memory(block), _) => Ok(block.clone()),
+            (MergeIntoSourceBlock::Spilled { location, .. }, Some(reader)) => {
+                reader.read_spilled(location).await
+            }
+            (MergeIntoSourceBlock::Spilled { .. }, None) => Err(ErrorCode::Internal(
+                "The source of MERGE INTO is spilled without a reader. It's a bug",
+            )),
+        }
+    }
+
+    /// Take the rows by their numbers, only the blocks containing them are read.
+    async fn take(&self, rows: &[usize]) -> Result<DataBlock> {
+        let mut blocks = vec![];
+        let mut read_blocks: HashMap<usize, usize> = HashMap::new();
+        let mut indices = Vec::with_capacity(rows.len());
+        for row in rows {
+            let index = self.offsets.partition_point(|offset| offset <= row) - 1;
+            let block_idx = match read_blocks.get(&index) {
+                Some(block_idx) => *block_idx,
+                None => {
+                    blocks.push(self.read_block(index).await?);
+                    read_blocks.insert(index, blocks.len() - 1);
+                    blocks.len() - 1
+                }
+            };
+            indices.push((block_idx, row - self.offsets[index], 1));
+        }
+        Ok(DataBlock::take_blocks(&blocks, &indices))
+    }
+}
+
+/// The state of `MERGE INTO` shared by all the [`MergeIntoTransform`]s.
+///
+/// Only the join keys of the source rows are kept in memory, together with the
+/// source blocks that are not spilled.
+pub struct MergeIntoState {
+    func_ctx: FunctionContext,
+    target_types: Vec<DataType>,
+    source: SourceBlocks,
+    /// Maps the join keys of the source to the numbers of the source rows.
+    source_rows: HashMap<Vec<Scalar>, Vec<usize>>,
+    /// The min and max values of each join key of the source, NULL excluded.
+    key_ranges: Vec<Option<(Scalar, Scalar)>>,
+    target_keys: Vec<Expr>,
+    join_filter: Option<Expr>,
+    matched: Vec<(Option<Expr>, MatchedAction)>,
+    not_matched: Vec<(Option<Expr>, Vec<Expr>)>,
+
+    /// Whether the source rows have been matched by some target row.
+    matched_rows: Vec<AtomicBool>,
+    /// The number of transforms that are still processing the target blocks.
+    running_transforms: AtomicUsize,
+}
+
+impl MergeIntoState {
+    /// Read the source blocks once to map their join keys, the spilled blocks are
+    /// read again when their rows are matched or inserted.
+    pub async fn try_create(
+        func_ctx: FunctionContext,
+        target_types: Vec<DataType>,
+        merge_info: MergeIntoInfo,
+        source: MergeIntoSource,
+    ) -> Result<Arc<Self>> {
+        let source_keys = merge_info
+            .source_keys
+            .iter()
+            .map(|key| key.as_expr(&BUILTIN_FUNCTIONS))
+            .collect::<Vec<_>>();
+        let mut source_rows: HashMap<Vec<Scalar>, Vec<usize>> = HashMap::new();
+        let mut key_ranges: Vec<Option<(Scalar, Scalar)>> = vec![None; source_keys.len()];
+        let mut offsets = Vec::with_capacity(source.blocks.len());
+        let mut num_rows = 0;
+        let blocks = source
+            .blocks
+            .into_iter()
+            .map(|block| {
+                offsets.push(num_rows);
+                num_rows += block.num_rows();
+                match block {
+                    MergeIntoSourceBlock::Memory(block) => {
+                        MergeIntoSourceBlock::Memory(block.convert_to_full())
+                    }
+                    spilled => spilled,
+                }
+            })
+            .collect();
+        let source = SourceBlocks {
+            blocks,
+            offsets,
+            spill_reader: source.spill_reader,
+        };
+        for index in 0..source.blocks.len() {
+            let block = source.read_block(index).await?;
+            let key_columns = source_keys
+                .iter()
+                .map(|key| eval_column(func_ctx, &block, key))
+                .collect::<Result<Vec<_>>>()?;
+            for row in 0..block.num_rows() {
+                if let Some(key) = row_key(&key_columns, row) {
+                    for (range, value) in key_ranges.iter_mut().zip(key.iter()) {
+                        *range = match range.take() {
+                            Some((min, max)) => {
+                                Some((min.min(value.clone()), max.max(value.clone())))
+                            }
+                            None => Some((value.clone(), value.clone())),
+                        };
+                    }
+                    source_rows
+                        .entry(key)
+                        .or_default()
+                        .push(source.offsets[index] + row);
+                }
+            }
+        }
+
+        let as_expr = |expr: Option<RemoteExpr>| expr.map(|expr| expr.as_expr(&BUILTIN_FUNCTIONS));
+        let matched = merge_info
+            .matched
+            .into_iter()
+            .map(|clause| {
+                let action = match clause.operation {
+                    MergeMatchedOperation::Update(update_list) => MatchedAction::Update(
+                        update_list
+                            .into_iter()
+                            .map(|(index, expr)| (index, expr.as_expr(&BUILTIN_FUNCTIONS)))
+                            .collect(),
+                    ),
+                    MergeMatchedOperation::Delete => MatchedAction::Delete,
+                };
+                (as_expr(clause.condition), action)
+            })
+            .collect();
+        let not_matched = merge_info
+            .not_matched
+            .into_iter()
+            .map(|clause| {
+                let values = clause
+                    .values
+                    .iter()
+                    .map(|expr| expr.as_expr(&BUILTIN_FUNCTIONS))
+                    .collect();
+                (as_expr(clause.condition), values)
+            })
+            .collect();
+
+        Ok(Arc::new(MergeIntoState {
+            func_ctx,
+            target_types,
+            source,
+            source_rows,
+            key_ranges,
+            target_keys: merge_info
+                .target_keys
+                .iter()
+                .map(|key| key.as_expr(&BUILTIN_FUNCTIONS))
+                .collect(),
+            join_filter: as_expr(merge_info.join_filter),
+            matched,
+            not_matched,
+            matched_rows: (0..num_rows).map(|_| AtomicBool::new(false)).collect(),
+            running_transforms: AtomicUsize::new(0),
+        }))
+    }
+
+    /// The min and max values of each join key of the source, `None` if all the
+    /// values of the key are NULL.
+    pub fn key_ranges(&self) -> &[Option<(Scalar, Scalar)>] {
+        &self.key_ranges
+    }
+
+    pub fn num_source_blocks(&self) -> usize {
+        self.source.blocks.len()
+    }
+
+    /// Apply the matched clauses to a target block.
+    ///
+    /// Returns `None` if no row of the block is updated or deleted.
+    async fn merge_matched(&self, target: DataBlock) -> Result<Option<DataBlock>> {
+        let target = target.convert_to_full();
+        let num_rows = target.num_rows();
+
+        let key_columns = self
+            .target_keys
+            .iter()
+            .map(|key| eval_column(self.func_ctx, &target, key))
+            .collect::<Result<Vec<_>>>()?;
+        let mut target_indices = vec![];
+        let mut source_indices = vec![];
+        for row in 0..num_rows {
+            if let Some(source_rows) = row_key(&key_columns, row)
+                .as_ref()
+                .and_then(|key| self.source_rows.get(key))
+            {
+                for source_row in source_rows {
+                    target_indices.push(row);
+                    source_indices.push(*source_row);
+                }
+            }
+        }
+        if target_indices.is_empty() {
+            return Ok(None);
+        }
+
+        // The joined block is the matched target rows followed by the matched source rows.
+        let mut joined = target.take(&target_indices)?;
+        for entry in self.source.take(&source_indices).await?.columns() {
+            joined.add_column(entry.clone());
+        }
+        if let Some(join_filter) = &self.join_filter {
+            let positions = eval_predicate(self.func_ctx, &joined, join_filter)?
+                .into_iter()
+                .enumerate()
+                .filter_map(|(pos, selected)| selected.then_some(pos))
+                .collect::<Vec<_>>();
+            if positions.is_empty() {
+                return Ok(None);
+            }
+            target_indices = positions.iter().map(|pos| target_indices[*pos]).collect();
+            source_indices = positions.iter().map(|pos| source_indices[*pos]).collect();
+            joined = joined.take(&positions)?;
+        }
+
+        for source_row in source_indices.iter() {
+            self.matched_rows[*source_row].store(true, Ordering::Relaxed);
+        }
+
+        // Decide which clause applies to each target row, the first satisfied clause wins.
+        let mut actions: Vec<Option<(usize, usize)>> = vec![None; num_rows];
+        let mut remain = vec![true; target_indices.len()];
+        let mut changed = false;
+        for (clause_idx, (condition, _)) in self.matched.iter().enumerate() {
+            let predicates = match condition {
+                Some(condition) => eval_predicate(self.func_ctx, &joined, condition)?,
+                None => vec![true; target_indices.len()],
+            };
+            for (pos, target_row) in target_indices.iter().enumerate() {
+                if remain[pos] && predicates[pos] {
+                    remain[pos] = false;
+                    if actions[*target_row].is_some() {
+                        return Err(ErrorCode::BadArguments(
+                            "MERGE INTO failed, a target row matched more than one source row",
+                        ));
+                    }
+                    actions[*target_row] = Some((clause_idx, pos));
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return Ok(None);
+        }
+
+        // Evaluate the updated values on the joined block.
+        let mut updated_columns: HashMap<(usize, FieldIndex), Column> = HashMap::new();
+        for (clause_idx, (_, action)) in self.matched.iter().enumerate() {
+            if let MatchedAction::Update(update_list) = action {
+                for (field, expr) in update_list {
+                    let column = eval_column(self.func_ctx, &joined, expr)?;
+                    updated_columns.insert((clause_idx, *field), column);
+                }
+            }
+        }
+
+        let kept_rows = (0..num_rows)
+            .filter(|row| {
+                !matches!(actions[*row], Some((clause_idx, _))
+                    if matches!(self.matched[clause_idx].1, MatchedAction::Delete))
+            })
+            .collect::<Vec<_>>();
+        let mut columns = Vec::with_capacity(self.target_types.len());
+        for (field, data_type) in self.target_types.iter().enumerate() {
+            let origin = target.get_by_offset(field).value.as_column().unwrap();
+            let mut builder = ColumnBuilder::with_capacity(data_type, kept_rows.len());
+            for row in kept_rows.iter() {
+                let updated = actions[*row].and_then(|(clause_idx, pos)| {
+                    updated_columns
+                        .get(&(clause_idx, field))
+                        .and_then(|column| column.index(pos))
+                });
+                match updated {
+                    Some(value) => builder.push(value),
+                    None => builder.push(origin.index(*row).unwrap()),
+                }
+            }
+            columns.push(BlockEntry {
+                data_type: data_type.clone(),
+                value: Value::Column(builder.build()),
+            });
+        }
+        Ok(Some(DataBlock::new(columns, kept_rows.len())))
+    }
+
+    /// Apply the not matched clauses to the rows of a source block that are not
+    /// matched by any target row.
+    ///
+    /// Returns `None` if there is no row to insert.
+    pub async fn merge_not_matched(&self, block_index: usize) -> Result<Option<DataBlock>> {
+        let offset = self.source.offsets[block_index];
+        let num_rows = self.source.blocks[block_index].num_rows();
+        let mut remain = (0..num_rows)
+            .filter(|row| !self.matched_rows[offset + row].load(Ordering::Relaxed))
+            .collect::<Vec<_>>();
+        if remain.is_empty() || self.not_matched.is_empty() {
+            return Ok(None);
+        }
+
+        let block = self.source.read_block(block_index).await?;
+        let mut blocks = vec![];
+        for (condition, values) in self.not_matched.iter() {
+            if remain.is_empty() {
+                break;
+            }
+            let source = block.take(&remain)?;
+            let predicates = match condition {
+                Some(condition) => eval_predicate(self.func_ctx, &source, condition)?,
+                None => vec![true; remain.len()],
+            };
+            let (selected, rest): (Vec<_>, Vec<_>) = remain
+                .into_iter()
+                .zip(predicates.into_iter())
+                .partition(|(_, selected)| *selected);
+            remain = rest.into_iter().map(|(row, _)| row).collect();
+            if selected.is_empty() {
+                continue;
+            }
+
+            let source =
+                block.take(&selected.into_iter().map(|(row, _)| row).collect::<Vec<_>>())?;
+            let columns = values
+                .iter()
+                .map(|expr| eval_column(self.func_ctx, &source, expr))
+                .collect::<Result<Vec<_>>>()?;
+            blocks.push(DataBlock::new_from_columns(columns));
+        }
+
+        if blocks.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(DataBlock::concat(&blocks)?))
+    }
+}
+
+fn eval_column(func_ctx: FunctionContext, block: &DataBlock, expr: &Expr) -> Result<Column> {
+    let evaluator = Evaluator::new(block, func_ctx, &BUILTIN_FUNCTIONS);
+    let value = evaluator.run(expr)?;
+    Ok(value.convert_to_full_column(expr.data_type(), block.num_rows()))
+}
+
+fn eval_predicate(func_ctx: FunctionContext, block: &DataBlock, expr: &Expr) -> Result<Vec<bool>> {
+    let column = eval_column(func_ctx, block, expr)?;
+    let bitmap = column
+        .as_boolean()
+        .ok_or_else(|| ErrorCode::Internal("The predicate must be boolean. It's a bug"))?;
+    Ok(bitmap.iter().collect())
+}
+
+/// Returns `None` if any of the keys is NULL, which never matches.
+fn row_key(key_columns: &[Column], row: usize) -> Option<Vec<Scalar>> {
+    key_columns
+        .iter()
+        .map(|column| match column.index(row).unwrap().to_owned() {
+            Scalar::Null => None,
+            scalar => Some(scalar),
+        })
+        .collect()
+}
+
+/// Applies `MERGE INTO` to the blocks of the target table.
+///
+/// The matched blocks are rewritten and sent to `SerializeDataTransform` with their
+/// origin meta, the other blocks are sent without meta and left untouched. The
+/// last transform to finish also emits the rows to be inserted by the not matched
+/// clauses, one source block at a time, which are marked with `AppendDataMeta`.
+pub struct MergeIntoTransform {
+    state: Arc<MergeIntoState>,
+    input: Arc<InputPort>,
+    output: Arc<OutputPort>,
+    input_data: Option<DataBlock>,
+    output_data: VecDeque<DataBlock>,
+    max_rows_per_block: usize,
+    finished: bool,
+    /// The source blocks whose not matched rows are to be inserted.
+    append_blocks: Range<usize>,
+}
+
+impl MergeIntoTransform {
+    pub fn try_create(
+        state: Arc<MergeIntoState>,
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        max_rows_per_block: usize,
+    ) -> Result<ProcessorPtr> {
+        state.running_transforms.fetch_add(1, Ordering::SeqCst);
+        Ok(ProcessorPtr::create(Box::new(MergeIntoTransform {
+            state,
+            input,
+            output,
+            input_data: None,
+            output_data: VecDeque::new(),
+            max_rows_per_block,
+            finished: false,
+            append_blocks: 0..0,
+        })))
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for MergeIntoTransform {
+    fn name(&self) -> String {
+        "MergeIntoTransform".to_string()
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        if self.output.is_finished() {
+            self.input.finish();
+            return Ok(Event::Finished);
+        }
+
+        if !self.output.can_push() {
+            return Ok(Event::NeedConsume);
+        }
+
+        if let Some(data_block) = self.output_data.pop_front() {
+            self.output.push_data(Ok(data_block));
+            return Ok(Event::NeedConsume);
+        }
+
+        // The source rows may be spilled, so they are read asynchronously.
+        if self.input_data.is_some() || !self.append_blocks.is_empty() {
+            return Ok(Event::Async);
+        }
+
+        if self.input.has_data() {
+            self.input_data = Some(self.input.pull_data().unwrap()?);
+            return Ok(Event::Async);
+        }
+
+        if self.input.is_finished() {
+            if !self.finished {
+                self.finished = true;
+                // The last finished transform sees all the matched source rows.
+                if self.state.running_transforms.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    self.append_blocks = 0..self.state.num_source_blocks();
+                    if !self.append_blocks.is_empty() {
+                        return Ok(Event::Async);
+                    }
+                }
+            }
+            self.output.finish();
+            return Ok(Event::Finished);
+        }
+
+        self.input.set_need_data();
+        Ok(Event::NeedData)
+    }
+
+    async fn async_process(&mut self) -> Result<()> {
+        if let Some(mut data_block) = self.input_data.take() {
+            let meta = data_block.take_meta();
+            let output = match (meta, data_block.is_empty()) {
+                (Some(meta), false) => match self.state.merge_matched(data_block).await? {
+                    Some(block) => block.add_meta(Some(meta))?,
+                    None => DataBlock::empty(),
+                },
+                _ => DataBlock::empty(),
+            };
+            self.output_data.push_back(output);
+        } else if let Some(block_index) = self.append_blocks.next() {
+            if let Some(block) = self.state.merge_not_matched(block_index).await? {
+                let num_rows = block.num_rows();
+                let mut start = 0;
+                while start < num_rows {
+                    let end = (start + self.max_rows_per_block).min(num_rows);
+                    let meta = AppendDataMeta::create();
+                    self.output_data
+                        .push_back(block.slice(start..end).add_meta(Some(meta))?);
+                    start = end;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Emits the rows inserted by the not matched clauses when the target table is
+/// empty, one source block at a time.
+pub struct MergeIntoAppendSource {
+    state: Arc<MergeIntoState>,
+    append_blocks: Range<usize>,
+}
+
+impl MergeIntoAppendSource {
+    pub fn create(state: Arc<MergeIntoState>) -> Self {
+        let append_blocks = 0..state.num_source_blocks();
+        MergeIntoAppendSource {
+            state,
+            append_blocks,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncSource for MergeIntoAppendSource {
+    const NAME: &'static str = "MergeIntoAppendSource";
+
+    #[async_trait::unboxed_simple]
+    async fn generate(&mut self) -> Result<Option<DataBlock>> {
+        for block_index in self.append_blocks.by_ref() {
+            if let Some(block) = self.state.merge_not_matched(block_index).await? {
+                return Ok(Some(block));
+            }
+        }
+        Ok(None)
+    }
+}
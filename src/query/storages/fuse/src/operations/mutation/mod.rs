@@ -15,6 +15,7 @@
 pub mod abort_operation;
 pub mod base_mutator;
 mod compact;
+mod merge_into_transform;
 pub mod mutation_meta;
 mod mutation_part;
 pub mod mutation_sink;
@@ -32,6 +33,10 @@ pub use compact::MergeSegmentsTransform;
 pub use compact::SegmentCompactMutator;
 pub use compact::SegmentCompactionState;
 pub use compact::SegmentCompactor;
+pub use merge_into_transform::MergeIntoAppendSource;
+pub use merge_into_transform::MergeIntoState;
+pub use merge_into_transform::MergeIntoTransform;
+pub use mutation_meta::AppendDataMeta;
 pub use mutation_meta::Mutation;
 pub use mutation_meta::MutationSinkMeta;
 pub use mutation_meta::MutationTransformMeta;
@@ -25,12 +25,15 @@ use storages_common_table_meta::meta::ClusterStatistics;
 use storages_common_table_meta::meta::Location;
 use storages_common_table_meta::meta::Statistics;
 
+use crate::io::DeletionVector;
 use crate::operations::mutation::AbortOperation;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 pub struct SerializeDataMeta {
     pub index: BlockMetaIndex,
     pub cluster_stats: Option<ClusterStatistics>,
+    /// The deleted rows are marked in it instead of rewriting the block.
+    pub deletion: Option<DeletionVector>,
 }
 
 #[typetag::serde(name = "serialize_data_meta")]
@@ -63,6 +66,19 @@ impl SerializeDataMeta {
         Box::new(SerializeDataMeta {
             index,
             cluster_stats,
+            deletion: None,
+        })
+    }
+
+    pub fn create_with_deletion(
+        index: BlockMetaIndex,
+        cluster_stats: Option<ClusterStatistics>,
+        deletion: DeletionVector,
+    ) -> BlockMetaInfoPtr {
+        Box::new(SerializeDataMeta {
+            index,
+            cluster_stats,
+            deletion: Some(deletion),
         })
     }
 
@@ -76,11 +92,50 @@ impl SerializeDataMeta {
     }
 }
 
+/// Marks the blocks to be written as new blocks, instead of replacing the origin ones.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct AppendDataMeta;
+
+#[typetag::serde(name = "append_data_meta")]
+impl BlockMetaInfo for AppendDataMeta {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_self(&self) -> Box<dyn BlockMetaInfo> {
+        Box::new(self.clone())
+    }
+
+    fn equals(&self, info: &Box<dyn BlockMetaInfo>) -> bool {
+        info.as_any().downcast_ref::<AppendDataMeta>().is_some()
+    }
+}
+
+impl AppendDataMeta {
+    pub fn create() -> BlockMetaInfoPtr {
+        Box::new(AppendDataMeta)
+    }
+
+    pub fn is_append(info: &BlockMetaInfoPtr) -> bool {
+        info.as_any().downcast_ref::<AppendDataMeta>().is_some()
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum Mutation {
     DoNothing,
     Replaced(Arc<BlockMeta>),
     Deleted,
+    Appended(Arc<BlockMeta>),
+    /// The block is kept, some of its rows are marked in the new deletion vector.
+    DeletionMarked {
+        location: Location,
+        deleted_row_count: u64,
+    },
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
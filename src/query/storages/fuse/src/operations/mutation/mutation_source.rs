@@ -36,6 +36,7 @@ use storages_common_table_meta::meta::ClusterStatistics;
 
 use crate::fuse_part::FusePartInfo;
 use crate::io::BlockReader;
+use crate::io::DeletionVector;
 use crate::io::ReadSettings;
 use crate::operations::mutation::MutationPartInfo;
 use crate::operations::mutation::SerializeDataMeta;
@@ -82,9 +83,11 @@ pub struct MutationSource {
     operators: Vec<BlockOperator>,
     storage_format: FuseStorageFormat,
     action: MutationAction,
+    merge_on_read: bool,
 
     index: BlockMetaIndex,
     origin_stats: Option<ClusterStatistics>,
+    deletion: Option<DeletionVector>,
 }
 
 impl MutationSource {
@@ -98,8 +101,14 @@ impl MutationSource {
         remain_reader: Arc<Option<BlockReader>>,
         operators: Vec<BlockOperator>,
         storage_format: FuseStorageFormat,
+        merge_on_read: bool,
     ) -> Result<ProcessorPtr> {
         let scan_progress = ctx.get_scan_progress();
+        // Mark the deleted rows in the deletion vector instead of rewriting the blocks, only
+        // the blocks in parquet format can be read with the deletion vectors.
+        let merge_on_read = merge_on_read
+            && matches!(action, MutationAction::Deletion)
+            && matches!(storage_format, FuseStorageFormat::Parquet);
         Ok(ProcessorPtr::create(Box::new(MutationSource {
             state: State::ReadData(None),
             output,
@@ -110,8 +119,10 @@ impl MutationSource {
             remain_reader,
             operators,
             action,
+            merge_on_read,
             index: BlockMetaIndex::default(),
             origin_stats: None,
+            deletion: None,
             storage_format,
         })))
     }
@@ -178,6 +189,10 @@ impl Processor for MutationSource {
                     chunks,
                     &self.storage_format,
                 )?;
+                // The rows in the deletion vector are invisible to the mutation.
+                if let Some(deletion) = &self.deletion {
+                    data_block = deletion.filter(data_block)?;
+                }
                 let num_rows = data_block.num_rows();
 
                 if let Some(filter) = self.filter.as_ref() {
@@ -222,6 +237,21 @@ impl Processor for MutationSource {
                                         self.ctx.get_partition(),
                                         DataBlock::empty_with_meta(meta),
                                     );
+                                } else if self.merge_on_read {
+                                    let predicate_col = predicates.into_column().unwrap();
+                                    let deletion = DeletionVector::merge(
+                                        self.deletion.as_ref(),
+                                        &predicate_col,
+                                    );
+                                    let meta = SerializeDataMeta::create_with_deletion(
+                                        self.index.clone(),
+                                        self.origin_stats.clone(),
+                                        deletion,
+                                    );
+                                    self.state = State::Output(
+                                        self.ctx.get_partition(),
+                                        DataBlock::empty_with_meta(meta),
+                                    );
                                 } else {
                                     let predicate_col = predicates.into_column().unwrap();
                                     let filter = predicate_col.not();
@@ -274,8 +304,11 @@ impl Processor for MutationSource {
             } => {
                 if let Some(remain_reader) = self.remain_reader.as_ref() {
                     let chunks = merged_io_read_result.columns_chunks()?;
-                    let remain_block =
+                    let mut remain_block =
                         remain_reader.deserialize_chunks(part, chunks, &self.storage_format)?;
+                    if let Some(deletion) = &self.deletion {
+                        remain_block = deletion.filter(remain_block)?;
+                    }
 
                     match self.action {
                         MutationAction::Deletion => {
@@ -323,6 +356,12 @@ impl Processor for MutationSource {
                 self.origin_stats = part.cluster_stats.clone();
                 let inner_part = part.inner_part.clone();
                 let fuse_part = FusePartInfo::from_part(&inner_part)?;
+                self.deletion = DeletionVector::try_read(
+                    &self.block_reader.operator,
+                    fuse_part.deletion_location.as_ref(),
+                    fuse_part.nums_rows,
+                )
+                .await?;
 
                 let read_res = self
                     .block_reader
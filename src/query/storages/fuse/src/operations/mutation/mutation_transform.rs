@@ -83,6 +83,9 @@ pub struct MutationTransform {
 
     inputs: Vec<Arc<InputPort>>,
     input_metas: MutationMap,
+    appended_blocks: Vec<Arc<BlockMeta>>,
+    // (segment index, block index, location of the deletion vector, deleted row count)
+    deletion_marks: Vec<(usize, usize, Location, u64)>,
     cur_input_index: usize,
     output: Arc<OutputPort>,
     output_data: Option<DataBlock>,
@@ -111,6 +114,8 @@ impl MutationTransform {
             abort_operation: AbortOperation::default(),
             inputs,
             input_metas: HashMap::new(),
+            appended_blocks: vec![],
+            deletion_marks: vec![],
             cur_input_index: 0,
             output,
             output_data: None,
@@ -247,10 +252,40 @@ impl Processor for MutationTransform {
                             .and_modify(|v| v.1.push(meta.index.block_idx))
                             .or_insert((vec![], vec![meta.index.block_idx]));
                     }
+                    Mutation::Appended(block_meta) => {
+                        self.appended_blocks.push(block_meta.clone());
+                        self.abort_operation.add_block(block_meta);
+                    }
+                    Mutation::DeletionMarked {
+                        location,
+                        deleted_row_count,
+                    } => {
+                        self.deletion_marks.push((
+                            meta.index.segment_idx,
+                            meta.index.block_idx,
+                            location.clone(),
+                            *deleted_row_count,
+                        ));
+                        self.abort_operation.add_deletion_vector(location.0.clone());
+                    }
                     Mutation::DoNothing => (),
                 }
             }
             State::GenerateSegments(segment_infos) => {
+                // the blocks with new deletion vectors are replaced by the copies of them.
+                for (seg_idx, block_idx, location, deleted_row_count) in
+                    std::mem::take(&mut self.deletion_marks)
+                {
+                    let mut new_meta = segment_infos[seg_idx].blocks[block_idx].as_ref().clone();
+                    new_meta.deletion_location = Some(location);
+                    new_meta.deleted_row_count = deleted_row_count;
+                    self.input_metas
+                        .entry(seg_idx)
+                        .or_default()
+                        .0
+                        .push((block_idx, Arc::new(new_meta)));
+                }
+
                 let segments = self.base_segments.clone();
                 let mut summary = Statistics::default();
                 let mut serialized_data = Vec::with_capacity(self.input_metas.len());
@@ -301,7 +336,24 @@ impl Processor for MutationTransform {
                 }
 
                 // assign back the mutated segments to snapshot
-                let segments = segments_editor.into_values().collect();
+                let mut segments: Vec<Location> = segments_editor.into_values().collect();
+
+                // the appended blocks go to a new segment, which is placed before the others.
+                if !self.appended_blocks.is_empty() {
+                    let blocks = std::mem::take(&mut self.appended_blocks);
+                    let new_summary = reduce_block_metas(&blocks, self.thresholds)?;
+                    merge_statistics_mut(&mut summary, &new_summary)?;
+                    let new_segment = SegmentInfo::new(blocks, new_summary);
+
+                    let location = self.location_gen.gen_segment_info_location();
+                    self.abort_operation.add_segment(location.clone());
+                    segments.insert(0, (location.clone(), new_segment.format_version()));
+                    serialized_data.push(SerializedData {
+                        data: serde_json::to_vec(&new_segment)?,
+                        location,
+                        segment: Arc::new(new_segment),
+                    });
+                }
                 self.state = State::SerializedSegments {
                     serialized_data,
                     segments,
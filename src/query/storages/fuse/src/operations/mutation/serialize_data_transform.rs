@@ -32,8 +32,10 @@ use storages_common_table_meta::table::TableCompression;
 
 use crate::io::write_block;
 use crate::io::write_data;
+use crate::io::DeletionVector;
 use crate::io::TableMetaLocationGenerator;
 use crate::io::WriteSettings;
+use crate::operations::mutation::AppendDataMeta;
 use crate::operations::mutation::Mutation;
 use crate::operations::mutation::MutationTransformMeta;
 use crate::operations::mutation::SerializeDataMeta;
@@ -56,6 +58,7 @@ enum State {
     Consume,
     NeedSerialize(DataBlock),
     Serialized(SerializeState, Arc<BlockMeta>),
+    WriteDeletion(DeletionVector),
     Output(Mutation),
 }
 
@@ -74,6 +77,7 @@ pub struct SerializeDataTransform {
     schema: TableSchemaRef,
     index: BlockMetaIndex,
     origin_stats: Option<ClusterStatistics>,
+    is_append: bool,
     table_compression: TableCompression,
 }
 
@@ -98,6 +102,7 @@ impl SerializeDataTransform {
             schema: table.schema(),
             index: BlockMetaIndex::default(),
             origin_stats: None,
+            is_append: false,
             table_compression: table.table_compression,
         })))
     }
@@ -118,7 +123,10 @@ impl Processor for SerializeDataTransform {
             return Ok(Event::Sync);
         }
 
-        if matches!(self.state, State::Serialized(_, _)) {
+        if matches!(
+            self.state,
+            State::Serialized(_, _) | State::WriteDeletion(_)
+        ) {
             return Ok(Event::Async);
         }
 
@@ -147,10 +155,22 @@ impl Processor for SerializeDataTransform {
 
         let mut input_data = self.input.pull_data().unwrap()?;
         let meta = input_data.take_meta();
+        self.is_append = false;
         if let Some(meta) = meta {
+            if AppendDataMeta::is_append(&meta) {
+                self.is_append = true;
+                self.index = BlockMetaIndex::default();
+                self.origin_stats = None;
+                self.state = State::NeedSerialize(input_data);
+                return Ok(Event::Sync);
+            }
             let meta = SerializeDataMeta::from_meta(&meta)?;
             self.index = meta.index.clone();
             self.origin_stats = meta.cluster_stats.clone();
+            if let Some(deletion) = &meta.deletion {
+                self.state = State::WriteDeletion(deletion.clone());
+                return Ok(Event::Async);
+            }
             if input_data.is_empty() {
                 self.state = State::Output(Mutation::Deleted);
             } else {
@@ -255,7 +275,20 @@ impl Processor for SerializeDataTransform {
                     write_data(&index_data, &self.dal, &index_location).await?;
                 }
 
-                self.state = State::Output(Mutation::Replaced(block_meta));
+                let op = if self.is_append {
+                    Mutation::Appended(block_meta)
+                } else {
+                    Mutation::Replaced(block_meta)
+                };
+                self.state = State::Output(op);
+            }
+            State::WriteDeletion(deletion) => {
+                let location = self.location_gen.gen_deletion_vector_location();
+                write_data(&deletion.to_bytes(), &self.dal, &location.0).await?;
+                self.state = State::Output(Mutation::DeletionMarked {
+                    location,
+                    deleted_row_count: deletion.num_deleted() as u64,
+                });
             }
             _ => return Err(ErrorCode::Internal("It's a bug.")),
         }
@@ -23,16 +23,26 @@ use common_expression::BlockMetaInfoPtr;
 use serde::Deserializer;
 use serde::Serializer;
 
+use crate::io::DeletionVector;
 use crate::io::MergeIOReadResult;
 
 pub struct DataSourceMeta {
     pub part: Vec<PartInfoPtr>,
     pub data: Vec<MergeIOReadResult>,
+    pub deletions: Vec<Option<DeletionVector>>,
 }
 
 impl DataSourceMeta {
-    pub fn create(part: Vec<PartInfoPtr>, data: Vec<MergeIOReadResult>) -> BlockMetaInfoPtr {
-        Box::new(DataSourceMeta { part, data })
+    pub fn create(
+        part: Vec<PartInfoPtr>,
+        data: Vec<MergeIOReadResult>,
+        deletions: Vec<Option<DeletionVector>>,
+    ) -> BlockMetaInfoPtr {
+        Box::new(DataSourceMeta {
+            part,
+            data,
+            deletions,
+        })
     }
 }
 
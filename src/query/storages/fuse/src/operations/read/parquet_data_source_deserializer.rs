@@ -30,6 +30,7 @@ use common_pipeline_core::processors::Processor;
 
 use crate::fuse_part::FusePartInfo;
 use crate::io::BlockReader;
+use crate::io::DeletionVector;
 use crate::io::MergeIOReadResult;
 use crate::io::UncompressedBuffer;
 use crate::metrics::metrics_inc_remote_io_deserialize_milliseconds;
@@ -44,6 +45,7 @@ pub struct DeserializeDataTransform {
     output_data: Option<DataBlock>,
     parts: Vec<PartInfoPtr>,
     chunks: Vec<MergeIOReadResult>,
+    deletions: Vec<Option<DeletionVector>>,
     uncompressed_buffer: Arc<UncompressedBuffer>,
 }
 
@@ -66,6 +68,7 @@ impl DeserializeDataTransform {
             output_data: None,
             parts: vec![],
             chunks: vec![],
+            deletions: vec![],
             uncompressed_buffer: UncompressedBuffer::new(buffer_size),
         })))
     }
@@ -113,6 +116,7 @@ impl Processor for DeserializeDataTransform {
                 {
                     self.parts = source_meta.part.clone();
                     self.chunks = std::mem::take(&mut source_meta.data);
+                    self.deletions = std::mem::take(&mut source_meta.deletions);
                     return Ok(Event::Sync);
                 }
             }
@@ -133,6 +137,7 @@ impl Processor for DeserializeDataTransform {
     fn process(&mut self) -> Result<()> {
         let part = self.parts.pop();
         let chunks = self.chunks.pop();
+        let deletion = self.deletions.pop().flatten();
         if let Some((part, read_res)) = part.zip(chunks) {
             let start = Instant::now();
 
@@ -147,6 +152,10 @@ impl Processor for DeserializeDataTransform {
                 columns_chunks,
                 Some(self.uncompressed_buffer.clone()),
             )?;
+            let data_block = match deletion {
+                None => data_block,
+                Some(deletion) => deletion.filter(data_block)?,
+            };
 
             // Perf.
             {
@@ -19,6 +19,7 @@ use common_base::base::tokio;
 use common_catalog::plan::PartInfoPtr;
 use common_catalog::plan::StealablePartitions;
 use common_catalog::table_context::TableContext;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_expression::DataBlock;
 use common_pipeline_core::processors::port::OutputPort;
@@ -30,6 +31,7 @@ use common_pipeline_sources::SyncSourcer;
 
 use crate::fuse_part::FusePartInfo;
 use crate::io::BlockReader;
+use crate::io::DeletionVector;
 use crate::io::ReadSettings;
 use crate::operations::read::parquet_data_source::DataSourceMeta;
 use crate::MergeIOReadResult;
@@ -41,7 +43,10 @@ pub struct ReadParquetDataSource<const BLOCKING_IO: bool> {
     block_reader: Arc<BlockReader>,
 
     output: Arc<OutputPort>,
-    output_data: Option<(Vec<PartInfoPtr>, Vec<MergeIOReadResult>)>,
+    output_data: Option<(
+        Vec<PartInfoPtr>,
+        Vec<(MergeIOReadResult, Option<DeletionVector>)>,
+    )>,
     partitions: StealablePartitions,
 }
 
@@ -87,13 +92,22 @@ impl SyncSource for ReadParquetDataSource<true> {
     fn generate(&mut self) -> Result<Option<DataBlock>> {
         match self.partitions.steal_one(self.id) {
             None => Ok(None),
-            Some(part) => Ok(Some(DataBlock::empty_with_meta(DataSourceMeta::create(
-                vec![part.clone()],
-                vec![self.block_reader.sync_read_columns_data_by_merge_io(
-                    &ReadSettings::from_ctx(&self.partitions.ctx)?,
-                    part,
-                )?],
-            )))),
+            Some(part) => {
+                let fuse_part = FusePartInfo::from_part(&part)?;
+                let deletion = DeletionVector::try_sync_read(
+                    &self.block_reader.operator,
+                    fuse_part.deletion_location.as_ref(),
+                    fuse_part.nums_rows,
+                )?;
+                Ok(Some(DataBlock::empty_with_meta(DataSourceMeta::create(
+                    vec![part.clone()],
+                    vec![self.block_reader.sync_read_columns_data_by_merge_io(
+                        &ReadSettings::from_ctx(&self.partitions.ctx)?,
+                        part,
+                    )?],
+                    vec![deletion],
+                ))))
+            }
         }
     }
 }
@@ -123,7 +137,8 @@ impl Processor for ReadParquetDataSource<false> {
         }
 
         if let Some((part, data)) = self.output_data.take() {
-            let output = DataBlock::empty_with_meta(DataSourceMeta::create(part, data));
+            let (data, deletions) = data.into_iter().unzip();
+            let output = DataBlock::empty_with_meta(DataSourceMeta::create(part, data, deletions));
 
             self.output.push_data(Ok(output));
             // return Ok(Event::NeedConsume);
@@ -146,13 +161,20 @@ impl Processor for ReadParquetDataSource<false> {
                     tokio::spawn(async move {
                         let part = FusePartInfo::from_part(&part)?;
 
-                        block_reader
+                        let data = block_reader
                             .read_columns_data_by_merge_io(
                                 &settings,
                                 &part.location,
                                 &part.columns_meta,
                             )
-                            .await
+                            .await?;
+                        let deletion = DeletionVector::try_read(
+                            &block_reader.operator,
+                            part.deletion_location.as_ref(),
+                            part.nums_rows,
+                        )
+                        .await?;
+                        Ok::<_, ErrorCode>((data, deletion))
                     })
                     .await
                     .unwrap()
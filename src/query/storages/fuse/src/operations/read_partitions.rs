@@ -283,7 +283,7 @@ impl FuseTable {
 
         let mut remaining = limit;
         for (range, block_meta) in block_metas.iter() {
-            let rows = block_meta.row_count as usize;
+            let rows = block_meta.live_row_count() as usize;
             partitions.partitions.push(Self::all_columns_part(
                 schema,
                 range.clone(),
@@ -333,7 +333,7 @@ impl FuseTable {
                 projection,
             ));
 
-            let rows = block_meta.row_count as usize;
+            let rows = block_meta.live_row_count() as usize;
 
             statistics.read_rows += rows;
             for column in &columns {
@@ -398,6 +398,7 @@ impl FuseTable {
             meta.compression(),
             sort_min_max,
             range,
+            meta.deletion_location.clone(),
         )
     }
 
@@ -440,6 +441,7 @@ impl FuseTable {
             meta.compression(),
             sort_min_max,
             range,
+            meta.deletion_location.clone(),
         )
     }
 }
@@ -25,11 +25,23 @@ use storages_common_table_meta::meta::Versioned;
 use storages_common_table_meta::table::OPT_KEY_SNAPSHOT_LOCATION;
 use uuid::Uuid;
 
+use crate::operations::PurgeStatistics;
 use crate::FuseTable;
 
 impl FuseTable {
     #[inline]
     pub async fn do_truncate(&self, ctx: Arc<dyn TableContext>, purge: bool) -> Result<()> {
+        self.truncate_with_statistics(ctx, purge).await?;
+        Ok(())
+    }
+
+    // Truncate the table, returns the files removed by the purge, if any.
+    pub async fn truncate_with_statistics(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        purge: bool,
+    ) -> Result<PurgeStatistics> {
+        let mut statistics = PurgeStatistics::default();
         if let Some(prev_snapshot) = self.read_table_snapshot().await? {
             let prev_id = prev_snapshot.snapshot_id;
 
@@ -52,7 +64,7 @@ impl FuseTable {
 
             if purge {
                 let keep_last_snapshot = false;
-                self.do_purge(&ctx, keep_last_snapshot).await?
+                statistics = self.do_purge(&ctx, keep_last_snapshot).await?;
             }
 
             let mut new_table_meta = self.table_info.meta.clone();
@@ -90,6 +102,6 @@ impl FuseTable {
             .await;
         }
 
-        Ok(())
+        Ok(statistics)
     }
 }
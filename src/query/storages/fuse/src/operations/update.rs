@@ -205,6 +205,7 @@ impl FuseTable {
                     remain_reader.clone(),
                     ops.clone(),
                     self.storage_format,
+                    false,
                 )
             },
             max_threads,
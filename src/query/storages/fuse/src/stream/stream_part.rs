@@ -0,0 +1,73 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use common_catalog::plan::PartInfo;
+use common_catalog::plan::PartInfoPtr;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_app::schema::TableInfo;
+
+use crate::operations::BlockChanges;
+
+/// The changes of the table tracked by a stream, read as a whole by one source.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct StreamPartInfo {
+    /// The table tracked by the stream, at the snapshot the changes are read up to.
+    pub table_info: TableInfo,
+    pub changes: BlockChanges,
+}
+
+#[typetag::serde(name = "stream")]
+impl PartInfo for StreamPartInfo {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn equals(&self, info: &Box<dyn PartInfo>) -> bool {
+        match info.as_any().downcast_ref::<StreamPartInfo>() {
+            None => false,
+            Some(other) => self == other,
+        }
+    }
+
+    fn hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.table_info.ident.table_id.hash(&mut s);
+        s.finish()
+    }
+}
+
+impl StreamPartInfo {
+    pub fn create(table_info: TableInfo, changes: BlockChanges) -> PartInfoPtr {
+        Arc::new(Box::new(StreamPartInfo {
+            table_info,
+            changes,
+        }))
+    }
+
+    pub fn from_part(info: &PartInfoPtr) -> Result<&StreamPartInfo> {
+        match info.as_any().downcast_ref::<StreamPartInfo>() {
+            Some(part_ref) => Ok(part_ref),
+            None => Err(ErrorCode::Internal(
+                "Cannot downcast from PartInfo to StreamPartInfo.",
+            )),
+        }
+    }
+}
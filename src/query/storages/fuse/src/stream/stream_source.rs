@@ -0,0 +1,274 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use common_arrow::arrow::bitmap::Bitmap;
+use common_arrow::arrow::bitmap::MutableBitmap;
+use common_catalog::plan::Projection;
+use common_exception::Result;
+use common_expression::types::DataType;
+use common_expression::BlockEntry;
+use common_expression::DataBlock;
+use common_expression::Scalar;
+use common_expression::Value;
+use storages_common_table_meta::meta::BlockMeta;
+use storages_common_table_meta::meta::Location;
+
+use crate::io::BlockReader;
+use crate::io::DeletionVector;
+use crate::io::ReadSettings;
+use crate::operations::BlockChanges;
+use crate::pipelines::processors::port::OutputPort;
+use crate::pipelines::processors::processor::ProcessorPtr;
+use crate::pipelines::processors::AsyncSource;
+use crate::pipelines::processors::AsyncSourcer;
+use crate::sessions::TableContext;
+use crate::FuseStorageFormat;
+use crate::FuseTable;
+
+const ACTION_INSERT: &str = "INSERT";
+const ACTION_DELETE: &str = "DELETE";
+
+/// Read the rows of the changed blocks, with the action of the change appended.
+///
+/// The rows moved by compaction are told by the origins of the compacted blocks, they are
+/// skipped without being compared. A block rewritten by a mutation in copy-on-write mode
+/// is removed with all of its rows, and the rows kept are added again within the new block.
+/// The rows both removed and added are net-zero changes, they are canceled out, so that
+/// only the rows really inserted or deleted are emitted. The removed rows are held in
+/// memory to do so.
+pub struct StreamSource {
+    block_reader: Arc<BlockReader>,
+    read_settings: ReadSettings,
+    storage_format: FuseStorageFormat,
+
+    added: Vec<BlockMeta>,
+    /// The added blocks with rows moved from the removed blocks by compaction.
+    compacted: Vec<(BlockMeta, Vec<Range<u64>>)>,
+    removed: Vec<BlockMeta>,
+    marked: Vec<(BlockMeta, Option<Location>)>,
+
+    removed_loaded: bool,
+    /// The rows of the compacted blocks not moved by the compaction.
+    compacted_blocks: Vec<DataBlock>,
+    removed_blocks: Vec<DataBlock>,
+    /// The count of the removed rows not canceled out yet, by the values of the row.
+    removed_rows: HashMap<Vec<Scalar>, usize>,
+}
+
+impl StreamSource {
+    pub fn create(
+        ctx: Arc<dyn TableContext>,
+        output: Arc<OutputPort>,
+        table: &FuseTable,
+        changes: BlockChanges,
+    ) -> Result<ProcessorPtr> {
+        let num_fields = table.table_info.schema().num_fields();
+        let block_reader = table
+            .create_block_reader(Projection::Columns((0..num_fields).collect()), ctx.clone())?;
+        let read_settings = ReadSettings::from_ctx(&ctx)?;
+        let BlockChanges {
+            added,
+            removed,
+            marked,
+        } = changes;
+        let (compacted, added): (Vec<_>, Vec<_>) =
+            added.into_iter().partition(|(_, moved)| !moved.is_empty());
+        AsyncSourcer::create(ctx, output, StreamSource {
+            block_reader,
+            read_settings,
+            storage_format: table.storage_format,
+            added: added.into_iter().map(|(block, _)| block).collect(),
+            compacted,
+            removed,
+            marked,
+            removed_loaded: false,
+            compacted_blocks: vec![],
+            removed_blocks: vec![],
+            removed_rows: HashMap::new(),
+        })
+    }
+
+    async fn load_removed(&mut self) -> Result<()> {
+        for block in std::mem::take(&mut self.removed) {
+            let data = self.read_block(&block).await?;
+            self.hold_removed(data);
+        }
+
+        // only the rows newly marked in the deletion vector are deleted.
+        let operator = self.block_reader.operator.clone();
+        for (block, previous) in std::mem::take(&mut self.marked) {
+            let num_rows = block.row_count as usize;
+            let (current, previous) = match (
+                DeletionVector::try_read(&operator, block.deletion_location.as_ref(), num_rows)
+                    .await?,
+                DeletionVector::try_read(&operator, previous.as_ref(), num_rows).await?,
+            ) {
+                (Some(current), previous) => (current, previous),
+                // the deletion vector is never removed from a block.
+                (None, _) => continue,
+            };
+            let raw = BlockMeta {
+                deletion_location: None,
+                ..block
+            };
+            let data = self.read_block(&raw).await?;
+            let data = data.filter_with_bitmap(&current.marked_since(previous.as_ref()))?;
+            self.hold_removed(data);
+        }
+
+        // the rows moved by the compaction are not changes, unless they are deleted since.
+        for (block, moved) in std::mem::take(&mut self.compacted) {
+            let num_rows = block.row_count as usize;
+            let moved = moved_rows(&moved, num_rows);
+            let deleted = match DeletionVector::try_read(
+                &operator,
+                block.deletion_location.as_ref(),
+                num_rows,
+            )
+            .await?
+            {
+                Some(deletion) => deletion.marked_since(None),
+                None => MutableBitmap::from_len_zeroed(num_rows).into(),
+            };
+            if moved.unset_bits() == 0 && deleted.unset_bits() == num_rows {
+                continue;
+            }
+
+            let raw = BlockMeta {
+                deletion_location: None,
+                ..block
+            };
+            let data = self.read_block(&raw).await?;
+            self.hold_removed(data.clone().filter_with_bitmap(&(&moved & &deleted))?);
+            self.compacted_blocks
+                .push(data.filter_with_bitmap(&!&(&moved | &deleted))?);
+        }
+        self.removed_loaded = true;
+        Ok(())
+    }
+
+    fn hold_removed(&mut self, data: DataBlock) {
+        if data.num_rows() == 0 {
+            return;
+        }
+        for row in 0..data.num_rows() {
+            *self.removed_rows.entry(row_values(&data, row)).or_default() += 1;
+        }
+        self.removed_blocks.push(data);
+    }
+
+    async fn read_block(&self, block: &BlockMeta) -> Result<DataBlock> {
+        self.block_reader
+            .read_by_meta(&self.read_settings, block, &self.storage_format)
+            .await
+    }
+
+    /// Take one of the removed rows with the same values as the row, returns false if
+    /// there is none left.
+    fn take_removed(&mut self, data: &DataBlock, row: usize) -> bool {
+        match self.removed_rows.get_mut(&row_values(data, row)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Keep the added rows which do not cancel out a removed row.
+    fn filter_added(&mut self, data: DataBlock) -> Result<DataBlock> {
+        if self.removed_rows.is_empty() {
+            return Ok(data);
+        }
+        let mut filter = MutableBitmap::with_capacity(data.num_rows());
+        for row in 0..data.num_rows() {
+            filter.push(!self.take_removed(&data, row));
+        }
+        data.filter_with_bitmap(&filter.into())
+    }
+
+    /// Keep the removed rows which are not canceled out by the added rows.
+    fn filter_removed(&mut self, data: DataBlock) -> Result<DataBlock> {
+        let mut filter = MutableBitmap::with_capacity(data.num_rows());
+        for row in 0..data.num_rows() {
+            filter.push(self.take_removed(&data, row));
+        }
+        data.filter_with_bitmap(&filter.into())
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncSource for StreamSource {
+    const NAME: &'static str = "StreamSource";
+
+    #[async_trait::unboxed_simple]
+    async fn generate(&mut self) -> Result<Option<DataBlock>> {
+        if !self.removed_loaded {
+            self.load_removed().await?;
+        }
+
+        while let Some(block) = self.added.pop() {
+            let data = self.read_block(&block).await?;
+            let data = self.filter_added(data)?;
+            if data.num_rows() > 0 {
+                return Ok(Some(with_action(data, ACTION_INSERT)));
+            }
+        }
+
+        while let Some(data) = self.compacted_blocks.pop() {
+            let data = self.filter_added(data)?;
+            if data.num_rows() > 0 {
+                return Ok(Some(with_action(data, ACTION_INSERT)));
+            }
+        }
+
+        while let Some(data) = self.removed_blocks.pop() {
+            let data = self.filter_removed(data)?;
+            if data.num_rows() > 0 {
+                return Ok(Some(with_action(data, ACTION_DELETE)));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+fn moved_rows(moved: &[Range<u64>], num_rows: usize) -> Bitmap {
+    let mut bitmap = MutableBitmap::from_len_zeroed(num_rows);
+    for range in moved {
+        for row in range.clone() {
+            bitmap.set(row as usize, true);
+        }
+    }
+    bitmap.into()
+}
+
+fn row_values(data: &DataBlock, row: usize) -> Vec<Scalar> {
+    data.columns()
+        .iter()
+        .map(|entry| entry.value.as_ref().index(row).unwrap().to_owned())
+        .collect()
+}
+
+fn with_action(mut data: DataBlock, action: &str) -> DataBlock {
+    data.add_column(BlockEntry {
+        data_type: DataType::String,
+        value: Value::Scalar(Scalar::String(action.as_bytes().to_vec())),
+    });
+    data
+}
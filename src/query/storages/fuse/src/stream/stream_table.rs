@@ -0,0 +1,332 @@
+//  Copyright 2023 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_catalog::catalog::StorageDescription;
+use common_catalog::plan::DataSourcePlan;
+use common_catalog::plan::PartStatistics;
+use common_catalog::plan::Partitions;
+use common_catalog::plan::PartitionsShuffleKind;
+use common_catalog::plan::PushDownInfo;
+use common_catalog::table::Table;
+use common_catalog::table_context::TableContext;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_expression::TableDataType;
+use common_expression::TableField;
+use common_expression::TableSchema;
+use common_meta_app::schema::TableInfo;
+use common_meta_app::schema::UpdateTableMetaReq;
+use common_meta_types::MatchSeq;
+use futures::TryStreamExt;
+use parking_lot::Mutex;
+use storages_common_table_meta::meta::TableSnapshot;
+use storages_common_table_meta::table::OPT_KEY_SNAPSHOT_LOCATION;
+use tracing::warn;
+
+use crate::pipelines::processors::EmptySource;
+use crate::pipelines::Pipeline;
+use crate::stream::stream_source::StreamSource;
+use crate::stream::StreamPartInfo;
+use crate::FuseTable;
+
+pub const STREAM_ENGINE: &str = "STREAM";
+
+/// The database of the table tracked by the stream.
+pub const OPT_KEY_TABLE_DATABASE: &str = "table_database";
+/// The name of the table tracked by the stream.
+pub const OPT_KEY_TABLE_NAME: &str = "table_name";
+/// The id of the table tracked by the stream, to tell if the table is dropped and created again.
+pub const OPT_KEY_TABLE_ID: &str = "table_id";
+
+/// The column appended to the columns of the table, tells how the row is changed,
+/// `INSERT` or `DELETE`.
+pub const CHANGE_ACTION_COL_NAME: &str = "change$action";
+
+/// A stream tracks the changes of a fuse table since its offset.
+///
+/// The offset is the snapshot of the table the changes are read from, which is kept in
+/// the option `snapshot_location` of the stream. Reading the stream diffs the blocks of
+/// the latest snapshot of the table against the offset, the offset is not moved by the
+/// queries, only by the DML statements which consume the stream, after they commit.
+pub struct StreamTable {
+    table_info: TableInfo,
+    /// The snapshot of the table the changes are read up to, by the current query.
+    read_location: Mutex<Option<String>>,
+}
+
+impl StreamTable {
+    pub fn try_create(table_info: TableInfo) -> Result<Box<dyn Table>> {
+        let options = table_info.options();
+        for key in [OPT_KEY_TABLE_DATABASE, OPT_KEY_TABLE_NAME, OPT_KEY_TABLE_ID] {
+            if !options.contains_key(key) {
+                return Err(ErrorCode::Internal(format!(
+                    "Need `{key}` when creating StreamTable"
+                )));
+            }
+        }
+        Ok(Box::new(StreamTable {
+            table_info,
+            read_location: Mutex::new(None),
+        }))
+    }
+
+    pub fn description() -> StorageDescription {
+        StorageDescription {
+            engine_name: STREAM_ENGINE.to_string(),
+            comment: "STREAM Storage Engine".to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn try_from_table(tbl: &dyn Table) -> Result<&StreamTable> {
+        tbl.as_any().downcast_ref::<StreamTable>().ok_or_else(|| {
+            ErrorCode::Internal(format!(
+                "expects table of engine STREAM, but got {}",
+                tbl.engine()
+            ))
+        })
+    }
+
+    /// The schema of the stream on a table of `schema`.
+    pub fn stream_schema(schema: &TableSchema) -> Result<TableSchema> {
+        let mut schema = schema.clone();
+        schema.add_columns(&[TableField::new(
+            CHANGE_ACTION_COL_NAME,
+            TableDataType::String,
+        )])?;
+        Ok(schema)
+    }
+
+    /// The location of the snapshot of the table the changes are read from.
+    pub fn offset(&self) -> Option<&str> {
+        self.table_info
+            .options()
+            .get(OPT_KEY_SNAPSHOT_LOCATION)
+            .map(|s| s.as_str())
+    }
+
+    async fn source_table(&self, ctx: &dyn TableContext) -> Result<Arc<dyn Table>> {
+        let options = self.table_info.options();
+        let table = ctx
+            .get_catalog(&self.table_info.meta.catalog)?
+            .get_table(
+                ctx.get_tenant().as_str(),
+                &options[OPT_KEY_TABLE_DATABASE],
+                &options[OPT_KEY_TABLE_NAME],
+            )
+            .await?;
+        if table.get_id().to_string() != options[OPT_KEY_TABLE_ID] {
+            return Err(ErrorCode::UnknownTable(format!(
+                "the table tracked by stream {} has been dropped",
+                self.table_info.desc
+            )));
+        }
+
+        let fields = self.table_info.schema().fields().clone();
+        if table.schema().fields()[..] != fields[..fields.len() - 1] {
+            return Err(ErrorCode::TableInfoError(format!(
+                "the schema of the table tracked by stream {} has changed, please recreate the stream",
+                self.table_info.desc
+            )));
+        }
+        Ok(table)
+    }
+
+    /// Check that the stream is not consumed by another statement since it is read by the
+    /// current query, called before the DML statement consuming the stream commits.
+    pub async fn check_offset(&self, ctx: &dyn TableContext) -> Result<()> {
+        if self.read_location.lock().is_none() {
+            return Ok(());
+        }
+
+        let catalog = ctx.get_catalog(&self.table_info.meta.catalog)?;
+        let (ident, _) = catalog
+            .get_table_meta_by_id(self.table_info.ident.table_id)
+            .await?;
+        if ident.seq != self.table_info.ident.seq {
+            return Err(ErrorCode::TableVersionMismatched(format!(
+                "stream {} is consumed by another statement at the same time",
+                self.table_info.desc
+            )));
+        }
+        Ok(())
+    }
+
+    /// Move the offset to the snapshot the changes are read up to by the current query,
+    /// called once the DML statement consuming the stream commits.
+    pub async fn advance_offset(&self, ctx: &dyn TableContext) -> Result<()> {
+        let location = match self.read_location.lock().take() {
+            Some(location) => location,
+            None => return Ok(()),
+        };
+        if self.offset() == Some(location.as_str()) {
+            return Ok(());
+        }
+
+        let mut new_table_meta = self.table_info.meta.clone();
+        new_table_meta
+            .options
+            .insert(OPT_KEY_SNAPSHOT_LOCATION.to_owned(), location);
+        let req = UpdateTableMetaReq {
+            table_id: self.table_info.ident.table_id,
+            seq: MatchSeq::Exact(self.table_info.ident.seq),
+            new_table_meta,
+        };
+        let catalog = ctx.get_catalog(&self.table_info.meta.catalog)?;
+        match catalog.update_table_meta(&self.table_info, req).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.code() == ErrorCode::TABLE_VERSION_MISMATCHED => {
+                Err(ErrorCode::TableVersionMismatched(format!(
+                    "stream {} is consumed by another statement at the same time",
+                    self.table_info.desc
+                )))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl FuseTable {
+    /// Register the stream of `stream_id` on this table by a marker file, so that the gc
+    /// of this table keeps the snapshot at the offset of the stream, with its files.
+    pub async fn register_stream(&self, stream_id: u64) -> Result<()> {
+        let marker = self
+            .meta_location_generator
+            .stream_marker_location(stream_id);
+        self.operator.object(&marker).write(vec![]).await?;
+        Ok(())
+    }
+
+    /// The snapshots at the offsets of the streams registered on this table.
+    ///
+    /// The markers of the streams which no longer exist are removed.
+    pub(crate) async fn get_stream_offsets(
+        &self,
+        ctx: &dyn TableContext,
+    ) -> Result<Vec<Arc<TableSnapshot>>> {
+        let mut offsets = vec![];
+        let marker_prefix = self.meta_location_generator.stream_marker_prefix();
+        let mut markers = match self.operator.object(&marker_prefix).list().await {
+            Err(e) if e.kind() == opendal::ErrorKind::ObjectNotFound => return Ok(offsets),
+            Err(e) => return Err(e.into()),
+            Ok(v) => v,
+        };
+
+        let catalog = ctx.get_catalog(self.table_info.catalog())?;
+        while let Some(marker) = markers.try_next().await? {
+            let stream_id = match marker.name().parse::<u64>() {
+                Ok(stream_id) => stream_id,
+                Err(_) => {
+                    warn!("invalid stream marker {}, ignored", marker.path());
+                    continue;
+                }
+            };
+
+            let (_, meta) = match catalog.get_table_meta_by_id(stream_id).await {
+                Err(e) if e.code() == ErrorCode::UNKNOWN_TABLE_ID => {
+                    // the stream has been dropped and collected, unregister it.
+                    self.operator.object(marker.path()).delete().await?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+                Ok(v) => v,
+            };
+            let location = match meta.options.get(OPT_KEY_SNAPSHOT_LOCATION) {
+                Some(location) => location,
+                None => continue,
+            };
+            match self.read_snapshot_by_location(location).await {
+                Err(e) if e.code() == ErrorCode::STORAGE_NOT_FOUND => {
+                    warn!(
+                        "the offset {} of stream {} has been purged from table {}",
+                        location, stream_id, self.table_info.desc
+                    );
+                }
+                Err(e) => return Err(e),
+                Ok(snapshot) => offsets.push(snapshot),
+            }
+        }
+        Ok(offsets)
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for StreamTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_table_info(&self) -> &TableInfo {
+        &self.table_info
+    }
+
+    async fn read_partitions(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        _push_downs: Option<PushDownInfo>,
+    ) -> Result<(PartStatistics, Partitions)> {
+        let table = self.source_table(ctx.as_ref()).await?;
+        let fuse_table = FuseTable::try_from_table(table.as_ref())?;
+        let changes = fuse_table
+            .changes_since(ctx.clone(), self.offset())
+            .await
+            .map_err(|e| {
+                if e.code() == ErrorCode::STORAGE_NOT_FOUND {
+                    ErrorCode::TableHistoricalDataNotFound(format!(
+                        "the offset of stream {} has been purged from the table, please recreate the stream",
+                        self.table_info.desc
+                    ))
+                } else {
+                    e
+                }
+            })?;
+        *self.read_location.lock() = fuse_table.snapshot_loc().await?;
+
+        let parts = if changes.is_empty() {
+            vec![]
+        } else {
+            vec![StreamPartInfo::create(
+                fuse_table.get_table_info().clone(),
+                changes,
+            )]
+        };
+        Ok((
+            PartStatistics::default(),
+            Partitions::create_nolazy(PartitionsShuffleKind::Seq, parts),
+        ))
+    }
+
+    fn read_data(
+        &self,
+        ctx: Arc<dyn TableContext>,
+        plan: &DataSourcePlan,
+        pipeline: &mut Pipeline,
+    ) -> Result<()> {
+        let part = match plan.parts.partitions.first() {
+            None => return pipeline.add_source(EmptySource::create, 1),
+            Some(part) => StreamPartInfo::from_part(part)?,
+        };
+
+        let table = FuseTable::do_create(part.table_info.clone())?;
+        pipeline.add_source(
+            |output| {
+                StreamSource::create(ctx.clone(), output, table.as_ref(), part.changes.clone())
+            },
+            1,
+        )
+    }
+}
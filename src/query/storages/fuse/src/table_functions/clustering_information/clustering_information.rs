@@ -148,6 +148,14 @@ impl<'a> ClusteringInformation<'a> {
         ))
     }
 
+    /// The average depth of the blocks on the cluster key of the table.
+    pub fn average_depth<'b>(
+        &self,
+        blocks: impl Iterator<Item = &'b Arc<BlockMeta>>,
+    ) -> Result<f64> {
+        Ok(self.get_clustering_stats(blocks)?.average_depth)
+    }
+
     fn get_min_max_stats(&self, block: &BlockMeta) -> Result<(Vec<Scalar>, Vec<Scalar>)> {
         if self.table.cluster_keys(self.ctx.clone()) != self.cluster_keys
             || block.cluster_stats.is_none()
@@ -22,4 +22,5 @@ mod write;
 
 pub use common::gen_result_cache_key;
 pub use read::ResultCacheReader;
+pub use write::ResultCacheWriter;
 pub use write::WriteResultCacheSink;
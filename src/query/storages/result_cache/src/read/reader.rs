@@ -68,7 +68,10 @@ impl ResultCacheReader {
                     if value.num_rows == 0 {
                         Ok(Some(vec![DataBlock::empty()]))
                     } else {
-                        Ok(Some(self.read_result_from_cache(&value.location).await?))
+                        Ok(Some(
+                            Self::read_result_from_location(&self.operator, &value.location)
+                                .await?,
+                        ))
                     }
                 } else {
                     // The cache is invalid (due to data update or other reasons).
@@ -79,8 +82,12 @@ impl ResultCacheReader {
         }
     }
 
-    async fn read_result_from_cache(&self, location: &str) -> Result<Vec<DataBlock>> {
-        let object = self.operator.object(location);
+    /// Read the result written by `ResultCacheWriter`.
+    pub async fn read_result_from_location(
+        operator: &Operator,
+        location: &str,
+    ) -> Result<Vec<DataBlock>> {
+        let object = operator.object(location);
         let data = object.read().await?;
         let mut reader = Cursor::new(data);
         let meta = read_metadata(&mut reader)?;
@@ -16,3 +16,4 @@ mod sink;
 mod writer;
 
 pub use sink::WriteResultCacheSink;
+pub use writer::ResultCacheWriter;
@@ -20,7 +20,8 @@ use storages_common_blocks::blocks_to_parquet;
 use storages_common_table_meta::table::TableCompression;
 use uuid::Uuid;
 
-pub(super) struct ResultCacheWriter {
+/// Buffer the result blocks and write them to one parquet file of the storage.
+pub struct ResultCacheWriter {
     operator: Operator,
     location: String,
 
@@ -32,6 +32,7 @@ mod malloc_stats_totals_table;
 mod metrics_table;
 mod one_table;
 mod processes_table;
+mod purge_history_table;
 mod query_log_table;
 mod roles_table;
 mod settings_table;
@@ -62,6 +63,9 @@ pub use malloc_stats_totals_table::MallocStatsTotalsTable;
 pub use metrics_table::MetricsTable;
 pub use one_table::OneTable;
 pub use processes_table::ProcessesTable;
+pub use purge_history_table::PurgeHistoryLogElement;
+pub use purge_history_table::PurgeHistoryQueue;
+pub use purge_history_table::PurgeHistoryTable;
 pub use query_log_table::LogType;
 pub use query_log_table::QueryLogElement;
 pub use query_log_table::QueryLogQueue;
@@ -17,12 +17,18 @@ use std::sync::Arc;
 use common_base::base::GlobalInstance;
 use common_exception::Result;
 use common_grpc::RpcClientConf;
+use common_management::AsyncQueryApi;
+use common_management::AsyncQueryMgr;
 use common_management::FileFormatApi;
 use common_management::FileFormatMgr;
+use common_management::LeaseApi;
+use common_management::LeaseMgr;
 use common_management::QuotaApi;
 use common_management::QuotaMgr;
 use common_management::RoleApi;
 use common_management::RoleMgr;
+use common_management::SessionApi;
+use common_management::SessionMgr;
 use common_management::SettingApi;
 use common_management::SettingMgr;
 use common_management::StageApi;
@@ -115,6 +121,21 @@ impl UserApiProvider {
         Ok(Arc::new(SettingMgr::create(self.client.clone(), tenant)?))
     }
 
+    pub fn get_session_api_client(&self, tenant: &str) -> Result<Arc<dyn SessionApi>> {
+        Ok(Arc::new(SessionMgr::create(self.client.clone(), tenant)?))
+    }
+
+    pub fn get_async_query_api_client(&self, tenant: &str) -> Result<Arc<dyn AsyncQueryApi>> {
+        Ok(Arc::new(AsyncQueryMgr::create(
+            self.client.clone(),
+            tenant,
+        )?))
+    }
+
+    pub fn get_lease_api_client(&self, tenant: &str) -> Result<Arc<dyn LeaseApi>> {
+        Ok(Arc::new(LeaseMgr::create(self.client.clone(), tenant)?))
+    }
+
     pub fn get_meta_store_client(&self) -> Arc<MetaStore> {
         Arc::new(self.meta.clone())
     }